@@ -0,0 +1,189 @@
+//! Temporary inputs for application streams
+//!
+//! With `app_streams` configured, every application that starts playing
+//! gets an input channel of its own, connected to its ports, and loses it
+//! again when it exits: everything goes through the mixer without listing
+//! each player in the config. Applications are told apart from devices by
+//! the JACK physical port flag, which PipeWire sets on device nodes only;
+//! the rules match the client name, which PipeWire takes from the
+//! application name. Ports a configured input or passthrough already takes
+//! are left alone.
+
+use std::collections::BTreeMap;
+
+use crate::config::{AppStreamsConfig, ChannelConfig, Config, RouteConfig};
+use crate::ipc::MAX_CHANNEL_PORTS;
+
+/// Whether `name` matches `pattern`, where `*` stands for any text
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No wildcard: the whole name must match
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Local port name base of an application's input
+fn port_base(client: &str) -> String {
+    let name: String = client
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("app_{}", name)
+}
+
+/// Temporary inputs the application ports call for, given as full
+/// `client:port` names; inputs the config already has for an application
+/// are kept as they are so their fader settings survive
+pub fn wanted_inputs(
+    settings: &AppStreamsConfig,
+    ports: &[String],
+    config: &Config,
+) -> Vec<ChannelConfig> {
+    let taken: Vec<&String> = config
+        .inputs
+        .iter()
+        .filter(|input| !input.temporary)
+        .flat_map(|input| &input.connect)
+        .chain(config.passthrough.iter().flat_map(|p| &p.connect_inputs))
+        .collect();
+
+    let mut clients: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for port in ports {
+        let Some((client, _)) = port.split_once(':') else {
+            continue;
+        };
+        let included = settings.include.iter().any(|p| matches(p, client))
+            && !settings.exclude.iter().any(|p| matches(p, client));
+        if included && !taken.contains(&port) {
+            clients.entry(client).or_default().push(port.clone());
+        }
+    }
+
+    clients
+        .into_iter()
+        .filter(|(client, _)| {
+            !config
+                .inputs
+                .iter()
+                .any(|input| !input.temporary && input.name == *client)
+        })
+        .map(|(client, mut connect)| {
+            connect.truncate(MAX_CHANNEL_PORTS);
+            let existing = config
+                .inputs
+                .iter()
+                .find(|input| input.temporary && input.name == client && input.connect == connect);
+            if let Some(input) = existing {
+                return input.clone();
+            }
+            let base = port_base(client);
+            let routes = (!settings.routes.is_empty()).then(|| {
+                settings
+                    .routes
+                    .iter()
+                    .map(|output| RouteConfig {
+                        output: output.clone(),
+                        gain_db: 0.0,
+                        mono: false,
+                    })
+                    .collect()
+            });
+            ChannelConfig {
+                name: client.to_string(),
+                ports: (1..=connect.len())
+                    .map(|i| format!("{}_{}", base, i))
+                    .collect(),
+                connect,
+                volume_db: Some(settings.volume_db),
+                routes,
+                temporary: true,
+                ..ChannelConfig::default()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns() {
+        assert!(matches("*", "Firefox"));
+        assert!(matches("Firefox", "Firefox"));
+        assert!(!matches("Firefox", "Firefox 2"));
+        assert!(matches("Fire*", "Firefox"));
+        assert!(matches("*fox", "Firefox"));
+        assert!(matches("F*r*x", "Firefox"));
+        assert!(!matches("F*z*x", "Firefox"));
+        assert!(!matches("*fox*zz", "Firefox"));
+    }
+
+    #[test]
+    fn test_each_application_gets_an_input() {
+        let yaml = r#"
+client_name: "Mixer"
+app_streams:
+  exclude: ["Zoom"]
+  routes: ["Main"]
+  volume_db: -6
+inputs:
+  - name: "Music"
+    ports: ["music_L", "music_R"]
+    connect: ["mpv:output_FL", "mpv:output_FR"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let settings = config.app_streams.clone().unwrap();
+        let ports: Vec<String> = [
+            "mpv:output_FL",
+            "mpv:output_FR",
+            "Zoom:output_MONO",
+            "Firefox:output_FL",
+            "Firefox:output_FR",
+        ]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+
+        let inputs = wanted_inputs(&settings, &ports, &config);
+        assert_eq!(inputs.len(), 1);
+        let firefox = &inputs[0];
+        assert_eq!(firefox.name, "Firefox");
+        assert_eq!(firefox.ports, ["app_firefox_1", "app_firefox_2"]);
+        assert_eq!(firefox.connect, ["Firefox:output_FL", "Firefox:output_FR"]);
+        assert_eq!(firefox.volume_db, Some(-6.0));
+        assert!(firefox.temporary);
+
+        // A known application keeps its input and settings
+        config.inputs.extend(inputs);
+        config.inputs[1].volume_db = Some(-20.0);
+        config.validate().unwrap();
+        let inputs = wanted_inputs(&settings, &ports, &config);
+        assert_eq!(inputs[0].volume_db, Some(-20.0));
+
+        // It goes once its ports are gone
+        assert!(wanted_inputs(&settings, &ports[..3], &config).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+//! Automatic port connections
+//!
+//! Connects channel ports to the external ports listed in the config and,
+//! when a remembered device is missing, proposes the closest available
+//! port from the same device family.
+
+use crate::config::ChannelConfig;
+
+/// Minimum share of the device family name that must match for a substitute
+const MIN_FAMILY_MATCH: f32 = 0.5;
+
+/// Which side of the mixer a connection belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// External source -> mixer input port
+    Input,
+
+    /// Mixer output port -> external sink
+    Output,
+}
+
+/// A configured connection whose target is missing, with a proposed substitute
+#[derive(Debug, Clone, PartialEq)]
+pub struct Substitution {
+    /// Side of the channel
+    pub side: Side,
+
+    /// Channel index within its side
+    pub channel: usize,
+
+    /// Index into the channel's `connect` list
+    pub entry: usize,
+
+    /// Local port name (without client prefix)
+    pub local_port: String,
+
+    /// Configured target that no longer exists
+    pub missing: String,
+
+    /// Best-match replacement
+    pub replacement: String,
+}
+
+/// A configured connection that could not be made or substituted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unresolved {
+    pub local_port: String,
+    pub missing: String,
+}
+
+/// Outcome of applying the configured connections
+#[derive(Debug, Default)]
+pub struct ConnectionReport {
+    /// Number of connections made
+    pub connected: usize,
+
+    /// Missing targets with a proposed substitute (awaiting confirmation)
+    pub substitutions: Vec<Substitution>,
+
+    /// Missing targets without any substitute
+    pub unresolved: Vec<Unresolved>,
+}
+
+/// Local port a `connect` entry belongs to
+///
+/// Entries map to ports by position; extra entries on a mono channel all
+/// use its single port (e.g. one mono output feeding two speakers).
+pub fn local_port_for_entry(channel: &ChannelConfig, entry: usize) -> Option<&String> {
+    channel.ports.get(entry.min(channel.ports.len().saturating_sub(1)))
+}
+
+/// Device family of a port: client name, lowercased, without digits
+fn device_family(port: &str) -> String {
+    let client = port.split(':').next().unwrap_or(port);
+    client
+        .chars()
+        .filter(|c| !c.is_ascii_digit())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Short port name after the client prefix
+fn short_name(port: &str) -> &str {
+    port.split_once(':').map(|(_, p)| p).unwrap_or(port)
+}
+
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count()
+}
+
+/// Find the best available substitute for a missing port
+///
+/// Candidates must share most of the device family name (client name with
+/// serial numbers removed); ties prefer the same short port name.
+pub fn best_match<'a>(missing: &str, candidates: &'a [String]) -> Option<&'a String> {
+    let family = device_family(missing);
+    let short = short_name(missing);
+    let min_len = ((family.chars().count() as f32) * MIN_FAMILY_MATCH).ceil() as usize;
+
+    candidates
+        .iter()
+        .filter(|c| c.as_str() != missing)
+        .map(|candidate| {
+            let family_score = common_prefix_len(&family, &device_family(candidate));
+            let port_score = if short_name(candidate) == short {
+                usize::MAX / 2
+            } else {
+                common_prefix_len(short, short_name(candidate))
+            };
+            (candidate, family_score, port_score)
+        })
+        .filter(|(_, family_score, _)| *family_score >= min_len.max(1))
+        .max_by_key(|(_, family_score, port_score)| (*family_score, *port_score))
+        .map(|(candidate, _, _)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_match_prefers_same_device_family() {
+        let candidates = vec![
+            "alsa_input.pci-0000_00_1f.3.analog-stereo:capture_FL".to_string(),
+            "alsa_input.usb-Blue_Yeti-01.analog-stereo:capture_FR".to_string(),
+            "alsa_input.usb-Blue_Yeti-01.analog-stereo:capture_FL".to_string(),
+        ];
+        let missing = "alsa_input.usb-Blue_Yeti-00.analog-stereo:capture_FL";
+        assert_eq!(best_match(missing, &candidates), Some(&candidates[2]));
+    }
+
+    #[test]
+    fn test_best_match_rejects_unrelated_devices() {
+        let candidates = vec!["system:capture_1".to_string()];
+        let missing = "alsa_input.usb-Blue_Yeti-00.analog-stereo:capture_FL";
+        assert_eq!(best_match(missing, &candidates), None);
+    }
+}
@@ -0,0 +1,188 @@
+//! Gain-sharing automixer for multi-mic panels
+//!
+//! Each input in the group gets the share of a fixed total gain that its
+//! level has of the group's summed level, as in Dugan's design: the power
+//! gains add up to one, so the mix of the group stays at the level of a
+//! single open microphone however many people sit at the table. Whoever
+//! speaks gets close to unity gain, the others drop by how much quieter
+//! they are. Levels rise fast and fall slowly; when everyone stops they all
+//! decay at the same rate, so the last talker keeps the gain until someone
+//! else speaks. A weight scales an input's level before the shares are
+//! taken, e.g. to favour the host over the guests.
+
+use crate::ipc::MeterData;
+
+/// Level smoothing time in ms for rising levels
+const ATTACK_MS: f32 = 5.0;
+
+/// Level smoothing time in ms for falling levels
+const RELEASE_MS: f32 = 250.0;
+
+/// Mean square added to every level (about -80 dBFS) so silent inputs
+/// share the gain evenly
+const NOISE_FLOOR: f32 = 1e-8;
+
+/// One input of the group
+#[derive(Debug, Clone)]
+struct Member {
+    /// Input channel index
+    input: usize,
+
+    /// Weight as a power factor
+    weight: f32,
+
+    /// Smoothed mean square
+    level: f32,
+
+    /// Gain (linear) at the start of the block
+    from: f32,
+
+    /// Gain (linear) at the end of the block
+    to: f32,
+}
+
+/// Automixer over a group of inputs
+#[derive(Debug, Clone)]
+pub struct Automixer {
+    members: Vec<Member>,
+
+    /// Smoothing time constants in samples
+    attack: f32,
+    release: f32,
+}
+
+impl Automixer {
+    /// Create an automixer over `(input, weight_db)` members at the given
+    /// sample rate (None for an empty group)
+    pub fn new(members: impl IntoIterator<Item = (usize, f32)>, sample_rate: f32) -> Option<Self> {
+        let members: Vec<Member> = members
+            .into_iter()
+            .map(|(input, weight_db)| Member {
+                input,
+                weight: power(weight_db),
+                level: 0.0,
+                from: 0.0,
+                to: 0.0,
+            })
+            .collect();
+        if members.is_empty() {
+            return None;
+        }
+        let mut automixer = Self {
+            members,
+            attack: ATTACK_MS / 1000.0 * sample_rate,
+            release: RELEASE_MS / 1000.0 * sample_rate,
+        };
+        // Start from even shares rather than fading in
+        automixer.update();
+        for member in &mut automixer.members {
+            member.from = member.to;
+        }
+        Some(automixer)
+    }
+
+    /// Change the weight of an input in the group
+    pub fn set_weight(&mut self, input: usize, weight_db: f32) {
+        if let Some(member) = self.members.iter_mut().find(|m| m.input == input) {
+            member.weight = power(weight_db);
+        }
+    }
+
+    /// Feed the mean square of an input over a block of `nframes` (0 for a
+    /// muted input, which then gives its share to the others)
+    pub fn measure(&mut self, input: usize, mean_square: f32, nframes: usize) {
+        let Some(member) = self.members.iter_mut().find(|m| m.input == input) else {
+            return;
+        };
+        let time = if mean_square > member.level {
+            self.attack
+        } else {
+            self.release
+        };
+        let keep = (-(nframes as f32) / time).exp();
+        member.level = mean_square + (member.level - mean_square) * keep;
+    }
+
+    /// Work out the gains for the block once every member is measured
+    pub fn update(&mut self) {
+        let total: f32 = self
+            .members
+            .iter()
+            .map(|m| m.weight * (m.level + NOISE_FLOOR))
+            .sum();
+        for member in &mut self.members {
+            member.from = member.to;
+            member.to = (member.weight * (member.level + NOISE_FLOOR) / total).sqrt();
+        }
+    }
+
+    /// Gain ramp of an input over the block (None outside the group)
+    pub fn gain(&self, input: usize) -> Option<(f32, f32)> {
+        self.members
+            .iter()
+            .find(|m| m.input == input)
+            .map(|m| (m.from, m.to))
+    }
+
+    /// Gain of an input at the end of the block in dB (0 outside the group)
+    pub fn gain_db(&self, input: usize) -> f32 {
+        self.gain(input)
+            .map_or(0.0, |(_, to)| MeterData::linear_to_db(to))
+    }
+}
+
+/// Power factor of a level in dB
+fn power(db: f32) -> f32 {
+    10f32.powf(db / 10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed constant levels for a second in 256-frame blocks
+    fn run(automixer: &mut Automixer, levels: &[(usize, f32)]) {
+        for _ in 0..188 {
+            for &(input, mean_square) in levels {
+                automixer.measure(input, mean_square, 256);
+            }
+            automixer.update();
+        }
+    }
+
+    fn total_power(automixer: &Automixer, inputs: &[usize]) -> f32 {
+        inputs
+            .iter()
+            .map(|&i| automixer.gain(i).unwrap().1.powi(2))
+            .sum()
+    }
+
+    #[test]
+    fn test_talker_takes_the_gain() {
+        let mut automixer = Automixer::new([(0, 0.0), (2, 0.0), (3, 0.0)], 48000.0).unwrap();
+        // Silence shares evenly
+        assert!((automixer.gain_db(0) + 4.77).abs() < 0.05);
+        assert_eq!(automixer.gain(1), None);
+        assert_eq!(automixer.gain_db(1), 0.0);
+
+        // One talker 30 dB over the room noise of the others
+        run(&mut automixer, &[(0, 1e-2), (2, 1e-5), (3, 1e-5)]);
+        assert!(automixer.gain_db(0) > -0.1);
+        assert!(automixer.gain_db(2) < -29.0);
+        assert!((total_power(&automixer, &[0, 2, 3]) - 1.0).abs() < 1e-4);
+
+        // The last talker holds the gain through a pause
+        run(&mut automixer, &[(0, 0.0), (2, 0.0), (3, 0.0)]);
+        assert!(automixer.gain_db(0) > -1.0);
+
+        // Two equal talkers share it, a weight tips the balance
+        run(&mut automixer, &[(0, 1e-2), (2, 1e-2), (3, 0.0)]);
+        assert!((automixer.gain_db(0) + 3.01).abs() < 0.05);
+        automixer.set_weight(2, 6.0);
+        automixer.update();
+        assert!(automixer.gain_db(2) > automixer.gain_db(0) + 5.9);
+        assert!((total_power(&automixer, &[0, 2, 3]) - 1.0).abs() < 1e-4);
+
+        assert!(Automixer::new([], 48000.0).is_none());
+    }
+}
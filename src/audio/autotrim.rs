@@ -0,0 +1,148 @@
+//! Automatic output trim on sustained overload
+//!
+//! A safety net for unattended installations: when a bus stays above its
+//! ceiling for longer than the hold time, its trim is lowered one step;
+//! once the bus has been comfortably below the ceiling for a while, the
+//! trim is slowly restored.
+
+use crate::config::AutoTrimConfig;
+use crate::ipc::MeterData;
+
+/// Margin below the ceiling the bus must stay under before trim is restored
+const RELEASE_MARGIN_DB: f32 = 3.0;
+
+/// Quiet time after the last reduction before trim starts to recover
+const RELEASE_DELAY_SECS: f32 = 2.0;
+
+/// Auto-trim state for one output channel
+#[derive(Debug, Clone)]
+pub struct AutoTrim {
+    /// Ceiling as a linear peak
+    ceiling: f32,
+
+    /// Release threshold as a linear peak
+    release_below: f32,
+
+    /// Samples the bus may stay above the ceiling
+    hold_samples: usize,
+
+    /// Reduction per step in dB
+    step_db: f32,
+
+    /// Maximum total reduction in dB
+    max_reduction_db: f32,
+
+    /// Recovery per sample in dB
+    release_per_sample_db: f32,
+
+    /// Samples to wait after a reduction before recovering
+    release_delay_samples: usize,
+
+    /// Consecutive samples above the ceiling
+    over_samples: usize,
+
+    /// Samples since the last reduction
+    since_reduction: usize,
+
+    /// Current reduction in dB (0 = untouched)
+    reduction_db: f32,
+}
+
+impl AutoTrim {
+    /// Create auto-trim from its config at the given sample rate
+    pub fn new(config: &AutoTrimConfig, sample_rate: f32) -> Self {
+        let release_delay_samples = (RELEASE_DELAY_SECS * sample_rate) as usize;
+        Self {
+            ceiling: MeterData::db_to_linear(config.ceiling_db),
+            release_below: MeterData::db_to_linear(config.ceiling_db - RELEASE_MARGIN_DB),
+            hold_samples: (config.hold_ms / 1000.0 * sample_rate) as usize,
+            step_db: config.step_db,
+            max_reduction_db: config.max_reduction_db,
+            release_per_sample_db: config.release_db_per_s / sample_rate,
+            release_delay_samples,
+            over_samples: 0,
+            since_reduction: release_delay_samples,
+            reduction_db: 0.0,
+        }
+    }
+
+    /// Current reduction in dB
+    pub fn reduction_db(&self) -> f32 {
+        self.reduction_db
+    }
+
+    /// Linear gain to apply to the bus
+    pub fn gain(&self) -> f32 {
+        MeterData::db_to_linear(-self.reduction_db)
+    }
+
+    /// Update with the (trimmed) peak of the last block
+    pub fn update(&mut self, peak: f32, nframes: usize) {
+        self.since_reduction = self.since_reduction.saturating_add(nframes);
+
+        if peak > self.ceiling {
+            self.over_samples += nframes;
+            if self.over_samples > self.hold_samples && self.reduction_db < self.max_reduction_db {
+                self.reduction_db = (self.reduction_db + self.step_db).min(self.max_reduction_db);
+                self.over_samples = 0;
+                self.since_reduction = 0;
+            }
+            return;
+        }
+
+        self.over_samples = 0;
+        if peak < self.release_below && self.since_reduction >= self.release_delay_samples {
+            self.reduction_db =
+                (self.reduction_db - self.release_per_sample_db * nframes as f32).max(0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AutoTrimConfig {
+        AutoTrimConfig {
+            ceiling_db: -1.0,
+            hold_ms: 10.0,
+            step_db: 1.0,
+            max_reduction_db: 3.0,
+            release_db_per_s: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_reduces_after_hold_and_caps() {
+        // 1 kHz sample rate: 10 samples of hold
+        let mut trim = AutoTrim::new(&config(), 1000.0);
+        trim.update(1.0, 10);
+        assert_eq!(trim.reduction_db(), 0.0);
+        trim.update(1.0, 1);
+        assert_eq!(trim.reduction_db(), 1.0);
+
+        for _ in 0..20 {
+            trim.update(1.0, 11);
+        }
+        assert_eq!(trim.reduction_db(), 3.0);
+    }
+
+    #[test]
+    fn test_recovers_slowly_when_quiet() {
+        let mut trim = AutoTrim::new(&config(), 1000.0);
+        trim.update(1.0, 11);
+        assert_eq!(trim.reduction_db(), 1.0);
+
+        // Nothing is restored during the release delay
+        trim.update(0.1, 1500);
+        assert_eq!(trim.reduction_db(), 1.0);
+
+        // Then 1 dB per second
+        trim.update(0.1, 500);
+        assert!((trim.reduction_db() - 0.5).abs() < 1e-3);
+
+        // Signal near the ceiling holds the current trim
+        trim.update(0.85, 1000);
+        assert!((trim.reduction_db() - 0.5).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,294 @@
+//! Capacity model
+//!
+//! Estimates what a config costs the audio thread per JACK period, so a
+//! config that is unlikely to keep up at the server's buffer size is
+//! reported at startup rather than found through xruns during a show. The
+//! costs are rough per-sample figures for a modest x86-64 core, rounded up:
+//! the estimate catches configs that are off by a wide margin, it does not
+//! predict the load. Every input is assumed to reach every output, since
+//! routes can be switched on while running.
+//!
+//! The model also sizes what the engine allocates before the audio thread
+//! starts: scratch buffers hold `MAX_BUFFER_FRAMES` so a buffer size change
+//! does not reallocate them, the meter queue holds every meter update the
+//! UI may leave waiting, and the control queue holds a burst of changes to
+//! every channel at once.
+
+use std::time::Duration;
+
+use crate::config::{ChannelConfig, Config, DspNodeKind, EqConfig, MULTIBAND_BANDS};
+use crate::ipc::MAX_NOTCHES;
+
+/// Frames the scratch buffers are allocated for up front
+pub const MAX_BUFFER_FRAMES: usize = 8192;
+
+/// Share of the period the estimate may take before a warning
+pub const LOAD_WARNING: f64 = 0.5;
+
+/// Smallest meter queue
+const MIN_METER_QUEUE: usize = 1024;
+
+/// Longest the UI may leave meter updates waiting (a few slow frames)
+const METER_DRAIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Smallest control queue
+const MIN_CONTROL_QUEUE: usize = 64;
+
+/// Control messages a burst may send per channel before the engine takes
+/// them (a scene recall sends a volume and a mute, with room to spare)
+const CONTROL_MESSAGES_PER_CHANNEL: usize = 4;
+
+/// One biquad section, per port and sample
+const BIQUAD_NS: f64 = 2.0;
+
+/// Fader ramp, peak, RMS and K-weighting of a port, per sample
+const CHANNEL_NS: f64 = 6.0;
+
+/// An envelope follower and gain computer (gate, compressor, de-esser,
+/// protection, limiter, auto trim), per port and sample
+const DYNAMICS_NS: f64 = 10.0;
+
+/// Summing one input into one output port, per sample
+const CROSSPOINT_NS: f64 = 0.5;
+
+/// Copying a sample of a passthrough or tap port
+const COPY_NS: f64 = 1.0;
+
+/// Port buffer lookup and meter update of a port, per period
+const PORT_PERIOD_NS: f64 = 500.0;
+
+/// Estimated cost of a config at one buffer size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CapacityEstimate {
+    /// Ports the client registers
+    pub ports: usize,
+
+    /// Input channel to output port sums
+    pub crosspoints: usize,
+
+    /// Frames per period
+    pub buffer_size: usize,
+
+    /// Duration of one period
+    pub period: Duration,
+
+    /// Estimated processing time per period
+    pub cost: Duration,
+
+    /// Meter updates the meter queue holds
+    pub meter_queue: usize,
+
+    /// Control messages the control queue holds
+    pub control_queue: usize,
+}
+
+impl CapacityEstimate {
+    /// Estimate the cost of `config` at the given sample rate and buffer size
+    pub fn new(config: &Config, sample_rate: usize, buffer_size: usize) -> Self {
+        let frames = buffer_size as f64;
+        let mut ports = 0;
+        let mut per_sample = 0.0;
+
+        for input in &config.inputs {
+            let count = input.port_count();
+            ports += count + tap_ports(input);
+            per_sample += count as f64 * (CHANNEL_NS + input_ns(input));
+        }
+
+        let mut output_ports = 0;
+        for output in &config.outputs {
+            let count = output.port_count();
+            output_ports += count;
+            ports += count + tap_ports(output);
+            per_sample += count as f64 * (CHANNEL_NS + output_ns(output));
+        }
+        let crosspoints = output_ports * config.inputs.len();
+        per_sample += crosspoints as f64 * CROSSPOINT_NS;
+
+        for passthrough in &config.passthrough {
+            let count = passthrough.inputs.len() + passthrough.outputs.len();
+            ports += count;
+            per_sample += passthrough.inputs.len() as f64 * COPY_NS;
+        }
+
+        let cost_ns = per_sample * frames + ports as f64 * PORT_PERIOD_NS;
+        let period = Duration::from_secs_f64(frames / sample_rate.max(1) as f64);
+
+        // Inputs, outputs, the master and passthrough channels each send one
+        // meter update per period
+        let channels = config.inputs.len() + config.outputs.len() + 1 + config.passthrough.len();
+        let drain_frames = sample_rate * METER_DRAIN_INTERVAL.as_millis() as usize / 1000;
+        let meter_queue = channels * drain_frames.div_ceil(buffer_size.max(1));
+        let control_queue = channels * CONTROL_MESSAGES_PER_CHANNEL;
+
+        Self {
+            ports,
+            crosspoints,
+            buffer_size,
+            period,
+            cost: Duration::from_nanos(cost_ns as u64),
+            meter_queue: meter_queue.max(MIN_METER_QUEUE),
+            control_queue: control_queue.max(MIN_CONTROL_QUEUE),
+        }
+    }
+
+    /// Estimated share of the period spent processing
+    pub fn load(&self) -> f64 {
+        self.cost.as_secs_f64() / self.period.as_secs_f64().max(1e-9)
+    }
+
+    /// Whether the config is unlikely to keep up at this buffer size
+    pub fn is_over_budget(&self) -> bool {
+        self.load() > LOAD_WARNING
+    }
+
+    /// Smallest power-of-two buffer size the estimate fits, if any up to
+    /// `MAX_BUFFER_FRAMES`
+    pub fn suggested_buffer_size(&self, config: &Config, sample_rate: usize) -> Option<usize> {
+        std::iter::successors(Some(16), |frames| Some(frames * 2))
+            .take_while(|frames| *frames <= MAX_BUFFER_FRAMES)
+            .find(|frames| !Self::new(config, sample_rate, *frames).is_over_budget())
+    }
+}
+
+/// A zeroed scratch buffer of `frames` samples that can grow to
+/// `MAX_BUFFER_FRAMES` without reallocating
+pub fn scratch_buffer(frames: usize) -> Vec<f32> {
+    let mut buffer = Vec::with_capacity(frames.max(MAX_BUFFER_FRAMES));
+    buffer.resize(frames, 0.0);
+    buffer
+}
+
+/// Extra ports a channel registers for its tap
+fn tap_ports(channel: &ChannelConfig) -> usize {
+    if channel.tap.is_some() {
+        channel.port_count()
+    } else {
+        0
+    }
+}
+
+/// Biquad sections of an EQ: the three fixed bands are always counted
+/// since they can be raised while running
+fn eq_sections(eq: Option<&EqConfig>) -> usize {
+    eq.map_or(3, |eq| {
+        3 + eq.bands.len()
+            + usize::from(eq.high_pass.is_some())
+            + usize::from(eq.low_pass.is_some())
+    })
+}
+
+/// Processing cost of an input port per sample
+fn input_ns(input: &ChannelConfig) -> f64 {
+    let mut ns = eq_sections(input.eq.as_ref()) as f64 * BIQUAD_NS;
+    if let Some(dsp) = &input.dsp {
+        for node in &dsp.nodes {
+            ns += match &node.kind {
+                DspNodeKind::Gain { .. } => COPY_NS,
+                DspNodeKind::Eq(eq) => eq_sections(Some(eq)) as f64 * BIQUAD_NS,
+                DspNodeKind::Gate { .. } | DspNodeKind::Comp { .. } => DYNAMICS_NS,
+                DspNodeKind::Send { .. } => COPY_NS,
+            };
+        }
+    }
+    if input.deesser.is_some() {
+        ns += 2.0 * BIQUAD_NS + DYNAMICS_NS;
+    }
+    if input.protect.is_some() {
+        ns += DYNAMICS_NS;
+    }
+    ns + mid_side_ns(input)
+}
+
+/// Processing cost of an output port per sample; the notches count in full
+/// since they can be set while running
+fn output_ns(output: &ChannelConfig) -> f64 {
+    let mut ns = MAX_NOTCHES as f64 * BIQUAD_NS;
+    if output.auto_trim.is_some() {
+        ns += DYNAMICS_NS;
+    }
+    if output.limiter.is_some() {
+        ns += DYNAMICS_NS;
+    }
+    if output.multiband.is_some() {
+        // Two crossovers of four sections, the all-pass and a gain per band
+        ns += 10.0 * BIQUAD_NS + MULTIBAND_BANDS as f64 * DYNAMICS_NS;
+    }
+    if let Some(clip) = &output.soft_clip {
+        // Eight filter sections around the curve when oversampling
+        let factor = clip.oversampling.max(1) as f64;
+        let filters = if factor > 1.0 { 8.0 * BIQUAD_NS } else { 0.0 };
+        ns += factor * (filters + COPY_NS);
+    }
+    if output.delay_ms.is_some() {
+        ns += COPY_NS;
+    }
+    ns + mid_side_ns(output)
+}
+
+/// Cost of the mid/side stage of a stereo channel per port and sample,
+/// counted since mid/side can be switched on while running
+fn mid_side_ns(channel: &ChannelConfig) -> f64 {
+    if channel.port_count() == 2 {
+        COPY_NS
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(inputs: usize, outputs: usize) -> Config {
+        let channels = |kind: &str, count: usize| {
+            (0..count)
+                .map(|i| {
+                    format!(
+                        "  - name: \"{kind}{i}\"\n    ports: [\"{kind}{i}_l\", \"{kind}{i}_r\"]\n"
+                    )
+                })
+                .collect::<String>()
+        };
+        let yaml = format!(
+            "client_name: \"Mixer\"\ninputs:\n{}outputs:\n{}",
+            channels("in", inputs),
+            channels("out", outputs)
+        );
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    #[test]
+    fn test_estimate_scales_with_ports_and_buses() {
+        let small = CapacityEstimate::new(&config(4, 2), 48000, 256);
+        assert_eq!(small.ports, 12);
+        assert_eq!(small.crosspoints, 16);
+        assert!(!small.is_over_budget());
+        assert_eq!(small.meter_queue, MIN_METER_QUEUE);
+        assert_eq!(small.control_queue, MIN_CONTROL_QUEUE);
+
+        // A large console at a tiny buffer runs out of time
+        let large = config(96, 32);
+        let tight = CapacityEstimate::new(&large, 48000, 16);
+        assert_eq!(tight.crosspoints, 96 * 64);
+        assert!(tight.is_over_budget());
+        // 129 channels, 300 periods in 100 ms
+        assert_eq!(tight.meter_queue, 129 * 300);
+        assert_eq!(tight.control_queue, 129 * CONTROL_MESSAGES_PER_CHANNEL);
+
+        // Longer periods spread the per-period overhead
+        let suggested = tight.suggested_buffer_size(&large, 48000).unwrap();
+        assert!(suggested > 16);
+        assert!(!CapacityEstimate::new(&large, 48000, suggested).is_over_budget());
+        assert!(CapacityEstimate::new(&large, 48000, suggested / 2).is_over_budget());
+    }
+
+    #[test]
+    fn test_scratch_buffer_grows_in_place() {
+        let mut buffer = scratch_buffer(64);
+        assert_eq!(buffer.len(), 64);
+        let capacity = buffer.capacity();
+        buffer.resize(MAX_BUFFER_FRAMES, 0.0);
+        assert_eq!(buffer.capacity(), capacity);
+    }
+}
@@ -0,0 +1,143 @@
+//! Metronome click
+//!
+//! Each beat starts a short sine burst that dies away within a few tens of
+//! milliseconds, pitched higher and louder on the first beat of the bar so
+//! the downbeat stands out in headphones. Beats are counted in samples with
+//! a fractional beat length, so the tempo does not drift over a long take.
+//! Starting the click begins a new bar; a tempo change takes effect at once
+//! without restarting it.
+
+use std::f32::consts::TAU;
+
+use crate::config::ClickConfig;
+use crate::ipc::MeterData;
+
+/// Pitch of the first beat of a bar in Hz
+const ACCENT_HZ: f32 = 1600.0;
+
+/// Pitch of the other beats in Hz
+const BEAT_HZ: f32 = 1000.0;
+
+/// Length of a click in ms
+const BURST_MS: f32 = 40.0;
+
+/// Decay time constant of a click in ms
+const DECAY_MS: f32 = 8.0;
+
+/// Click generator
+#[derive(Debug, Clone)]
+pub struct Click {
+    sample_rate: f32,
+
+    /// Peak gain of the beats and of the first beat of a bar (linear)
+    gain: f32,
+    accent_gain: f32,
+
+    beats_per_bar: u32,
+
+    /// Beat length in samples
+    beat_length: f64,
+
+    /// Samples since the current beat started
+    position: f64,
+
+    /// Beat within the bar (0 for the downbeat)
+    beat: u32,
+
+    running: bool,
+}
+
+impl Click {
+    /// Create a stopped click from its config at the given sample rate
+    pub fn new(config: &ClickConfig, sample_rate: f32) -> Self {
+        let mut click = Self {
+            sample_rate,
+            gain: MeterData::db_to_linear(config.level_db),
+            accent_gain: MeterData::db_to_linear(config.level_db + config.accent_db),
+            beats_per_bar: config.beats_per_bar.max(1),
+            beat_length: 0.0,
+            position: 0.0,
+            beat: 0,
+            running: false,
+        };
+        click.set_tempo(config.tempo_bpm);
+        click
+    }
+
+    /// Change the tempo in beats per minute
+    pub fn set_tempo(&mut self, tempo_bpm: f32) {
+        self.beat_length = 60.0 * f64::from(self.sample_rate) / f64::from(tempo_bpm.max(1.0));
+    }
+
+    /// Start (at the top of a bar) or stop the click
+    pub fn set_running(&mut self, running: bool) {
+        if running && !self.running {
+            self.position = 0.0;
+            self.beat = 0;
+        }
+        self.running = running;
+    }
+
+    /// Fill `samples` with the next block of the click (silence when stopped)
+    pub fn render(&mut self, samples: &mut [f32]) {
+        if !self.running {
+            samples.fill(0.0);
+            return;
+        }
+        let burst = BURST_MS / 1000.0 * self.sample_rate;
+        let decay = DECAY_MS / 1000.0 * self.sample_rate;
+        for s in samples.iter_mut() {
+            if self.position >= self.beat_length {
+                self.position -= self.beat_length;
+                self.beat = (self.beat + 1) % self.beats_per_bar;
+            }
+            let t = self.position as f32;
+            *s = if t < burst {
+                let (freq, gain) = if self.beat == 0 {
+                    (ACCENT_HZ, self.accent_gain)
+                } else {
+                    (BEAT_HZ, self.gain)
+                };
+                gain * (TAU * freq * t / self.sample_rate).sin() * (-t / decay).exp()
+            } else {
+                0.0
+            };
+            self.position += 1.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beats_and_accents() {
+        let config = ClickConfig {
+            outputs: vec!["Phones".to_string()],
+            tempo_bpm: 120.0,
+            beats_per_bar: 3,
+            level_db: -12.0,
+            accent_db: 6.0,
+        };
+        let mut click = Click::new(&config, 48000.0);
+        let mut samples = vec![1.0; 48000 * 2];
+        click.render(&mut samples);
+        assert!(samples.iter().all(|s| *s == 0.0));
+
+        // Two seconds at 120 BPM: beats every 24000 samples, the fourth
+        // starting the next bar
+        click.set_running(true);
+        click.render(&mut samples);
+        let peak = |from: usize| {
+            samples[from..from + 2400]
+                .iter()
+                .fold(0.0f32, |m, s| m.max(s.abs()))
+        };
+        assert!(peak(0) > 0.4 && peak(0) <= 0.51);
+        assert!(peak(24000) > 0.2 && peak(24000) <= 0.26);
+        assert!(peak(48000) < 0.26);
+        assert!(peak(72000) > 0.4);
+        assert!(samples[2400..24000].iter().all(|s| *s == 0.0));
+    }
+}
@@ -0,0 +1,160 @@
+//! De-esser for voice channels
+//!
+//! A band-pass around the sibilance frequency feeds a fast peak detector.
+//! While the band is above the threshold only the band is turned down: the
+//! attenuated share of the band-passed signal is subtracted from each port,
+//! leaving the rest of the spectrum untouched. The ports of a channel share
+//! one gain so stereo images stay in place.
+
+use std::f32::consts::PI;
+
+use super::dsp::Biquad;
+use crate::config::DeEsserConfig;
+use crate::ipc::{MeterData, MAX_CHANNEL_PORTS};
+
+/// Quality factor of the sibilance band (about an octave wide)
+const BAND_Q: f32 = 1.4;
+
+/// Detector attack time in ms (sibilants are short)
+const ATTACK_MS: f32 = 1.0;
+
+/// Detector release time in ms
+const RELEASE_MS: f32 = 60.0;
+
+/// Fraction of the overshoot removed (a 4:1 ratio)
+const SLOPE: f32 = 0.75;
+
+/// De-esser state for one input channel
+#[derive(Debug, Clone)]
+pub struct DeEsser {
+    /// Band-pass filter per port
+    bands: [Biquad; MAX_CHANNEL_PORTS],
+
+    threshold_db: f32,
+
+    max_reduction_db: f32,
+
+    /// Envelope smoothing for rising levels
+    attack: f32,
+
+    /// Envelope smoothing for falling levels
+    release: f32,
+
+    /// Current band envelope (linear)
+    envelope: f32,
+
+    /// Most reduction applied since the last `take_reduction_db`
+    max_applied_db: f32,
+}
+
+impl DeEsser {
+    /// Create a de-esser from its config at the given sample rate
+    pub fn new(config: &DeEsserConfig, sample_rate: f32) -> Self {
+        let w0 = 2.0 * PI * (config.freq / sample_rate).min(0.49);
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * BAND_Q);
+        let a0 = 1.0 + alpha;
+        // RBJ band-pass with 0 dB gain at the center
+        let band = Biquad::new(
+            alpha / a0,
+            0.0,
+            -alpha / a0,
+            -2.0 * cos / a0,
+            (1.0 - alpha) / a0,
+        );
+        let smoothing = |ms: f32| 1.0 - (-1000.0 / (ms * sample_rate)).exp();
+
+        Self {
+            bands: [band; MAX_CHANNEL_PORTS],
+            threshold_db: config.threshold_db,
+            max_reduction_db: config.max_reduction_db,
+            attack: smoothing(ATTACK_MS),
+            release: smoothing(RELEASE_MS),
+            envelope: 0.0,
+            max_applied_db: 0.0,
+        }
+    }
+
+    /// Most band reduction in dB since the last call (0 when idle)
+    pub fn take_reduction_db(&mut self) -> f32 {
+        std::mem::take(&mut self.max_applied_db)
+    }
+
+    /// De-ess the ports of a channel in place (all slices the same length)
+    pub fn process(&mut self, ports: &mut [&mut [f32]]) {
+        let count = ports.len().min(MAX_CHANNEL_PORTS);
+        let ports = &mut ports[..count];
+        let nframes = ports.first().map_or(0, |p| p.len());
+
+        for i in 0..nframes {
+            let mut band = [0.0f32; MAX_CHANNEL_PORTS];
+            let mut level = 0.0f32;
+            for ((port, filter), b) in ports.iter().zip(&mut self.bands).zip(&mut band) {
+                *b = filter.process(port[i]);
+                level = level.max(b.abs());
+            }
+
+            let coeff = if level > self.envelope {
+                self.attack
+            } else {
+                self.release
+            };
+            self.envelope += (level - self.envelope) * coeff;
+
+            let over_db = MeterData::linear_to_db(self.envelope) - self.threshold_db;
+            if over_db <= 0.0 {
+                continue;
+            }
+            let reduction_db = (over_db * SLOPE).min(self.max_reduction_db);
+            self.max_applied_db = self.max_applied_db.max(reduction_db);
+            let cut = 1.0 - MeterData::db_to_linear(-reduction_db);
+            for (port, b) in ports.iter_mut().zip(band) {
+                port[i] -= b * cut;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deesser() -> DeEsser {
+        let config = DeEsserConfig {
+            threshold_db: -30.0,
+            freq: 6000.0,
+            max_reduction_db: 12.0,
+        };
+        DeEsser::new(&config, 48000.0)
+    }
+
+    /// Peak of the second half of a sine at `freq` through the de-esser
+    fn settled_peak(deesser: &mut DeEsser, freq: f32, amplitude: f32) -> f32 {
+        let mut samples: Vec<f32> = (0..9600)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / 48000.0).sin())
+            .collect();
+        deesser.process(&mut [&mut samples]);
+        crate::audio::dsp::peak(&samples[4800..])
+    }
+
+    #[test]
+    fn test_sibilance_is_reduced() {
+        let mut deesser = deesser();
+        let peak = settled_peak(&mut deesser, 6000.0, 0.5);
+        let reduction = deesser.take_reduction_db();
+        assert!(reduction > 11.0, "{}", reduction);
+        assert!(MeterData::linear_to_db(peak / 0.5) < -10.0);
+    }
+
+    #[test]
+    fn test_low_frequencies_pass() {
+        let mut deesser = deesser();
+        let peak = settled_peak(&mut deesser, 300.0, 0.5);
+        assert!((peak - 0.5).abs() < 0.01);
+        assert_eq!(deesser.take_reduction_db(), 0.0);
+
+        // Quiet sibilance below the threshold is left alone
+        let peak = settled_peak(&mut deesser, 6000.0, 0.01);
+        assert!((peak - 0.01).abs() < 0.001);
+    }
+}
@@ -0,0 +1,66 @@
+//! Output delay for lip sync
+//!
+//! Holds an output bus back by a fixed time so a monitor feed lines up with
+//! video that reaches the screen late, while other outputs (e.g. the one
+//! being recorded) stay undelayed. The ports of a channel share one delay
+//! line of whole frames.
+
+use crate::ipc::MAX_CHANNEL_PORTS;
+
+/// Delay line of one output channel
+#[derive(Debug, Clone)]
+pub struct OutputDelay {
+    /// One frame of all ports per sample of delay
+    frames: Vec<[f32; MAX_CHANNEL_PORTS]>,
+
+    /// Next frame to read and overwrite
+    pos: usize,
+}
+
+impl OutputDelay {
+    /// Create a delay of `delay_ms` at the given sample rate (None if it
+    /// rounds to no samples)
+    pub fn new(delay_ms: f32, sample_rate: f32) -> Option<Self> {
+        let samples = (delay_ms / 1000.0 * sample_rate).round() as usize;
+        (samples > 0).then(|| Self {
+            frames: vec![[0.0; MAX_CHANNEL_PORTS]; samples],
+            pos: 0,
+        })
+    }
+
+    /// Delay the ports of a channel in place
+    pub fn process(&mut self, ports: &mut [&mut [f32]]) {
+        let len = ports.first().map_or(0, |p| p.len());
+        for i in 0..len {
+            let frame = &mut self.frames[self.pos];
+            for (port, delayed) in ports.iter_mut().zip(frame.iter_mut()) {
+                std::mem::swap(&mut port[i], delayed);
+            }
+            self.pos = (self.pos + 1) % self.frames.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ports_come_out_later() {
+        // 3 samples at 1 kHz
+        let mut delay = OutputDelay::new(3.0, 1000.0).unwrap();
+        let mut left = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let mut right = [-1.0, -2.0, -3.0, -4.0, -5.0];
+        delay.process(&mut [&mut left, &mut right]);
+        assert_eq!(left, [0.0, 0.0, 0.0, 1.0, 2.0]);
+        assert_eq!(right, [0.0, 0.0, 0.0, -1.0, -2.0]);
+
+        // Continues across periods
+        let mut left = [0.0; 2];
+        let mut right = [0.0; 2];
+        delay.process(&mut [&mut left, &mut right]);
+        assert_eq!(left, [3.0, 4.0]);
+
+        assert!(OutputDelay::new(0.0, 48000.0).is_none());
+    }
+}
@@ -0,0 +1,430 @@
+//! DSP building blocks used by the audio engine
+//!
+//! All types here are allocation-free after construction and safe to use
+//! from the real-time process callback.
+
+use std::f32::consts::PI;
+
+use crate::ipc::SCOPE_POINTS;
+
+/// Mean square below which a stereo pair counts as silent (about -90 dBFS)
+const SILENCE_MEAN_SQUARE: f32 = 1e-9;
+
+/// Second-order IIR filter (transposed direct form II)
+#[derive(Debug, Clone, Copy)]
+pub struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    /// Create a biquad from normalized coefficients (a0 == 1)
+    pub fn new(b0: f32, b1: f32, b2: f32, a1: f32, a2: f32) -> Self {
+        Self {
+            b0,
+            b1,
+            b2,
+            a1,
+            a2,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    /// Replace the coefficients, keeping the filter state
+    pub fn set_coefficients(&mut self, b0: f32, b1: f32, b2: f32, a1: f32, a2: f32) {
+        self.b0 = b0;
+        self.b1 = b1;
+        self.b2 = b2;
+        self.a1 = a1;
+        self.a2 = a2;
+    }
+
+    /// Clear the filter state
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+
+    /// Process a single sample
+    #[inline]
+    pub fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+/// ITU-R BS.1770 K-weighting filter (high shelf followed by high pass)
+#[derive(Debug, Clone, Copy)]
+pub struct KWeighting {
+    shelf: Biquad,
+    highpass: Biquad,
+}
+
+impl KWeighting {
+    /// Create a K-weighting filter for the given sample rate
+    pub fn new(sample_rate: f32) -> Self {
+        // Stage 1: high shelf modelling the acoustic effect of the head
+        let f0 = 1_681.974_5_f32;
+        let gain_db = 3.999_843_9_f32;
+        let q = 0.707_175_24_f32;
+        let k = (PI * f0 / sample_rate).tan();
+        let vh = 10.0_f32.powf(gain_db / 20.0);
+        let vb = vh.powf(0.499_666_77);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = Biquad::new(
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        );
+
+        // Stage 2: RLB high pass
+        let f0 = 38.135_47_f32;
+        let q = 0.500_327_04_f32;
+        let k = (PI * f0 / sample_rate).tan();
+        let a0 = 1.0 + k / q + k * k;
+        let highpass = Biquad::new(
+            1.0,
+            -2.0,
+            1.0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        );
+
+        Self { shelf, highpass }
+    }
+
+    /// Filter a block and return its mean square (K-weighted power)
+    pub fn mean_square(&mut self, samples: &[f32]) -> f32 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+        let mut sum = 0.0_f32;
+        for &s in samples {
+            let y = self.highpass.process(self.shelf.process(s));
+            sum += y * y;
+        }
+        sum / samples.len() as f32
+    }
+}
+
+/// Gain fade between silence and full level, so switching a channel off
+/// and on does not click
+#[derive(Debug, Clone, Copy)]
+pub struct Fade {
+    /// Current gain (0 to 1)
+    gain: f32,
+
+    /// Gain change per sample
+    step: f32,
+}
+
+impl Fade {
+    /// Create a fade taking `fade_ms`, starting fully open or closed
+    pub fn new(fade_ms: f32, sample_rate: f32, open: bool) -> Self {
+        Self {
+            gain: if open { 1.0 } else { 0.0 },
+            step: 1000.0 / (fade_ms * sample_rate).max(1.0),
+        }
+    }
+
+    /// Move towards full level (`open`) or silence over a block of
+    /// `nframes` samples; returns the gain at the start and at the end
+    pub fn advance(&mut self, open: bool, nframes: usize) -> (f32, f32) {
+        let from = self.gain;
+        let distance = self.step * nframes as f32;
+        self.gain = if open {
+            (from + distance).min(1.0)
+        } else {
+            (from - distance).max(0.0)
+        };
+        (from, self.gain)
+    }
+}
+
+/// Left and right gains of a stereo balance correction, split evenly so
+/// a centred sound keeps its level: positive raises the right side
+pub fn balance_gains(balance_db: f32) -> [f32; 2] {
+    let half = 10f32.powf(balance_db / 40.0);
+    [1.0 / half, half]
+}
+
+/// Multiply samples by a gain moving linearly from `from` to `to`
+pub fn apply_ramp(samples: &mut [f32], from: f32, to: f32) {
+    if from == to {
+        for s in samples.iter_mut() {
+            *s *= to;
+        }
+        return;
+    }
+    let delta = (to - from) / samples.len() as f32;
+    for (i, s) in samples.iter_mut().enumerate() {
+        *s *= from + delta * (i + 1) as f32;
+    }
+}
+
+/// Add `input` times a gain moving linearly from `from` to `to` to `output`
+pub fn mix_ramp(output: &mut [f32], input: &[f32], from: f32, to: f32) {
+    if from == to {
+        for (out_s, in_s) in output.iter_mut().zip(input) {
+            *out_s += in_s * to;
+        }
+        return;
+    }
+    let delta = (to - from) / output.len().min(input.len()) as f32;
+    for (i, (out_s, in_s)) in output.iter_mut().zip(input).enumerate() {
+        *out_s += in_s * (from + delta * (i + 1) as f32);
+    }
+}
+
+/// Compute peak level of samples (linear scale)
+pub fn peak(samples: &[f32]) -> f32 {
+    samples
+        .iter()
+        .map(|s| s.abs())
+        .fold(0.0_f32, |a, b| a.max(b))
+}
+
+/// Number of samples above full scale once multiplied by `gain`
+pub fn count_clipped(samples: &[f32], gain: f32) -> u32 {
+    samples.iter().filter(|s| (*s * gain).abs() > 1.0).count() as u32
+}
+
+/// Compute RMS level of samples (linear scale)
+pub fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f32 = samples.iter().map(|s| s * s).sum();
+    (sum / samples.len() as f32).sqrt()
+}
+
+/// Peak and RMS levels (linear) of the mid, (L + R) / 2, and the side,
+/// (L - R) / 2, of a stereo pair whose sides are first scaled by `gains`
+pub fn mid_side_levels(left: &[f32], right: &[f32], gains: [f32; 2]) -> ([f32; 2], [f32; 2]) {
+    let mut peaks = [0.0f32; 2];
+    let mut sums = [0.0f32; 2];
+    for (l, r) in left.iter().zip(right) {
+        let (l, r) = (l * gains[0], r * gains[1]);
+        for (i, s) in [(l + r) * 0.5, (l - r) * 0.5].into_iter().enumerate() {
+            peaks[i] = peaks[i].max(s.abs());
+            sums[i] += s * s;
+        }
+    }
+    let frames = left.len().min(right.len()).max(1) as f32;
+    (peaks, sums.map(|sum| (sum / frames).sqrt()))
+}
+
+/// Encode a stereo pair to mid and side, scale them by gains moving linearly
+/// from `from` to `to` ([mid, side]) and decode back to left and right
+pub fn mid_side_ramp(left: &mut [f32], right: &mut [f32], from: [f32; 2], to: [f32; 2]) {
+    let frames = left.len().min(right.len()) as f32;
+    let delta = [(to[0] - from[0]) / frames, (to[1] - from[1]) / frames];
+    for (i, (l, r)) in left.iter_mut().zip(right.iter_mut()).enumerate() {
+        let step = (i + 1) as f32;
+        let mid = (*l + *r) * 0.5 * (from[0] + delta[0] * step);
+        let side = (*l - *r) * 0.5 * (from[1] + delta[1] * step);
+        *l = mid + side;
+        *r = mid - side;
+    }
+}
+
+/// Correlation of a stereo pair, from +1 (mono) through 0 (unrelated or
+/// one side silent) to -1 (one side inverted); None for silence
+pub fn correlation(left: &[f32], right: &[f32]) -> Option<f32> {
+    let (mut lr, mut ll, mut rr) = (0.0f32, 0.0f32, 0.0f32);
+    for (l, r) in left.iter().zip(right) {
+        lr += l * r;
+        ll += l * l;
+        rr += r * r;
+    }
+    let frames = left.len().min(right.len()).max(1) as f32;
+    if (ll + rr) / frames < SILENCE_MEAN_SQUARE {
+        return None;
+    }
+    Some((lr / (ll * rr).sqrt().max(f32::MIN_POSITIVE)).clamp(-1.0, 1.0))
+}
+
+/// Left/right sample pairs picked evenly across a block, for a goniometer
+pub fn scope_points(left: &[f32], right: &[f32]) -> [[f32; 2]; SCOPE_POINTS] {
+    let frames = left.len().min(right.len());
+    let mut points = [[0.0; 2]; SCOPE_POINTS];
+    if frames == 0 {
+        return points;
+    }
+    for (i, point) in points.iter_mut().enumerate() {
+        let at = i * frames / SCOPE_POINTS;
+        *point = [left[at], right[at]];
+    }
+    points
+}
+
+/// Convert a K-weighted mean square to LUFS
+pub fn mean_square_to_lufs(mean_square: f32) -> f32 {
+    if mean_square <= 0.0 {
+        f32::NEG_INFINITY
+    } else {
+        -0.691 + 10.0 * mean_square.log10()
+    }
+}
+
+/// Integrated loudness in LUFS (BS.1770 gating) from K-weighted powers of
+/// consecutive 100 ms blocks, summed over the channels
+///
+/// Gating blocks are 400 ms long and overlap by 75%. Returns `None` when
+/// nothing is above the absolute gate (or less than 400 ms was measured).
+pub fn integrated_loudness(block_powers: &[f32]) -> Option<f32> {
+    const ABSOLUTE_GATE_LUFS: f32 = -70.0;
+    const RELATIVE_GATE_LU: f32 = 10.0;
+
+    let gating_blocks: Vec<f32> = block_powers
+        .windows(4)
+        .map(|w| w.iter().sum::<f32>() / 4.0)
+        .filter(|&p| mean_square_to_lufs(p) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if gating_blocks.is_empty() {
+        return None;
+    }
+
+    let mean = |blocks: &mut dyn Iterator<Item = f32>| {
+        let (sum, count) = blocks.fold((0.0f64, 0usize), |(s, n), p| (s + f64::from(p), n + 1));
+        (sum / count.max(1) as f64) as f32
+    };
+    let relative_gate =
+        mean_square_to_lufs(mean(&mut gating_blocks.iter().copied())) - RELATIVE_GATE_LU;
+    let gated = mean(
+        &mut gating_blocks
+            .iter()
+            .copied()
+            .filter(|&p| mean_square_to_lufs(p) > relative_gate),
+    );
+    Some(mean_square_to_lufs(gated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rms_of_full_scale_square_wave() {
+        let samples = [1.0, -1.0, 1.0, -1.0];
+        assert!((rms(&samples) - 1.0).abs() < 1e-6);
+        assert!((peak(&samples) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_count_clipped() {
+        // Full scale itself does not clip; trim can push samples over it
+        let samples = [1.0, -1.2, 0.6, -0.9];
+        assert_eq!(count_clipped(&samples, 1.0), 1);
+        assert_eq!(count_clipped(&samples, 2.0), 4);
+        assert_eq!(count_clipped(&samples, 0.5), 0);
+    }
+
+    #[test]
+    fn test_mid_side_levels() {
+        // Identical channels are all mid, opposite ones all side
+        let left = [0.5, -0.5, 0.5, -0.5];
+        let unity = [1.0, 1.0];
+        assert_eq!(mid_side_levels(&left, &left, unity), ([0.5, 0.0], [0.5, 0.0]));
+        let right = left.map(|s| -s);
+        assert_eq!(mid_side_levels(&left, &right, unity), ([0.0, 0.5], [0.0, 0.5]));
+        // Gains scale the sides before encoding, like a balance correction
+        assert_eq!(mid_side_levels(&left, &left, [2.0, 0.0]), ([0.5, 0.5], [0.5, 0.5]));
+    }
+
+    #[test]
+    fn test_mid_side_ramp() {
+        // Unity gains decode back to the source
+        let mut left = [0.5, 0.25, -0.5, 1.0];
+        let mut right = [0.25, -0.25, 0.5, 0.0];
+        let (source_left, source_right) = (left, right);
+        mid_side_ramp(&mut left, &mut right, [1.0, 1.0], [1.0, 1.0]);
+        assert_eq!((left, right), (source_left, source_right));
+
+        // Muting the side leaves the mid on both sides
+        mid_side_ramp(&mut left, &mut right, [1.0, 0.0], [1.0, 0.0]);
+        assert_eq!(left, right);
+        assert_eq!(left, [0.375, 0.0, 0.0, 0.5]);
+
+        // Gains ramp to the target by the last frame
+        let mut left = [1.0; 4];
+        let mut right = [1.0; 4];
+        mid_side_ramp(&mut left, &mut right, [0.0, 1.0], [1.0, 1.0]);
+        assert_eq!(left, [0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(right, left);
+    }
+
+    #[test]
+    fn test_correlation() {
+        let left: Vec<f32> = (0..256).map(|i| (i as f32 * 0.1).sin() * 0.5).collect();
+        let inverted: Vec<f32> = left.iter().map(|s| -s).collect();
+        let silent = vec![0.0; 256];
+        assert!((correlation(&left, &left).unwrap() - 1.0).abs() < 1e-5);
+        assert!((correlation(&left, &inverted).unwrap() + 1.0).abs() < 1e-5);
+        assert_eq!(correlation(&left, &silent), Some(0.0));
+        assert_eq!(correlation(&silent, &silent), None);
+
+        let points = scope_points(&left, &inverted);
+        assert_eq!(points[1], [left[8], -left[8]]);
+    }
+
+    #[test]
+    fn test_fade_ramps_without_steps() {
+        // 10 ms at 48 kHz: 480 samples, spread over two 256-sample blocks
+        let mut fade = Fade::new(10.0, 48000.0, true);
+        let (from, to) = fade.advance(false, 256);
+        assert_eq!(from, 1.0);
+        assert!((to - (1.0 - 256.0 / 480.0)).abs() < 1e-6);
+
+        let mut samples = [1.0f32; 256];
+        apply_ramp(&mut samples, from, to);
+        assert!(samples.windows(2).all(|w| w[0] - w[1] < 0.01 && w[1] < w[0]));
+        assert_eq!(samples[255], to);
+
+        assert_eq!(fade.advance(false, 256), (to, 0.0));
+        assert_eq!(fade.advance(false, 256), (0.0, 0.0));
+
+        let mut mix = [0.5f32; 4];
+        mix_ramp(&mut mix, &[1.0; 4], 0.0, 1.0);
+        assert_eq!(mix, [0.75, 1.0, 1.25, 1.5]);
+    }
+
+    #[test]
+    fn test_k_weighting_1khz_sine_reads_near_minus_3_lufs() {
+        // A full-scale 1 kHz sine on one channel reads about -3.01 LUFS
+        let sample_rate = 48000.0;
+        let mut filter = KWeighting::new(sample_rate);
+        let samples: Vec<f32> = (0..48000)
+            .map(|i| (2.0 * PI * 1000.0 * i as f32 / sample_rate).sin())
+            .collect();
+        // Let the filter settle before measuring
+        filter.mean_square(&samples[..4800]);
+        let lufs = mean_square_to_lufs(filter.mean_square(&samples[4800..]));
+        assert!((lufs - -3.01).abs() < 0.1, "got {}", lufs);
+    }
+
+    #[test]
+    fn test_integrated_loudness_gates_silence() {
+        // -20 LUFS program with a quiet stretch below the relative gate
+        let program = 10f32.powf((-20.0 + 0.691) / 10.0);
+        let quiet = 10f32.powf((-40.0 + 0.691) / 10.0);
+        let mut blocks = vec![program; 100];
+        blocks.extend(vec![quiet; 100]);
+        blocks.extend(vec![0.0; 100]);
+        let lufs = integrated_loudness(&blocks).unwrap();
+        assert!((lufs - -20.0).abs() < 0.1, "got {}", lufs);
+
+        assert_eq!(integrated_loudness(&[0.0; 50]), None);
+        assert_eq!(integrated_loudness(&[program; 3]), None);
+    }
+}
@@ -5,43 +5,138 @@
 //! Works with PipeWire's JACK compatibility layer.
 
 use anyhow::{Context, Result};
-use jack::{AudioIn, AudioOut, Client, ClientOptions, Control, Port, ProcessScope};
+use jack::{AudioIn, AudioOut, Client, ClientOptions, Control, MidiIn, Port, ProcessScope};
 use rtrb::{Consumer, Producer, RingBuffer};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::config::Config;
-use crate::ipc::{ChannelState, ControlMsg, MeterData, MixerState};
+use super::autoconnect::{self, ConnectionReport, Side, Substitution, Unresolved};
+use super::autotrim::AutoTrim;
+use super::capacity::{self, CapacityEstimate};
+use super::dsp::{self, Fade, KWeighting};
+use super::eq::ChannelEq;
+use super::graph::DspGraph;
+use super::automix::Automixer;
+use super::click::Click;
+use super::deesser::DeEsser;
+use super::delay::OutputDelay;
+use super::limiter::Limiter;
+use super::midi::ProgramChangeDecoder;
+use super::multiband::Multiband;
+use super::notch::OutputNotches;
+use super::mixing::MixStrategy;
+use super::noise::PinkNoise;
+use super::protect::Protector;
+use super::recorder::{RecorderFeed, Recording};
+use super::replay::{self, ReplayBuffer, ReplayTap};
+use super::silence::{PortStatus, PortStatuses, SilenceDetector};
+use super::softclip::SoftClip;
+use super::watchdog::{EngineHealth, Watchdog, WatchdogSignals};
+use crate::config::{ChannelConfig, Config, TapPoint, MULTIBAND_BANDS};
+use crate::ipc::{
+    ControlLatencyStats, ControlMsg, LatencySnapshot, MeterData, MixerState, ProgramChange,
+    ScopeData, AnalyzerSource, TimedControlMsg, MAX_CHANNEL_PORTS, VOLUME_MIN_DB,
+};
+use crate::solo;
 
-/// Size of the ring buffer for meter data
-const METER_RING_BUFFER_SIZE: usize = 1024;
+/// Size of the ring buffer for MIDI program changes
+const PROGRAM_RING_BUFFER_SIZE: usize = 32;
 
-/// Size of the ring buffer for control messages
-const CONTROL_RING_BUFFER_SIZE: usize = 64;
+/// Size of the ring buffer for stereo scope data (audio -> UI)
+const SCOPE_RING_BUFFER_SIZE: usize = 256;
+
+/// Length of the fade when a channel is muted, unmuted or soloed out
+const MUTE_FADE_MS: f32 = 10.0;
+
+/// Longest the callback waits for the recorder while freewheeling
+const FREEWHEEL_WAIT: Duration = Duration::from_secs(1);
 
 /// Audio engine that manages JACK connections and processing
 pub struct AudioEngine {
-    /// JACK async client handle
-    _async_client: jack::AsyncClient<Notifications, ProcessHandler>,
+    /// JACK async client handle (None once stopped)
+    async_client: Option<jack::AsyncClient<Notifications, ProcessHandler>>,
 
     /// Producer for sending control messages to audio thread
-    control_producer: Producer<ControlMsg>,
+    control_producer: Producer<TimedControlMsg>,
 
     /// Consumer for receiving meter data from audio thread
     meter_consumer: Consumer<MeterData>,
 
+    /// Consumer for MIDI program changes from the audio thread
+    program_consumer: Consumer<ProgramChange>,
+
+    /// Consumer for stereo scope data from the audio thread
+    scope_consumer: Consumer<ScopeData>,
+
+    /// Consumer for the samples of the analyzed channel
+    analyzer_consumer: Consumer<f32>,
+
+    /// Sample rate of the JACK server in Hz
+    sample_rate: u32,
+
     /// Flag to signal the audio thread to quit
     quit_flag: Arc<AtomicBool>,
+
+    /// Control latency statistics (recorded by the audio thread)
+    latency_stats: Arc<ControlLatencyStats>,
+
+    /// Duration of one JACK process cycle
+    period: Duration,
+
+    /// Watchdog for the process callback
+    watchdog: Watchdog,
+
+    /// Rolling buffer of the recorded output, if configured
+    replay: Option<ReplayBuffer>,
+
+    /// Recorder of one output, if configured
+    recorder: Option<RecorderFeed>,
+
+    /// Xruns reported by the server since the engine started
+    xruns: Arc<AtomicU64>,
+
+    /// Whether the server is freewheeling (set by the notification handler)
+    freewheeling: Arc<AtomicBool>,
+
+    /// Whether this engine switched freewheeling on
+    freewheel_requested: bool,
+
+    /// Signal state of the input ports (updated by the audio thread)
+    port_statuses: Arc<PortStatuses>,
+
+    /// Estimated cost of the config at the buffer size the engine started with
+    capacity: CapacityEstimate,
+}
+
+/// Control queue diagnostics for the UI
+#[derive(Debug, Clone, Copy)]
+pub struct ControlDiagnostics {
+    /// Latency statistics
+    pub latency: LatencySnapshot,
+
+    /// Messages waiting for the audio thread
+    pub queued: usize,
+
+    /// Control queue capacity
+    pub capacity: usize,
+
+    /// Duration of one JACK process cycle
+    pub period: Duration,
 }
 
 impl AudioEngine {
     /// Create and start the audio engine
     pub fn new(config: Config) -> Result<Self> {
         // Create ring buffers for communication
-        let (meter_producer, meter_consumer) = RingBuffer::new(METER_RING_BUFFER_SIZE);
-        let (control_producer, control_consumer) = RingBuffer::new(CONTROL_RING_BUFFER_SIZE);
+        let (program_producer, program_consumer) = RingBuffer::new(PROGRAM_RING_BUFFER_SIZE);
+        let (scope_producer, scope_consumer) = RingBuffer::new(SCOPE_RING_BUFFER_SIZE);
 
         let quit_flag = Arc::new(AtomicBool::new(false));
+        let latency_stats = Arc::new(ControlLatencyStats::default());
+        let watchdog_signals = Arc::new(WatchdogSignals::default());
+        let freewheeling = Arc::new(AtomicBool::new(false));
 
         // Create JACK client
         let (client, _status) = Client::new(&config.client_name, ClientOptions::NO_START_SERVER)
@@ -53,6 +148,35 @@ impl AudioEngine {
             client.sample_rate(),
             client.buffer_size()
         );
+        let period =
+            Duration::from_secs_f64(client.buffer_size() as f64 / client.sample_rate() as f64);
+
+        let capacity =
+            CapacityEstimate::new(&config, client.sample_rate(), client.buffer_size() as usize);
+        log::info!(
+            "Estimated load: {:.0}% of the period ({} ports, {} sums)",
+            capacity.load() * 100.0,
+            capacity.ports,
+            capacity.crosspoints
+        );
+        if capacity.is_over_budget() {
+            match capacity.suggested_buffer_size(&config, client.sample_rate()) {
+                Some(frames) => log::warn!(
+                    "Config may not keep up at {} frames; try a buffer size of {} or more",
+                    capacity.buffer_size,
+                    frames
+                ),
+                None => log::warn!("Config may not keep up at any buffer size"),
+            }
+        }
+
+        // Room for every meter update the UI may leave waiting, and for a
+        // burst of control messages to every channel
+        let (meter_producer, meter_consumer) = RingBuffer::new(capacity.meter_queue);
+        let (control_producer, control_consumer) = RingBuffer::new(capacity.control_queue);
+
+        // One second of the analyzed channel, mixed to mono
+        let (analyzer_producer, analyzer_consumer) = RingBuffer::new(client.sample_rate());
 
         // Create input ports
         let mut input_ports: Vec<Port<AudioIn>> = Vec::new();
@@ -76,45 +200,342 @@ impl AudioEngine {
             }
         }
 
+        // Create passthrough ports (one input and one output per channel)
+        let mut passthrough_inputs: Vec<Port<AudioIn>> = Vec::new();
+        let mut passthrough_outputs: Vec<Port<AudioOut>> = Vec::new();
+        for passthrough in &config.passthrough {
+            for (in_name, out_name) in passthrough.inputs.iter().zip(&passthrough.outputs) {
+                passthrough_inputs.push(
+                    client
+                        .register_port(in_name, AudioIn::default())
+                        .with_context(|| format!("Failed to register input port '{}'", in_name))?,
+                );
+                passthrough_outputs.push(
+                    client
+                        .register_port(out_name, AudioOut::default())
+                        .with_context(|| format!("Failed to register output port '{}'", out_name))?,
+                );
+            }
+        }
+        let passthrough_port_counts: Vec<usize> =
+            config.passthrough.iter().map(|p| p.inputs.len()).collect();
+
+        // Create the MIDI control port
+        let midi_port = match &config.midi {
+            Some(midi) => Some(
+                client
+                    .register_port(&midi.port, MidiIn::default())
+                    .with_context(|| format!("Failed to register MIDI port '{}'", midi.port))?,
+            ),
+            None => None,
+        };
+        let program_decoder =
+            ProgramChangeDecoder::new(config.midi.as_ref().and_then(|m| m.channel).map(|c| c - 1));
+
+        // Create tap ports (one per port of channels with a tap point)
+        let input_taps = Self::register_taps(&client, &config.inputs)?;
+        let output_taps = Self::register_taps(&client, &config.outputs)?;
+
+        // Create the send ports of the input processing graphs
+        let mut input_sends: Vec<Vec<Port<AudioOut>>> = Vec::new();
+        for input_cfg in &config.inputs {
+            let mut sends = Vec::new();
+            for port_name in input_cfg.send_port_names() {
+                sends.push(
+                    client
+                        .register_port(port_name, AudioOut::default())
+                        .with_context(|| format!("Failed to register send port '{}'", port_name))?,
+                );
+            }
+            input_sends.push(sends);
+        }
+
         log::info!(
             "Registered {} input ports and {} output ports",
             input_ports.len(),
             output_ports.len()
         );
 
-        // Build mixer state
-        let inputs: Vec<ChannelState> = config
+        // Start from the saved volumes, switches and volume caps, so a
+        // channel saved as muted never plays and no message exceeds a cap
+        let mixer_state = MixerState::from_config(&config);
+
+        // Mute and solo fades start settled on the saved switches: closed
+        // for what is saved as muted or soloed out, open in the cue for
+        // what is saved as soloed, so nothing ramps at startup
+        let fade = |open: bool| Fade::new(MUTE_FADE_MS, client.sample_rate() as f32, open);
+        let input_fades = (0..mixer_state.inputs.len())
+            .map(|i| fade(mixer_state.input_audible(i)))
+            .collect();
+        let cueing = mixer_state.solo_cue && solo::active(&mixer_state);
+        let cue_fades = (0..mixer_state.inputs.len())
+            .map(|i| fade(cueing && solo::input_soloed(&mixer_state, i)))
+            .collect();
+        let solo_fade = fade(cueing);
+        let output_fades = (0..mixer_state.outputs.len())
+            .map(|o| fade(mixer_state.output_audible(o)))
+            .collect();
+        let master_fade = fade(!mixer_state.master.muted);
+        // Saved mid and side levels apply from the first cycle
+        let input_mid_side = mixer_state
             .inputs
             .iter()
-            .map(|c| ChannelState::new(c.name.clone(), c.port_count()))
+            .map(|input| (input.mid_side_gains(), input.mid_side_gains()))
             .collect();
-
-        let outputs: Vec<ChannelState> = config
-            .outputs
+        let output_mid_side = mixer_state.outputs.iter().map(|o| o.mid_side_gains()).collect();
+        let passthrough_fades = mixer_state
+            .passthrough
             .iter()
-            .map(|c| ChannelState::new(c.name.clone(), c.port_count()))
+            .map(|c| fade(!c.muted))
             .collect();
 
-        let mixer_state = MixerState { inputs, outputs };
-
         // Build port mapping info
         let input_port_counts: Vec<usize> = config.inputs.iter().map(|c| c.port_count()).collect();
         let output_port_counts: Vec<usize> = config.outputs.iter().map(|c| c.port_count()).collect();
 
+        let input_tap_points: Vec<Option<TapPoint>> = config.inputs.iter().map(|c| c.tap).collect();
+        let output_tap_points: Vec<Option<TapPoint>> = config.outputs.iter().map(|c| c.tap).collect();
+
+        // One K-weighting filter per port for loudness measurement
+        let sample_rate = client.sample_rate() as f32;
+        let input_k_filters = vec![KWeighting::new(sample_rate); input_ports.len()];
+        let port_statuses = Arc::new(PortStatuses::new(input_ports.len()));
+        let silence = SilenceDetector::new(port_statuses.clone(), sample_rate);
+        let output_k_filters = vec![KWeighting::new(sample_rate); output_ports.len()];
+
+        let output_mixes: Vec<MixStrategy> = config
+            .outputs
+            .iter()
+            .map(|o| MixStrategy::from_config(o, &config.inputs, sample_rate))
+            .collect();
+
+        // One EQ (and scratch buffer for its output) per input port
+        let input_eqs: Vec<ChannelEq> = config
+            .inputs
+            .iter()
+            .flat_map(|c| {
+                let eq = ChannelEq::new(&c.eq.clone().unwrap_or_default(), sample_rate);
+                std::iter::repeat_n(eq, c.port_count())
+            })
+            .collect();
+        let eq_buffers = (0..input_eqs.len())
+            .map(|_| capacity::scratch_buffer(client.buffer_size() as usize))
+            .collect();
+
+        // Processing graphs, compiled once (run ahead of the EQ)
+        let input_graphs = config
+            .inputs
+            .iter()
+            .map(|c| {
+                c.dsp
+                    .as_ref()
+                    .map(|dsp| {
+                        let buffer_size = client.buffer_size() as usize;
+                        DspGraph::new(dsp, c.port_count(), sample_rate, buffer_size)
+                            .with_context(|| format!("Invalid dsp graph on input '{}'", c.name))
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Outputs each input feeds unprocessed
+        let monitor_splits: Vec<Vec<bool>> = config
+            .inputs
+            .iter()
+            .map(|i| {
+                config
+                    .outputs
+                    .iter()
+                    .map(|o| i.monitor_outputs.contains(&o.name))
+                    .collect()
+            })
+            .collect();
+
+        let input_deessers: Vec<Option<DeEsser>> = config
+            .inputs
+            .iter()
+            .map(|c| c.deesser.as_ref().map(|d| DeEsser::new(d, sample_rate)))
+            .collect();
+
+        let click = config.click.as_ref().map(|c| Click::new(c, sample_rate));
+        let click_outputs: Vec<bool> = config
+            .outputs
+            .iter()
+            .map(|o| config.click.as_ref().is_some_and(|c| c.outputs.contains(&o.name)))
+            .collect();
+
+        let input_protectors: Vec<Option<Protector>> = config
+            .inputs
+            .iter()
+            .map(|c| c.protect.as_ref().map(|p| Protector::new(p, sample_rate)))
+            .collect();
+
+        let automixer = Automixer::new(
+            config
+                .inputs
+                .iter()
+                .enumerate()
+                .filter_map(|(i, c)| Some((i, c.automix_weight_db?))),
+            sample_rate,
+        );
+
+        let output_mono: Vec<bool> = config.outputs.iter().map(|o| o.mono).collect();
+
+        let output_trims: Vec<Option<AutoTrim>> = config
+            .outputs
+            .iter()
+            .map(|o| o.auto_trim.as_ref().map(|t| AutoTrim::new(t, sample_rate)))
+            .collect();
+
+        let output_limiters: Vec<Option<Limiter>> = config
+            .outputs
+            .iter()
+            .map(|o| o.limiter.as_ref().map(|l| Limiter::new(l, sample_rate)))
+            .collect();
+
+        let output_multibands: Vec<Option<Multiband>> = config
+            .outputs
+            .iter()
+            .map(|o| o.multiband.as_ref().map(|m| Multiband::new(m, sample_rate)))
+            .collect();
+
+        let output_clips: Vec<Option<SoftClip>> = config
+            .outputs
+            .iter()
+            .map(|o| o.soft_clip.as_ref().map(SoftClip::new))
+            .collect();
+
+        let output_delays: Vec<Option<OutputDelay>> = config
+            .outputs
+            .iter()
+            .map(|o| o.delay_ms.and_then(|ms| OutputDelay::new(ms, sample_rate)))
+            .collect();
+
+        let output_notches = vec![OutputNotches::new(sample_rate); config.outputs.len()];
+
+        // Replay buffer of one output bus
+        let (replay_tap, replay) = match &config.replay_buffer {
+            Some(replay) => {
+                let output = config
+                    .outputs
+                    .iter()
+                    .position(|o| o.name == replay.output)
+                    .context("Replay buffer output not found")?;
+                let (tap, buffer) = ReplayBuffer::new(
+                    output,
+                    config.outputs[output].port_count(),
+                    client.sample_rate() as u32,
+                    replay.seconds,
+                );
+                (Some(tap), Some(buffer))
+            }
+            None => (None, None),
+        };
+
+        // Recorder feed of one output bus (front left/right)
+        let (record_tap, recorder) = match &config.recorder {
+            Some(recorder) => {
+                let output = config
+                    .outputs
+                    .iter()
+                    .position(|o| o.name == recorder.output)
+                    .context("Recorder output not found")?;
+                let ports = config.outputs[output].port_count().min(2);
+                let sample_rate = client.sample_rate() as u32;
+                let (tap, consumer) = replay::feed(output, ports, sample_rate);
+                let feed = RecorderFeed::new(consumer, ports, sample_rate, recorder.trigger);
+                (Some(tap), Some(feed))
+            }
+            None => (None, None),
+        };
+
         // Create process handler
         let process_handler = ProcessHandler {
             input_ports,
             output_ports,
+            input_taps,
+            output_taps,
+            input_tap_points,
+            output_tap_points,
+            input_k_filters,
+            output_k_filters,
+            silence,
+            calibration_noise: None,
+            pink_noise: PinkNoise::new(),
+            click,
+            click_outputs,
+            click_buffer: capacity::scratch_buffer(client.buffer_size() as usize),
+            output_mixes,
+            input_eqs,
+            eq_buffers,
+            input_graphs,
+            input_deessers,
+            input_protectors,
+            automixer,
+            monitor_splits,
+            input_sends,
+            output_mono,
+            output_trims,
+            output_limiters,
+            output_multibands,
+            output_clips,
+            output_delays,
+            output_notches,
+            sample_rate,
+            input_gains: vec![(0.0, 0.0); config.inputs.len()],
+            input_mid_side,
+            output_mid_side,
+            input_fades,
+            cue_fades,
+            cue_gains: vec![(0.0, 0.0); config.inputs.len()],
+            cue_levels: vec![1.0; config.inputs.len()],
+            solo_fade,
+            solo_dims: config
+                .outputs
+                .iter()
+                .map(|o| {
+                    let dim_db = o.solo_cue?.dim_db;
+                    Some(if dim_db <= VOLUME_MIN_DB {
+                        0.0
+                    } else {
+                        MeterData::db_to_linear(dim_db)
+                    })
+                })
+                .collect(),
+            output_fades,
+            master_fade,
+            passthrough_fades,
+            input_levels: vec![0.0; config.inputs.len()],
+            mix_levels: vec![0.0; config.inputs.len()],
             input_port_counts,
             output_port_counts,
+            passthrough_inputs,
+            passthrough_outputs,
+            passthrough_port_counts,
+            replay_tap,
+            record_tap,
+            freewheeling: freewheeling.clone(),
+            midi_port,
+            program_decoder,
+            program_producer,
             mixer_state,
             meter_producer,
+            scope_producer,
+            analyzer_source: None,
+            analyzer_producer,
             control_consumer,
             quit_flag: quit_flag.clone(),
+            latency_stats: latency_stats.clone(),
+            watchdog_signals: watchdog_signals.clone(),
         };
 
         // Create notification handler
-        let notifications = Notifications;
+        let xruns = Arc::new(AtomicU64::new(0));
+        let notifications = Notifications {
+            watchdog_signals: watchdog_signals.clone(),
+            xruns: xruns.clone(),
+            freewheeling: freewheeling.clone(),
+        };
 
         // Activate client
         let async_client = client
@@ -123,19 +544,104 @@ impl AudioEngine {
 
         log::info!("JACK client activated");
 
+        let watchdog = Watchdog::start(
+            watchdog_signals,
+            quit_flag.clone(),
+            config.client_name.clone(),
+            config.watchdog_hook.clone(),
+        );
+
         Ok(Self {
-            _async_client: async_client,
+            async_client: Some(async_client),
             control_producer,
             meter_consumer,
+            program_consumer,
+            scope_consumer,
+            analyzer_consumer,
+            sample_rate: sample_rate as u32,
             quit_flag,
+            latency_stats,
+            period,
+            watchdog,
+            replay,
+            recorder,
+            xruns,
+            freewheeling,
+            freewheel_requested: false,
+            port_statuses,
+            capacity,
         })
     }
 
+    /// Register tap output ports for every port of channels that define a tap
+    fn register_taps(
+        client: &Client,
+        channels: &[ChannelConfig],
+    ) -> Result<Vec<Option<Port<AudioOut>>>> {
+        let mut taps = Vec::new();
+        for channel in channels {
+            for port_name in &channel.ports {
+                let tap = match channel.tap {
+                    Some(_) => {
+                        let tap_name = ChannelConfig::tap_port_name(port_name);
+                        Some(client.register_port(&tap_name, AudioOut::default()).with_context(
+                            || format!("Failed to register tap port '{}'", tap_name),
+                        )?)
+                    }
+                    None => None,
+                };
+                taps.push(tap);
+            }
+        }
+        Ok(taps)
+    }
+
     /// Send a control message to the audio thread
     pub fn send_control(&mut self, msg: ControlMsg) -> Result<()> {
-        self.control_producer
-            .push(msg)
-            .map_err(|_| anyhow::anyhow!("Control message queue full"))
+        let timed = TimedControlMsg {
+            msg,
+            sent_at: Instant::now(),
+        };
+        self.control_producer.push(timed).map_err(|_| {
+            self.latency_stats.record_dropped();
+            anyhow::anyhow!("Control message queue full")
+        })
+    }
+
+    /// Current control queue and latency diagnostics
+    pub fn control_diagnostics(&self) -> ControlDiagnostics {
+        let capacity = self.control_producer.buffer().capacity();
+        ControlDiagnostics {
+            latency: self.latency_stats.snapshot(),
+            queued: capacity - self.control_producer.slots(),
+            capacity,
+            period: self.period,
+        }
+    }
+
+    /// Estimated cost of the config at the buffer size the engine started with
+    pub fn capacity(&self) -> CapacityEstimate {
+        self.capacity
+    }
+
+    /// Health of the audio callback as seen by the watchdog
+    pub fn health(&self) -> EngineHealth {
+        self.watchdog.health()
+    }
+
+    /// Xruns reported by the server since the engine started
+    pub fn xrun_count(&self) -> u64 {
+        self.xruns.load(Ordering::Relaxed)
+    }
+
+    /// Signal state of every input port, in config order
+    pub fn input_port_statuses(&self) -> Vec<PortStatus> {
+        self.port_statuses.snapshot()
+    }
+
+    /// Clear the control latency statistics
+    pub fn reset_control_diagnostics(&self) {
+        self.latency_stats.reset();
     }
 
     /// Try to receive meter data from the audio thread
@@ -143,11 +649,294 @@ impl AudioEngine {
         self.meter_consumer.pop().ok()
     }
 
+    /// Try to receive a MIDI program change from the audio thread
+    pub fn try_recv_program_change(&mut self) -> Option<ProgramChange> {
+        self.program_consumer.pop().ok()
+    }
+
+    /// Try to receive stereo scope data from the audio thread
+    pub fn try_recv_scope(&mut self) -> Option<ScopeData> {
+        self.scope_consumer.pop().ok()
+    }
+
+    /// Sample rate of the JACK server in Hz
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Move the samples of the analyzed channel into `samples`
+    pub fn drain_analyzer(&mut self, samples: &mut Vec<f32>) {
+        let available = self.analyzer_consumer.slots();
+        if let Ok(chunk) = self.analyzer_consumer.read_chunk(available) {
+            let (first, second) = chunk.as_slices();
+            samples.extend_from_slice(first);
+            samples.extend_from_slice(second);
+            chunk.commit_all();
+        }
+    }
+
+    /// Move recorded audio into the replay buffer (call regularly)
+    pub fn drain_replay(&mut self) {
+        if let Some(replay) = self.replay.as_mut() {
+            replay.drain();
+        }
+    }
+
+    /// Write the last `seconds` (default: the whole buffer) of the replay
+    /// buffer to a WAV file, returning the length saved
+    pub fn save_replay(&mut self, path: &Path, seconds: Option<f32>) -> Result<Duration> {
+        match self.replay.as_mut() {
+            Some(replay) => replay.save(path, seconds),
+            None => anyhow::bail!("No replay_buffer configured"),
+        }
+    }
+
+    /// Move recorded audio into the take in progress (call regularly)
+    ///
+    /// A write error ends the take.
+    pub fn drain_recording(&mut self) -> Result<()> {
+        match self.recorder.as_mut() {
+            Some(recorder) => recorder.drain(),
+            None => Ok(()),
+        }
+    }
+
+    /// Start recording to a raw WAV file at `path`
+    pub fn start_recording(&mut self, path: &Path) -> Result<()> {
+        match self.recorder.as_mut() {
+            Some(recorder) => recorder.start(path),
+            None => anyhow::bail!("No recorder configured"),
+        }
+    }
+
+    /// Finish the take in progress
+    pub fn stop_recording(&mut self) -> Result<Recording> {
+        match self.recorder.as_mut() {
+            Some(recorder) => recorder.stop(),
+            None => anyhow::bail!("No recorder configured"),
+        }
+    }
+
+    /// Length of the take in progress, if recording
+    pub fn recording_elapsed(&self) -> Option<Duration> {
+        self.recorder.as_ref().and_then(|r| r.elapsed())
+    }
+
+    /// Whether a triggered take is waiting for signal
+    pub fn recording_waiting(&self) -> bool {
+        self.recorder.as_ref().is_some_and(|r| r.is_waiting())
+    }
+
     /// Request the audio engine to quit
     pub fn quit(&mut self) {
         self.quit_flag.store(true, Ordering::SeqCst);
         let _ = self.send_control(ControlMsg::Quit);
     }
+
+    /// Make the connections listed in the config
+    ///
+    /// Targets that no longer exist are not connected; instead a best-match
+    /// substitute from the same device family is proposed in the report.
+    pub fn auto_connect(&self, config: &Config) -> ConnectionReport {
+        let mut report = ConnectionReport::default();
+        let Some(client) = self.async_client.as_ref().map(|c| c.as_client()) else {
+            return report;
+        };
+
+        for (side, channels) in [(Side::Input, &config.inputs), (Side::Output, &config.outputs)] {
+            let mut candidates: Option<Vec<String>> = None;
+
+            for (channel_idx, channel) in channels.iter().enumerate() {
+                for (entry, target) in channel.connect.iter().enumerate() {
+                    let Some(local_port) = autoconnect::local_port_for_entry(channel, entry) else {
+                        continue;
+                    };
+
+                    if client.port_by_name(target).is_some() {
+                        match self.connect_port(side, local_port, target) {
+                            Ok(()) => report.connected += 1,
+                            Err(e) => log::warn!("{:#}", e),
+                        }
+                        continue;
+                    }
+
+                    let candidates =
+                        candidates.get_or_insert_with(|| self.external_ports(client, side));
+                    match autoconnect::best_match(target, candidates) {
+                        Some(replacement) => report.substitutions.push(Substitution {
+                            side,
+                            channel: channel_idx,
+                            entry,
+                            local_port: local_port.clone(),
+                            missing: target.clone(),
+                            replacement: replacement.clone(),
+                        }),
+                        None => report.unresolved.push(Unresolved {
+                            local_port: local_port.clone(),
+                            missing: target.clone(),
+                        }),
+                    }
+                }
+            }
+        }
+
+        // Passthrough ports are connected as-is (no device substitution)
+        for passthrough in &config.passthrough {
+            for (side, ports, targets) in [
+                (Side::Input, &passthrough.inputs, &passthrough.connect_inputs),
+                (Side::Output, &passthrough.outputs, &passthrough.connect_outputs),
+            ] {
+                for (local_port, target) in ports.iter().zip(targets) {
+                    if client.port_by_name(target).is_none() {
+                        report.unresolved.push(Unresolved {
+                            local_port: local_port.clone(),
+                            missing: target.clone(),
+                        });
+                        continue;
+                    }
+                    match self.connect_port(side, local_port, target) {
+                        Ok(()) => report.connected += 1,
+                        Err(e) => log::warn!("{:#}", e),
+                    }
+                }
+            }
+        }
+
+        // MIDI sources are connected as-is (no device substitution)
+        if let Some(midi) = &config.midi {
+            let local = format!("{}:{}", client.name(), midi.port);
+            for target in &midi.connect {
+                if client.port_by_name(target).is_none() {
+                    report.unresolved.push(Unresolved {
+                        local_port: midi.port.clone(),
+                        missing: target.clone(),
+                    });
+                    continue;
+                }
+                match client.connect_ports_by_name(target, &local) {
+                    Ok(()) => report.connected += 1,
+                    Err(e) => log::warn!("Failed to connect '{}' -> '{}': {}", target, local, e),
+                }
+            }
+        }
+
+        log::info!(
+            "Auto-connect: {} connected, {} substitutes proposed, {} unresolved",
+            report.connected,
+            report.substitutions.len(),
+            report.unresolved.len()
+        );
+        report
+    }
+
+    /// External audio ports a channel on `side` can connect to
+    fn external_ports(&self, client: &Client, side: Side) -> Vec<String> {
+        let flags = match side {
+            Side::Input => jack::PortFlags::IS_OUTPUT,
+            Side::Output => jack::PortFlags::IS_INPUT,
+        };
+        let own_prefix = format!("{}:", client.name());
+        client
+            .ports(None, Some("audio"), flags)
+            .into_iter()
+            .filter(|p| !p.starts_with(&own_prefix))
+            .collect()
+    }
+
+    /// Audio output ports of other clients that are not hardware, i.e.
+    /// application streams
+    pub fn application_ports(&self) -> Vec<String> {
+        let Some(client) = self.async_client.as_ref().map(|c| c.as_client()) else {
+            return Vec::new();
+        };
+        let physical = client.ports(
+            None,
+            Some("audio"),
+            jack::PortFlags::IS_OUTPUT | jack::PortFlags::IS_PHYSICAL,
+        );
+        self.external_ports(client, Side::Input)
+            .into_iter()
+            .filter(|p| !physical.contains(p))
+            .collect()
+    }
+
+    /// Connect one of our ports to an external port
+    pub fn connect_port(&self, side: Side, local_port: &str, external: &str) -> Result<()> {
+        let Some(client) = self.async_client.as_ref().map(|c| c.as_client()) else {
+            anyhow::bail!("Audio engine is stopped");
+        };
+        let local = format!("{}:{}", client.name(), local_port);
+        let (source, destination) = match side {
+            Side::Input => (external.to_string(), local),
+            Side::Output => (local, external.to_string()),
+        };
+        client
+            .connect_ports_by_name(&source, &destination)
+            .with_context(|| format!("Failed to connect '{}' -> '{}'", source, destination))
+    }
+
+    /// Disconnect one of our ports from an external port
+    pub fn disconnect_port(&self, side: Side, local_port: &str, external: &str) -> Result<()> {
+        let Some(client) = self.async_client.as_ref().map(|c| c.as_client()) else {
+            anyhow::bail!("Audio engine is stopped");
+        };
+        let local = format!("{}:{}", client.name(), local_port);
+        let (source, destination) = match side {
+            Side::Input => (external.to_string(), local),
+            Side::Output => (local, external.to_string()),
+        };
+        client
+            .disconnect_ports_by_name(&source, &destination)
+            .with_context(|| format!("Failed to disconnect '{}' -> '{}'", source, destination))
+    }
+
+    /// Switch the server's freewheel mode, in which the process callback
+    /// runs as fast as the CPU allows instead of at the sound card's pace
+    /// (for every client: the sound card is silent meanwhile)
+    pub fn set_freewheel(&mut self, enabled: bool) -> Result<()> {
+        let client = self
+            .async_client
+            .as_ref()
+            .map(|c| c.as_client())
+            .context("Engine stopped")?;
+        // The jack crate has no wrapper for this call
+        let onoff = i32::from(enabled);
+        let result = unsafe { jack::jack_sys::jack_set_freewheel(client.raw(), onoff) };
+        if result != 0 {
+            anyhow::bail!("The JACK server refused to switch freewheel mode");
+        }
+        self.freewheel_requested = enabled;
+        Ok(())
+    }
+
+    /// Whether the server is freewheeling
+    pub fn is_freewheeling(&self) -> bool {
+        self.freewheeling.load(Ordering::Relaxed)
+    }
+
+    /// Switch freewheeling off again if this engine switched it on, leaving
+    /// a freewheel another client started alone
+    pub fn release_freewheel(&mut self) -> Result<()> {
+        if self.freewheel_requested {
+            self.set_freewheel(false)?;
+        }
+        Ok(())
+    }
+
+    /// Deactivate the JACK client, releasing its name and ports
+    pub fn stop(&mut self) {
+        // Leaving the server freewheeling would keep every client off the
+        // sound card
+        if let Err(e) = self.release_freewheel() {
+            log::warn!("{:#}", e);
+        }
+        self.quit();
+        if let Some(client) = self.async_client.take() {
+            if let Err(e) = client.deactivate() {
+                log::warn!("Failed to deactivate JACK client: {}", e);
+            }
+        }
+    }
 }
 
 impl Drop for AudioEngine {
@@ -157,11 +946,21 @@ impl Drop for AudioEngine {
 }
 
 /// JACK notification handler
-struct Notifications;
+struct Notifications {
+    /// Signals shared with the watchdog
+    watchdog_signals: Arc<WatchdogSignals>,
+
+    /// Xrun counter shared with the engine
+    xruns: Arc<AtomicU64>,
+
+    /// Freewheel state shared with the engine and process handler
+    freewheeling: Arc<AtomicBool>,
+}
 
 impl jack::NotificationHandler for Notifications {
     unsafe fn shutdown(&mut self, _status: jack::ClientStatus, reason: &str) {
         log::error!("JACK client shutdown: {}", reason);
+        self.watchdog_signals.shutdown.store(true, Ordering::Relaxed);
     }
 
     fn sample_rate(&mut self, _: &Client, srate: jack::Frames) -> Control {
@@ -170,9 +969,15 @@ impl jack::NotificationHandler for Notifications {
     }
 
     fn xrun(&mut self, _: &Client) -> Control {
-        // Silently ignore xruns to avoid garbling the TUI
+        // Only counted (for the session report) to avoid garbling the TUI
+        self.xruns.fetch_add(1, Ordering::Relaxed);
         Control::Continue
     }
+
+    fn freewheel(&mut self, _: &Client, enabled: bool) {
+        log::info!("JACK freewheel {}", if enabled { "started" } else { "stopped" });
+        self.freewheeling.store(enabled, Ordering::Relaxed);
+    }
 }
 
 /// JACK process handler - runs in the real-time audio thread
@@ -183,38 +988,249 @@ struct ProcessHandler {
     /// Output ports  
     output_ports: Vec<Port<AudioOut>>,
 
+    /// Tap ports per input port (None if the channel has no tap)
+    input_taps: Vec<Option<Port<AudioOut>>>,
+
+    /// Tap ports per output port (None if the channel has no tap)
+    output_taps: Vec<Option<Port<AudioOut>>>,
+
+    /// Tap point per input channel
+    input_tap_points: Vec<Option<TapPoint>>,
+
+    /// Tap point per output channel
+    output_tap_points: Vec<Option<TapPoint>>,
+
+    /// K-weighting filters for input ports (loudness metering)
+    input_k_filters: Vec<KWeighting>,
+
+    /// K-weighting filters for output ports (loudness metering)
+    output_k_filters: Vec<KWeighting>,
+
+    /// Digital silence tracking per input port
+    silence: SilenceDetector,
+
+    /// Output playing calibration noise and the noise gain (linear), if any
+    calibration_noise: Option<(usize, f32)>,
+
+    /// Source of the calibration noise
+    pink_noise: PinkNoise,
+
+    /// Metronome click, if configured
+    click: Option<Click>,
+
+    /// Per output channel, whether it gets the click
+    click_outputs: Vec<bool>,
+
+    /// Scratch buffer the click is rendered into
+    click_buffer: Vec<f32>,
+
+    /// Mixing strategy per output channel
+    output_mixes: Vec<MixStrategy>,
+
+    /// Three-band EQ per input port
+    input_eqs: Vec<ChannelEq>,
+
+    /// Processing graph per input channel, if configured
+    input_graphs: Vec<Option<DspGraph>>,
+
+    /// De-esser per input channel (None if not enabled)
+    input_deessers: Vec<Option<DeEsser>>,
+
+    /// Protective dip per input channel (None if not enabled)
+    input_protectors: Vec<Option<Protector>>,
+
+    /// Gain-sharing automixer over its group of inputs, if any
+    automixer: Option<Automixer>,
+
+    /// Per input channel, the outputs fed unprocessed (monitor split)
+    monitor_splits: Vec<Vec<bool>>,
+
+    /// Send ports of each input channel's graph
+    input_sends: Vec<Vec<Port<AudioOut>>>,
+
+    /// Scratch buffers holding the equalized signal per input port
+    eq_buffers: Vec<Vec<f32>>,
+
+    /// Whether each output channel is summed to mono
+    output_mono: Vec<bool>,
+
+    /// Auto-trim per output channel (None if not enabled)
+    output_trims: Vec<Option<AutoTrim>>,
+
+    /// Brickwall limiter per output channel (None if not enabled)
+    output_limiters: Vec<Option<Limiter>>,
+
+    /// Multiband compressor per output channel (None until configured)
+    output_multibands: Vec<Option<Multiband>>,
+
+    /// Soft clipper per output channel (None if not enabled)
+    output_clips: Vec<Option<SoftClip>>,
+
+    /// Lip-sync delay per output channel (None if not delayed)
+    output_delays: Vec<Option<OutputDelay>>,
+
+    /// Temporary feedback notches per output channel
+    output_notches: Vec<OutputNotches>,
+
+    /// Sample rate in Hz, for processors created while running
+    sample_rate: f32,
+
+    /// Effective gain per input channel at the start and the end of the
+    /// current cycle
+    input_gains: Vec<(f32, f32)>,
+
+    /// Mid and side gains per input channel at the start and the end of the
+    /// current cycle
+    input_mid_side: Vec<([f32; 2], [f32; 2])>,
+
+    /// Mid and side gains last applied per output channel
+    output_mid_side: Vec<[f32; 2]>,
+
+    /// Mute and solo fade per input channel
+    input_fades: Vec<Fade>,
+
+    /// Fade of each input into the solo cue outputs
+    cue_fades: Vec<Fade>,
+
+    /// Gain ramp of each input into the solo cue outputs this cycle
+    cue_gains: Vec<(f32, f32)>,
+
+    /// Level (linear) of each input in the cue while last soloed, kept for
+    /// its fade out
+    cue_levels: Vec<f32>,
+
+    /// Fade of the solo cue outputs from their program to the dimmed one
+    solo_fade: Fade,
+
+    /// Program gain (linear) of each solo cue output while an input is
+    /// soloed (None for other outputs)
+    solo_dims: Vec<Option<f32>>,
+
+    /// Mute fade per output channel
+    output_fades: Vec<Fade>,
+
+    /// Mute fade of the master fader
+    master_fade: Fade,
+
+    /// Mute fade per passthrough channel
+    passthrough_fades: Vec<Fade>,
+
+    /// Post-fader peak level per input channel for the current cycle
+    input_levels: Vec<f32>,
+
+    /// Scratch buffer: input levels as seen by one output (after its sends)
+    mix_levels: Vec<f32>,
+
     /// Number of ports per input channel
     input_port_counts: Vec<usize>,
 
     /// Number of ports per output channel
     output_port_counts: Vec<usize>,
 
+    /// Passthrough input ports, all channels in order
+    passthrough_inputs: Vec<Port<AudioIn>>,
+
+    /// Passthrough output ports, one per passthrough input port
+    passthrough_outputs: Vec<Port<AudioOut>>,
+
+    /// Number of ports per passthrough channel
+    passthrough_port_counts: Vec<usize>,
+
+    /// Feed for the replay buffer, if configured
+    replay_tap: Option<ReplayTap>,
+
+    /// Feed for the recorder, if configured
+    record_tap: Option<ReplayTap>,
+
+    /// Whether the server is freewheeling
+    freewheeling: Arc<AtomicBool>,
+
+    /// MIDI control input, if configured
+    midi_port: Option<Port<MidiIn>>,
+
+    /// Program change decoder for the MIDI input
+    program_decoder: ProgramChangeDecoder,
+
+    /// Producer for sending program changes to the UI
+    program_producer: Producer<ProgramChange>,
+
     /// Mixer state with gains, mute, solo
     mixer_state: MixerState,
 
     /// Producer for sending meter data to UI
     meter_producer: Producer<MeterData>,
 
+    /// Producer for sending the stereo image of two-port outputs to the UI
+    scope_producer: Producer<ScopeData>,
+
+    /// Channel fed to the analyzer views, if one is open
+    analyzer_source: Option<AnalyzerSource>,
+
+    /// Producer for the mono samples of the analyzed channel
+    analyzer_producer: Producer<f32>,
+
     /// Consumer for receiving control messages from UI
-    control_consumer: Consumer<ControlMsg>,
+    control_consumer: Consumer<TimedControlMsg>,
 
     /// Quit flag reference
     quit_flag: Arc<AtomicBool>,
+
+    /// Control latency statistics shared with the UI
+    latency_stats: Arc<ControlLatencyStats>,
+
+    /// Heartbeat for the watchdog
+    watchdog_signals: Arc<WatchdogSignals>,
 }
 
 impl ProcessHandler {
     /// Process control messages from UI
     fn process_control_messages(&mut self) {
-        while let Ok(msg) = self.control_consumer.pop() {
+        let now = Instant::now();
+        while let Ok(TimedControlMsg { msg, sent_at }) = self.control_consumer.pop() {
+            self.latency_stats.record(now.saturating_duration_since(sent_at));
             match msg {
                 ControlMsg::SetInputVolume { channel, volume_db } => {
                     if channel < self.mixer_state.inputs.len() {
-                        self.mixer_state.inputs[channel].volume_db = volume_db;
+                        let input = &mut self.mixer_state.inputs[channel];
+                        input.volume_db = input.clamp_volume(volume_db);
+                    }
+                }
+                ControlMsg::SetInputTrim { channel, trim_db } => {
+                    if channel < self.mixer_state.inputs.len() {
+                        self.mixer_state.inputs[channel].trim_db = trim_db;
+                    }
+                }
+                ControlMsg::SetInputBalance { channel, balance_db } => {
+                    if let Some(input) = self.mixer_state.inputs.get_mut(channel) {
+                        input.balance_db = balance_db;
+                    }
+                }
+                ControlMsg::SetInputMidSide { channel, enabled } => {
+                    if let Some(input) = self.mixer_state.inputs.get_mut(channel) {
+                        input.mid_side = enabled;
+                    }
+                }
+                ControlMsg::SetOutputMidSide { channel, enabled } => {
+                    if let Some(output) = self.mixer_state.outputs.get_mut(channel) {
+                        output.mid_side = enabled;
+                    }
+                }
+                ControlMsg::SetInputMidSideLevels { channel, mid_db, side_db } => {
+                    if let Some(input) = self.mixer_state.inputs.get_mut(channel) {
+                        input.mid_db = mid_db;
+                        input.side_db = side_db;
+                    }
+                }
+                ControlMsg::SetOutputMidSideLevels { channel, mid_db, side_db } => {
+                    if let Some(output) = self.mixer_state.outputs.get_mut(channel) {
+                        output.mid_db = mid_db;
+                        output.side_db = side_db;
                     }
                 }
                 ControlMsg::SetOutputVolume { channel, volume_db } => {
                     if channel < self.mixer_state.outputs.len() {
-                        self.mixer_state.outputs[channel].volume_db = volume_db;
+                        let output = &mut self.mixer_state.outputs[channel];
+                        output.volume_db = output.clamp_volume(volume_db);
                     }
                 }
                 ControlMsg::ToggleInputMute { channel } => {
@@ -223,6 +1239,21 @@ impl ProcessHandler {
                             !self.mixer_state.inputs[channel].muted;
                     }
                 }
+                ControlMsg::SetInputMeterPoint { channel, point } => {
+                    if let Some(input) = self.mixer_state.inputs.get_mut(channel) {
+                        input.meter_point = point;
+                    }
+                }
+                ControlMsg::SetAutomixWeight { channel, weight_db } => {
+                    if let Some(automixer) = self.automixer.as_mut() {
+                        automixer.set_weight(channel, weight_db);
+                    }
+                }
+                ControlMsg::SetInputSwitching { channel, switching } => {
+                    if let Some(input) = self.mixer_state.inputs.get_mut(channel) {
+                        input.switching = switching;
+                    }
+                }
                 ControlMsg::ToggleOutputMute { channel } => {
                     if channel < self.mixer_state.outputs.len() {
                         self.mixer_state.outputs[channel].muted =
@@ -230,11 +1261,97 @@ impl ProcessHandler {
                     }
                 }
                 ControlMsg::ToggleInputSolo { channel } => {
-                    if channel < self.mixer_state.inputs.len() {
-                        self.mixer_state.inputs[channel].soloed =
-                            !self.mixer_state.inputs[channel].soloed;
+                    solo::toggle_input(&mut self.mixer_state, channel);
+                }
+                ControlMsg::ToggleOutputSolo { channel } => {
+                    solo::toggle_output(&mut self.mixer_state, channel);
+                }
+                ControlMsg::SetInputEq {
+                    channel,
+                    band,
+                    gain_db,
+                } => {
+                    for port_eq in self.channel_eqs(channel) {
+                        port_eq.set_gain(band, gain_db);
+                    }
+                }
+                ControlMsg::SetInputEqBand {
+                    channel,
+                    band,
+                    settings,
+                } => {
+                    for port_eq in self.channel_eqs(channel) {
+                        port_eq.set_band(band, settings);
+                    }
+                }
+                ControlMsg::SetInputFilter {
+                    channel,
+                    filter,
+                    settings,
+                } => {
+                    for port_eq in self.channel_eqs(channel) {
+                        port_eq.set_pass_filter(filter, settings);
                     }
                 }
+                ControlMsg::SetMultiband { channel, settings } => {
+                    match self.output_multibands.get_mut(channel) {
+                        Some(Some(multiband)) => multiband.configure(&settings),
+                        // Fixed-size state: creating it does not allocate
+                        Some(slot) => *slot = Some(Multiband::new(&settings, self.sample_rate)),
+                        None => {}
+                    }
+                }
+                ControlMsg::SetOutputNotch {
+                    channel,
+                    slot,
+                    freq_hz,
+                } => {
+                    if let (Some(output), Some(notches)) = (
+                        self.mixer_state.outputs.get_mut(channel),
+                        self.output_notches.get_mut(channel),
+                    ) {
+                        if let Some(notch) = output.notches.get_mut(slot) {
+                            *notch = freq_hz;
+                            notches.set(slot, freq_hz);
+                        }
+                    }
+                }
+                ControlMsg::SetMasterVolume { volume_db } => {
+                    self.mixer_state.master.volume_db = volume_db;
+                }
+                ControlMsg::ToggleMasterMute => {
+                    self.mixer_state.master.muted = !self.mixer_state.master.muted;
+                }
+                ControlMsg::SetPassthroughVolume { channel, volume_db } => {
+                    if let Some(passthrough) = self.mixer_state.passthrough.get_mut(channel) {
+                        passthrough.volume_db = volume_db;
+                    }
+                }
+                ControlMsg::TogglePassthroughMute { channel } => {
+                    if let Some(passthrough) = self.mixer_state.passthrough.get_mut(channel) {
+                        passthrough.muted = !passthrough.muted;
+                    }
+                }
+                ControlMsg::SetRoute {
+                    input,
+                    output,
+                    crosspoint,
+                } => {
+                    self.mixer_state.routing.set(input, output, crosspoint);
+                }
+                ControlMsg::SetCalibrationNoise { output, level_db } => {
+                    self.calibration_noise =
+                        output.map(|output| (output, MeterData::db_to_linear(level_db)));
+                }
+                ControlMsg::SetClick { running, tempo_bpm } => {
+                    if let Some(click) = self.click.as_mut() {
+                        click.set_tempo(tempo_bpm);
+                        click.set_running(running);
+                    }
+                }
+                ControlMsg::SetAnalyzer { source } => {
+                    self.analyzer_source = source;
+                }
                 ControlMsg::Quit => {
                     self.quit_flag.store(true, Ordering::SeqCst);
                 }
@@ -242,25 +1359,50 @@ impl ProcessHandler {
         }
     }
 
-    /// Compute peak level of samples (linear scale)
-    fn compute_peak(samples: &[f32]) -> f32 {
-        samples
-            .iter()
-            .map(|s| s.abs())
-            .fold(0.0_f32, |a, b| a.max(b))
+    /// EQs of the ports of an input channel (none if out of range)
+    fn channel_eqs(&mut self, channel: usize) -> &mut [ChannelEq] {
+        let Some(&count) = self.input_port_counts.get(channel) else {
+            return &mut [];
+        };
+        let first: usize = self.input_port_counts[..channel].iter().sum();
+        &mut self.input_eqs[first..first + count]
     }
 }
 
 impl jack::ProcessHandler for ProcessHandler {
+    fn buffer_size(&mut self, _: &Client, size: jack::Frames) -> Control {
+        // Not called in real time; the scratch buffers only reallocate
+        // beyond `MAX_BUFFER_FRAMES`
+        for buffer in &mut self.eq_buffers {
+            buffer.resize(size as usize, 0.0);
+        }
+        self.click_buffer.resize(size as usize, 0.0);
+        for graph in self.input_graphs.iter_mut().flatten() {
+            graph.set_buffer_size(size as usize);
+        }
+        Control::Continue
+    }
+
     fn process(&mut self, _: &Client, ps: &ProcessScope) -> Control {
+        self.watchdog_signals.heartbeat.fetch_add(1, Ordering::Relaxed);
+
         // Process any pending control messages
         self.process_control_messages();
 
+        // Forward MIDI program changes to the UI
+        if let Some(port) = &self.midi_port {
+            for event in port.iter(ps) {
+                if let Some(change) = self.program_decoder.decode(event.bytes) {
+                    let _ = self.program_producer.push(change);
+                }
+            }
+        }
+
         if self.quit_flag.load(Ordering::Relaxed) {
             return Control::Quit;
         }
 
-        let any_soloed = self.mixer_state.any_input_soloed();
+        let nframes = ps.n_frames() as usize;
 
         // First, zero all output buffers
         for port in &mut self.output_ports {
@@ -270,91 +1412,704 @@ impl jack::ProcessHandler for ProcessHandler {
             }
         }
 
-        // Process inputs and mix to outputs
+        // Measure inputs and work out their effective gains
         let mut in_port_idx = 0;
         for (ch_idx, &port_count) in self.input_port_counts.iter().enumerate() {
-            let input_state = &self.mixer_state.inputs[ch_idx];
-            
-            // Calculate effective input gain
-            let input_gain = if input_state.muted {
-                0.0
-            } else if any_soloed && !input_state.soloed {
-                0.0
+            // Calculate effective input gain, fading mute and solo changes
+            let fader_gain = MeterData::db_to_linear(self.mixer_state.inputs[ch_idx].volume_db);
+            let audible = self.mixer_state.input_audible(ch_idx);
+            let (fade_from, fade_to) = self.input_fades[ch_idx].advance(audible, nframes);
+            let mut input_gain = (fader_gain * fade_from, fader_gain * fade_to);
+
+            let mut peaks = [0.0f32; MAX_CHANNEL_PORTS];
+            let mut rms = [0.0f32; MAX_CHANNEL_PORTS];
+            let mut loudness_ms = 0.0f32;
+            let mut clipped = 0;
+            let trim = MeterData::db_to_linear(self.mixer_state.inputs[ch_idx].trim_db);
+            let balance_db = self.mixer_state.inputs[ch_idx].balance_db;
+            let balanced = port_count == 2 && balance_db != 0.0;
+            let balance = dsp::balance_gains(balance_db);
+            let mid_side = (
+                self.input_mid_side[ch_idx].1,
+                self.mixer_state.inputs[ch_idx].mid_side_gains(),
+            );
+            self.input_mid_side[ch_idx] = mid_side;
+            let mid_siding = mid_side != ([1.0; 2], [1.0; 2]);
+
+            for p in 0..port_count {
+                // The meters show the trimmed and balanced source level
+                let port_trim = if balanced { trim * balance[p] } else { trim };
+                let port = &self.input_ports[in_port_idx];
+                let in_samples = port.as_slice(ps);
+                let peak = dsp::peak(in_samples);
+                peaks[p] = peak * port_trim;
+                let connected = port.connected_count().map_or(true, |n| n > 0);
+                self.silence.update(in_port_idx, connected, peak, nframes);
+                rms[p] = dsp::rms(in_samples) * port_trim;
+                clipped += dsp::count_clipped(in_samples, port_trim);
+                loudness_ms += self.input_k_filters[in_port_idx].mean_square(in_samples)
+                    * port_trim
+                    * port_trim;
+
+                // Trim and equalize into the scratch buffer; a processing
+                // graph goes first
+                let eq = &mut self.input_eqs[in_port_idx];
+                if let Some(graph) = self.input_graphs[ch_idx].as_mut() {
+                    let buffer = &mut graph.input_mut(p)[..in_samples.len()];
+                    buffer.copy_from_slice(in_samples);
+                    dsp::apply_ramp(buffer, port_trim, port_trim);
+                } else if !eq.is_flat()
+                    || self.input_deessers[ch_idx].is_some()
+                    || trim != 1.0
+                    || balanced
+                    || mid_siding
+                {
+                    let buffer = &mut self.eq_buffers[in_port_idx][..in_samples.len()];
+                    buffer.copy_from_slice(in_samples);
+                    dsp::apply_ramp(buffer, port_trim, port_trim);
+                    eq.process(buffer);
+                }
+                in_port_idx += 1;
+            }
+
+            if let Some(graph) = self.input_graphs[ch_idx].as_mut() {
+                graph.process(nframes);
+                let first = in_port_idx - port_count;
+                for p in 0..port_count {
+                    let buffer = &mut self.eq_buffers[first + p][..nframes];
+                    buffer.copy_from_slice(graph.output(p, nframes));
+                    let eq = &mut self.input_eqs[first + p];
+                    if !eq.is_flat() {
+                        eq.process(buffer);
+                    }
+                }
+                let sends = self.input_sends[ch_idx].iter_mut().zip(graph.sends(nframes));
+                for (port, samples) in sends {
+                    port.as_mut_slice(ps).copy_from_slice(samples);
+                }
+            }
+
+            // De-ess after the EQ, all ports of the channel together
+            let mut deesser_db = 0.0;
+            if let Some(deesser) = self.input_deessers[ch_idx].as_mut() {
+                let first = in_port_idx - port_count;
+                let mut buffers: [&mut [f32]; MAX_CHANNEL_PORTS] = Default::default();
+                for (slot, buffer) in buffers
+                    .iter_mut()
+                    .zip(self.eq_buffers[first..first + port_count].iter_mut())
+                {
+                    *slot = &mut buffer[..nframes];
+                }
+                deesser.process(&mut buffers[..port_count]);
+                deesser_db = deesser.take_reduction_db();
+            }
+
+            // Mid and side levels last, ahead of the fader
+            if mid_siding {
+                let first = in_port_idx - port_count;
+                let (left, right) = self.eq_buffers.split_at_mut(first + 1);
+                let (left, right) = (&mut left[first][..nframes], &mut right[0][..nframes]);
+                dsp::mid_side_ramp(left, right, mid_side.0, mid_side.1);
+            }
+
+            // A loud event dips the whole channel, wherever it goes
+            let mut dip_db = 0.0;
+            if let Some(protector) = self.input_protectors[ch_idx].as_mut() {
+                let ports = port_count.clamp(1, MAX_CHANNEL_PORTS);
+                let peak = peaks[..ports].iter().fold(0.0f32, |a, b| a.max(*b));
+                let mean_square = rms[..ports].iter().map(|r| r * r).sum::<f32>() / ports as f32;
+                let (from, to) = protector.process(peak, mean_square, nframes);
+                input_gain = (input_gain.0 * from, input_gain.1 * to);
+                dip_db = protector.reduction_db();
+            }
+
+            self.input_gains[ch_idx] = input_gain;
+            self.input_levels[ch_idx] = peaks.iter().fold(0.0f32, |a, b| a.max(*b)) * input_gain.1;
+            if let Some(automixer) = self.automixer.as_mut() {
+                let ports = &rms[..port_count.clamp(1, MAX_CHANNEL_PORTS)];
+                let mean_square = ports.iter().map(|r| r * r).sum::<f32>() / ports.len() as f32;
+                automixer.measure(ch_idx, if audible { mean_square } else { 0.0 }, nframes);
+            }
+
+            // Stereo image of the source for the strip's correlation
+            let correlation = if port_count == 2 {
+                let first = in_port_idx - port_count;
+                dsp::correlation(
+                    self.input_ports[first].as_slice(ps),
+                    self.input_ports[first + 1].as_slice(ps),
+                )
             } else {
-                MeterData::db_to_linear(input_state.volume_db)
+                None
             };
 
-            let mut peaks = [0.0f32; 2];
+            // Mid/side meters show the trimmed and balanced source as mid and
+            // side at their levels
+            if self.mixer_state.inputs[ch_idx].mid_side && port_count == 2 {
+                let first = in_port_idx - port_count;
+                let gains = if balanced {
+                    balance.map(|b| trim * b)
+                } else {
+                    [trim; 2]
+                };
+                let (mid_side_peaks, mid_side_rms) = dsp::mid_side_levels(
+                    self.input_ports[first].as_slice(ps),
+                    self.input_ports[first + 1].as_slice(ps),
+                    gains,
+                );
+                for p in 0..2 {
+                    peaks[p] = mid_side_peaks[p] * mid_side.1[p];
+                    rms[p] = mid_side_rms[p] * mid_side.1[p];
+                }
+            }
+
+            // Post-fader meters show what reaches the buses
+            if self.mixer_state.inputs[ch_idx].meter_point == TapPoint::PostFader {
+                let automix = self.automixer.as_ref().and_then(|a| a.gain(ch_idx));
+                let gain = input_gain.1 * automix.map_or(1.0, |(_, to)| to);
+                for p in 0..port_count {
+                    peaks[p] *= gain;
+                    rms[p] *= gain;
+                }
+                loudness_ms *= gain * gain;
+            }
+
+            // Send meter data for this input channel
+            let meter = MeterData {
+                channel_index: ch_idx,
+                peaks,
+                rms,
+                loudness_ms,
+                gain_reduction_db: deesser_db,
+                limiter_db: 0.0,
+                multiband_db: [0.0; MULTIBAND_BANDS],
+                clipped,
+                automix_db: self.automixer.as_ref().map_or(0.0, |a| a.gain_db(ch_idx)),
+                dip_db,
+                correlation,
+                port_count,
+                timestamp: std::time::Instant::now(),
+            };
+            let _ = self.meter_producer.push(meter);
+        }
 
-            // Process each port of this input channel
+        // Share out the automixer gain now that every member is measured
+        if let Some(automixer) = self.automixer.as_mut() {
+            automixer.update();
+            for (ch_idx, gain) in self.input_gains.iter_mut().enumerate() {
+                if let Some((from, to)) = automixer.gain(ch_idx) {
+                    *gain = (gain.0 * from, gain.1 * to);
+                    self.input_levels[ch_idx] *= to;
+                }
+            }
+        }
+
+        // Decide which inputs each output bus takes this cycle
+        // (each only sees the levels of the inputs routed to it)
+        for (out_ch_idx, mix) in self.output_mixes.iter_mut().enumerate() {
+            for (in_ch_idx, level) in self.mix_levels.iter_mut().enumerate() {
+                *level = self.input_levels[in_ch_idx]
+                    * self.mixer_state.routing.gain(in_ch_idx, out_ch_idx);
+            }
+            mix.update(&self.mix_levels, nframes);
+        }
+
+        // Solo cue: the soloed inputs fade into the cue outputs, after their
+        // faders (and at their send into a soloed output), while anything
+        // is soloed
+        let cueing = self.mixer_state.solo_cue && solo::active(&self.mixer_state);
+        let solo_ramp = self.solo_fade.advance(cueing, nframes);
+        for (ch_idx, fade) in self.cue_fades.iter_mut().enumerate() {
+            let level = solo::input_level(&self.mixer_state, ch_idx);
+            if level > 0.0 {
+                self.cue_levels[ch_idx] = level;
+            }
+            let (from, to) = fade.advance(cueing && level > 0.0, nframes);
+            let (input_gain, level) = (self.input_gains[ch_idx], self.cue_levels[ch_idx]);
+            self.cue_gains[ch_idx] = (input_gain.0 * from * level, input_gain.1 * to * level);
+        }
+
+        // Mix inputs to outputs
+        let mut in_port_idx = 0;
+        for (ch_idx, &port_count) in self.input_port_counts.iter().enumerate() {
+            let input_gain = self.input_gains[ch_idx];
+
+            // Process each port of this input channel (after its trim, graph,
+            // EQ, de-esser and mid/side levels; the outputs of a monitor split
+            // only get the trim and balance)
+            let trim = MeterData::db_to_linear(self.mixer_state.inputs[ch_idx].trim_db);
+            let balance_db = self.mixer_state.inputs[ch_idx].balance_db;
+            let balanced = port_count == 2 && balance_db != 0.0;
+            let processed = self.input_graphs[ch_idx].is_some()
+                || self.input_deessers[ch_idx].is_some()
+                || trim != 1.0
+                || balanced
+                || self.input_mid_side[ch_idx] != ([1.0; 2], [1.0; 2]);
             for p in 0..port_count {
-                let in_samples = self.input_ports[in_port_idx].as_slice(ps);
-                peaks[p] = Self::compute_peak(in_samples);
+                let trim = if balanced {
+                    trim * dsp::balance_gains(balance_db)[p]
+                } else {
+                    trim
+                };
+                let raw_samples = self.input_ports[in_port_idx].as_slice(ps);
+                let in_samples = if !processed && self.input_eqs[in_port_idx].is_flat() {
+                    raw_samples
+                } else {
+                    &self.eq_buffers[in_port_idx][..ps.n_frames() as usize]
+                };
 
-                // Mix this input to all outputs
+                // Mix this input to every output it is routed to and accepted by
                 let mut out_port_idx = 0;
                 for (out_ch_idx, &out_port_count) in self.output_port_counts.iter().enumerate() {
-                    let output_state = &self.mixer_state.outputs[out_ch_idx];
-                    let output_gain = output_state.get_linear_gain();
-
+                    let crosspoint = self.mixer_state.routing.get(ch_idx, out_ch_idx);
+                    let send_gain = crosspoint.linear_gain();
+                    let accepted = send_gain > 0.0 && self.output_mixes[out_ch_idx].accepts(ch_idx);
+                    // A mono fold sends every port of a multi-port input to every
+                    // output port, scaled down by the input's port count
+                    let fold = port_count > 1 && crosspoint.mono;
+                    let gain = if fold {
+                        send_gain / port_count as f32
+                    } else {
+                        send_gain
+                    };
+                    // A solo cue output dims its program under the soloed inputs
+                    let (dim, cue) = match self.solo_dims[out_ch_idx] {
+                        Some(dim) => (
+                            (
+                                1.0 - (1.0 - dim) * solo_ramp.0,
+                                1.0 - (1.0 - dim) * solo_ramp.1,
+                            ),
+                            self.cue_gains[ch_idx],
+                        ),
+                        None => ((1.0, 1.0), (0.0, 0.0)),
+                    };
                     for out_p in 0..out_port_count {
                         // Determine which input port maps to this output port
-                        // For mono input -> stereo output: use same input for both
-                        // For stereo input -> stereo output: use matching channels
-                        let use_this_input = if port_count == 1 {
-                            // Mono input goes to all output ports
+                        // For mono input -> any output: use same input for all ports
+                        // For multi-port inputs: use matching positions (L->L, R->R,
+                        // C->C, ...); ports the other side lacks are left out
+                        let use_this_input = if port_count == 1 || fold {
+                            // Mono (or folded) input goes to all output ports
                             true
                         } else {
-                            // Stereo input: left->left, right->right
-                            p == out_p || (p == 0 && out_p >= port_count)
+                            p == out_p
                         };
+                        // The cue takes the ports as they are, whatever the routing
+                        let cued = cue != (0.0, 0.0) && (port_count == 1 || p == out_p);
 
-                        if use_this_input {
+                        if (accepted && use_this_input) || cued {
                             let out_samples = self.output_ports[out_port_idx].as_mut_slice(ps);
-                            let combined_gain = input_gain * output_gain;
-                            
-                            for (out_s, in_s) in out_samples.iter_mut().zip(in_samples.iter()) {
-                                *out_s += in_s * combined_gain;
+                            let split = self.monitor_splits[ch_idx][out_ch_idx];
+                            let (in_samples, split_trim) = if split {
+                                (raw_samples, trim)
+                            } else {
+                                (in_samples, 1.0)
+                            };
+
+                            let (mut from, mut to) = if accepted && use_this_input {
+                                (input_gain.0 * gain * dim.0, input_gain.1 * gain * dim.1)
+                            } else {
+                                (0.0, 0.0)
+                            };
+                            if cued {
+                                from += cue.0;
+                                to += cue.1;
                             }
+                            dsp::mix_ramp(
+                                out_samples,
+                                in_samples,
+                                from * split_trim,
+                                to * split_trim,
+                            );
                         }
                         out_port_idx += 1;
                     }
                 }
 
+                // Feed the tap port, if this channel has one
+                if let (Some(tap), Some(point)) =
+                    (&mut self.input_taps[in_port_idx], self.input_tap_points[ch_idx])
+                {
+                    let (from, to) = match point {
+                        TapPoint::PreFader => (1.0, 1.0),
+                        TapPoint::PostFader => input_gain,
+                    };
+                    let tap_samples = tap.as_mut_slice(ps);
+                    tap_samples.copy_from_slice(in_samples);
+                    dsp::apply_ramp(tap_samples, from, to);
+                }
+
                 in_port_idx += 1;
             }
+        }
 
-            // Send meter data for this input channel
-            let meter = MeterData {
-                channel_index: ch_idx,
-                peaks,
-                port_count,
-                timestamp: std::time::Instant::now(),
+        // Metronome click into its outputs, ahead of their faders
+        if let Some(click) = self.click.as_mut() {
+            let buffer = &mut self.click_buffer[..nframes];
+            click.render(buffer);
+            let mut out_port_idx = 0;
+            for (ch_idx, &port_count) in self.output_port_counts.iter().enumerate() {
+                if self.click_outputs[ch_idx] {
+                    for port in out_port_idx..out_port_idx + port_count {
+                        let out_samples = self.output_ports[port].as_mut_slice(ps);
+                        for (out, s) in out_samples.iter_mut().zip(buffer.iter()) {
+                            *out += s;
+                        }
+                    }
+                }
+                out_port_idx += port_count;
+            }
+        }
+
+        // Calibration: pink noise (independent per port) replaces the mix of
+        // one output and every other output is silent
+        if let Some((target, gain)) = self.calibration_noise {
+            let mut out_port_idx = 0;
+            for (ch_idx, &port_count) in self.output_port_counts.iter().enumerate() {
+                for port in out_port_idx..out_port_idx + port_count {
+                    let out_samples = self.output_ports[port].as_mut_slice(ps);
+                    if ch_idx == target {
+                        self.pink_noise.fill(out_samples, gain);
+                    } else {
+                        out_samples.fill(0.0);
+                    }
+                }
+                out_port_idx += port_count;
+            }
+        }
+
+        // Fold mono-summed stereo outputs: (L + R) at -3 dB on both ports
+        let mut out_port_idx = 0;
+        for (ch_idx, &port_count) in self.output_port_counts.iter().enumerate() {
+            if self.output_mono[ch_idx] && port_count == 2 {
+                let (left, right) = self.output_ports.split_at_mut(out_port_idx + 1);
+                let left = left[out_port_idx].as_mut_slice(ps);
+                let right = right[0].as_mut_slice(ps);
+                for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+                    let sum = (*l + *r) * std::f32::consts::FRAC_1_SQRT_2;
+                    *l = sum;
+                    *r = sum;
+                }
+            }
+            out_port_idx += port_count;
+        }
+
+        // Apply output gains (times the master gain), the mid and side levels,
+        // the feedback notches, the multiband compressor, the soft clipper,
+        // the limiter and the lip-sync delay, feeding output taps before and
+        // after the fader
+        let master = &self.mixer_state.master;
+        let master_gain = MeterData::db_to_linear(master.volume_db);
+        let master_fade = self.master_fade.advance(!master.muted, nframes);
+        let mut out_port_idx = 0;
+        for (ch_idx, &port_count) in self.output_port_counts.iter().enumerate() {
+            let trim_gain = self.output_trims[ch_idx].as_ref().map_or(1.0, |t| t.gain());
+            let output = &self.mixer_state.outputs[ch_idx];
+            let output_gain = MeterData::db_to_linear(output.volume_db) * master_gain * trim_gain;
+            let audible = self.mixer_state.output_audible(ch_idx);
+            let fade = self.output_fades[ch_idx].advance(audible, nframes);
+            let (gain_from, gain_to) = (
+                output_gain * fade.0 * master_fade.0,
+                output_gain * fade.1 * master_fade.1,
+            );
+            let tap_point = self.output_tap_points[ch_idx];
+            let ports = out_port_idx..out_port_idx + port_count;
+
+            for port in ports.clone() {
+                let out_samples = self.output_ports[port].as_mut_slice(ps);
+                if let (Some(tap), Some(TapPoint::PreFader)) =
+                    (self.output_taps[port].as_mut(), tap_point)
+                {
+                    tap.as_mut_slice(ps).copy_from_slice(out_samples);
+                }
+                dsp::apply_ramp(out_samples, gain_from, gain_to);
+            }
+
+            let mid_side = (self.output_mid_side[ch_idx], output.mid_side_gains());
+            self.output_mid_side[ch_idx] = mid_side.1;
+            if mid_side != ([1.0; 2], [1.0; 2]) {
+                let first = ports.start;
+                let (left, right) = self.output_ports.split_at_mut(first + 1);
+                let (left, right) = (left[first].as_mut_slice(ps), right[0].as_mut_slice(ps));
+                dsp::mid_side_ramp(left, right, mid_side.0, mid_side.1);
+            }
+
+            let notches = Some(&mut self.output_notches[ch_idx]).filter(|n| n.is_active());
+            let multiband = self.output_multibands[ch_idx].as_mut();
+            let clip = self.output_clips[ch_idx].as_mut();
+            let limiter = self.output_limiters[ch_idx].as_mut();
+            let delay = self.output_delays[ch_idx].as_mut();
+            if notches.is_some()
+                || multiband.is_some()
+                || clip.is_some()
+                || limiter.is_some()
+                || delay.is_some()
+            {
+                let mut buffers: [&mut [f32]; MAX_CHANNEL_PORTS] = Default::default();
+                for (slot, port) in buffers
+                    .iter_mut()
+                    .zip(self.output_ports[ports.clone()].iter_mut())
+                {
+                    *slot = port.as_mut_slice(ps);
+                }
+                if let Some(notches) = notches {
+                    notches.process(&mut buffers[..port_count]);
+                }
+                if let Some(multiband) = multiband {
+                    multiband.process(&mut buffers[..port_count]);
+                }
+                if let Some(clip) = clip {
+                    clip.process(&mut buffers[..port_count]);
+                }
+                if let Some(limiter) = limiter {
+                    limiter.process(&mut buffers[..port_count]);
+                }
+                if let Some(delay) = delay {
+                    delay.process(&mut buffers[..port_count]);
+                }
+            }
+
+            if tap_point == Some(TapPoint::PostFader) {
+                for port in ports {
+                    if let Some(tap) = self.output_taps[port].as_mut() {
+                        let out_samples = self.output_ports[port].as_mut_slice(ps);
+                        tap.as_mut_slice(ps).copy_from_slice(out_samples);
+                    }
+                }
+            }
+
+            out_port_idx += port_count;
+        }
+
+        // Freewheeling, the callback runs outside real time and may wait for
+        // the UI thread to empty the recorder feed, so a bounce loses no frames
+        if self.freewheeling.load(Ordering::Relaxed) {
+            if let Some(tap) = &self.record_tap {
+                tap.wait_for_room(ps.n_frames() as usize, FREEWHEEL_WAIT);
+            }
+        }
+
+        // Feed the analyzer views with the trimmed input or the finished
+        // output, its ports averaged; a period that does not fit is dropped
+        if let Some(source) = self.analyzer_source {
+            let mut ports: [&[f32]; MAX_CHANNEL_PORTS] = [&[]; MAX_CHANNEL_PORTS];
+            let (port_count, gain) = match source {
+                AnalyzerSource::Input(ch) if ch < self.input_port_counts.len() => {
+                    let first: usize = self.input_port_counts[..ch].iter().sum();
+                    let port_count = self.input_port_counts[ch];
+                    for (slot, port) in ports
+                        .iter_mut()
+                        .zip(&self.input_ports[first..first + port_count])
+                    {
+                        *slot = port.as_slice(ps);
+                    }
+                    let trim = MeterData::db_to_linear(self.mixer_state.inputs[ch].trim_db);
+                    (port_count, trim)
+                }
+                AnalyzerSource::Output(ch) if ch < self.output_port_counts.len() => {
+                    let first: usize = self.output_port_counts[..ch].iter().sum();
+                    let port_count = self.output_port_counts[ch];
+                    for (slot, port) in ports
+                        .iter_mut()
+                        .zip(self.output_ports[first..first + port_count].iter_mut())
+                    {
+                        *slot = port.as_mut_slice(ps);
+                    }
+                    (port_count, 1.0)
+                }
+                _ => (0, 0.0),
             };
-            let _ = self.meter_producer.push(meter);
+            if port_count > 0 && self.analyzer_producer.slots() >= nframes {
+                let gain = gain / port_count as f32;
+                for frame in 0..nframes {
+                    let sum: f32 = ports[..port_count].iter().map(|p| p[frame]).sum();
+                    let _ = self.analyzer_producer.push(sum * gain);
+                }
+            }
         }
 
-        // Calculate and send output meters
+        // Copy the recorded outputs (post-fader) to the replay buffer and recorder
+        for tap in [self.replay_tap.as_mut(), self.record_tap.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            let first: usize = self.output_port_counts[..tap.output].iter().sum();
+            let port_count = self.output_port_counts[tap.output].min(tap.ports);
+            let mut ports: [&[f32]; MAX_CHANNEL_PORTS] = [&[]; MAX_CHANNEL_PORTS];
+            for (slot, port) in ports
+                .iter_mut()
+                .zip(self.output_ports[first..first + port_count].iter_mut())
+            {
+                *slot = port.as_mut_slice(ps);
+            }
+            tap.push(&ports[..port_count]);
+        }
+
+        // Calculate and send output meters; the master meter shows the
+        // hottest output on each side (front left/right of surround outputs)
         let num_inputs = self.mixer_state.inputs.len();
+        let mut master_peaks = [0.0f32; MAX_CHANNEL_PORTS];
+        let mut master_rms = [0.0f32; MAX_CHANNEL_PORTS];
+        let mut master_loudness_ms = 0.0f32;
+        let mut master_clipped = 0;
         let mut out_port_idx = 0;
         for (ch_idx, &port_count) in self.output_port_counts.iter().enumerate() {
-            let mut peaks = [0.0f32; 2];
+            let mut peaks = [0.0f32; MAX_CHANNEL_PORTS];
+            let mut rms = [0.0f32; MAX_CHANNEL_PORTS];
+            let mut loudness_ms = 0.0f32;
+            let mut clipped = 0;
             
             for p in 0..port_count {
                 let out_samples = self.output_ports[out_port_idx].as_mut_slice(ps);
-                peaks[p] = Self::compute_peak(out_samples);
+                peaks[p] = dsp::peak(out_samples);
+                rms[p] = dsp::rms(out_samples);
+                clipped += dsp::count_clipped(out_samples, 1.0);
+                loudness_ms += self.output_k_filters[out_port_idx].mean_square(out_samples);
                 out_port_idx += 1;
             }
 
+            for side in 0..2 {
+                let p = side.min(port_count - 1);
+                master_peaks[side] = master_peaks[side].max(peaks[p]);
+                master_rms[side] = master_rms[side].max(rms[p]);
+            }
+            master_loudness_ms = master_loudness_ms.max(loudness_ms);
+            master_clipped = master_clipped.max(clipped);
+
+            // Auto-trim reacts to the level it let through this cycle (ahead
+            // of the limiter, which would otherwise hide any overload)
+            let (limiter_peak, limiter_db) = match self.output_limiters[ch_idx].as_mut() {
+                Some(limiter) => (Some(limiter.take_input_peak()), limiter.take_reduction_db()),
+                None => (None, 0.0),
+            };
+            let gain_reduction_db = match self.output_trims[ch_idx].as_mut() {
+                Some(trim) => {
+                    let peak = limiter_peak
+                        .unwrap_or_else(|| peaks.iter().fold(0.0f32, |a, b| a.max(*b)));
+                    trim.update(peak, ps.n_frames() as usize);
+                    trim.reduction_db()
+                }
+                None => 0.0,
+            };
+
+            let multiband_db = self.output_multibands[ch_idx]
+                .as_mut()
+                .map_or([0.0; MULTIBAND_BANDS], |m| m.take_reduction_db());
+
+            // Stereo image for the correlation meter and goniometer
+            let mut correlation = None;
+            if port_count == 2 {
+                let first = out_port_idx - port_count;
+                let (left, right) = self.output_ports.split_at_mut(first + 1);
+                let (left, right) = (left[first].as_mut_slice(ps), right[0].as_mut_slice(ps));
+                correlation = dsp::correlation(left, right);
+                let _ = self.scope_producer.push(ScopeData {
+                    output: ch_idx,
+                    points: dsp::scope_points(left, right),
+                });
+            }
+
+            // The master meter and auto-trim above keep left and right
+            if self.mixer_state.outputs[ch_idx].mid_side && port_count == 2 {
+                let first = out_port_idx - port_count;
+                let (left, right) = self.output_ports.split_at_mut(first + 1);
+                let (mid_side_peaks, mid_side_rms) = dsp::mid_side_levels(
+                    left[first].as_mut_slice(ps),
+                    right[0].as_mut_slice(ps),
+                    [1.0, 1.0],
+                );
+                peaks[..2].copy_from_slice(&mid_side_peaks);
+                rms[..2].copy_from_slice(&mid_side_rms);
+            }
+
             let meter = MeterData {
                 channel_index: num_inputs + ch_idx,
                 peaks,
+                rms,
+                loudness_ms,
+                gain_reduction_db,
+                limiter_db,
+                multiband_db,
+                clipped,
+                automix_db: 0.0,
+                dip_db: 0.0,
+                correlation,
                 port_count,
                 timestamp: std::time::Instant::now(),
             };
             let _ = self.meter_producer.push(meter);
         }
 
+        let meter = MeterData {
+            channel_index: num_inputs + self.output_port_counts.len(),
+            peaks: master_peaks,
+            rms: master_rms,
+            loudness_ms: master_loudness_ms,
+            gain_reduction_db: 0.0,
+            limiter_db: 0.0,
+            multiband_db: [0.0; MULTIBAND_BANDS],
+            clipped: master_clipped,
+            automix_db: 0.0,
+            dip_db: 0.0,
+            correlation: None,
+            port_count: self.mixer_state.master.port_count,
+            timestamp: std::time::Instant::now(),
+        };
+        let _ = self.meter_producer.push(meter);
+
+        // Passthrough channels: each input port to its output port through
+        // one fader; the meter shows the hottest port
+        let first_index = num_inputs + self.output_port_counts.len() + 1;
+        let mut port_idx = 0;
+        for (ch_idx, &port_count) in self.passthrough_port_counts.iter().enumerate() {
+            let channel = &self.mixer_state.passthrough[ch_idx];
+            let gain = MeterData::db_to_linear(channel.volume_db);
+            let (fade_from, fade_to) = self.passthrough_fades[ch_idx].advance(!channel.muted, nframes);
+            let mut peak = 0.0f32;
+            let mut rms = 0.0f32;
+            let mut clipped = 0;
+
+            for _ in 0..port_count {
+                let in_samples = self.passthrough_inputs[port_idx].as_slice(ps);
+                let out_samples = self.passthrough_outputs[port_idx].as_mut_slice(ps);
+                out_samples.copy_from_slice(in_samples);
+                dsp::apply_ramp(out_samples, gain * fade_from, gain * fade_to);
+                peak = peak.max(dsp::peak(out_samples));
+                rms = rms.max(dsp::rms(out_samples));
+                clipped += dsp::count_clipped(out_samples, 1.0);
+                port_idx += 1;
+            }
+
+            // A stereo passthrough also gets the strip's correlation
+            let correlation = if port_count == 2 {
+                let first = port_idx - port_count;
+                let (left, right) = self.passthrough_outputs.split_at_mut(first + 1);
+                dsp::correlation(left[first].as_mut_slice(ps), right[0].as_mut_slice(ps))
+            } else {
+                None
+            };
+
+            let mut peaks = [0.0f32; MAX_CHANNEL_PORTS];
+            let mut rms_levels = [0.0f32; MAX_CHANNEL_PORTS];
+            peaks[0] = peak;
+            rms_levels[0] = rms;
+            let meter = MeterData {
+                channel_index: first_index + ch_idx,
+                peaks,
+                rms: rms_levels,
+                loudness_ms: 0.0,
+                gain_reduction_db: 0.0,
+                limiter_db: 0.0,
+                multiband_db: [0.0; MULTIBAND_BANDS],
+                clipped,
+                automix_db: 0.0,
+                dip_db: 0.0,
+                correlation,
+                port_count: 1,
+                timestamp: std::time::Instant::now(),
+            };
+            let _ = self.meter_producer.push(meter);
+        }
+
         Control::Continue
     }
 }
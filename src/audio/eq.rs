@@ -0,0 +1,321 @@
+//! Channel EQ
+//!
+//! Optional high- and low-pass filters, then low shelf, mid peak and high
+//! shelf biquads (RBJ cookbook) at fixed frequencies, followed by up to
+//! `MAX_EQ_BANDS` parametric peaking bands.
+//! Filters for every band are allocated up front so bands can be changed,
+//! added and removed from the audio thread. Coefficients are recomputed in
+//! place when a band changes, keeping the filter state so adjustments do not
+//! click; bands at 0 dB and filters switched off are skipped.
+
+use std::f32::consts::PI;
+
+use super::dsp::Biquad;
+use crate::config::{
+    EqBand, EqBandConfig, EqConfig, PassFilter, PassFilterConfig, MAX_EQ_BANDS,
+};
+
+/// Low shelf corner frequency in Hz
+const LOW_FREQ: f32 = 100.0;
+
+/// Mid band center frequency in Hz
+const MID_FREQ: f32 = 1_000.0;
+
+/// Mid band quality factor
+const MID_Q: f32 = 0.7;
+
+/// High shelf corner frequency in Hz
+const HIGH_FREQ: f32 = 10_000.0;
+
+/// Shelf slope (1.0 = steepest without overshoot)
+const SHELF_SLOPE: f32 = 1.0;
+
+/// Quality factor of the pass filters (Butterworth, no resonance)
+const BUTTERWORTH_Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// Number of pass filters ahead of the bands (high-pass, low-pass)
+const PASS_FILTERS: usize = 2;
+
+/// Number of fixed bands ahead of the parametric ones
+const FIXED_BANDS: usize = 3;
+
+/// Total filters per EQ
+const FILTERS: usize = PASS_FILTERS + FIXED_BANDS + MAX_EQ_BANDS;
+
+/// Filter shape of one band
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    HighPass,
+    LowPass,
+    LowShelf,
+    Peak,
+    HighShelf,
+}
+
+/// Channel EQ for one port
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelEq {
+    /// Pass filters (high, low), fixed bands (low, mid, high), then the
+    /// parametric bands
+    filters: [Biquad; FILTERS],
+
+    /// Which filters are switched in or have a non-zero gain
+    active: [bool; FILTERS],
+
+    sample_rate: f32,
+}
+
+impl ChannelEq {
+    /// Create an EQ with the given settings
+    pub fn new(settings: &EqConfig, sample_rate: f32) -> Self {
+        let mut eq = Self {
+            filters: [Biquad::new(1.0, 0.0, 0.0, 0.0, 0.0); FILTERS],
+            active: [false; FILTERS],
+            sample_rate,
+        };
+        eq.set_gain(EqBand::Low, settings.low_db);
+        eq.set_gain(EqBand::Mid, settings.mid_db);
+        eq.set_gain(EqBand::High, settings.high_db);
+        for (index, band) in settings.bands.iter().enumerate() {
+            eq.set_band(index, *band);
+        }
+        eq.set_pass_filter(PassFilter::HighPass, settings.high_pass);
+        eq.set_pass_filter(PassFilter::LowPass, settings.low_pass);
+        eq
+    }
+
+    /// Whether every band is at 0 dB and no filter is on (processing can be skipped)
+    pub fn is_flat(&self) -> bool {
+        !self.active.contains(&true)
+    }
+
+    /// Change the gain of a fixed band, keeping the filter state
+    pub fn set_gain(&mut self, band: EqBand, gain_db: f32) {
+        let (index, shape, freq) = match band {
+            EqBand::Low => (0, Shape::LowShelf, LOW_FREQ),
+            EqBand::Mid => (1, Shape::Peak, MID_FREQ),
+            EqBand::High => (2, Shape::HighShelf, HIGH_FREQ),
+        };
+        self.configure(PASS_FILTERS + index, shape, freq, gain_db, MID_Q, gain_db != 0.0);
+    }
+
+    /// Change or switch a high- or low-pass filter (None switches it off)
+    pub fn set_pass_filter(&mut self, filter: PassFilter, settings: Option<PassFilterConfig>) {
+        let (index, shape) = match filter {
+            PassFilter::HighPass => (0, Shape::HighPass),
+            PassFilter::LowPass => (1, Shape::LowPass),
+        };
+        let freq = settings.map_or(filter.default_freq(), |s| s.freq);
+        let active = settings.is_some_and(|s| s.enabled);
+        self.configure(index, shape, freq, 0.0, BUTTERWORTH_Q, active);
+    }
+
+    /// Change a parametric band (0 dB disables it)
+    pub fn set_band(&mut self, index: usize, band: EqBandConfig) {
+        if index < MAX_EQ_BANDS {
+            self.configure(
+                PASS_FILTERS + FIXED_BANDS + index,
+                Shape::Peak,
+                band.freq,
+                band.gain_db,
+                band.q,
+                band.gain_db != 0.0,
+            );
+        }
+    }
+
+    /// Recompute one filter's coefficients and switch it in or out
+    fn configure(
+        &mut self,
+        index: usize,
+        shape: Shape,
+        freq: f32,
+        gain_db: f32,
+        q: f32,
+        active: bool,
+    ) {
+        if active && !self.active[index] {
+            // Start from silence rather than whatever the band last held
+            self.filters[index].reset();
+        }
+        self.active[index] = active;
+        let (b0, b1, b2, a1, a2) = coefficients(shape, freq, gain_db, q, self.sample_rate);
+        self.filters[index].set_coefficients(b0, b1, b2, a1, a2);
+    }
+
+    /// Filter a block in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for (filter, _) in self
+            .filters
+            .iter_mut()
+            .zip(self.active)
+            .filter(|(_, active)| *active)
+        {
+            for s in samples.iter_mut() {
+                *s = filter.process(*s);
+            }
+        }
+    }
+}
+
+/// Normalized coefficients of a peaking band, for filters outside the EQ
+pub fn peak_coefficients(
+    freq: f32,
+    gain_db: f32,
+    q: f32,
+    sample_rate: f32,
+) -> (f32, f32, f32, f32, f32) {
+    coefficients(Shape::Peak, freq, gain_db, q, sample_rate)
+}
+
+/// Normalized biquad coefficients (b0, b1, b2, a1, a2) for one band
+fn coefficients(
+    shape: Shape,
+    freq: f32,
+    gain_db: f32,
+    q: f32,
+    sample_rate: f32,
+) -> (f32, f32, f32, f32, f32) {
+    let a = 10.0_f32.powf(gain_db / 40.0);
+    let w0 = 2.0 * PI * (freq / sample_rate).min(0.49);
+    let (sin, cos) = w0.sin_cos();
+
+    let (b0, b1, b2, a0, a1, a2) = match shape {
+        Shape::HighPass | Shape::LowPass => {
+            let alpha = sin / (2.0 * q);
+            let (b0, b1) = match shape {
+                Shape::HighPass => ((1.0 + cos) / 2.0, -(1.0 + cos)),
+                _ => ((1.0 - cos) / 2.0, 1.0 - cos),
+            };
+            (b0, b1, b0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+        }
+        Shape::Peak => {
+            let alpha = sin / (2.0 * q);
+            (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            )
+        }
+        Shape::LowShelf | Shape::HighShelf => {
+            let alpha = sin / 2.0 * ((a + 1.0 / a) * (1.0 / SHELF_SLOPE - 1.0) + 2.0).sqrt();
+            let beta = 2.0 * a.sqrt() * alpha;
+            // The high shelf is the low shelf with the cos terms negated
+            let sign = match shape {
+                Shape::LowShelf => 1.0,
+                _ => -1.0,
+            };
+            (
+                a * ((a + 1.0) - sign * (a - 1.0) * cos + beta),
+                sign * 2.0 * a * ((a - 1.0) - sign * (a + 1.0) * cos),
+                a * ((a + 1.0) - sign * (a - 1.0) * cos - beta),
+                (a + 1.0) + sign * (a - 1.0) * cos + beta,
+                -sign * 2.0 * ((a - 1.0) + sign * (a + 1.0) * cos),
+                (a + 1.0) + sign * (a - 1.0) * cos - beta,
+            )
+        }
+    };
+
+    (b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steady-state gain in dB of a sine through the EQ
+    fn gain_at(eq: &mut ChannelEq, freq: f32) -> f32 {
+        let sample_rate = 48000.0;
+        let mut samples: Vec<f32> = (0..48000)
+            .map(|i| (2.0 * PI * freq * i as f32 / sample_rate).sin())
+            .collect();
+        eq.process(&mut samples);
+        let settled = &samples[24000..];
+        20.0 * crate::audio::dsp::peak(settled).log10()
+    }
+
+    #[test]
+    fn test_flat_eq_is_transparent() {
+        let mut eq = ChannelEq::new(&EqConfig::default(), 48000.0);
+        assert!(eq.is_flat());
+        assert!(gain_at(&mut eq, 1000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_bands_boost_their_region() {
+        let settings = EqConfig {
+            low_db: 6.0,
+            high_db: -6.0,
+            ..EqConfig::default()
+        };
+        let mut eq = ChannelEq::new(&settings, 48000.0);
+        assert!((gain_at(&mut eq, 30.0) - 6.0).abs() < 0.5);
+        assert!(gain_at(&mut eq, 1000.0).abs() < 0.5);
+
+        let mut eq = ChannelEq::new(&settings, 48000.0);
+        assert!((gain_at(&mut eq, 18000.0) + 6.0).abs() < 0.5);
+
+        let mut eq = ChannelEq::new(&EqConfig::default(), 48000.0);
+        eq.set_gain(EqBand::Mid, 6.0);
+        assert!(!eq.is_flat());
+        assert!((gain_at(&mut eq, 1000.0) - 6.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_parametric_band() {
+        let settings = EqConfig {
+            bands: vec![EqBandConfig {
+                freq: 3000.0,
+                gain_db: -9.0,
+                q: 4.0,
+            }],
+            ..EqConfig::default()
+        };
+        let mut eq = ChannelEq::new(&settings, 48000.0);
+        assert!((gain_at(&mut eq, 3000.0) + 9.0).abs() < 0.1);
+        assert!(gain_at(&mut eq, 500.0).abs() < 0.5);
+
+        // Back to 0 dB switches the band off
+        eq.set_band(0, EqBandConfig::default());
+        assert!(eq.is_flat());
+    }
+
+    #[test]
+    fn test_pass_filters() {
+        let settings = EqConfig {
+            high_pass: Some(PassFilterConfig {
+                freq: 100.0,
+                enabled: true,
+            }),
+            ..EqConfig::default()
+        };
+        let mut eq = ChannelEq::new(&settings, 48000.0);
+        assert!(!eq.is_flat());
+        assert!((gain_at(&mut eq, 100.0) + 3.0).abs() < 0.1);
+        let mut eq = ChannelEq::new(&settings, 48000.0);
+        assert!(gain_at(&mut eq, 25.0) < -20.0);
+        let mut eq = ChannelEq::new(&settings, 48000.0);
+        assert!(gain_at(&mut eq, 1000.0).abs() < 0.1);
+
+        let low_pass = PassFilterConfig {
+            freq: 5000.0,
+            enabled: true,
+        };
+        let mut eq = ChannelEq::new(&EqConfig::default(), 48000.0);
+        eq.set_pass_filter(PassFilter::LowPass, Some(low_pass));
+        assert!(gain_at(&mut eq, 15000.0) < -15.0);
+
+        // Switched off, the filter keeps no processing
+        eq.set_pass_filter(
+            PassFilter::LowPass,
+            Some(PassFilterConfig {
+                enabled: false,
+                ..low_pass
+            }),
+        );
+        assert!(eq.is_flat());
+    }
+}
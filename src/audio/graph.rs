@@ -0,0 +1,440 @@
+//! Per-channel processing graph
+//!
+//! A `dsp` graph from the config is compiled once into a flat list of steps
+//! in execution order. Every node owns one buffer per channel port, all
+//! allocated up front; a step sums its sources into its buffer and processes
+//! it in place, so running the graph in the audio thread never allocates.
+//! Gate and compressor detect the loudest port, keeping stereo images intact.
+
+use anyhow::Result;
+
+use super::capacity::scratch_buffer;
+use super::eq::ChannelEq;
+use crate::config::{DspGraphConfig, DspNodeKind, DspSource};
+use crate::ipc::MeterData;
+
+/// Decay time of the gate's level detector in ms
+const GATE_DETECTOR_MS: f32 = 20.0;
+
+/// Processing done by one step
+#[derive(Debug, Clone)]
+enum Processor {
+    Gain(f32),
+    Eq(Vec<ChannelEq>),
+    Gate(Gate),
+    Comp(Compressor),
+    /// Keeps its input for the send ports
+    Send,
+}
+
+/// One node in execution order
+#[derive(Debug, Clone)]
+struct Step {
+    /// Index of the node (and its buffer)
+    node: usize,
+
+    /// Buffers summed into this node
+    sources: Vec<DspSource>,
+
+    processor: Processor,
+}
+
+/// Compiled processing graph of one input channel
+#[derive(Debug, Clone)]
+pub struct DspGraph {
+    steps: Vec<Step>,
+
+    /// Sources summed into the channel's feed to the mix
+    output_sources: Vec<DspSource>,
+
+    /// Send nodes in config order (the order their ports are registered in)
+    send_nodes: Vec<usize>,
+
+    /// Input samples per port
+    input: Vec<Vec<f32>>,
+
+    /// Output samples per node and port
+    buffers: Vec<Vec<Vec<f32>>>,
+
+    /// Feed to the mix per port
+    output: Vec<Vec<f32>>,
+}
+
+impl DspGraph {
+    /// Compile a graph for a channel with `port_count` ports
+    pub fn new(
+        config: &DspGraphConfig,
+        port_count: usize,
+        sample_rate: f32,
+        buffer_size: usize,
+    ) -> Result<Self> {
+        let schedule = config.schedule()?;
+        let steps = schedule
+            .steps
+            .into_iter()
+            .map(|(node, sources)| {
+                let processor = match &config.nodes[node].kind {
+                    DspNodeKind::Gain { gain_db } => {
+                        Processor::Gain(MeterData::db_to_linear(*gain_db))
+                    }
+                    DspNodeKind::Eq(eq) => {
+                        Processor::Eq(vec![ChannelEq::new(eq, sample_rate); port_count])
+                    }
+                    DspNodeKind::Gate {
+                        threshold_db,
+                        attack_ms,
+                        release_ms,
+                    } => Processor::Gate(Gate::new(
+                        *threshold_db,
+                        *attack_ms,
+                        *release_ms,
+                        sample_rate,
+                    )),
+                    DspNodeKind::Comp {
+                        threshold_db,
+                        ratio,
+                        attack_ms,
+                        release_ms,
+                        makeup_db,
+                    } => Processor::Comp(Compressor {
+                        threshold_db: *threshold_db,
+                        slope: 1.0 - 1.0 / ratio,
+                        makeup_db: *makeup_db,
+                        attack: smoothing(*attack_ms, sample_rate),
+                        release: smoothing(*release_ms, sample_rate),
+                        envelope: 0.0,
+                    }),
+                    DspNodeKind::Send { .. } => Processor::Send,
+                };
+                Step {
+                    node,
+                    sources,
+                    processor,
+                }
+            })
+            .collect();
+
+        let send_nodes = config
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| matches!(n.kind, DspNodeKind::Send { .. }))
+            .map(|(i, _)| i)
+            .collect();
+
+        let ports = || -> Vec<Vec<f32>> {
+            (0..port_count)
+                .map(|_| scratch_buffer(buffer_size))
+                .collect()
+        };
+        Ok(Self {
+            steps,
+            output_sources: schedule.output,
+            send_nodes,
+            input: ports(),
+            buffers: (0..config.nodes.len()).map(|_| ports()).collect(),
+            output: ports(),
+        })
+    }
+
+    /// Resize the buffers (not real-time safe beyond `MAX_BUFFER_FRAMES`)
+    pub fn set_buffer_size(&mut self, size: usize) {
+        for buffer in self
+            .input
+            .iter_mut()
+            .chain(self.buffers.iter_mut().flatten())
+            .chain(self.output.iter_mut())
+        {
+            buffer.resize(size, 0.0);
+        }
+    }
+
+    /// Input buffer of one port, to be filled before `process`
+    pub fn input_mut(&mut self, port: usize) -> &mut [f32] {
+        &mut self.input[port]
+    }
+
+    /// Run every step on the first `nframes` samples
+    pub fn process(&mut self, nframes: usize) {
+        for step in &mut self.steps {
+            // Take the node's buffer out so its sources can be read meanwhile
+            let mut buffer = std::mem::take(&mut self.buffers[step.node]);
+            sum_sources(
+                &mut buffer,
+                &step.sources,
+                &self.input,
+                &self.buffers,
+                nframes,
+            );
+
+            match &mut step.processor {
+                Processor::Gain(gain) => {
+                    for s in buffer.iter_mut().flat_map(|b| &mut b[..nframes]) {
+                        *s *= *gain;
+                    }
+                }
+                Processor::Eq(eqs) => {
+                    for (eq, samples) in eqs.iter_mut().zip(&mut buffer) {
+                        eq.process(&mut samples[..nframes]);
+                    }
+                }
+                Processor::Gate(gate) => gate.process(&mut buffer, nframes),
+                Processor::Comp(comp) => comp.process(&mut buffer, nframes),
+                Processor::Send => {}
+            }
+
+            self.buffers[step.node] = buffer;
+        }
+
+        sum_sources(
+            &mut self.output,
+            &self.output_sources,
+            &self.input,
+            &self.buffers,
+            nframes,
+        );
+    }
+
+    /// Feed to the mix of one port after `process`
+    pub fn output(&self, port: usize, nframes: usize) -> &[f32] {
+        &self.output[port][..nframes]
+    }
+
+    /// Samples for the send ports after `process`, in registration order
+    pub fn sends(&self, nframes: usize) -> impl Iterator<Item = &[f32]> {
+        self.send_nodes
+            .iter()
+            .flat_map(move |&node| self.buffers[node].iter().map(move |b| &b[..nframes]))
+    }
+}
+
+/// Overwrite `target` with the sum of `sources`
+fn sum_sources(
+    target: &mut [Vec<f32>],
+    sources: &[DspSource],
+    input: &[Vec<f32>],
+    buffers: &[Vec<Vec<f32>>],
+    nframes: usize,
+) {
+    for (port, out) in target.iter_mut().enumerate() {
+        let out = &mut out[..nframes];
+        out.fill(0.0);
+        for source in sources {
+            let samples = match source {
+                DspSource::Input => &input[port],
+                DspSource::Node(node) => &buffers[*node][port],
+            };
+            for (o, s) in out.iter_mut().zip(samples) {
+                *o += s;
+            }
+        }
+    }
+}
+
+/// One-pole smoothing coefficient for a time constant in ms
+fn smoothing(ms: f32, sample_rate: f32) -> f32 {
+    1.0 - (-1000.0 / (ms * sample_rate)).exp()
+}
+
+/// Loudest sample of any port at frame `i`
+fn linked_level(buffer: &[Vec<f32>], i: usize) -> f32 {
+    buffer.iter().fold(0.0, |level, b| level.max(b[i].abs()))
+}
+
+/// Noise gate
+#[derive(Debug, Clone)]
+struct Gate {
+    /// Opening threshold (linear)
+    threshold: f32,
+
+    /// Gain smoothing while opening
+    attack: f32,
+
+    /// Gain smoothing while closing
+    release: f32,
+
+    /// Per-sample decay of the level detector
+    detector_decay: f32,
+
+    /// Current detected level
+    level: f32,
+
+    /// Current gain (0 = closed, 1 = open)
+    gain: f32,
+}
+
+impl Gate {
+    fn new(threshold_db: f32, attack_ms: f32, release_ms: f32, sample_rate: f32) -> Self {
+        Self {
+            threshold: MeterData::db_to_linear(threshold_db),
+            attack: smoothing(attack_ms, sample_rate),
+            release: smoothing(release_ms, sample_rate),
+            detector_decay: 1.0 - smoothing(GATE_DETECTOR_MS, sample_rate),
+            level: 0.0,
+            gain: 0.0,
+        }
+    }
+
+    fn process(&mut self, buffer: &mut [Vec<f32>], nframes: usize) {
+        for i in 0..nframes {
+            self.level = linked_level(buffer, i).max(self.level * self.detector_decay);
+            let (target, coeff) = if self.level >= self.threshold {
+                (1.0, self.attack)
+            } else {
+                (0.0, self.release)
+            };
+            self.gain += (target - self.gain) * coeff;
+            for b in buffer.iter_mut() {
+                b[i] *= self.gain;
+            }
+        }
+    }
+}
+
+/// Feed-forward peak compressor
+#[derive(Debug, Clone)]
+struct Compressor {
+    threshold_db: f32,
+
+    /// Fraction of the overshoot removed (1 - 1/ratio)
+    slope: f32,
+
+    makeup_db: f32,
+
+    /// Envelope smoothing for rising levels
+    attack: f32,
+
+    /// Envelope smoothing for falling levels
+    release: f32,
+
+    /// Current envelope (linear)
+    envelope: f32,
+}
+
+impl Compressor {
+    fn process(&mut self, buffer: &mut [Vec<f32>], nframes: usize) {
+        for i in 0..nframes {
+            let level = linked_level(buffer, i);
+            let coeff = if level > self.envelope {
+                self.attack
+            } else {
+                self.release
+            };
+            self.envelope += (level - self.envelope) * coeff;
+
+            let over_db = MeterData::linear_to_db(self.envelope) - self.threshold_db;
+            let reduction_db = over_db.max(0.0) * self.slope;
+            let gain = MeterData::db_to_linear(self.makeup_db - reduction_db);
+            for b in buffer.iter_mut() {
+                b[i] *= gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DspNodeConfig;
+
+    fn node(name: &str, kind: DspNodeKind) -> DspNodeConfig {
+        DspNodeConfig {
+            name: name.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_chain_and_split() {
+        // input -> half -> output, input -> send (split) -> output
+        let config = DspGraphConfig {
+            nodes: vec![
+                node("half", DspNodeKind::Gain { gain_db: -6.0206 }),
+                node(
+                    "fx",
+                    DspNodeKind::Send {
+                        ports: vec!["fx_send".to_string()],
+                    },
+                ),
+            ],
+            edges: vec![
+                ("input".to_string(), "half".to_string()),
+                ("half".to_string(), "output".to_string()),
+                ("input".to_string(), "fx".to_string()),
+                ("fx".to_string(), "output".to_string()),
+            ],
+        };
+        let mut graph = DspGraph::new(&config, 1, 48000.0, 4).unwrap();
+        graph.input_mut(0).copy_from_slice(&[1.0, 0.5, -1.0, 0.0]);
+        graph.process(4);
+
+        let out = graph.output(0, 4);
+        assert!((out[0] - 1.5).abs() < 1e-3);
+        assert!((out[2] + 1.5).abs() < 1e-3);
+        assert_eq!(graph.sends(4).next().unwrap(), &[1.0, 0.5, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_gate_and_compressor() {
+        let config = DspGraphConfig {
+            nodes: vec![
+                node(
+                    "gate",
+                    DspNodeKind::Gate {
+                        threshold_db: -40.0,
+                        attack_ms: 1.0,
+                        release_ms: 10.0,
+                    },
+                ),
+                node(
+                    "comp",
+                    DspNodeKind::Comp {
+                        threshold_db: -20.0,
+                        ratio: 4.0,
+                        attack_ms: 1.0,
+                        release_ms: 50.0,
+                        makeup_db: 0.0,
+                    },
+                ),
+            ],
+            edges: Vec::new(),
+        };
+        let mut graph = DspGraph::new(&config, 2, 48000.0, 4800).unwrap();
+
+        // Quiet noise is gated away
+        graph.input_mut(0).fill(0.001);
+        graph.input_mut(1).fill(0.001);
+        graph.process(4800);
+        assert!(graph.output(0, 4800)[4799].abs() < 1e-5);
+
+        // A 0 dBFS signal is 20 dB over: 15 dB of reduction once settled
+        graph.input_mut(0).fill(1.0);
+        graph.input_mut(1).fill(0.0);
+        graph.process(4800);
+        let level_db = MeterData::linear_to_db(graph.output(0, 4800)[4799]);
+        assert!((level_db + 15.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_schedule_errors() {
+        let mut config = DspGraphConfig {
+            nodes: vec![
+                node("a", DspNodeKind::Gain { gain_db: 0.0 }),
+                node("b", DspNodeKind::Gain { gain_db: 0.0 }),
+            ],
+            edges: vec![
+                ("input".to_string(), "a".to_string()),
+                ("a".to_string(), "b".to_string()),
+                ("b".to_string(), "a".to_string()),
+                ("b".to_string(), "output".to_string()),
+            ],
+        };
+        assert!(config.schedule().is_err());
+
+        config.edges.remove(2);
+        assert_eq!(config.schedule().unwrap().steps.len(), 2);
+
+        config.edges.pop();
+        assert!(config.schedule().is_err());
+    }
+}
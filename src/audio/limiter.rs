@@ -0,0 +1,209 @@
+//! Lookahead brickwall limiter for output buses
+//!
+//! The bus is delayed by the lookahead time while the gain each sample needs
+//! to stay under the ceiling is known in advance. The gain follows the
+//! minimum of those over the lookahead window, recovers with the release
+//! time, and is averaged over the window again so it ramps down smoothly and
+//! reaches its target exactly when the peak leaves the delay line. The ports
+//! of a channel share one gain, keeping the stereo image in place.
+
+use crate::config::LimiterConfig;
+use crate::ipc::{MeterData, MAX_CHANNEL_PORTS};
+
+/// Limiter state for one output channel
+#[derive(Debug, Clone)]
+pub struct Limiter {
+    /// Ceiling as a linear peak
+    ceiling: f32,
+
+    /// Delay line, one frame of all ports per sample of lookahead
+    delay: Vec<[f32; MAX_CHANNEL_PORTS]>,
+
+    /// Next delay line frame to read and overwrite
+    delay_pos: usize,
+
+    /// Samples in the gain window (lookahead plus the current sample)
+    window: usize,
+
+    /// Sample counter for the sliding minimum
+    sample: u64,
+
+    /// Sliding minimum of the required gain: (sample, gain) in increasing
+    /// gain order, stored as a ring of `window` entries
+    min_queue: Vec<(u64, f32)>,
+
+    /// First entry of `min_queue`
+    min_head: usize,
+
+    /// Entries in `min_queue`
+    min_len: usize,
+
+    /// Gain after the release smoothing
+    envelope: f32,
+
+    /// Release smoothing coefficient per sample
+    release_coef: f32,
+
+    /// Last `window` envelope values for the moving average
+    average: Vec<f32>,
+
+    /// Next slot of `average` to overwrite
+    average_pos: usize,
+
+    /// Sum of `average`
+    average_sum: f64,
+
+    /// Lowest gain applied since the last `take_reduction_db`
+    min_gain: f32,
+
+    /// Highest input peak since the last `take_input_peak`
+    input_peak: f32,
+}
+
+impl Limiter {
+    /// Create a limiter from its config at the given sample rate
+    pub fn new(config: &LimiterConfig, sample_rate: f32) -> Self {
+        let lookahead = ((config.lookahead_ms / 1000.0 * sample_rate).round() as usize).max(1);
+        let window = lookahead + 1;
+        Self {
+            ceiling: MeterData::db_to_linear(config.ceiling_db),
+            delay: vec![[0.0; MAX_CHANNEL_PORTS]; lookahead],
+            delay_pos: 0,
+            window,
+            sample: 0,
+            min_queue: vec![(0, 1.0); window],
+            min_head: 0,
+            min_len: 0,
+            envelope: 1.0,
+            release_coef: 1.0 - (-1.0 / (config.release_ms / 1000.0 * sample_rate)).exp(),
+            average: vec![1.0; window],
+            average_pos: 0,
+            average_sum: window as f64,
+            min_gain: 1.0,
+            input_peak: 0.0,
+        }
+    }
+
+    /// Gain reduction in dB since the last call (0 when nothing was limited)
+    pub fn take_reduction_db(&mut self) -> f32 {
+        let reduction = -20.0 * self.min_gain.log10();
+        self.min_gain = 1.0;
+        reduction.max(0.0)
+    }
+
+    /// Highest peak that reached the limiter since the last call
+    pub fn take_input_peak(&mut self) -> f32 {
+        std::mem::take(&mut self.input_peak)
+    }
+
+    /// Limit the ports of a channel in place (all slices the same length)
+    pub fn process(&mut self, ports: &mut [&mut [f32]]) {
+        let count = ports.len().min(MAX_CHANNEL_PORTS);
+        let ports = &mut ports[..count];
+        let nframes = ports.first().map_or(0, |p| p.len());
+
+        for i in 0..nframes {
+            let peak = ports.iter().fold(0.0f32, |a, p| a.max(p[i].abs()));
+            self.input_peak = self.input_peak.max(peak);
+            let required = if peak > self.ceiling {
+                self.ceiling / peak
+            } else {
+                1.0
+            };
+
+            let target = self.window_min(required);
+            self.envelope = if target < self.envelope {
+                target
+            } else {
+                self.envelope + (target - self.envelope) * self.release_coef
+            };
+
+            self.average_sum += f64::from(self.envelope - self.average[self.average_pos]);
+            self.average[self.average_pos] = self.envelope;
+            self.average_pos = (self.average_pos + 1) % self.window;
+            let gain = ((self.average_sum / self.window as f64) as f32).min(1.0);
+            self.min_gain = self.min_gain.min(gain);
+
+            let frame = &mut self.delay[self.delay_pos];
+            for (port, delayed) in ports.iter_mut().zip(frame.iter_mut()) {
+                let input = port[i];
+                port[i] = *delayed * gain;
+                *delayed = input;
+            }
+            self.delay_pos = (self.delay_pos + 1) % self.delay.len();
+        }
+
+        // Recompute the running sum now and then so rounding cannot drift
+        self.average_sum = self.average.iter().map(|&g| f64::from(g)).sum();
+    }
+
+    /// Add a required gain and return the minimum over the window
+    fn window_min(&mut self, required: f32) -> f32 {
+        let len = self.min_queue.len();
+
+        // Entries not below the new one can never be the minimum again
+        while self.min_len > 0 {
+            let back = (self.min_head + self.min_len - 1) % len;
+            if self.min_queue[back].1 < required {
+                break;
+            }
+            self.min_len -= 1;
+        }
+        self.min_queue[(self.min_head + self.min_len) % len] = (self.sample, required);
+        self.min_len += 1;
+
+        // Drop the front once it is older than the window
+        let oldest = (self.sample + 1).saturating_sub(self.window as u64);
+        if self.min_queue[self.min_head].0 < oldest {
+            self.min_head = (self.min_head + 1) % len;
+            self.min_len -= 1;
+        }
+
+        self.sample += 1;
+        self.min_queue[self.min_head].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LimiterConfig {
+        LimiterConfig {
+            ceiling_db: -6.0,
+            lookahead_ms: 5.0,
+            release_ms: 50.0,
+        }
+    }
+
+    #[test]
+    fn test_spike_never_passes_the_ceiling() {
+        // 1 kHz sample rate: 5 samples of lookahead
+        let mut limiter = Limiter::new(&config(), 1000.0);
+        let ceiling = MeterData::db_to_linear(-6.0);
+
+        let mut left: Vec<f32> = (0..200).map(|i| if i == 50 { 2.0 } else { 0.25 }).collect();
+        let mut right = vec![0.1f32; 200];
+        limiter.process(&mut [&mut left, &mut right]);
+
+        assert!(left.iter().all(|s| s.abs() <= ceiling * 1.0001));
+        // The spike comes out delayed by the lookahead, exactly at the ceiling
+        assert!((left[55] - ceiling).abs() < 1e-4);
+        // Both ports are reduced together
+        assert!(right[55] < 0.1 * ceiling);
+        assert!(limiter.take_reduction_db() > 11.0);
+        assert_eq!(limiter.take_input_peak(), 2.0);
+    }
+
+    #[test]
+    fn test_quiet_signal_is_only_delayed() {
+        let mut limiter = Limiter::new(&config(), 1000.0);
+        let input: Vec<f32> = (0..100).map(|i| (i as f32 * 0.1).sin() * 0.4).collect();
+        let mut output = input.clone();
+        limiter.process(&mut [&mut output]);
+
+        assert!(output[..5].iter().all(|&s| s == 0.0));
+        assert_eq!(&output[5..], &input[..95]);
+        assert_eq!(limiter.take_reduction_db(), 0.0);
+    }
+}
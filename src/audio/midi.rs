@@ -0,0 +1,85 @@
+//! MIDI program change handling
+//!
+//! Decodes raw MIDI from the control port in the audio thread, tracking
+//! bank select (CC 0) per channel so program changes can be mapped to
+//! scenes by bank and program number.
+
+use crate::ipc::ProgramChange;
+
+/// Bank select MSB controller number
+const CC_BANK_SELECT: u8 = 0;
+
+/// Decoder for program change messages
+#[derive(Debug, Clone)]
+pub struct ProgramChangeDecoder {
+    /// Last bank select value per MIDI channel
+    banks: [u8; 16],
+
+    /// Only accept messages on this channel (0-15), if set
+    channel: Option<u8>,
+}
+
+impl ProgramChangeDecoder {
+    /// Create a decoder, optionally limited to one MIDI channel (0-15)
+    pub fn new(channel: Option<u8>) -> Self {
+        Self {
+            banks: [0; 16],
+            channel,
+        }
+    }
+
+    /// Feed one raw MIDI message, returning a program change if it is one
+    pub fn decode(&mut self, bytes: &[u8]) -> Option<ProgramChange> {
+        let (&status, data) = bytes.split_first()?;
+        let channel = status & 0x0f;
+        if self.channel.is_some_and(|c| c != channel) {
+            return None;
+        }
+
+        match (status & 0xf0, data) {
+            (0xb0, [CC_BANK_SELECT, value, ..]) => {
+                self.banks[channel as usize] = *value;
+                None
+            }
+            (0xc0, [program, ..]) => Some(ProgramChange {
+                channel,
+                bank: self.banks[channel as usize],
+                program: *program,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_change_with_bank() {
+        let mut decoder = ProgramChangeDecoder::new(None);
+        assert_eq!(
+            decoder.decode(&[0xc0, 5]),
+            Some(ProgramChange {
+                channel: 0,
+                bank: 0,
+                program: 5
+            })
+        );
+
+        // Bank select on channel 3 only affects that channel
+        assert_eq!(decoder.decode(&[0xb2, 0, 1]), None);
+        assert_eq!(decoder.decode(&[0xc2, 7]).map(|pc| pc.bank), Some(1));
+        assert_eq!(decoder.decode(&[0xc0, 7]).map(|pc| pc.bank), Some(0));
+
+        // Other messages are ignored
+        assert_eq!(decoder.decode(&[0x90, 60, 100]), None);
+    }
+
+    #[test]
+    fn test_channel_filter() {
+        let mut decoder = ProgramChangeDecoder::new(Some(9));
+        assert_eq!(decoder.decode(&[0xc0, 1]), None);
+        assert_eq!(decoder.decode(&[0xc9, 1]).map(|pc| pc.program), Some(1));
+    }
+}
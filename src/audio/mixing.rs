@@ -0,0 +1,143 @@
+//! Output bus mixing strategies
+//!
+//! Decides, once per process cycle, which inputs take part in each output's
+//! mix: all of them (sum), only the loudest (max-select) or only the
+//! priority inputs while one of them is active (priority ducking).
+
+use crate::config::{ChannelConfig, MixMode};
+use crate::ipc::MeterData;
+
+/// Default activity threshold for priority inputs (dBFS, post-fader)
+pub const DEFAULT_PRIORITY_THRESHOLD_DB: f32 = -40.0;
+
+/// How long priority inputs keep other inputs ducked after going quiet
+const PRIORITY_HOLD_SECS: f32 = 0.5;
+
+/// Per-output mixing strategy with its real-time state
+#[derive(Debug, Clone)]
+pub enum MixStrategy {
+    /// Every input is summed
+    Sum,
+
+    /// Only the loudest input passes
+    Max { selected: Option<usize> },
+
+    /// Priority inputs replace all others while active
+    Priority {
+        /// Whether each input is a priority input
+        priority: Vec<bool>,
+
+        /// Activity threshold (linear, post-fader peak)
+        threshold: f32,
+
+        /// Hold time in samples
+        hold_samples: usize,
+
+        /// Remaining hold time in samples (> 0 while ducking)
+        hold_remaining: usize,
+    },
+}
+
+impl MixStrategy {
+    /// Build the strategy for an output channel
+    pub fn from_config(output: &ChannelConfig, inputs: &[ChannelConfig], sample_rate: f32) -> Self {
+        match output.mix_mode {
+            MixMode::Sum => MixStrategy::Sum,
+            MixMode::Max => MixStrategy::Max { selected: None },
+            MixMode::Priority => MixStrategy::Priority {
+                priority: inputs
+                    .iter()
+                    .map(|i| output.priority_inputs.contains(&i.name))
+                    .collect(),
+                threshold: MeterData::db_to_linear(
+                    output
+                        .priority_threshold_db
+                        .unwrap_or(DEFAULT_PRIORITY_THRESHOLD_DB),
+                ),
+                hold_samples: (PRIORITY_HOLD_SECS * sample_rate) as usize,
+                hold_remaining: 0,
+            },
+        }
+    }
+
+    /// Update the decision from the post-fader peak level of every input
+    pub fn update(&mut self, input_levels: &[f32], nframes: usize) {
+        match self {
+            MixStrategy::Sum => {}
+            MixStrategy::Max { selected } => {
+                *selected = input_levels
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, level)| **level > 0.0)
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map(|(i, _)| i);
+            }
+            MixStrategy::Priority {
+                priority,
+                threshold,
+                hold_samples,
+                hold_remaining,
+            } => {
+                let active = input_levels
+                    .iter()
+                    .zip(priority.iter())
+                    .any(|(level, is_priority)| *is_priority && *level >= *threshold);
+                if active {
+                    *hold_remaining = *hold_samples;
+                } else {
+                    *hold_remaining = hold_remaining.saturating_sub(nframes);
+                }
+            }
+        }
+    }
+
+    /// Whether an input takes part in this output's mix
+    pub fn accepts(&self, input: usize) -> bool {
+        match self {
+            MixStrategy::Sum => true,
+            MixStrategy::Max { selected } => *selected == Some(input),
+            MixStrategy::Priority {
+                priority,
+                hold_remaining,
+                ..
+            } => *hold_remaining == 0 || priority.get(input).copied().unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_selects_loudest_input() {
+        let mut mix = MixStrategy::Max { selected: None };
+        mix.update(&[0.1, 0.5, 0.2], 256);
+        assert!(!mix.accepts(0));
+        assert!(mix.accepts(1));
+        assert!(!mix.accepts(2));
+    }
+
+    #[test]
+    fn test_priority_ducks_others_with_hold() {
+        let mut mix = MixStrategy::Priority {
+            priority: vec![true, false],
+            threshold: 0.1,
+            hold_samples: 512,
+            hold_remaining: 0,
+        };
+
+        mix.update(&[0.0, 0.5], 256);
+        assert!(mix.accepts(1));
+
+        mix.update(&[0.5, 0.5], 256);
+        assert!(mix.accepts(0));
+        assert!(!mix.accepts(1));
+
+        // Still ducked during the hold time, released afterwards
+        mix.update(&[0.0, 0.5], 256);
+        assert!(!mix.accepts(1));
+        mix.update(&[0.0, 0.5], 256);
+        assert!(mix.accepts(1));
+    }
+}
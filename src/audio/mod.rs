@@ -3,6 +3,33 @@
 //! Handles Pipewire integration including client registration,
 //! port creation, and real-time audio processing.
 
+mod autoconnect;
+mod automix;
+mod autotrim;
+mod capacity;
+mod click;
+mod deesser;
+mod delay;
+mod dsp;
 mod engine;
+mod eq;
+mod graph;
+mod limiter;
+mod midi;
+mod multiband;
+mod mixing;
+mod noise;
+mod notch;
+mod protect;
+mod recorder;
+mod replay;
+mod silence;
+mod softclip;
+mod watchdog;
 
+pub use autoconnect::{local_port_for_entry, Side, Substitution};
+pub use dsp::{balance_gains, mean_square_to_lufs, Biquad};
 pub use engine::AudioEngine;
+pub use recorder::export_recording;
+pub use silence::{PortStatus, SILENT_PORT_TIMEOUT};
+pub use watchdog::EngineHealth;
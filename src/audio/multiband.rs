@@ -0,0 +1,294 @@
+//! Three-band compressor for output buses
+//!
+//! Linkwitz-Riley crossovers (24 dB/octave) split every port into a low, a
+//! mid and a high band. The low band also runs through an all-pass matching
+//! the upper crossover, so without compression the bands sum back to a flat
+//! response. Each band has its own envelope and gain; the ports of a channel
+//! share them so stereo images stay in place.
+
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+use super::dsp::Biquad;
+use crate::config::{MultibandConfig, MULTIBAND_BANDS};
+use crate::ipc::{MeterData, MAX_CHANNEL_PORTS};
+
+/// Linkwitz-Riley crossover: two cascaded Butterworth sections per side
+#[derive(Debug, Clone, Copy)]
+struct Crossover {
+    low: [Biquad; 2],
+    high: [Biquad; 2],
+}
+
+impl Crossover {
+    fn new(freq: f32, sample_rate: f32) -> Self {
+        let (b0, b1, b2, a1, a2) = butterworth(freq, sample_rate, false);
+        let low = Biquad::new(b0, b1, b2, a1, a2);
+        let (b0, b1, b2, a1, a2) = butterworth(freq, sample_rate, true);
+        let high = Biquad::new(b0, b1, b2, a1, a2);
+        Self {
+            low: [low; 2],
+            high: [high; 2],
+        }
+    }
+
+    /// Move the crossover, keeping the filter state
+    fn set_freq(&mut self, freq: f32, sample_rate: f32) {
+        for (filters, high) in [(&mut self.low, false), (&mut self.high, true)] {
+            let (b0, b1, b2, a1, a2) = butterworth(freq, sample_rate, high);
+            for filter in filters {
+                filter.set_coefficients(b0, b1, b2, a1, a2);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        for filter in self.low.iter_mut().chain(&mut self.high) {
+            filter.reset();
+        }
+    }
+
+    /// Split a sample into its low and high part
+    #[inline]
+    fn split(&mut self, x: f32) -> (f32, f32) {
+        let low = self.low.iter_mut().fold(x, |s, f| f.process(s));
+        let high = self.high.iter_mut().fold(x, |s, f| f.process(s));
+        (low, high)
+    }
+}
+
+/// Normalized Butterworth low- or high-pass coefficients (RBJ)
+fn butterworth(freq: f32, sample_rate: f32, high: bool) -> (f32, f32, f32, f32, f32) {
+    let w0 = 2.0 * PI * (freq / sample_rate).min(0.49);
+    let (sin, cos) = w0.sin_cos();
+    let alpha = sin / (2.0 * FRAC_1_SQRT_2);
+    let a0 = 1.0 + alpha;
+    let (b0, b1) = if high {
+        ((1.0 + cos) / 2.0, -(1.0 + cos))
+    } else {
+        ((1.0 - cos) / 2.0, 1.0 - cos)
+    };
+    (
+        b0 / a0,
+        b1 / a0,
+        b0 / a0,
+        -2.0 * cos / a0,
+        (1.0 - alpha) / a0,
+    )
+}
+
+/// Crossovers of one port
+#[derive(Debug, Clone, Copy)]
+struct PortSplit {
+    /// Low band versus the rest
+    lower: Crossover,
+
+    /// Mid band versus high band
+    upper: Crossover,
+
+    /// Phase match of the low band to the upper crossover
+    allpass: Crossover,
+}
+
+impl PortSplit {
+    fn new(crossovers_hz: [f32; 2], sample_rate: f32) -> Self {
+        Self {
+            lower: Crossover::new(crossovers_hz[0], sample_rate),
+            upper: Crossover::new(crossovers_hz[1], sample_rate),
+            allpass: Crossover::new(crossovers_hz[1], sample_rate),
+        }
+    }
+
+    /// Low, mid and high band of a sample
+    #[inline]
+    fn split(&mut self, x: f32) -> [f32; MULTIBAND_BANDS] {
+        let (low, rest) = self.lower.split(x);
+        let (mid, high) = self.upper.split(rest);
+        let (low_low, low_high) = self.allpass.split(low);
+        [low_low + low_high, mid, high]
+    }
+}
+
+/// Multiband compressor state for one output channel
+///
+/// Holds no heap memory, so it can be created on the real-time thread.
+#[derive(Debug, Clone)]
+pub struct Multiband {
+    enabled: bool,
+
+    sample_rate: f32,
+
+    /// Crossovers in Hz, to skip recomputing unchanged coefficients
+    crossovers_hz: [f32; 2],
+
+    /// Crossover filters per port
+    ports: [PortSplit; MAX_CHANNEL_PORTS],
+
+    threshold_db: [f32; MULTIBAND_BANDS],
+
+    /// Fraction of the overshoot removed per band (1 - 1/ratio)
+    slope: [f32; MULTIBAND_BANDS],
+
+    makeup_db: [f32; MULTIBAND_BANDS],
+
+    /// Envelope smoothing for rising levels
+    attack: f32,
+
+    /// Envelope smoothing for falling levels
+    release: f32,
+
+    /// Current band envelopes (linear)
+    envelopes: [f32; MULTIBAND_BANDS],
+
+    /// Most reduction per band since the last `take_reduction_db`
+    max_applied_db: [f32; MULTIBAND_BANDS],
+}
+
+impl Multiband {
+    /// Create a compressor from its config at the given sample rate
+    pub fn new(config: &MultibandConfig, sample_rate: f32) -> Self {
+        let mut multiband = Self {
+            enabled: config.enabled,
+            sample_rate,
+            crossovers_hz: config.crossovers_hz,
+            ports: [PortSplit::new(config.crossovers_hz, sample_rate); MAX_CHANNEL_PORTS],
+            threshold_db: [0.0; MULTIBAND_BANDS],
+            slope: [0.0; MULTIBAND_BANDS],
+            makeup_db: [0.0; MULTIBAND_BANDS],
+            attack: 0.0,
+            release: 0.0,
+            envelopes: [0.0; MULTIBAND_BANDS],
+            max_applied_db: [0.0; MULTIBAND_BANDS],
+        };
+        multiband.configure(config);
+        multiband
+    }
+
+    /// Apply new settings without interrupting the signal
+    pub fn configure(&mut self, config: &MultibandConfig) {
+        if config.enabled && !self.enabled {
+            // Stale filter state from before the bypass would click
+            for split in &mut self.ports {
+                split.lower.reset();
+                split.upper.reset();
+                split.allpass.reset();
+            }
+            self.envelopes = [0.0; MULTIBAND_BANDS];
+        }
+        self.enabled = config.enabled;
+
+        if config.crossovers_hz != self.crossovers_hz {
+            self.crossovers_hz = config.crossovers_hz;
+            let [low, high] = config.crossovers_hz;
+            for split in &mut self.ports {
+                split.lower.set_freq(low, self.sample_rate);
+                split.upper.set_freq(high, self.sample_rate);
+                split.allpass.set_freq(high, self.sample_rate);
+            }
+        }
+
+        for (band, settings) in config.bands.iter().enumerate() {
+            self.threshold_db[band] = settings.threshold_db;
+            self.slope[band] = 1.0 - 1.0 / settings.ratio.max(1.0);
+            self.makeup_db[band] = settings.makeup_db;
+        }
+        let smoothing = |ms: f32| 1.0 - (-1000.0 / (ms * self.sample_rate)).exp();
+        self.attack = smoothing(config.attack_ms);
+        self.release = smoothing(config.release_ms);
+    }
+
+    /// Most reduction per band in dB since the last call (0 when idle)
+    pub fn take_reduction_db(&mut self) -> [f32; MULTIBAND_BANDS] {
+        std::mem::take(&mut self.max_applied_db)
+    }
+
+    /// Compress the ports of a channel in place (all slices the same length)
+    pub fn process(&mut self, ports: &mut [&mut [f32]]) {
+        if !self.enabled {
+            return;
+        }
+        let count = ports.len().min(MAX_CHANNEL_PORTS);
+        let ports = &mut ports[..count];
+        let nframes = ports.first().map_or(0, |p| p.len());
+
+        for i in 0..nframes {
+            let mut bands = [[0.0f32; MULTIBAND_BANDS]; MAX_CHANNEL_PORTS];
+            let mut levels = [0.0f32; MULTIBAND_BANDS];
+            for ((port, split), port_bands) in ports.iter().zip(&mut self.ports).zip(&mut bands) {
+                *port_bands = split.split(port[i]);
+                for (level, sample) in levels.iter_mut().zip(port_bands.iter()) {
+                    *level = level.max(sample.abs());
+                }
+            }
+
+            let mut gains = [1.0f32; MULTIBAND_BANDS];
+            for (band, gain) in gains.iter_mut().enumerate() {
+                let envelope = &mut self.envelopes[band];
+                let coeff = if levels[band] > *envelope {
+                    self.attack
+                } else {
+                    self.release
+                };
+                *envelope += (levels[band] - *envelope) * coeff;
+
+                let over_db = MeterData::linear_to_db(*envelope) - self.threshold_db[band];
+                let reduction_db = over_db.max(0.0) * self.slope[band];
+                self.max_applied_db[band] = self.max_applied_db[band].max(reduction_db);
+                *gain = MeterData::db_to_linear(self.makeup_db[band] - reduction_db);
+            }
+
+            for (port, port_bands) in ports.iter_mut().zip(&bands) {
+                port[i] = port_bands.iter().zip(&gains).map(|(s, g)| s * g).sum();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MultibandBandConfig;
+
+    /// Peak of the second half of a sine at `freq` through the compressor
+    fn settled_peak(multiband: &mut Multiband, freq: f32, amplitude: f32) -> f32 {
+        let mut samples: Vec<f32> = (0..9600)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / 48000.0).sin())
+            .collect();
+        multiband.process(&mut [&mut samples]);
+        crate::audio::dsp::peak(&samples[4800..])
+    }
+
+    #[test]
+    fn test_bands_sum_flat_below_threshold() {
+        let config = MultibandConfig {
+            bands: [MultibandBandConfig {
+                threshold_db: 0.0,
+                ..Default::default()
+            }; MULTIBAND_BANDS],
+            ..Default::default()
+        };
+        for freq in [60.0, 200.0, 1000.0, 3000.0, 10000.0] {
+            let mut multiband = Multiband::new(&config, 48000.0);
+            let peak = settled_peak(&mut multiband, freq, 0.25);
+            assert!((peak - 0.25).abs() < 0.005, "{} Hz: {}", freq, peak);
+            assert_eq!(multiband.take_reduction_db(), [0.0; MULTIBAND_BANDS]);
+        }
+    }
+
+    #[test]
+    fn test_only_the_loud_band_is_compressed() {
+        let mut multiband = Multiband::new(&MultibandConfig::default(), 48000.0);
+        // -6 dBFS at 80 Hz: 14 dB over the -20 dB threshold, 2:1
+        let peak = settled_peak(&mut multiband, 80.0, 0.5);
+        let [low, mid, high] = multiband.take_reduction_db();
+        assert!((6.0..8.0).contains(&low), "{}", low);
+        assert!(mid < 1.0 && high == 0.0, "{} {}", mid, high);
+        assert!(MeterData::linear_to_db(peak / 0.5) < -5.0);
+
+        // Bypassed: untouched
+        multiband.configure(&MultibandConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        assert!((settled_peak(&mut multiband, 80.0, 0.5) - 0.5).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,78 @@
+//! Pink noise generator for speaker calibration
+//!
+//! White noise from a xorshift generator goes through Paul Kellett's
+//! refined filter, which tilts it by -3 dB per octave within about 0.05 dB
+//! across the audio band. Pink noise carries equal power per octave, so a
+//! measurement microphone picks up a level that does not depend on where in
+//! the spectrum a speaker is loud.
+
+/// Gain that brings the filter output to an RMS of 1.0
+const NORMALIZE: f32 = 0.566;
+
+/// Pink noise source with a fixed seed
+#[derive(Debug, Clone)]
+pub struct PinkNoise {
+    /// Xorshift state (never zero)
+    state: u32,
+
+    /// Filter stages
+    b: [f32; 7],
+}
+
+impl PinkNoise {
+    /// Create a generator
+    pub fn new() -> Self {
+        Self {
+            state: 0x2545_f491,
+            b: [0.0; 7],
+        }
+    }
+
+    /// Next sample with an RMS of 1.0
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        let white = self.state as f32 / 2_147_483_648.0 - 1.0;
+
+        let b = &mut self.b;
+        b[0] = 0.99886 * b[0] + white * 0.055_517_9;
+        b[1] = 0.99332 * b[1] + white * 0.075_075_9;
+        b[2] = 0.96900 * b[2] + white * 0.153_852;
+        b[3] = 0.86650 * b[3] + white * 0.310_485_6;
+        b[4] = 0.55000 * b[4] + white * 0.532_952_2;
+        b[5] = -0.7616 * b[5] - white * 0.016_898;
+        let pink = b[..6].iter().sum::<f32>() + b[6] + white * 0.5362;
+        b[6] = white * 0.115_926;
+        pink * NORMALIZE
+    }
+
+    /// Fill `samples` with noise at an RMS of `gain` (linear)
+    pub fn fill(&mut self, samples: &mut [f32], gain: f32) {
+        for s in samples.iter_mut() {
+            *s = self.next() * gain;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::dsp::{peak, rms};
+    use super::*;
+
+    #[test]
+    fn test_noise_level_and_tilt() {
+        let mut noise = PinkNoise::new();
+        let mut samples = vec![0.0; 480_000];
+        noise.fill(&mut samples, 0.1);
+        assert!((rms(&samples) - 0.1).abs() < 0.005, "{}", rms(&samples));
+        assert!(peak(&samples) < 0.6);
+
+        // Pink noise changes slowly: neighbours are strongly correlated,
+        // unlike white noise
+        let correlation: f32 = samples.windows(2).map(|w| w[0] * w[1]).sum::<f32>()
+            / samples.iter().map(|s| s * s).sum::<f32>();
+        assert!(correlation > 0.5, "{}", correlation);
+    }
+}
@@ -0,0 +1,106 @@
+//! Feedback notches
+//!
+//! Narrow, deep cuts placed on an output bus at frequencies that ring, as a
+//! quick fix during a show until the system is retuned. An output has
+//! `MAX_NOTCHES` slots with their filters allocated up front, so notches can
+//! be set and cleared from the audio thread; a cleared slot starts from
+//! silence the next time it is used.
+
+use super::dsp::Biquad;
+use super::eq::peak_coefficients;
+use crate::ipc::{MAX_CHANNEL_PORTS, MAX_NOTCHES};
+
+/// Depth of a notch in dB
+const DEPTH_DB: f32 = -15.0;
+
+/// Quality factor of a notch (about a sixth of an octave wide)
+const Q: f32 = 8.0;
+
+/// Notch filters of one output channel
+#[derive(Debug, Clone)]
+pub struct OutputNotches {
+    sample_rate: f32,
+
+    /// Filters per slot and port
+    filters: [[Biquad; MAX_CHANNEL_PORTS]; MAX_NOTCHES],
+
+    /// Whether each slot holds a notch
+    active: [bool; MAX_NOTCHES],
+}
+
+impl OutputNotches {
+    /// Create an output without notches at the given sample rate
+    pub fn new(sample_rate: f32) -> Self {
+        let flat = Biquad::new(1.0, 0.0, 0.0, 0.0, 0.0);
+        Self {
+            sample_rate,
+            filters: [[flat; MAX_CHANNEL_PORTS]; MAX_NOTCHES],
+            active: [false; MAX_NOTCHES],
+        }
+    }
+
+    /// Place a notch in `slot` at `freq_hz`, or clear it (None)
+    pub fn set(&mut self, slot: usize, freq_hz: Option<f32>) {
+        let Some(filters) = self.filters.get_mut(slot) else {
+            return;
+        };
+        match freq_hz {
+            Some(freq) => {
+                let (b0, b1, b2, a1, a2) = peak_coefficients(freq, DEPTH_DB, Q, self.sample_rate);
+                for filter in filters.iter_mut() {
+                    filter.set_coefficients(b0, b1, b2, a1, a2);
+                }
+            }
+            None => filters.iter_mut().for_each(Biquad::reset),
+        }
+        self.active[slot] = freq_hz.is_some();
+    }
+
+    /// Whether any slot holds a notch
+    pub fn is_active(&self) -> bool {
+        self.active.contains(&true)
+    }
+
+    /// Filter the ports of a channel in place
+    pub fn process(&mut self, ports: &mut [&mut [f32]]) {
+        for (filters, _) in self.filters.iter_mut().zip(self.active).filter(|(_, a)| *a) {
+            for (port, filter) in ports.iter_mut().zip(filters.iter_mut()) {
+                for s in port.iter_mut() {
+                    *s = filter.process(*s);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    /// Peak of a sine at `freq` after settling through the notches
+    fn level_at(notches: &mut OutputNotches, freq: f32) -> f32 {
+        let mut samples: Vec<f32> = (0..48000)
+            .map(|i| (TAU * freq * i as f32 / 48000.0).sin())
+            .collect();
+        notches.process(&mut [&mut samples]);
+        crate::audio::dsp::peak(&samples[24000..])
+    }
+
+    #[test]
+    fn test_notch_cuts_only_its_frequency() {
+        let mut notches = OutputNotches::new(48000.0);
+        assert!(!notches.is_active());
+        assert!((level_at(&mut notches, 2500.0) - 1.0).abs() < 0.01);
+
+        notches.set(1, Some(2500.0));
+        assert!(notches.is_active());
+        assert!(level_at(&mut notches, 2500.0) < 0.2);
+        assert!(level_at(&mut notches, 1000.0) > 0.95);
+        assert!(level_at(&mut notches, 5000.0) > 0.95);
+
+        notches.set(1, None);
+        assert!(!notches.is_active());
+        assert!((level_at(&mut notches, 2500.0) - 1.0).abs() < 0.01);
+    }
+}
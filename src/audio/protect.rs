@@ -0,0 +1,153 @@
+//! Protective dip for loud events
+//!
+//! A microphone dropped on the stage or a feedback squeal reaches the PA
+//! and the performers' ears within a block. An input with `protect` watches
+//! its trimmed peak against its recent level: a peak above the threshold
+//! that jumps well over what the channel has been doing turns the whole
+//! channel down by the dip at once. The dip holds while the channel stays
+//! above the threshold and for the hold time after, then recovers quickly.
+//! The recent level is frozen during a dip, so a squeal that keeps ringing
+//! cannot become the new normal and come back at full level.
+
+use crate::config::ProtectConfig;
+use crate::ipc::MeterData;
+
+/// Time constant in ms of the recent level a peak is compared against
+const LEVEL_MS: f32 = 1000.0;
+
+/// Loud event detector and dip for one input channel
+#[derive(Debug, Clone)]
+pub struct Protector {
+    /// Peak (linear) at or above which an event can fire
+    threshold: f32,
+
+    /// Least ratio of the peak to the recent RMS level (linear)
+    rise: f32,
+
+    /// Depth of the dip in dB
+    dip_db: f32,
+
+    /// Hold after the last loud block in samples
+    hold: usize,
+
+    /// Recovery in dB per sample
+    recovery: f32,
+
+    /// Level smoothing time constant in samples
+    smoothing: f32,
+
+    /// Recent mean square of the channel
+    level: f32,
+
+    /// Samples left of the hold
+    holding: usize,
+
+    /// Current reduction in dB (0 when idle)
+    reduction_db: f32,
+}
+
+impl Protector {
+    /// Create a protector from its config at the given sample rate
+    pub fn new(config: &ProtectConfig, sample_rate: f32) -> Self {
+        let samples = |ms: f32| ms / 1000.0 * sample_rate;
+        Self {
+            threshold: MeterData::db_to_linear(config.threshold_db),
+            rise: MeterData::db_to_linear(config.rise_db),
+            dip_db: config.dip_db,
+            hold: samples(config.hold_ms) as usize,
+            recovery: config.dip_db / samples(config.release_ms).max(1.0),
+            smoothing: samples(LEVEL_MS),
+            level: 0.0,
+            holding: 0,
+            reduction_db: 0.0,
+        }
+    }
+
+    /// Feed the peak and mean square of a block of `nframes` and return the
+    /// gain ramp over it
+    pub fn process(&mut self, peak: f32, mean_square: f32, nframes: usize) -> (f32, f32) {
+        let from = self.reduction_db;
+        let dipping = self.reduction_db > 0.0;
+        let loud = peak >= self.threshold;
+        let sudden = peak >= self.rise * self.level.sqrt();
+
+        if loud && (dipping || sudden) {
+            // Down at once: the event is already in this block
+            self.reduction_db = self.dip_db;
+            self.holding = self.hold;
+            let gain = MeterData::db_to_linear(-self.dip_db);
+            return (gain, gain);
+        }
+
+        if self.holding > 0 {
+            self.holding = self.holding.saturating_sub(nframes);
+        } else if dipping {
+            self.reduction_db = (self.reduction_db - self.recovery * nframes as f32).max(0.0);
+        } else {
+            let keep = (-(nframes as f32) / self.smoothing).exp();
+            self.level = mean_square + (self.level - mean_square) * keep;
+        }
+        let gain = |db: f32| MeterData::db_to_linear(-db);
+        (gain(from), gain(self.reduction_db))
+    }
+
+    /// Current reduction in dB (0 when idle)
+    pub fn reduction_db(&self) -> f32 {
+        self.reduction_db
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ProtectConfig {
+        ProtectConfig {
+            threshold_db: -6.0,
+            rise_db: 20.0,
+            dip_db: 20.0,
+            hold_ms: 100.0,
+            release_ms: 200.0,
+        }
+    }
+
+    /// Feed `blocks` 256-frame blocks of a sine with the given peak
+    fn run(protector: &mut Protector, peak: f32, blocks: usize) {
+        for _ in 0..blocks {
+            protector.process(peak, peak * peak / 2.0, 256);
+        }
+    }
+
+    #[test]
+    fn test_sudden_peak_dips_and_recovers() {
+        let mut protector = Protector::new(&config(), 48000.0);
+
+        // Speech around -30 dBFS settles the recent level
+        run(&mut protector, 0.03, 400);
+        assert_eq!(protector.reduction_db(), 0.0);
+
+        // A mic drop dips at once
+        let (from, to) = protector.process(1.0, 0.5, 256);
+        assert!((from - 0.1).abs() < 1e-4 && (to - 0.1).abs() < 1e-4);
+
+        // A squeal that keeps ringing keeps it down
+        run(&mut protector, 0.9, 400);
+        assert_eq!(protector.reduction_db(), 20.0);
+
+        // Held, then back within the release
+        run(&mut protector, 0.03, 18);
+        assert_eq!(protector.reduction_db(), 20.0);
+        run(&mut protector, 0.03, 40);
+        assert_eq!(protector.reduction_db(), 0.0);
+    }
+
+    #[test]
+    fn test_steady_loud_channel_passes() {
+        let mut protector = Protector::new(&config(), 48000.0);
+        run(&mut protector, 0.3, 400);
+
+        // Loud, but only a few dB over what the channel has been doing
+        run(&mut protector, 0.6, 10);
+        assert_eq!(protector.reduction_db(), 0.0);
+    }
+}
@@ -0,0 +1,511 @@
+//! Stereo recorder with loudness normalized export
+//!
+//! The audio thread feeds one output bus (post-fader) through the same kind
+//! of tap as the replay buffer. While recording, the UI thread streams it to
+//! a float WAV file and measures its K-weighted loudness on the way. Once
+//! stopped, the take is exported: brought to the loudness target (without
+//! pushing its peak over the ceiling) and, for mp3 and opus, encoded by
+//! `ffmpeg`. Exporting can take a while for long takes, so it runs on its
+//! own thread.
+//!
+//! With a trigger, the take only grows while the output carries signal:
+//! frames wait in a short pre-roll until a peak crosses the threshold, and
+//! writing pauses again after a stretch of silence, so an intermittent
+//! source like a talkback or scanner feed gives a compact file.
+
+use anyhow::{Context, Result};
+use rtrb::Consumer;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use super::dsp::{self, KWeighting};
+use super::replay::{write_wav_header, WAV_HEADER_LEN};
+use crate::config::{RecordFormat, RecordTriggerConfig, RecorderConfig};
+use crate::ipc::MeterData;
+
+/// Channels of a recording
+const CHANNELS: usize = 2;
+
+/// Length of a loudness measurement block
+const BLOCK_SECONDS: f32 = 0.1;
+
+/// Samples processed per read when applying gain to a WAV file
+const COPY_CHUNK: usize = 16_384;
+
+/// A finished take, ready for export
+#[derive(Debug, Clone)]
+pub struct Recording {
+    /// Raw float WAV file of the take
+    pub path: PathBuf,
+
+    /// Length of the take
+    pub duration: Duration,
+
+    /// Integrated loudness in LUFS (None for silence)
+    pub loudness: Option<f32>,
+
+    /// Highest sample peak (linear)
+    pub peak: f32,
+}
+
+/// Take being written
+struct Take {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    frames: u64,
+    peak: f32,
+
+    /// K-weighting per channel
+    k_filters: [KWeighting; CHANNELS],
+
+    /// Samples of the current loudness block per channel
+    block: [Vec<f32>; CHANNELS],
+
+    /// K-weighted power of every finished block, summed over the channels
+    block_powers: Vec<f32>,
+
+    /// Signal gate, for triggered recording
+    trigger: Option<Trigger>,
+}
+
+/// Gate of a triggered take
+struct Trigger {
+    /// Peak that opens the gate (linear)
+    threshold: f32,
+
+    /// Frames below the threshold that close the gate
+    hold_frames: u64,
+
+    /// Frames kept while closed
+    pre_roll_frames: usize,
+
+    /// Latest frames while closed, oldest first
+    pre_roll: VecDeque<[f32; CHANNELS]>,
+
+    /// Frames below the threshold since the last peak above it
+    quiet_frames: u64,
+
+    /// Whether frames are being written
+    open: bool,
+}
+
+impl Trigger {
+    fn new(config: &RecordTriggerConfig, sample_rate: u32) -> Self {
+        let pre_roll_frames = (config.pre_roll_seconds * sample_rate as f32) as usize;
+        Self {
+            threshold: MeterData::db_to_linear(config.threshold_db),
+            hold_frames: (config.hold_seconds * sample_rate as f32) as u64,
+            pre_roll_frames,
+            pre_roll: VecDeque::with_capacity(pre_roll_frames),
+            quiet_frames: 0,
+            open: false,
+        }
+    }
+
+    /// Pass a frame through the gate, appending what is to be written to `out`
+    fn gate(&mut self, frame: [f32; CHANNELS], out: &mut Vec<[f32; CHANNELS]>) {
+        let loud = frame.iter().any(|s| s.abs() >= self.threshold);
+        if self.open {
+            if loud {
+                self.quiet_frames = 0;
+            } else {
+                self.quiet_frames += 1;
+                if self.quiet_frames >= self.hold_frames {
+                    self.open = false;
+                }
+            }
+            out.push(frame);
+        } else if loud {
+            self.open = true;
+            self.quiet_frames = 0;
+            out.extend(self.pre_roll.drain(..));
+            out.push(frame);
+        } else if self.pre_roll_frames > 0 {
+            if self.pre_roll.len() == self.pre_roll_frames {
+                self.pre_roll.pop_front();
+            }
+            self.pre_roll.push_back(frame);
+        }
+    }
+}
+
+/// UI thread side of the recorder: drains the tap and writes takes
+pub struct RecorderFeed {
+    /// Samples from the audio thread
+    consumer: Consumer<f32>,
+
+    /// Interleaved ports per frame in the feed (1 or 2)
+    ports: usize,
+
+    sample_rate: u32,
+
+    /// Take in progress
+    take: Option<Take>,
+
+    /// Signal trigger for new takes, if any
+    trigger: Option<RecordTriggerConfig>,
+}
+
+impl RecorderFeed {
+    /// Wrap the consumer of a tap carrying `ports` ports
+    pub fn new(
+        consumer: Consumer<f32>,
+        ports: usize,
+        sample_rate: u32,
+        trigger: Option<RecordTriggerConfig>,
+    ) -> Self {
+        Self {
+            consumer,
+            ports: ports.clamp(1, CHANNELS),
+            sample_rate,
+            take: None,
+            trigger,
+        }
+    }
+
+    /// Whether a triggered take is waiting for signal
+    pub fn is_waiting(&self) -> bool {
+        self.take
+            .as_ref()
+            .and_then(|t| t.trigger.as_ref())
+            .is_some_and(|t| !t.open)
+    }
+
+    /// Length of the take in progress
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.take
+            .as_ref()
+            .map(|t| Duration::from_secs_f64(t.frames as f64 / f64::from(self.sample_rate)))
+    }
+
+    /// Start writing a take to `path` (a raw float WAV file)
+    pub fn start(&mut self, path: &Path) -> Result<()> {
+        if self.take.is_some() {
+            anyhow::bail!("Already recording");
+        }
+        // Skip what piled up while idle
+        self.discard();
+
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        write_wav_header(&mut writer, 0, CHANNELS as u16, self.sample_rate)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        let block_len = (self.sample_rate as f32 * BLOCK_SECONDS) as usize;
+        self.take = Some(Take {
+            path: path.to_path_buf(),
+            writer,
+            frames: 0,
+            peak: 0.0,
+            k_filters: [KWeighting::new(self.sample_rate as f32); CHANNELS],
+            block: [Vec::with_capacity(block_len), Vec::with_capacity(block_len)],
+            block_powers: Vec::new(),
+            trigger: self.trigger.as_ref().map(|t| Trigger::new(t, self.sample_rate)),
+        });
+        Ok(())
+    }
+
+    /// Move pending samples into the take (call regularly)
+    ///
+    /// A write error ends the take; the file written so far is kept.
+    pub fn drain(&mut self) -> Result<()> {
+        let Some(take) = self.take.as_mut() else {
+            self.discard();
+            return Ok(());
+        };
+
+        // Whole frames only; the rest follows with the next drain
+        let available = self.consumer.slots() / self.ports * self.ports;
+        let Ok(chunk) = self.consumer.read_chunk(available) else {
+            return Ok(());
+        };
+        let (first, second) = chunk.as_slices();
+        let mut samples = Vec::with_capacity(available);
+        samples.extend_from_slice(first);
+        samples.extend_from_slice(second);
+        chunk.commit_all();
+
+        let block_len = (self.sample_rate as f32 * BLOCK_SECONDS) as usize;
+        let result = take.write(&samples, self.ports, block_len);
+        if result.is_err() {
+            self.take = None;
+        }
+        result
+    }
+
+    /// Finish the take in progress
+    pub fn stop(&mut self) -> Result<Recording> {
+        self.drain()?;
+        let mut take = self.take.take().context("Not recording")?;
+
+        let sample_count = take.frames as usize * CHANNELS;
+        take.writer
+            .flush()
+            .and_then(|_| take.writer.seek(SeekFrom::Start(0)).map(|_| ()))
+            .and_then(|_| {
+                write_wav_header(
+                    &mut take.writer,
+                    sample_count,
+                    CHANNELS as u16,
+                    self.sample_rate,
+                )
+            })
+            .and_then(|_| take.writer.flush())
+            .with_context(|| format!("Failed to write {}", take.path.display()))?;
+
+        Ok(Recording {
+            duration: Duration::from_secs_f64(take.frames as f64 / f64::from(self.sample_rate)),
+            loudness: dsp::integrated_loudness(&take.block_powers),
+            peak: take.peak,
+            path: take.path,
+        })
+    }
+
+    /// Drop everything waiting in the tap
+    fn discard(&mut self) {
+        let available = self.consumer.slots();
+        if let Ok(chunk) = self.consumer.read_chunk(available) {
+            chunk.commit_all();
+        }
+    }
+}
+
+impl Take {
+    /// Append interleaved frames of `ports` ports as stereo (those the
+    /// trigger lets through, if any)
+    fn write(&mut self, samples: &[f32], ports: usize, block_len: usize) -> Result<()> {
+        let mut frames = Vec::with_capacity(samples.len() / ports);
+        for frame in samples.chunks_exact(ports) {
+            let stereo = [frame[0], frame[ports - 1]];
+            match self.trigger.as_mut() {
+                Some(trigger) => trigger.gate(stereo, &mut frames),
+                None => frames.push(stereo),
+            }
+        }
+
+        for stereo in frames {
+            for (channel, &sample) in stereo.iter().enumerate() {
+                self.writer
+                    .write_all(&sample.to_le_bytes())
+                    .with_context(|| format!("Failed to write {}", self.path.display()))?;
+                self.peak = self.peak.max(sample.abs());
+                self.block[channel].push(sample);
+            }
+            self.frames += 1;
+
+            if self.block[0].len() >= block_len {
+                let power = self
+                    .k_filters
+                    .iter_mut()
+                    .zip(self.block.iter_mut())
+                    .map(|(filter, block)| {
+                        let power = filter.mean_square(block);
+                        block.clear();
+                        power
+                    })
+                    .sum();
+                self.block_powers.push(power);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Gain in dB that brings a take to the loudness target without exceeding the ceiling
+pub fn normalize_gain_db(recording: &Recording, settings: &RecorderConfig) -> f32 {
+    let (Some(target), Some(loudness)) = (settings.normalize_lufs, recording.loudness) else {
+        return 0.0;
+    };
+    let headroom = settings.ceiling_db - MeterData::linear_to_db(recording.peak);
+    (target - loudness).min(headroom)
+}
+
+/// Export a take to `path` in the configured format, removing the raw file
+pub fn export_recording(recording: &Recording, settings: &RecorderConfig, path: &Path) -> Result<()> {
+    let gain_db = normalize_gain_db(recording, settings);
+    match settings.format {
+        RecordFormat::Wav => copy_with_gain(&recording.path, path, gain_db)?,
+        RecordFormat::Mp3 | RecordFormat::Opus => encode(recording, settings, path, gain_db)?,
+    }
+    if recording.path != path {
+        std::fs::remove_file(&recording.path)
+            .with_context(|| format!("Failed to remove {}", recording.path.display()))?;
+    }
+    Ok(())
+}
+
+/// Write a float WAV file with `gain_db` applied (a rename when there is no gain)
+fn copy_with_gain(source: &Path, target: &Path, gain_db: f32) -> Result<()> {
+    if gain_db == 0.0 {
+        return std::fs::rename(source, target).with_context(|| {
+            format!("Failed to move {} to {}", source.display(), target.display())
+        });
+    }
+
+    let gain = MeterData::db_to_linear(gain_db);
+    let mut reader = BufReader::new(
+        File::open(source).with_context(|| format!("Failed to open {}", source.display()))?,
+    );
+    let mut writer = BufWriter::new(
+        File::create(target).with_context(|| format!("Failed to create {}", target.display()))?,
+    );
+
+    let mut copy = || -> std::io::Result<()> {
+        let mut header = [0u8; WAV_HEADER_LEN as usize];
+        reader.read_exact(&mut header)?;
+        writer.write_all(&header)?;
+
+        let mut buffer = vec![0u8; COPY_CHUNK * 4];
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            // Reads come in whole samples except at a short read; finish it
+            let whole = read.div_ceil(4) * 4;
+            reader.read_exact(&mut buffer[read..whole])?;
+            for bytes in buffer[..whole].chunks_exact(4) {
+                let sample = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                writer.write_all(&(sample * gain).to_le_bytes())?;
+            }
+        }
+        writer.flush()
+    };
+    copy().with_context(|| format!("Failed to write {}", target.display()))
+}
+
+/// Encode a take with ffmpeg
+fn encode(
+    recording: &Recording,
+    settings: &RecorderConfig,
+    path: &Path,
+    gain_db: f32,
+) -> Result<()> {
+    let (codec, default_bitrate) = match settings.format {
+        RecordFormat::Mp3 => ("libmp3lame", 192),
+        _ => ("libopus", 96),
+    };
+    let bitrate = settings.bitrate_kbps.unwrap_or(default_bitrate);
+
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-y", "-i"])
+        .arg(&recording.path)
+        .args(["-af", &format!("volume={:.2}dB", gain_db)])
+        .args(["-c:a", codec, "-b:a", &format!("{}k", bitrate)])
+        .arg(path)
+        .stdin(Stdio::null())
+        .output()
+        .context("Failed to run ffmpeg (is it installed?)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "ffmpeg failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> RecorderConfig {
+        RecorderConfig {
+            output: "Main".to_string(),
+            format: RecordFormat::Wav,
+            directory: None,
+            normalize_lufs: Some(-16.0),
+            ceiling_db: -1.0,
+            bitrate_kbps: None,
+            trigger: None,
+            freewheel: false,
+        }
+    }
+
+    #[test]
+    fn test_normalize_gain_respects_ceiling() {
+        let mut recording = Recording {
+            path: PathBuf::new(),
+            duration: Duration::from_secs(60),
+            loudness: Some(-24.0),
+            peak: MeterData::db_to_linear(-12.0),
+        };
+        assert!((normalize_gain_db(&recording, &settings()) - 8.0).abs() < 1e-3);
+
+        // Only 5 dB until the peak hits -1 dBFS
+        recording.peak = MeterData::db_to_linear(-6.0);
+        assert!((normalize_gain_db(&recording, &settings()) - 5.0).abs() < 1e-3);
+
+        recording.loudness = None;
+        assert_eq!(normalize_gain_db(&recording, &settings()), 0.0);
+    }
+
+    #[test]
+    fn test_take_is_stereo_wav_with_loudness() {
+        let dir = std::env::temp_dir();
+        let raw = dir.join(format!("rmixer-test-take-{}.rec.wav", std::process::id()));
+        let out = dir.join(format!("rmixer-test-take-{}.wav", std::process::id()));
+
+        // One second of a mono feed at 8 kHz: doubled to both channels
+        let (mut tap, consumer) = super::super::replay::feed(0, 1, 8000);
+        let mut feed = RecorderFeed::new(consumer, 1, 8000, None);
+        feed.start(&raw).unwrap();
+        let signal: Vec<f32> = (0..8000).map(|i| if i % 8 < 4 { 0.5 } else { -0.5 }).collect();
+        tap.push(&[&signal]);
+        let recording = feed.stop().unwrap();
+
+        assert_eq!(recording.duration, Duration::from_secs(1));
+        assert_eq!(recording.peak, 0.5);
+        assert!(recording.loudness.is_some());
+        let bytes = std::fs::read(&raw).unwrap();
+        assert_eq!(bytes.len() as u64, WAV_HEADER_LEN + 8000 * 2 * 4);
+
+        export_recording(&recording, &settings(), &out).unwrap();
+        assert!(!raw.exists());
+        let exported = std::fs::read(&out).unwrap();
+        assert_eq!(exported.len(), bytes.len());
+        let gain = MeterData::db_to_linear(normalize_gain_db(&recording, &settings()));
+        let first = f32::from_le_bytes(exported[58..62].try_into().unwrap());
+        assert!((first - 0.5 * gain).abs() < 1e-6);
+        std::fs::remove_file(&out).unwrap();
+    }
+
+    #[test]
+    fn test_trigger_records_signal_with_pre_roll() {
+        let raw = std::env::temp_dir()
+            .join(format!("rmixer-test-trigger-{}.rec.wav", std::process::id()));
+
+        // 100 Hz feed: 0.1 s pre-roll and 0.5 s hold are 10 and 50 frames
+        let (mut tap, consumer) = super::super::replay::feed(0, 1, 100);
+        let trigger = RecordTriggerConfig {
+            threshold_db: -20.0,
+            hold_seconds: 0.5,
+            pre_roll_seconds: 0.1,
+        };
+        let mut feed = RecorderFeed::new(consumer, 1, 100, Some(trigger));
+        feed.start(&raw).unwrap();
+
+        // Quiet, a burst, then a long silence: pre-roll + burst + hold
+        let mut signal = vec![0.01; 100];
+        signal.extend([0.5; 20]);
+        tap.push(&[&signal]);
+        feed.drain().unwrap();
+        assert!(!feed.is_waiting());
+        tap.push(&[&[0.0; 200]]);
+        feed.drain().unwrap();
+        assert!(feed.is_waiting());
+
+        let recording = feed.stop().unwrap();
+        assert_eq!(recording.duration, Duration::from_secs_f64(0.8));
+        let bytes = std::fs::read(&raw).unwrap();
+        let first = f32::from_le_bytes(bytes[58..62].try_into().unwrap());
+        assert_eq!(first, 0.01);
+        std::fs::remove_file(&raw).unwrap();
+    }
+}
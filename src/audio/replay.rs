@@ -0,0 +1,253 @@
+//! In-memory replay buffer
+//!
+//! The audio thread copies one output bus (post-fader, interleaved) into a
+//! small lock-free ring. The UI thread drains it into a rolling buffer that
+//! holds the last few seconds, which can be written to a WAV file on demand.
+
+use anyhow::{Context, Result};
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Seconds of audio the transfer ring between the threads can hold
+const TRANSFER_SECONDS: usize = 2;
+
+/// Audio thread side: feeds the samples of one output channel
+pub struct ReplayTap {
+    /// Output channel recorded
+    pub output: usize,
+
+    /// Ports of the output fed (its first ones)
+    pub ports: usize,
+
+    /// Interleaved samples for the UI thread
+    producer: Producer<f32>,
+}
+
+impl ReplayTap {
+    /// Push one period of the output's ports, interleaved
+    ///
+    /// A period that does not fit is dropped as a whole (the UI thread is
+    /// not draining), so frames stay aligned.
+    pub fn push(&mut self, ports: &[&[f32]]) {
+        let frames = ports.first().map_or(0, |p| p.len());
+        if self.producer.slots() < frames * ports.len() {
+            return;
+        }
+        for frame in 0..frames {
+            for port in ports {
+                let _ = self.producer.push(port[frame]);
+            }
+        }
+    }
+
+    /// Wait up to `timeout` until a period of `frames` fits; only for when
+    /// the callback runs outside real time (freewheeling)
+    pub fn wait_for_room(&self, frames: usize, timeout: Duration) {
+        let started = Instant::now();
+        while self.producer.slots() < frames * self.ports && started.elapsed() < timeout {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+/// Create a tap feeding the first `ports` ports of an output to the UI thread
+pub fn feed(output: usize, ports: usize, sample_rate: u32) -> (ReplayTap, Consumer<f32>) {
+    let (producer, consumer) = RingBuffer::new(sample_rate as usize * ports * TRANSFER_SECONDS);
+    (
+        ReplayTap {
+            output,
+            ports,
+            producer,
+        },
+        consumer,
+    )
+}
+
+/// UI thread side: rolling buffer of the last `seconds` of audio
+pub struct ReplayBuffer {
+    /// Samples from the audio thread
+    consumer: Consumer<f32>,
+
+    /// Interleaved samples, oldest first
+    samples: VecDeque<f32>,
+
+    /// Maximum number of samples kept
+    capacity: usize,
+
+    /// Interleaved channel count (one per port)
+    channels: usize,
+
+    /// Sample rate in Hz
+    sample_rate: u32,
+}
+
+impl ReplayBuffer {
+    /// Create a buffer keeping `seconds` of an output with `channels` ports
+    pub fn new(output: usize, channels: usize, sample_rate: u32, seconds: f32) -> (ReplayTap, Self) {
+        let frames_per_second = sample_rate as usize * channels;
+        let (tap, consumer) = feed(output, channels, sample_rate);
+        let capacity = (seconds * frames_per_second as f32) as usize / channels * channels;
+        (
+            tap,
+            Self {
+                consumer,
+                samples: VecDeque::with_capacity(capacity),
+                capacity,
+                channels,
+                sample_rate,
+            },
+        )
+    }
+
+    /// Move pending samples from the audio thread into the rolling buffer
+    pub fn drain(&mut self) {
+        let available = self.consumer.slots();
+        let Ok(chunk) = self.consumer.read_chunk(available) else {
+            return;
+        };
+        self.samples.extend(chunk);
+        let excess = self.samples.len().saturating_sub(self.capacity);
+        self.samples.drain(..excess);
+    }
+
+    /// Write the last `seconds` (or everything held) to a WAV file
+    pub fn save(&mut self, path: &Path, seconds: Option<f32>) -> Result<Duration> {
+        self.drain();
+        let held = self.samples.len();
+        let wanted = match seconds {
+            Some(s) => {
+                ((s.max(0.0) * self.sample_rate as f32) as usize * self.channels).min(held)
+            }
+            None => held,
+        };
+        if wanted == 0 {
+            anyhow::bail!("Replay buffer is empty");
+        }
+
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        write_wav(
+            &mut writer,
+            self.samples.range(held - wanted..),
+            wanted,
+            self.channels as u16,
+            self.sample_rate,
+        )
+        .and_then(|_| writer.flush())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        Ok(Duration::from_secs_f64(
+            (wanted / self.channels) as f64 / self.sample_rate as f64,
+        ))
+    }
+}
+
+/// Write interleaved samples as a 32-bit float WAV file
+fn write_wav<'a, W: Write>(
+    writer: &mut W,
+    samples: impl Iterator<Item = &'a f32>,
+    sample_count: usize,
+    channels: u16,
+    sample_rate: u32,
+) -> std::io::Result<()> {
+    write_wav_header(writer, sample_count, channels, sample_rate)?;
+    for sample in samples {
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Length of the header written by `write_wav_header`
+pub const WAV_HEADER_LEN: u64 = 58;
+
+/// Write the header of a 32-bit float WAV file holding `sample_count` samples
+pub fn write_wav_header<W: Write>(
+    writer: &mut W,
+    sample_count: usize,
+    channels: u16,
+    sample_rate: u32,
+) -> std::io::Result<()> {
+    const FORMAT_IEEE_FLOAT: u16 = 3;
+    let bytes_per_sample = 4u32;
+    let data_len = sample_count as u32 * bytes_per_sample;
+    let frames = sample_count as u32 / channels as u32;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(4 + 26 + 12 + 8 + data_len).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    // fmt chunk (with the empty extension size required for float)
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&18u32.to_le_bytes())?;
+    writer.write_all(&FORMAT_IEEE_FLOAT.to_le_bytes())?;
+    writer.write_all(&channels.to_le_bytes())?;
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * channels as u32 * bytes_per_sample).to_le_bytes())?;
+    writer.write_all(&(channels * bytes_per_sample as u16).to_le_bytes())?;
+    writer.write_all(&32u16.to_le_bytes())?;
+    writer.write_all(&0u16.to_le_bytes())?;
+
+    writer.write_all(b"fact")?;
+    writer.write_all(&4u32.to_le_bytes())?;
+    writer.write_all(&frames.to_le_bytes())?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_len.to_le_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_keeps_last_seconds() {
+        let (mut tap, mut buffer) = ReplayBuffer::new(0, 2, 10, 1.0);
+        let left: Vec<f32> = (0..15).map(|i| i as f32).collect();
+        let right: Vec<f32> = (0..15).map(|i| -(i as f32)).collect();
+        tap.push(&[&left, &right]);
+        buffer.drain();
+
+        // 1 s at 10 Hz stereo: the last 10 frames
+        assert_eq!(buffer.samples.len(), 20);
+        assert_eq!(buffer.samples.front(), Some(&5.0));
+        assert_eq!(buffer.samples.get(1), Some(&-5.0));
+    }
+
+    #[test]
+    fn test_freewheel_wait_gives_up() {
+        // 2 s at 10 Hz stereo fit in the transfer ring
+        let (mut tap, _consumer) = feed(0, 2, 10);
+        let wait = Duration::from_millis(50);
+        let started = Instant::now();
+        tap.wait_for_room(20, wait);
+        assert!(started.elapsed() < wait);
+
+        // A full ring nobody drains holds the callback for the timeout only
+        let frames = [0.0f32; 20];
+        tap.push(&[&frames, &frames]);
+        let started = Instant::now();
+        tap.wait_for_room(1, wait);
+        assert!(started.elapsed() >= wait);
+    }
+
+    #[test]
+    fn test_wav_header() {
+        let samples = [0.5f32, -0.5, 0.25, -0.25];
+        let mut out = Vec::new();
+        write_wav(&mut out, samples.iter(), samples.len(), 2, 48000).unwrap();
+
+        assert_eq!(&out[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(out[4..8].try_into().unwrap()) as usize, out.len() - 8);
+        assert_eq!(u16::from_le_bytes([out[20], out[21]]), 3);
+        assert_eq!(u16::from_le_bytes([out[22], out[23]]), 2);
+        assert_eq!(&out[50..54], b"data");
+        assert_eq!(out.len() as u64, WAV_HEADER_LEN + 16);
+        assert_eq!(f32::from_le_bytes(out[58..62].try_into().unwrap()), 0.5);
+    }
+}
@@ -0,0 +1,141 @@
+//! Silent input port detection
+//!
+//! A dead meter looks the same whether nothing is connected to a port or the
+//! connected source only delivers zeros, e.g. a muted device or a bridge with
+//! a sample format it cannot convert. The audio thread counts how long each
+//! connected input port has carried nothing but digital silence and publishes
+//! the result for the diagnostics overlay.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long a connected port may deliver only zeros before it counts as silent
+pub const SILENT_PORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Signal state of an input port
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortStatus {
+    /// No other port is connected
+    Disconnected,
+
+    /// Connected and carrying signal (or not silent for long)
+    Active,
+
+    /// Connected but only zeros for longer than `SILENT_PORT_TIMEOUT`
+    Silent,
+}
+
+impl PortStatus {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => PortStatus::Active,
+            2 => PortStatus::Silent,
+            _ => PortStatus::Disconnected,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            PortStatus::Disconnected => 0,
+            PortStatus::Active => 1,
+            PortStatus::Silent => 2,
+        }
+    }
+}
+
+/// Port states shared between the audio thread and the UI
+#[derive(Debug)]
+pub struct PortStatuses {
+    statuses: Vec<AtomicU8>,
+}
+
+impl PortStatuses {
+    /// States of `ports` input ports, all disconnected
+    pub fn new(ports: usize) -> Self {
+        Self {
+            statuses: (0..ports).map(|_| AtomicU8::new(0)).collect(),
+        }
+    }
+
+    /// Current state of every port, in registration order
+    pub fn snapshot(&self) -> Vec<PortStatus> {
+        self.statuses
+            .iter()
+            .map(|s| PortStatus::from_u8(s.load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+/// Audio thread side: tracks the silence of each input port
+#[derive(Debug)]
+pub struct SilenceDetector {
+    statuses: Arc<PortStatuses>,
+
+    /// Consecutive frames of digital silence per port
+    silent_frames: Vec<u64>,
+
+    /// Frames of silence after which a port counts as silent
+    timeout_frames: u64,
+}
+
+impl SilenceDetector {
+    /// Create a detector publishing into `statuses`
+    pub fn new(statuses: Arc<PortStatuses>, sample_rate: f32) -> Self {
+        Self {
+            silent_frames: vec![0; statuses.statuses.len()],
+            statuses,
+            timeout_frames: (SILENT_PORT_TIMEOUT.as_secs_f32() * sample_rate) as u64,
+        }
+    }
+
+    /// Record one period of a port given whether it is connected and the
+    /// peak of its buffer
+    pub fn update(&mut self, port: usize, connected: bool, peak: f32, nframes: usize) {
+        let Some(frames) = self.silent_frames.get_mut(port) else {
+            return;
+        };
+        let status = if !connected {
+            *frames = 0;
+            PortStatus::Disconnected
+        } else if peak == 0.0 {
+            *frames += nframes as u64;
+            if *frames >= self.timeout_frames {
+                PortStatus::Silent
+            } else {
+                PortStatus::Active
+            }
+        } else {
+            *frames = 0;
+            PortStatus::Active
+        };
+        self.statuses.statuses[port].store(status.as_u8(), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connected_zeros_turn_silent() {
+        let statuses = Arc::new(PortStatuses::new(2));
+        // 100 Hz: the timeout is 1000 frames
+        let mut detector = SilenceDetector::new(statuses.clone(), 100.0);
+
+        detector.update(0, true, 0.0, 999);
+        detector.update(1, false, 0.0, 999);
+        assert_eq!(
+            statuses.snapshot(),
+            [PortStatus::Active, PortStatus::Disconnected]
+        );
+
+        detector.update(0, true, 0.0, 1);
+        assert_eq!(statuses.snapshot()[0], PortStatus::Silent);
+
+        // Any signal resets the count
+        detector.update(0, true, 0.001, 10);
+        detector.update(0, true, 0.0, 999);
+        assert_eq!(statuses.snapshot()[0], PortStatus::Active);
+    }
+}
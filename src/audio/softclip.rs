@@ -0,0 +1,220 @@
+//! Soft clipper for output buses
+//!
+//! A tanh or cubic curve rounds off peaks instead of cutting them. The drive
+//! pushes the bus into the curve and the same gain is taken off afterwards,
+//! so quiet passages keep their level while the output can never exceed the
+//! ceiling minus the drive. Without oversampling it is memoryless and adds no
+//! latency; with it, the curve runs at a multiple of the sample rate between
+//! two low-pass filters so the harmonics it adds do not fold back as aliases,
+//! at the cost of a few samples of filter delay.
+
+use std::f32::consts::PI;
+
+use super::dsp::Biquad;
+use crate::config::{ClipCurve, SoftClipConfig};
+use crate::ipc::{MeterData, MAX_CHANNEL_PORTS};
+
+/// Cutoff of the oversampling filters as a fraction of the base sample rate
+const FILTER_CUTOFF: f32 = 0.4;
+
+/// Quality factors of the sections of an 8th-order Butterworth low-pass
+const BUTTERWORTH_QS: [f32; 4] = [0.5098, 0.6013, 0.9000, 2.5629];
+
+/// Anti-imaging and anti-aliasing filters of one port
+#[derive(Debug, Clone, Copy)]
+struct Oversampler {
+    /// Smooths the zero-stuffed input
+    up: [Biquad; 4],
+
+    /// Removes everything above the base band before decimating
+    down: [Biquad; 4],
+}
+
+impl Oversampler {
+    /// Filters for a curve running at `factor` times the sample rate
+    fn new(factor: usize) -> Self {
+        let mut filters = [Biquad::new(1.0, 0.0, 0.0, 0.0, 0.0); 4];
+        for (filter, q) in filters.iter_mut().zip(BUTTERWORTH_QS) {
+            *filter = lowpass(FILTER_CUTOFF / factor as f32, q);
+        }
+        Self {
+            up: filters,
+            down: filters,
+        }
+    }
+}
+
+/// RBJ low-pass at `freq` (a fraction of the sample rate)
+fn lowpass(freq: f32, q: f32) -> Biquad {
+    let w0 = 2.0 * PI * freq;
+    let (sin, cos) = w0.sin_cos();
+    let alpha = sin / (2.0 * q);
+    let a0 = 1.0 + alpha;
+    let b1 = (1.0 - cos) / a0;
+    Biquad::new(b1 / 2.0, b1, b1 / 2.0, -2.0 * cos / a0, (1.0 - alpha) / a0)
+}
+
+/// The clipping curve with the gains around it
+#[derive(Debug, Clone, Copy)]
+struct Shaper {
+    curve: ClipCurve,
+
+    /// Gain into the curve (drive over ceiling, linear)
+    input_gain: f32,
+
+    /// Gain after the curve (ceiling over drive, linear)
+    output_gain: f32,
+}
+
+impl Shaper {
+    #[inline]
+    fn apply(&self, x: f32) -> f32 {
+        let x = x * self.input_gain;
+        let y = match self.curve {
+            ClipCurve::Tanh => x.tanh(),
+            // Unity slope at zero, flat at the ceiling from 1.5 on
+            ClipCurve::Cubic if x.abs() < 1.5 => x - x * x * x * 4.0 / 27.0,
+            ClipCurve::Cubic => x.signum(),
+        };
+        y * self.output_gain
+    }
+}
+
+/// Soft clipper state for one output channel
+#[derive(Debug, Clone, Copy)]
+pub struct SoftClip {
+    shaper: Shaper,
+
+    /// Oversampling factor (1 runs the curve at the sample rate)
+    factor: usize,
+
+    /// Oversampling filters per port
+    ports: [Oversampler; MAX_CHANNEL_PORTS],
+}
+
+impl SoftClip {
+    /// Create a soft clipper from its config
+    pub fn new(config: &SoftClipConfig) -> Self {
+        let drive = MeterData::db_to_linear(config.drive_db.max(0.0));
+        let ceiling = MeterData::db_to_linear(config.ceiling_db.min(0.0));
+        let factor = config.oversampling.max(1) as usize;
+        Self {
+            shaper: Shaper {
+                curve: config.curve,
+                input_gain: drive / ceiling,
+                output_gain: ceiling / drive,
+            },
+            factor,
+            ports: [Oversampler::new(factor); MAX_CHANNEL_PORTS],
+        }
+    }
+
+    /// Clip the ports of a channel in place
+    pub fn process(&mut self, ports: &mut [&mut [f32]]) {
+        let shaper = self.shaper;
+        if self.factor == 1 {
+            for port in ports.iter_mut() {
+                for s in port.iter_mut() {
+                    *s = shaper.apply(*s);
+                }
+            }
+            return;
+        }
+
+        let factor = self.factor;
+        for (port, filters) in ports.iter_mut().zip(&mut self.ports) {
+            for s in port.iter_mut() {
+                let mut out = 0.0;
+                for k in 0..factor {
+                    // Zero stuffing: one input sample (scaled to keep the
+                    // level), then silence
+                    let x = if k == 0 { *s * factor as f32 } else { 0.0 };
+                    let up = filters.up.iter_mut().fold(x, |v, f| f.process(v));
+                    let clipped = shaper.apply(up);
+                    out = filters.down.iter_mut().fold(clipped, |v, f| f.process(v));
+                }
+                *s = out;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quiet_passes_and_peaks_stay_under_the_ceiling() {
+        let mut clip = SoftClip::new(&SoftClipConfig {
+            drive_db: 6.0,
+            ..Default::default()
+        });
+        let mut samples = [0.01, -0.01, 0.5, 4.0, -4.0];
+        clip.process(&mut [&mut samples]);
+
+        assert!((samples[0] - 0.01).abs() < 1e-4);
+        assert!((samples[1] + 0.01).abs() < 1e-4);
+        // Half scale is already rounded off with 6 dB of drive
+        assert!(samples[2] < 0.4);
+        let ceiling = MeterData::db_to_linear(-6.0);
+        assert!(samples[3] <= ceiling * 1.0001 && samples[3] > 0.99 * ceiling);
+        assert_eq!(samples[4], -samples[3]);
+    }
+
+    #[test]
+    fn test_cubic_curve_with_ceiling() {
+        let mut clip = SoftClip::new(&SoftClipConfig {
+            ceiling_db: -6.0,
+            curve: ClipCurve::Cubic,
+            ..Default::default()
+        });
+        let mut samples = [0.01, 0.2, 4.0];
+        clip.process(&mut [&mut samples]);
+
+        assert!((samples[0] - 0.01).abs() < 1e-4);
+        // Closer to linear than tanh at the same level
+        assert!(samples[1] > 0.19);
+        assert!((samples[2] - MeterData::db_to_linear(-6.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_oversampling_keeps_the_level_and_cuts_aliases() {
+        let sine = |freq: f32, amplitude: f32| -> Vec<f32> {
+            (0..9600)
+                .map(|i| amplitude * (2.0 * PI * freq * i as f32 / 48000.0).sin())
+                .collect()
+        };
+
+        // Below the curve a quiet tone passes at its level
+        let mut clip = SoftClip::new(&SoftClipConfig {
+            oversampling: 4,
+            ..Default::default()
+        });
+        let mut quiet = sine(1000.0, 0.01);
+        clip.process(&mut [&mut quiet]);
+        assert!((crate::audio::dsp::peak(&quiet[4800..]) - 0.01).abs() < 5e-4);
+
+        // A hard-driven 9 kHz tone: its third harmonic (27 kHz) folds back to
+        // 21 kHz at the base rate; oversampled, the filters remove it, so the
+        // output changes less from sample to sample
+        let settings = SoftClipConfig {
+            drive_db: 18.0,
+            ..Default::default()
+        };
+        let roughness = |samples: &[f32]| -> f32 {
+            samples[4800..]
+                .windows(3)
+                .map(|w| (w[0] - 2.0 * w[1] + w[2]).powi(2))
+                .sum()
+        };
+        let mut plain = sine(9000.0, 0.5);
+        SoftClip::new(&settings).process(&mut [&mut plain]);
+        let mut oversampled = sine(9000.0, 0.5);
+        SoftClip::new(&SoftClipConfig {
+            oversampling: 4,
+            ..settings
+        })
+        .process(&mut [&mut oversampled]);
+        assert!(roughness(&oversampled) < roughness(&plain));
+    }
+}
@@ -0,0 +1,189 @@
+//! Audio callback watchdog
+//!
+//! The process callback bumps a heartbeat counter every cycle. A watchdog
+//! thread checks that the counter keeps moving and flags the engine as
+//! stalled (or shut down by the server) so the UI can warn instead of
+//! silently freezing, optionally running a hook command on each change.
+
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How long the heartbeat may stand still before the callback counts as stalled
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often the watchdog checks the heartbeat
+const CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Health of the audio callback as seen by the watchdog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineHealth {
+    /// Callback running normally
+    Running,
+
+    /// Callback has not run for longer than `STALL_TIMEOUT`
+    Stalled,
+
+    /// The JACK server shut the client down
+    Shutdown,
+}
+
+impl EngineHealth {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => EngineHealth::Stalled,
+            2 => EngineHealth::Shutdown,
+            _ => EngineHealth::Running,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            EngineHealth::Running => 0,
+            EngineHealth::Stalled => 1,
+            EngineHealth::Shutdown => 2,
+        }
+    }
+
+    /// Event name passed to the hook command
+    fn event(self) -> &'static str {
+        match self {
+            EngineHealth::Running => "recovered",
+            EngineHealth::Stalled => "stalled",
+            EngineHealth::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// Signals shared between the audio thread, notification handler and watchdog
+#[derive(Debug, Default)]
+pub struct WatchdogSignals {
+    /// Process cycles run so far (bumped by the audio thread)
+    pub heartbeat: AtomicU64,
+
+    /// Set by the JACK shutdown notification
+    pub shutdown: AtomicBool,
+}
+
+/// Watchdog thread handle
+pub struct Watchdog {
+    health: Arc<AtomicU8>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Start watching the heartbeat
+    ///
+    /// `quit_flag` suppresses alarms while the engine is being stopped.
+    /// `hook` is run through `sh -c` with `RMIXER_EVENT` set to `stalled`,
+    /// `recovered` or `shutdown` and `RMIXER_CLIENT` set to the client name.
+    pub fn start(
+        signals: Arc<WatchdogSignals>,
+        quit_flag: Arc<AtomicBool>,
+        client_name: String,
+        hook: Option<String>,
+    ) -> Self {
+        let health = Arc::new(AtomicU8::new(EngineHealth::Running.as_u8()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let health = health.clone();
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name("rmixer-watchdog".to_string())
+                .spawn(move || {
+                    let mut last_beat = signals.heartbeat.load(Ordering::Relaxed);
+                    let mut last_change = Instant::now();
+                    let mut state = EngineHealth::Running;
+
+                    while !stop.load(Ordering::Relaxed) {
+                        thread::sleep(CHECK_INTERVAL);
+                        if quit_flag.load(Ordering::Relaxed) {
+                            continue;
+                        }
+
+                        let beat = signals.heartbeat.load(Ordering::Relaxed);
+                        let now = Instant::now();
+                        if beat != last_beat {
+                            last_beat = beat;
+                            last_change = now;
+                        }
+
+                        let next = if signals.shutdown.load(Ordering::Relaxed) {
+                            EngineHealth::Shutdown
+                        } else if now.duration_since(last_change) > STALL_TIMEOUT {
+                            EngineHealth::Stalled
+                        } else {
+                            EngineHealth::Running
+                        };
+
+                        if next != state {
+                            match next {
+                                EngineHealth::Running => log::warn!("Audio callback recovered"),
+                                EngineHealth::Stalled => log::error!(
+                                    "Audio callback stalled: no process cycle for {:?}",
+                                    now.duration_since(last_change)
+                                ),
+                                EngineHealth::Shutdown => {
+                                    log::error!("JACK server shut down the client")
+                                }
+                            }
+                            health.store(next.as_u8(), Ordering::Relaxed);
+                            if let Some(hook) = &hook {
+                                run_hook(hook, next.event(), &client_name);
+                            }
+                            state = next;
+                        }
+                    }
+                })
+                .ok()
+        };
+        if handle.is_none() {
+            log::warn!("Failed to start the audio watchdog thread");
+        }
+
+        Self {
+            health,
+            stop,
+            handle,
+        }
+    }
+
+    /// Current health of the audio callback
+    pub fn health(&self) -> EngineHealth {
+        EngineHealth::from_u8(self.health.load(Ordering::Relaxed))
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Run the hook command in the background, reaping it on its own thread
+fn run_hook(hook: &str, event: &str, client_name: &str) {
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(hook)
+        .env("RMIXER_EVENT", event)
+        .env("RMIXER_CLIENT", client_name)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match child {
+        Ok(mut child) => {
+            thread::spawn(move || {
+                let _ = child.wait();
+            });
+        }
+        Err(e) => log::warn!("Failed to run watchdog hook: {}", e),
+    }
+}
@@ -5,9 +5,112 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
+use std::net::IpAddr;
 use std::path::Path;
 
+use crate::ipc::{RoutingMatrix, MAX_CHANNEL_PORTS, VOLUME_MAX_DB, VOLUME_MIN_DB};
+use crate::remote::{RemoteCommand, MAX_FEEDBACK_RATE_HZ};
+
+/// Longest replay buffer accepted (memory use grows with the length)
+const MAX_REPLAY_SECONDS: f32 = 600.0;
+
+/// Largest boost or cut of a channel EQ band in dB
+pub const EQ_RANGE_DB: f32 = 12.0;
+
+/// Most parametric bands per channel EQ
+pub const MAX_EQ_BANDS: usize = 8;
+
+/// Lowest and highest parametric band frequency in Hz
+pub const EQ_FREQ_RANGE: (f32, f32) = (20.0, 20_000.0);
+
+/// Narrowest and widest parametric band quality factor
+pub const EQ_Q_RANGE: (f32, f32) = (0.1, 10.0);
+
+/// Cutoff a high-pass filter gets when switched on without one configured
+pub const DEFAULT_HIGH_PASS_HZ: f32 = 100.0;
+
+/// Cutoff a low-pass filter gets when switched on without one configured
+pub const DEFAULT_LOW_PASS_HZ: f32 = 12_000.0;
+
+/// Lowest and highest de-esser center frequency in Hz
+pub const DEESSER_FREQ_RANGE: (f32, f32) = (1_000.0, 16_000.0);
+
+/// Bands of the multiband compressor (low, mid, high)
+pub const MULTIBAND_BANDS: usize = 3;
+
+/// Lowest and highest multiband compressor threshold in dBFS
+pub const MULTIBAND_THRESHOLD_RANGE: (f32, f32) = (-60.0, 0.0);
+
+/// Lowest and highest multiband compressor ratio
+pub const MULTIBAND_RATIO_RANGE: (f32, f32) = (1.0, 20.0);
+
+/// Largest makeup gain or cut of a multiband compressor band in dB
+pub const MULTIBAND_MAKEUP_RANGE_DB: f32 = 12.0;
+
+/// Largest input trim boost or cut in dB
+pub const INPUT_TRIM_RANGE_DB: f32 = 24.0;
+
+/// Largest stereo balance correction of an input in dB
+pub const BALANCE_RANGE_DB: f32 = 12.0;
+
+/// Largest boost or cut of the mid or side of a mid/side channel in dB
+pub const MID_SIDE_RANGE_DB: f32 = 12.0;
+
+/// Largest automixer weight boost or cut in dB
+pub const AUTOMIX_WEIGHT_RANGE_DB: f32 = 12.0;
+
+/// Highest capture device volume in dB (about 150%, where pavucontrol stops)
+pub const DEVICE_GAIN_MAX_DB: f32 = 10.0;
+
+/// Slowest and fastest click tempo in beats per minute
+pub const CLICK_TEMPO_RANGE: (f32, f32) = (20.0, 300.0);
+
+/// Longest protective dip hold or release in ms
+pub const PROTECT_MAX_MS: f32 = 10_000.0;
+
+/// Lowest recorder loudness target accepted in LUFS
+const MIN_NORMALIZE_LUFS: f32 = -40.0;
+
+/// Lowest recorder trigger threshold accepted in dBFS
+const MIN_TRIGGER_THRESHOLD_DB: f32 = -90.0;
+
+/// Longest recorder pre-roll accepted (it is kept in memory)
+const MAX_PRE_ROLL_SECONDS: f32 = 30.0;
+
+/// Lowest and highest calibration noise level in dBFS
+const MIN_CALIBRATION_LEVEL_DB: f32 = -60.0;
+const MAX_CALIBRATION_LEVEL_DB: f32 = -10.0;
+
+/// Shortest and longest calibration noise per output in seconds
+const MIN_CALIBRATION_SECONDS: f32 = 2.0;
+const MAX_CALIBRATION_SECONDS: f32 = 30.0;
+
+/// Lowest limiter ceiling accepted in dBFS
+const MIN_LIMITER_CEILING_DB: f32 = -24.0;
+
+/// Longest limiter lookahead accepted (it delays the whole bus)
+const MAX_LOOKAHEAD_MS: f32 = 20.0;
+
+/// Longest output delay accepted
+const MAX_OUTPUT_DELAY_MS: f32 = 500.0;
+
+/// Most soft clipper drive accepted in dB
+const MAX_SOFT_CLIP_DRIVE_DB: f32 = 24.0;
+
+/// Lowest soft clipper ceiling in dBFS
+const MIN_SOFT_CLIP_CEILING_DB: f32 = -24.0;
+
+/// Oversampling factors of the soft clipper
+pub const SOFT_CLIP_OVERSAMPLING: [u32; 4] = [1, 2, 4, 8];
+
+/// Longest recent peak range accepted for the meters
+const MAX_METER_RANGE_SECONDS: f32 = 60.0;
+
+/// Most ports a passthrough channel may have (third-order ambisonics needs 16)
+pub const MAX_PASSTHROUGH_PORTS: usize = 64;
+
 /// Main configuration structure
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
@@ -19,6 +122,138 @@ pub struct Config {
 
     /// Output channel configurations
     pub outputs: Vec<ChannelConfig>,
+
+    /// Control socket address: a Unix socket path or TCP host:port (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_socket: Option<String>,
+
+    /// Writes per second to each control socket client, with the lines in
+    /// between sent together (optional, defaults to no limit; clients can
+    /// change their own with `feedback`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_feedback_hz: Option<f32>,
+
+    /// Which control socket clients may change the mixer and which may only
+    /// watch it (optional, defaults to every client having control)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_access: Option<ControlAccessConfig>,
+
+    /// Command run (via `sh -c`) when the audio callback stalls, recovers or
+    /// the server shuts the client down; `RMIXER_EVENT` names the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchdog_hook: Option<String>,
+
+    /// Master volume in dB, applied after every output (optional, defaults to 0.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub master_volume_db: Option<f32>,
+
+    /// Whether the master is muted, saved on exit (optional, defaults to false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub master_muted: bool,
+
+    /// Seconds of peak history shown as a band on the meters (optional,
+    /// defaults to 3; 0 turns the band off)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meter_range_seconds: Option<f32>,
+
+    /// Seconds the peak hold marks stay up (optional, defaults to 5; `.inf`
+    /// holds them until they are reset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_hold_seconds: Option<f32>,
+
+    /// Level in dBFS above which a channel's signal light is lit (optional,
+    /// defaults to -50)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal_threshold_db: Option<f32>,
+
+    /// UI language code, e.g. "de" (optional, defaults to the environment)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+
+    /// MIDI control input (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub midi: Option<MidiConfig>,
+
+    /// Stored mixer scenes, recalled by MIDI program change
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scenes: Vec<SceneConfig>,
+
+    /// Scene recalled at startup, ahead of the saved volumes (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup_scene: Option<String>,
+
+    /// Keep the last seconds of an output in memory for saving on demand (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_buffer: Option<ReplayConfig>,
+
+    /// Record one output to a file on demand, optionally normalized and encoded (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorder: Option<RecorderConfig>,
+
+    /// Multichannel level controllers that bypass the mix
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub passthrough: Vec<PassthroughConfig>,
+
+    /// Whether soloing a channel releases the other solos (defaults to
+    /// additive: solos add up)
+    #[serde(default, skip_serializing_if = "SoloMode::is_additive")]
+    pub solo_mode: SoloMode,
+
+    /// Warn about solo or mute left engaged for a long time (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reminders: Option<ReminderConfig>,
+
+    /// On-air tally outputs for the inputs (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tally: Option<TallyConfig>,
+
+    /// Control commands run at set times of day (optional)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<ScheduleEntry>,
+
+    /// Speaker level matching with pink noise and a measurement microphone
+    /// (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<CalibrationConfig>,
+
+    /// Temporary inputs for application streams that appear while running
+    /// (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_streams: Option<AppStreamsConfig>,
+
+    /// Metronome click into monitor outputs (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub click: Option<ClickConfig>,
+
+    /// How volume key steps map to dB on every fader without its own taper
+    /// (optional, defaults to equal dB steps)
+    #[serde(default, skip_serializing_if = "FaderTaper::is_linear_db")]
+    pub fader_taper: FaderTaper,
+
+    /// How volume readouts are written in the UI and on the control socket
+    /// (optional, defaults to one decimal place without a unit)
+    #[serde(default, skip_serializing_if = "DbDisplay::is_default")]
+    pub db_display: DbDisplay,
+
+    /// Range and color zones of the level meters (optional, defaults to
+    /// `digital`)
+    #[serde(default, skip_serializing_if = "MeterScale::is_digital")]
+    pub meter_scale: MeterScale,
+
+    /// Attack and release of the level meters (optional, defaults to
+    /// `digital`)
+    #[serde(default, skip_serializing_if = "MeterBallistics::is_digital")]
+    pub meter_ballistics: MeterBallistics,
+
+    /// Where input meters take their level unless an input sets its own
+    /// (optional, defaults to `pre_fader`)
+    #[serde(default, skip_serializing_if = "TapPoint::is_pre_fader")]
+    pub meter_point: TapPoint,
+
+    /// Keep the audio running on Ctrl-Z by opening a shell instead of
+    /// stopping the process (optional, defaults to false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keep_audio_on_suspend: bool,
     
     /// Path to the config file (not serialized)
     #[serde(skip)]
@@ -26,156 +261,3820 @@ pub struct Config {
 }
 
 /// Configuration for a single channel (input or output)
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ChannelConfig {
     /// Display name for the channel
     pub name: String,
 
-    /// Port names to create. Length determines mono (1) or stereo (2)
+    /// Port names to create. Length determines mono (1), stereo (2) or
+    /// surround (up to 8, in the server's channel order, e.g. L R C LFE Ls Rs)
     /// Ports will be exposed as "{client_name}:{port_name}"
     pub ports: Vec<String>,
     
     /// Volume level in dB (optional, defaults to 0.0)
     #[serde(default)]
     pub volume_db: Option<f32>,
+
+    /// Whether the channel is muted, saved on exit (defaults to false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub muted: bool,
+
+    /// Whether the channel is soloed, saved on exit (defaults to false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub soloed: bool,
+
+    /// Gain in dB applied to every port before metering and processing, for
+    /// gain staging hot or quiet sources (inputs only, defaults to 0.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_db: Option<f32>,
+
+    /// Level difference in dB corrected between the sides of a stereo
+    /// input, split evenly: positive raises the right side and lowers the
+    /// left (inputs only, defaults to 0.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_db: Option<f32>,
+
+    /// External ports to connect at startup, one entry per port
+    /// (extra entries on a mono channel all use its single port)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connect: Vec<String>,
+
+    /// Optional tap point; adds a "{port}_tap" output port per port
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tap: Option<TapPoint>,
+
+    /// Outputs this input feeds (inputs only; omitted = every output at 0 dB)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routes: Option<Vec<RouteConfig>>,
+
+    /// How inputs are combined on this output (outputs only)
+    #[serde(default, skip_serializing_if = "MixMode::is_sum")]
+    pub mix_mode: MixMode,
+
+    /// Input channel names that take over in `priority` mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub priority_inputs: Vec<String>,
+
+    /// Level (dBFS, post-fader) above which a priority input counts as active
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_threshold_db: Option<f32>,
+
+    /// Three-band EQ (inputs only; omitted = flat)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eq: Option<EqConfig>,
+
+    /// Sum L+R (-3 dB) to both ports, e.g. for mono PA sends (stereo outputs only)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mono: bool,
+
+    /// Meter and level the mid (L+R) and side (L-R) signals instead of left
+    /// and right (stereo channels only)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mid_side: bool,
+
+    /// Level of the mid in mid/side mode in dB, saved on exit (omitted = 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mid_db: Option<f32>,
+
+    /// Level of the side in mid/side mode in dB, saved on exit (omitted = 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side_db: Option<f32>,
+
+    /// Where the meters take their level: the port signal after trim
+    /// (`pre_fader`) or what reaches the buses after fader, mute and solo
+    /// (`post_fader`); omitted = the global `meter_point` (inputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meter_point: Option<TapPoint>,
+
+    /// Seconds below the signal threshold after which the strip warns, for
+    /// channels that should always carry signal (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub silence_warning_seconds: Option<f32>,
+
+    /// Automatic trim when the bus overloads (outputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_trim: Option<AutoTrimConfig>,
+
+    /// Lookahead brickwall limiter at the end of the bus (outputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limiter: Option<LimiterConfig>,
+
+    /// Three-band compressor after the fader, ahead of the limiter (outputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiband: Option<MultibandConfig>,
+
+    /// Tanh soft clipper after the fader, ahead of the limiter (outputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soft_clip: Option<SoftClipConfig>,
+
+    /// Milliseconds the bus is held back at its very end, e.g. to keep a
+    /// monitor in sync with late video (outputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<f32>,
+
+    /// Play the soloed inputs here over the dimmed program while any input
+    /// is soloed, leaving the other outputs alone (outputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solo_cue: Option<SoloCueConfig>,
+
+    /// Processing graph run ahead of the EQ and fader (inputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dsp: Option<DspGraphConfig>,
+
+    /// De-esser after the EQ (inputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deesser: Option<DeEsserConfig>,
+
+    /// Dip the channel on sudden loud events, e.g. a dropped mic or a
+    /// feedback squeal (inputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protect: Option<ProtectConfig>,
+
+    /// Output channel names fed this input as captured, skipping its graph,
+    /// EQ and de-esser, e.g. the performer's headphone mix (inputs only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub monitor_outputs: Vec<String>,
+
+    /// How volume key steps map to dB on this fader (omitted = `fader_taper`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub taper: Option<FaderTaper>,
+
+    /// Highest volume in dB the fader can be set to by keys, scenes, the
+    /// control socket or the schedule (omitted = +12)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_volume_db: Option<f32>,
+
+    /// Other source port sets the channel can switch to live, e.g. a backup
+    /// microphone; `connect` is the primary source (inputs only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<AlternateSource>,
+
+    /// Puts the input in the gain-sharing automixer group with this weight
+    /// in dB; a higher weight wins a larger share of the gain (inputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub automix_weight_db: Option<f32>,
+
+    /// Capture device whose own volume and mute the strip controls through
+    /// PipeWire, like pavucontrol (inputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_gain: Option<DeviceGainConfig>,
+
+    /// Source (as listed by `pactl list short sources`) whose own peaks the
+    /// big meter shows next to the mixer's, to tell clipping in the device
+    /// from clipping in the mixer (inputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_meter: Option<String>,
+
+    /// Created for an application stream while running; never saved
+    #[serde(skip)]
+    pub temporary: bool,
+}
+
+/// Hardware gain of the capture device behind an input, set through
+/// `pactl` on the device's PipeWire (or PulseAudio) source
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DeviceGainConfig {
+    /// Source name as listed by `pactl list short sources`
+    pub source: String,
+
+    /// Device volume in dB set at startup (optional; omitted = left as the
+    /// device has it; saved on exit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_db: Option<f32>,
 }
 
-impl ChannelConfig {
-    /// Returns true if this is a stereo channel (2 ports)
-    pub fn is_stereo(&self) -> bool {
-        self.ports.len() >= 2
-    }
+/// Named set of external ports an input can take its signal from instead
+/// of its `connect` list
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AlternateSource {
+    /// Name shown on the strip while this source is active
+    pub name: String,
 
-    /// Returns the number of ports (1 for mono, 2 for stereo)
-    pub fn port_count(&self) -> usize {
-        self.ports.len().min(2)
-    }
+    /// External ports to connect, one entry per port (as in `connect`)
+    pub connect: Vec<String>,
 }
 
-impl Config {
-    /// Load configuration from a YAML file
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref();
-        let contents = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+/// Multichannel passthrough: each input port feeds the output port at the
+/// same position through one shared fader, without any stereo assumptions
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PassthroughConfig {
+    /// Display name
+    pub name: String,
 
-        let mut config: Config = serde_yaml::from_str(&contents)
-            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+    /// Input port names, in channel order
+    pub inputs: Vec<String>,
 
-        config.config_path = Some(path.to_string_lossy().to_string());
-        config.validate()?;
-        Ok(config)
+    /// Output port names, one per input port
+    pub outputs: Vec<String>,
+
+    /// Volume level in dB (optional, defaults to 0.0)
+    #[serde(default)]
+    pub volume_db: Option<f32>,
+
+    /// Whether the channel is muted, saved on exit (defaults to false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub muted: bool,
+
+    /// External ports to connect to the inputs, one entry per port
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connect_inputs: Vec<String>,
+
+    /// External ports to connect the outputs to, one entry per port
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connect_outputs: Vec<String>,
+}
+
+/// Send from an input channel to an output channel
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RouteConfig {
+    /// Output channel name
+    pub output: String,
+
+    /// Send level in dB (defaults to 0.0)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub gain_db: f32,
+
+    /// Fold a stereo input down to mono on this output
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mono: bool,
+}
+
+fn is_zero(value: &f32) -> bool {
+    *value == 0.0
+}
+
+/// MIDI control input port
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MidiConfig {
+    /// MIDI input port name (defaults to "midi_in")
+    #[serde(default = "default_midi_port")]
+    pub port: String,
+
+    /// External MIDI ports to connect at startup
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connect: Vec<String>,
+
+    /// Only listen on this MIDI channel (1-16; omitted = all channels)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u8>,
+}
+
+fn default_midi_port() -> String {
+    "midi_in".to_string()
+}
+
+/// A stored set of channel settings
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SceneConfig {
+    /// Scene name
+    pub name: String,
+
+    /// MIDI program number (0-127) that recalls this scene
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub program: Option<u8>,
+
+    /// Bank select (CC 0) value the program must arrive in (omitted = any bank)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bank: Option<u8>,
+
+    /// Settings per input channel name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub inputs: BTreeMap<String, SceneChannel>,
+
+    /// Settings per output channel name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outputs: BTreeMap<String, SceneChannel>,
+
+    /// Master volume in dB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub master_volume_db: Option<f32>,
+}
+
+impl SceneConfig {
+    /// Whether a program change recalls this scene
+    pub fn matches_program(&self, bank: u8, program: u8) -> bool {
+        self.program == Some(program) && self.bank.is_none_or(|b| b == bank)
     }
-    
-    /// Save configuration to a YAML file
-    pub fn save(&self) -> Result<()> {
-        if let Some(ref path) = self.config_path {
-            let contents = serde_yaml::to_string(self)
-                .context("Failed to serialize config")?;
-            fs::write(path, contents)
-                .with_context(|| format!("Failed to write config file: {}", path))?;
+}
+
+/// Channel settings stored in a scene (unset values are left alone)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct SceneChannel {
+    /// Volume in dB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_db: Option<f32>,
+
+    /// Mute state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muted: Option<bool>,
+}
+
+/// In-memory replay buffer of one output bus
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ReplayConfig {
+    /// Output channel name to record (post-fader)
+    pub output: String,
+
+    /// Seconds of audio to keep (defaults to 60)
+    #[serde(default = "default_replay_seconds")]
+    pub seconds: f32,
+
+    /// Directory for saved WAV files (defaults to the working directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+}
+
+fn default_replay_seconds() -> f32 {
+    60.0
+}
+
+/// Stereo recorder of one output bus
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RecorderConfig {
+    /// Output channel name to record (post-fader; a mono output is doubled,
+    /// a surround output gives its first two ports)
+    pub output: String,
+
+    /// File format of finished recordings (defaults to wav)
+    #[serde(default)]
+    pub format: RecordFormat,
+
+    /// Directory for recordings (defaults to the working directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<String>,
+
+    /// Integrated loudness in LUFS the finished file is brought to (optional,
+    /// e.g. -16 for podcasts; omitted = recorded level)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalize_lufs: Option<f32>,
+
+    /// Sample peak in dBFS normalization may not push the file above (defaults to -1)
+    #[serde(default = "default_recorder_ceiling")]
+    pub ceiling_db: f32,
+
+    /// Encoder bitrate in kbit/s (defaults to 192 for mp3 and 96 for opus)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bitrate_kbps: Option<u32>,
+
+    /// Only record while the output carries signal (optional; omitted =
+    /// record everything)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<RecordTriggerConfig>,
+
+    /// Switch the JACK server to freewheel mode while recording, so a bounce
+    /// from a player or DAW runs faster than real time (the sound card stays
+    /// silent meanwhile)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub freewheel: bool,
+}
+
+fn default_recorder_ceiling() -> f32 {
+    -1.0
+}
+
+/// Signal-triggered recording: the take runs while the recorded output is
+/// above the threshold and pauses after a stretch of silence
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct RecordTriggerConfig {
+    /// Sample peak in dBFS that starts recording (defaults to -40)
+    #[serde(default = "default_trigger_threshold")]
+    pub threshold_db: f32,
+
+    /// Seconds below the threshold before recording pauses (defaults to 5)
+    #[serde(default = "default_trigger_hold")]
+    pub hold_seconds: f32,
+
+    /// Seconds before the trigger kept and written when recording resumes
+    /// (defaults to 1)
+    #[serde(default = "default_trigger_pre_roll")]
+    pub pre_roll_seconds: f32,
+}
+
+fn default_trigger_threshold() -> f32 {
+    -40.0
+}
+
+fn default_trigger_hold() -> f32 {
+    5.0
+}
+
+fn default_trigger_pre_roll() -> f32 {
+    1.0
+}
+
+impl Default for RecordTriggerConfig {
+    fn default() -> Self {
+        Self {
+            threshold_db: default_trigger_threshold(),
+            hold_seconds: default_trigger_hold(),
+            pre_roll_seconds: default_trigger_pre_roll(),
         }
-        Ok(())
     }
-    
-    /// Update volume levels from mixer state
-    pub fn update_volumes(&mut self, input_volumes: &[f32], output_volumes: &[f32]) {
-        for (i, vol) in input_volumes.iter().enumerate() {
-            if i < self.inputs.len() {
-                self.inputs[i].volume_db = Some(*vol);
-            }
-        }
-        for (i, vol) in output_volumes.iter().enumerate() {
-            if i < self.outputs.len() {
-                self.outputs[i].volume_db = Some(*vol);
-            }
+}
+
+/// File format of finished recordings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordFormat {
+    /// 32-bit float WAV
+    #[default]
+    Wav,
+
+    /// MP3, encoded by ffmpeg
+    Mp3,
+
+    /// Opus in an Ogg container, encoded by ffmpeg
+    Opus,
+}
+
+impl RecordFormat {
+    /// File name extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RecordFormat::Wav => "wav",
+            RecordFormat::Mp3 => "mp3",
+            RecordFormat::Opus => "opus",
         }
     }
+}
 
-    /// Validate the configuration
-    fn validate(&self) -> Result<()> {
-        if self.client_name.is_empty() {
-            anyhow::bail!("client_name cannot be empty");
-        }
+/// Channel EQ: three fixed bands plus optional parametric bands
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct EqConfig {
+    /// Low shelf (100 Hz) gain in dB
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub low_db: f32,
 
-        if self.inputs.is_empty() {
-            anyhow::bail!("At least one input channel is required");
-        }
+    /// Mid peak (1 kHz) gain in dB
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub mid_db: f32,
 
-        if self.outputs.is_empty() {
-            anyhow::bail!("At least one output channel is required");
-        }
+    /// High shelf (10 kHz) gain in dB
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub high_db: f32,
 
-        for (i, input) in self.inputs.iter().enumerate() {
-            if input.name.is_empty() {
-                anyhow::bail!("Input channel {} has empty name", i);
-            }
-            if input.ports.is_empty() {
-                anyhow::bail!("Input channel '{}' has no ports defined", input.name);
-            }
-            if input.ports.len() > 2 {
-                anyhow::bail!(
-                    "Input channel '{}' has {} ports, max 2 supported",
-                    input.name,
-                    input.ports.len()
-                );
-            }
-        }
+    /// Parametric (peaking) bands, applied after the fixed bands
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bands: Vec<EqBandConfig>,
 
-        for (i, output) in self.outputs.iter().enumerate() {
-            if output.name.is_empty() {
-                anyhow::bail!("Output channel {} has empty name", i);
-            }
-            if output.ports.is_empty() {
-                anyhow::bail!("Output channel '{}' has no ports defined", output.name);
-            }
-            if output.ports.len() > 2 {
-                anyhow::bail!(
-                    "Output channel '{}' has {} ports, max 2 supported",
-                    output.name,
-                    output.ports.len()
-                );
-            }
+    /// High-pass filter ahead of the bands, e.g. 80-120 Hz on mics
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_pass: Option<PassFilterConfig>,
+
+    /// Low-pass filter ahead of the bands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_pass: Option<PassFilterConfig>,
+}
+
+impl EqConfig {
+    /// Whether every band is at 0 dB
+    pub fn is_flat(&self) -> bool {
+        self.low_db == 0.0
+            && self.mid_db == 0.0
+            && self.high_db == 0.0
+            && self.bands.iter().all(|b| b.gain_db == 0.0)
+    }
+
+    /// Gain of one band
+    pub fn band_mut(&mut self, band: EqBand) -> &mut f32 {
+        match band {
+            EqBand::Low => &mut self.low_db,
+            EqBand::Mid => &mut self.mid_db,
+            EqBand::High => &mut self.high_db,
         }
+    }
 
-        Ok(())
+    /// Settings of a high- or low-pass filter
+    pub fn pass_filter(&self, filter: PassFilter) -> Option<PassFilterConfig> {
+        match filter {
+            PassFilter::HighPass => self.high_pass,
+            PassFilter::LowPass => self.low_pass,
+        }
     }
 
-    /// Get total number of input ports
-    pub fn total_input_ports(&self) -> usize {
-        self.inputs.iter().map(|c| c.port_count()).sum()
+    /// Settings of a high- or low-pass filter, for changing
+    pub fn pass_filter_mut(&mut self, filter: PassFilter) -> &mut Option<PassFilterConfig> {
+        match filter {
+            PassFilter::HighPass => &mut self.high_pass,
+            PassFilter::LowPass => &mut self.low_pass,
+        }
     }
 
-    /// Get total number of output ports
-    pub fn total_output_ports(&self) -> usize {
-        self.outputs.iter().map(|c| c.port_count()).sum()
+    /// Whether the cutoffs of the pass filters are within `EQ_FREQ_RANGE`
+    fn pass_filters_in_range(&self) -> bool {
+        [self.high_pass, self.low_pass]
+            .iter()
+            .flatten()
+            .all(|f| (EQ_FREQ_RANGE.0..=EQ_FREQ_RANGE.1).contains(&f.freq))
     }
 }
 
-#[cfg(test)]
-mod tests {
+/// High- or low-pass filter of a channel EQ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassFilter {
+    HighPass,
+    LowPass,
+}
+
+impl PassFilter {
+    /// Cutoff used when the filter is switched on without settings
+    pub fn default_freq(self) -> f32 {
+        match self {
+            Self::HighPass => DEFAULT_HIGH_PASS_HZ,
+            Self::LowPass => DEFAULT_LOW_PASS_HZ,
+        }
+    }
+}
+
+/// High- or low-pass filter settings (12 dB/octave Butterworth)
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct PassFilterConfig {
+    /// Cutoff frequency in Hz
+    pub freq: f32,
+
+    /// Whether the filter is switched in (defaults to true; a filter
+    /// switched off keeps its cutoff)
+    #[serde(default = "default_filter_enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+}
+
+fn default_filter_enabled() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+/// Band of the three-band EQ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EqBand {
+    Low,
+    Mid,
+    High,
+}
+
+/// One parametric (peaking) EQ band
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct EqBandConfig {
+    /// Center frequency in Hz
+    pub freq: f32,
+
+    /// Gain in dB
+    #[serde(default)]
+    pub gain_db: f32,
+
+    /// Quality factor (higher is narrower, defaults to 1)
+    #[serde(default = "default_eq_q")]
+    pub q: f32,
+}
+
+impl Default for EqBandConfig {
+    fn default() -> Self {
+        Self {
+            freq: 1_000.0,
+            gain_db: 0.0,
+            q: default_eq_q(),
+        }
+    }
+}
+
+fn default_eq_q() -> f32 {
+    1.0
+}
+
+/// Processing graph of an input channel
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct DspGraphConfig {
+    /// Processing nodes
+    pub nodes: Vec<DspNodeConfig>,
+
+    /// Connections as `[from, to]` node names, where `input` is the channel's
+    /// ports and `output` its feed into the mix (omitted = nodes in order)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edges: Vec<(String, String)>,
+}
+
+/// One node of a processing graph
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DspNodeConfig {
+    /// Node name, referenced by the edges
+    pub name: String,
+
+    /// Processing done by the node
+    #[serde(flatten)]
+    pub kind: DspNodeKind,
+}
+
+/// Processing done by a graph node
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DspNodeKind {
+    /// Fixed gain
+    Gain { gain_db: f32 },
+
+    /// Equalizer (fixed and parametric bands)
+    Eq(EqConfig),
+
+    /// Noise gate: silences the signal below the threshold
+    Gate {
+        threshold_db: f32,
+        #[serde(default = "default_gate_attack")]
+        attack_ms: f32,
+        #[serde(default = "default_dynamics_release")]
+        release_ms: f32,
+    },
+
+    /// Compressor: reduces the level above the threshold by the ratio
+    Comp {
+        threshold_db: f32,
+        ratio: f32,
+        #[serde(default = "default_comp_attack")]
+        attack_ms: f32,
+        #[serde(default = "default_dynamics_release")]
+        release_ms: f32,
+        #[serde(default, skip_serializing_if = "is_zero")]
+        makeup_db: f32,
+    },
+
+    /// Copies the signal to extra output ports (one per channel port) and
+    /// passes it on unchanged
+    Send { ports: Vec<String> },
+}
+
+/// Where a graph node takes its signal from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DspSource {
+    /// The channel's input ports
+    Input,
+
+    /// The output of the node with this index
+    Node(usize),
+}
+
+/// A processing graph resolved into execution order
+#[derive(Debug, Clone, PartialEq)]
+pub struct DspSchedule {
+    /// Node indices in execution order, each with the sources summed into it
+    pub steps: Vec<(usize, Vec<DspSource>)>,
+
+    /// Sources summed into the channel's feed to the mix
+    pub output: Vec<DspSource>,
+}
+
+impl DspGraphConfig {
+    /// Check node settings and resolve the edges for a channel with `port_count` ports
+    pub fn validate(&self, port_count: usize) -> Result<()> {
+        for node in &self.nodes {
+            let valid = match &node.kind {
+                DspNodeKind::Gain { gain_db } => (-60.0..=24.0).contains(gain_db),
+                DspNodeKind::Eq(eq) => {
+                    eq.bands.len() <= MAX_EQ_BANDS
+                        && [eq.low_db, eq.mid_db, eq.high_db]
+                            .iter()
+                            .chain(eq.bands.iter().map(|b| &b.gain_db))
+                            .all(|g| (-EQ_RANGE_DB..=EQ_RANGE_DB).contains(g))
+                        && eq.bands.iter().all(|b| {
+                            (EQ_FREQ_RANGE.0..=EQ_FREQ_RANGE.1).contains(&b.freq)
+                                && (EQ_Q_RANGE.0..=EQ_Q_RANGE.1).contains(&b.q)
+                        })
+                        && eq.pass_filters_in_range()
+                }
+                DspNodeKind::Gate {
+                    threshold_db,
+                    attack_ms,
+                    release_ms,
+                } => *threshold_db <= 0.0 && *attack_ms > 0.0 && *release_ms > 0.0,
+                DspNodeKind::Comp {
+                    threshold_db,
+                    ratio,
+                    attack_ms,
+                    release_ms,
+                    makeup_db,
+                } => {
+                    *threshold_db <= 0.0
+                        && *ratio >= 1.0
+                        && *attack_ms > 0.0
+                        && *release_ms > 0.0
+                        && (0.0..=24.0).contains(makeup_db)
+                }
+                DspNodeKind::Send { ports } => {
+                    if ports.len() != port_count {
+                        anyhow::bail!(
+                            "send node '{}' needs {} ports, one per channel port",
+                            node.name,
+                            port_count
+                        );
+                    }
+                    true
+                }
+            };
+            if !valid {
+                anyhow::bail!("node '{}' has invalid settings", node.name);
+            }
+        }
+        self.schedule().map(|_| ())
+    }
+
+    /// Resolve the edges into execution order
+    pub fn schedule(&self) -> Result<DspSchedule> {
+        let mut names: Vec<&str> = Vec::new();
+        for node in &self.nodes {
+            if node.name.is_empty() || node.name == "input" || node.name == "output" {
+                anyhow::bail!("node name '{}' is reserved or empty", node.name);
+            }
+            if names.contains(&node.name.as_str()) {
+                anyhow::bail!("node '{}' is defined more than once", node.name);
+            }
+            names.push(&node.name);
+        }
+
+        // Without edges the nodes form a chain in the order given
+        let chain: Vec<(String, String)>;
+        let edges = if self.edges.is_empty() {
+            let order: Vec<&str> = std::iter::once("input")
+                .chain(names.iter().copied())
+                .chain(std::iter::once("output"))
+                .collect();
+            chain = order
+                .windows(2)
+                .map(|w| (w[0].to_string(), w[1].to_string()))
+                .collect();
+            &chain
+        } else {
+            &self.edges
+        };
+
+        // Incoming sources per node, and those of the output
+        let mut sources: Vec<Vec<DspSource>> = vec![Vec::new(); names.len()];
+        let mut output = Vec::new();
+        let mut feeds_something = vec![false; names.len()];
+        for (from, to) in edges {
+            let source = match from.as_str() {
+                "input" => DspSource::Input,
+                name => match names.iter().position(|n| *n == name) {
+                    Some(i) => {
+                        feeds_something[i] = true;
+                        DspSource::Node(i)
+                    }
+                    None => anyhow::bail!("edge from unknown node '{}'", from),
+                },
+            };
+            match to.as_str() {
+                "output" => output.push(source),
+                name => match names.iter().position(|n| *n == name) {
+                    Some(i) => sources[i].push(source),
+                    None => anyhow::bail!("edge to unknown node '{}'", to),
+                },
+            }
+        }
+        if output.is_empty() {
+            anyhow::bail!("nothing is connected to 'output'");
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            if sources[i].is_empty() {
+                anyhow::bail!("node '{}' has no incoming edge", node.name);
+            }
+            if !feeds_something[i] && !matches!(node.kind, DspNodeKind::Send { .. }) {
+                anyhow::bail!("node '{}' leads nowhere", node.name);
+            }
+        }
+
+        // Topological order: a node runs once all of its sources have run
+        let mut steps = Vec::with_capacity(names.len());
+        let mut done = vec![false; names.len()];
+        while steps.len() < names.len() {
+            let ready = (0..names.len()).find(|&i| {
+                !done[i]
+                    && sources[i].iter().all(|s| match s {
+                        DspSource::Input => true,
+                        DspSource::Node(j) => done[*j],
+                    })
+            });
+            let Some(i) = ready else {
+                anyhow::bail!("edges form a cycle");
+            };
+            done[i] = true;
+            steps.push((i, std::mem::take(&mut sources[i])));
+        }
+
+        Ok(DspSchedule { steps, output })
+    }
+}
+
+fn default_gate_attack() -> f32 {
+    1.0
+}
+
+fn default_comp_attack() -> f32 {
+    10.0
+}
+
+fn default_dynamics_release() -> f32 {
+    100.0
+}
+
+/// Automatic trim of an output bus on sustained overload
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AutoTrimConfig {
+    /// Post-fader peak level in dBFS the bus should stay under (defaults to -1)
+    #[serde(default = "default_auto_trim_ceiling")]
+    pub ceiling_db: f32,
+
+    /// Milliseconds above the ceiling before trim is reduced (defaults to 50)
+    #[serde(default = "default_auto_trim_hold")]
+    pub hold_ms: f32,
+
+    /// Reduction per step in dB (defaults to 1)
+    #[serde(default = "default_auto_trim_step")]
+    pub step_db: f32,
+
+    /// Maximum total reduction in dB (defaults to 12)
+    #[serde(default = "default_auto_trim_max")]
+    pub max_reduction_db: f32,
+
+    /// Recovery speed in dB per second once the bus is quiet (defaults to 0.5)
+    #[serde(default = "default_auto_trim_release")]
+    pub release_db_per_s: f32,
+}
+
+fn default_auto_trim_ceiling() -> f32 {
+    -1.0
+}
+
+fn default_auto_trim_hold() -> f32 {
+    50.0
+}
+
+fn default_auto_trim_step() -> f32 {
+    1.0
+}
+
+fn default_auto_trim_max() -> f32 {
+    12.0
+}
+
+fn default_auto_trim_release() -> f32 {
+    0.5
+}
+
+/// Lookahead brickwall limiter on an output bus
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LimiterConfig {
+    /// Highest peak level in dBFS let through (defaults to -1)
+    #[serde(default = "default_limiter_ceiling")]
+    pub ceiling_db: f32,
+
+    /// Milliseconds the bus is delayed so gain can drop ahead of a peak (defaults to 5)
+    #[serde(default = "default_limiter_lookahead")]
+    pub lookahead_ms: f32,
+
+    /// Milliseconds for the gain to recover after a peak (defaults to 80)
+    #[serde(default = "default_limiter_release")]
+    pub release_ms: f32,
+}
+
+impl Default for LimiterConfig {
+    fn default() -> Self {
+        Self {
+            ceiling_db: default_limiter_ceiling(),
+            lookahead_ms: default_limiter_lookahead(),
+            release_ms: default_limiter_release(),
+        }
+    }
+}
+
+fn default_limiter_ceiling() -> f32 {
+    -1.0
+}
+
+fn default_limiter_lookahead() -> f32 {
+    5.0
+}
+
+fn default_limiter_release() -> f32 {
+    80.0
+}
+
+/// Soft clipper on an output bus
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct SoftClipConfig {
+    /// Gain into the clipping curve in dB, taken off again afterwards; the
+    /// output never exceeds ceiling_db - drive_db dBFS (defaults to 0)
+    #[serde(default)]
+    pub drive_db: f32,
+
+    /// Level the curve saturates towards in dBFS before the drive is taken
+    /// off (defaults to 0)
+    #[serde(default)]
+    pub ceiling_db: f32,
+
+    /// Shape of the curve (defaults to tanh)
+    #[serde(default)]
+    pub curve: ClipCurve,
+
+    /// Rate the curve runs at, as a multiple of the sample rate, to keep the
+    /// added harmonics from folding back (1, 2, 4 or 8; defaults to 1)
+    #[serde(default = "default_soft_clip_oversampling")]
+    pub oversampling: u32,
+}
+
+impl Default for SoftClipConfig {
+    fn default() -> Self {
+        Self {
+            drive_db: 0.0,
+            ceiling_db: 0.0,
+            curve: ClipCurve::default(),
+            oversampling: default_soft_clip_oversampling(),
+        }
+    }
+}
+
+fn default_soft_clip_oversampling() -> u32 {
+    1
+}
+
+impl SoftClipConfig {
+    /// Check the settings are usable, naming the offending value
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=MAX_SOFT_CLIP_DRIVE_DB).contains(&self.drive_db) {
+            anyhow::bail!("drive_db must be within 0-{} dB", MAX_SOFT_CLIP_DRIVE_DB);
+        }
+        if !(MIN_SOFT_CLIP_CEILING_DB..=0.0).contains(&self.ceiling_db) {
+            anyhow::bail!(
+                "ceiling_db must be within {} to 0 dBFS",
+                MIN_SOFT_CLIP_CEILING_DB
+            );
+        }
+        if !SOFT_CLIP_OVERSAMPLING.contains(&self.oversampling) {
+            anyhow::bail!("oversampling must be one of {:?}", SOFT_CLIP_OVERSAMPLING);
+        }
+        Ok(())
+    }
+}
+
+/// Soft clipper curves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipCurve {
+    /// Hyperbolic tangent: rounds off early and approaches the ceiling slowly
+    #[default]
+    Tanh,
+
+    /// Cubic: stays closer to linear, then bends over and reaches the ceiling
+    Cubic,
+}
+
+/// Three-band compressor on an output bus: the bus is split at two crossover
+/// frequencies and each band is compressed on its own before summing again
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct MultibandConfig {
+    /// Whether the compressor runs (defaults to true)
+    #[serde(default = "default_filter_enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+
+    /// Crossovers between low and mid and between mid and high in Hz
+    /// (defaults to 200 and 3000)
+    #[serde(default = "default_multiband_crossovers")]
+    pub crossovers_hz: [f32; 2],
+
+    /// Envelope attack time in ms, shared by the bands (defaults to 10)
+    #[serde(default = "default_comp_attack")]
+    pub attack_ms: f32,
+
+    /// Envelope release time in ms, shared by the bands (defaults to 100)
+    #[serde(default = "default_dynamics_release")]
+    pub release_ms: f32,
+
+    /// Low, mid and high band settings (default to gentle 2:1 above -20 dBFS)
+    #[serde(default)]
+    pub bands: [MultibandBandConfig; MULTIBAND_BANDS],
+}
+
+impl Default for MultibandConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            crossovers_hz: default_multiband_crossovers(),
+            attack_ms: default_comp_attack(),
+            release_ms: default_dynamics_release(),
+            bands: Default::default(),
+        }
+    }
+}
+
+fn default_multiband_crossovers() -> [f32; 2] {
+    [200.0, 3_000.0]
+}
+
+/// Compressor settings of one multiband band
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct MultibandBandConfig {
+    /// Band level in dBFS above which the band is compressed (defaults to -20)
+    #[serde(default = "default_multiband_threshold")]
+    pub threshold_db: f32,
+
+    /// Compression ratio above the threshold (defaults to 2)
+    #[serde(default = "default_multiband_ratio")]
+    pub ratio: f32,
+
+    /// Gain added to the band after compression in dB (defaults to 0)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub makeup_db: f32,
+}
+
+impl Default for MultibandBandConfig {
+    fn default() -> Self {
+        Self {
+            threshold_db: default_multiband_threshold(),
+            ratio: default_multiband_ratio(),
+            makeup_db: 0.0,
+        }
+    }
+}
+
+fn default_multiband_threshold() -> f32 {
+    -20.0
+}
+
+fn default_multiband_ratio() -> f32 {
+    2.0
+}
+
+impl MultibandConfig {
+    /// Check the settings are usable, naming the offending value
+    pub fn validate(&self) -> Result<()> {
+        let [low, high] = self.crossovers_hz;
+        if !(EQ_FREQ_RANGE.0..=EQ_FREQ_RANGE.1).contains(&low)
+            || !(EQ_FREQ_RANGE.0..=EQ_FREQ_RANGE.1).contains(&high)
+            || low >= high
+        {
+            anyhow::bail!(
+                "crossovers_hz must rise within {}-{} Hz",
+                EQ_FREQ_RANGE.0,
+                EQ_FREQ_RANGE.1
+            );
+        }
+        if !(self.attack_ms > 0.0 && self.release_ms > 0.0) {
+            anyhow::bail!("attack_ms and release_ms must be above 0");
+        }
+        for band in &self.bands {
+            if !(MULTIBAND_THRESHOLD_RANGE.0..=MULTIBAND_THRESHOLD_RANGE.1)
+                .contains(&band.threshold_db)
+            {
+                anyhow::bail!(
+                    "band threshold_db must be within {} to {} dB",
+                    MULTIBAND_THRESHOLD_RANGE.0,
+                    MULTIBAND_THRESHOLD_RANGE.1
+                );
+            }
+            if !(MULTIBAND_RATIO_RANGE.0..=MULTIBAND_RATIO_RANGE.1).contains(&band.ratio) {
+                anyhow::bail!(
+                    "band ratio must be within {} to {}",
+                    MULTIBAND_RATIO_RANGE.0,
+                    MULTIBAND_RATIO_RANGE.1
+                );
+            }
+            if band.makeup_db.abs() > MULTIBAND_MAKEUP_RANGE_DB {
+                anyhow::bail!("band makeup_db must be within ±{} dB", MULTIBAND_MAKEUP_RANGE_DB);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// De-esser: compressor acting on the sibilance band of a voice channel
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DeEsserConfig {
+    /// Band level in dBFS above which the band is reduced (defaults to -30)
+    #[serde(default = "default_deesser_threshold")]
+    pub threshold_db: f32,
+
+    /// Center frequency of the sibilance band in Hz (defaults to 6000)
+    #[serde(default = "default_deesser_freq")]
+    pub freq: f32,
+
+    /// Most reduction of the band in dB (defaults to 12)
+    #[serde(default = "default_deesser_max_reduction")]
+    pub max_reduction_db: f32,
+}
+
+fn default_deesser_threshold() -> f32 {
+    -30.0
+}
+
+fn default_deesser_freq() -> f32 {
+    6_000.0
+}
+
+fn default_deesser_max_reduction() -> f32 {
+    12.0
+}
+
+/// Protective dip of an input: a peak over the threshold that jumps far
+/// above the channel's recent level turns it down for a moment
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ProtectConfig {
+    /// Peak in dBFS after the trim at or above which an event can fire
+    /// (defaults to -6)
+    #[serde(default = "default_protect_threshold")]
+    pub threshold_db: f32,
+
+    /// How far in dB the peak must jump over the recent RMS level
+    /// (defaults to 20)
+    #[serde(default = "default_protect_rise")]
+    pub rise_db: f32,
+
+    /// How far in dB the channel is turned down (defaults to 20)
+    #[serde(default = "default_protect_dip")]
+    pub dip_db: f32,
+
+    /// Milliseconds the dip holds once the channel is below the threshold
+    /// again (defaults to 250)
+    #[serde(default = "default_protect_hold")]
+    pub hold_ms: f32,
+
+    /// Milliseconds to recover from the full dip (defaults to 500)
+    #[serde(default = "default_protect_release")]
+    pub release_ms: f32,
+}
+
+fn default_protect_threshold() -> f32 {
+    -6.0
+}
+
+fn default_protect_rise() -> f32 {
+    20.0
+}
+
+fn default_protect_dip() -> f32 {
+    20.0
+}
+
+fn default_protect_hold() -> f32 {
+    250.0
+}
+
+fn default_protect_release() -> f32 {
+    500.0
+}
+
+/// Solo cue of a monitor output, as on a console with solo-to-monitor
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct SoloCueConfig {
+    /// Level of the output's own program under the soloed inputs in dB
+    /// (defaults to -20; -60 silences it)
+    #[serde(default = "default_solo_dim")]
+    pub dim_db: f32,
+}
+
+fn default_solo_dim() -> f32 {
+    -20.0
+}
+
+/// Reminders for solo and mute left engaged
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ReminderConfig {
+    /// Minutes any input may stay soloed before a reminder (omitted = never)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solo_minutes: Option<f32>,
+
+    /// Minutes an input may stay muted while its source has signal before a
+    /// reminder (omitted = never)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mute_minutes: Option<f32>,
+
+    /// Source level in dBFS that counts as signal (defaults to -50)
+    #[serde(default = "default_reminder_threshold")]
+    pub signal_threshold_db: f32,
+}
+
+fn default_reminder_threshold() -> f32 {
+    -50.0
+}
+
+/// On-air tally: an input is on air while it is audible and has signal
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TallyConfig {
+    /// Source level in dBFS that counts as signal (defaults to -50)
+    #[serde(default = "default_reminder_threshold")]
+    pub signal_threshold_db: f32,
+
+    /// Serial device (or any file) that gets a `tally 1 0 ...` line on every
+    /// change, one flag per input (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial: Option<String>,
+
+    /// GPIO value files by input name, written `1` while the input is on air
+    /// and `0` otherwise (optional)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub gpio: BTreeMap<String, String>,
+}
+
+/// A control command run at set times
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ScheduleEntry {
+    /// Cron expression in local time: `minute hour day-of-month month
+    /// day-of-week`, e.g. `0 22 * * *` for every day at 22:00
+    pub when: String,
+
+    /// Control socket command that changes the mixer, e.g.
+    /// `volume input Music -30`
+    pub command: String,
+
+    /// Spread a `volume` or `adjust` change over this many seconds (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fade_seconds: Option<f32>,
+}
+
+impl ScheduleEntry {
+    /// Check the time expression and the command
+    pub fn validate(&self) -> Result<()> {
+        crate::scheduler::CronSpec::parse(&self.when).map_err(anyhow::Error::msg)?;
+        let command = RemoteCommand::parse(&self.command).map_err(anyhow::Error::msg)?;
+        if !command.changes_mixer() {
+            anyhow::bail!("'{}' does not change the mixer", self.command);
+        }
+        if let Some(seconds) = self.fade_seconds {
+            if !matches!(command, RemoteCommand::Volume { .. }) {
+                anyhow::bail!("fade_seconds only applies to volume and adjust commands");
+            }
+            if !(seconds > 0.0 && seconds.is_finite()) {
+                anyhow::bail!("fade_seconds must be above 0");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a control socket client may do
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlAccess {
+    /// Every command
+    #[default]
+    Control,
+
+    /// Read the state and subscribe to meters and tally; nothing that
+    /// changes the mixer or writes files
+    Observe,
+}
+
+fn default_control_access() -> ControlAccess {
+    ControlAccess::Observe
+}
+
+/// Access of control socket clients by address. Clients on a Unix socket or
+/// standard input always have control; TCP clients get the access of the
+/// first rule matching their address, or else the default
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ControlAccessConfig {
+    /// Access of TCP clients no rule matches (defaults to observe)
+    #[serde(default = "default_control_access")]
+    pub default: ControlAccess,
+
+    /// Rules by client address, first match wins
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clients: Vec<ControlClientRule>,
+}
+
+impl ControlAccessConfig {
+    /// Check the rule addresses
+    pub fn validate(&self) -> Result<()> {
+        for rule in &self.clients {
+            rule.network()?;
+        }
+        Ok(())
+    }
+
+    /// Access of a client connected from `peer` (None for a Unix socket or
+    /// standard input)
+    pub fn access(&self, peer: Option<IpAddr>) -> ControlAccess {
+        let Some(peer) = peer else {
+            return ControlAccess::Control;
+        };
+        self.clients
+            .iter()
+            .find(|rule| rule.matches(peer))
+            .map_or(self.default, |rule| rule.access)
+    }
+}
+
+/// Access of the TCP clients connecting from an address or network
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ControlClientRule {
+    /// IP address or network in CIDR notation, e.g. `192.168.1.0/24`
+    pub address: String,
+
+    /// Access of matching clients
+    pub access: ControlAccess,
+}
+
+impl ControlClientRule {
+    /// Network address and prefix length
+    fn network(&self) -> Result<(IpAddr, u32)> {
+        let (address, prefix) = match self.address.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (self.address.as_str(), None),
+        };
+        let address: IpAddr = address
+            .parse()
+            .with_context(|| format!("Invalid control client address '{}'", self.address))?;
+        let bits = if address.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u32>()
+                .ok()
+                .filter(|p| *p <= bits)
+                .with_context(|| format!("Invalid prefix length in '{}'", self.address))?,
+            None => bits,
+        };
+        Ok((address, prefix))
+    }
+
+    /// Whether a client address lies in the rule's network
+    pub fn matches(&self, peer: IpAddr) -> bool {
+        let Ok((network, prefix)) = self.network() else {
+            return false;
+        };
+        // IPv4 clients of a dual-stack listener arrive as mapped addresses
+        let peer = match peer {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(peer, IpAddr::V4),
+            IpAddr::V4(_) => peer,
+        };
+        match (network, peer) {
+            (IpAddr::V4(network), IpAddr::V4(peer)) => {
+                let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+                u32::from(network) & mask == u32::from(peer) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(peer)) => {
+                let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+                u128::from(network) & mask == u128::from(peer) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Speaker level matching: pink noise is played through each output in turn
+/// and measured on an input
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CalibrationConfig {
+    /// Input channel name of the measurement microphone
+    pub input: String,
+
+    /// RMS level of the noise in dBFS, before the output fader (defaults to -20)
+    #[serde(default = "default_calibration_level")]
+    pub level_db: f32,
+
+    /// Seconds of noise per output, the first of which settles (defaults to 5)
+    #[serde(default = "default_calibration_seconds")]
+    pub seconds: f32,
+}
+
+fn default_calibration_level() -> f32 {
+    -20.0
+}
+
+fn default_calibration_seconds() -> f32 {
+    5.0
+}
+
+/// Metronome: a click on every beat, accented on the first of each bar,
+/// mixed into the listed outputs only
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ClickConfig {
+    /// Output channel names that get the click, e.g. headphone mixes
+    pub outputs: Vec<String>,
+
+    /// Beats per minute (defaults to 120; tap tempo changes it)
+    #[serde(default = "default_click_tempo")]
+    pub tempo_bpm: f32,
+
+    /// Beats in a bar (defaults to 4)
+    #[serde(default = "default_click_beats")]
+    pub beats_per_bar: u32,
+
+    /// Peak level of the click in dBFS, before the output fader (defaults to -12)
+    #[serde(default = "default_click_level")]
+    pub level_db: f32,
+
+    /// How much louder the first beat of a bar is in dB (defaults to 6)
+    #[serde(default = "default_click_accent")]
+    pub accent_db: f32,
+}
+
+fn default_click_tempo() -> f32 {
+    120.0
+}
+
+fn default_click_beats() -> u32 {
+    4
+}
+
+fn default_click_level() -> f32 {
+    -12.0
+}
+
+fn default_click_accent() -> f32 {
+    6.0
+}
+
+/// Inputs created on the fly for application streams: every client whose
+/// name matches `include` and not `exclude` gets an input of its own,
+/// connected to its ports, for as long as it plays
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AppStreamsConfig {
+    /// Client name patterns of the applications to take in, `*` matching
+    /// any text (defaults to every application)
+    #[serde(default = "default_app_stream_include")]
+    pub include: Vec<String>,
+
+    /// Client name patterns to leave alone (optional)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+
+    /// Outputs the new inputs feed (omitted = every output)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<String>,
+
+    /// Fader level a new input starts at in dB (defaults to 0)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub volume_db: f32,
+}
+
+fn default_app_stream_include() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+/// Fader motion curve: how a fader position from 0 (bottom) to 1 (top) maps
+/// to dB. Volume keys move the position, so the taper sets how large a step
+/// is in each part of the range.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum FaderTaper {
+    /// One of the built-in curves
+    Named(TaperCurve),
+
+    /// Piecewise linear through `[position, dB]` breakpoints
+    Custom(Vec<[f32; 2]>),
+}
+
+/// Built-in fader curves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaperCurve {
+    /// Equal dB steps over the whole range
+    #[default]
+    LinearDb,
+
+    /// Cubic gain law of analog faders: finer steps near the top,
+    /// coarser steps near the bottom
+    Audio,
+}
+
+/// Gain of the audio taper at the bottom of the fader (cubic law)
+fn audio_taper_floor() -> f32 {
+    10f32.powf((VOLUME_MIN_DB - VOLUME_MAX_DB) / 60.0)
+}
+
+impl Default for FaderTaper {
+    fn default() -> Self {
+        Self::Named(TaperCurve::LinearDb)
+    }
+}
+
+impl FaderTaper {
+    /// Whether this is the default equal-dB taper
+    pub fn is_linear_db(&self) -> bool {
+        *self == Self::Named(TaperCurve::LinearDb)
+    }
+
+    /// dB at a fader position (0-1)
+    pub fn position_to_db(&self, position: f32) -> f32 {
+        let position = position.clamp(0.0, 1.0);
+        let db = match self {
+            Self::Named(TaperCurve::LinearDb) => {
+                VOLUME_MIN_DB + position * (VOLUME_MAX_DB - VOLUME_MIN_DB)
+            }
+            Self::Named(TaperCurve::Audio) => {
+                let floor = audio_taper_floor();
+                VOLUME_MAX_DB + 60.0 * (floor + position * (1.0 - floor)).log10()
+            }
+            Self::Custom(points) => interpolate(points, position, 0, 1),
+        };
+        db.clamp(VOLUME_MIN_DB, VOLUME_MAX_DB)
+    }
+
+    /// Fader position (0-1) of a dB value
+    pub fn db_to_position(&self, db: f32) -> f32 {
+        let db = db.clamp(VOLUME_MIN_DB, VOLUME_MAX_DB);
+        let position = match self {
+            Self::Named(TaperCurve::LinearDb) => {
+                (db - VOLUME_MIN_DB) / (VOLUME_MAX_DB - VOLUME_MIN_DB)
+            }
+            Self::Named(TaperCurve::Audio) => {
+                let floor = audio_taper_floor();
+                (10f32.powf((db - VOLUME_MAX_DB) / 60.0) - floor) / (1.0 - floor)
+            }
+            Self::Custom(points) => interpolate(points, db, 1, 0),
+        };
+        position.clamp(0.0, 1.0)
+    }
+
+    /// Volume after one key step from `volume_db`. `step_db` is the step on
+    /// the equal-dB taper; other tapers move the fader by the same distance.
+    pub fn step(&self, volume_db: f32, step_db: f32) -> f32 {
+        if self.is_linear_db() {
+            return (volume_db + step_db).clamp(VOLUME_MIN_DB, VOLUME_MAX_DB);
+        }
+        let distance = step_db / (VOLUME_MAX_DB - VOLUME_MIN_DB);
+        let db = self.position_to_db(self.db_to_position(volume_db) + distance);
+        // Round to 0.01 dB so stored volumes stay readable
+        (db * 100.0).round() / 100.0
+    }
+
+    /// Check the breakpoints of a custom taper
+    pub fn validate(&self) -> Result<()> {
+        let Self::Custom(points) = self else {
+            return Ok(());
+        };
+        if points.len() < 2 {
+            anyhow::bail!("a custom taper needs at least two [position, dB] points");
+        }
+        if points[0][0] != 0.0 || points[points.len() - 1][0] != 1.0 {
+            anyhow::bail!("custom taper positions must run from 0 to 1");
+        }
+        if points.iter().any(|p| !(VOLUME_MIN_DB..=VOLUME_MAX_DB).contains(&p[1])) {
+            anyhow::bail!(
+                "custom taper levels must be between {} and {} dB",
+                VOLUME_MIN_DB,
+                VOLUME_MAX_DB
+            );
+        }
+        if points.windows(2).any(|w| w[1][0] <= w[0][0] || w[1][1] <= w[0][1]) {
+            anyhow::bail!("custom taper positions and levels must both increase");
+        }
+        Ok(())
+    }
+}
+
+/// Level meter scale: the range shown and where the yellow and red zones
+/// start. The K-System scales put 0 K (the average level to mix at) the
+/// given number of dB below full scale, with the red zone 4 dB above it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeterScale {
+    /// -60 to +6 dBFS, yellow from -12 and red from 0 dBFS
+    #[default]
+    Digital,
+
+    /// K-12, for broadcast
+    K12,
+
+    /// K-14, for pop and rock
+    K14,
+
+    /// K-20, for film and classical music
+    K20,
+}
+
+impl MeterScale {
+    pub fn is_digital(&self) -> bool {
+        *self == MeterScale::Digital
+    }
+
+    /// Full scale level of 0 K in dBFS (None for the digital scale)
+    fn k_level(self) -> Option<f32> {
+        match self {
+            MeterScale::Digital => None,
+            MeterScale::K12 => Some(-12.0),
+            MeterScale::K14 => Some(-14.0),
+            MeterScale::K20 => Some(-20.0),
+        }
+    }
+
+    /// Bottom and top of the meters in dBFS (K scales span -40 K to full scale)
+    pub fn range_db(self) -> (f32, f32) {
+        match self.k_level() {
+            None => (VOLUME_MIN_DB, 6.0),
+            Some(k) => (k - 40.0, 0.0),
+        }
+    }
+
+    /// Levels in dBFS where the yellow and the red zone start
+    pub fn zones_db(self) -> (f32, f32) {
+        match self.k_level() {
+            None => (-12.0, 0.0),
+            Some(k) => (k, k + 4.0),
+        }
+    }
+}
+
+/// How the meters move: instantly with each block, or with the attack and
+/// release of a classic meter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeterBallistics {
+    /// Block peaks and RMS as measured
+    #[default]
+    Digital,
+
+    /// Digital PPM: instant attack, falling back 20 dB in 1.7 s
+    Ppm,
+
+    /// VU: the RMS bar reaches 99% of a step in 300 ms and falls back as
+    /// slowly; the peak marker falls back like a PPM
+    Vu,
+}
+
+impl MeterBallistics {
+    pub fn is_digital(&self) -> bool {
+        *self == MeterBallistics::Digital
+    }
+}
+
+/// How dB readouts are written
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct DbDisplay {
+    /// Decimal places, 0 to 2 (optional, defaults to 1)
+    #[serde(default = "default_db_decimals")]
+    pub decimals: u8,
+
+    /// Append " dB" to every readout (optional, defaults to false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub suffix: bool,
+
+    /// Show levels at or below this as `-inf` (optional, defaults to never)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub neg_inf_below_db: Option<f32>,
+}
+
+fn default_db_decimals() -> u8 {
+    1
+}
+
+impl Default for DbDisplay {
+    fn default() -> Self {
+        Self {
+            decimals: default_db_decimals(),
+            suffix: false,
+            neg_inf_below_db: None,
+        }
+    }
+}
+
+impl DbDisplay {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The same display with the unit switched on or off, for places that
+    /// always or never show it
+    pub fn with_suffix(self, suffix: bool) -> Self {
+        Self { suffix, ..self }
+    }
+
+    /// Write a level, e.g. `-6.0`
+    pub fn format(&self, db: f32) -> String {
+        self.write(db, false)
+    }
+
+    /// Write a level with its sign, e.g. `+3.0`
+    pub fn format_signed(&self, db: f32) -> String {
+        self.write(db, true)
+    }
+
+    /// Write a level as one protocol token: the unit follows without a
+    /// space, e.g. `-6.0dB`
+    pub fn format_token(&self, db: f32) -> String {
+        let value = self.with_suffix(false).format(db);
+        if self.suffix {
+            format!("{}dB", value)
+        } else {
+            value
+        }
+    }
+
+    fn write(&self, db: f32, signed: bool) -> String {
+        let value = if self.neg_inf_below_db.is_some_and(|t| db <= t) {
+            "-inf".to_string()
+        } else if signed {
+            format!("{:+.*}", self.decimals as usize, db)
+        } else {
+            format!("{:.*}", self.decimals as usize, db)
+        };
+        if self.suffix {
+            format!("{} dB", value)
+        } else {
+            value
+        }
+    }
+
+    /// Check the decimal places
+    pub fn validate(&self) -> Result<()> {
+        if self.decimals > 2 {
+            anyhow::bail!("decimals must be 0, 1 or 2");
+        }
+        Ok(())
+    }
+}
+
+/// Piecewise linear lookup in breakpoints sorted by both columns, from
+/// column `from` to column `to`
+fn interpolate(points: &[[f32; 2]], x: f32, from: usize, to: usize) -> f32 {
+    let Some(first) = points.first() else {
+        return x;
+    };
+    if x <= first[from] {
+        return first[to];
+    }
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if x <= b[from] {
+            return a[to] + (x - a[from]) / (b[from] - a[from]) * (b[to] - a[to]);
+        }
+    }
+    points[points.len() - 1][to]
+}
+
+/// Point in the channel chain a tap port carries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TapPoint {
+    /// Signal before the channel fader (inputs: raw port signal, outputs: bus sum)
+    #[default]
+    PreFader,
+
+    /// Signal after the channel fader, mute and solo
+    PostFader,
+}
+
+impl TapPoint {
+    /// Whether this is the pre-fader point (the default)
+    pub fn is_pre_fader(&self) -> bool {
+        *self == TapPoint::PreFader
+    }
+
+    /// The other point
+    pub fn toggled(self) -> Self {
+        match self {
+            TapPoint::PreFader => TapPoint::PostFader,
+            TapPoint::PostFader => TapPoint::PreFader,
+        }
+    }
+}
+
+/// How an output bus combines its inputs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MixMode {
+    /// Sum all inputs (normal mixing)
+    #[default]
+    Sum,
+
+    /// Pass only the loudest input (intercom-style)
+    Max,
+
+    /// Priority inputs replace all others while active (ducking)
+    Priority,
+}
+
+impl MixMode {
+    /// Returns true for the default summing mode
+    pub fn is_sum(&self) -> bool {
+        *self == MixMode::Sum
+    }
+}
+
+/// How a new solo treats the channels already soloed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoloMode {
+    /// Solos add up, each channel is released on its own
+    #[default]
+    Additive,
+
+    /// Soloing a channel releases every other input and output solo
+    Exclusive,
+}
+
+impl SoloMode {
+    /// Returns true for the default additive mode
+    pub fn is_additive(&self) -> bool {
+        *self == SoloMode::Additive
+    }
+}
+
+impl ChannelConfig {
+    /// Returns true if this channel has more than one port (stereo or surround)
+    pub fn is_stereo(&self) -> bool {
+        self.ports.len() >= 2
+    }
+
+    /// Returns the number of ports (1 for mono, 2 for stereo, more for surround)
+    pub fn port_count(&self) -> usize {
+        self.ports.len().min(MAX_CHANNEL_PORTS)
+    }
+
+    /// Name of the tap port created for a channel port
+    pub fn tap_port_name(port: &str) -> String {
+        format!("{}_tap", port)
+    }
+
+    /// Names of all ports this channel registers (including taps and sends)
+    pub fn registered_port_names(&self) -> Vec<String> {
+        let mut names = self.ports.clone();
+        if self.tap.is_some() {
+            names.extend(self.ports.iter().map(|p| Self::tap_port_name(p)));
+        }
+        names.extend(self.send_port_names().cloned());
+        names
+    }
+
+    /// Ports of the send nodes of the processing graph, in node order
+    pub fn send_port_names(&self) -> impl Iterator<Item = &String> {
+        self.dsp.iter().flat_map(|dsp| {
+            dsp.nodes.iter().flat_map(|node| match &node.kind {
+                DspNodeKind::Send { ports } => ports.as_slice(),
+                _ => &[],
+            })
+        })
+    }
+}
+
+impl Config {
+    /// Load configuration from a YAML file, applying `key=value` overrides
+    ///
+    /// Keys are dotted paths with optional list indices, e.g.
+    /// `inputs[0].volume_db=-10`; values are parsed as YAML. A config loaded
+    /// with overrides is never written back, so the file stays untouched.
+    pub fn load_with_overrides<P: AsRef<Path>>(path: P, overrides: &[String]) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+        let mut value: serde_yaml::Value = serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+        for assignment in overrides {
+            apply_override(&mut value, assignment)
+                .with_context(|| format!("Invalid override '{}'", assignment))?;
+        }
+
+        let mut config: Config = serde_yaml::from_value(value)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+
+        if overrides.is_empty() {
+            config.config_path = Some(path.to_string_lossy().to_string());
+        }
+        config.validate()?;
+        Ok(config)
+    }
+    
+    /// Save configuration to a YAML file
+    pub fn save(&self) -> Result<()> {
+        if let Some(ref path) = self.config_path {
+            let mut saved = self.clone();
+            saved.inputs.retain(|input| !input.temporary);
+            let contents = serde_yaml::to_string(&saved)
+                .context("Failed to serialize config")?;
+            fs::write(path, contents)
+                .with_context(|| format!("Failed to write config file: {}", path))?;
+        }
+        Ok(())
+    }
+    
+    /// Update volume levels from mixer state
+    pub fn update_volumes(
+        &mut self,
+        input_volumes: &[f32],
+        output_volumes: &[f32],
+        master_volume: f32,
+    ) {
+        for (i, vol) in input_volumes.iter().enumerate() {
+            if i < self.inputs.len() {
+                self.inputs[i].volume_db = Some(*vol);
+            }
+        }
+        for (i, vol) in output_volumes.iter().enumerate() {
+            if i < self.outputs.len() {
+                self.outputs[i].volume_db = Some(*vol);
+            }
+        }
+        self.master_volume_db = Some(master_volume);
+    }
+
+    /// Update the mute states of inputs, outputs, passthrough channels and
+    /// the master
+    pub fn update_mutes(
+        &mut self,
+        inputs: &[bool],
+        outputs: &[bool],
+        passthrough: &[bool],
+        master: bool,
+    ) {
+        let channels = self.inputs.iter_mut().zip(inputs);
+        for (channel, &muted) in channels.chain(self.outputs.iter_mut().zip(outputs)) {
+            channel.muted = muted;
+        }
+        for (channel, &muted) in self.passthrough.iter_mut().zip(passthrough) {
+            channel.muted = muted;
+        }
+        self.master_muted = master;
+    }
+
+    /// Update the solo states of inputs and outputs
+    pub fn update_solos(&mut self, inputs: &[bool], outputs: &[bool]) {
+        let channels = self.inputs.iter_mut().zip(inputs);
+        for (channel, &soloed) in channels.chain(self.outputs.iter_mut().zip(outputs)) {
+            channel.soloed = soloed;
+        }
+    }
+
+    /// Update input trims (0 dB trims are omitted)
+    pub fn update_trims(&mut self, trims: &[f32]) {
+        for (input, &trim) in self.inputs.iter_mut().zip(trims) {
+            input.trim_db = (trim != 0.0).then_some(trim);
+        }
+    }
+
+    /// Update the stereo balance corrections of the inputs (0 dB
+    /// corrections are omitted)
+    pub fn update_balances(&mut self, balances: &[f32]) {
+        for (input, &balance) in self.inputs.iter_mut().zip(balances) {
+            input.balance_db = (balance != 0.0).then_some(balance);
+        }
+    }
+
+    /// Metering point of an input, falling back to the global one
+    pub fn input_meter_point(&self, input: &ChannelConfig) -> TapPoint {
+        input.meter_point.unwrap_or(self.meter_point)
+    }
+
+    /// Update the metering points of the inputs; points equal to the
+    /// global one are left to it
+    pub fn update_meter_points(&mut self, points: &[TapPoint]) {
+        let global = self.meter_point;
+        for (input, &point) in self.inputs.iter_mut().zip(points) {
+            input.meter_point = (point != global).then_some(point);
+        }
+    }
+
+    /// Update the automixer weights of the inputs in the group
+    pub fn update_automix_weights(&mut self, weights: &[Option<f32>]) {
+        for (input, &weight) in self.inputs.iter_mut().zip(weights) {
+            if input.automix_weight_db.is_some() {
+                input.automix_weight_db = weight;
+            }
+        }
+    }
+
+    /// Update the saved capture device volumes of inputs (None: not read)
+    pub fn update_device_gains(&mut self, volumes: &[Option<f32>]) {
+        for (input, &volume_db) in self.inputs.iter_mut().zip(volumes) {
+            if let (Some(device), Some(volume_db)) = (&mut input.device_gain, volume_db) {
+                device.volume_db = Some(volume_db);
+            }
+        }
+    }
+
+    /// Update the mid/side mode of inputs and outputs
+    pub fn update_mid_side(&mut self, inputs: &[bool], outputs: &[bool]) {
+        let channels = self.inputs.iter_mut().zip(inputs);
+        for (channel, &mid_side) in channels.chain(self.outputs.iter_mut().zip(outputs)) {
+            channel.mid_side = mid_side;
+        }
+    }
+
+    /// Update the mid and side levels of inputs and outputs in dB (0 dB
+    /// levels are omitted)
+    pub fn update_mid_side_levels(&mut self, inputs: &[(f32, f32)], outputs: &[(f32, f32)]) {
+        let channels = self.inputs.iter_mut().zip(inputs);
+        for (channel, &(mid_db, side_db)) in channels.chain(self.outputs.iter_mut().zip(outputs))
+        {
+            channel.mid_db = (mid_db != 0.0).then_some(mid_db);
+            channel.side_db = (side_db != 0.0).then_some(side_db);
+        }
+    }
+
+    /// Update input EQ settings (flat EQs are omitted)
+    pub fn update_eq(&mut self, input_eqs: &[EqConfig]) {
+        for (input, eq) in self.inputs.iter_mut().zip(input_eqs) {
+            input.eq = (*eq != EqConfig::default()).then(|| eq.clone());
+        }
+    }
+
+    /// Update output multiband compressor settings
+    pub fn update_multiband(&mut self, multiband: &[Option<MultibandConfig>]) {
+        for (output, settings) in self.outputs.iter_mut().zip(multiband) {
+            output.multiband = *settings;
+        }
+    }
+
+    /// Update passthrough volume levels
+    pub fn update_passthrough_volumes(&mut self, volumes: &[f32]) {
+        for (passthrough, vol) in self.passthrough.iter_mut().zip(volumes) {
+            passthrough.volume_db = Some(*vol);
+        }
+    }
+
+    /// Update input routes from the routing matrix
+    pub fn update_routing(&mut self, routing: &RoutingMatrix) {
+        let output_names: Vec<String> = self.outputs.iter().map(|o| o.name.clone()).collect();
+        for (input, channel) in self.inputs.iter_mut().enumerate() {
+            channel.routes = if routing.is_default(input) {
+                None
+            } else {
+                Some(
+                    output_names
+                        .iter()
+                        .enumerate()
+                        .map(|(output, name)| (routing.get(input, output), name))
+                        .filter(|(crosspoint, _)| crosspoint.enabled)
+                        .map(|(crosspoint, name)| RouteConfig {
+                            output: name.clone(),
+                            gain_db: crosspoint.gain_db,
+                            mono: crosspoint.mono,
+                        })
+                        .collect(),
+                )
+            };
+        }
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<()> {
+        if self.client_name.is_empty() {
+            anyhow::bail!("client_name cannot be empty");
+        }
+
+        if self.inputs.is_empty() {
+            anyhow::bail!("At least one input channel is required");
+        }
+
+        if self.outputs.is_empty() {
+            anyhow::bail!("At least one output channel is required");
+        }
+
+        for (i, input) in self.inputs.iter().enumerate() {
+            if input.name.is_empty() {
+                anyhow::bail!("Input channel {} has empty name", i);
+            }
+            if input.ports.is_empty() {
+                anyhow::bail!("Input channel '{}' has no ports defined", input.name);
+            }
+            if input.ports.len() > MAX_CHANNEL_PORTS {
+                anyhow::bail!(
+                    "Input channel '{}' has {} ports, max {} supported",
+                    input.name,
+                    input.ports.len(),
+                    MAX_CHANNEL_PORTS
+                );
+            }
+            if input.auto_trim.is_some() {
+                anyhow::bail!("Input channel '{}' cannot have auto_trim", input.name);
+            }
+            if input.mono {
+                anyhow::bail!("Input channel '{}' cannot be mono-summed", input.name);
+            }
+            if input.limiter.is_some() {
+                anyhow::bail!("Input channel '{}' cannot have a limiter", input.name);
+            }
+            if input.multiband.is_some() {
+                anyhow::bail!("Input channel '{}' cannot have a multiband compressor", input.name);
+            }
+            if input.soft_clip.is_some() {
+                anyhow::bail!("Input channel '{}' cannot have a soft clipper", input.name);
+            }
+            if input.delay_ms.is_some() {
+                anyhow::bail!("Input channel '{}' cannot have a delay", input.name);
+            }
+            if input.solo_cue.is_some() {
+                anyhow::bail!("Input channel '{}' cannot be a solo cue", input.name);
+            }
+            if let Some(eq) = &input.eq {
+                if [eq.low_db, eq.mid_db, eq.high_db]
+                    .iter()
+                    .any(|g| !(-EQ_RANGE_DB..=EQ_RANGE_DB).contains(g))
+                {
+                    anyhow::bail!(
+                        "Input channel '{}' EQ gains must be within ±{} dB",
+                        input.name,
+                        EQ_RANGE_DB
+                    );
+                }
+                if eq.bands.len() > MAX_EQ_BANDS {
+                    anyhow::bail!(
+                        "Input channel '{}' has {} EQ bands, at most {} are supported",
+                        input.name,
+                        eq.bands.len(),
+                        MAX_EQ_BANDS
+                    );
+                }
+                for band in &eq.bands {
+                    if !(EQ_FREQ_RANGE.0..=EQ_FREQ_RANGE.1).contains(&band.freq)
+                        || !(EQ_Q_RANGE.0..=EQ_Q_RANGE.1).contains(&band.q)
+                        || !(-EQ_RANGE_DB..=EQ_RANGE_DB).contains(&band.gain_db)
+                    {
+                        anyhow::bail!(
+                            "Input channel '{}' EQ band needs freq {}-{} Hz, q {}-{} and gain within ±{} dB",
+                            input.name,
+                            EQ_FREQ_RANGE.0,
+                            EQ_FREQ_RANGE.1,
+                            EQ_Q_RANGE.0,
+                            EQ_Q_RANGE.1,
+                            EQ_RANGE_DB
+                        );
+                    }
+                }
+                if !eq.pass_filters_in_range() {
+                    anyhow::bail!(
+                        "Input channel '{}' filter cutoffs must be {}-{} Hz",
+                        input.name,
+                        EQ_FREQ_RANGE.0,
+                        EQ_FREQ_RANGE.1
+                    );
+                }
+            }
+            if input
+                .trim_db
+                .is_some_and(|trim| !(-INPUT_TRIM_RANGE_DB..=INPUT_TRIM_RANGE_DB).contains(&trim))
+            {
+                anyhow::bail!(
+                    "Input channel '{}' trim_db must be within ±{} dB",
+                    input.name,
+                    INPUT_TRIM_RANGE_DB
+                );
+            }
+            if let Some(balance) = input.balance_db {
+                if input.port_count() != 2 {
+                    anyhow::bail!(
+                        "Input channel '{}' balance_db needs a stereo input",
+                        input.name
+                    );
+                }
+                if !(-BALANCE_RANGE_DB..=BALANCE_RANGE_DB).contains(&balance) {
+                    anyhow::bail!(
+                        "Input channel '{}' balance_db must be within ±{} dB",
+                        input.name,
+                        BALANCE_RANGE_DB
+                    );
+                }
+            }
+            if input.automix_weight_db.is_some_and(|weight| {
+                !(-AUTOMIX_WEIGHT_RANGE_DB..=AUTOMIX_WEIGHT_RANGE_DB).contains(&weight)
+            }) {
+                anyhow::bail!(
+                    "Input channel '{}' automix_weight_db must be within ±{} dB",
+                    input.name,
+                    AUTOMIX_WEIGHT_RANGE_DB
+                );
+            }
+            if input.device_meter.as_ref().is_some_and(|s| s.is_empty()) {
+                anyhow::bail!("Input channel '{}' device_meter needs a source", input.name);
+            }
+            if let Some(device) = &input.device_gain {
+                if device.source.is_empty() {
+                    anyhow::bail!("Input channel '{}' device_gain needs a source", input.name);
+                }
+                if device
+                    .volume_db
+                    .is_some_and(|db| !(VOLUME_MIN_DB..=DEVICE_GAIN_MAX_DB).contains(&db))
+                {
+                    anyhow::bail!(
+                        "Input channel '{}' device volume_db must be between {} and {}",
+                        input.name,
+                        VOLUME_MIN_DB,
+                        DEVICE_GAIN_MAX_DB
+                    );
+                }
+            }
+            for alternate in &input.alternates {
+                if alternate.name.is_empty() || alternate.connect.is_empty() {
+                    anyhow::bail!(
+                        "Input channel '{}' alternate sources need a name and ports to connect",
+                        input.name
+                    );
+                }
+            }
+            for name in &input.monitor_outputs {
+                if !self.outputs.iter().any(|o| &o.name == name) {
+                    anyhow::bail!(
+                        "Input channel '{}' lists unknown monitor output '{}'",
+                        input.name,
+                        name
+                    );
+                }
+            }
+            if let Some(deesser) = &input.deesser {
+                if deesser.threshold_db > 0.0
+                    || !(DEESSER_FREQ_RANGE.0..=DEESSER_FREQ_RANGE.1).contains(&deesser.freq)
+                    || !(deesser.max_reduction_db > 0.0 && deesser.max_reduction_db <= 24.0)
+                {
+                    anyhow::bail!(
+                        "Input channel '{}' de-esser needs threshold_db <= 0, freq {}-{} Hz \
+                         and max_reduction_db up to 24",
+                        input.name,
+                        DEESSER_FREQ_RANGE.0,
+                        DEESSER_FREQ_RANGE.1
+                    );
+                }
+            }
+            if let Some(protect) = &input.protect {
+                let ms = 0.0..=PROTECT_MAX_MS;
+                if protect.threshold_db > 0.0
+                    || protect.threshold_db <= VOLUME_MIN_DB
+                    || !(0.0..=60.0).contains(&protect.rise_db)
+                    || protect.dip_db <= 0.0
+                    || protect.dip_db > 60.0
+                    || !ms.contains(&protect.hold_ms)
+                    || protect.release_ms <= 0.0
+                    || !ms.contains(&protect.release_ms)
+                {
+                    anyhow::bail!(
+                        "Input channel '{}' protect needs threshold_db {} to 0, rise_db and \
+                         dip_db up to 60 and hold_ms and release_ms up to {}",
+                        input.name,
+                        VOLUME_MIN_DB,
+                        PROTECT_MAX_MS
+                    );
+                }
+            }
+            if let Some(dsp) = &input.dsp {
+                dsp.validate(input.port_count())
+                    .with_context(|| format!("Input channel '{}' dsp", input.name))?;
+            }
+            for route in input.routes.iter().flatten() {
+                if !self.outputs.iter().any(|o| o.name == route.output) {
+                    anyhow::bail!(
+                        "Input channel '{}' routes to unknown output '{}'",
+                        input.name,
+                        route.output
+                    );
+                }
+            }
+        }
+
+        for (i, output) in self.outputs.iter().enumerate() {
+            if output.name.is_empty() {
+                anyhow::bail!("Output channel {} has empty name", i);
+            }
+            if output.ports.is_empty() {
+                anyhow::bail!("Output channel '{}' has no ports defined", output.name);
+            }
+            if output.ports.len() > MAX_CHANNEL_PORTS {
+                anyhow::bail!(
+                    "Output channel '{}' has {} ports, max {} supported",
+                    output.name,
+                    output.ports.len(),
+                    MAX_CHANNEL_PORTS
+                );
+            }
+            if output.routes.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have routes", output.name);
+            }
+            if output.eq.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have an EQ", output.name);
+            }
+            if output.dsp.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have a dsp graph", output.name);
+            }
+            if output.deesser.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have a de-esser", output.name);
+            }
+            if output.protect.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have a protective dip", output.name);
+            }
+            if !output.monitor_outputs.is_empty() {
+                anyhow::bail!("Output channel '{}' cannot have monitor_outputs", output.name);
+            }
+            if output.trim_db.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have an input trim", output.name);
+            }
+            if output.balance_db.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have a balance_db", output.name);
+            }
+            if !output.alternates.is_empty() {
+                anyhow::bail!("Output channel '{}' cannot have alternate sources", output.name);
+            }
+            if output.automix_weight_db.is_some() {
+                anyhow::bail!("Output channel '{}' cannot join the automixer", output.name);
+            }
+            if output.device_gain.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have a device_gain", output.name);
+            }
+            if output.device_meter.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have a device_meter", output.name);
+            }
+            if output.meter_point.is_some() {
+                anyhow::bail!("Output channel '{}' cannot have a meter_point", output.name);
+            }
+            if output.mix_mode == MixMode::Priority && output.priority_inputs.is_empty() {
+                anyhow::bail!(
+                    "Output channel '{}' uses priority mode but lists no priority_inputs",
+                    output.name
+                );
+            }
+            if let Some(trim) = &output.auto_trim {
+                if trim.ceiling_db > 0.0
+                    || trim.hold_ms < 0.0
+                    || trim.step_db <= 0.0
+                    || trim.max_reduction_db < trim.step_db
+                    || trim.release_db_per_s < 0.0
+                {
+                    anyhow::bail!("Output channel '{}' has invalid auto_trim settings", output.name);
+                }
+            }
+            if let Some(limiter) = &output.limiter {
+                if !(MIN_LIMITER_CEILING_DB..=0.0).contains(&limiter.ceiling_db)
+                    || limiter.lookahead_ms <= 0.0
+                    || limiter.lookahead_ms > MAX_LOOKAHEAD_MS
+                    || limiter.release_ms <= 0.0
+                {
+                    anyhow::bail!("Output channel '{}' has invalid limiter settings", output.name);
+                }
+            }
+            if output
+                .delay_ms
+                .is_some_and(|ms| !(0.0..=MAX_OUTPUT_DELAY_MS).contains(&ms))
+            {
+                anyhow::bail!(
+                    "Output channel '{}' delay_ms must be between 0 and {}",
+                    output.name,
+                    MAX_OUTPUT_DELAY_MS
+                );
+            }
+            if output
+                .solo_cue
+                .is_some_and(|cue| !(VOLUME_MIN_DB..=0.0).contains(&cue.dim_db))
+            {
+                anyhow::bail!(
+                    "Output channel '{}' solo_cue dim_db must be between {} and 0",
+                    output.name,
+                    VOLUME_MIN_DB
+                );
+            }
+            for name in &output.priority_inputs {
+                if !self.inputs.iter().any(|i| &i.name == name) {
+                    anyhow::bail!(
+                        "Output channel '{}' lists unknown priority input '{}'",
+                        output.name,
+                        name
+                    );
+                }
+            }
+        }
+
+        for output in &self.outputs {
+            if let Some(multiband) = &output.multiband {
+                multiband
+                    .validate()
+                    .with_context(|| format!("Output channel '{}' multiband", output.name))?;
+            }
+            if let Some(clip) = &output.soft_clip {
+                clip.validate()
+                    .with_context(|| format!("Output channel '{}' soft_clip", output.name))?;
+            }
+        }
+
+        for channel in self.inputs.iter().chain(&self.outputs) {
+            if channel.mid_side && channel.ports.len() != 2 {
+                anyhow::bail!("Channel '{}' needs two ports for mid_side", channel.name);
+            }
+            if [channel.mid_db, channel.side_db]
+                .iter()
+                .flatten()
+                .any(|db| !(-MID_SIDE_RANGE_DB..=MID_SIDE_RANGE_DB).contains(db))
+            {
+                anyhow::bail!(
+                    "Channel '{}' mid_db and side_db must be within ±{} dB",
+                    channel.name,
+                    MID_SIDE_RANGE_DB
+                );
+            }
+            if let Some(max) = channel.max_volume_db {
+                if !(VOLUME_MIN_DB..=VOLUME_MAX_DB).contains(&max) {
+                    anyhow::bail!(
+                        "Channel '{}' max_volume_db must be between {} and {}",
+                        channel.name,
+                        VOLUME_MIN_DB,
+                        VOLUME_MAX_DB
+                    );
+                }
+                if channel.volume_db.is_some_and(|v| v > max) {
+                    anyhow::bail!(
+                        "Channel '{}' volume_db is above its max_volume_db",
+                        channel.name
+                    );
+                }
+            }
+        }
+
+        self.fader_taper.validate().context("fader_taper")?;
+        self.db_display.validate().context("db_display")?;
+        for channel in self.inputs.iter().chain(&self.outputs) {
+            if let Some(taper) = &channel.taper {
+                taper
+                    .validate()
+                    .with_context(|| format!("Channel '{}' taper", channel.name))?;
+            }
+            if channel
+                .silence_warning_seconds
+                .is_some_and(|s| !(s > 0.0 && s.is_finite()))
+            {
+                anyhow::bail!(
+                    "Channel '{}' silence_warning_seconds must be above 0",
+                    channel.name
+                );
+            }
+        }
+
+        if self
+            .meter_range_seconds
+            .is_some_and(|s| !(0.0..=MAX_METER_RANGE_SECONDS).contains(&s))
+        {
+            anyhow::bail!(
+                "meter_range_seconds must be between 0 and {}",
+                MAX_METER_RANGE_SECONDS
+            );
+        }
+
+        if self
+            .control_feedback_hz
+            .is_some_and(|r| !(r > 0.0 && r <= MAX_FEEDBACK_RATE_HZ))
+        {
+            anyhow::bail!(
+                "control_feedback_hz must be between 0 and {}",
+                MAX_FEEDBACK_RATE_HZ
+            );
+        }
+
+        if let Some(access) = &self.control_access {
+            access.validate()?;
+        }
+
+        if self.peak_hold_seconds.is_some_and(|s| s.is_nan() || s < 0.0) {
+            anyhow::bail!("peak_hold_seconds must not be negative");
+        }
+
+        if self
+            .signal_threshold_db
+            .is_some_and(|db| !(VOLUME_MIN_DB..=0.0).contains(&db))
+        {
+            anyhow::bail!("signal_threshold_db must be between {} and 0", VOLUME_MIN_DB);
+        }
+
+        if let Some(replay) = &self.replay_buffer {
+            if !self.outputs.iter().any(|o| o.name == replay.output) {
+                anyhow::bail!("replay_buffer records unknown output '{}'", replay.output);
+            }
+            if !(replay.seconds > 0.0 && replay.seconds <= MAX_REPLAY_SECONDS) {
+                anyhow::bail!(
+                    "replay_buffer seconds must be between 0 and {}",
+                    MAX_REPLAY_SECONDS
+                );
+            }
+        }
+
+        if let Some(recorder) = &self.recorder {
+            if !self.outputs.iter().any(|o| o.name == recorder.output) {
+                anyhow::bail!("recorder records unknown output '{}'", recorder.output);
+            }
+            if recorder
+                .normalize_lufs
+                .is_some_and(|lufs| !(MIN_NORMALIZE_LUFS..=0.0).contains(&lufs))
+            {
+                anyhow::bail!(
+                    "recorder normalize_lufs must be between {} and 0",
+                    MIN_NORMALIZE_LUFS
+                );
+            }
+            if recorder.ceiling_db > 0.0 {
+                anyhow::bail!("recorder ceiling_db cannot be above 0");
+            }
+            if recorder.bitrate_kbps.is_some_and(|b| !(8..=512).contains(&b)) {
+                anyhow::bail!("recorder bitrate_kbps must be between 8 and 512");
+            }
+            if let Some(trigger) = &recorder.trigger {
+                if !(MIN_TRIGGER_THRESHOLD_DB..0.0).contains(&trigger.threshold_db) {
+                    anyhow::bail!(
+                        "recorder trigger threshold_db must be between {} and 0",
+                        MIN_TRIGGER_THRESHOLD_DB
+                    );
+                }
+                if trigger.hold_seconds <= 0.0 {
+                    anyhow::bail!("recorder trigger hold_seconds must be positive");
+                }
+                if !(0.0..=MAX_PRE_ROLL_SECONDS).contains(&trigger.pre_roll_seconds) {
+                    anyhow::bail!(
+                        "recorder trigger pre_roll_seconds must be between 0 and {}",
+                        MAX_PRE_ROLL_SECONDS
+                    );
+                }
+            }
+        }
+
+        if self.solo_mode == SoloMode::Exclusive {
+            let soloed = self.inputs.iter().chain(&self.outputs).filter(|c| c.soloed);
+            if soloed.count() > 1 {
+                anyhow::bail!("Only one channel can be soloed with solo_mode exclusive");
+            }
+        }
+
+        if let Some(reminders) = &self.reminders {
+            if [reminders.solo_minutes, reminders.mute_minutes]
+                .iter()
+                .flatten()
+                .any(|&minutes| minutes <= 0.0)
+            {
+                anyhow::bail!("reminder minutes must be above 0");
+            }
+            if reminders.signal_threshold_db > 0.0 {
+                anyhow::bail!("reminders signal_threshold_db cannot be above 0");
+            }
+        }
+
+        if let Some(tally) = &self.tally {
+            if tally.signal_threshold_db > 0.0 {
+                anyhow::bail!("tally signal_threshold_db cannot be above 0");
+            }
+            if tally.serial.as_ref().is_some_and(|path| path.is_empty()) {
+                anyhow::bail!("tally serial device path cannot be empty");
+            }
+            for (input, path) in &tally.gpio {
+                if !self.inputs.iter().any(|i| &i.name == input) {
+                    anyhow::bail!("tally GPIO for unknown input '{}'", input);
+                }
+                if path.is_empty() {
+                    anyhow::bail!("tally GPIO path for input '{}' cannot be empty", input);
+                }
+            }
+        }
+
+        for entry in &self.schedule {
+            entry
+                .validate()
+                .with_context(|| format!("schedule entry '{}'", entry.when))?;
+        }
+
+        if let Some(calibration) = &self.calibration {
+            if !self.inputs.iter().any(|i| i.name == calibration.input) {
+                anyhow::bail!("calibration measures unknown input '{}'", calibration.input);
+            }
+            let levels = MIN_CALIBRATION_LEVEL_DB..=MAX_CALIBRATION_LEVEL_DB;
+            if !levels.contains(&calibration.level_db) {
+                anyhow::bail!(
+                    "calibration level_db must be between {} and {}",
+                    MIN_CALIBRATION_LEVEL_DB,
+                    MAX_CALIBRATION_LEVEL_DB
+                );
+            }
+            if !(MIN_CALIBRATION_SECONDS..=MAX_CALIBRATION_SECONDS).contains(&calibration.seconds) {
+                anyhow::bail!(
+                    "calibration seconds must be between {} and {}",
+                    MIN_CALIBRATION_SECONDS,
+                    MAX_CALIBRATION_SECONDS
+                );
+            }
+        }
+
+        if let Some(click) = &self.click {
+            if click.outputs.is_empty() {
+                anyhow::bail!("click needs at least one output");
+            }
+            for output in &click.outputs {
+                if !self.outputs.iter().any(|o| &o.name == output) {
+                    anyhow::bail!("click goes to unknown output '{}'", output);
+                }
+            }
+            if !(CLICK_TEMPO_RANGE.0..=CLICK_TEMPO_RANGE.1).contains(&click.tempo_bpm) {
+                anyhow::bail!(
+                    "click tempo_bpm must be between {} and {}",
+                    CLICK_TEMPO_RANGE.0,
+                    CLICK_TEMPO_RANGE.1
+                );
+            }
+            if !(1..=16).contains(&click.beats_per_bar) {
+                anyhow::bail!("click beats_per_bar must be between 1 and 16");
+            }
+            if !(click.level_db <= 0.0 && click.level_db > VOLUME_MIN_DB) {
+                anyhow::bail!("click level_db must be between {} and 0", VOLUME_MIN_DB);
+            }
+            if !(0.0..=20.0).contains(&click.accent_db) {
+                anyhow::bail!("click accent_db must be between 0 and 20");
+            }
+        }
+
+        if let Some(app_streams) = &self.app_streams {
+            for output in &app_streams.routes {
+                if !self.outputs.iter().any(|o| &o.name == output) {
+                    anyhow::bail!("app_streams routes to unknown output '{}'", output);
+                }
+            }
+            if !(VOLUME_MIN_DB..=VOLUME_MAX_DB).contains(&app_streams.volume_db) {
+                anyhow::bail!(
+                    "app_streams volume_db must be between {} and {}",
+                    VOLUME_MIN_DB,
+                    VOLUME_MAX_DB
+                );
+            }
+        }
+
+        if let Some(midi) = &self.midi {
+            if midi.port.is_empty() {
+                anyhow::bail!("MIDI port name cannot be empty");
+            }
+            if midi.channel.is_some_and(|c| !(1..=16).contains(&c)) {
+                anyhow::bail!("MIDI channel must be between 1 and 16");
+            }
+        }
+
+        for (i, scene) in self.scenes.iter().enumerate() {
+            if scene.name.is_empty() {
+                anyhow::bail!("Scene {} has empty name", i);
+            }
+            if scene.program.is_some_and(|p| p > 127) || scene.bank.is_some_and(|b| b > 127) {
+                anyhow::bail!("Scene '{}': program and bank must be 0-127", scene.name);
+            }
+            for (names, kind, channels) in [
+                (scene.inputs.keys(), "input", &self.inputs),
+                (scene.outputs.keys(), "output", &self.outputs),
+            ] {
+                for name in names {
+                    if !channels.iter().any(|c| &c.name == name) {
+                        anyhow::bail!("Scene '{}' sets unknown {} '{}'", scene.name, kind, name);
+                    }
+                }
+            }
+        }
+        if let Some(name) = &self.startup_scene {
+            if !self.scenes.iter().any(|s| &s.name == name) {
+                anyhow::bail!("Startup scene '{}' is not a scene", name);
+            }
+        }
+
+        for (i, passthrough) in self.passthrough.iter().enumerate() {
+            if passthrough.name.is_empty() {
+                anyhow::bail!("Passthrough channel {} has empty name", i);
+            }
+            if passthrough.inputs.is_empty() || passthrough.inputs.len() > MAX_PASSTHROUGH_PORTS {
+                anyhow::bail!(
+                    "Passthrough channel '{}' needs 1 to {} ports",
+                    passthrough.name,
+                    MAX_PASSTHROUGH_PORTS
+                );
+            }
+            if passthrough.outputs.len() != passthrough.inputs.len() {
+                anyhow::bail!(
+                    "Passthrough channel '{}' has {} inputs but {} outputs",
+                    passthrough.name,
+                    passthrough.inputs.len(),
+                    passthrough.outputs.len()
+                );
+            }
+            if passthrough.connect_inputs.len() > passthrough.inputs.len()
+                || passthrough.connect_outputs.len() > passthrough.outputs.len()
+            {
+                anyhow::bail!(
+                    "Passthrough channel '{}' has more connect entries than ports",
+                    passthrough.name
+                );
+            }
+        }
+
+        let mut port_names: Vec<String> = Vec::new();
+        for port in self
+            .inputs
+            .iter()
+            .chain(self.outputs.iter())
+            .flat_map(|c| c.registered_port_names())
+            .chain(self.midi.iter().map(|m| m.port.clone()))
+            .chain(
+                self.passthrough
+                    .iter()
+                    .flat_map(|p| p.inputs.iter().chain(p.outputs.iter()).cloned()),
+            )
+        {
+            if port.is_empty() {
+                anyhow::bail!("Port names cannot be empty");
+            }
+            if port_names.contains(&port) {
+                anyhow::bail!("Port '{}' is defined more than once", port);
+            }
+            port_names.push(port);
+        }
+
+        Ok(())
+    }
+
+    /// Get total number of input ports
+    pub fn total_input_ports(&self) -> usize {
+        self.inputs.iter().map(|c| c.port_count()).sum()
+    }
+
+    /// Get total number of output ports
+    pub fn total_output_ports(&self) -> usize {
+        self.outputs.iter().map(|c| c.port_count()).sum()
+    }
+}
+
+/// One step of an override path
+#[derive(Debug, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Whether one of the `key=value` overrides sets the value at `path`, itself
+/// or through a mapping or list holding it
+pub fn override_covers(overrides: &[String], path: &str) -> bool {
+    let Ok(target) = parse_override_path(path) else {
+        return false;
+    };
+    overrides
+        .iter()
+        .filter_map(|assignment| assignment.split_once('='))
+        .filter_map(|(key, _)| parse_override_path(key.trim()).ok())
+        .any(|segments| target.starts_with(&segments))
+}
+
+/// Split `inputs[0].volume_db` (or `inputs.0.volume_db`) into path segments
+fn parse_override_path(path: &str) -> Result<Vec<PathSegment>> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !key.is_empty() {
+            segments.push(match key.parse::<usize>() {
+                Ok(index) => PathSegment::Index(index),
+                Err(_) => PathSegment::Key(key.to_string()),
+            });
+        } else if rest.is_empty() {
+            anyhow::bail!("empty key in '{}'", path);
+        }
+        while let Some(stripped) = rest.strip_prefix('[') {
+            let (index, after) = stripped
+                .split_once(']')
+                .with_context(|| format!("missing ']' in '{}'", path))?;
+            let index = index
+                .parse::<usize>()
+                .with_context(|| format!("invalid index '{}'", index))?;
+            segments.push(PathSegment::Index(index));
+            rest = after;
+        }
+        if !rest.is_empty() {
+            anyhow::bail!("unexpected '{}' in '{}'", rest, path);
+        }
+    }
+    Ok(segments)
+}
+
+/// Apply one `key=value` override to a parsed YAML document
+fn apply_override(root: &mut serde_yaml::Value, assignment: &str) -> Result<()> {
+    use serde_yaml::Value;
+
+    let (path, value) = assignment
+        .split_once('=')
+        .context("expected key=value")?;
+    let segments = parse_override_path(path.trim())?;
+    let value: Value = serde_yaml::from_str(value).context("value is not valid YAML")?;
+
+    let mut node = root;
+    for segment in &segments {
+        node = match segment {
+            PathSegment::Key(key) => {
+                if node.is_null() {
+                    *node = Value::Mapping(Default::default());
+                }
+                let map = node
+                    .as_mapping_mut()
+                    .with_context(|| format!("'{}' is not inside a mapping", key))?;
+                map.entry(Value::String(key.clone())).or_insert(Value::Null)
+            }
+            PathSegment::Index(index) => {
+                let list = node
+                    .as_sequence_mut()
+                    .with_context(|| format!("[{}] is not inside a list", index))?;
+                let len = list.len();
+                list.get_mut(*index)
+                    .with_context(|| format!("index {} out of range (length {})", index, len))?
+            }
+        };
+    }
+    *node = value;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_config() {
+    fn test_parse_config() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["capture_1"]
+  - name: "Music"
+    ports: ["capture_2", "capture_3"]
+outputs:
+  - name: "Main"
+    ports: ["playback_1", "playback_2"]
+"#;
+
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.client_name, "Mixer");
+        assert_eq!(config.inputs.len(), 2);
+        assert_eq!(config.outputs.len(), 1);
+        assert!(!config.inputs[0].is_stereo());
+        assert!(config.inputs[1].is_stereo());
+        assert!(config.outputs[0].is_stereo());
+    }
+
+    #[test]
+    fn test_duplicate_port_names_rejected() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["mic_in"]
+"#;
+
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_scenes() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+midi:
+  channel: 1
+scenes:
+  - name: "Verse"
+    program: 3
+    inputs:
+      Mic: { volume_db: -3.0, muted: false }
+  - name: "Break"
+    program: 3
+    bank: 1
+    outputs:
+      Main: { muted: true }
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.midi.as_ref().unwrap().port, "midi_in");
+        assert!(config.scenes[0].matches_program(1, 3));
+        assert!(!config.scenes[1].matches_program(0, 3));
+        assert_eq!(config.scenes[0].inputs["Mic"].volume_db, Some(-3.0));
+
+        let mut bad = config.clone();
+        bad.scenes[0].inputs.insert("Guitar".to_string(), SceneChannel::default());
+        assert!(bad.validate().is_err());
+
+        let mut startup = config.clone();
+        startup.startup_scene = Some("Break".to_string());
+        startup.validate().unwrap();
+        startup.startup_scene = Some("Chorus".to_string());
+        assert!(startup.validate().is_err());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+        let mut value: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+        apply_override(&mut value, "client_name=TestMixer").unwrap();
+        apply_override(&mut value, "inputs[0].volume_db=-10").unwrap();
+        apply_override(&mut value, "outputs.0.ports=[a, b]").unwrap();
+        assert!(apply_override(&mut value, "inputs[3].volume_db=0").is_err());
+        assert!(apply_override(&mut value, "client_name").is_err());
+
+        let config: Config = serde_yaml::from_value(value).unwrap();
+        assert_eq!(config.client_name, "TestMixer");
+        assert_eq!(config.inputs[0].volume_db, Some(-10.0));
+        assert!(config.outputs[0].is_stereo());
+
+        let overrides = ["inputs.0.volume_db=-10".to_string(), "outputs=[]".to_string()];
+        assert!(override_covers(&overrides, "inputs[0].volume_db"));
+        assert!(!override_covers(&overrides, "inputs[1].volume_db"));
+        assert!(override_covers(&overrides, "outputs[0].volume_db"));
+        assert!(!override_covers(&overrides, "master_volume_db"));
+    }
+
+    #[test]
+    fn test_parse_mix_modes() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+  - name: "Stream"
+    ports: ["stream_out"]
+    mix_mode: priority
+    priority_inputs: ["Mic"]
+  - name: "Talkback"
+    ports: ["talk_out"]
+    mix_mode: priority
+    priority_inputs: ["Host"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.outputs[0].mix_mode, MixMode::Sum);
+        assert_eq!(config.outputs[1].mix_mode, MixMode::Priority);
+        assert!(config.validate().is_err());
+
+        config.outputs.pop();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_surround_channels() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Film"
+    ports: ["film_L", "film_R", "film_C", "film_LFE", "film_Ls", "film_Rs"]
+outputs:
+  - name: "Room"
+    ports: ["room_L", "room_R", "room_C", "room_LFE", "room_Ls", "room_Rs"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.outputs[0].port_count(), 6);
+
+        config.outputs[0].ports.extend(["a", "b", "c"].map(String::from));
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_passthrough() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+passthrough:
+  - name: "Ambisonic"
+    inputs: ["amb_in_1", "amb_in_2", "amb_in_3", "amb_in_4"]
+    outputs: ["amb_out_1", "amb_out_2", "amb_out_3", "amb_out_4"]
+    volume_db: -6.0
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.passthrough[0].inputs.len(), 4);
+
+        config.passthrough[0].outputs.pop();
+        assert!(config.validate().is_err());
+
+        config.passthrough[0].outputs.push("main_out".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_dsp_graph() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    dsp:
+      nodes:
+        - name: gate
+          type: gate
+          threshold_db: -50
+        - name: voice
+          type: eq
+          bands:
+            - freq: 200
+              gain_db: -3
+        - name: comp
+          type: comp
+          threshold_db: -18
+          ratio: 3
+        - name: fx
+          type: send
+          ports: ["mic_fx"]
+      edges:
+        - [input, gate]
+        - [gate, voice]
+        - [voice, comp]
+        - [comp, output]
+        - [gate, fx]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let dsp = config.inputs[0].dsp.clone().unwrap();
+        assert_eq!(
+            dsp.nodes[2].kind,
+            DspNodeKind::Comp {
+                threshold_db: -18.0,
+                ratio: 3.0,
+                attack_ms: 10.0,
+                release_ms: 100.0,
+                makeup_db: 0.0,
+            }
+        );
+        assert_eq!(
+            config.inputs[0].registered_port_names(),
+            vec!["mic_in".to_string(), "mic_fx".to_string()]
+        );
+
+        // Round trip through YAML
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let reparsed: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(reparsed.inputs[0].dsp, Some(dsp));
+
+        // A send port name must be unique
+        config.inputs[0].dsp.as_mut().unwrap().nodes[3].kind = DspNodeKind::Send {
+            ports: vec!["main_out".to_string()],
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_parametric_eq() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    eq:
+      low_db: -3.0
+      bands:
+        - freq: 250
+          gain_db: -4.0
+          q: 1.4
+        - freq: 5000
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let eq = config.inputs[0].eq.clone().unwrap();
+        assert_eq!(eq.bands.len(), 2);
+        assert_eq!(eq.bands[1].q, 1.0);
+        assert_eq!(eq.bands[1].gain_db, 0.0);
+
+        // Configured bands are kept even while flat
+        let mut flat = eq;
+        flat.low_db = 0.0;
+        flat.bands[0].gain_db = 0.0;
+        assert!(flat.is_flat());
+        config.update_eq(&[flat]);
+        assert_eq!(config.inputs[0].eq.as_ref().unwrap().bands.len(), 2);
+
+        config.inputs[0].eq.as_mut().unwrap().bands[0].freq = 5.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_pass_filters() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    eq:
+      high_pass:
+        freq: 90
+      low_pass:
+        freq: 12000
+        enabled: false
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let eq = config.inputs[0].eq.clone().unwrap();
+        assert!(eq.is_flat());
+        assert!(eq.high_pass.is_some_and(|f| f.enabled && f.freq == 90.0));
+        assert!(eq.low_pass.is_some_and(|f| !f.enabled));
+
+        // Filters alone keep the EQ in the saved config
+        config.update_eq(&[eq]);
+        assert!(config.inputs[0].eq.is_some());
+
+        config.inputs[0].eq.as_mut().unwrap().high_pass.as_mut().unwrap().freq = 5.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_multiband() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Stream"
+    ports: ["stream_l", "stream_r"]
+    multiband:
+      crossovers_hz: [150, 4000]
+      bands:
+        - threshold_db: -24
+          ratio: 3
+        - {}
+        - makeup_db: 2
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let multiband = config.outputs[0].multiband.unwrap();
+        assert!(multiband.enabled);
+        assert_eq!(multiband.attack_ms, 10.0);
+        assert_eq!(multiband.bands[0].ratio, 3.0);
+        assert_eq!(multiband.bands[1], MultibandBandConfig::default());
+        assert_eq!(multiband.bands[2].makeup_db, 2.0);
+
+        config.outputs[0].multiband.as_mut().unwrap().crossovers_hz = [4000.0, 150.0];
+        assert!(config.validate().is_err());
+        config.outputs[0].multiband = None;
+        config.outputs[0].soft_clip = Some(SoftClipConfig {
+            drive_db: 30.0,
+            ..Default::default()
+        });
+        assert!(config.validate().is_err());
+        config.outputs[0].soft_clip = Some(SoftClipConfig {
+            drive_db: 3.0,
+            oversampling: 3,
+            ..Default::default()
+        });
+        assert!(config.validate().is_err());
+        config.outputs[0].soft_clip = Some(SoftClipConfig {
+            drive_db: 3.0,
+            ceiling_db: -1.0,
+            curve: ClipCurve::Cubic,
+            oversampling: 4,
+        });
+        assert!(config.validate().is_ok());
+        config.inputs[0].multiband = Some(MultibandConfig::default());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_monitor_outputs() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    monitor_outputs: ["Phones"]
+outputs:
+  - name: "Phones"
+    ports: ["phones_l", "phones_r"]
+  - name: "Stream"
+    ports: ["stream_l", "stream_r"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        config.inputs[0].monitor_outputs.push("Monitor".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_input_trim() {
         let yaml = r#"
 client_name: "Mixer"
 inputs:
   - name: "Mic"
-    ports: ["capture_1"]
+    ports: ["mic_in"]
+    trim_db: 12
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.inputs[0].trim_db, Some(12.0));
+
+        config.update_trims(&[0.0]);
+        assert_eq!(config.inputs[0].trim_db, None);
+
+        config.inputs[0].trim_db = Some(30.0);
+        assert!(config.validate().is_err());
+        config.inputs[0].trim_db = None;
+        config.outputs[0].trim_db = Some(-6.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_automix_weights() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Host"
+    ports: ["host_in"]
+    automix_weight_db: 3
+  - name: "Guest"
+    ports: ["guest_in"]
+    automix_weight_db: 0
   - name: "Music"
-    ports: ["capture_2", "capture_3"]
+    ports: ["music_in"]
 outputs:
   - name: "Main"
-    ports: ["playback_1", "playback_2"]
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.inputs[0].automix_weight_db, Some(3.0));
+        assert_eq!(config.inputs[2].automix_weight_db, None);
+
+        // Only members of the group take a weight
+        config.update_automix_weights(&[Some(-2.0), Some(1.0), Some(4.0)]);
+        assert_eq!(config.inputs[0].automix_weight_db, Some(-2.0));
+        assert_eq!(config.inputs[2].automix_weight_db, None);
+
+        config.inputs[1].automix_weight_db = Some(20.0);
+        assert!(config.validate().is_err());
+        config.inputs[1].automix_weight_db = None;
+        config.outputs[0].automix_weight_db = Some(0.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_device_gain() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    device_gain:
+      source: "alsa_input.usb-mic"
+  - name: "Music"
+    ports: ["music_L", "music_R"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+
+        // Only inputs with a device keep a read volume
+        config.update_device_gains(&[Some(-6.0), Some(-3.0)]);
+        assert_eq!(config.inputs[0].device_gain.as_ref().unwrap().volume_db, Some(-6.0));
+        assert!(config.inputs[1].device_gain.is_none());
+
+        config.inputs[0].device_gain.as_mut().unwrap().volume_db = Some(20.0);
+        assert!(config.validate().is_err());
+        config.inputs[0].device_gain.as_mut().unwrap().volume_db = None;
+        config.outputs[0].device_gain = config.inputs[0].device_gain.clone();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_protect() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Stage"
+    ports: ["stage_in"]
+    protect:
+      dip_db: 12
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let protect = config.inputs[0].protect.clone().unwrap();
+        assert_eq!(protect.threshold_db, -6.0);
+        assert_eq!(protect.dip_db, 12.0);
+        assert_eq!(protect.release_ms, 500.0);
+
+        config.inputs[0].protect.as_mut().unwrap().release_ms = 0.0;
+        assert!(config.validate().is_err());
+        config.inputs[0].protect.as_mut().unwrap().release_ms = 500.0;
+        config.inputs[0].protect.as_mut().unwrap().threshold_db = 3.0;
+        assert!(config.validate().is_err());
+        config.inputs[0].protect = None;
+        config.outputs[0].protect = Some(protect);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_click() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Drums"
+    ports: ["drums_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+  - name: "Drummer"
+    ports: ["drummer_out"]
+click:
+  outputs: ["Drummer"]
+  beats_per_bar: 3
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let click = config.click.clone().unwrap();
+        assert_eq!(click.tempo_bpm, 120.0);
+        assert_eq!(click.beats_per_bar, 3);
+        assert_eq!(click.level_db, -12.0);
+
+        config.click.as_mut().unwrap().tempo_bpm = 400.0;
+        assert!(config.validate().is_err());
+        config.click.as_mut().unwrap().tempo_bpm = 90.0;
+        config.click.as_mut().unwrap().outputs = vec!["Monitor".to_string()];
+        assert!(config.validate().is_err());
+        config.click.as_mut().unwrap().outputs.clear();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_solo_cue() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+  - name: "Headphones"
+    ports: ["phones_L", "phones_R"]
+    solo_cue: {}
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.outputs[0].solo_cue, None);
+        assert_eq!(config.outputs[1].solo_cue, Some(SoloCueConfig { dim_db: -20.0 }));
+
+        config.outputs[1].solo_cue = Some(SoloCueConfig { dim_db: 6.0 });
+        assert!(config.validate().is_err());
+        config.outputs[1].solo_cue = None;
+        config.inputs[0].solo_cue = Some(SoloCueConfig { dim_db: -20.0 });
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_app_streams() {
+        let yaml = r#"
+client_name: "Mixer"
+app_streams:
+  exclude: ["Zoom*"]
+  routes: ["Main"]
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let app_streams = config.app_streams.clone().unwrap();
+        assert_eq!(app_streams.include, ["*"]);
+        assert_eq!(app_streams.exclude, ["Zoom*"]);
+
+        // Temporary inputs are never written out
+        config.inputs.push(ChannelConfig {
+            name: "Firefox".to_string(),
+            ports: vec!["app_firefox_1".to_string()],
+            temporary: true,
+            ..ChannelConfig::default()
+        });
+        let path = std::env::temp_dir()
+            .join(format!("rmixer-app-streams-{}.yaml", std::process::id()));
+        config.config_path = Some(path.to_string_lossy().into_owned());
+        config.save().unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!saved.contains("Firefox"));
+
+        config.app_streams.as_mut().unwrap().routes = vec!["Stream".to_string()];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_mid_side() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Music"
+    ports: ["music_L", "music_R"]
+    mid_side: true
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert!(config.inputs[0].mid_side);
+
+        config.update_mid_side(&[false], &[true]);
+        assert!(!config.inputs[0].mid_side);
+        // Mid and side need two ports
+        assert!(config.validate().is_err());
+        config.update_mid_side(&[true], &[false]);
+
+        // Levels are saved when not 0 dB and limited to the range
+        config.update_mid_side_levels(&[(0.0, -6.0)], &[(0.0, 0.0)]);
+        assert_eq!(config.inputs[0].mid_db, None);
+        assert_eq!(config.inputs[0].side_db, Some(-6.0));
+        assert!(config.validate().is_ok());
+        config.inputs[0].mid_db = Some(MID_SIDE_RANGE_DB + 1.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_mute_and_solo_state() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    soloed: true
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert!(config.inputs[0].soloed && !config.inputs[0].muted);
+
+        config.update_mutes(&[true], &[false], &[], true);
+        config.update_solos(&[false], &[true]);
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let reparsed: Config = serde_yaml::from_str(&yaml).unwrap();
+        assert!(reparsed.inputs[0].muted && !reparsed.inputs[0].soloed);
+        assert!(!reparsed.outputs[0].muted && reparsed.outputs[0].soloed);
+        assert!(reparsed.master_muted);
+        // Unset switches are left out
+        assert!(!yaml.contains("soloed: false"));
+    }
+
+    #[test]
+    fn test_solo_mode() {
+        let yaml = r#"
+client_name: "Mixer"
+solo_mode: exclusive
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    soloed: true
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.solo_mode, SoloMode::Exclusive);
+        assert!(config.validate().is_ok());
+
+        // Exclusive solos cannot be saved more than once
+        config.outputs[0].soloed = true;
+        assert!(config.validate().is_err());
+        config.solo_mode = SoloMode::Additive;
+        assert!(config.validate().is_ok());
+        assert!(!serde_yaml::to_string(&config).unwrap().contains("solo_mode"));
+    }
+
+    #[test]
+    fn test_max_volume() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    volume_db: -3.0
+    max_volume_db: 0.0
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.inputs[0].max_volume_db, Some(0.0));
+
+        config.inputs[0].volume_db = Some(3.0);
+        assert!(config.validate().is_err());
+        config.inputs[0].volume_db = None;
+        config.inputs[0].max_volume_db = Some(20.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_alternate_sources() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    connect: ["system:capture_1"]
+    alternates:
+      - name: "Backup mic"
+        connect: ["system:capture_2"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.inputs[0].alternates[0].name, "Backup mic");
+
+        config.inputs[0].alternates[0].connect.clear();
+        assert!(config.validate().is_err());
+        config.inputs[0].alternates[0].connect.push("system:capture_2".to_string());
+        config.outputs[0].alternates = config.inputs[0].alternates.clone();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_schedule() {
+        let yaml = r#"
+client_name: "Mixer"
+schedule:
+  - when: "0 22 * * *"
+    command: "volume input Music -30"
+    fade_seconds: 60
+  - when: "0 2 * * 1-5"
+    command: "mute master 0 on"
+inputs:
+  - name: "Music"
+    ports: ["music_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.schedule[0].fade_seconds, Some(60.0));
+
+        // Fades only apply to volume changes
+        config.schedule[1].fade_seconds = Some(5.0);
+        assert!(config.validate().is_err());
+        config.schedule[1].fade_seconds = None;
+        config.schedule[1].command = "state".to_string();
+        assert!(config.validate().is_err());
+        config.schedule[1].command = "mute master 0 on".to_string();
+        config.schedule[1].when = "0 25 * * *".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_fader_tapers() {
+        let yaml = r#"
+client_name: "Mixer"
+fader_taper: audio
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    taper: [[0, -60], [0.5, -20], [0.75, 0], [1, 12]]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+    taper: linear_db
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.fader_taper, FaderTaper::Named(TaperCurve::Audio));
+
+        // Equal dB steps stay exact on the default taper
+        let linear = config.outputs[0].taper.clone().unwrap();
+        assert!(linear.is_linear_db());
+        assert_eq!(linear.step(-6.0, 0.5), -5.5);
+        assert_eq!(linear.step(11.8, 0.5), VOLUME_MAX_DB);
+
+        // The audio taper takes small steps at the top and large ones at the bottom
+        let audio = &config.fader_taper;
+        let top = audio.step(0.0, 0.5);
+        let bottom = audio.step(-40.0, 0.5);
+        assert!(top > 0.0 && top < 0.5);
+        assert!(bottom > -39.5);
+        assert_eq!(audio.step(-60.0, -0.5), VOLUME_MIN_DB);
+        assert!((audio.position_to_db(audio.db_to_position(-12.0)) + 12.0).abs() < 1e-3);
+
+        let custom = config.inputs[0].taper.clone().unwrap();
+        assert_eq!(custom.position_to_db(0.25), -40.0);
+        assert_eq!(custom.db_to_position(-10.0), 0.625);
+
+        config.inputs[0].taper = Some(FaderTaper::Custom(vec![[0.0, -60.0], [0.5, 0.0]]));
+        assert!(config.validate().is_err());
+        config.inputs[0].taper = Some(FaderTaper::Custom(vec![[0.0, 0.0], [1.0, -10.0]]));
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_db_display() {
+        let yaml = r#"
+client_name: "Mixer"
+db_display:
+  decimals: 2
+  suffix: true
+  neg_inf_below_db: -59
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let display = config.db_display;
+        assert_eq!(display.format(-6.0), "-6.00 dB");
+        assert_eq!(display.format_signed(3.25), "+3.25 dB");
+        assert_eq!(display.format(VOLUME_MIN_DB), "-inf dB");
+        assert_eq!(display.format_token(-6.0), "-6.00dB");
+
+        let plain = DbDisplay::default();
+        assert_eq!(plain.format(-6.04), "-6.0");
+        assert_eq!(plain.format(VOLUME_MIN_DB), "-60.0");
+
+        config.db_display.decimals = 3;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_meter_scale() {
+        let yaml = r#"
+client_name: "Mixer"
+meter_scale: k14
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
 "#;
 
         let config: Config = serde_yaml::from_str(yaml).unwrap();
-        assert_eq!(config.client_name, "Mixer");
-        assert_eq!(config.inputs.len(), 2);
-        assert_eq!(config.outputs.len(), 1);
-        assert!(!config.inputs[0].is_stereo());
-        assert!(config.inputs[1].is_stereo());
-        assert!(config.outputs[0].is_stereo());
+        assert_eq!(config.meter_scale, MeterScale::K14);
+        assert_eq!(config.meter_scale.range_db(), (-54.0, 0.0));
+        assert_eq!(config.meter_scale.zones_db(), (-14.0, -10.0));
+        assert_eq!(MeterScale::default().zones_db(), (-12.0, 0.0));
+        assert_eq!(config.meter_ballistics, MeterBallistics::Digital);
+
+        let config: Config = serde_yaml::from_str(&yaml.replace("k14", "k14\nmeter_ballistics: vu"))
+            .unwrap();
+        assert_eq!(config.meter_ballistics, MeterBallistics::Vu);
+    }
+
+    #[test]
+    fn test_meter_points() {
+        let yaml = r#"
+client_name: "Mixer"
+meter_point: post_fader
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+  - name: "Music"
+    ports: ["music_L", "music_R"]
+    meter_point: pre_fader
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.input_meter_point(&config.inputs[0]), TapPoint::PostFader);
+        assert_eq!(config.input_meter_point(&config.inputs[1]), TapPoint::PreFader);
+
+        // Points matching the global one are not stored per input
+        config.update_meter_points(&[TapPoint::PreFader, TapPoint::PostFader]);
+        assert_eq!(config.inputs[0].meter_point, Some(TapPoint::PreFader));
+        assert_eq!(config.inputs[1].meter_point, None);
+
+        config.outputs[0].meter_point = Some(TapPoint::PostFader);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_peak_hold_seconds() {
+        let yaml = r#"
+client_name: "Mixer"
+peak_hold_seconds: .inf
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.peak_hold_seconds, Some(f32::INFINITY));
+
+        config.peak_hold_seconds = Some(-1.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_signal_settings() {
+        let yaml = r#"
+client_name: "Mixer"
+signal_threshold_db: -45
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    silence_warning_seconds: 10
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.signal_threshold_db, Some(-45.0));
+        assert_eq!(config.inputs[0].silence_warning_seconds, Some(10.0));
+
+        config.inputs[0].silence_warning_seconds = Some(0.0);
+        assert!(config.validate().is_err());
+        config.inputs[0].silence_warning_seconds = None;
+        config.signal_threshold_db = Some(6.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_control_access() {
+        let yaml = r#"
+client_name: "Mixer"
+control_socket: "0.0.0.0:9100"
+control_access:
+  clients:
+    - address: "127.0.0.1"
+      access: control
+    - address: "192.168.1.0/24"
+      access: control
+    - address: "192.168.1.77"
+      access: observe
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+"#;
+
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let access = config.control_access.clone().unwrap();
+        let ip = |s: &str| Some(s.parse::<IpAddr>().unwrap());
+        assert_eq!(access.access(ip("127.0.0.1")), ControlAccess::Control);
+        assert_eq!(access.access(ip("::ffff:192.168.1.20")), ControlAccess::Control);
+        // The first matching rule wins, anyone else only watches
+        assert_eq!(access.access(ip("192.168.1.77")), ControlAccess::Control);
+        assert_eq!(access.access(ip("10.0.0.5")), ControlAccess::Observe);
+        assert_eq!(access.access(ip("::1")), ControlAccess::Observe);
+        // Unix socket and standard input clients
+        assert_eq!(access.access(None), ControlAccess::Control);
+
+        config.control_access.as_mut().unwrap().clients[1].address = "192.168.1.0/33".into();
+        assert!(config.validate().is_err());
+        config.control_access.as_mut().unwrap().clients[1].address = "studio".into();
+        assert!(config.validate().is_err());
     }
 }
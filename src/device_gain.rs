@@ -0,0 +1,246 @@
+//! Capture device gain through PipeWire
+//!
+//! Inputs with `device_gain` control the volume and mute of their capture
+//! device itself, as pavucontrol does, next to the mixer's own fader: the
+//! gain in front of the converter is where a clipping interface or a
+//! too-quiet USB mic has to be fixed. Settings go through `pactl` (on
+//! PipeWire's pulse server or PulseAudio) on a worker thread so a slow call
+//! never holds up the UI, and when keys repeat faster than `pactl` runs
+//! only the latest value per device is sent. After every call the device is
+//! read back, so the strip shows what the device really took.
+
+use anyhow::{Context, Result};
+use std::mem::{discriminant, Discriminant};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::config::{Config, DEVICE_GAIN_MAX_DB};
+use crate::ipc::VOLUME_MIN_DB;
+
+/// A change to make on an input's device
+#[derive(Debug, Clone)]
+enum Request {
+    /// Read the device (setting the saved volume first, if any)
+    Read {
+        input: usize,
+        source: String,
+        volume_db: Option<f32>,
+    },
+
+    /// Set the device volume
+    Volume {
+        input: usize,
+        source: String,
+        volume_db: f32,
+    },
+
+    /// Mute or unmute the device
+    Mute {
+        input: usize,
+        source: String,
+        muted: bool,
+    },
+}
+
+impl Request {
+    /// Input and kind of change; a later request with the same key makes
+    /// an earlier one pointless
+    fn key(&self) -> (usize, Discriminant<Self>) {
+        let input = match self {
+            Request::Read { input, .. }
+            | Request::Volume { input, .. }
+            | Request::Mute { input, .. } => *input,
+        };
+        (input, discriminant(self))
+    }
+
+    /// Run the change and read the device back
+    fn run(&self) -> DeviceEvent {
+        let (input, source) = match self {
+            Request::Read { input, source, .. }
+            | Request::Volume { input, source, .. }
+            | Request::Mute { input, source, .. } => (*input, source.as_str()),
+        };
+        let result = match self {
+            Request::Read {
+                volume_db: Some(volume_db),
+                ..
+            }
+            | Request::Volume { volume_db, .. } => set_volume(source, *volume_db),
+            Request::Read {
+                volume_db: None, ..
+            } => Ok(()),
+            Request::Mute { muted, .. } => pactl(&[
+                "set-source-mute",
+                "--",
+                source,
+                if *muted { "1" } else { "0" },
+            ])
+            .map(|_| ()),
+        }
+        .and_then(|()| read(source));
+        match result {
+            Ok((volume_db, muted)) => DeviceEvent::State {
+                input,
+                volume_db,
+                muted,
+            },
+            Err(e) => DeviceEvent::Failed {
+                input,
+                error: format!("{:#}", e),
+            },
+        }
+    }
+}
+
+/// What the worker reports back
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceEvent {
+    /// Current volume and mute of an input's device
+    State {
+        input: usize,
+        volume_db: f32,
+        muted: bool,
+    },
+
+    /// A `pactl` call for an input's device failed
+    Failed { input: usize, error: String },
+}
+
+/// Worker running `pactl` for the inputs' devices
+pub struct DeviceGains {
+    requests: Sender<Request>,
+    events: Receiver<DeviceEvent>,
+}
+
+impl DeviceGains {
+    /// Start the worker and read (or set, where a volume is saved) every
+    /// configured device; None if no input has a device
+    pub fn start(config: &Config) -> Option<Self> {
+        let (requests, pending) = mpsc::channel::<Request>();
+        let (reports, events) = mpsc::channel();
+        let mut any = false;
+        for (input, channel) in config.inputs.iter().enumerate() {
+            if let Some(device) = &channel.device_gain {
+                any = true;
+                let _ = requests.send(Request::Read {
+                    input,
+                    source: device.source.clone(),
+                    volume_db: device.volume_db,
+                });
+            }
+        }
+        if !any {
+            return None;
+        }
+
+        thread::spawn(move || {
+            while let Ok(first) = pending.recv() {
+                let mut latest: Vec<Request> = Vec::new();
+                for request in std::iter::once(first).chain(pending.try_iter()) {
+                    latest.retain(|r| r.key() != request.key());
+                    latest.push(request);
+                }
+                for request in latest {
+                    if reports.send(request.run()).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Some(Self { requests, events })
+    }
+
+    /// Set the volume of an input's device
+    pub fn set_volume(&self, input: usize, source: &str, volume_db: f32) {
+        let _ = self.requests.send(Request::Volume {
+            input,
+            source: source.to_string(),
+            volume_db,
+        });
+    }
+
+    /// Mute or unmute an input's device
+    pub fn set_mute(&self, input: usize, source: &str, muted: bool) {
+        let _ = self.requests.send(Request::Mute {
+            input,
+            source: source.to_string(),
+            muted,
+        });
+    }
+
+    /// Take the next report from the worker, if any
+    pub fn try_recv(&self) -> Option<DeviceEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+/// Run `pactl` and return its output
+fn pactl(args: &[&str]) -> Result<String> {
+    let output = Command::new("pactl")
+        .args(args)
+        .output()
+        .context("Failed to run pactl (is pipewire-pulse or PulseAudio running?)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "pactl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Set the volume of a source on all its channels
+fn set_volume(source: &str, volume_db: f32) -> Result<()> {
+    let volume = format!(
+        "{:.2}dB",
+        volume_db.clamp(VOLUME_MIN_DB, DEVICE_GAIN_MAX_DB)
+    );
+    pactl(&["set-source-volume", "--", source, &volume]).map(|_| ())
+}
+
+/// Read the volume and mute of a source
+fn read(source: &str) -> Result<(f32, bool)> {
+    let volume = pactl(&["get-source-volume", "--", source])?;
+    let mute = pactl(&["get-source-mute", "--", source])?;
+    let volume_db = parse_volume_db(&volume).context("Unexpected pactl volume output")?;
+    let muted = parse_mute(&mute).context("Unexpected pactl mute output")?;
+    Ok((volume_db, muted))
+}
+
+/// Volume in dB of the first channel in `pactl get-source-volume` output,
+/// e.g. `Volume: front-left: 32768 /  50% / -18.06 dB, ...`
+pub fn parse_volume_db(text: &str) -> Option<f32> {
+    let (before, _) = text.split_once(" dB")?;
+    let db: f32 = before.rsplit(' ').next()?.parse().ok()?;
+    Some(db.max(VOLUME_MIN_DB))
+}
+
+/// Mute state in `pactl get-source-mute` output, e.g. `Mute: no`
+pub fn parse_mute(text: &str) -> Option<bool> {
+    match text.trim().strip_prefix("Mute:")?.trim() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pactl_output() {
+        let volume = "Volume: front-left: 32768 /  50% / -18.06 dB,   \
+                      front-right: 32768 /  50% / -18.06 dB\n        balance 0.00\n";
+        assert_eq!(parse_volume_db(volume), Some(-18.06));
+        let silent = "Volume: mono: 0 /   0% / -inf dB\n        balance 0.00\n";
+        assert_eq!(parse_volume_db(silent), Some(VOLUME_MIN_DB));
+        assert_eq!(parse_volume_db("No such entity"), None);
+
+        assert_eq!(parse_mute("Mute: yes\n"), Some(true));
+        assert_eq!(parse_mute("Mute: no\n"), Some(false));
+        assert_eq!(parse_mute("Failure: No such entity"), None);
+    }
+}
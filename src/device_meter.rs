@@ -0,0 +1,180 @@
+//! Capture device peaks through PipeWire
+//!
+//! Inputs with `device_meter` get a second meter in the big meter showing
+//! the peaks of their capture device as its PipeWire (or PulseAudio) source
+//! delivers them, ahead of the mixer's trim, processing and fader. With
+//! both side by side a clip can be placed: a device meter at full scale
+//! means the converter or the device gain clipped, a device meter with
+//! headroom under a clipping mixer meter means the mixer did. Each source
+//! is recorded by its own `parec` on a worker thread, which reports the
+//! peak and the full-scale samples of every short block.
+
+use anyhow::{Context, Result};
+use std::io::{BufReader, Read};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::config::Config;
+use crate::ipc::MAX_CHANNEL_PORTS;
+
+/// Frames per report, about 50 ms at 48 kHz
+const BLOCK_FRAMES: usize = 2400;
+
+/// Bytes of a 32-bit float sample
+const SAMPLE_BYTES: usize = 4;
+
+/// What the workers report back
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceMeterEvent {
+    /// Peak per channel of the source over the last block, and its samples
+    /// at or above full scale
+    Peaks {
+        input: usize,
+        peaks: [f32; MAX_CHANNEL_PORTS],
+        clipped: u32,
+    },
+
+    /// Recording the source failed or stopped
+    Failed { input: usize, error: String },
+}
+
+/// Recorders of the inputs' device sources
+pub struct DeviceMeters {
+    /// One `parec` per metered source, stopped on drop
+    children: Vec<Child>,
+
+    events: Receiver<DeviceMeterEvent>,
+}
+
+impl DeviceMeters {
+    /// Start recording every configured source; None if no input has a
+    /// device meter
+    pub fn start(config: &Config) -> Option<Self> {
+        let (reports, events) = mpsc::channel();
+        let mut children = Vec::new();
+        let mut any = false;
+        for (input, channel) in config.inputs.iter().enumerate() {
+            let Some(source) = &channel.device_meter else {
+                continue;
+            };
+            any = true;
+            let channels = channel.port_count().clamp(1, MAX_CHANNEL_PORTS);
+            match record(source, channels) {
+                Ok(mut child) => {
+                    if let Some(stdout) = child.stdout.take() {
+                        let reports = reports.clone();
+                        thread::spawn(move || read_peaks(input, channels, stdout, reports));
+                    }
+                    children.push(child);
+                }
+                Err(e) => {
+                    let _ = reports.send(DeviceMeterEvent::Failed {
+                        input,
+                        error: format!("{:#}", e),
+                    });
+                }
+            }
+        }
+        any.then_some(Self { children, events })
+    }
+
+    /// Take the next report from the workers, if any
+    pub fn try_recv(&self) -> Option<DeviceMeterEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+impl Drop for DeviceMeters {
+    fn drop(&mut self) {
+        for child in &mut self.children {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Start `parec` on a source, writing raw 32-bit floats at the source's
+/// own rate
+fn record(source: &str, channels: usize) -> Result<Child> {
+    Command::new("parec")
+        .args([
+            "--raw",
+            "--format=float32le",
+            "--fix-rate",
+            "--latency-msec=20",
+        ])
+        .arg(format!("--channels={}", channels))
+        .arg(format!("--device={}", source))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to run parec (is pipewire-pulse or PulseAudio running?)")
+}
+
+/// Worker: report the peaks of each block until the recorder stops
+fn read_peaks(
+    input: usize,
+    channels: usize,
+    stdout: ChildStdout,
+    reports: Sender<DeviceMeterEvent>,
+) {
+    let mut reader = BufReader::new(stdout);
+    let mut block = vec![0u8; BLOCK_FRAMES * channels * SAMPLE_BYTES];
+    loop {
+        if let Err(e) = reader.read_exact(&mut block) {
+            let _ = reports.send(DeviceMeterEvent::Failed {
+                input,
+                error: format!("parec stopped: {}", e),
+            });
+            return;
+        }
+        let (peaks, clipped) = block_peaks(&block, channels);
+        if reports
+            .send(DeviceMeterEvent::Peaks {
+                input,
+                peaks,
+                clipped,
+            })
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Peak per channel of interleaved little-endian floats, and the samples
+/// at or above full scale
+fn block_peaks(bytes: &[u8], channels: usize) -> ([f32; MAX_CHANNEL_PORTS], u32) {
+    let mut peaks = [0.0f32; MAX_CHANNEL_PORTS];
+    let mut clipped = 0;
+    let samples = bytes
+        .chunks_exact(SAMPLE_BYTES)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]).abs());
+    for (i, sample) in samples.enumerate() {
+        let peak = &mut peaks[i % channels];
+        *peak = peak.max(sample);
+        clipped += u32::from(sample >= 1.0);
+    }
+    (peaks, clipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_peaks() {
+        let samples = [0.25f32, -0.5, -0.75, 1.0, 0.1, -1.2];
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        let (peaks, clipped) = block_peaks(&bytes, 2);
+        assert_eq!(&peaks[..2], &[0.75, 1.2]);
+        assert_eq!(clipped, 2);
+
+        let (mono, _) = block_peaks(&bytes, 1);
+        assert_eq!(mono[0], 1.2);
+        assert_eq!(mono[1], 0.0);
+    }
+}
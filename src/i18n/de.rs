@@ -0,0 +1,306 @@
+//! German UI strings
+
+use super::Msg;
+
+pub fn lookup(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::HelpSelect => "Wahl",
+        Msg::HelpVolume => "Pegel",
+        Msg::HelpTrim => "Trim",
+        Msg::HelpMidSide => "M/S",
+        Msg::HelpMidSideLevels => "Mitte/Seite",
+        Msg::HelpMeterPoint => "Pre/Post",
+        Msg::HelpSplit => "Lautheit",
+        Msg::HelpCalibrate => "Einmessen",
+        Msg::HelpSource => "Quelle",
+        Msg::HelpFlip => "Flip",
+        Msg::HelpResetMeters => "Spitzen zurücksetzen",
+        Msg::HelpZeroDb => "0dB",
+        Msg::HelpMute => "Stumm",
+        Msg::HelpSolo => "Solo",
+        Msg::HelpEq => "EQ",
+        Msg::HelpEqBands => "EQ-Bänder/Multiband",
+        Msg::HelpReport => "Bericht",
+        Msg::HelpRecord => "Aufn.",
+        Msg::HelpSwitch => "Wechseln",
+        Msg::HelpEdit => "Bearbeiten",
+        Msg::HelpRoute => "Routing",
+        Msg::HelpMatrix => "Matrix",
+        Msg::HelpLoudest => "Lautester",
+        Msg::HelpContributions => "Quellen",
+        Msg::HelpGoniometer => "Phase",
+        Msg::HelpSpectrogram => "Spektrum",
+        Msg::HelpOscilloscope => "Oszilloskop",
+        Msg::HelpRta => "RTA",
+        Msg::HelpFeedback => "Rückkopplung",
+        Msg::HelpClips => "Clips",
+        Msg::HelpBigMeter => "Großanzeige",
+        Msg::HelpBalance => "Balance",
+        Msg::HelpFilters => "HP/TP",
+        Msg::HelpDiagnostics => "Diagnose",
+        Msg::HelpReplay => "Mitschnitt",
+        Msg::HelpSuspend => "Pause",
+        Msg::HelpQuit => "Beenden",
+
+        Msg::SectionInputs => "EINGÄNGE",
+        Msg::SectionOutputs => "AUSGÄNGE",
+        Msg::SectionMaster => "MASTER",
+        Msg::SectionPassthrough => "DURCHSCHLEIFEN",
+
+        Msg::MissingDeviceTitle => "Fehlendes Gerät",
+        Msg::MissingDeviceFor => "Das konfigurierte Gerät für {} fehlt:",
+        Msg::ConnectClosestMatch => "Stattdessen mit dem ähnlichsten Gerät verbinden?",
+        Msg::PromptYes => "Ja",
+        Msg::PromptNo => "Nein",
+        Msg::PromptAll => "Alle ({})",
+        Msg::PromptSkipAll => "Alle überspringen",
+
+        Msg::StatusConnectionsMissing => {
+            "{} konfigurierte Verbindung(en) fehlen - Ports bleiben unverbunden"
+        }
+        Msg::StatusOverBudget => {
+            "Konfiguration schafft {} Frames evtl. nicht (Last ca. {}%), besser {} Frames oder mehr"
+        }
+        Msg::StatusOverBudgetAny => {
+            "Konfiguration schafft evtl. keine Puffergröße (geschätzte Last {}%)"
+        }
+        Msg::StatusPortUnrouted => "'{}' bleibt unverbunden",
+        Msg::StatusConnectionsUnrouted => "{} Verbindung(en) bleiben unverbunden",
+        Msg::StatusConfigApplied => "Konfiguration übernommen und gespeichert",
+        Msg::StatusApplyFailed => "Übernehmen fehlgeschlagen: {}",
+        Msg::StatusLoudest => "Lautester Eingang: {} ({} dB)",
+        Msg::StatusLoudestMuted => "Lautester Eingang stummgeschaltet: {} ({} dB)",
+        Msg::StatusNothingAudible => "Kein Eingang hörbar",
+        Msg::StatusTrim => "Eingangspegel {}: {} dB",
+        Msg::StatusDeviceGain => "Gerät {}: {} dB",
+        Msg::StatusDeviceMuted => "Gerät {} stummgeschaltet",
+        Msg::StatusDeviceUnmuted => "Gerät {} aktiv",
+        Msg::StatusNoDeviceGain => "{} hat kein device_gain",
+        Msg::StatusDeviceGainFailed => "Geräteverstärkung von {} fehlgeschlagen: {}",
+        Msg::StatusDeviceMeterFailed => "Gerätemessung von {} fehlgeschlagen: {}",
+        Msg::StatusLoudEvent => "Lautes Ereignis auf {}: um {} dB abgesenkt",
+        Msg::StatusNoClick => "Kein Klick konfiguriert",
+        Msg::StatusClickTempo => "Klick-Tempo {} BPM",
+        Msg::StatusAutomixWeight => "Automix-Gewichtung {}: {} dB",
+        Msg::StatusMidSideOn => "{}: Anzeige und Pegel Mitte/Seite",
+        Msg::StatusMidSideOff => "{}: Anzeige links/rechts",
+        Msg::StatusMidSideStereo => "Mitte/Seite braucht einen Stereo-Ein- oder -Ausgang",
+        Msg::StatusMidSideLevels => "{}: Mitte {} dB, Seite {} dB",
+        Msg::StatusMidSideNeeded => "{}: zuerst auf Mitte/Seite schalten (M)",
+        Msg::StatusBalanceStereo => "Balance braucht einen Stereo-Eingang",
+        Msg::StatusBalanceMidSide => "Mitte/Seite-Anzeige zum Messen der Balance ausschalten",
+        Msg::StatusBalanceSet => "Balance von {} auf {} dB gesetzt",
+        Msg::StatusMeterPre => "{}: Anzeige vor dem Fader",
+        Msg::StatusMeterPost => "{}: Anzeige nach dem Fader",
+        Msg::StatusMeterPreAll => "Alle Eingänge: Anzeige vor dem Fader",
+        Msg::StatusMeterPostAll => "Alle Eingänge: Anzeige nach dem Fader",
+        Msg::StatusAppStreamAdded => "Eingang für {} angelegt",
+        Msg::StatusAppStreamRemoved => "Eingang von {} entfernt",
+        Msg::StatusAppStreamsFailed => "Anwendungseingänge nicht geändert: {}",
+        Msg::StatusCalibrated => "Ausgangspegel angeglichen ({} geändert)",
+        Msg::StatusNoCalibration => "Keine Einmessung konfiguriert",
+        Msg::StatusSplitNarrow => "Lautheitsanzeige braucht ein {} Spalten breites Terminal",
+        Msg::StatusScheduled => "Zeitplan: {}",
+        Msg::StatusScheduleFailed => "Zeitplan '{}' fehlgeschlagen: {}",
+        Msg::StatusSelectOutput => "Zuerst einen Ausgang wählen",
+        Msg::StatusSelectStereoOutput => "Zuerst einen Stereo-Ausgang wählen",
+        Msg::StatusSelectInputOrOutput => "Zuerst einen Eingang oder Ausgang wählen",
+        Msg::StatusSelectInput => "Zuerst einen Eingang wählen",
+        Msg::StatusNotInAutomix => "{} gehört nicht zur Automix-Gruppe",
+        Msg::StatusNoAlternates => "'{}' hat keine Ersatzquellen",
+        Msg::StatusSourceSwitched => "'{}' umgeschaltet auf {}",
+        Msg::StatusSourcePrimary => "'{}' wieder auf der Hauptquelle",
+        Msg::StatusSourceFailed => "Quellwechsel von '{}' fehlgeschlagen: {}",
+        Msg::StatusMetersReset => "Spitzenwerte und Clip-Zähler zurückgesetzt",
+        Msg::StatusFilterOn => "{} an bei {} Hz",
+        Msg::StatusFilterOff => "{} aus",
+        Msg::StatusReplaySaved => "Letzte {} s gespeichert in {}",
+        Msg::StatusReplayFailed => "Mitschnitt speichern fehlgeschlagen: {}",
+        Msg::StatusReportSaved => "Sitzungsbericht gespeichert in {}",
+        Msg::StatusReportFailed => "Sitzungsbericht fehlgeschlagen: {}",
+        Msg::StatusRecording => "Aufnahme läuft: {}",
+        Msg::StatusFreewheelFailed => "Freilauf nicht verfügbar, Aufnahme in Echtzeit: {}",
+        Msg::StatusRecordExporting => "Exportiere {}",
+        Msg::StatusRecordSaved => "Aufnahme gespeichert in {}",
+        Msg::StatusRecordFailed => "Aufnahme fehlgeschlagen: {}",
+        Msg::RecordWaiting => "wartet auf Signal",
+        Msg::RecordFreewheeling => "Freilauf",
+        Msg::StatusSceneRecalled => "Szene: {}",
+        Msg::StatusStartup => "Fader beim Start: {} aus --set, {} gespeichert, {} Standard",
+        Msg::StatusStartupScene => {
+            "Szene '{}' beim Start; Fader: {} aus ihr, {} aus --set, {} gespeichert, {} Standard"
+        }
+        Msg::StatusNotchSet => "Kerbfilter bei {} Hz auf {}",
+        Msg::StatusNotchesFull => "Alle {} Kerbfilter von {} belegt (c löscht sie)",
+        Msg::StatusNotchesCleared => "Kerbfilter von {} gelöscht",
+        Msg::StatusNoRinging => "Keine klingelnde Frequenz zum Absenken",
+        Msg::StatusShellFailed => "Shell fehlgeschlagen: {}",
+        Msg::SuspendShell => "Audio läuft in {} weiter. Mit 'exit' geht es zurück zum Mixer.",
+
+        Msg::EditorTitle => "Konfiguration",
+        Msg::EditorClient => "Client",
+        Msg::EditorConfirm => "Bestätigen",
+        Msg::EditorCancel => "Abbrechen",
+        Msg::EditorPortsHint => "(Ports: kommagetrennt)",
+        Msg::EditorRow => "Zeile",
+        Msg::EditorField => "Feld",
+        Msg::EditorEditCycle => "Bearbeiten/Wechseln",
+        Msg::EditorAddChannel => "Ein/Aus hinzufügen",
+        Msg::EditorDelete => "Löschen",
+        Msg::EditorApplySave => "Übernehmen+Speichern",
+        Msg::EditorClose => "Schließen",
+        Msg::EditorDiscardPrompt => {
+            "Nicht übernommene Änderungen - erneut Esc drücken zum Verwerfen"
+        }
+        Msg::EditorInvalid => "Ungültig: {}",
+        Msg::EditorMono => "mono",
+        Msg::EditorStereo => "stereo",
+        Msg::EditorSurround => "{}-kanal",
+        Msg::EditorTap => "Abgriff",
+        Msg::EditorTapOff => "aus",
+        Msg::EditorTapPre => "vor",
+        Msg::EditorTapPost => "nach",
+        Msg::EditorNewInput => "Eingang {}",
+        Msg::EditorNewOutput => "Ausgang {}",
+
+        Msg::MatrixTitle => "Routing-Matrix",
+        Msg::MatrixToggle => "Ein/Aus",
+        Msg::MatrixMono => "Mono",
+        Msg::MatrixTrim => "Pegel",
+        Msg::EqTitle => "EQ: {}",
+        Msg::EqLow => "Tiefen",
+        Msg::EqMid => "Mitten",
+        Msg::EqHigh => "Höhen",
+        Msg::EqBandLabel => "Band {}",
+        Msg::EqAdjust => "Ändern",
+        Msg::EqAdd => "Band hinzufügen",
+        Msg::EqRemove => "Entfernen",
+        Msg::EqHighPass => "HP",
+        Msg::EqLowPass => "TP",
+        Msg::EqToggle => "An/aus",
+        Msg::FilterHighPass => "Hochpass",
+        Msg::FilterLowPass => "Tiefpass",
+
+        Msg::MbTitle => "Multiband: {}",
+        Msg::MbOn => "an",
+        Msg::MbOff => "aus",
+        Msg::MbReduction => "Reduktion",
+        Msg::MbThreshold => "Schwelle",
+        Msg::MbRatio => "Ratio",
+        Msg::MbMakeup => "Aufholen",
+        Msg::MbCrossover => "Übergang",
+        Msg::MbAttack => "Attack",
+        Msg::MbRelease => "Release",
+
+        Msg::ReminderSolo => "Solo ist seit {} min aktiv",
+        Msg::ReminderMuted => "{} ist seit {} min stumm, obwohl Signal anliegt",
+        Msg::ReminderLater => "a: später erinnern",
+        Msg::AlertStalled => "AUDIO HÄNGT - der JACK-Process-Callback läuft nicht",
+        Msg::AlertShutdown => "JACK-SERVER HAT DEN CLIENT BEENDET - Audio gestoppt, rmixer neu starten",
+        Msg::TitleStalled => "HÄNGT",
+        Msg::TitleShutdown => "GESTOPPT",
+
+        Msg::DiagTitle => "Diagnose",
+        Msg::DiagLatency => "Steuerlatenz (UI -> Audio)",
+        Msg::DiagLast => "zuletzt",
+        Msg::DiagAverage => "Mittel",
+        Msg::DiagMax => "Maximum",
+        Msg::DiagLate => "verspätet (> {} ms)",
+        Msg::DiagApplied => "angewendet",
+        Msg::DiagDropped => "verworfen (Queue voll)",
+        Msg::DiagQueued => "wartend",
+        Msg::DiagPeriod => "JACK-Periode",
+        Msg::DiagLoad => "Geschätzte Last",
+        Msg::DiagLoadValue => "{}% der Periode ({} Ports, {} Summen)",
+        Msg::DiagInputPorts => "Eingangsports",
+        Msg::DiagPortsActive => "alle verbunden, keiner stumm",
+        Msg::DiagPortDisconnected => "nicht verbunden",
+        Msg::DiagPortSilent => "verbunden, nur Nullen seit {} s+",
+        Msg::DiagReset => "Zurücksetzen",
+        Msg::DiagClose => "Schließen",
+        Msg::ModeConfirm => "BESTÄTIGEN",
+        Msg::ModeDiagnostics => "DIAGNOSE",
+        Msg::ModeContributions => "QUELLEN",
+        Msg::ModeGoniometer => "PHASE",
+        Msg::ModeSpectrogram => "SPEKTRUM",
+        Msg::ModeOscilloscope => "OSZILLOSKOP",
+        Msg::ModeRta => "RTA",
+        Msg::ModeFeedback => "RÜCKKOPPLUNG",
+        Msg::ModeBalance => "BALANCE",
+        Msg::ModeClips => "CLIPS",
+        Msg::ModeBigMeter => "GROSSANZEIGE",
+        Msg::ModeEditor => "EDITOR",
+        Msg::ModeMatrix => "MATRIX",
+        Msg::ModeEq => "EQ",
+        Msg::ModeMultiband => "MULTIBAND",
+        Msg::ModeCalibration => "EINMESSEN",
+        Msg::ModeFlip => "FLIP",
+        Msg::FlipBanner => {
+            "FLIP: ↑/↓ stellt {} des gewählten Eingangs ein · g nächste Ebene · Esc zurück zu den Fadern"
+        }
+        Msg::FlipTrim => "die Vorverstärkung",
+        Msg::FlipDeviceGain => "die Geräteverstärkung (m schaltet das Gerät stumm)",
+        Msg::FlipSend => "den Send auf {}",
+        Msg::FlipAutomix => "die Automix-Gewichtung",
+        Msg::ContribTitle => "In {}",
+        Msg::ContribNotRouted => "nicht geroutet",
+        Msg::GonioTitle => "Phase von {}",
+        Msg::SpecTitle => "Spektrogramm von {}",
+        Msg::SpecFloor => "Untergrenze",
+        Msg::SpecFloorValue => "Untergrenze {} dBFS",
+        Msg::OscTitle => "Oszilloskop von {}",
+        Msg::OscZoom => "Zoom",
+        Msg::OscTrigger => "Trigger",
+        Msg::OscLevel => "Pegel",
+        Msg::OscFreeze => "Anhalten",
+        Msg::OscFrozen => "ANGEHALTEN",
+        Msg::OscRising => "Steigend",
+        Msg::OscFalling => "Fallend",
+        Msg::OscFree => "Frei",
+        Msg::OscPeak => "Spitze {} dBFS",
+        Msg::FbTitle => "Rückkopplungssuche auf {}",
+        Msg::FbListening => "Horche… ein Ton, der {} s anhält, erscheint hier",
+        Msg::FbRinging => "Klingelt",
+        Msg::FbBuilding => "Baut sich auf",
+        Msg::FbNotches => "Kerbfilter",
+        Msg::FbNoNotches => "keine",
+        Msg::FbNotch => "Lautesten absenken",
+        Msg::FbClear => "Kerbfilter löschen",
+        Msg::ClipsTitle => "Übersteuerungen ({})",
+        Msg::ClipsNone => "Bisher keine Übersteuerung",
+        Msg::ClipsDropped => "... und {} weitere, nicht gespeichert",
+        Msg::RtaTitle => "Terzanalyse von {}",
+        Msg::RtaWeighting => "Bewertung",
+        Msg::RtaSpeed => "Schnell/langsam",
+        Msg::RtaNoise => "Rauschen",
+        Msg::RtaFast => "schnell",
+        Msg::RtaSlow => "langsam",
+        Msg::RtaStatus => "{}-bewertet, {}, lautestes Band {} Hz mit {} dB",
+        Msg::RtaNoiseOn => "Rosa Rauschen auf {}",
+        Msg::LoudnessTitle => "Lautheit: {}",
+        Msg::LoudnessNow => "Jetzt",
+        Msg::LoudnessMax => "Max",
+        Msg::LoudnessFocus => "Anzeige wählen",
+        Msg::LoudnessStrips => "Zurück zu den Kanälen",
+        Msg::CalTitle => "Lautsprecher einmessen",
+        Msg::CalIntro => "Rosa Rauschen mit {} dBFS nacheinander auf jedem Ausgang, gemessen an {}",
+        Msg::CalOutput => "Ausgang",
+        Msg::CalReturn => "Pegel",
+        Msg::CalSuggested => "Änderung",
+        Msg::CalNoSignal => "kein Signal",
+        Msg::CalApply => "Übernehmen",
+        Msg::CalRestart => "Neu starten",
+        Msg::CalStop => "Stopp",
+        Msg::BalanceTitle => "Stereo-Balance",
+        Msg::BalanceIntro => "Langzeitpegel des linken und rechten Ports von {}, vor der Balance",
+        Msg::BalanceLeft => "Links",
+        Msg::BalanceRight => "Rechts",
+        Msg::BalanceDifference => "Differenz",
+        Msg::BalanceCurrent => "Balance",
+        Msg::BalanceSignal => "Signal",
+        Msg::BalanceMeasuring => "Misst, solange die Quelle spielt …",
+        Msg::BalanceSuggested => "Vorgeschlagene Balance: {} dB",
+        Msg::BalanceEven => "Die Ports sind ausgeglichen: keine Korrektur nötig",
+        Msg::BalanceInconsistent => "Die Differenz ändert sich mit dem Programm: keine Korrektur",
+    })
+}
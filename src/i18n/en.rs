@@ -0,0 +1,308 @@
+//! English UI strings (reference locale, must cover every key)
+
+use super::Msg;
+
+pub fn text(msg: Msg) -> &'static str {
+    match msg {
+        Msg::HelpSelect => "Sel",
+        Msg::HelpVolume => "Vol",
+        Msg::HelpTrim => "Trim",
+        Msg::HelpMidSide => "M/S",
+        Msg::HelpMidSideLevels => "Mid/Side",
+        Msg::HelpMeterPoint => "pre/post",
+        Msg::HelpSplit => "Loudness",
+        Msg::HelpCalibrate => "Calibrate",
+        Msg::HelpSource => "Source",
+        Msg::HelpFlip => "Flip",
+        Msg::HelpResetMeters => "Reset peaks",
+        Msg::HelpZeroDb => "0dB",
+        Msg::HelpMute => "Mute",
+        Msg::HelpSolo => "Solo",
+        Msg::HelpEq => "EQ",
+        Msg::HelpEqBands => "EQ bands/multiband",
+        Msg::HelpReport => "Report",
+        Msg::HelpRecord => "Rec",
+        Msg::HelpSwitch => "Switch",
+        Msg::HelpEdit => "Edit",
+        Msg::HelpRoute => "Route",
+        Msg::HelpMatrix => "Matrix",
+        Msg::HelpLoudest => "Loudest",
+        Msg::HelpContributions => "Sources",
+        Msg::HelpGoniometer => "Phase",
+        Msg::HelpSpectrogram => "Spectrum",
+        Msg::HelpOscilloscope => "Scope",
+        Msg::HelpRta => "RTA",
+        Msg::HelpFeedback => "Feedback",
+        Msg::HelpClips => "Clips",
+        Msg::HelpBigMeter => "Big meter",
+        Msg::HelpBalance => "Balance",
+        Msg::HelpFilters => "HPF/LPF",
+        Msg::HelpDiagnostics => "Diag",
+        Msg::HelpReplay => "Replay",
+        Msg::HelpSuspend => "Suspend",
+        Msg::HelpQuit => "Quit",
+
+        Msg::SectionInputs => "INPUTS",
+        Msg::SectionOutputs => "OUTPUTS",
+        Msg::SectionMaster => "MASTER",
+        Msg::SectionPassthrough => "PASSTHROUGH",
+
+        Msg::MissingDeviceTitle => "Missing Device",
+        Msg::MissingDeviceFor => "Configured device for {} is missing:",
+        Msg::ConnectClosestMatch => "Connect to the closest match instead?",
+        Msg::PromptYes => "Yes",
+        Msg::PromptNo => "No",
+        Msg::PromptAll => "All ({})",
+        Msg::PromptSkipAll => "Skip all",
+
+        Msg::StatusConnectionsMissing => {
+            "{} configured connection(s) missing - ports left unrouted"
+        }
+        Msg::StatusOverBudget => {
+            "Config may not keep up at {} frames (estimated load {}%), try {} frames or more"
+        }
+        Msg::StatusOverBudgetAny => {
+            "Config may not keep up at any buffer size (estimated load {}%)"
+        }
+        Msg::StatusPortUnrouted => "'{}' left unrouted",
+        Msg::StatusConnectionsUnrouted => "{} connection(s) left unrouted",
+        Msg::StatusConfigApplied => "Configuration applied and saved",
+        Msg::StatusApplyFailed => "Apply failed: {}",
+        Msg::StatusLoudest => "Loudest input: {} ({} dB)",
+        Msg::StatusLoudestMuted => "Muted loudest input: {} ({} dB)",
+        Msg::StatusNothingAudible => "No input is audible",
+        Msg::StatusTrim => "Trim {}: {} dB",
+        Msg::StatusDeviceGain => "Device {}: {} dB",
+        Msg::StatusDeviceMuted => "Device {} muted",
+        Msg::StatusDeviceUnmuted => "Device {} unmuted",
+        Msg::StatusNoDeviceGain => "{} has no device_gain",
+        Msg::StatusDeviceGainFailed => "Device gain of {} failed: {}",
+        Msg::StatusDeviceMeterFailed => "Device meter of {} failed: {}",
+        Msg::StatusLoudEvent => "Loud event on {}: dipped by {} dB",
+        Msg::StatusNoClick => "No click configured",
+        Msg::StatusClickTempo => "Click tempo {} BPM",
+        Msg::StatusAutomixWeight => "Automix weight {}: {} dB",
+        Msg::StatusMidSideOn => "{}: meters and levels in mid/side",
+        Msg::StatusMidSideOff => "{}: meters show left/right",
+        Msg::StatusMidSideStereo => "Mid/side needs a stereo input or output",
+        Msg::StatusMidSideLevels => "{}: mid {} dB, side {} dB",
+        Msg::StatusMidSideNeeded => "{}: switch to mid/side first (M)",
+        Msg::StatusBalanceStereo => "Balance needs a stereo input",
+        Msg::StatusBalanceMidSide => "Switch off mid/side metering to measure the balance",
+        Msg::StatusBalanceSet => "{} balance set to {} dB",
+        Msg::StatusMeterPre => "{}: meters show pre-fader level",
+        Msg::StatusMeterPost => "{}: meters show post-fader level",
+        Msg::StatusMeterPreAll => "All input meters show pre-fader level",
+        Msg::StatusMeterPostAll => "All input meters show post-fader level",
+        Msg::StatusAppStreamAdded => "Added an input for {}",
+        Msg::StatusAppStreamRemoved => "Removed the input of {}",
+        Msg::StatusAppStreamsFailed => "Application inputs not changed: {}",
+        Msg::StatusCalibrated => "Output volumes matched ({} changed)",
+        Msg::StatusNoCalibration => "No calibration configured",
+        Msg::StatusSplitNarrow => "Loudness pane needs a terminal {} columns wide",
+        Msg::StatusScheduled => "Scheduled: {}",
+        Msg::StatusScheduleFailed => "Scheduled '{}' failed: {}",
+        Msg::StatusSelectOutput => "Select an output first",
+        Msg::StatusSelectStereoOutput => "Select a stereo output first",
+        Msg::StatusSelectInputOrOutput => "Select an input or output first",
+        Msg::StatusSelectInput => "Select an input first",
+        Msg::StatusNotInAutomix => "{} is not in the automix group",
+        Msg::StatusNoAlternates => "'{}' has no alternate sources",
+        Msg::StatusSourceSwitched => "'{}' switched to {}",
+        Msg::StatusSourcePrimary => "'{}' back on its primary source",
+        Msg::StatusSourceFailed => "Switching the source of '{}' failed: {}",
+        Msg::StatusMetersReset => "Peak holds and clip counters reset",
+        Msg::StatusFilterOn => "{} on at {} Hz",
+        Msg::StatusFilterOff => "{} off",
+        Msg::StatusReplaySaved => "Saved last {} s to {}",
+        Msg::StatusReplayFailed => "Replay save failed: {}",
+        Msg::StatusReportSaved => "Session report saved to {}",
+        Msg::StatusReportFailed => "Session report failed: {}",
+        Msg::StatusRecording => "Recording {}",
+        Msg::StatusFreewheelFailed => "Freewheel not available, recording in real time: {}",
+        Msg::StatusRecordExporting => "Exporting {}",
+        Msg::StatusRecordSaved => "Recording saved to {}",
+        Msg::StatusRecordFailed => "Recording failed: {}",
+        Msg::RecordWaiting => "waiting for signal",
+        Msg::RecordFreewheeling => "freewheeling",
+        Msg::StatusSceneRecalled => "Scene: {}",
+        Msg::StatusStartup => "Faders at startup: {} from --set, {} saved, {} at default",
+        Msg::StatusStartupScene => {
+            "Scene '{}' at startup; faders: {} from it, {} from --set, {} saved, {} at default"
+        }
+        Msg::StatusNotchSet => "Notch at {} Hz on {}",
+        Msg::StatusNotchesFull => "All {} notches of {} in use (c clears them)",
+        Msg::StatusNotchesCleared => "Notches of {} cleared",
+        Msg::StatusNoRinging => "No ringing frequency to notch",
+        Msg::StatusShellFailed => "Shell failed: {}",
+        Msg::SuspendShell => "Audio keeps running in {}. Type 'exit' to return to the mixer.",
+
+        Msg::EditorTitle => "Config Editor",
+        Msg::EditorClient => "Client",
+        Msg::EditorConfirm => "Confirm",
+        Msg::EditorCancel => "Cancel",
+        Msg::EditorPortsHint => "(ports: comma-separated)",
+        Msg::EditorRow => "Row",
+        Msg::EditorField => "Field",
+        Msg::EditorEditCycle => "Edit/Cycle",
+        Msg::EditorAddChannel => "Add in/out",
+        Msg::EditorDelete => "Delete",
+        Msg::EditorApplySave => "Apply+Save",
+        Msg::EditorClose => "Close",
+        Msg::EditorDiscardPrompt => "Unapplied changes - press Esc again to discard",
+        Msg::EditorInvalid => "Invalid: {}",
+        Msg::EditorMono => "mono",
+        Msg::EditorStereo => "stereo",
+        Msg::EditorSurround => "{}ch",
+        Msg::EditorTap => "tap",
+        Msg::EditorTapOff => "off",
+        Msg::EditorTapPre => "pre",
+        Msg::EditorTapPost => "post",
+        Msg::EditorNewInput => "Input {}",
+        Msg::EditorNewOutput => "Output {}",
+
+        Msg::MatrixTitle => "Routing Matrix",
+        Msg::MatrixToggle => "On/Off",
+        Msg::MatrixMono => "Mono",
+        Msg::MatrixTrim => "Trim",
+        Msg::EqTitle => "EQ: {}",
+        Msg::EqLow => "Low",
+        Msg::EqMid => "Mid",
+        Msg::EqHigh => "High",
+        Msg::EqBandLabel => "Band {}",
+        Msg::EqAdjust => "Adjust",
+        Msg::EqAdd => "Add band",
+        Msg::EqRemove => "Remove",
+        Msg::EqHighPass => "HPF",
+        Msg::EqLowPass => "LPF",
+        Msg::EqToggle => "On/off",
+        Msg::FilterHighPass => "High-pass",
+        Msg::FilterLowPass => "Low-pass",
+
+        Msg::MbTitle => "Multiband: {}",
+        Msg::MbOn => "on",
+        Msg::MbOff => "off",
+        Msg::MbReduction => "Reduction",
+        Msg::MbThreshold => "Threshold",
+        Msg::MbRatio => "Ratio",
+        Msg::MbMakeup => "Makeup",
+        Msg::MbCrossover => "Crossover",
+        Msg::MbAttack => "Attack",
+        Msg::MbRelease => "Release",
+
+        Msg::ReminderSolo => "Solo has been on for {} min",
+        Msg::ReminderMuted => "{} has been muted for {} min while it has signal",
+        Msg::ReminderLater => "a: remind me later",
+        Msg::AlertStalled => "AUDIO STALLED - the JACK process callback is not running",
+        Msg::AlertShutdown => "JACK SERVER SHUT DOWN THE CLIENT - audio stopped, restart rmixer",
+        Msg::TitleStalled => "STALLED",
+        Msg::TitleShutdown => "STOPPED",
+
+        Msg::DiagTitle => "Diagnostics",
+        Msg::DiagLatency => "Control latency (UI -> audio)",
+        Msg::DiagLast => "last",
+        Msg::DiagAverage => "average",
+        Msg::DiagMax => "max",
+        Msg::DiagLate => "late (> {} ms)",
+        Msg::DiagApplied => "applied",
+        Msg::DiagDropped => "dropped (queue full)",
+        Msg::DiagQueued => "queued",
+        Msg::DiagPeriod => "JACK period",
+        Msg::DiagLoad => "Estimated load",
+        Msg::DiagLoadValue => "{}% of the period ({} ports, {} sums)",
+        Msg::DiagInputPorts => "Input ports",
+        Msg::DiagPortsActive => "all connected, none silent",
+        Msg::DiagPortDisconnected => "not connected",
+        Msg::DiagPortSilent => "connected, only zeros for {} s+",
+        Msg::DiagReset => "Reset",
+        Msg::DiagClose => "Close",
+        Msg::ModeConfirm => "CONFIRM",
+        Msg::ModeDiagnostics => "DIAGNOSTICS",
+        Msg::ModeContributions => "SOURCES",
+        Msg::ModeGoniometer => "PHASE",
+        Msg::ModeSpectrogram => "SPECTRUM",
+        Msg::ModeOscilloscope => "SCOPE",
+        Msg::ModeRta => "RTA",
+        Msg::ModeFeedback => "FEEDBACK",
+        Msg::ModeBalance => "BALANCE",
+        Msg::ModeClips => "CLIPS",
+        Msg::ModeBigMeter => "BIG METER",
+        Msg::ModeEditor => "EDITOR",
+        Msg::ModeMatrix => "MATRIX",
+        Msg::ModeEq => "EQ",
+        Msg::ModeMultiband => "MULTIBAND",
+        Msg::ModeCalibration => "CALIBRATION",
+        Msg::ModeFlip => "FLIP",
+        Msg::FlipBanner => {
+            "FLIP: ↑/↓ set {} on the selected input · g next layer · Esc back to faders"
+        }
+        Msg::FlipTrim => "the trim",
+        Msg::FlipDeviceGain => "the device gain (m mutes the device)",
+        Msg::FlipSend => "the send to {}",
+        Msg::FlipAutomix => "the automix weight",
+        Msg::ContribTitle => "Into {}",
+        Msg::ContribNotRouted => "not routed",
+        Msg::GonioTitle => "Phase of {}",
+        Msg::SpecTitle => "Spectrogram of {}",
+        Msg::SpecFloor => "Floor",
+        Msg::SpecFloorValue => "Floor {} dBFS",
+        Msg::OscTitle => "Oscilloscope of {}",
+        Msg::OscZoom => "Zoom",
+        Msg::OscTrigger => "Trigger",
+        Msg::OscLevel => "Level",
+        Msg::OscFreeze => "Freeze",
+        Msg::OscFrozen => "FROZEN",
+        Msg::OscRising => "Rising",
+        Msg::OscFalling => "Falling",
+        Msg::OscFree => "Free",
+        Msg::OscPeak => "Peak {} dBFS",
+        Msg::FbTitle => "Feedback finder on {}",
+        Msg::FbListening => "Listening… a tone that holds for {} s shows here",
+        Msg::FbRinging => "Ringing",
+        Msg::FbBuilding => "Building",
+        Msg::FbNotches => "Notches",
+        Msg::FbNoNotches => "none",
+        Msg::FbNotch => "Notch loudest",
+        Msg::FbClear => "Clear notches",
+        Msg::ClipsTitle => "Clip events ({})",
+        Msg::ClipsNone => "No clips so far",
+        Msg::ClipsDropped => "... and {} more, not kept",
+        Msg::RtaTitle => "1/3-octave RTA of {}",
+        Msg::RtaWeighting => "Weighting",
+        Msg::RtaSpeed => "Fast/slow",
+        Msg::RtaNoise => "Noise",
+        Msg::RtaFast => "fast",
+        Msg::RtaSlow => "slow",
+        Msg::RtaStatus => "{}-weighted, {}, loudest {} Hz at {} dB",
+        Msg::RtaNoiseOn => "Pink noise on {}",
+        Msg::LoudnessTitle => "Loudness: {}",
+        Msg::LoudnessNow => "Now",
+        Msg::LoudnessMax => "Max",
+        Msg::LoudnessFocus => "Focus pane",
+        Msg::LoudnessStrips => "Back to strips",
+        Msg::CalTitle => "Speaker calibration",
+        Msg::CalIntro => "Pink noise at {} dBFS through each output in turn, measured on {}",
+        Msg::CalOutput => "Output",
+        Msg::CalReturn => "Return",
+        Msg::CalSuggested => "Change",
+        Msg::CalNoSignal => "no signal",
+        Msg::CalApply => "Apply",
+        Msg::CalRestart => "Restart",
+        Msg::CalStop => "Stop",
+        Msg::BalanceTitle => "Stereo balance",
+        Msg::BalanceIntro => {
+            "Long-term level of the left and right ports of {}, before its balance"
+        }
+        Msg::BalanceLeft => "Left",
+        Msg::BalanceRight => "Right",
+        Msg::BalanceDifference => "Difference",
+        Msg::BalanceCurrent => "Balance",
+        Msg::BalanceSignal => "Signal",
+        Msg::BalanceMeasuring => "Measuring while the source plays…",
+        Msg::BalanceSuggested => "Suggested balance: {} dB",
+        Msg::BalanceEven => "The ports are even: no correction needed",
+        Msg::BalanceInconsistent => {
+            "The difference changes with the program: no correction offered"
+        }
+    }
+}
@@ -0,0 +1,730 @@
+//! UI string translations
+//!
+//! All user-facing TUI text is looked up through [`tr`] by message key.
+//! English is the reference and must cover every key; other locales may be
+//! partial and fall back to English for anything they don't translate.
+//!
+//! To add a translation, create `src/i18n/<code>.rs` with a
+//! `lookup(Msg) -> Option<&'static str>` function and register it in
+//! [`LOCALES`]. Templates use `{}` placeholders, filled in order by [`trf`].
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod de;
+mod en;
+
+/// Message keys for every translatable UI string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // Help bar
+    HelpSelect,
+    HelpVolume,
+    HelpTrim,
+    HelpMidSide,
+    HelpMidSideLevels,
+    HelpMeterPoint,
+    HelpSplit,
+    HelpCalibrate,
+    HelpSource,
+    HelpFlip,
+    HelpResetMeters,
+    HelpZeroDb,
+    HelpMute,
+    HelpSolo,
+    HelpEq,
+    HelpEqBands,
+    HelpReport,
+    HelpRecord,
+    HelpSwitch,
+    HelpEdit,
+    HelpRoute,
+    HelpMatrix,
+    HelpLoudest,
+    HelpContributions,
+    HelpGoniometer,
+    HelpSpectrogram,
+    HelpOscilloscope,
+    HelpRta,
+    HelpFeedback,
+    HelpClips,
+    HelpBigMeter,
+    HelpBalance,
+    HelpFilters,
+    HelpDiagnostics,
+    HelpReplay,
+    HelpSuspend,
+    HelpQuit,
+
+    // Channel sections
+    SectionInputs,
+    SectionOutputs,
+    SectionMaster,
+    SectionPassthrough,
+
+    // Missing device prompt
+    MissingDeviceTitle,
+    MissingDeviceFor,
+    ConnectClosestMatch,
+    PromptYes,
+    PromptNo,
+    PromptAll,
+    PromptSkipAll,
+
+    // Status messages
+    StatusConnectionsMissing,
+    StatusOverBudget,
+    StatusOverBudgetAny,
+    StatusPortUnrouted,
+    StatusConnectionsUnrouted,
+    StatusConfigApplied,
+    StatusApplyFailed,
+    StatusLoudest,
+    StatusLoudestMuted,
+    StatusNothingAudible,
+    StatusTrim,
+    StatusDeviceGain,
+    StatusDeviceMuted,
+    StatusDeviceUnmuted,
+    StatusNoDeviceGain,
+    StatusDeviceGainFailed,
+    StatusDeviceMeterFailed,
+    StatusLoudEvent,
+    StatusNoClick,
+    StatusClickTempo,
+    StatusAutomixWeight,
+    StatusMidSideOn,
+    StatusMidSideOff,
+    StatusMidSideStereo,
+    StatusMidSideLevels,
+    StatusMidSideNeeded,
+    StatusBalanceStereo,
+    StatusBalanceMidSide,
+    StatusBalanceSet,
+    StatusMeterPre,
+    StatusMeterPost,
+    StatusMeterPreAll,
+    StatusMeterPostAll,
+    StatusAppStreamAdded,
+    StatusAppStreamRemoved,
+    StatusAppStreamsFailed,
+    StatusSplitNarrow,
+    StatusCalibrated,
+    StatusNoCalibration,
+    StatusScheduled,
+    StatusScheduleFailed,
+    StatusSelectOutput,
+    StatusSelectStereoOutput,
+    StatusSelectInputOrOutput,
+    StatusSelectInput,
+    StatusNotInAutomix,
+    StatusNoAlternates,
+    StatusSourceSwitched,
+    StatusSourcePrimary,
+    StatusSourceFailed,
+    StatusMetersReset,
+    StatusFilterOn,
+    StatusFilterOff,
+    StatusReplaySaved,
+    StatusReplayFailed,
+    StatusReportSaved,
+    StatusReportFailed,
+    StatusRecording,
+    StatusFreewheelFailed,
+    StatusRecordExporting,
+    StatusRecordSaved,
+    StatusRecordFailed,
+    RecordWaiting,
+    RecordFreewheeling,
+    StatusSceneRecalled,
+    StatusStartup,
+    StatusStartupScene,
+    StatusNotchSet,
+    StatusNotchesFull,
+    StatusNotchesCleared,
+    StatusNoRinging,
+    StatusShellFailed,
+
+    // Suspend
+    SuspendShell,
+
+    // Config editor
+    EditorTitle,
+    EditorClient,
+    EditorConfirm,
+    EditorCancel,
+    EditorPortsHint,
+    EditorRow,
+    EditorField,
+    EditorEditCycle,
+    EditorAddChannel,
+    EditorDelete,
+    EditorApplySave,
+    EditorClose,
+    EditorDiscardPrompt,
+    EditorInvalid,
+    EditorMono,
+    EditorStereo,
+    EditorSurround,
+    EditorTap,
+    EditorTapOff,
+    EditorTapPre,
+    EditorTapPost,
+    EditorNewInput,
+    EditorNewOutput,
+
+    // Routing matrix
+    MatrixTitle,
+    MatrixToggle,
+    MatrixMono,
+    MatrixTrim,
+
+    // EQ view
+    EqTitle,
+    EqLow,
+    EqMid,
+    EqHigh,
+    EqBandLabel,
+    EqAdjust,
+    EqAdd,
+    EqRemove,
+    EqHighPass,
+    EqLowPass,
+    EqToggle,
+    FilterHighPass,
+    FilterLowPass,
+
+    // Multiband compressor page
+    MbTitle,
+    MbOn,
+    MbOff,
+    MbReduction,
+    MbThreshold,
+    MbRatio,
+    MbMakeup,
+    MbCrossover,
+    MbAttack,
+    MbRelease,
+
+    // Reminders
+    ReminderSolo,
+    ReminderMuted,
+    ReminderLater,
+
+    // Engine alerts
+    AlertStalled,
+    AlertShutdown,
+    TitleStalled,
+    TitleShutdown,
+
+    // Diagnostics overlay
+    DiagTitle,
+    DiagLatency,
+    DiagLast,
+    DiagAverage,
+    DiagMax,
+    DiagLate,
+    DiagApplied,
+    DiagDropped,
+    DiagQueued,
+    DiagPeriod,
+    DiagLoad,
+    DiagLoadValue,
+    DiagInputPorts,
+    DiagPortsActive,
+    DiagPortDisconnected,
+    DiagPortSilent,
+    DiagReset,
+    DiagClose,
+
+    // Mode indicator
+    ModeConfirm,
+    ModeDiagnostics,
+    ModeContributions,
+    ModeGoniometer,
+    ModeSpectrogram,
+    ModeOscilloscope,
+    ModeRta,
+    ModeFeedback,
+    ModeBalance,
+    ModeClips,
+    ModeBigMeter,
+    ModeEditor,
+    ModeMatrix,
+    ModeEq,
+    ModeMultiband,
+    ModeCalibration,
+    ModeFlip,
+    FlipBanner,
+    FlipTrim,
+    FlipDeviceGain,
+    FlipSend,
+    FlipAutomix,
+
+    // Contributions overlay
+    ContribTitle,
+    ContribNotRouted,
+    GonioTitle,
+    SpecTitle,
+    SpecFloor,
+    SpecFloorValue,
+    OscTitle,
+    OscZoom,
+    OscTrigger,
+    OscLevel,
+    OscFreeze,
+    OscFrozen,
+    OscRising,
+    OscFalling,
+    OscFree,
+    OscPeak,
+    FbTitle,
+    FbListening,
+    FbRinging,
+    FbBuilding,
+    FbNotches,
+    FbNoNotches,
+    FbNotch,
+    FbClear,
+    ClipsTitle,
+    ClipsNone,
+    ClipsDropped,
+    RtaTitle,
+    RtaWeighting,
+    RtaSpeed,
+    RtaNoise,
+    RtaFast,
+    RtaSlow,
+    RtaStatus,
+    RtaNoiseOn,
+
+    // Loudness pane
+    LoudnessTitle,
+    LoudnessNow,
+    LoudnessMax,
+    LoudnessFocus,
+    LoudnessStrips,
+
+    // Speaker calibration
+    CalTitle,
+    CalIntro,
+    CalOutput,
+    CalReturn,
+    CalSuggested,
+    CalNoSignal,
+    CalApply,
+    CalRestart,
+    CalStop,
+    BalanceTitle,
+    BalanceIntro,
+    BalanceLeft,
+    BalanceRight,
+    BalanceDifference,
+    BalanceCurrent,
+    BalanceSignal,
+    BalanceMeasuring,
+    BalanceSuggested,
+    BalanceEven,
+    BalanceInconsistent,
+}
+
+#[cfg(test)]
+impl Msg {
+    /// Every message key (used to check translations)
+    pub const ALL: &'static [Msg] = &[
+        Msg::HelpSelect,
+        Msg::HelpVolume,
+        Msg::HelpTrim,
+        Msg::HelpMidSide,
+        Msg::HelpMidSideLevels,
+        Msg::HelpMeterPoint,
+        Msg::HelpSplit,
+        Msg::HelpCalibrate,
+        Msg::HelpSource,
+        Msg::HelpFlip,
+        Msg::HelpResetMeters,
+        Msg::HelpZeroDb,
+        Msg::HelpMute,
+        Msg::HelpSolo,
+        Msg::HelpEq,
+        Msg::HelpEqBands,
+        Msg::HelpReport,
+        Msg::HelpRecord,
+        Msg::HelpSwitch,
+        Msg::HelpEdit,
+        Msg::HelpRoute,
+        Msg::HelpMatrix,
+        Msg::HelpLoudest,
+        Msg::HelpContributions,
+        Msg::HelpGoniometer,
+        Msg::HelpSpectrogram,
+        Msg::HelpOscilloscope,
+        Msg::HelpRta,
+        Msg::HelpFeedback,
+        Msg::HelpClips,
+        Msg::HelpBigMeter,
+        Msg::HelpBalance,
+        Msg::HelpFilters,
+        Msg::HelpDiagnostics,
+        Msg::HelpReplay,
+        Msg::HelpSuspend,
+        Msg::HelpQuit,
+        Msg::SectionInputs,
+        Msg::SectionOutputs,
+        Msg::SectionMaster,
+        Msg::SectionPassthrough,
+        Msg::MissingDeviceTitle,
+        Msg::MissingDeviceFor,
+        Msg::ConnectClosestMatch,
+        Msg::PromptYes,
+        Msg::PromptNo,
+        Msg::PromptAll,
+        Msg::PromptSkipAll,
+        Msg::StatusConnectionsMissing,
+        Msg::StatusOverBudget,
+        Msg::StatusOverBudgetAny,
+        Msg::StatusPortUnrouted,
+        Msg::StatusConnectionsUnrouted,
+        Msg::StatusConfigApplied,
+        Msg::StatusApplyFailed,
+        Msg::StatusLoudest,
+        Msg::StatusLoudestMuted,
+        Msg::StatusNothingAudible,
+        Msg::StatusTrim,
+        Msg::StatusDeviceGain,
+        Msg::StatusDeviceMuted,
+        Msg::StatusDeviceUnmuted,
+        Msg::StatusNoDeviceGain,
+        Msg::StatusDeviceGainFailed,
+        Msg::StatusDeviceMeterFailed,
+        Msg::StatusLoudEvent,
+        Msg::StatusNoClick,
+        Msg::StatusClickTempo,
+        Msg::StatusAutomixWeight,
+        Msg::StatusMidSideOn,
+        Msg::StatusMidSideOff,
+        Msg::StatusMidSideStereo,
+        Msg::StatusMidSideLevels,
+        Msg::StatusMidSideNeeded,
+        Msg::StatusBalanceStereo,
+        Msg::StatusBalanceMidSide,
+        Msg::StatusBalanceSet,
+        Msg::StatusMeterPre,
+        Msg::StatusMeterPost,
+        Msg::StatusMeterPreAll,
+        Msg::StatusMeterPostAll,
+        Msg::StatusAppStreamAdded,
+        Msg::StatusAppStreamRemoved,
+        Msg::StatusAppStreamsFailed,
+        Msg::StatusSplitNarrow,
+        Msg::StatusCalibrated,
+        Msg::StatusNoCalibration,
+        Msg::StatusScheduled,
+        Msg::StatusScheduleFailed,
+        Msg::StatusSelectOutput,
+        Msg::StatusSelectStereoOutput,
+        Msg::StatusSelectInputOrOutput,
+        Msg::StatusSelectInput,
+        Msg::StatusNotInAutomix,
+        Msg::StatusNoAlternates,
+        Msg::StatusSourceSwitched,
+        Msg::StatusSourcePrimary,
+        Msg::StatusSourceFailed,
+        Msg::StatusMetersReset,
+        Msg::StatusFilterOn,
+        Msg::StatusFilterOff,
+        Msg::StatusReplaySaved,
+        Msg::StatusReplayFailed,
+        Msg::StatusReportSaved,
+        Msg::StatusReportFailed,
+        Msg::StatusRecording,
+        Msg::StatusFreewheelFailed,
+        Msg::StatusRecordExporting,
+        Msg::StatusRecordSaved,
+        Msg::StatusRecordFailed,
+        Msg::RecordWaiting,
+        Msg::RecordFreewheeling,
+        Msg::StatusSceneRecalled,
+        Msg::StatusStartup,
+        Msg::StatusStartupScene,
+        Msg::StatusNotchSet,
+        Msg::StatusNotchesFull,
+        Msg::StatusNotchesCleared,
+        Msg::StatusNoRinging,
+        Msg::StatusShellFailed,
+        Msg::SuspendShell,
+        Msg::EditorTitle,
+        Msg::EditorClient,
+        Msg::EditorConfirm,
+        Msg::EditorCancel,
+        Msg::EditorPortsHint,
+        Msg::EditorRow,
+        Msg::EditorField,
+        Msg::EditorEditCycle,
+        Msg::EditorAddChannel,
+        Msg::EditorDelete,
+        Msg::EditorApplySave,
+        Msg::EditorClose,
+        Msg::EditorDiscardPrompt,
+        Msg::EditorInvalid,
+        Msg::EditorMono,
+        Msg::EditorStereo,
+        Msg::EditorSurround,
+        Msg::EditorTap,
+        Msg::EditorTapOff,
+        Msg::EditorTapPre,
+        Msg::EditorTapPost,
+        Msg::EditorNewInput,
+        Msg::EditorNewOutput,
+        Msg::MatrixTitle,
+        Msg::MatrixToggle,
+        Msg::MatrixMono,
+        Msg::MatrixTrim,
+        Msg::EqTitle,
+        Msg::EqLow,
+        Msg::EqMid,
+        Msg::EqHigh,
+        Msg::EqBandLabel,
+        Msg::EqAdjust,
+        Msg::EqAdd,
+        Msg::EqRemove,
+        Msg::EqHighPass,
+        Msg::EqLowPass,
+        Msg::EqToggle,
+        Msg::FilterHighPass,
+        Msg::FilterLowPass,
+        Msg::MbTitle,
+        Msg::MbOn,
+        Msg::MbOff,
+        Msg::MbReduction,
+        Msg::MbThreshold,
+        Msg::MbRatio,
+        Msg::MbMakeup,
+        Msg::MbCrossover,
+        Msg::MbAttack,
+        Msg::MbRelease,
+        Msg::ReminderSolo,
+        Msg::ReminderMuted,
+        Msg::ReminderLater,
+        Msg::AlertStalled,
+        Msg::AlertShutdown,
+        Msg::TitleStalled,
+        Msg::TitleShutdown,
+        Msg::DiagTitle,
+        Msg::DiagLatency,
+        Msg::DiagLast,
+        Msg::DiagAverage,
+        Msg::DiagMax,
+        Msg::DiagLate,
+        Msg::DiagApplied,
+        Msg::DiagDropped,
+        Msg::DiagQueued,
+        Msg::DiagPeriod,
+        Msg::DiagLoad,
+        Msg::DiagLoadValue,
+        Msg::DiagInputPorts,
+        Msg::DiagPortsActive,
+        Msg::DiagPortDisconnected,
+        Msg::DiagPortSilent,
+        Msg::DiagReset,
+        Msg::DiagClose,
+        Msg::ModeConfirm,
+        Msg::ModeDiagnostics,
+        Msg::ModeContributions,
+        Msg::ModeGoniometer,
+        Msg::ModeSpectrogram,
+        Msg::ModeOscilloscope,
+        Msg::ModeRta,
+        Msg::ModeFeedback,
+        Msg::ModeBalance,
+        Msg::ModeClips,
+        Msg::ModeBigMeter,
+        Msg::ModeEditor,
+        Msg::ModeMatrix,
+        Msg::ModeEq,
+        Msg::ModeMultiband,
+        Msg::ModeCalibration,
+        Msg::ModeFlip,
+        Msg::FlipBanner,
+        Msg::FlipTrim,
+        Msg::FlipDeviceGain,
+        Msg::FlipSend,
+        Msg::FlipAutomix,
+        Msg::ContribTitle,
+        Msg::ContribNotRouted,
+        Msg::GonioTitle,
+        Msg::SpecTitle,
+        Msg::SpecFloor,
+        Msg::SpecFloorValue,
+        Msg::OscTitle,
+        Msg::OscZoom,
+        Msg::OscTrigger,
+        Msg::OscLevel,
+        Msg::OscFreeze,
+        Msg::OscFrozen,
+        Msg::OscRising,
+        Msg::OscFalling,
+        Msg::OscFree,
+        Msg::OscPeak,
+        Msg::FbTitle,
+        Msg::FbListening,
+        Msg::FbRinging,
+        Msg::FbBuilding,
+        Msg::FbNotches,
+        Msg::FbNoNotches,
+        Msg::FbNotch,
+        Msg::FbClear,
+        Msg::ClipsTitle,
+        Msg::ClipsNone,
+        Msg::ClipsDropped,
+        Msg::RtaTitle,
+        Msg::RtaWeighting,
+        Msg::RtaSpeed,
+        Msg::RtaNoise,
+        Msg::RtaFast,
+        Msg::RtaSlow,
+        Msg::RtaStatus,
+        Msg::RtaNoiseOn,
+        Msg::LoudnessTitle,
+        Msg::LoudnessNow,
+        Msg::LoudnessMax,
+        Msg::LoudnessFocus,
+        Msg::LoudnessStrips,
+        Msg::CalTitle,
+        Msg::CalIntro,
+        Msg::CalOutput,
+        Msg::CalReturn,
+        Msg::CalSuggested,
+        Msg::CalNoSignal,
+        Msg::CalApply,
+        Msg::CalRestart,
+        Msg::CalStop,
+        Msg::BalanceTitle,
+        Msg::BalanceIntro,
+        Msg::BalanceLeft,
+        Msg::BalanceRight,
+        Msg::BalanceDifference,
+        Msg::BalanceCurrent,
+        Msg::BalanceSignal,
+        Msg::BalanceMeasuring,
+        Msg::BalanceSuggested,
+        Msg::BalanceEven,
+        Msg::BalanceInconsistent,
+    ];
+}
+
+/// A registered translation
+struct Locale {
+    /// Language code (e.g. "de")
+    code: &'static str,
+
+    /// Translation lookup; None falls back to English
+    lookup: fn(Msg) -> Option<&'static str>,
+}
+
+/// Available locales; index 0 is the English reference
+static LOCALES: &[Locale] = &[
+    Locale {
+        code: "en",
+        lookup: |msg| Some(en::text(msg)),
+    },
+    Locale {
+        code: "de",
+        lookup: de::lookup,
+    },
+];
+
+/// Index into `LOCALES` of the active locale
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+/// Language code of a locale string ("de_DE.UTF-8" -> "de")
+fn language_code(locale: &str) -> String {
+    locale
+        .split(['_', '.', '@', '-'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+fn find_locale(locale: &str) -> Option<usize> {
+    let code = language_code(locale);
+    LOCALES.iter().position(|l| l.code == code)
+}
+
+/// Select the UI locale
+///
+/// Uses the configured locale if given, otherwise the environment
+/// (`LC_ALL`, `LC_MESSAGES`, `LANG`). Unknown locales fall back to English.
+pub fn init(configured: Option<&str>) {
+    let index = match configured {
+        Some(locale) => find_locale(locale).unwrap_or_else(|| {
+            log::warn!("Unknown locale '{}', using English", locale);
+            0
+        }),
+        None => ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| find_locale(&value))
+            .unwrap_or(0),
+    };
+    CURRENT.store(index, Ordering::Relaxed);
+    log::info!("UI locale: {}", LOCALES[index].code);
+}
+
+/// Look up a UI string in the active locale
+pub fn tr(msg: Msg) -> &'static str {
+    let locale = &LOCALES[CURRENT.load(Ordering::Relaxed)];
+    (locale.lookup)(msg).unwrap_or_else(|| en::text(msg))
+}
+
+/// Look up a UI string and fill its `{}` placeholders in order
+pub fn trf(msg: Msg, args: &[&dyn Display]) -> String {
+    fill(tr(msg), args)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    if let Some(first) = parts.next() {
+        result.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            result.push_str(&arg.to_string());
+        }
+        result.push_str(part);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_codes() {
+        assert_eq!(find_locale("de_DE.UTF-8"), Some(1));
+        assert_eq!(find_locale("en"), Some(0));
+        assert_eq!(find_locale("xx_YY"), None);
+        assert_eq!(fill("{} of {}", &[&1, &"two"]), "1 of two");
+    }
+
+    #[test]
+    fn test_translations_keep_placeholders() {
+        for locale in LOCALES {
+            for &msg in Msg::ALL {
+                if let Some(text) = (locale.lookup)(msg) {
+                    assert_eq!(
+                        text.matches("{}").count(),
+                        en::text(msg).matches("{}").count(),
+                        "{:?} in '{}'",
+                        msg,
+                        locale.code
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Import of PulseAudio/PipeWire loopback setups
+//!
+//! Reads the output of `pactl list short modules` and turns null sinks and
+//! loopback modules into an equivalent rmixer config:
+//! - a null sink whose monitor feeds a loopback becomes an input channel
+//! - a real source (mic) feeding a loopback becomes an input channel
+//! - every loopback target becomes an output channel
+//! - each loopback becomes a route from its input to its output
+
+use anyhow::{Context, Result};
+use std::process::Command;
+
+use crate::config::{
+    ChannelConfig, Config, DbDisplay, FaderTaper, MeterBallistics, MeterScale, RouteConfig,
+    SoloMode, TapPoint,
+};
+
+/// One loaded module from `pactl list short modules`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PulseModule {
+    pub name: String,
+    pub args: Vec<(String, String)>,
+}
+
+impl PulseModule {
+    fn arg(&self, key: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Channel count from `channels=` (defaults to stereo)
+    fn channels(&self) -> usize {
+        self.arg("channels")
+            .and_then(|c| c.parse::<usize>().ok())
+            .unwrap_or(2)
+            .clamp(1, 2)
+    }
+}
+
+/// Run `pactl list short modules` and parse its output
+pub fn read_pactl_modules() -> Result<Vec<PulseModule>> {
+    let output = Command::new("pactl")
+        .args(["list", "short", "modules"])
+        .output()
+        .context("Failed to run pactl (is pipewire-pulse or PulseAudio running?)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "pactl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_modules(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `pactl list short modules` output (index, name, arguments; tab-separated)
+pub fn parse_modules(text: &str) -> Vec<PulseModule> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let _index = fields.next()?;
+            let name = fields.next()?.trim().to_string();
+            let args = parse_args(fields.next().unwrap_or(""));
+            Some(PulseModule { name, args })
+        })
+        .collect()
+}
+
+/// Split module arguments (`key=value key="quoted value"`) into pairs
+fn parse_args(text: &str) -> Vec<(String, String)> {
+    let mut args = Vec::new();
+    let mut chars = text.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let key: String =
+            std::iter::from_fn(|| chars.next_if(|c| *c != '=' && !c.is_whitespace())).collect();
+        if key.is_empty() {
+            break;
+        }
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            let mut quote = None;
+            while let Some(&c) = chars.peek() {
+                match quote {
+                    Some(q) if c == q => quote = None,
+                    None if c == '"' || c == '\'' => quote = Some(c),
+                    None if c.is_whitespace() => break,
+                    _ => value.push(c),
+                }
+                chars.next();
+            }
+        }
+        args.push((key, value));
+    }
+    args
+}
+
+/// Value of a property inside a `*_properties=` argument
+fn property<'a>(properties: &'a str, key: &str) -> Option<&'a str> {
+    properties
+        .split_whitespace()
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// Port name safe for JACK ("My Sink" -> "my_sink")
+fn port_base(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn channel_ports(base: &str, suffix: &str, channels: usize) -> Vec<String> {
+    match channels {
+        1 => vec![format!("{}_{}", base, suffix)],
+        _ => vec![
+            format!("{}_{}_L", base, suffix),
+            format!("{}_{}_R", base, suffix),
+        ],
+    }
+}
+
+fn find_or_add(
+    channels: &mut Vec<ChannelConfig>,
+    name: &str,
+    make: impl FnOnce() -> ChannelConfig,
+) -> usize {
+    match channels.iter().position(|c| c.name == name) {
+        Some(index) => index,
+        None => {
+            channels.push(make());
+            channels.len() - 1
+        }
+    }
+}
+
+/// Build an rmixer config equivalent to the given modules
+pub fn build_config(modules: &[PulseModule], client_name: &str) -> Result<Config> {
+    // Null sinks by sink name, with their display name and channel count
+    let null_sinks: Vec<(String, String, usize)> = modules
+        .iter()
+        .filter(|m| m.name == "module-null-sink")
+        .map(|m| {
+            let sink = m.arg("sink_name").unwrap_or("null").to_string();
+            let display = m
+                .arg("sink_properties")
+                .and_then(|p| property(p, "device.description"))
+                .unwrap_or(&sink)
+                .to_string();
+            (sink, display, m.channels())
+        })
+        .collect();
+    let null_sink = |name: &str| null_sinks.iter().find(|(sink, _, _)| sink == name);
+
+    let mut inputs: Vec<ChannelConfig> = Vec::new();
+    let mut outputs: Vec<ChannelConfig> = Vec::new();
+
+    for module in modules.iter().filter(|m| m.name == "module-loopback") {
+        let (Some(source), Some(sink)) = (module.arg("source"), module.arg("sink")) else {
+            log::warn!("Skipping loopback without explicit source and sink");
+            continue;
+        };
+        let channels = module.channels();
+
+        // Input side: a null sink's monitor or a real capture device
+        let input = match source.strip_suffix(".monitor").and_then(&null_sink) {
+            Some((sink_name, display, sink_channels)) => {
+                find_or_add(&mut inputs, display, || ChannelConfig {
+                    name: display.clone(),
+                    ports: channel_ports(&port_base(sink_name), "in", *sink_channels),
+                    connect: match sink_channels {
+                        1 => vec![format!("{}:monitor_MONO", sink_name)],
+                        _ => vec![
+                            format!("{}:monitor_FL", sink_name),
+                            format!("{}:monitor_FR", sink_name),
+                        ],
+                    },
+                    ..Default::default()
+                })
+            }
+            None => find_or_add(&mut inputs, source, || ChannelConfig {
+                name: source.to_string(),
+                ports: channel_ports(&port_base(source), "in", channels),
+                connect: match channels {
+                    1 => vec![format!("{}:capture_MONO", source)],
+                    _ => vec![
+                        format!("{}:capture_FL", source),
+                        format!("{}:capture_FR", source),
+                    ],
+                },
+                ..Default::default()
+            }),
+        };
+
+        // Output side: the loopback's sink (virtual or hardware)
+        let (display, sink_channels) = match null_sink(sink) {
+            Some((_, display, sink_channels)) => (display.as_str(), *sink_channels),
+            None => (sink, channels),
+        };
+        let output = find_or_add(&mut outputs, display, || ChannelConfig {
+            name: display.to_string(),
+            ports: channel_ports(&port_base(sink), "out", sink_channels),
+            connect: match sink_channels {
+                1 => vec![format!("{}:playback_MONO", sink)],
+                _ => vec![
+                    format!("{}:playback_FL", sink),
+                    format!("{}:playback_FR", sink),
+                ],
+            },
+            ..Default::default()
+        });
+
+        let output_name = outputs[output].name.clone();
+        let routes = inputs[input].routes.get_or_insert_with(Vec::new);
+        if !routes.iter().any(|r| r.output == output_name) {
+            routes.push(RouteConfig {
+                output: output_name,
+                gain_db: 0.0,
+                mono: false,
+            });
+        }
+    }
+
+    // Null sinks nobody loops back are still app destinations worth metering
+    for (sink_name, display, sink_channels) in &null_sinks {
+        if outputs.iter().any(|o| &o.name == display) {
+            continue;
+        }
+        find_or_add(&mut inputs, display, || ChannelConfig {
+            name: display.clone(),
+            ports: channel_ports(&port_base(sink_name), "in", *sink_channels),
+            connect: match sink_channels {
+                1 => vec![format!("{}:monitor_MONO", sink_name)],
+                _ => vec![
+                    format!("{}:monitor_FL", sink_name),
+                    format!("{}:monitor_FR", sink_name),
+                ],
+            },
+            routes: Some(Vec::new()),
+            ..Default::default()
+        });
+    }
+
+    if inputs.is_empty() || outputs.is_empty() {
+        anyhow::bail!("No loopback modules found to import (need at least one source and sink)");
+    }
+
+    let config = Config {
+        client_name: client_name.to_string(),
+        inputs,
+        outputs,
+        control_socket: None,
+        control_feedback_hz: None,
+        control_access: None,
+        watchdog_hook: None,
+        master_volume_db: None,
+        master_muted: false,
+        meter_range_seconds: None,
+        peak_hold_seconds: None,
+        signal_threshold_db: None,
+        locale: None,
+        midi: None,
+        scenes: Vec::new(),
+        startup_scene: None,
+        replay_buffer: None,
+        recorder: None,
+        passthrough: Vec::new(),
+        solo_mode: SoloMode::default(),
+        reminders: None,
+        tally: None,
+        schedule: Vec::new(),
+        calibration: None,
+        app_streams: None,
+        click: None,
+        fader_taper: FaderTaper::default(),
+        db_display: DbDisplay::default(),
+        meter_scale: MeterScale::default(),
+        meter_ballistics: MeterBallistics::default(),
+        meter_point: TapPoint::default(),
+        keep_audio_on_suspend: false,
+        config_path: None,
+    };
+    config
+        .validate()
+        .context("Imported configuration is invalid")?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACTL: &str = "536870913\tmodule-null-sink\tsink_name=games sink_properties=device.description=\"Games\"\t\n\
+536870914\tmodule-loopback\tsource=games.monitor sink=alsa_output.usb-headset.analog-stereo latency_msec=20\t\n\
+536870915\tmodule-loopback\tsource=alsa_input.usb-mic.mono-fallback sink=alsa_output.usb-headset.analog-stereo channels=1\t\n\
+536870916\tmodule-null-sink\tsink_name=spare\t\n";
+
+    #[test]
+    fn test_parse_modules() {
+        let modules = parse_modules(PACTL);
+        assert_eq!(modules.len(), 4);
+        assert_eq!(
+            modules[0].arg("sink_properties"),
+            Some("device.description=Games")
+        );
+        assert_eq!(modules[1].arg("source"), Some("games.monitor"));
+        assert_eq!(modules[2].channels(), 1);
+    }
+
+    #[test]
+    fn test_build_config_from_loopbacks() {
+        let config = build_config(&parse_modules(PACTL), "Mixer").unwrap();
+
+        let names: Vec<&str> = config.inputs.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Games", "alsa_input.usb-mic.mono-fallback", "spare"]
+        );
+        assert_eq!(config.inputs[0].connect[0], "games:monitor_FL");
+        assert!(!config.inputs[1].is_stereo());
+        assert_eq!(config.inputs[2].routes, Some(Vec::new()));
+
+        assert_eq!(config.outputs.len(), 1);
+        let routes = config.inputs[0].routes.as_ref().unwrap();
+        assert_eq!(routes[0].output, config.outputs[0].name);
+    }
+}
@@ -3,7 +3,14 @@
 //! Defines lock-free communication structures between the audio thread
 //! and the UI thread for real-time safe operation.
 
-use std::time::Instant;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::config::{
+    ChannelConfig, Config, EqBand, EqBandConfig, EqConfig, FaderTaper, MeterBallistics,
+    MultibandConfig, PassFilter, PassFilterConfig, SoloMode, TapPoint, MULTIBAND_BANDS,
+};
+use crate::solo;
 
 /// Volume limits in dB
 pub const VOLUME_MIN_DB: f32 = -60.0;
@@ -13,17 +20,89 @@ pub const VOLUME_STEP_DB: f32 = 0.5;
 /// Default volume in dB
 pub const VOLUME_DEFAULT_DB: f32 = 0.0;
 
+/// Name of the master channel
+pub const MASTER_NAME: &str = "Master";
+
+/// Length of the rolling window for crest factor readouts
+pub const CREST_WINDOW: Duration = Duration::from_secs(3);
+
+/// Most ports a mixer channel can have (7.1 surround)
+pub const MAX_CHANNEL_PORTS: usize = 8;
+
+/// Temporary feedback notches an output can hold
+pub const MAX_NOTCHES: usize = 4;
+
+/// Default length of the recent peak range shown on meters
+pub const RANGE_WINDOW: Duration = Duration::from_secs(3);
+
+/// Number of buckets the recent peak range window is split into
+const RANGE_BUCKETS: usize = 15;
+
+/// Number of buckets the crest factor window is split into
+const CREST_BUCKETS: usize = 30;
+
+/// Seconds of level history kept per channel, one bucket each
+pub const LEVEL_HISTORY_SECONDS: usize = 60;
+
+/// How fast a PPM falls back in dB per second (20 dB in 1.7 s)
+const PPM_RELEASE_DB_PER_SEC: f32 = 20.0 / 1.7;
+
+/// Time a VU meter takes to reach 99% of a step, up or down
+const VU_RISE_SECS: f32 = 0.3;
+
+/// Share of each block's correlation in the shown value
+const CORRELATION_SMOOTHING: f32 = 0.1;
+
+/// Default level above which a channel counts as carrying signal in dBFS
+pub const SIGNAL_THRESHOLD_DB: f32 = -50.0;
+
+/// How long the signal light stays lit after the last block above the
+/// threshold, so it does not flicker between blocks
+const SIGNAL_HOLD: Duration = Duration::from_millis(300);
+
+/// Control latency above which a message counts as late
+pub const CONTROL_LATENCY_LIMIT: Duration = Duration::from_millis(20);
+
 /// Meter data sent from audio thread to UI thread
 #[derive(Debug, Clone, Copy)]
 pub struct MeterData {
     /// Channel index this meter data belongs to
     pub channel_index: usize,
 
-    /// Peak levels for each port (up to 2 for stereo)
+    /// Peak levels for each port (only the first `port_count` are valid)
     /// Values are in linear scale (0.0 to 1.0+, can exceed 1.0 for clipping)
-    pub peaks: [f32; 2],
+    pub peaks: [f32; MAX_CHANNEL_PORTS],
+
+    /// RMS levels for each port over the processed block (linear scale)
+    pub rms: [f32; MAX_CHANNEL_PORTS],
+
+    /// K-weighted mean square summed over all ports (BS.1770 channel power)
+    pub loudness_ms: f32,
+
+    /// Gain reduction applied by auto-trim (outputs) or the most band
+    /// reduction of the de-esser (inputs) in dB (0 when inactive)
+    pub gain_reduction_db: f32,
+
+    /// Most gain reduction applied by the limiter during the block in dB
+    pub limiter_db: f32,
+
+    /// Most gain reduction per band of the multiband compressor in dB
+    pub multiband_db: [f32; MULTIBAND_BANDS],
+
+    /// Samples above full scale in the block, over all ports
+    pub clipped: u32,
+
+    /// Gain the automixer gave an input in dB (0 outside the group)
+    pub automix_db: f32,
 
-    /// Number of valid peaks (1 for mono, 2 for stereo)
+    /// Reduction of an input's protective dip in dB (0 when idle)
+    pub dip_db: f32,
+
+    /// Correlation of left and right over the block, -1 to +1 (None for
+    /// silence and for channels without exactly two ports)
+    pub correlation: Option<f32>,
+
+    /// Number of valid peaks (1 for mono, 2 for stereo, more for surround)
     pub port_count: usize,
 
     /// Timestamp when this measurement was taken
@@ -32,20 +111,46 @@ pub struct MeterData {
 
 impl MeterData {
     /// Create new meter data for a mono channel
-    pub fn mono(channel_index: usize, peak: f32) -> Self {
+    pub fn mono(channel_index: usize, peak: f32, rms: f32) -> Self {
+        let mut peaks = [0.0; MAX_CHANNEL_PORTS];
+        let mut rms_levels = [0.0; MAX_CHANNEL_PORTS];
+        peaks[0] = peak;
+        rms_levels[0] = rms;
         Self {
             channel_index,
-            peaks: [peak, 0.0],
+            peaks,
+            rms: rms_levels,
+            loudness_ms: 0.0,
+            gain_reduction_db: 0.0,
+            limiter_db: 0.0,
+            multiband_db: [0.0; MULTIBAND_BANDS],
+            clipped: 0,
+            automix_db: 0.0,
+            dip_db: 0.0,
+            correlation: None,
             port_count: 1,
             timestamp: Instant::now(),
         }
     }
 
     /// Create new meter data for a stereo channel
-    pub fn stereo(channel_index: usize, peak_l: f32, peak_r: f32) -> Self {
+    pub fn stereo(channel_index: usize, peak_l: f32, peak_r: f32, rms_l: f32, rms_r: f32) -> Self {
+        let mut peaks = [0.0; MAX_CHANNEL_PORTS];
+        let mut rms = [0.0; MAX_CHANNEL_PORTS];
+        peaks[..2].copy_from_slice(&[peak_l, peak_r]);
+        rms[..2].copy_from_slice(&[rms_l, rms_r]);
         Self {
             channel_index,
-            peaks: [peak_l, peak_r],
+            peaks,
+            rms,
+            loudness_ms: 0.0,
+            gain_reduction_db: 0.0,
+            limiter_db: 0.0,
+            multiband_db: [0.0; MULTIBAND_BANDS],
+            clipped: 0,
+            automix_db: 0.0,
+            dip_db: 0.0,
+            correlation: None,
             port_count: 2,
             timestamp: Instant::now(),
         }
@@ -66,12 +171,68 @@ impl MeterData {
     }
 }
 
+/// MIDI program change received by the audio thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramChange {
+    /// MIDI channel (0-15)
+    pub channel: u8,
+
+    /// Last bank select (CC 0) value on this channel
+    pub bank: u8,
+
+    /// Program number (0-127)
+    pub program: u8,
+}
+
+/// Points per block sent for the goniometer of a stereo output
+pub const SCOPE_POINTS: usize = 32;
+
+/// Stereo image of a two-port output over one block
+#[derive(Debug, Clone, Copy)]
+pub struct ScopeData {
+    /// Output channel index
+    pub output: usize,
+
+    /// Left/right sample pairs picked evenly across the block
+    pub points: [[f32; 2]; SCOPE_POINTS],
+}
+
+/// Channel whose signal feeds the analyzer views (spectrogram, oscilloscope)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalyzerSource {
+    /// Input channel index (trimmed source, ahead of any processing)
+    Input(usize),
+
+    /// Output channel index (as sent to its ports)
+    Output(usize),
+}
+
 /// Control message sent from UI thread to audio thread
 #[derive(Debug, Clone, Copy)]
 pub enum ControlMsg {
     /// Set volume for an input channel (index, volume in dB)
     SetInputVolume { channel: usize, volume_db: f32 },
 
+    /// Set the trim of an input channel (index, gain in dB)
+    SetInputTrim { channel: usize, trim_db: f32 },
+
+    /// Set the stereo balance correction of an input (index, dB)
+    SetInputBalance { channel: usize, balance_db: f32 },
+
+    /// Meter and level a stereo input as mid and side (or left and right
+    /// again)
+    SetInputMidSide { channel: usize, enabled: bool },
+
+    /// Meter and level a stereo output as mid and side (or left and right
+    /// again)
+    SetOutputMidSide { channel: usize, enabled: bool },
+
+    /// Set the mid and side levels of a stereo input (index, dB each)
+    SetInputMidSideLevels { channel: usize, mid_db: f32, side_db: f32 },
+
+    /// Set the mid and side levels of a stereo output (index, dB each)
+    SetOutputMidSideLevels { channel: usize, mid_db: f32, side_db: f32 },
+
     /// Set volume for an output channel (index, volume in dB)
     SetOutputVolume { channel: usize, volume_db: f32 },
 
@@ -84,36 +245,338 @@ pub enum ControlMsg {
     /// Toggle solo for an input channel
     ToggleInputSolo { channel: usize },
 
+    /// Toggle solo for an output channel (auditions the inputs feeding it)
+    ToggleOutputSolo { channel: usize },
+
+    /// Meter an input before or after its fader
+    SetInputMeterPoint { channel: usize, point: TapPoint },
+
+    /// Set the automixer weight of an input in the group
+    SetAutomixWeight { channel: usize, weight_db: f32 },
+
+    /// Fade an input out (or back in) around a change of its source ports
+    SetInputSwitching { channel: usize, switching: bool },
+
+    /// Set the gain of a fixed EQ band of an input channel
+    SetInputEq {
+        channel: usize,
+        band: EqBand,
+        gain_db: f32,
+    },
+
+    /// Set a parametric EQ band of an input channel (0 dB disables it)
+    SetInputEqBand {
+        channel: usize,
+        band: usize,
+        settings: EqBandConfig,
+    },
+
+    /// Set a high- or low-pass filter of an input channel (None removes it)
+    SetInputFilter {
+        channel: usize,
+        filter: PassFilter,
+        settings: Option<PassFilterConfig>,
+    },
+
+    /// Change the multiband compressor of an output channel
+    SetMultiband {
+        channel: usize,
+        settings: MultibandConfig,
+    },
+
+    /// Set or clear (None) a temporary feedback notch of an output channel
+    SetOutputNotch {
+        channel: usize,
+        slot: usize,
+        freq_hz: Option<f32>,
+    },
+
+    /// Set the master volume (applied after every output fader)
+    SetMasterVolume { volume_db: f32 },
+
+    /// Toggle the master mute
+    ToggleMasterMute,
+
+    /// Set volume for a passthrough channel (index, volume in dB)
+    SetPassthroughVolume { channel: usize, volume_db: f32 },
+
+    /// Toggle mute for a passthrough channel
+    TogglePassthroughMute { channel: usize },
+
+    /// Set the crosspoint from an input to an output
+    SetRoute {
+        input: usize,
+        output: usize,
+        crosspoint: Crosspoint,
+    },
+
+    /// Play pink noise at an RMS level (dBFS) on one output in place of its
+    /// mix, silencing the other outputs; None ends the noise
+    SetCalibrationNoise {
+        output: Option<usize>,
+        level_db: f32,
+    },
+
+    /// Start or stop the metronome click and set its tempo
+    SetClick { running: bool, tempo_bpm: f32 },
+
+    /// Feed a channel to the analyzer views (None stops the feed)
+    SetAnalyzer { source: Option<AnalyzerSource> },
+
     /// Request to quit the audio engine
     Quit,
 }
 
+/// Control message stamped with the time the UI sent it
+#[derive(Debug, Clone, Copy)]
+pub struct TimedControlMsg {
+    /// The control message
+    pub msg: ControlMsg,
+
+    /// Timestamp when the message was queued
+    pub sent_at: Instant,
+}
+
+/// UI -> audio control latency statistics
+///
+/// Updated with atomics only, so the audio thread can record into it
+/// without locking.
+#[derive(Debug, Default)]
+pub struct ControlLatencyStats {
+    applied: AtomicU64,
+    total_us: AtomicU64,
+    last_us: AtomicU64,
+    max_us: AtomicU64,
+    late: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Point-in-time copy of the control latency statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LatencySnapshot {
+    /// Messages applied by the audio thread
+    pub applied: u64,
+
+    /// Latency of the most recent message
+    pub last: Duration,
+
+    /// Mean latency
+    pub average: Duration,
+
+    /// Worst latency
+    pub max: Duration,
+
+    /// Messages slower than `CONTROL_LATENCY_LIMIT`
+    pub late: u64,
+
+    /// Messages dropped because the queue was full
+    pub dropped: u64,
+}
+
+impl ControlLatencyStats {
+    /// Record a message applied after the given latency (audio thread)
+    pub fn record(&self, latency: Duration) {
+        let us = latency.as_micros() as u64;
+        self.applied.fetch_add(1, Ordering::Relaxed);
+        self.total_us.fetch_add(us, Ordering::Relaxed);
+        self.last_us.store(us, Ordering::Relaxed);
+        self.max_us.fetch_max(us, Ordering::Relaxed);
+        if latency > CONTROL_LATENCY_LIMIT {
+            self.late.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record a message that could not be queued (UI thread)
+    pub fn record_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Read the current statistics
+    pub fn snapshot(&self) -> LatencySnapshot {
+        let applied = self.applied.load(Ordering::Relaxed);
+        let total_us = self.total_us.load(Ordering::Relaxed);
+        LatencySnapshot {
+            applied,
+            last: Duration::from_micros(self.last_us.load(Ordering::Relaxed)),
+            average: Duration::from_micros(total_us.checked_div(applied).unwrap_or(0)),
+            max: Duration::from_micros(self.max_us.load(Ordering::Relaxed)),
+            late: self.late.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Clear all statistics
+    pub fn reset(&self) {
+        for counter in [
+            &self.applied,
+            &self.total_us,
+            &self.last_us,
+            &self.max_us,
+            &self.late,
+            &self.dropped,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Signal light of a channel strip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalStatus {
+    /// Above the signal threshold just now
+    Present,
+
+    /// Below it, for less than the channel's silence warning if it has one
+    Absent,
+
+    /// Below it for longer than the channel's silence warning
+    Missing,
+}
+
 /// State of a single channel (shared representation for UI)
 #[derive(Debug, Clone)]
 pub struct ChannelState {
     /// Channel name from config
     pub name: String,
 
-    /// Number of ports (1=mono, 2=stereo)
+    /// Number of ports (1=mono, 2=stereo, up to MAX_CHANNEL_PORTS)
     pub port_count: usize,
 
     /// Current volume in dB (-60 to +12)
     pub volume_db: f32,
 
+    /// Gain before metering and processing in dB (inputs only)
+    pub trim_db: f32,
+
+    /// Level difference corrected between the sides in dB, split evenly;
+    /// positive raises the right side (stereo inputs only)
+    pub balance_db: f32,
+
+    /// Whether the meters show mid and side instead of left and right, and
+    /// the mid and side levels apply
+    pub mid_side: bool,
+
+    /// Level of the mid in mid/side mode in dB
+    pub mid_db: f32,
+
+    /// Level of the side in mid/side mode in dB
+    pub side_db: f32,
+
+    /// Where the meters take their level (inputs only)
+    pub meter_point: TapPoint,
+
     /// Whether the channel is muted
     pub muted: bool,
 
     /// Whether the channel is soloed
     pub soloed: bool,
 
+    /// Whether the input is faded out while its source ports are swapped
+    pub switching: bool,
+
+    /// Automixer weight in dB (None outside the group; inputs only)
+    pub automix_weight_db: Option<f32>,
+
+    /// Gain the automixer gave the input in the last block in dB
+    pub automix_db: f32,
+
+    /// Reduction of the protective dip in dB (0 when idle; inputs only)
+    pub dip_db: f32,
+
+    /// Smoothed correlation of left and right (None for silence; stereo
+    /// channels only)
+    pub correlation: Option<f32>,
+
+    /// Volume of the capture device in dB once read (None without a
+    /// device_gain; inputs only)
+    pub device_db: Option<f32>,
+
+    /// Whether the capture device is muted
+    pub device_muted: bool,
+
     /// Current peak levels (linear, 0.0-1.0+)
-    pub current_peaks: [f32; 2],
+    pub current_peaks: [f32; MAX_CHANNEL_PORTS],
+
+    /// Current RMS levels (linear)
+    pub current_rms: [f32; MAX_CHANNEL_PORTS],
 
     /// Peak hold levels (linear, 0.0-1.0+)
-    pub peak_hold: [f32; 2],
+    pub peak_hold: [f32; MAX_CHANNEL_PORTS],
 
     /// Timestamp of last peak hold update
-    pub peak_hold_time: [Instant; 2],
+    pub peak_hold_time: [Instant; MAX_CHANNEL_PORTS],
+
+    /// Rolling peak/RMS window for the crest factor readout
+    pub crest: CrestWindow,
+
+    /// Gain reduction currently applied by auto-trim in dB
+    pub auto_trim_db: f32,
+
+    /// Gain reduction of the limiter in the last block in dB
+    pub limiter_db: f32,
+
+    /// Band reduction of the de-esser in the last block in dB (inputs only)
+    pub deesser_db: f32,
+
+    /// Reduction per band of the multiband compressor in dB (outputs only)
+    pub multiband_db: [f32; MULTIBAND_BANDS],
+
+    /// Rolling min/max of the peaks for the meter range band
+    pub range: RangeWindow,
+
+    /// Peak and RMS per second over the last minute
+    pub history: LevelHistory,
+
+    /// Samples above full scale since the clip counters were last cleared
+    pub clip_count: u64,
+
+    /// Peaks the capture device delivered in its last block (None without
+    /// a device meter or before its first block; inputs only)
+    pub device_peaks: Option<[f32; MAX_CHANNEL_PORTS]>,
+
+    /// Held device peaks, as `peak_hold`
+    pub device_peak_hold: [f32; MAX_CHANNEL_PORTS],
+
+    /// Device samples at full scale since the clip counters were last cleared
+    pub device_clip_count: u64,
+
+    /// Three-band EQ gains (inputs only)
+    pub eq: EqConfig,
+
+    /// Multiband compressor settings (outputs only)
+    pub multiband: Option<MultibandConfig>,
+
+    /// Frequencies of the temporary feedback notches in Hz (outputs only;
+    /// never saved)
+    pub notches: [Option<f32>; MAX_NOTCHES],
+
+    /// How volume key steps map to dB
+    pub taper: FaderTaper,
+
+    /// Highest volume the fader can be set to in dB (at most +12)
+    pub max_volume_db: f32,
+
+    /// Attack and release of the shown peaks and RMS
+    pub ballistics: MeterBallistics,
+
+    /// Peak level (linear) above which the channel counts as carrying signal
+    pub signal_threshold: f32,
+
+    /// Time without signal after which the channel is flagged (None for
+    /// channels that may stay silent)
+    pub silence_warning: Option<Duration>,
+
+    /// When the channel last carried signal, or was created
+    signal_time: Instant,
+
+    /// Whether `signal_time` is the time of a block with signal
+    signal_seen: bool,
+
+    /// When the device peak holds last rose or were reset
+    device_hold_time: Instant,
+
+    /// When the engine measured the last meter block
+    meter_time: Instant,
 }
 
 impl ChannelState {
@@ -124,48 +587,549 @@ impl ChannelState {
             name,
             port_count,
             volume_db: VOLUME_DEFAULT_DB,
+            trim_db: 0.0,
+            balance_db: 0.0,
+            mid_side: false,
+            mid_db: 0.0,
+            side_db: 0.0,
+            meter_point: TapPoint::PreFader,
             muted: false,
             soloed: false,
-            current_peaks: [0.0; 2],
-            peak_hold: [0.0; 2],
-            peak_hold_time: [now; 2],
+            switching: false,
+            automix_weight_db: None,
+            automix_db: 0.0,
+            dip_db: 0.0,
+            correlation: None,
+            device_db: None,
+            device_muted: false,
+            current_peaks: [0.0; MAX_CHANNEL_PORTS],
+            current_rms: [0.0; MAX_CHANNEL_PORTS],
+            peak_hold: [0.0; MAX_CHANNEL_PORTS],
+            peak_hold_time: [now; MAX_CHANNEL_PORTS],
+            crest: CrestWindow::new(now),
+            auto_trim_db: 0.0,
+            limiter_db: 0.0,
+            deesser_db: 0.0,
+            multiband_db: [0.0; MULTIBAND_BANDS],
+            range: RangeWindow::new(RANGE_WINDOW, now),
+            history: LevelHistory::new(now),
+            clip_count: 0,
+            device_peaks: None,
+            device_peak_hold: [0.0; MAX_CHANNEL_PORTS],
+            device_clip_count: 0,
+            eq: EqConfig::default(),
+            multiband: None,
+            notches: [None; MAX_NOTCHES],
+            taper: FaderTaper::default(),
+            max_volume_db: VOLUME_MAX_DB,
+            ballistics: MeterBallistics::Digital,
+            signal_threshold: MeterData::db_to_linear(SIGNAL_THRESHOLD_DB),
+            silence_warning: None,
+            signal_time: now,
+            signal_seen: false,
+            device_hold_time: now,
+            meter_time: now,
         }
     }
 
-    /// Update meter data with new peaks
-    pub fn update_meter(&mut self, peaks: [f32; 2], peak_hold_duration_secs: f32) {
-        let now = Instant::now();
+    /// Linear mid and side gains the audio thread applies; unity unless a
+    /// stereo channel is in mid/side mode
+    pub fn mid_side_gains(&self) -> [f32; 2] {
+        if self.mid_side && self.port_count == 2 {
+            [self.mid_db, self.side_db].map(MeterData::db_to_linear)
+        } else {
+            [1.0, 1.0]
+        }
+    }
+
+    /// A volume limited to the valid range and the channel's cap
+    pub fn clamp_volume(&self, volume_db: f32) -> f32 {
+        volume_db.clamp(VOLUME_MIN_DB, self.max_volume_db)
+    }
 
-        for i in 0..self.port_count {
-            self.current_peaks[i] = peaks[i];
+    /// Update meter data with new peaks measured at `timestamp` and count
+    /// clipped samples; the ballistics follow the engine's block times, so
+    /// blocks drained together still move the meters by their own length
+    pub fn update_meter(
+        &mut self,
+        peaks: [f32; MAX_CHANNEL_PORTS],
+        rms: [f32; MAX_CHANNEL_PORTS],
+        clipped: u32,
+        timestamp: Instant,
+        peak_hold_duration_secs: f32,
+    ) {
+        let now = Instant::now();
+        self.clip_count += u64::from(clipped);
+        let elapsed = timestamp.saturating_duration_since(self.meter_time).as_secs_f32();
+        self.meter_time = self.meter_time.max(timestamp);
+        self.apply_ballistics(&peaks, &rms, elapsed);
 
+        let held = self.peak_hold.iter_mut().zip(&mut self.peak_hold_time);
+        for (&peak, (hold, hold_time)) in peaks.iter().zip(held).take(self.port_count) {
             // Update peak hold if new peak is higher or hold has expired
-            if peaks[i] > self.peak_hold[i] {
-                self.peak_hold[i] = peaks[i];
-                self.peak_hold_time[i] = now;
-            } else if now.duration_since(self.peak_hold_time[i]).as_secs_f32()
-                > peak_hold_duration_secs
-            {
+            if peak > *hold {
+                *hold = peak;
+                *hold_time = now;
+            } else if now.duration_since(*hold_time).as_secs_f32() > peak_hold_duration_secs {
                 // Decay peak hold
-                self.peak_hold[i] = peaks[i];
-                self.peak_hold_time[i] = now;
+                *hold = peak;
+                *hold_time = now;
+            }
+        }
+
+        let ports = &rms[..self.port_count.clamp(1, MAX_CHANNEL_PORTS)];
+        let mean_square = ports.iter().map(|r| r * r).sum::<f32>() / ports.len() as f32;
+        // The crest factor and range take the block as measured
+        let peak = peaks[..self.port_count.min(MAX_CHANNEL_PORTS)]
+            .iter()
+            .fold(0.0f32, |a, b| a.max(*b));
+        self.crest.push(peak, mean_square, now);
+        self.range.push(&peaks[..self.port_count.min(MAX_CHANNEL_PORTS)], now);
+        self.history.push(peak, mean_square, now);
+        self.update_signal(peak, now);
+    }
+
+    /// Update the capture device's peaks with a block it reported; the
+    /// holds of all ports rise together and fall back together
+    pub fn update_device_meter(
+        &mut self,
+        peaks: [f32; MAX_CHANNEL_PORTS],
+        clipped: u32,
+        peak_hold_duration_secs: f32,
+    ) {
+        let now = Instant::now();
+        self.device_peaks = Some(peaks);
+        self.device_clip_count += u64::from(clipped);
+        let ports = self.port_count.min(MAX_CHANNEL_PORTS);
+        let expired =
+            now.duration_since(self.device_hold_time).as_secs_f32() > peak_hold_duration_secs;
+        let holds = &mut self.device_peak_hold[..ports];
+        if expired || peaks[..ports].iter().zip(holds.iter()).any(|(p, h)| p > h) {
+            for (hold, &peak) in holds.iter_mut().zip(&peaks) {
+                *hold = if expired { peak } else { hold.max(peak) };
+            }
+            self.device_hold_time = now;
+        }
+    }
+
+    /// Note a block whose loudest port peaked at `peak`
+    fn update_signal(&mut self, peak: f32, now: Instant) {
+        if peak > self.signal_threshold {
+            self.signal_time = now;
+            self.signal_seen = true;
+        }
+    }
+
+    /// State of the signal light at `now`
+    pub fn signal_status(&self, now: Instant) -> SignalStatus {
+        let since = now.saturating_duration_since(self.signal_time);
+        if self.signal_seen && since < SIGNAL_HOLD {
+            SignalStatus::Present
+        } else if self.silence_warning.is_some_and(|limit| since >= limit) {
+            SignalStatus::Missing
+        } else {
+            SignalStatus::Absent
+        }
+    }
+
+    /// Move the shown correlation towards that of a new block; silence
+    /// clears it
+    pub fn update_correlation(&mut self, correlation: Option<f32>) {
+        self.correlation = correlation.map(|c| match self.correlation {
+            Some(shown) => shown + (c - shown) * CORRELATION_SMOOTHING,
+            None => c,
+        });
+    }
+
+    /// Move the shown peaks and RMS towards a new block measured `elapsed`
+    /// seconds after the previous one
+    fn apply_ballistics(
+        &mut self,
+        peaks: &[f32; MAX_CHANNEL_PORTS],
+        rms: &[f32; MAX_CHANNEL_PORTS],
+        elapsed: f32,
+    ) {
+        let fall = 10f32.powf(-PPM_RELEASE_DB_PER_SEC * elapsed / 20.0);
+        // Share of the way to the new RMS, 99% after VU_RISE_SECS
+        let approach = 1.0 - 0.01f32.powf(elapsed / VU_RISE_SECS);
+        for i in 0..self.port_count.min(MAX_CHANNEL_PORTS) {
+            match self.ballistics {
+                MeterBallistics::Digital => {
+                    self.current_peaks[i] = peaks[i];
+                    self.current_rms[i] = rms[i];
+                }
+                MeterBallistics::Ppm => {
+                    self.current_peaks[i] = peaks[i].max(self.current_peaks[i] * fall);
+                    self.current_rms[i] = rms[i];
+                }
+                MeterBallistics::Vu => {
+                    self.current_peaks[i] = peaks[i].max(self.current_peaks[i] * fall);
+                    self.current_rms[i] += (rms[i] - self.current_rms[i]) * approach;
+                }
             }
         }
     }
 
-    /// Adjust volume by delta, clamping to valid range
+    /// Highest current peak across the channel's ports (linear)
+    pub fn peak(&self) -> f32 {
+        self.current_peaks[..self.port_count.min(MAX_CHANNEL_PORTS)]
+            .iter()
+            .fold(0.0, |a, b| a.max(*b))
+    }
+
+    /// Highest held peak across the channel's ports (linear)
+    pub fn held_peak(&self) -> f32 {
+        self.peak_hold[..self.port_count.min(MAX_CHANNEL_PORTS)]
+            .iter()
+            .fold(0.0, |a, b| a.max(*b))
+    }
+
+    /// Crest factor (peak minus RMS, dB) over the rolling window
+    pub fn crest_factor_db(&self) -> Option<f32> {
+        self.crest.crest_factor_db()
+    }
+
+    /// Adjust volume by a key step along the channel's taper, clamping to
+    /// valid range
     pub fn adjust_volume(&mut self, delta_db: f32) {
-        self.volume_db = (self.volume_db + delta_db).clamp(VOLUME_MIN_DB, VOLUME_MAX_DB);
+        self.volume_db = self.clamp_volume(self.taper.step(self.volume_db, delta_db));
     }
+}
 
-    /// Get volume as linear gain
-    pub fn get_linear_gain(&self) -> f32 {
-        if self.muted {
-            0.0
+/// One slice of the crest factor window
+#[derive(Debug, Clone, Copy, Default)]
+struct CrestBucket {
+    peak: f32,
+    power_sum: f32,
+    count: u32,
+}
+
+/// Rolling window of peak and mean power, split into fixed-length buckets
+#[derive(Debug, Clone)]
+pub struct CrestWindow {
+    buckets: [CrestBucket; CREST_BUCKETS],
+    current: usize,
+    bucket_start: Instant,
+}
+
+impl CrestWindow {
+    /// Create an empty window
+    pub fn new(now: Instant) -> Self {
+        Self {
+            buckets: [CrestBucket::default(); CREST_BUCKETS],
+            current: 0,
+            bucket_start: now,
+        }
+    }
+
+    /// Add one block measurement (linear peak and mean square)
+    pub fn push(&mut self, peak: f32, mean_square: f32, now: Instant) {
+        let bucket_len = CREST_WINDOW / CREST_BUCKETS as u32;
+        let elapsed = now.saturating_duration_since(self.bucket_start);
+        if elapsed >= bucket_len {
+            // Move on, clearing every bucket we skipped over
+            let steps = (elapsed.as_nanos() / bucket_len.as_nanos()) as usize;
+            for _ in 0..steps.min(CREST_BUCKETS) {
+                self.current = (self.current + 1) % CREST_BUCKETS;
+                self.buckets[self.current] = CrestBucket::default();
+            }
+            self.bucket_start += bucket_len * steps as u32;
+        }
+
+        let bucket = &mut self.buckets[self.current];
+        bucket.peak = bucket.peak.max(peak);
+        bucket.power_sum += mean_square;
+        bucket.count += 1;
+    }
+
+    /// Crest factor in dB, or None if the window holds only silence
+    pub fn crest_factor_db(&self) -> Option<f32> {
+        let peak = self.buckets.iter().map(|b| b.peak).fold(0.0, f32::max);
+        let count: u32 = self.buckets.iter().map(|b| b.count).sum();
+        let power: f32 = self.buckets.iter().map(|b| b.power_sum).sum();
+        if count == 0 || peak <= 0.0 || power <= 0.0 {
+            return None;
+        }
+        let mean_square = power / count as f32;
+        Some(20.0 * peak.log10() - 10.0 * mean_square.log10())
+    }
+}
+
+/// One slice of the recent peak range window
+#[derive(Debug, Clone, Copy)]
+struct RangeBucket {
+    min: [f32; MAX_CHANNEL_PORTS],
+    max: [f32; MAX_CHANNEL_PORTS],
+    count: u32,
+}
+
+impl Default for RangeBucket {
+    fn default() -> Self {
+        Self {
+            min: [f32::INFINITY; MAX_CHANNEL_PORTS],
+            max: [0.0; MAX_CHANNEL_PORTS],
+            count: 0,
+        }
+    }
+}
+
+/// Rolling minimum and maximum peak per port over a configurable window
+#[derive(Debug, Clone)]
+pub struct RangeWindow {
+    buckets: [RangeBucket; RANGE_BUCKETS],
+    current: usize,
+    bucket_start: Instant,
+
+    /// Length of one bucket (zero disables the window)
+    bucket_len: Duration,
+}
+
+impl RangeWindow {
+    /// Create an empty window covering `length` (zero disables it)
+    pub fn new(length: Duration, now: Instant) -> Self {
+        Self {
+            buckets: [RangeBucket::default(); RANGE_BUCKETS],
+            current: 0,
+            bucket_start: now,
+            bucket_len: length / RANGE_BUCKETS as u32,
+        }
+    }
+
+    /// Add one block of per-port peaks (linear)
+    pub fn push(&mut self, peaks: &[f32], now: Instant) {
+        if self.bucket_len.is_zero() {
+            return;
+        }
+        let elapsed = now.saturating_duration_since(self.bucket_start);
+        if elapsed >= self.bucket_len {
+            // Move on, clearing every bucket we skipped over
+            let steps = (elapsed.as_nanos() / self.bucket_len.as_nanos()) as usize;
+            for _ in 0..steps.min(RANGE_BUCKETS) {
+                self.current = (self.current + 1) % RANGE_BUCKETS;
+                self.buckets[self.current] = RangeBucket::default();
+            }
+            self.bucket_start += self.bucket_len * steps as u32;
+        }
+
+        let bucket = &mut self.buckets[self.current];
+        for (port, &peak) in peaks.iter().enumerate().take(MAX_CHANNEL_PORTS) {
+            bucket.min[port] = bucket.min[port].min(peak);
+            bucket.max[port] = bucket.max[port].max(peak);
+        }
+        bucket.count += 1;
+    }
+
+    /// Lowest and highest peak of a port over the window, or None if empty
+    pub fn range(&self, port: usize) -> Option<(f32, f32)> {
+        let filled = self.buckets.iter().filter(|b| b.count > 0);
+        let (min, max) = filled.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), b| {
+            (lo.min(b.min[port]), hi.max(b.max[port]))
+        });
+        (min <= max).then_some((min, max))
+    }
+}
+
+/// Peak and RMS of a channel per second over the last minute, so a source
+/// that dropped out while nobody watched still shows
+#[derive(Debug, Clone)]
+pub struct LevelHistory {
+    /// Highest peak (linear) of each second
+    peaks: [f32; LEVEL_HISTORY_SECONDS],
+
+    /// Sum of the block mean squares of each second
+    energy: [f32; LEVEL_HISTORY_SECONDS],
+
+    /// Meter blocks received in each second
+    blocks: [u32; LEVEL_HISTORY_SECONDS],
+
+    /// Bucket of the current second
+    current: usize,
+    bucket_start: Instant,
+}
+
+impl LevelHistory {
+    /// Create an empty history
+    pub fn new(now: Instant) -> Self {
+        Self {
+            peaks: [0.0; LEVEL_HISTORY_SECONDS],
+            energy: [0.0; LEVEL_HISTORY_SECONDS],
+            blocks: [0; LEVEL_HISTORY_SECONDS],
+            current: 0,
+            bucket_start: now,
+        }
+    }
+
+    /// Add the peak and mean square (linear) of one meter block
+    pub fn push(&mut self, peak: f32, mean_square: f32, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.bucket_start).as_secs() as usize;
+        if elapsed > 0 {
+            // Move on, clearing every second we skipped over
+            for _ in 0..elapsed.min(LEVEL_HISTORY_SECONDS) {
+                self.current = (self.current + 1) % LEVEL_HISTORY_SECONDS;
+                self.peaks[self.current] = 0.0;
+                self.energy[self.current] = 0.0;
+                self.blocks[self.current] = 0;
+            }
+            self.bucket_start += Duration::from_secs(elapsed as u64);
+        }
+        self.peaks[self.current] = self.peaks[self.current].max(peak);
+        self.energy[self.current] += mean_square;
+        self.blocks[self.current] += 1;
+    }
+
+    /// Peak and RMS (linear) of each second, oldest first and the current
+    /// one last; None for seconds without meter data
+    pub fn seconds(&self) -> impl Iterator<Item = Option<(f32, f32)>> + '_ {
+        (1..=LEVEL_HISTORY_SECONDS).map(move |age| {
+            let i = (self.current + age) % LEVEL_HISTORY_SECONDS;
+            let blocks = self.blocks[i];
+            (blocks > 0).then(|| (self.peaks[i], (self.energy[i] / blocks as f32).sqrt()))
+        })
+    }
+}
+
+/// Send from one input to one output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crosspoint {
+    /// Whether the input feeds the output at all
+    pub enabled: bool,
+
+    /// Send level in dB
+    pub gain_db: f32,
+
+    /// Fold a stereo input down to mono on this output
+    pub mono: bool,
+}
+
+/// Quick routing state of a crosspoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteMode {
+    Off,
+    Mono,
+    Stereo,
+}
+
+impl Default for Crosspoint {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            gain_db: VOLUME_DEFAULT_DB,
+            mono: false,
+        }
+    }
+}
+
+impl Crosspoint {
+    /// Quick routing state
+    pub fn mode(&self) -> RouteMode {
+        match (self.enabled, self.mono) {
+            (false, _) => RouteMode::Off,
+            (true, true) => RouteMode::Mono,
+            (true, false) => RouteMode::Stereo,
+        }
+    }
+
+    /// Same send level with a different routing state
+    pub fn with_mode(self, mode: RouteMode) -> Self {
+        Self {
+            enabled: mode != RouteMode::Off,
+            mono: mode == RouteMode::Mono,
+            ..self
+        }
+    }
+
+    /// Next quick routing state: stereo -> mono -> off for stereo inputs,
+    /// on -> off for mono inputs
+    pub fn cycle(self, stereo_input: bool) -> Self {
+        let next = match (self.mode(), stereo_input) {
+            (RouteMode::Stereo, true) => RouteMode::Mono,
+            (RouteMode::Off, _) => RouteMode::Stereo,
+            _ => RouteMode::Off,
+        };
+        self.with_mode(next)
+    }
+
+    /// Linear send gain (0.0 when disabled)
+    pub fn linear_gain(&self) -> f32 {
+        if self.enabled {
+            MeterData::db_to_linear(self.gain_db)
         } else {
-            MeterData::db_to_linear(self.volume_db)
+            0.0
+        }
+    }
+}
+
+/// Input x output crosspoint matrix
+#[derive(Debug, Clone)]
+pub struct RoutingMatrix {
+    outputs: usize,
+    points: Vec<Crosspoint>,
+}
+
+impl RoutingMatrix {
+    /// Create a matrix with every input sent to every output at 0 dB
+    pub fn new(inputs: usize, outputs: usize) -> Self {
+        Self {
+            outputs,
+            points: vec![Crosspoint::default(); inputs * outputs],
+        }
+    }
+
+    /// Build the matrix from the `routes` of each input channel
+    ///
+    /// Inputs without `routes` feed every output at 0 dB.
+    pub fn from_config(config: &Config) -> Self {
+        let mut matrix = Self::new(config.inputs.len(), config.outputs.len());
+        for (input, channel) in config.inputs.iter().enumerate() {
+            let Some(routes) = &channel.routes else {
+                continue;
+            };
+            for (output, out_channel) in config.outputs.iter().enumerate() {
+                let route = routes.iter().find(|r| r.output == out_channel.name);
+                matrix.set(
+                    input,
+                    output,
+                    Crosspoint {
+                        enabled: route.is_some(),
+                        gain_db: route.map(|r| r.gain_db).unwrap_or(VOLUME_DEFAULT_DB),
+                        mono: route.is_some_and(|r| r.mono),
+                    },
+                );
+            }
+        }
+        matrix
+    }
+
+    /// Crosspoint from an input to an output (disabled if out of range)
+    pub fn get(&self, input: usize, output: usize) -> Crosspoint {
+        let off = Crosspoint::default().with_mode(RouteMode::Off);
+        if output >= self.outputs {
+            return off;
+        }
+        self.points
+            .get(input * self.outputs + output)
+            .copied()
+            .unwrap_or(off)
+    }
+
+    /// Change a crosspoint (ignored if out of range)
+    pub fn set(&mut self, input: usize, output: usize, crosspoint: Crosspoint) {
+        if output < self.outputs {
+            if let Some(point) = self.points.get_mut(input * self.outputs + output) {
+                *point = crosspoint;
+            }
         }
     }
+
+    /// Linear send gain from an input to an output
+    pub fn gain(&self, input: usize, output: usize) -> f32 {
+        self.get(input, output).linear_gain()
+    }
+
+    /// Whether an input uses the default routing (every output at 0 dB)
+    pub fn is_default(&self, input: usize) -> bool {
+        (0..self.outputs).all(|output| self.get(input, output) == Crosspoint::default())
+    }
 }
 
 /// Mixer state containing all channel states
@@ -173,26 +1137,482 @@ impl ChannelState {
 pub struct MixerState {
     pub inputs: Vec<ChannelState>,
     pub outputs: Vec<ChannelState>,
+
+    /// Master fader, applied after every output's own gain
+    pub master: ChannelState,
+
+    /// Multichannel passthrough faders (independent of the mix and master)
+    pub passthrough: Vec<ChannelState>,
+
+    /// Input -> output crosspoints
+    pub routing: RoutingMatrix,
+
+    /// Whether solos go to a cue output instead of silencing the other inputs
+    pub solo_cue: bool,
+
+    /// Whether a new solo releases the others
+    pub solo_mode: SoloMode,
 }
 
 impl MixerState {
-    /// Check if any input channel is soloed
-    pub fn any_input_soloed(&self) -> bool {
-        self.inputs.iter().any(|ch| ch.soloed)
+    /// Mixer state of a configuration with its saved volumes, switches and
+    /// settings; the engine and the UI both start from it
+    pub fn from_config(config: &Config) -> Self {
+        let channel_states = |channels: &[ChannelConfig]| -> Vec<ChannelState> {
+            channels
+                .iter()
+                .map(|c| {
+                    let mut state = ChannelState::new(c.name.clone(), c.port_count());
+                    state.max_volume_db = c.max_volume_db.unwrap_or(VOLUME_MAX_DB);
+                    if let Some(vol) = c.volume_db {
+                        state.volume_db = state.clamp_volume(vol);
+                    }
+                    state.trim_db = c.trim_db.unwrap_or(0.0);
+                    state.muted = c.muted;
+                    state.soloed = c.soloed;
+                    state.balance_db = c.balance_db.unwrap_or(0.0);
+                    state.mid_side = c.mid_side;
+                    state.mid_db = c.mid_db.unwrap_or(0.0);
+                    state.side_db = c.side_db.unwrap_or(0.0);
+                    state.automix_weight_db = c.automix_weight_db;
+                    state.meter_point = config.input_meter_point(c);
+                    state.eq = c.eq.clone().unwrap_or_default();
+                    state.multiband = c.multiband;
+                    state.taper = c.taper.clone().unwrap_or(config.fader_taper.clone());
+                    state.silence_warning =
+                        c.silence_warning_seconds.map(Duration::from_secs_f32);
+                    state
+                })
+                .collect()
+        };
+
+        let outputs = channel_states(&config.outputs);
+        let mut master = Self::master_for(&outputs);
+        if let Some(vol) = config.master_volume_db {
+            master.volume_db = master.clamp_volume(vol);
+        }
+        master.muted = config.master_muted;
+        master.taper = config.fader_taper.clone();
+
+        let passthrough = config
+            .passthrough
+            .iter()
+            .map(|p| {
+                let mut state = ChannelState::new(p.name.clone(), 1);
+                if let Some(vol) = p.volume_db {
+                    state.volume_db = state.clamp_volume(vol);
+                }
+                state.muted = p.muted;
+                state.taper = config.fader_taper.clone();
+                state
+            })
+            .collect();
+
+        let mut state = Self {
+            inputs: channel_states(&config.inputs),
+            outputs,
+            master,
+            passthrough,
+            routing: RoutingMatrix::from_config(config),
+            solo_cue: config.outputs.iter().any(|o| o.solo_cue.is_some()),
+            solo_mode: config.solo_mode,
+        };
+
+        let range = config
+            .meter_range_seconds
+            .map_or(RANGE_WINDOW, Duration::from_secs_f32);
+        let now = Instant::now();
+        for channel in state
+            .inputs
+            .iter_mut()
+            .chain(state.outputs.iter_mut())
+            .chain(state.passthrough.iter_mut())
+            .chain(std::iter::once(&mut state.master))
+        {
+            channel.range = RangeWindow::new(range, now);
+            channel.ballistics = config.meter_ballistics;
+            channel.signal_threshold = MeterData::db_to_linear(
+                config.signal_threshold_db.unwrap_or(SIGNAL_THRESHOLD_DB),
+            );
+        }
+        state
+    }
+
+    /// Mixer state of the given channels with the default routing, for tests
+    #[cfg(test)]
+    pub fn for_test(inputs: Vec<ChannelState>, outputs: Vec<ChannelState>) -> Self {
+        Self {
+            master: Self::master_for(&outputs),
+            passthrough: Vec::new(),
+            routing: RoutingMatrix::new(inputs.len(), outputs.len()),
+            solo_cue: false,
+            solo_mode: SoloMode::default(),
+            inputs,
+            outputs,
+        }
+    }
+
+    /// Master channel state for the given outputs (stereo unless every output is mono)
+    pub fn master_for(outputs: &[ChannelState]) -> ChannelState {
+        let port_count = outputs.iter().map(|c| c.port_count).max().unwrap_or(2).min(2);
+        ChannelState::new(MASTER_NAME.to_string(), port_count)
+    }
+
+    /// Input currently contributing the highest post-fader level
+    ///
+    /// Considers the sends to the given output, or to any output if None.
+    /// Returns the channel index and its contributed peak in dB, or None if
+    /// every input is silent, muted, soloed out or not routed.
+    pub fn loudest_input(&self, output: Option<usize>) -> Option<(usize, f32)> {
+        (0..self.inputs.len())
+            .map(|i| {
+                let peak = self.inputs[i].peak();
+                let send = match output {
+                    Some(o) => self.routing.gain(i, o),
+                    None => (0..self.outputs.len())
+                        .map(|o| self.routing.gain(i, o))
+                        .fold(0.0, f32::max),
+                };
+                (i, peak * self.get_input_effective_gain(i) * send)
+            })
+            .filter(|(_, level)| MeterData::linear_to_db(*level) > VOLUME_MIN_DB)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, level)| (i, MeterData::linear_to_db(level)))
+    }
+
+    /// What each input currently sends into an output
+    ///
+    /// For every input: its peak and held peak after fader, mute and solo,
+    /// times the send gain (linear), or None if it is not routed to the output.
+    pub fn contributions(&self, output: usize) -> Vec<Option<(f32, f32)>> {
+        (0..self.inputs.len())
+            .map(|i| {
+                let crosspoint = self.routing.get(i, output);
+                crosspoint.enabled.then(|| {
+                    let gain = self.get_input_effective_gain(i) * crosspoint.linear_gain();
+                    (self.inputs[i].peak() * gain, self.inputs[i].held_peak() * gain)
+                })
+            })
+            .collect()
+    }
+
+    /// Whether an input is neither muted nor silenced by a solo (solos only
+    /// silence inputs without a solo cue output)
+    pub fn input_audible(&self, index: usize) -> bool {
+        let channel = &self.inputs[index];
+        !channel.muted && !channel.switching && solo::input_passes(self, index)
+    }
+
+    /// Whether an output is neither muted nor silenced by another output's solo
+    pub fn output_audible(&self, index: usize) -> bool {
+        !self.outputs[index].muted && solo::output_passes(self, index)
     }
 
     /// Get effective gain for an input channel (considering solo state)
     pub fn get_input_effective_gain(&self, index: usize) -> f32 {
-        let channel = &self.inputs[index];
-        if channel.muted {
+        if !self.input_audible(index) {
             return 0.0;
         }
+        MeterData::db_to_linear(self.inputs[index].volume_db)
+    }
+}
 
-        let any_soloed = self.any_input_soloed();
-        if any_soloed && !channel.soloed {
-            return 0.0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crest_factor_of_sine() {
+        let start = Instant::now();
+        let mut window = CrestWindow::new(start);
+        assert_eq!(window.crest_factor_db(), None);
+
+        window.push(1.0, 0.5, start);
+        let crest = window.crest_factor_db().unwrap();
+        assert!((crest - 3.01).abs() < 0.01, "{}", crest);
+    }
+
+    #[test]
+    fn test_crest_window_forgets_old_peaks() {
+        let start = Instant::now();
+        let mut window = CrestWindow::new(start);
+        window.push(1.0, 0.01, start);
+        window.push(0.1, 0.005, start + CREST_WINDOW + Duration::from_millis(200));
+        let crest = window.crest_factor_db().unwrap();
+        assert!((crest - 3.01).abs() < 0.01, "{}", crest);
+    }
+
+    #[test]
+    fn test_volume_cap() {
+        let mut channel = ChannelState::new("Mic".to_string(), 1);
+        channel.max_volume_db = -6.0;
+        channel.volume_db = -7.0;
+        channel.adjust_volume(3.0);
+        assert_eq!(channel.volume_db, -6.0);
+        assert_eq!(channel.clamp_volume(12.0), -6.0);
+        assert_eq!(channel.clamp_volume(-100.0), VOLUME_MIN_DB);
+    }
+
+    #[test]
+    fn test_correlation_smoothing() {
+        let mut channel = ChannelState::new("Keys".to_string(), 2);
+        channel.update_correlation(Some(1.0));
+        assert_eq!(channel.correlation, Some(1.0));
+
+        // A polarity flip pulls the shown value down over a few blocks
+        channel.update_correlation(Some(-1.0));
+        assert!((channel.correlation.unwrap() - 0.8).abs() < 1e-6);
+        for _ in 0..30 {
+            channel.update_correlation(Some(-1.0));
+        }
+        assert!(channel.correlation.unwrap() < -0.9);
+
+        channel.update_correlation(None);
+        assert_eq!(channel.correlation, None);
+    }
+
+    #[test]
+    fn test_signal_status() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut channel = ChannelState::new("Mic".to_string(), 1);
+        channel.signal_time = start;
+        assert_eq!(channel.signal_status(at(0)), SignalStatus::Absent);
+
+        // -40 dBFS lights it, -60 does not; it stays lit briefly after
+        channel.update_signal(0.01, at(100));
+        assert_eq!(channel.signal_status(at(100)), SignalStatus::Present);
+        channel.update_signal(0.001, at(200));
+        assert_eq!(channel.signal_status(at(300)), SignalStatus::Present);
+        assert_eq!(channel.signal_status(at(500)), SignalStatus::Absent);
+
+        // A channel that should have signal is flagged once silent too long,
+        // counting from its creation if it never had any
+        channel.silence_warning = Some(Duration::from_secs(5));
+        assert_eq!(channel.signal_status(at(5000)), SignalStatus::Absent);
+        assert_eq!(channel.signal_status(at(5100)), SignalStatus::Missing);
+        let mut idle = ChannelState::new("Spare".to_string(), 1);
+        idle.silence_warning = Some(Duration::from_secs(5));
+        idle.signal_time = start;
+        assert_eq!(idle.signal_status(at(5000)), SignalStatus::Missing);
+    }
+
+    #[test]
+    fn test_device_meter() {
+        let mut channel = ChannelState::new("Mic".to_string(), 2);
+        assert_eq!(channel.device_peaks, None);
+
+        let mut block = [0.0; MAX_CHANNEL_PORTS];
+        block[..2].copy_from_slice(&[0.5, 1.0]);
+        channel.update_device_meter(block, 3, 5.0);
+        block[..2].copy_from_slice(&[0.7, 0.2]);
+        channel.update_device_meter(block, 0, 5.0);
+        assert_eq!(channel.device_peaks.unwrap()[..2], [0.7, 0.2]);
+        assert_eq!(channel.device_peak_hold[..2], [0.7, 1.0]);
+        assert_eq!(channel.device_clip_count, 3);
+
+        // Expired holds fall back to the latest block
+        std::thread::sleep(Duration::from_millis(2));
+        block[..2].copy_from_slice(&[0.1, 0.1]);
+        channel.update_device_meter(block, 0, 0.0);
+        assert_eq!(channel.device_peak_hold[..2], [0.1, 0.1]);
+    }
+
+    #[test]
+    fn test_meter_ballistics() {
+        let mut channel = ChannelState::new("Mic".to_string(), 1);
+        let mut block = [0.0; MAX_CHANNEL_PORTS];
+        block[0] = 1.0;
+        let silence = [0.0; MAX_CHANNEL_PORTS];
+
+        channel.apply_ballistics(&block, &block, 0.0);
+        channel.apply_ballistics(&silence, &silence, 0.85);
+        assert_eq!(channel.current_peaks[0], 0.0);
+
+        // A PPM falls back 10 dB in 0.85 s
+        channel.ballistics = MeterBallistics::Ppm;
+        channel.apply_ballistics(&block, &block, 0.0);
+        channel.apply_ballistics(&silence, &silence, 0.85);
+        let db = MeterData::linear_to_db(channel.current_peaks[0]);
+        assert!((db + 10.0).abs() < 0.01, "{}", db);
+        assert_eq!(channel.current_rms[0], 0.0);
+
+        // A VU meter gets to 99% of a step in 300 ms
+        channel.ballistics = MeterBallistics::Vu;
+        channel.apply_ballistics(&block, &block, 0.15);
+        assert!((channel.current_rms[0] - 0.9).abs() < 0.001);
+        channel.apply_ballistics(&block, &block, 0.15);
+        assert!((channel.current_rms[0] - 0.99).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_meter_ballistics_follow_block_times() {
+        // Blocks drained in one UI frame arrive together, but each moves a
+        // VU meter by the 10 ms the engine measured it apart
+        let mut channel = ChannelState::new("Music".to_string(), 1);
+        channel.ballistics = MeterBallistics::Vu;
+        let mut block = [0.0; MAX_CHANNEL_PORTS];
+        block[0] = 1.0;
+        let start = channel.meter_time;
+        for i in 1..=30 {
+            let timestamp = start + Duration::from_millis(10 * i);
+            channel.update_meter(block, block, 0, timestamp, 1.0);
+            if i == 15 {
+                assert!((channel.current_rms[0] - 0.9).abs() < 0.001);
+            }
         }
+        assert!((channel.current_rms[0] - 0.99).abs() < 0.001);
+
+        // A block measured before the last one does not move it
+        channel.update_meter(block.map(|_| 0.0), block.map(|_| 0.0), 0, start, 1.0);
+        assert!((channel.current_rms[0] - 0.99).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_contributions() {
+        let mut state = MixerState::for_test(
+            vec![
+                ChannelState::new("Mic".to_string(), 1),
+                ChannelState::new("Music".to_string(), 2),
+                ChannelState::new("Game".to_string(), 2),
+            ],
+            vec![ChannelState::new("Stream".to_string(), 2)],
+        );
+        for input in &mut state.inputs {
+            input.current_peaks = [0.5; MAX_CHANNEL_PORTS];
+            input.peak_hold = [0.8; MAX_CHANNEL_PORTS];
+        }
+        state.inputs[1].volume_db = -6.0;
+        state.inputs[2].muted = true;
+        state.routing.set(0, 0, Crosspoint::default().with_mode(RouteMode::Off));
+
+        let contributions = state.contributions(0);
+        assert_eq!(contributions[0], None);
+        let (peak, held) = contributions[1].unwrap();
+        assert!((MeterData::linear_to_db(peak) - (-12.02)).abs() < 0.01);
+        assert!(held > peak);
+        assert_eq!(contributions[2], Some((0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_range_window() {
+        let start = Instant::now();
+        let mut window = RangeWindow::new(RANGE_WINDOW, start);
+        assert_eq!(window.range(0), None);
+
+        window.push(&[0.5, 0.2], start);
+        window.push(&[0.1, 0.9], start + Duration::from_millis(500));
+        assert_eq!(window.range(0), Some((0.1, 0.5)));
+        assert_eq!(window.range(1), Some((0.2, 0.9)));
+
+        // Old buckets drop out of the window
+        window.push(&[0.3, 0.3], start + RANGE_WINDOW + Duration::from_millis(300));
+        assert_eq!(window.range(0), Some((0.1, 0.3)));
+
+        let mut disabled = RangeWindow::new(Duration::ZERO, start);
+        disabled.push(&[0.5], start);
+        assert_eq!(disabled.range(0), None);
+    }
+
+    #[test]
+    fn test_level_history() {
+        let start = Instant::now();
+        let mut history = LevelHistory::new(start);
+        assert!(history.seconds().all(|s| s.is_none()));
+
+        history.push(0.5, 0.04, start);
+        history.push(0.8, 0.16, start + Duration::from_millis(500));
+        let at = |history: &LevelHistory, age: usize| {
+            history.seconds().nth(LEVEL_HISTORY_SECONDS - 1 - age).unwrap()
+        };
+        let (peak, rms) = at(&history, 0).unwrap();
+        assert_eq!(peak, 0.8);
+        assert!((rms - 0.316).abs() < 0.001);
+
+        // A gap of three seconds leaves empty seconds behind
+        history.push(0.1, 0.0, start + Duration::from_millis(4200));
+        assert_eq!(at(&history, 0), Some((0.1, 0.0)));
+        assert_eq!(at(&history, 1), None);
+        assert_eq!(at(&history, 3), None);
+        assert_eq!(at(&history, 4).unwrap().0, 0.8);
+
+        // A minute later it has all scrolled out
+        history.push(0.2, 0.0, start + Duration::from_secs(65));
+        assert_eq!(history.seconds().flatten().count(), 1);
+    }
+
+    #[test]
+    fn test_routing_matrix_from_config() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    routes:
+      - output: "Stream"
+        gain_db: -3.0
+  - name: "Music"
+    ports: ["music_in"]
+outputs:
+  - name: "Monitor"
+    ports: ["mon_out"]
+  - name: "Stream"
+    ports: ["stream_out"]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let matrix = RoutingMatrix::from_config(&config);
+
+        assert!(!matrix.get(0, 0).enabled);
+        assert_eq!(matrix.get(0, 1).gain_db, -3.0);
+        assert!(!matrix.is_default(0));
+        assert!(matrix.is_default(1));
+        assert!(!matrix.get(1, 2).enabled);
+    }
+
+    #[test]
+    fn test_mixer_state_from_config() {
+        let yaml = r#"
+client_name: "Mixer"
+master_volume_db: 20.0
+master_muted: true
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    volume_db: 9.0
+    max_volume_db: 6.0
+    muted: true
+  - name: "Music"
+    ports: ["music_l", "music_r"]
+    soloed: true
+outputs:
+  - name: "Stream"
+    ports: ["stream_l", "stream_r"]
+    muted: true
+passthrough:
+  - name: "Surround"
+    inputs: ["in_1", "in_2"]
+    outputs: ["out_1", "out_2"]
+    muted: true
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let state = MixerState::from_config(&config);
+
+        assert_eq!(state.inputs[0].volume_db, 6.0);
+        assert_eq!(state.master.volume_db, VOLUME_MAX_DB);
+        assert!(state.master.muted && state.outputs[0].muted && state.passthrough[0].muted);
+
+        // The saved switches are in place before anything plays
+        assert!(!state.input_audible(0));
+        assert!(state.input_audible(1) && state.inputs[1].soloed);
+        assert!(!state.output_audible(0));
+    }
 
-        MeterData::db_to_linear(channel.volume_db)
+    #[test]
+    fn test_route_mode_cycle() {
+        let stereo = Crosspoint::default();
+        assert_eq!(stereo.cycle(true).mode(), RouteMode::Mono);
+        assert_eq!(stereo.cycle(true).cycle(true).mode(), RouteMode::Off);
+        assert_eq!(stereo.cycle(false).mode(), RouteMode::Off);
+        assert_eq!(stereo.cycle(false).cycle(false).mode(), RouteMode::Stereo);
     }
 }
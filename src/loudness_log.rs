@@ -0,0 +1,173 @@
+//! Loudness logging module for rmixer
+//!
+//! Periodically writes the momentary (400 ms) and short-term (3 s) loudness
+//! and the sample peak of every output to a CSV file, so a show can be
+//! checked against a loudness target afterwards. Both loudness values are
+//! ungated BS.1770 windows ending at the time of the row; the peak is the
+//! highest since the previous row.
+
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::audio::mean_square_to_lufs;
+use crate::ipc::{MeterData, MixerState, MAX_CHANNEL_PORTS};
+use crate::meter_log::{csv_field, format_db};
+
+/// Length of the momentary loudness window
+const MOMENTARY: Duration = Duration::from_millis(400);
+
+/// Length of the short-term loudness window
+const SHORT_TERM: Duration = Duration::from_secs(3);
+
+/// Recent meter blocks of one output
+#[derive(Debug, Clone, Default)]
+struct OutputWindow {
+    /// Arrival time and K-weighted mean square of the blocks of the last
+    /// short-term window, oldest first
+    blocks: VecDeque<(Instant, f32)>,
+
+    /// Highest sample peak of any port since the last row
+    peak: f32,
+}
+
+impl OutputWindow {
+    /// Loudness in LUFS of the blocks received within `window` of `now`
+    fn lufs(&self, window: Duration, now: Instant) -> f32 {
+        let (sum, count) = self
+            .blocks
+            .iter()
+            .rev()
+            .take_while(|(time, _)| now.duration_since(*time) <= window)
+            .fold((0.0, 0), |(sum, count), (_, ms)| (sum + ms, count + 1));
+        mean_square_to_lufs(sum / count.max(1) as f32)
+    }
+}
+
+/// Writes periodic loudness snapshots of the outputs to a CSV file
+pub struct LoudnessLogger {
+    /// Buffered CSV output
+    writer: BufWriter<File>,
+
+    /// Time between two rows
+    interval: Duration,
+
+    /// Session start time
+    started: Instant,
+
+    /// Time the last row was written
+    last_write: Instant,
+
+    /// Meter channel index of the first output
+    first_output: usize,
+
+    /// Windows per output
+    windows: Vec<OutputWindow>,
+}
+
+impl LoudnessLogger {
+    /// Create a logger writing to `path`, emitting one row every `interval`
+    pub fn create<P: AsRef<Path>>(path: P, interval: Duration, state: &MixerState) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create loudness log: {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+
+        let mut header = String::from("unix_time,elapsed_s");
+        for channel in &state.outputs {
+            for column in ["momentary_lufs", "short_term_lufs", "peak_db"] {
+                header.push(',');
+                header.push_str(&csv_field(&format!("{}.{}", channel.name, column)));
+            }
+        }
+        writeln!(writer, "{}", header).context("Failed to write loudness log header")?;
+
+        let now = Instant::now();
+        Ok(Self {
+            writer,
+            interval,
+            started: now,
+            last_write: now,
+            first_output: state.inputs.len(),
+            windows: vec![OutputWindow::default(); state.outputs.len()],
+        })
+    }
+
+    /// Add a meter update from the audio thread (only outputs are kept)
+    pub fn record(&mut self, meter: &MeterData, now: Instant) {
+        let Some(index) = meter.channel_index.checked_sub(self.first_output) else {
+            return;
+        };
+        if let Some(window) = self.windows.get_mut(index) {
+            while window
+                .blocks
+                .front()
+                .is_some_and(|(time, _)| now.duration_since(*time) > SHORT_TERM)
+            {
+                window.blocks.pop_front();
+            }
+            window.blocks.push_back((now, meter.loudness_ms));
+            let ports = meter.port_count.min(MAX_CHANNEL_PORTS);
+            let peak = meter.peaks[..ports].iter().fold(0.0f32, |m, p| m.max(*p));
+            window.peak = window.peak.max(peak);
+        }
+    }
+
+    /// Write a row if the interval has elapsed
+    pub fn tick(&mut self, now: Instant) -> Result<()> {
+        if now.duration_since(self.last_write) < self.interval {
+            return Ok(());
+        }
+        self.last_write = now;
+
+        let unix_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        let elapsed = now.duration_since(self.started).as_secs_f64();
+        let mut row = format!("{:.3},{:.3}", unix_time, elapsed);
+        for window in &mut self.windows {
+            row.push_str(&format!(
+                ",{},{},{}",
+                format_db(window.lufs(MOMENTARY, now)),
+                format_db(window.lufs(SHORT_TERM, now)),
+                format_db(MeterData::linear_to_db(window.peak))
+            ));
+            window.peak = 0.0;
+        }
+
+        writeln!(self.writer, "{}", row).context("Failed to write loudness log row")?;
+        self.writer.flush().context("Failed to flush loudness log")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_momentary_and_short_term_windows() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut window = OutputWindow::default();
+
+        // Two and a half seconds at -20 LUFS, then half a second at -10
+        let quiet = 10f32.powf((-20.0 + 0.691) / 10.0);
+        let loud = 10f32.powf((-10.0 + 0.691) / 10.0);
+        for ms in (0..3000).step_by(10) {
+            let power = if ms < 2500 { quiet } else { loud };
+            window.blocks.push_back((at(ms), power));
+        }
+        let now = at(2990);
+        assert!((window.lufs(MOMENTARY, now) + 10.0).abs() < 0.01);
+        // 250 blocks at the quiet power and 50 at ten times it
+        assert!((window.lufs(SHORT_TERM, now) + 16.02).abs() < 0.01);
+
+        // Nothing received: the meter floor
+        let empty = OutputWindow::default();
+        assert_eq!(format_db(empty.lufs(MOMENTARY, now)), "-60.0");
+    }
+}
@@ -7,14 +7,28 @@
 //! - Per-channel volume, mute, and solo controls
 //! - Terminal-based user interface
 
+mod app_streams;
 mod audio;
 mod config;
+mod device_gain;
+mod device_meter;
+mod i18n;
+mod import;
 mod ipc;
+mod loudness_log;
+mod meter_log;
+mod remote;
+mod scheduler;
+mod session_report;
+mod solo;
+mod startup;
+mod tally;
 mod ui;
 
 use anyhow::{Context, Result};
 use clap::Parser;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// RMixer - Pipewire Audio Mixer
 #[derive(Parser, Debug)]
@@ -28,6 +42,50 @@ struct Args {
     /// Enable verbose logging
     #[arg(short, long)]
     verbose: bool,
+
+    /// Log periodic meter values (peak/RMS/LUFS per channel) to a CSV file
+    #[arg(long, value_name = "PATH")]
+    meter_log: Option<PathBuf>,
+
+    /// Interval between meter log rows in seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 1.0)]
+    meter_log_interval: f64,
+
+    /// Log momentary/short-term loudness and peak per output to a CSV file
+    #[arg(long, value_name = "PATH")]
+    log_loudness: Option<PathBuf>,
+
+    /// Interval between loudness log rows in seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 1.0)]
+    log_loudness_interval: f64,
+
+    /// Write a session report (levels, clips, xruns, scene recalls, files) to
+    /// this file on exit
+    #[arg(long, value_name = "PATH")]
+    session_report: Option<PathBuf>,
+
+    /// Control socket address: a Unix socket path or TCP host:port (overrides config)
+    #[arg(long, value_name = "ADDR")]
+    control_socket: Option<String>,
+
+    /// Override a config value, e.g. `--set inputs[0].volume_db=-10` (repeatable;
+    /// the config file is not saved while overrides are active)
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    overrides: Vec<String>,
+
+    /// Generate the config file from the running PulseAudio/PipeWire loopback
+    /// and null-sink modules (`pactl`) instead of starting the mixer
+    #[arg(long)]
+    import_pulse: bool,
+
+    /// Show the selected channel and mixer status in the terminal/tmux title
+    #[arg(long)]
+    terminal_title: bool,
+
+    /// Run without the UI, reading control socket commands from stdin and
+    /// answering on stdout; exits when stdin is closed
+    #[arg(long, conflicts_with = "terminal_title")]
+    stdin_commands: bool,
 }
 
 fn main() -> Result<()> {
@@ -41,11 +99,18 @@ fn main() -> Result<()> {
         env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
     }
 
+    if args.import_pulse {
+        return import_pulse(&args.config);
+    }
+
     log::info!("Starting RMixer");
 
     // Load configuration
-    let config = config::Config::load(&args.config)
+    let mut config = config::Config::load_with_overrides(&args.config, &args.overrides)
         .with_context(|| format!("Failed to load config from {:?}", args.config))?;
+    if !args.overrides.is_empty() {
+        log::warn!("Config overrides active: changes will not be saved to {:?}", args.config);
+    }
 
     log::info!(
         "Loaded config: client='{}', {} inputs, {} outputs",
@@ -54,11 +119,66 @@ fn main() -> Result<()> {
         config.outputs.len()
     );
 
+    i18n::init(config.locale.as_deref());
+
+    // Settle the startup volumes: overrides, then the startup scene, then
+    // the saved volumes
+    let startup = startup::resolve(&mut config, &args.overrides);
+    startup.log();
+
     // Create and run the application
-    let app = ui::App::new(config)?;
+    let control_socket = args.control_socket.clone().or(config.control_socket.clone());
+    let mut app = ui::App::new(config)?.with_startup_report(&startup)?;
+    if let Some(address) = &control_socket {
+        app = app
+            .with_control_socket(address)
+            .with_context(|| format!("Failed to start control socket {}", address))?;
+    }
+    if let Some(path) = &args.meter_log {
+        if !args.meter_log_interval.is_finite() || args.meter_log_interval <= 0.0 {
+            anyhow::bail!("--meter-log-interval must be greater than zero");
+        }
+        app = app
+            .with_meter_log(path, Duration::from_secs_f64(args.meter_log_interval))
+            .with_context(|| format!("Failed to start meter log {:?}", path))?;
+    }
+    if let Some(path) = &args.log_loudness {
+        if !args.log_loudness_interval.is_finite() || args.log_loudness_interval <= 0.0 {
+            anyhow::bail!("--log-loudness-interval must be greater than zero");
+        }
+        app = app
+            .with_loudness_log(path, Duration::from_secs_f64(args.log_loudness_interval))
+            .with_context(|| format!("Failed to start loudness log {:?}", path))?;
+    }
+    if let Some(path) = &args.session_report {
+        app = app.with_session_report(path);
+    }
+    if args.terminal_title {
+        app = app.with_terminal_title();
+    }
+    if args.stdin_commands {
+        app = app.with_stdin_commands();
+    }
     app.run()?;
 
     log::info!("RMixer exiting");
     Ok(())
 }
 
+/// Write a config generated from the current `pactl` modules to `path`
+fn import_pulse(path: &std::path::Path) -> Result<()> {
+    if path.exists() {
+        anyhow::bail!("Refusing to overwrite existing config {:?}", path);
+    }
+    let modules = import::read_pactl_modules()?;
+    let config = import::build_config(&modules, "RMixer")?;
+    let yaml = serde_yaml::to_string(&config).context("Failed to serialize config")?;
+    std::fs::write(path, yaml).with_context(|| format!("Failed to write {:?}", path))?;
+    println!(
+        "Imported {} inputs and {} outputs into {:?}",
+        config.inputs.len(),
+        config.outputs.len(),
+        path
+    );
+    Ok(())
+}
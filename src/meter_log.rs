@@ -0,0 +1,162 @@
+//! Meter logging module for rmixer
+//!
+//! Periodically writes per-channel peak, RMS and loudness values to a CSV
+//! file so levels of a whole session can be analyzed offline.
+
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::ipc::{MeterData, MixerState, MAX_CHANNEL_PORTS};
+
+/// Accumulated meter values for one channel over a logging interval
+#[derive(Debug, Clone, Copy, Default)]
+struct ChannelAccumulator {
+    /// Highest peak seen per port
+    max_peak: [f32; MAX_CHANNEL_PORTS],
+
+    /// Sum of squared RMS values per port (mean power)
+    rms_power_sum: [f32; MAX_CHANNEL_PORTS],
+
+    /// Sum of K-weighted mean squares
+    loudness_sum: f32,
+
+    /// Number of meter blocks accumulated
+    blocks: u32,
+}
+
+/// Writes periodic meter snapshots to a CSV file
+pub struct MeterLogger {
+    /// Buffered CSV output
+    writer: BufWriter<File>,
+
+    /// Time between two rows
+    interval: Duration,
+
+    /// Session start time
+    started: Instant,
+
+    /// Time the last row was written
+    last_write: Instant,
+
+    /// Port count per channel (inputs followed by outputs)
+    port_counts: Vec<usize>,
+
+    /// Accumulators per channel (inputs followed by outputs)
+    accumulators: Vec<ChannelAccumulator>,
+}
+
+impl MeterLogger {
+    /// Create a logger writing to `path`, emitting one row every `interval`
+    pub fn create<P: AsRef<Path>>(path: P, interval: Duration, state: &MixerState) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create meter log: {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+
+        let channels = state
+            .inputs
+            .iter()
+            .map(|c| ("input", c))
+            .chain(state.outputs.iter().map(|c| ("output", c)));
+
+        let mut header = String::from("unix_time,elapsed_s");
+        let mut port_counts = Vec::new();
+        for (kind, channel) in channels {
+            let name = format!("{}.{}", kind, channel.name);
+            for port in port_suffixes(channel.port_count) {
+                for column in ["peak_db", "rms_db"] {
+                    header.push(',');
+                    header.push_str(&csv_field(&format!("{}{}.{}", name, port, column)));
+                }
+            }
+            header.push(',');
+            header.push_str(&csv_field(&format!("{}.lufs", name)));
+            port_counts.push(channel.port_count);
+        }
+        writeln!(writer, "{}", header).context("Failed to write meter log header")?;
+
+        let now = Instant::now();
+        Ok(Self {
+            writer,
+            interval,
+            started: now,
+            last_write: now,
+            accumulators: vec![ChannelAccumulator::default(); port_counts.len()],
+            port_counts,
+        })
+    }
+
+    /// Accumulate a meter update from the audio thread
+    pub fn record(&mut self, meter: &MeterData) {
+        if let Some(acc) = self.accumulators.get_mut(meter.channel_index) {
+            for i in 0..meter.port_count.min(MAX_CHANNEL_PORTS) {
+                acc.max_peak[i] = acc.max_peak[i].max(meter.peaks[i]);
+                acc.rms_power_sum[i] += meter.rms[i] * meter.rms[i];
+            }
+            acc.loudness_sum += meter.loudness_ms;
+            acc.blocks += 1;
+        }
+    }
+
+    /// Write a row if the interval has elapsed
+    pub fn tick(&mut self) -> Result<()> {
+        if self.last_write.elapsed() < self.interval {
+            return Ok(());
+        }
+        self.last_write = Instant::now();
+
+        let unix_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        let mut row = format!("{:.3},{:.3}", unix_time, self.started.elapsed().as_secs_f64());
+
+        for (acc, &port_count) in self.accumulators.iter_mut().zip(&self.port_counts) {
+            let blocks = acc.blocks.max(1) as f32;
+            for i in 0..port_count {
+                let rms = (acc.rms_power_sum[i] / blocks).sqrt();
+                row.push_str(&format!(
+                    ",{},{}",
+                    format_db(MeterData::linear_to_db(acc.max_peak[i])),
+                    format_db(MeterData::linear_to_db(rms))
+                ));
+            }
+            let lufs = crate::audio::mean_square_to_lufs(acc.loudness_sum / blocks);
+            row.push_str(&format!(",{}", format_db(lufs)));
+            *acc = ChannelAccumulator::default();
+        }
+
+        writeln!(self.writer, "{}", row).context("Failed to write meter log row")?;
+        self.writer.flush().context("Failed to flush meter log")
+    }
+}
+
+/// Column suffixes for the ports of a channel
+fn port_suffixes(port_count: usize) -> Vec<String> {
+    match port_count {
+        1 => vec![String::new()],
+        2 => vec![".L".to_string(), ".R".to_string()],
+        n => (1..=n).map(|i| format!(".{}", i)).collect(),
+    }
+}
+
+/// Format a dB value for CSV output, clamping silence to the meter floor
+pub fn format_db(db: f32) -> String {
+    if db.is_finite() {
+        format!("{:.1}", db)
+    } else {
+        format!("{:.1}", crate::ipc::VOLUME_MIN_DB)
+    }
+}
+
+/// Quote a CSV field if it contains separators or quotes
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,1367 @@
+//! Remote control socket for rmixer
+//!
+//! Exposes a line-based text protocol on a Unix socket or TCP address so
+//! scripts and remote displays can read state, control channels and
+//! subscribe to meters. Connections are served on their own threads; the
+//! UI thread polls decoded commands and pushes replies and meter frames.
+
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TrySendError};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::config::{ControlAccess, ControlAccessConfig, DbDisplay, EqBand};
+use crate::ipc::{ChannelState, Crosspoint, MeterData, MixerState, VOLUME_MIN_DB};
+
+/// Maximum number of queued outgoing lines per client before dropping
+const CLIENT_QUEUE_LINES: usize = 256;
+
+/// Default meter frame rate in Hz
+const DEFAULT_METER_RATE_HZ: f32 = 10.0;
+
+/// Highest meter frame rate a client can request
+const MAX_METER_RATE_HZ: f32 = 60.0;
+
+/// Highest feedback rate a client can request
+pub const MAX_FEEDBACK_RATE_HZ: f32 = 1000.0;
+
+/// Interval between full keyframes in compact meter mode
+const COMPACT_KEYFRAME_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Version of the line protocol
+///
+/// New commands and extra trailing fields keep the version; it is bumped
+/// when an existing command or reply changes its meaning or layout.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Features every server offers, named after their commands
+pub const CORE_FEATURES: &[&str] = &[
+    "state", "volume", "mute", "solo", "eq", "route", "meters", "tally", "loudest", "report",
+    "feedback", "topology",
+];
+
+/// Features an observer can use
+pub const OBSERVER_FEATURES: &[&str] = &["state", "meters", "tally", "feedback", "topology"];
+
+/// Channel section addressed by a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelKind {
+    Input,
+    Output,
+    Master,
+    Passthrough,
+}
+
+/// Reference to a channel by index or name
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelRef {
+    Index(usize),
+    Name(String),
+}
+
+impl ChannelRef {
+    /// Resolve against the channel list of a section
+    pub fn resolve(&self, channels: &[ChannelState]) -> Option<usize> {
+        match self {
+            ChannelRef::Index(i) if *i < channels.len() => Some(*i),
+            ChannelRef::Index(_) => None,
+            ChannelRef::Name(name) => channels.iter().position(|c| {
+                c.name.eq_ignore_ascii_case(name)
+                    || c.name.replace(' ', "_").eq_ignore_ascii_case(name)
+            }),
+        }
+    }
+}
+
+/// On/off/toggle argument of switch commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Switch {
+    On,
+    Off,
+    Toggle,
+}
+
+impl Switch {
+    /// Apply the switch to a current boolean value
+    pub fn apply(self, current: bool) -> bool {
+        match self {
+            Switch::On => true,
+            Switch::Off => false,
+            Switch::Toggle => !current,
+        }
+    }
+}
+
+/// Meter subscription mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeterMode {
+    /// No meter frames
+    Off,
+
+    /// Text dB values for every channel each frame
+    Full,
+
+    /// Delta-compressed, 1-byte quantized dB per channel
+    Compact,
+}
+
+/// A command received from a remote client
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteCommand {
+    /// Liveness check
+    Ping,
+
+    /// Handshake: agree on a protocol version (the client's highest, if
+    /// given) and list the available features
+    Hello { version: Option<u32> },
+
+    /// List available commands
+    Help,
+
+    /// Dump the state of all channels
+    State,
+
+    /// Report the channel layout
+    Topology,
+
+    /// Set (or adjust, if relative) a channel volume
+    Volume {
+        kind: ChannelKind,
+        channel: ChannelRef,
+        volume_db: f32,
+        relative: bool,
+    },
+
+    /// Change the mute state of a channel
+    Mute {
+        kind: ChannelKind,
+        channel: ChannelRef,
+        switch: Switch,
+    },
+
+    /// Change the solo state of an input channel
+    Solo { channel: ChannelRef, switch: Switch },
+
+    /// Set one EQ band of an input channel
+    Eq {
+        channel: ChannelRef,
+        band: EqBand,
+        gain_db: f32,
+    },
+
+    /// Subscribe to meter frames
+    Meters { mode: MeterMode, rate_hz: f32 },
+
+    /// Limit the writes to this client to a number per second, batching
+    /// the lines in between (None: write every line right away)
+    Feedback { rate_hz: Option<f32> },
+
+    /// Report the on-air state of the inputs once (None) or subscribe to
+    /// (Some(true)) or unsubscribe from (Some(false)) its changes
+    Tally { subscribe: Option<bool> },
+
+    /// Find (select, optionally mute) the loudest post-fader input,
+    /// optionally only counting its send to one output
+    Loudest {
+        output: Option<ChannelRef>,
+        mute: bool,
+    },
+
+    /// Save the replay buffer (optionally only the last seconds) to a WAV file
+    Replay { seconds: Option<f32> },
+
+    /// Write the session report
+    Report,
+
+    /// Start or stop the recorder
+    Record { start: bool },
+
+    /// Change the crosspoint from an input to an output
+    Route {
+        input: ChannelRef,
+        output: ChannelRef,
+        switch: Switch,
+        /// Some(true) for `mono`, Some(false) for `stereo`
+        mono: Option<bool>,
+        gain_db: Option<f32>,
+    },
+}
+
+/// Help text listing the protocol commands
+pub const HELP_TEXT: &[&str] = &[
+    "ping",
+    "hello [protocol version]",
+    "state",
+    "topology",
+    "volume <input|output|master|passthrough> <index|name> <dB>",
+    "adjust <input|output|master|passthrough> <index|name> <delta dB>",
+    "mute <input|output|master|passthrough> <index|name> [on|off|toggle]",
+    "solo <index|name> [on|off|toggle]",
+    "eq <input> <low|mid|high> <dB>",
+    "meters <off|full|compact> [rate_hz]",
+    "feedback <rate_hz|off>",
+    "tally [on|off]",
+    "route <input> <output> [on|off|toggle|mono|stereo] [gain dB]",
+    "loudest [output] [mute]",
+    "replay [seconds]",
+    "report",
+    "record <start|stop>",
+];
+
+impl RemoteCommand {
+    /// Whether the command changes the mixer (as opposed to querying it,
+    /// subscribing or writing files)
+    pub fn changes_mixer(&self) -> bool {
+        matches!(
+            self,
+            RemoteCommand::Volume { .. }
+                | RemoteCommand::Mute { .. }
+                | RemoteCommand::Solo { .. }
+                | RemoteCommand::Eq { .. }
+                | RemoteCommand::Route { .. }
+        )
+    }
+
+    /// Whether a client with `access` may send the command: observers only
+    /// read the state and subscribe
+    pub fn allowed(&self, access: ControlAccess) -> bool {
+        access == ControlAccess::Control
+            || matches!(
+                self,
+                RemoteCommand::Ping
+                    | RemoteCommand::Hello { .. }
+                    | RemoteCommand::Help
+                    | RemoteCommand::State
+                    | RemoteCommand::Topology
+                    | RemoteCommand::Meters { .. }
+                    | RemoteCommand::Feedback { .. }
+                    | RemoteCommand::Tally { .. }
+            )
+    }
+
+    /// Parse a command line
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = tokens.split_first() else {
+            return Err("empty command".to_string());
+        };
+
+        match command.to_ascii_lowercase().as_str() {
+            "ping" => Ok(RemoteCommand::Ping),
+            "hello" => match args {
+                [] => Ok(RemoteCommand::Hello { version: None }),
+                [version] => Ok(RemoteCommand::Hello {
+                    version: Some(
+                        version
+                            .parse::<u32>()
+                            .map_err(|_| format!("invalid protocol version '{}'", version))?,
+                    ),
+                }),
+                _ => Err("usage: hello [protocol version]".to_string()),
+            },
+            "help" => Ok(RemoteCommand::Help),
+            "state" | "get" => Ok(RemoteCommand::State),
+            "topology" => Ok(RemoteCommand::Topology),
+            "volume" | "vol" | "adjust" => {
+                let [kind, channel, value] = args else {
+                    return Err(format!(
+                        "usage: {} <input|output|master|passthrough> <index|name> <dB>",
+                        command
+                    ));
+                };
+                Ok(RemoteCommand::Volume {
+                    kind: parse_kind(kind)?,
+                    channel: parse_channel(channel),
+                    volume_db: parse_db(value)?,
+                    relative: command.eq_ignore_ascii_case("adjust"),
+                })
+            }
+            "mute" => match args {
+                [kind, channel] | [kind, channel, _] => Ok(RemoteCommand::Mute {
+                    kind: parse_kind(kind)?,
+                    channel: parse_channel(channel),
+                    switch: parse_switch(args.get(2))?,
+                }),
+                _ => Err(
+                    "usage: mute <input|output|master|passthrough> <index|name> [on|off|toggle]".to_string(),
+                ),
+            },
+            "solo" => match args {
+                [channel] | [channel, _] => Ok(RemoteCommand::Solo {
+                    channel: parse_channel(channel),
+                    switch: parse_switch(args.get(1))?,
+                }),
+                _ => Err("usage: solo <index|name> [on|off|toggle]".to_string()),
+            },
+            "eq" => {
+                let [channel, band, value] = args else {
+                    return Err("usage: eq <input> <low|mid|high> <dB>".to_string());
+                };
+                let band = match band.to_ascii_lowercase().as_str() {
+                    "low" | "lo" => EqBand::Low,
+                    "mid" => EqBand::Mid,
+                    "high" | "hi" => EqBand::High,
+                    other => return Err(format!("expected low, mid or high, got '{}'", other)),
+                };
+                Ok(RemoteCommand::Eq {
+                    channel: parse_channel(channel),
+                    band,
+                    gain_db: parse_db(value)?,
+                })
+            }
+            "meters" => {
+                let mode = match args.first().map(|s| s.to_ascii_lowercase()).as_deref() {
+                    Some("off") => MeterMode::Off,
+                    Some("full") | Some("on") => MeterMode::Full,
+                    Some("compact") => MeterMode::Compact,
+                    _ => return Err("usage: meters <off|full|compact> [rate_hz]".to_string()),
+                };
+                let rate_hz = match args.get(1) {
+                    Some(rate) => rate
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|r| *r > 0.0 && *r <= MAX_METER_RATE_HZ)
+                        .ok_or_else(|| format!("rate must be between 0 and {}", MAX_METER_RATE_HZ))?,
+                    None => DEFAULT_METER_RATE_HZ,
+                };
+                Ok(RemoteCommand::Meters { mode, rate_hz })
+            }
+            "feedback" => match args {
+                [rate] if rate.eq_ignore_ascii_case("off") => {
+                    Ok(RemoteCommand::Feedback { rate_hz: None })
+                }
+                [rate] => Ok(RemoteCommand::Feedback {
+                    rate_hz: Some(
+                        rate.parse::<f32>()
+                            .ok()
+                            .filter(|r| *r > 0.0 && *r <= MAX_FEEDBACK_RATE_HZ)
+                            .ok_or_else(|| {
+                                format!("rate must be between 0 and {}", MAX_FEEDBACK_RATE_HZ)
+                            })?,
+                    ),
+                }),
+                _ => Err("usage: feedback <rate_hz|off>".to_string()),
+            },
+            "tally" => match args.first().map(|s| s.to_ascii_lowercase()).as_deref() {
+                None => Ok(RemoteCommand::Tally { subscribe: None }),
+                Some("on") if args.len() == 1 => Ok(RemoteCommand::Tally {
+                    subscribe: Some(true),
+                }),
+                Some("off") if args.len() == 1 => Ok(RemoteCommand::Tally {
+                    subscribe: Some(false),
+                }),
+                _ => Err("usage: tally [on|off]".to_string()),
+            },
+            "route" => match args {
+                [input, output] | [input, output, _] | [input, output, _, _] => {
+                    let mode = args.get(2).map(|s| s.to_ascii_lowercase());
+                    let (switch, mono) = match mode.as_deref() {
+                        Some("mono") => (Switch::On, Some(true)),
+                        Some("stereo") => (Switch::On, Some(false)),
+                        _ => (parse_switch(args.get(2))?, None),
+                    };
+                    Ok(RemoteCommand::Route {
+                        input: parse_channel(input),
+                        output: parse_channel(output),
+                        switch,
+                        mono,
+                        gain_db: args.get(3).map(|v| parse_db(v)).transpose()?,
+                    })
+                }
+                _ => Err(
+                    "usage: route <input> <output> [on|off|toggle|mono|stereo] [gain dB]"
+                        .to_string(),
+                ),
+            },
+            "loudest" => {
+                let (mute, rest) = match args.split_last() {
+                    Some((last, rest)) if last.eq_ignore_ascii_case("mute") => (true, rest),
+                    _ => (false, args),
+                };
+                match rest {
+                    [] => Ok(RemoteCommand::Loudest { output: None, mute }),
+                    [output] => Ok(RemoteCommand::Loudest {
+                        output: Some(parse_channel(output)),
+                        mute,
+                    }),
+                    _ => Err("usage: loudest [output] [mute]".to_string()),
+                }
+            }
+            "replay" => match args {
+                [] => Ok(RemoteCommand::Replay { seconds: None }),
+                [seconds] => Ok(RemoteCommand::Replay {
+                    seconds: Some(
+                        seconds
+                            .parse::<f32>()
+                            .ok()
+                            .filter(|s| *s > 0.0)
+                            .ok_or_else(|| format!("invalid seconds '{}'", seconds))?,
+                    ),
+                }),
+                _ => Err("usage: replay [seconds]".to_string()),
+            },
+            "report" => Ok(RemoteCommand::Report),
+            "record" => match args {
+                [action] if action.eq_ignore_ascii_case("start") => {
+                    Ok(RemoteCommand::Record { start: true })
+                }
+                [action] if action.eq_ignore_ascii_case("stop") => {
+                    Ok(RemoteCommand::Record { start: false })
+                }
+                _ => Err("usage: record <start|stop>".to_string()),
+            },
+            other => Err(format!("unknown command '{}'", other)),
+        }
+    }
+}
+
+/// Shortest time between writes at a feedback rate (zero for no limit)
+fn feedback_interval(rate_hz: Option<f32>) -> Duration {
+    rate_hz.map_or(Duration::ZERO, |r| Duration::from_secs_f32(1.0 / r))
+}
+
+fn parse_kind(token: &str) -> Result<ChannelKind, String> {
+    match token.to_ascii_lowercase().as_str() {
+        "input" | "in" => Ok(ChannelKind::Input),
+        "output" | "out" => Ok(ChannelKind::Output),
+        "master" => Ok(ChannelKind::Master),
+        "passthrough" | "pt" => Ok(ChannelKind::Passthrough),
+        other => Err(format!(
+            "expected input, output, master or passthrough, got '{}'",
+            other
+        )),
+    }
+}
+
+fn parse_channel(token: &str) -> ChannelRef {
+    match token.parse::<usize>() {
+        Ok(index) => ChannelRef::Index(index),
+        Err(_) => ChannelRef::Name(token.to_string()),
+    }
+}
+
+fn parse_switch(token: Option<&&str>) -> Result<Switch, String> {
+    match token.map(|s| s.to_ascii_lowercase()).as_deref() {
+        None | Some("toggle") => Ok(Switch::Toggle),
+        Some("on") | Some("1") => Ok(Switch::On),
+        Some("off") | Some("0") => Ok(Switch::Off),
+        Some(other) => Err(format!("expected on, off or toggle, got '{}'", other)),
+    }
+}
+
+fn parse_db(token: &str) -> Result<f32, String> {
+    let value = token.trim_end_matches("dB").trim_end_matches("db");
+    // Readouts below the display threshold come back as -inf
+    if value == "-inf" {
+        return Ok(VOLUME_MIN_DB);
+    }
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| format!("invalid dB value '{}'", token))
+}
+
+/// Quantize a dB value to one byte (0.5 dB steps from the meter floor)
+pub fn quantize_db(db: f32) -> u8 {
+    ((db - VOLUME_MIN_DB) * 2.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// Encoder for the compact meter stream
+///
+/// Emits a keyframe (`K` followed by two hex digits per channel) every few
+/// seconds and otherwise only the channels whose quantized level changed
+/// (`D` followed by index/value hex pairs). Nothing is sent if no channel
+/// changed.
+#[derive(Debug, Default)]
+pub struct CompactMeterEncoder {
+    /// Last transmitted values
+    last: Vec<u8>,
+
+    /// Time of the last keyframe
+    last_keyframe: Option<Instant>,
+}
+
+impl CompactMeterEncoder {
+    /// Encode the given levels, returning the line to send (if any)
+    pub fn encode(&mut self, levels_db: &[f32], now: Instant) -> Option<String> {
+        let values: Vec<u8> = levels_db.iter().map(|db| quantize_db(*db)).collect();
+
+        let keyframe_due = self.last.len() != values.len()
+            || self
+                .last_keyframe
+                .is_none_or(|t| now.duration_since(t) >= COMPACT_KEYFRAME_INTERVAL);
+
+        let line = if keyframe_due {
+            self.last_keyframe = Some(now);
+            let mut line = String::with_capacity(2 + values.len() * 2);
+            line.push('K');
+            line.push(' ');
+            for v in &values {
+                line.push_str(&format!("{:02x}", v));
+            }
+            Some(line)
+        } else {
+            let mut line = String::from("D ");
+            for (i, (new, old)) in values.iter().zip(&self.last).enumerate().take(256) {
+                if new != old {
+                    line.push_str(&format!("{:02x}{:02x}", i, new));
+                }
+            }
+            (line.len() > 2).then_some(line)
+        };
+
+        self.last = values;
+        line
+    }
+}
+
+/// Meter subscription state of one client (owned by the UI thread)
+#[derive(Debug)]
+struct MeterSubscription {
+    mode: MeterMode,
+    interval: Duration,
+    last_sent: Instant,
+    /// Highest level per channel since the last frame
+    max_db: Vec<f32>,
+    encoder: CompactMeterEncoder,
+}
+
+impl Default for MeterSubscription {
+    fn default() -> Self {
+        Self {
+            mode: MeterMode::Off,
+            interval: Duration::from_secs_f32(1.0 / DEFAULT_METER_RATE_HZ),
+            last_sent: Instant::now(),
+            max_db: Vec::new(),
+            encoder: CompactMeterEncoder::default(),
+        }
+    }
+}
+
+/// A connected client as seen from the UI thread
+struct ClientHandle {
+    id: usize,
+    sender: SyncSender<String>,
+    meters: MeterSubscription,
+    /// On-air state last sent, if subscribed to tally changes
+    tally: Option<Vec<bool>>,
+    /// Channel layout last sent or current when the client connected (None
+    /// until the first publish after connecting)
+    topology: Option<String>,
+    /// Shortest time between writes in microseconds, 0 for no limit
+    /// (shared with the writer thread)
+    feedback: Arc<AtomicU64>,
+}
+
+impl ClientHandle {
+    /// Shortest time between writes
+    fn feedback_interval(&self) -> Duration {
+        Duration::from_micros(self.feedback.load(Ordering::Relaxed))
+    }
+}
+
+/// A command from a specific client
+pub struct RemoteRequest {
+    pub client: usize,
+    pub access: ControlAccess,
+    pub command: Result<RemoteCommand, String>,
+}
+
+/// What the connection threads pass to the UI thread
+enum Incoming {
+    Request(RemoteRequest),
+
+    /// The client has sent its last line; queued after its requests so their
+    /// replies still go out
+    Closed(usize),
+}
+
+/// Either kind of connected stream
+enum Stream {
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl Stream {
+    fn try_clone(&self) -> std::io::Result<Self> {
+        match self {
+            Stream::Unix(s) => s.try_clone().map(Stream::Unix),
+            Stream::Tcp(s) => s.try_clone().map(Stream::Tcp),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Stream::Unix(s) => s.read(buf),
+            Stream::Tcp(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Stream::Unix(s) => s.write(buf),
+            Stream::Tcp(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Stream::Unix(s) => s.flush(),
+            Stream::Tcp(s) => s.flush(),
+        }
+    }
+}
+
+/// Control socket server
+pub struct ControlServer {
+    /// Connected clients (shared with the acceptor thread)
+    clients: Arc<Mutex<Vec<ClientHandle>>>,
+
+    /// Commands decoded by the connection threads
+    requests: Receiver<Incoming>,
+
+    /// Sender handed to new connections
+    request_tx: Sender<Incoming>,
+
+    /// Feedback interval of new connections in microseconds, 0 for no limit
+    /// (shared with the acceptor thread)
+    feedback: Arc<AtomicU64>,
+
+    /// Unix socket path to remove on shutdown
+    socket_path: Option<PathBuf>,
+
+    /// Writer of the standard output client, joined on shutdown so its last
+    /// replies are not cut off
+    stdio_writer: Option<JoinHandle<()>>,
+}
+
+/// A server without a listener (see `start` and `attach_stdio`)
+impl Default for ControlServer {
+    fn default() -> Self {
+        let (request_tx, requests) = mpsc::channel();
+        Self {
+            clients: Arc::new(Mutex::new(Vec::new())),
+            requests,
+            request_tx,
+            feedback: Arc::new(AtomicU64::new(0)),
+            socket_path: None,
+            stdio_writer: None,
+        }
+    }
+}
+
+impl ControlServer {
+    /// Start listening on `address`: a TCP `host:port` or a Unix socket path;
+    /// TCP clients get the access `rules` give their address
+    pub fn start(address: &str, rules: Option<ControlAccessConfig>) -> Result<Self> {
+        let mut server = Self::default();
+        let clients = server.clients.clone();
+        let request_tx = server.request_tx.clone();
+        let feedback = server.feedback.clone();
+
+        server.socket_path = if let Ok(addr) = address.parse::<SocketAddr>() {
+            let listener = TcpListener::bind(addr)
+                .with_context(|| format!("Failed to listen on {}", addr))?;
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let _ = stream.set_nodelay(true);
+                    let peer = stream.peer_addr().ok().map(|a| a.ip());
+                    // A peer whose address cannot be read gets the default
+                    let access = rules.as_ref().map_or(ControlAccess::Control, |rules| {
+                        peer.map_or(rules.default, |ip| rules.access(Some(ip)))
+                    });
+                    Self::accept(Stream::Tcp(stream), &clients, &request_tx, &feedback, access);
+                }
+            });
+            log::info!("Control socket listening on tcp://{}", addr);
+            None
+        } else {
+            let path = PathBuf::from(address);
+            // Remove a stale socket left behind by a previous run
+            if path.exists() && UnixStream::connect(&path).is_err() {
+                let _ = std::fs::remove_file(&path);
+            }
+            let listener = UnixListener::bind(&path)
+                .with_context(|| format!("Failed to bind control socket {}", path.display()))?;
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let access = ControlAccess::Control;
+                    Self::accept(Stream::Unix(stream), &clients, &request_tx, &feedback, access);
+                }
+            });
+            log::info!("Control socket listening on {}", path.display());
+            Some(path)
+        };
+        Ok(server)
+    }
+
+    /// Read commands from standard input and answer on standard output, as
+    /// one more client; returns its id
+    pub fn attach_stdio(&mut self) -> Option<usize> {
+        let (id, writer) = Self::connect(
+            std::io::stdin(),
+            std::io::stdout(),
+            &self.clients,
+            &self.request_tx,
+            &self.feedback,
+            ControlAccess::Control,
+        )?;
+        self.stdio_writer = Some(writer);
+        Some(id)
+    }
+
+    /// Set the feedback rate of clients that connect from now on (None for
+    /// no limit); they can change their own with `feedback`
+    pub fn set_feedback_rate(&self, rate_hz: Option<f32>) {
+        let interval = feedback_interval(rate_hz).as_micros() as u64;
+        self.feedback.store(interval, Ordering::Relaxed);
+    }
+
+    /// Whether a client is still connected
+    pub fn is_connected(&self, client: usize) -> bool {
+        self.clients
+            .lock()
+            .is_ok_and(|clients| clients.iter().any(|c| c.id == client))
+    }
+
+    /// Register a new connection
+    fn accept(
+        stream: Stream,
+        clients: &Arc<Mutex<Vec<ClientHandle>>>,
+        request_tx: &Sender<Incoming>,
+        feedback: &AtomicU64,
+        access: ControlAccess,
+    ) {
+        if let Ok(writer) = stream.try_clone() {
+            Self::connect(stream, writer, clients, request_tx, feedback, access);
+        }
+    }
+
+    /// Register a client and spawn its reader and writer threads; returns
+    /// its id and the writer thread
+    fn connect(
+        reader: impl Read + Send + 'static,
+        mut writer: impl Write + Send + 'static,
+        clients: &Arc<Mutex<Vec<ClientHandle>>>,
+        request_tx: &Sender<Incoming>,
+        feedback: &AtomicU64,
+        access: ControlAccess,
+    ) -> Option<(usize, JoinHandle<()>)> {
+        let (sender, outgoing) = mpsc::sync_channel::<String>(CLIENT_QUEUE_LINES);
+        let feedback = Arc::new(AtomicU64::new(feedback.load(Ordering::Relaxed)));
+
+        let id = {
+            let Ok(mut clients) = clients.lock() else {
+                return None;
+            };
+            let id = clients.iter().map(|c| c.id + 1).max().unwrap_or(0);
+            clients.push(ClientHandle {
+                id,
+                sender,
+                meters: MeterSubscription::default(),
+                tally: None,
+                topology: None,
+                feedback: feedback.clone(),
+            });
+            id
+        };
+        match access {
+            ControlAccess::Control => log::info!("Control client {} connected", id),
+            ControlAccess::Observe => log::info!("Control client {} connected as observer", id),
+        }
+
+        // Writer: drains the outgoing queue; exits when the handle is dropped.
+        // Lines queued in the meantime go out in one write, and with a
+        // feedback rate it waits between writes, so a slow link (USB, Wi-Fi)
+        // gets a few larger writes instead of a stream of small ones
+        let writer = thread::spawn(move || {
+            while let Ok(line) = outgoing.recv() {
+                let written = Instant::now();
+                let mut batch = line;
+                batch.push('\n');
+                for line in outgoing.try_iter() {
+                    batch.push_str(&line);
+                    batch.push('\n');
+                }
+                if writer
+                    .write_all(batch.as_bytes())
+                    .and_then(|_| writer.flush())
+                    .is_err()
+                {
+                    break;
+                }
+                let interval = Duration::from_micros(feedback.load(Ordering::Relaxed));
+                thread::sleep(interval.saturating_sub(written.elapsed()));
+            }
+        });
+
+        // Reader: decodes command lines for the UI thread, turning commands
+        // the client may not send into errors before the UI sees them
+        let request_tx = request_tx.clone();
+        thread::spawn(move || {
+            let reader = BufReader::new(reader);
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let command = RemoteCommand::parse(line).and_then(|command| {
+                    if command.allowed(access) {
+                        Ok(command)
+                    } else {
+                        let name = line.split_whitespace().next().unwrap_or(line);
+                        Err(format!("'{}' is not allowed for observers", name))
+                    }
+                });
+                let request = RemoteRequest {
+                    client: id,
+                    access,
+                    command,
+                };
+                if request_tx.send(Incoming::Request(request)).is_err() {
+                    break;
+                }
+            }
+            let _ = request_tx.send(Incoming::Closed(id));
+        });
+        Some((id, writer))
+    }
+
+    /// Take the next pending command, if any
+    pub fn try_recv(&self) -> Option<RemoteRequest> {
+        loop {
+            match self.requests.try_recv().ok()? {
+                Incoming::Request(request) => return Some(request),
+                Incoming::Closed(id) => {
+                    // Dropping the handle ends the writer once its queue is sent
+                    if let Ok(mut clients) = self.clients.lock() {
+                        clients.retain(|c| c.id != id);
+                    }
+                    log::info!("Control client {} disconnected", id);
+                }
+            }
+        }
+    }
+
+    /// Send a line to one client
+    pub fn reply(&self, client: usize, line: impl Into<String>) {
+        if let Ok(clients) = self.clients.lock() {
+            if let Some(handle) = clients.iter().find(|c| c.id == client) {
+                let _ = handle.sender.try_send(line.into());
+            }
+        }
+    }
+
+    /// Change the meter subscription of a client
+    pub fn subscribe_meters(&self, client: usize, mode: MeterMode, rate_hz: f32) {
+        if let Ok(mut clients) = self.clients.lock() {
+            if let Some(handle) = clients.iter_mut().find(|c| c.id == client) {
+                handle.meters = MeterSubscription {
+                    mode,
+                    interval: Duration::from_secs_f32(1.0 / rate_hz),
+                    ..Default::default()
+                };
+            }
+        }
+    }
+
+    /// Change the feedback rate of a client (None for no limit)
+    pub fn set_client_feedback(&self, client: usize, rate_hz: Option<f32>) {
+        if let Ok(clients) = self.clients.lock() {
+            if let Some(handle) = clients.iter().find(|c| c.id == client) {
+                let interval = feedback_interval(rate_hz).as_micros() as u64;
+                handle.feedback.store(interval, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Subscribe a client to on-air changes, or unsubscribe it
+    pub fn subscribe_tally(&self, client: usize, subscribe: bool) {
+        if let Ok(mut clients) = self.clients.lock() {
+            if let Some(handle) = clients.iter_mut().find(|c| c.id == client) {
+                // An empty state differs from any real one: the current
+                // state is sent right away
+                handle.tally = subscribe.then(Vec::new);
+            }
+        }
+    }
+
+    /// Send the on-air state to subscribed clients that have not seen it yet
+    pub fn publish_tally(&self, on_air: &[bool]) {
+        let Ok(mut clients) = self.clients.lock() else {
+            return;
+        };
+        clients.retain_mut(|client| match &mut client.tally {
+            Some(sent) if sent.as_slice() != on_air => {
+                // Unlike meter frames a tally change must not be lost: keep
+                // it pending while the queue is full
+                match client.sender.try_send(format_tally(on_air)) {
+                    Ok(()) => {
+                        *sent = on_air.to_vec();
+                        true
+                    }
+                    Err(TrySendError::Full(_)) => true,
+                    Err(TrySendError::Disconnected(_)) => false,
+                }
+            }
+            _ => true,
+        });
+    }
+
+    /// Tell every client about a change of the channel layout (given as
+    /// its `topology` line) since it connected or was last told
+    pub fn publish_topology(&self, topology: &str) {
+        let Ok(mut clients) = self.clients.lock() else {
+            return;
+        };
+        clients.retain_mut(|client| match &client.topology {
+            // A new client reads the layout with its first commands
+            None => {
+                client.topology = Some(topology.to_string());
+                true
+            }
+            Some(sent) if sent != topology => {
+                // Remotes rebuild their layout from it: keep it pending while
+                // the queue is full, like a tally change
+                match client.sender.try_send(topology.to_string()) {
+                    Ok(()) => {
+                        client.topology = Some(topology.to_string());
+                        true
+                    }
+                    Err(TrySendError::Full(_)) => true,
+                    Err(TrySendError::Disconnected(_)) => false,
+                }
+            }
+            _ => true,
+        });
+    }
+
+    /// Feed the current meter levels and send due meter frames
+    pub fn publish_meters(&self, state: &MixerState, display: &DbDisplay) {
+        let levels: Vec<f32> = state
+            .inputs
+            .iter()
+            .chain(state.outputs.iter())
+            .map(|c| {
+                MeterData::linear_to_db(c.peak())
+            })
+            .collect();
+
+        let Ok(mut clients) = self.clients.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        clients.retain_mut(|client| {
+            let link_interval = client.feedback_interval();
+            let meters = &mut client.meters;
+            if meters.mode == MeterMode::Off {
+                return true;
+            }
+
+            // Track the maximum between frames so short peaks are not lost
+            if meters.max_db.len() != levels.len() {
+                meters.max_db = levels.clone();
+            } else {
+                for (max, level) in meters.max_db.iter_mut().zip(&levels) {
+                    *max = max.max(*level);
+                }
+            }
+
+            // No more frames than the link takes writes
+            let interval = meters.interval.max(link_interval);
+            if now.duration_since(meters.last_sent) < interval {
+                return true;
+            }
+            meters.last_sent = now;
+
+            let line = match meters.mode {
+                MeterMode::Full => Some(format!(
+                    "meters {}",
+                    meters
+                        .max_db
+                        .iter()
+                        .map(|&db| display.format_token(db))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                )),
+                MeterMode::Compact => meters.encoder.encode(&meters.max_db, now),
+                MeterMode::Off => None,
+            };
+            meters.max_db.clear();
+
+            match line {
+                // A full queue means a slow link: drop the frame, keep the client
+                Some(line) => !matches!(
+                    client.sender.try_send(line),
+                    Err(TrySendError::Disconnected(_))
+                ),
+                None => true,
+            }
+        });
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        if let Some(path) = &self.socket_path {
+            let _ = std::fs::remove_file(path);
+        }
+        if let Some(writer) = self.stdio_writer.take() {
+            if let Ok(mut clients) = self.clients.lock() {
+                clients.clear();
+            }
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Format the state of a channel as a protocol line
+pub fn format_channel_state(
+    kind: ChannelKind,
+    index: usize,
+    channel: &ChannelState,
+    display: &DbDisplay,
+) -> String {
+    let kind = match kind {
+        ChannelKind::Input => "input",
+        ChannelKind::Output => "output",
+        ChannelKind::Master => "master",
+        ChannelKind::Passthrough => "passthrough",
+    };
+    format!(
+        "channel {} {} {} volume {} muted {} soloed {}",
+        kind,
+        index,
+        channel.name.replace(' ', "_"),
+        display.format_token(channel.volume_db),
+        channel.muted as u8,
+        channel.soloed as u8
+    )
+}
+
+/// Answer a handshake: the agreed protocol version, the features on offer
+/// and the client's access, or an error if the client only speaks versions
+/// older than ours
+pub fn format_hello(
+    client_version: Option<u32>,
+    features: &[&str],
+    access: ControlAccess,
+) -> Result<String, String> {
+    let version = client_version.map_or(PROTOCOL_VERSION, |v| v.min(PROTOCOL_VERSION));
+    if version < 1 {
+        return Err(format!(
+            "protocol version {} is not supported (1-{})",
+            version, PROTOCOL_VERSION
+        ));
+    }
+    let access = match access {
+        ControlAccess::Control => "control",
+        ControlAccess::Observe => "observe",
+    };
+    Ok(format!(
+        "hello rmixer {} protocol {} features {} access {}",
+        env!("CARGO_PKG_VERSION"),
+        version,
+        features.join(","),
+        access
+    ))
+}
+
+/// Format the channel layout as a protocol line: the number of inputs,
+/// outputs and passthrough channels, then all their names in that order
+pub fn format_topology(state: &MixerState) -> String {
+    let names: Vec<String> = state
+        .inputs
+        .iter()
+        .chain(&state.outputs)
+        .chain(&state.passthrough)
+        .map(|c| c.name.replace(' ', "_"))
+        .collect();
+    let mut line = format!(
+        "topology {} {} {}",
+        state.inputs.len(),
+        state.outputs.len(),
+        state.passthrough.len()
+    );
+    for name in names {
+        line.push(' ');
+        line.push_str(&name);
+    }
+    line
+}
+
+/// Format the on-air state of the inputs as a protocol line
+pub fn format_tally(on_air: &[bool]) -> String {
+    let flags: Vec<&str> = on_air.iter().map(|&on| if on { "1" } else { "0" }).collect();
+    format!("tally {}", flags.join(" "))
+}
+
+/// Format one crosspoint as a protocol line
+pub fn format_route(
+    input: usize,
+    output: usize,
+    crosspoint: Crosspoint,
+    display: &DbDisplay,
+) -> String {
+    format!(
+        "route {} {} enabled {} gain {} mono {}",
+        input,
+        output,
+        crosspoint.enabled as u8,
+        display.format_token(crosspoint.gain_db),
+        crosspoint.mono as u8
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(RemoteCommand::parse("ping"), Ok(RemoteCommand::Ping));
+        assert_eq!(
+            RemoteCommand::parse("hello 2"),
+            Ok(RemoteCommand::Hello { version: Some(2) })
+        );
+        assert!(RemoteCommand::parse("hello two").is_err());
+        assert_eq!(
+            RemoteCommand::parse("mute output Main on"),
+            Ok(RemoteCommand::Mute {
+                kind: ChannelKind::Output,
+                channel: ChannelRef::Name("Main".to_string()),
+                switch: Switch::On,
+            })
+        );
+        assert_eq!(
+            RemoteCommand::parse("meters compact 5"),
+            Ok(RemoteCommand::Meters {
+                mode: MeterMode::Compact,
+                rate_hz: 5.0,
+            })
+        );
+        assert_eq!(
+            RemoteCommand::parse("loudest Stream mute"),
+            Ok(RemoteCommand::Loudest {
+                output: Some(ChannelRef::Name("Stream".to_string())),
+                mute: true,
+            })
+        );
+        assert_eq!(
+            RemoteCommand::parse("route Mic 1 off"),
+            Ok(RemoteCommand::Route {
+                input: ChannelRef::Name("Mic".to_string()),
+                output: ChannelRef::Index(1),
+                switch: Switch::Off,
+                mono: None,
+                gain_db: None,
+            })
+        );
+        assert_eq!(
+            RemoteCommand::parse("replay 30"),
+            Ok(RemoteCommand::Replay {
+                seconds: Some(30.0)
+            })
+        );
+        assert_eq!(
+            RemoteCommand::parse("eq Mic high -3"),
+            Ok(RemoteCommand::Eq {
+                channel: ChannelRef::Name("Mic".to_string()),
+                band: EqBand::High,
+                gain_db: -3.0,
+            })
+        );
+        assert_eq!(
+            RemoteCommand::parse("record STOP"),
+            Ok(RemoteCommand::Record { start: false })
+        );
+        assert_eq!(
+            RemoteCommand::parse("tally on"),
+            Ok(RemoteCommand::Tally {
+                subscribe: Some(true)
+            })
+        );
+        assert!(RemoteCommand::parse("tally maybe").is_err());
+        assert_eq!(
+            RemoteCommand::parse("feedback 20"),
+            Ok(RemoteCommand::Feedback { rate_hz: Some(20.0) })
+        );
+        assert_eq!(
+            RemoteCommand::parse("feedback OFF"),
+            Ok(RemoteCommand::Feedback { rate_hz: None })
+        );
+        assert!(RemoteCommand::parse("feedback 0").is_err());
+        assert_eq!(format_tally(&[true, false]), "tally 1 0");
+        assert!(RemoteCommand::parse("record").is_err());
+        assert!(RemoteCommand::parse("volume input 0 loud").is_err());
+
+        // Readouts written with the display options parse back
+        let display = DbDisplay {
+            suffix: true,
+            neg_inf_below_db: Some(-50.0),
+            ..Default::default()
+        };
+        let channel = ChannelState::new("Mic".to_string(), 1);
+        let line = format_channel_state(ChannelKind::Input, 0, &channel, &display);
+        assert_eq!(line, "channel input 0 Mic volume 0.0dB muted 0 soloed 0");
+        assert_eq!(parse_db(&display.format_token(-55.0)), Ok(VOLUME_MIN_DB));
+    }
+
+    #[test]
+    fn test_hello_negotiates_the_version() {
+        let line = format_hello(None, &["state", "replay"], ControlAccess::Control).unwrap();
+        assert!(line.starts_with("hello rmixer "));
+        assert!(line.ends_with(&format!(
+            "protocol {} features state,replay access control",
+            PROTOCOL_VERSION
+        )));
+        // A newer client falls back to our version
+        let control = ControlAccess::Control;
+        let newer = format_hello(Some(PROTOCOL_VERSION + 1), CORE_FEATURES, control).unwrap();
+        assert!(newer.contains(&format!("protocol {} ", PROTOCOL_VERSION)));
+        assert!(format_hello(Some(0), CORE_FEATURES, control).is_err());
+    }
+
+    #[test]
+    fn test_observers_cannot_change_the_mixer() {
+        let server = ControlServer::default();
+        let commands = "state\nmeters full\nvolume input 0 -6\nmute output Main\nreport\n";
+        ControlServer::connect(
+            std::io::Cursor::new(commands),
+            std::io::sink(),
+            &server.clients,
+            &server.request_tx,
+            &server.feedback,
+            ControlAccess::Observe,
+        )
+        .unwrap();
+
+        let mut results = Vec::new();
+        while results.len() < 5 {
+            match server.try_recv() {
+                Some(request) => {
+                    assert_eq!(request.access, ControlAccess::Observe);
+                    results.push(request.command);
+                }
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        assert_eq!(results[0], Ok(RemoteCommand::State));
+        assert!(matches!(results[1], Ok(RemoteCommand::Meters { .. })));
+        assert_eq!(
+            results[2],
+            Err("'volume' is not allowed for observers".to_string())
+        );
+        assert!(results[3].is_err());
+        assert!(results[4].is_err());
+
+        // The same commands are fine for a client with control
+        let volume = RemoteCommand::parse("volume input 0 -6").unwrap();
+        assert!(volume.allowed(ControlAccess::Control));
+        assert!(!volume.allowed(ControlAccess::Observe));
+    }
+
+    /// Writer recording each write separately
+    #[derive(Clone, Default)]
+    struct Writes(Arc<Mutex<Vec<String>>>);
+
+    impl Write for Writes {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let text = String::from_utf8_lossy(buf).to_string();
+            self.0.lock().unwrap().push(text);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_feedback_rate_batches_lines() {
+        let server = ControlServer::default();
+        server.set_feedback_rate(Some(10.0));
+        let writes = Writes::default();
+        let (client, _) = ControlServer::connect(
+            std::io::empty(),
+            writes.clone(),
+            &server.clients,
+            &server.request_tx,
+            &server.feedback,
+            ControlAccess::Control,
+        )
+        .unwrap();
+
+        // The first line goes out at once, the rest waits for the next write
+        server.reply(client, "one");
+        thread::sleep(Duration::from_millis(20));
+        server.reply(client, "two");
+        server.reply(client, "three");
+        thread::sleep(Duration::from_millis(40));
+        assert_eq!(*writes.0.lock().unwrap(), ["one\n"]);
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(*writes.0.lock().unwrap(), ["one\n", "two\nthree\n"]);
+    }
+
+    #[test]
+    fn test_topology_changes_are_pushed() {
+        let server = ControlServer::default();
+        let writes = Writes::default();
+        ControlServer::connect(
+            std::io::empty(),
+            writes.clone(),
+            &server.clients,
+            &server.request_tx,
+            &server.feedback,
+            ControlAccess::Control,
+        )
+        .unwrap();
+
+        let mut state = MixerState::for_test(
+            vec![ChannelState::new("Mic".to_string(), 1)],
+            vec![ChannelState::new("Main Out".to_string(), 2)],
+        );
+        let topology = format_topology(&state);
+        assert_eq!(topology, "topology 1 1 0 Mic Main_Out");
+
+        // Nothing for the layout the client connected to, one line per change
+        server.publish_topology(&topology);
+        server.publish_topology(&topology);
+        state.inputs.push(ChannelState::new("Firefox".to_string(), 2));
+        server.publish_topology(&format_topology(&state));
+        server.publish_topology(&format_topology(&state));
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(
+            writes.0.lock().unwrap().concat(),
+            "topology 2 1 0 Mic Firefox Main_Out\n"
+        );
+    }
+
+    #[test]
+    fn test_compact_encoder_sends_only_changes() {
+        let mut encoder = CompactMeterEncoder::default();
+        let now = Instant::now();
+
+        let key = encoder.encode(&[-60.0, 0.0], now).unwrap();
+        assert_eq!(key, "K 0078");
+
+        assert_eq!(encoder.encode(&[-60.0, 0.0], now), None);
+
+        let delta = encoder.encode(&[-60.0, -0.5], now).unwrap();
+        assert_eq!(delta, "D 0177");
+    }
+}
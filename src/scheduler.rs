@@ -0,0 +1,325 @@
+//! Time-of-day scheduled actions
+//!
+//! Each entry pairs a cron expression with a control socket command, e.g.
+//! fading the background music down at 22:00 or muting the master at 02:00.
+//! Times are local. The schedule is checked once per minute; a volume change
+//! can be spread over a number of seconds instead of jumping.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::ScheduleEntry;
+use crate::remote::{ChannelKind, RemoteCommand};
+
+/// Fields of a cron expression with their allowed ranges
+const CRON_FIELDS: [(&str, u32, u32); 5] = [
+    ("minute", 0, 59),
+    ("hour", 0, 23),
+    ("day of month", 1, 31),
+    ("month", 1, 12),
+    ("day of week", 0, 7),
+];
+
+/// A broken-down local time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    pub minute: u32,
+    pub hour: u32,
+    /// Day of the month (1-31)
+    pub day: u32,
+    /// Month (1-12)
+    pub month: u32,
+    /// Day of the week (0 = Sunday)
+    pub weekday: u32,
+}
+
+impl LocalTime {
+    /// Local time of a system time (seconds resolution)
+    pub fn at(time: SystemTime) -> Self {
+        let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) as libc::time_t;
+        // SAFETY: `localtime_r` only writes the `tm` we pass in
+        let tm = unsafe {
+            let mut tm: libc::tm = std::mem::zeroed();
+            libc::localtime_r(&secs, &mut tm);
+            tm
+        };
+        Self {
+            minute: tm.tm_min as u32,
+            hour: tm.tm_hour as u32,
+            day: tm.tm_mday as u32,
+            month: tm.tm_mon as u32 + 1,
+            weekday: tm.tm_wday as u32,
+        }
+    }
+}
+
+/// A parsed cron expression: `minute hour day-of-month month day-of-week`
+///
+/// Fields take `*`, values, ranges (`1-5`), lists (`0,30`) and steps
+/// (`*/15`, `8-18/2`). Sunday is 0 or 7. As in cron, when both day fields
+/// are restricted a time matches if either of them does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronSpec {
+    /// Allowed values per field as bit sets
+    fields: [u64; 5],
+
+    /// Whether the day of month and day of week fields are restricted
+    day_restricted: (bool, bool),
+}
+
+impl CronSpec {
+    /// Parse a five-field cron expression
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = text.split_whitespace().collect();
+        if parts.len() != CRON_FIELDS.len() {
+            return Err(format!(
+                "'{}' needs five fields: minute hour day-of-month month day-of-week",
+                text
+            ));
+        }
+
+        let mut fields = [0u64; 5];
+        for ((field, part), (name, min, max)) in fields.iter_mut().zip(&parts).zip(CRON_FIELDS) {
+            *field = parse_field(part, min, max).map_err(|e| format!("{}: {}", name, e))?;
+        }
+        // Sunday can be written as 7
+        if fields[4] & (1 << 7) != 0 {
+            fields[4] |= 1;
+        }
+        Ok(Self {
+            fields,
+            day_restricted: (parts[2] != "*", parts[4] != "*"),
+        })
+    }
+
+    /// Whether the expression matches a local time
+    pub fn matches(&self, time: &LocalTime) -> bool {
+        let has = |field: usize, value: u32| self.fields[field] & (1 << value) != 0;
+        let day = has(2, time.day);
+        let weekday = has(4, time.weekday);
+        let day_matches = match self.day_restricted {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        };
+        has(0, time.minute) && has(1, time.hour) && has(3, time.month) && day_matches
+    }
+}
+
+/// Parse one cron field into a bit set of the values it allows
+fn parse_field(text: &str, min: u32, max: u32) -> Result<u64, String> {
+    let number = |s: &str| {
+        s.parse::<u32>()
+            .ok()
+            .filter(|v| (min..=max).contains(v))
+            .ok_or_else(|| format!("'{}' is not a value from {} to {}", s, min, max))
+    };
+
+    let mut bits = 0u64;
+    for item in text.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("invalid step in '{}'", item)),
+            },
+            None => (item, 1),
+        };
+        let (first, last) = if range == "*" {
+            (min, max)
+        } else if let Some((first, last)) = range.split_once('-') {
+            (number(first)?, number(last)?)
+        } else {
+            let value = number(range)?;
+            // `5/10` runs from 5 to the end of the range
+            (value, if step > 1 { max } else { value })
+        };
+        if first > last {
+            return Err(format!("range '{}' runs backwards", range));
+        }
+        for value in (first..=last).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+/// A scheduled action that is due
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueAction {
+    /// The entry's command as written, for the log
+    pub text: String,
+
+    pub command: RemoteCommand,
+
+    /// Time to spread a volume change over
+    pub fade: Option<Duration>,
+}
+
+/// Runs the configured schedule
+#[derive(Debug)]
+pub struct Scheduler {
+    entries: Vec<(CronSpec, DueAction)>,
+
+    /// Minute (since the epoch) last checked
+    last_minute: Option<u64>,
+}
+
+impl Scheduler {
+    /// Create the scheduler (entries that fail to parse are skipped; the
+    /// config validation rejects them before)
+    pub fn new(entries: &[ScheduleEntry]) -> Self {
+        let entries = entries
+            .iter()
+            .filter_map(|entry| {
+                let spec = CronSpec::parse(&entry.when).ok()?;
+                let action = DueAction {
+                    text: entry.command.clone(),
+                    command: RemoteCommand::parse(&entry.command).ok()?,
+                    fade: entry.fade_seconds.map(Duration::from_secs_f32),
+                };
+                Some((spec, action))
+            })
+            .collect();
+        Self {
+            entries,
+            last_minute: None,
+        }
+    }
+
+    /// Actions due at `now`; each minute is only checked once
+    pub fn due(&mut self, now: SystemTime) -> Vec<DueAction> {
+        if self.entries.is_empty() {
+            return Vec::new();
+        }
+        let minute = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() / 60);
+        self.due_at(minute, &LocalTime::at(now))
+    }
+
+    fn due_at(&mut self, minute: u64, time: &LocalTime) -> Vec<DueAction> {
+        if self.last_minute == Some(minute) {
+            return Vec::new();
+        }
+        self.last_minute = Some(minute);
+        self.entries
+            .iter()
+            .filter(|(spec, _)| spec.matches(time))
+            .map(|(_, action)| action.clone())
+            .collect()
+    }
+}
+
+/// A volume change in progress, spread over time
+#[derive(Debug, Clone, Copy)]
+pub struct VolumeFade {
+    pub kind: ChannelKind,
+    pub index: usize,
+    from_db: f32,
+    to_db: f32,
+    start: Instant,
+    duration: Duration,
+}
+
+impl VolumeFade {
+    /// Start fading a channel from its current volume to `to_db`
+    pub fn new(
+        kind: ChannelKind,
+        index: usize,
+        from_db: f32,
+        to_db: f32,
+        duration: Duration,
+        start: Instant,
+    ) -> Self {
+        Self {
+            kind,
+            index,
+            from_db,
+            to_db,
+            start,
+            duration,
+        }
+    }
+
+    /// Volume at `now`, and whether the fade has ended
+    pub fn volume_at(&self, now: Instant) -> (f32, bool) {
+        let elapsed = now.duration_since(self.start);
+        if elapsed >= self.duration {
+            return (self.to_db, true);
+        }
+        let progress = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        (self.from_db + (self.to_db - self.from_db) * progress, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32, weekday: u32) -> LocalTime {
+        LocalTime {
+            minute,
+            hour,
+            day: 15,
+            month: 6,
+            weekday,
+        }
+    }
+
+    #[test]
+    fn test_cron_expressions() {
+        let nightly = CronSpec::parse("0 22 * * *").unwrap();
+        assert!(nightly.matches(&time(22, 0, 3)));
+        assert!(!nightly.matches(&time(22, 1, 3)));
+
+        let weekdays = CronSpec::parse("*/15 8-18/2 * * 1-5").unwrap();
+        assert!(weekdays.matches(&time(10, 45, 1)));
+        assert!(!weekdays.matches(&time(11, 45, 1)));
+        assert!(!weekdays.matches(&time(10, 45, 0)));
+
+        // Either restricted day field is enough; Sunday is also 7
+        let days = CronSpec::parse("0 2 1 * 7").unwrap();
+        assert!(days.matches(&time(2, 0, 0)));
+        assert!(days.matches(&LocalTime {
+            day: 1,
+            ..time(2, 0, 4)
+        }));
+        assert!(!days.matches(&time(2, 0, 4)));
+
+        assert!(CronSpec::parse("0 22 * *").is_err());
+        assert!(CronSpec::parse("60 * * * *").is_err());
+        assert!(CronSpec::parse("0 18-8 * * *").is_err());
+        assert!(CronSpec::parse("*/0 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_due_once_per_minute() {
+        let entries = [ScheduleEntry {
+            when: "0 2 * * *".to_string(),
+            command: "mute master 0 on".to_string(),
+            fade_seconds: None,
+        }];
+        let mut scheduler = Scheduler::new(&entries);
+        let due = scheduler.due_at(120, &time(2, 0, 1));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].text, "mute master 0 on");
+        assert!(scheduler.due_at(120, &time(2, 0, 1)).is_empty());
+        assert!(scheduler.due_at(121, &time(2, 1, 1)).is_empty());
+
+        let start = Instant::now();
+        let fade = VolumeFade::new(
+            ChannelKind::Input,
+            0,
+            0.0,
+            -30.0,
+            Duration::from_secs(30),
+            start,
+        );
+        assert_eq!(
+            fade.volume_at(start + Duration::from_secs(10)),
+            (-10.0, false)
+        );
+        assert_eq!(
+            fade.volume_at(start + Duration::from_secs(31)),
+            (-30.0, true)
+        );
+    }
+}
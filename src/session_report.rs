@@ -0,0 +1,403 @@
+//! Session report for rmixer
+//!
+//! Collects session statistics while the mixer runs (levels per channel,
+//! clip events, loud events, scene recalls, files written) and renders them as a plain
+//! text report, e.g. for a podcast producer's log.
+
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::ipc::{MeterData, MixerState};
+
+/// Clip or loud events kept in the report; later clip events are only counted
+const MAX_CLIP_EVENTS: usize = 1000;
+
+/// Level statistics for one channel
+#[derive(Debug, Clone)]
+struct ChannelStats {
+    /// Section and name, e.g. "input Mic"
+    label: String,
+
+    /// Highest peak of any port (linear)
+    max_peak: f32,
+
+    /// Sum of the mean RMS power of all meter blocks
+    rms_power_sum: f64,
+
+    /// Number of meter blocks accumulated
+    blocks: u64,
+
+    /// Clip events on this channel
+    clips: usize,
+
+    /// Whether the last block clipped (a clip event is one run of clipping blocks)
+    clipping: bool,
+}
+
+impl ChannelStats {
+    fn new(label: String) -> Self {
+        Self {
+            label,
+            max_peak: 0.0,
+            rms_power_sum: 0.0,
+            blocks: 0,
+            clips: 0,
+            clipping: false,
+        }
+    }
+}
+
+/// A channel reaching full scale
+#[derive(Debug, Clone)]
+pub struct ClipEvent {
+    /// Time since the session started
+    pub at: Duration,
+
+    /// Wall-clock time as Unix time in seconds
+    pub unix_secs: u64,
+
+    /// Channel label
+    pub channel: String,
+
+    /// Peak of the first clipping block (linear)
+    pub peak: f32,
+}
+
+/// Statistics of the running session
+pub struct SessionReport {
+    /// JACK client name
+    client_name: String,
+
+    /// Session start time
+    started: Instant,
+
+    /// Session start as Unix time in seconds
+    started_unix: u64,
+
+    /// Statistics per meter channel index
+    channels: Vec<ChannelStats>,
+
+    /// Statistics of channels removed by a config change
+    removed: Vec<ChannelStats>,
+
+    /// Recorded clip events (at most `MAX_CLIP_EVENTS`)
+    clip_events: Vec<ClipEvent>,
+
+    /// Clip events beyond `MAX_CLIP_EVENTS`
+    clips_dropped: usize,
+
+    /// Inputs dipped for a loud event with the time of the event (at most
+    /// `MAX_CLIP_EVENTS`)
+    dips: Vec<(Duration, String)>,
+
+    /// Recalled scenes with the time of recall
+    scene_recalls: Vec<(Duration, String)>,
+
+    /// Files written during the session
+    files: Vec<(Duration, PathBuf)>,
+
+    /// Xruns of audio engines that have since been replaced
+    earlier_xruns: u64,
+}
+
+impl SessionReport {
+    /// Start collecting statistics for the channels of `state`
+    pub fn new(client_name: &str, state: &MixerState) -> Self {
+        let started_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let mut report = Self {
+            client_name: client_name.to_string(),
+            started: Instant::now(),
+            started_unix,
+            channels: Vec::new(),
+            removed: Vec::new(),
+            clip_events: Vec::new(),
+            clips_dropped: 0,
+            dips: Vec::new(),
+            scene_recalls: Vec::new(),
+            files: Vec::new(),
+            earlier_xruns: 0,
+        };
+        report.set_channels(state);
+        report
+    }
+
+    /// Follow a channel layout change, keeping statistics of channels that remain
+    pub fn set_channels(&mut self, state: &MixerState) {
+        let labels = state
+            .inputs
+            .iter()
+            .map(|c| format!("input {}", c.name))
+            .chain(state.outputs.iter().map(|c| format!("output {}", c.name)))
+            .chain(std::iter::once("master".to_string()))
+            .chain(
+                state
+                    .passthrough
+                    .iter()
+                    .map(|c| format!("passthrough {}", c.name)),
+            );
+
+        let mut old = std::mem::take(&mut self.channels);
+        old.append(&mut self.removed);
+        for label in labels {
+            let stats = match old.iter().position(|s| s.label == label) {
+                Some(i) => old.remove(i),
+                None => ChannelStats::new(label),
+            };
+            self.channels.push(stats);
+        }
+        self.removed = old;
+    }
+
+    /// Accumulate a meter update from the audio thread
+    pub fn record(&mut self, meter: &MeterData) {
+        let Some(stats) = self.channels.get_mut(meter.channel_index) else {
+            return;
+        };
+        let ports = meter.port_count.clamp(1, meter.peaks.len());
+        let peak = meter.peaks[..ports].iter().copied().fold(0.0, f32::max);
+        let power = meter.rms[..ports]
+            .iter()
+            .map(|r| f64::from(r * r))
+            .sum::<f64>()
+            / ports as f64;
+
+        stats.max_peak = stats.max_peak.max(peak);
+        stats.rms_power_sum += power;
+        stats.blocks += 1;
+
+        let clipping = peak >= 1.0;
+        if clipping && !stats.clipping {
+            stats.clips += 1;
+            if self.clip_events.len() < MAX_CLIP_EVENTS {
+                let at = self.started.elapsed();
+                self.clip_events.push(ClipEvent {
+                    at,
+                    unix_secs: self.started_unix + at.as_secs(),
+                    channel: stats.label.clone(),
+                    peak,
+                });
+            } else {
+                self.clips_dropped += 1;
+            }
+        }
+        stats.clipping = clipping;
+    }
+
+    /// Recorded clip events, oldest first
+    pub fn clip_events(&self) -> &[ClipEvent] {
+        &self.clip_events
+    }
+
+    /// Clip events that were counted but not kept
+    pub fn clips_dropped(&self) -> usize {
+        self.clips_dropped
+    }
+
+    /// Note an input dipped for a loud event
+    pub fn record_dip(&mut self, input: &str) {
+        if self.dips.len() < MAX_CLIP_EVENTS {
+            self.dips
+                .push((self.started.elapsed(), format!("input {}", input)));
+        }
+    }
+
+    /// Note a recalled scene
+    pub fn record_scene(&mut self, name: &str) {
+        self.scene_recalls
+            .push((self.started.elapsed(), name.to_string()));
+    }
+
+    /// Note a file written during the session
+    pub fn record_file(&mut self, path: &Path) {
+        self.files
+            .push((self.started.elapsed(), path.to_path_buf()));
+    }
+
+    /// Add the xruns of an audio engine that is being replaced
+    pub fn add_xruns(&mut self, xruns: u64) {
+        self.earlier_xruns += xruns;
+    }
+
+    /// Render the report; `xruns` are those of the running audio engine
+    pub fn render(&self, xruns: u64) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "rmixer session report");
+        let _ = writeln!(out, "=====================");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Client:    {}", self.client_name);
+        let _ = writeln!(out, "Started:   {}", format_utc(self.started_unix));
+        let _ = writeln!(out, "Duration:  {}", format_elapsed(self.started.elapsed()));
+        let _ = writeln!(out, "Xruns:     {}", self.earlier_xruns + xruns);
+        let _ = writeln!(out);
+
+        let width = self
+            .channels
+            .iter()
+            .chain(&self.removed)
+            .map(|s| s.label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(7);
+        let _ = writeln!(out, "Levels (dBFS)");
+        let _ = writeln!(
+            out,
+            "  {:width$}  {:>8}  {:>8}  {:>5}",
+            "Channel", "Max peak", "Avg RMS", "Clips"
+        );
+        for stats in self.channels.iter().chain(&self.removed) {
+            let avg_rms = (stats.rms_power_sum / stats.blocks.max(1) as f64).sqrt() as f32;
+            let _ = writeln!(
+                out,
+                "  {:width$}  {:>8}  {:>8}  {:>5}",
+                stats.label,
+                format_db(stats.max_peak),
+                format_db(avg_rms),
+                stats.clips
+            );
+        }
+        let _ = writeln!(out);
+
+        let _ = writeln!(out, "Clip events");
+        for event in &self.clip_events {
+            let _ = writeln!(
+                out,
+                "  {}  {}  {}  {} dBFS",
+                format_elapsed(event.at),
+                format_utc(event.unix_secs),
+                event.channel,
+                format_db(event.peak)
+            );
+        }
+        if self.clips_dropped > 0 {
+            let _ = writeln!(out, "  ... and {} more", self.clips_dropped);
+        }
+        write_none_if_empty(&mut out, self.clip_events.is_empty());
+
+        let _ = writeln!(out, "Loud events");
+        for (at, channel) in &self.dips {
+            let _ = writeln!(out, "  {}  {}", format_elapsed(*at), channel);
+        }
+        write_none_if_empty(&mut out, self.dips.is_empty());
+
+        let _ = writeln!(out, "Scene recalls");
+        for (at, name) in &self.scene_recalls {
+            let _ = writeln!(out, "  {}  {}", format_elapsed(*at), name);
+        }
+        write_none_if_empty(&mut out, self.scene_recalls.is_empty());
+
+        let _ = writeln!(out, "Files");
+        for (at, path) in &self.files {
+            let _ = writeln!(out, "  {}  {}", format_elapsed(*at), path.display());
+        }
+        write_none_if_empty(&mut out, self.files.is_empty());
+        out
+    }
+
+    /// Write the report to `path`
+    pub fn write(&self, path: &Path, xruns: u64) -> Result<()> {
+        std::fs::write(path, self.render(xruns))
+            .with_context(|| format!("Failed to write session report: {}", path.display()))
+    }
+}
+
+/// Close a report section, marking it empty if nothing was listed
+fn write_none_if_empty(out: &mut String, empty: bool) {
+    if empty {
+        out.push_str("  (none)\n");
+    }
+    out.push('\n');
+}
+
+/// Format a linear level in dBFS, clamping silence to the meter floor
+pub fn format_db(level: f32) -> String {
+    let db = MeterData::linear_to_db(level);
+    if db.is_finite() && db > crate::ipc::VOLUME_MIN_DB {
+        format!("{:+.1}", db)
+    } else {
+        "-inf".to_string()
+    }
+}
+
+/// Format a duration as hh:mm:ss
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Format Unix time as a UTC date and time
+pub fn format_utc(unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let secs = unix_secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::ChannelState;
+
+    fn state() -> MixerState {
+        MixerState::for_test(
+            vec![ChannelState::new("Mic".to_string(), 1)],
+            vec![ChannelState::new("Main".to_string(), 2)],
+        )
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_utc(951_782_400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(format_utc(1_792_145_045), "2026-10-16 10:04:05 UTC");
+    }
+
+    #[test]
+    fn test_clip_runs_and_report() {
+        let mut report = SessionReport::new("Mixer", &state());
+        for peak in [0.5, 1.2, 1.1, 0.5, 1.0] {
+            report.record(&MeterData::mono(0, peak, 0.25));
+        }
+        report.record_scene("Interview");
+        report.record_dip("Mic");
+
+        let text = report.render(2);
+        assert!(text.contains("Xruns:     2"));
+        assert_eq!(report.clip_events.len(), 2);
+        assert!(text.contains("input Mic"));
+        assert!(text.contains("+1.6"));
+        let first = &report.clip_events()[0];
+        let line = format!("  00:00:00  {}  input Mic  +1.6 dBFS", format_utc(first.unix_secs));
+        assert!(text.contains(&line));
+        assert!(first.unix_secs >= report.started_unix);
+        assert!(text.contains("Interview"));
+        assert!(text.contains("Loud events\n  00:00:00  input Mic"));
+
+        // Statistics survive a layout change that keeps the channel
+        report.set_channels(&state());
+        assert_eq!(report.channels[0].clips, 2);
+    }
+}
@@ -0,0 +1,186 @@
+//! Solo logic
+//!
+//! Inputs and outputs can both be soloed. Soloing an output (a bus)
+//! auditions exactly what feeds it: every input routed to it counts as
+//! soloed, at its send level into that output, and inputs that do not
+//! reach it count as soloed out.
+//!
+//! Without a solo cue output, solos act in place: inputs that are not
+//! soloed are silenced, and while an output is soloed the outputs that are
+//! not are silenced as well, so only the soloed bus is heard. With a cue
+//! output the program is left alone and the soloed inputs are added to the
+//! cue outputs instead, after their faders.
+//!
+//! Solos add up by default. In exclusive mode soloing a channel releases
+//! every other input and output solo first, so one press moves the solo.
+//! The engine and the UI both toggle through here to stay in step.
+
+use crate::config::SoloMode;
+use crate::ipc::MixerState;
+
+/// Whether any input or output is soloed
+pub fn active(state: &MixerState) -> bool {
+    state.inputs.iter().chain(&state.outputs).any(|c| c.soloed)
+}
+
+/// Level (linear) at which a solo auditions an input: 1 when it is soloed
+/// itself, else its highest send into a soloed output (0 when not soloed)
+pub fn input_level(state: &MixerState, index: usize) -> f32 {
+    if state.inputs.get(index).is_some_and(|c| c.soloed) {
+        return 1.0;
+    }
+    state
+        .outputs
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.soloed)
+        .map(|(output, _)| state.routing.gain(index, output))
+        .fold(0.0, f32::max)
+}
+
+/// Whether an input is soloed, itself or through a soloed output it feeds
+pub fn input_soloed(state: &MixerState, index: usize) -> bool {
+    input_level(state, index) > 0.0
+}
+
+/// Whether an input is left in the program by the solos
+pub fn input_passes(state: &MixerState, index: usize) -> bool {
+    state.solo_cue || !active(state) || input_soloed(state, index)
+}
+
+/// Whether an output is left playing by the solos
+pub fn output_passes(state: &MixerState, index: usize) -> bool {
+    state.solo_cue
+        || !state.outputs.iter().any(|c| c.soloed)
+        || state.outputs.get(index).is_some_and(|c| c.soloed)
+}
+
+/// Toggle the solo of an input, releasing the others if the mode says so
+pub fn toggle_input(state: &mut MixerState, index: usize) {
+    if let Some(soloed) = state.inputs.get(index).map(|c| !c.soloed) {
+        release_for(state, soloed);
+        state.inputs[index].soloed = soloed;
+    }
+}
+
+/// Toggle the solo of an output, releasing the others if the mode says so
+pub fn toggle_output(state: &mut MixerState, index: usize) {
+    if let Some(soloed) = state.outputs.get(index).map(|c| !c.soloed) {
+        release_for(state, soloed);
+        state.outputs[index].soloed = soloed;
+    }
+}
+
+/// Release every solo before a new one in exclusive mode
+fn release_for(state: &mut MixerState, soloing: bool) {
+    if soloing && state.solo_mode == SoloMode::Exclusive {
+        for channel in state.inputs.iter_mut().chain(&mut state.outputs) {
+            channel.soloed = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::{ChannelState, Crosspoint};
+
+    /// Voice and music feed the stream, voice and talkback the headphones
+    fn state() -> MixerState {
+        let channel = |name: &str| ChannelState::new(name.to_string(), 2);
+        let mut state = MixerState::for_test(
+            vec![channel("Voice"), channel("Music"), channel("Talkback")],
+            vec![channel("Stream"), channel("Phones")],
+        );
+        let off = Crosspoint {
+            enabled: false,
+            ..Crosspoint::default()
+        };
+        let quiet = Crosspoint {
+            gain_db: -6.0,
+            ..Crosspoint::default()
+        };
+        state.routing.set(1, 0, quiet);
+        state.routing.set(1, 1, off);
+        state.routing.set(2, 0, off);
+        state
+    }
+
+    fn passing(state: &MixerState) -> (Vec<bool>, Vec<bool>) {
+        (
+            (0..3).map(|i| input_passes(state, i)).collect(),
+            (0..2).map(|o| output_passes(state, o)).collect(),
+        )
+    }
+
+    #[test]
+    fn test_no_solo_passes_everything() {
+        let state = state();
+        assert!(!active(&state));
+        assert_eq!(passing(&state), (vec![true; 3], vec![true; 2]));
+        assert_eq!(input_level(&state, 0), 0.0);
+    }
+
+    #[test]
+    fn test_input_solo_in_place() {
+        let mut state = state();
+        state.inputs[1].soloed = true;
+        assert!(active(&state));
+        assert_eq!(passing(&state), (vec![false, true, false], vec![true; 2]));
+        assert_eq!(input_level(&state, 1), 1.0);
+    }
+
+    #[test]
+    fn test_output_solo_auditions_what_feeds_it() {
+        let mut state = state();
+        state.outputs[0].soloed = true;
+        assert_eq!(
+            passing(&state),
+            (vec![true, true, false], vec![true, false])
+        );
+
+        // At the send level into the soloed output
+        assert_eq!(input_level(&state, 0), 1.0);
+        assert!((input_level(&state, 1) - 0.501).abs() < 0.001);
+        assert_eq!(input_level(&state, 2), 0.0);
+
+        // A direct solo adds to it, another output widens it
+        state.inputs[2].soloed = true;
+        assert_eq!(passing(&state), (vec![true, true, true], vec![true, false]));
+        state.inputs[2].soloed = false;
+        state.outputs[1].soloed = true;
+        assert_eq!(passing(&state), (vec![true; 3], vec![true; 2]));
+        assert!((input_level(&state, 1) - 0.501).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_cue_leaves_the_program_alone() {
+        let mut state = state();
+        state.solo_cue = true;
+        state.outputs[1].soloed = true;
+        assert_eq!(passing(&state), (vec![true; 3], vec![true; 2]));
+        let soloed: Vec<bool> = (0..3).map(|i| input_soloed(&state, i)).collect();
+        assert_eq!(soloed, [true, false, true]);
+    }
+
+    #[test]
+    fn test_exclusive_solo_moves() {
+        let mut state = state();
+        toggle_input(&mut state, 0);
+        toggle_output(&mut state, 1);
+        assert!(state.inputs[0].soloed && state.outputs[1].soloed);
+
+        state.solo_mode = SoloMode::Exclusive;
+        toggle_input(&mut state, 2);
+        let soloed = |c: &[ChannelState]| -> Vec<bool> { c.iter().map(|c| c.soloed).collect() };
+        assert_eq!(soloed(&state.inputs), vec![false, false, true]);
+        assert_eq!(soloed(&state.outputs), vec![false, false]);
+
+        // Releasing the solo leaves nothing soloed
+        toggle_input(&mut state, 2);
+        assert!(!active(&state));
+        toggle_output(&mut state, 0);
+        toggle_input(&mut state, 9);
+        assert_eq!(soloed(&state.outputs), vec![true, false]);
+    }
+}
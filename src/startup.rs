@@ -0,0 +1,257 @@
+//! Startup state
+//!
+//! Where each fader starts when several sources set it, in order of
+//! precedence: a `--set` override on the command line, then the scene named
+//! by `startup_scene`, then the volume saved in the config file, then 0 dB.
+//! Mutes start as saved, and the startup scene changes those it sets. The
+//! report keeps the origin of every fader for the log and the status line.
+
+use crate::config::{override_covers, Config};
+use crate::i18n::{trf, Msg};
+
+/// Where a startup value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    /// A `--set` override
+    Override,
+
+    /// The startup scene
+    Scene,
+
+    /// The volume saved in the config file
+    Saved,
+
+    /// Nothing set it
+    Default,
+}
+
+impl Origin {
+    fn label(self) -> &'static str {
+        match self {
+            Origin::Override => "--set",
+            Origin::Scene => "startup scene",
+            Origin::Saved => "saved",
+            Origin::Default => "default",
+        }
+    }
+
+    /// The origin of a value, most important source first
+    fn of(overridden: bool, scene: Option<f32>, saved: Option<f32>) -> (Option<f32>, Self) {
+        if overridden {
+            (saved, Origin::Override)
+        } else if scene.is_some() {
+            (scene, Origin::Scene)
+        } else if saved.is_some() {
+            (saved, Origin::Saved)
+        } else {
+            (None, Origin::Default)
+        }
+    }
+}
+
+/// Start value of one fader
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartupValue {
+    /// Channel, e.g. `input 'Mic'`
+    pub channel: String,
+
+    pub volume_db: f32,
+    pub origin: Origin,
+}
+
+/// Where the startup state came from
+#[derive(Debug, Default)]
+pub struct StartupReport {
+    /// Index of the startup scene in the config
+    pub scene: Option<usize>,
+
+    /// Every fader, inputs first and the master last
+    pub volumes: Vec<StartupValue>,
+
+    /// Whether any `--set` overrides were given
+    overrides: bool,
+}
+
+impl StartupReport {
+    /// Faders that came from `origin`
+    pub fn count(&self, origin: Origin) -> usize {
+        self.volumes.iter().filter(|v| v.origin == origin).count()
+    }
+
+    /// Write the origin of every fader to the log
+    pub fn log(&self) {
+        for value in &self.volumes {
+            log::info!(
+                "Startup: {} at {:.1} dB ({})",
+                value.channel,
+                value.volume_db,
+                value.origin.label()
+            );
+        }
+    }
+
+    /// One line for the status bar, when the startup involved more than the
+    /// saved volumes
+    pub fn summary(&self, config: &Config) -> Option<String> {
+        let (overrides, saved, default) = (
+            self.count(Origin::Override),
+            self.count(Origin::Saved),
+            self.count(Origin::Default),
+        );
+        match self.scene {
+            Some(index) => Some(trf(
+                Msg::StatusStartupScene,
+                &[
+                    &config.scenes[index].name,
+                    &self.count(Origin::Scene),
+                    &overrides,
+                    &saved,
+                    &default,
+                ],
+            )),
+            None if self.overrides => {
+                Some(trf(Msg::StatusStartup, &[&overrides, &saved, &default]))
+            }
+            None => None,
+        }
+    }
+}
+
+/// Apply the startup scene's volumes to `config` wherever no override in
+/// `overrides` sets them, and report where every fader starts
+pub fn resolve(config: &mut Config, overrides: &[String]) -> StartupReport {
+    let scene = config
+        .startup_scene
+        .as_ref()
+        .and_then(|name| config.scenes.iter().position(|s| &s.name == name));
+    let settings = scene.map(|index| config.scenes[index].clone());
+    let mut volumes = Vec::new();
+
+    for (key, kind, channels, scene_channels) in [
+        (
+            "inputs",
+            "input",
+            &mut config.inputs,
+            settings.as_ref().map(|s| &s.inputs),
+        ),
+        (
+            "outputs",
+            "output",
+            &mut config.outputs,
+            settings.as_ref().map(|s| &s.outputs),
+        ),
+    ] {
+        for (i, channel) in channels.iter_mut().enumerate() {
+            let (volume_db, origin) = Origin::of(
+                override_covers(overrides, &format!("{}[{}].volume_db", key, i)),
+                scene_channels
+                    .and_then(|s| s.get(&channel.name))
+                    .and_then(|c| c.volume_db),
+                channel.volume_db,
+            );
+            channel.volume_db = volume_db;
+            volumes.push(StartupValue {
+                channel: format!("{} '{}'", kind, channel.name),
+                volume_db: volume_db.unwrap_or(0.0),
+                origin,
+            });
+        }
+    }
+
+    for (i, passthrough) in config.passthrough.iter().enumerate() {
+        let (volume_db, origin) = Origin::of(
+            override_covers(overrides, &format!("passthrough[{}].volume_db", i)),
+            None,
+            passthrough.volume_db,
+        );
+        volumes.push(StartupValue {
+            channel: format!("passthrough '{}'", passthrough.name),
+            volume_db: volume_db.unwrap_or(0.0),
+            origin,
+        });
+    }
+
+    let (volume_db, origin) = Origin::of(
+        override_covers(overrides, "master_volume_db"),
+        settings.as_ref().and_then(|s| s.master_volume_db),
+        config.master_volume_db,
+    );
+    config.master_volume_db = volume_db;
+    volumes.push(StartupValue {
+        channel: "master".to_string(),
+        volume_db: volume_db.unwrap_or(0.0),
+        origin,
+    });
+
+    StartupReport {
+        scene,
+        volumes,
+        overrides: !overrides.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence() {
+        let yaml = r#"
+client_name: "Mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    volume_db: -6.0
+  - name: "Music"
+    ports: ["music_in"]
+    volume_db: -12.0
+  - name: "Guest"
+    ports: ["guest_in"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+master_volume_db: -3.0
+scenes:
+  - name: "Show"
+    inputs:
+      Mic: { volume_db: -20.0 }
+      Music: { volume_db: -30.0, muted: true }
+    master_volume_db: -1.0
+startup_scene: "Show"
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        // The override wins over the scene, the scene over the saved volume
+        let overrides = ["inputs[1].volume_db=-9".to_string()];
+        config.inputs[1].volume_db = Some(-9.0);
+        let report = resolve(&mut config, &overrides);
+
+        let origins: Vec<(f32, Origin)> = report
+            .volumes
+            .iter()
+            .map(|v| (v.volume_db, v.origin))
+            .collect();
+        assert_eq!(
+            origins,
+            [
+                (-20.0, Origin::Scene),
+                (-9.0, Origin::Override),
+                (0.0, Origin::Default),
+                (0.0, Origin::Default),
+                (-1.0, Origin::Scene),
+            ]
+        );
+        assert_eq!(report.scene, Some(0));
+        assert_eq!(config.inputs[0].volume_db, Some(-20.0));
+        assert_eq!(config.inputs[1].volume_db, Some(-9.0));
+        assert_eq!(config.master_volume_db, Some(-1.0));
+
+        // Without a startup scene the saved volumes stand
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.startup_scene = None;
+        let report = resolve(&mut config, &[]);
+        assert_eq!(report.count(Origin::Saved), 3);
+        assert_eq!(report.count(Origin::Default), 2);
+        assert_eq!(config.inputs[0].volume_db, Some(-6.0));
+        assert!(report.summary(&config).is_none());
+    }
+}
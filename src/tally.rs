@@ -0,0 +1,200 @@
+//! On-air tally for input channels
+//!
+//! An input is on air while it can be heard (unmuted, not silenced by another
+//! input's solo, fader above the bottom) and its source carries signal. Held
+//! peaks bridge the pauses between words, so a tally light stays lit while
+//! someone talks. Changes go to control socket subscribers and, when
+//! configured, to a serial device and to GPIO value files.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+use crate::config::{ChannelConfig, TallyConfig};
+use crate::ipc::{MeterData, MixerState};
+use crate::remote;
+
+/// Tracks which inputs are on air and drives the tally outputs
+#[derive(Debug)]
+pub struct Tally {
+    /// Source peak counting as signal (linear)
+    signal_threshold: f32,
+
+    /// On-air state per input (empty until the first update)
+    on_air: Vec<bool>,
+
+    /// Serial device path and its open handle
+    serial: Option<(String, Option<File>)>,
+
+    /// GPIO value file per input index
+    gpio: Vec<(usize, String)>,
+}
+
+impl Tally {
+    /// Create the tracker for the configured inputs (socket only without a config)
+    pub fn new(tally: Option<&TallyConfig>, inputs: &[ChannelConfig]) -> Self {
+        let gpio = tally
+            .map(|t| {
+                t.gpio
+                    .iter()
+                    .filter_map(|(name, path)| {
+                        let index = inputs.iter().position(|i| &i.name == name)?;
+                        Some((index, path.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            signal_threshold: MeterData::db_to_linear(
+                tally.map_or(-50.0, |t| t.signal_threshold_db),
+            ),
+            on_air: Vec::new(),
+            serial: tally
+                .and_then(|t| t.serial.clone())
+                .map(|path| (path, None)),
+            gpio,
+        }
+    }
+
+    /// On-air state per input as of the last update
+    pub fn on_air(&self) -> &[bool] {
+        &self.on_air
+    }
+
+    /// Sample the mixer state, writing the tally outputs if anything changed
+    pub fn update(&mut self, state: &MixerState) {
+        let on_air: Vec<bool> = (0..state.inputs.len())
+            .map(|i| {
+                state.get_input_effective_gain(i) > 0.0
+                    && state.inputs[i].held_peak() >= self.signal_threshold
+            })
+            .collect();
+        if on_air == self.on_air {
+            return;
+        }
+        self.on_air = on_air;
+        self.write_serial();
+        self.write_gpio();
+    }
+
+    /// Send the tally line to the serial device, opening it on first use
+    fn write_serial(&mut self) {
+        let Some((path, file)) = self.serial.as_mut() else {
+            return;
+        };
+        let line = remote::format_tally(&self.on_air);
+
+        let result = match file {
+            Some(file) => writeln!(file, "{}", line),
+            None => OpenOptions::new()
+                .write(true)
+                .open(&*path)
+                .and_then(|mut opened| {
+                    writeln!(opened, "{}", line)?;
+                    *file = Some(opened);
+                    Ok(())
+                }),
+        };
+        if let Err(e) = result {
+            log::error!("Tally output to {} stopped: {}", path, e);
+            self.serial = None;
+        }
+    }
+
+    /// Write each GPIO value file
+    fn write_gpio(&mut self) {
+        let on_air = &self.on_air;
+        self.gpio.retain(|(input, path)| {
+            let value = if on_air.get(*input).copied().unwrap_or(false) {
+                "1"
+            } else {
+                "0"
+            };
+            match std::fs::write(path, value) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::error!("Tally output to {} stopped: {}", path, e);
+                    false
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::{ChannelState, MAX_CHANNEL_PORTS};
+    use std::collections::BTreeMap;
+
+    fn inputs() -> Vec<ChannelConfig> {
+        vec![
+            ChannelConfig {
+                name: "Mic".to_string(),
+                ports: vec!["mic".to_string()],
+                ..Default::default()
+            },
+            ChannelConfig {
+                name: "Guest".to_string(),
+                ports: vec!["guest".to_string()],
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn config(dir: &std::path::Path) -> TallyConfig {
+        TallyConfig {
+            signal_threshold_db: -50.0,
+            serial: Some(dir.join("serial").to_string_lossy().into_owned()),
+            gpio: BTreeMap::from([(
+                "Guest".to_string(),
+                dir.join("gpio").to_string_lossy().into_owned(),
+            )]),
+        }
+    }
+
+    fn state() -> MixerState {
+        MixerState::for_test(
+            vec![
+                ChannelState::new("Mic".to_string(), 1),
+                ChannelState::new("Guest".to_string(), 1),
+            ],
+            vec![ChannelState::new("Main".to_string(), 2)],
+        )
+    }
+
+    #[test]
+    fn test_on_air_needs_signal_and_an_open_channel() {
+        let dir = std::env::temp_dir().join(format!("rmixer-tally-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("serial"), "").unwrap();
+        let mut tally = Tally::new(Some(&config(&dir)), &inputs());
+        let mut state = state();
+
+        tally.update(&state);
+        assert_eq!(tally.on_air(), [false, false]);
+
+        state.inputs[0].peak_hold = [0.1; MAX_CHANNEL_PORTS];
+        state.inputs[1].peak_hold = [0.1; MAX_CHANNEL_PORTS];
+        tally.update(&state);
+        assert_eq!(tally.on_air(), [true, true]);
+        assert_eq!(std::fs::read_to_string(dir.join("gpio")).unwrap(), "1");
+
+        // Muted, or silenced by another input's solo
+        state.inputs[0].muted = true;
+        state.inputs[1].soloed = true;
+        tally.update(&state);
+        assert_eq!(tally.on_air(), [false, true]);
+        state.inputs[0].muted = false;
+        tally.update(&state);
+        assert_eq!(tally.on_air(), [false, true]);
+
+        // Unchanged states write nothing
+        tally.update(&state);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("serial")).unwrap(),
+            "tally 0 0\ntally 1 1\ntally 0 1\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
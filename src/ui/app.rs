@@ -2,42 +2,193 @@
 //!
 //! Manages the TUI application lifecycle and rendering.
 
+use std::collections::VecDeque;
 use std::io::{self, Stdout};
-use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::SetTitle,
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph},
     Frame, Terminal,
 };
 
-use crate::audio::AudioEngine;
-use crate::config::Config;
-use crate::ipc::{ChannelState, ControlMsg, MixerState, VOLUME_STEP_DB};
+use crate::app_streams;
+use crate::audio::{
+    export_recording, local_port_for_entry, AudioEngine, EngineHealth, PortStatus, Side,
+    Substitution, SILENT_PORT_TIMEOUT,
+};
+use crate::config::{
+    ChannelConfig, Config, ControlAccess, EqBand, EqBandConfig, EqConfig, MultibandConfig,
+    PassFilter, PassFilterConfig, TapPoint, AUTOMIX_WEIGHT_RANGE_DB, DEVICE_GAIN_MAX_DB,
+    EQ_RANGE_DB, INPUT_TRIM_RANGE_DB, MID_SIDE_RANGE_DB,
+};
+use crate::device_gain::{DeviceEvent, DeviceGains};
+use crate::device_meter::{DeviceMeterEvent, DeviceMeters};
+use crate::i18n::{tr, trf, Msg};
+use crate::ipc::{
+    ChannelState, ControlMsg, Crosspoint, MeterData, MixerState, ProgramChange, AnalyzerSource,
+    CONTROL_LATENCY_LIMIT, MAX_NOTCHES, SCOPE_POINTS, VOLUME_MAX_DB, VOLUME_MIN_DB,
+    VOLUME_STEP_DB,
+};
+use crate::loudness_log::LoudnessLogger;
+use crate::meter_log::MeterLogger;
+use crate::session_report::{format_db, format_elapsed, format_utc, SessionReport};
+use crate::scheduler::{Scheduler, VolumeFade};
+use crate::tally::Tally;
+use crate::remote::{self, ChannelKind, ChannelRef, ControlServer, RemoteCommand};
+use crate::solo;
+use crate::startup::StartupReport;
+
+use super::balance::{BalanceAction, BalanceView};
+use super::editor::{ConfigEditor, EditorAction};
+use super::calibration::{CalibrationAction, CalibrationView};
+use super::eq::{EqAction, EqView};
+use super::feedback::{FeedbackAction, FeedbackView};
+use super::loudness::LoudnessHistory;
+use super::mode::{FlipLayer, Mode, Overlay};
+use super::multiband::{MultibandAction, MultibandView};
+use super::matrix::{MatrixAction, MatrixView};
+use super::reminders::{Reminder, Reminders};
+use super::oscilloscope::{OscilloscopeAction, OscilloscopeView};
+use super::rta::{RtaAction, RtaView};
+use super::spectrogram::{SpectrogramAction, SpectrogramView};
+use super::suspend::{self, SuspendSignals};
+use super::tap_tempo::TapTempo;
+use super::widgets::{BigMeter, ChannelStrip, Goniometer, HorizontalMeter};
+
+
+/// Peak hold duration in seconds unless the config sets one
+const PEAK_HOLD_DURATION: f32 = 5.0;
 
-use super::widgets::ChannelStrip;
+/// Width of the master strip
+const MASTER_WIDTH: u16 = 10;
 
+/// Narrowest terminal that shows the loudness pane next to the strips
+const SPLIT_MIN_WIDTH: u16 = 100;
 
-/// Peak hold duration in seconds
-const PEAK_HOLD_DURATION: f32 = 5.0;
+/// Smallest terminal (columns, rows) the full layout is drawn in; below
+/// it only the selected channel is shown
+const MIN_FULL_SIZE: (u16, u16) = (40, 16);
 
 /// Target frame rate
 const TARGET_FPS: u64 = 60;
 
+/// Level of the analyzer's pink noise in dBFS when no calibration is configured
+const RTA_NOISE_DB: f32 = -20.0;
+
+/// How long status messages stay visible
+const STATUS_DURATION: Duration = Duration::from_secs(5);
+
+/// Maximum gap between volume key presses to treat them as key repeat
+const KEY_REPEAT_GAP: Duration = Duration::from_millis(100);
+
+/// EQ band change per key press in dB
+const EQ_STEP_DB: f32 = 1.0;
+
+/// Input trim change per key press in dB
+const TRIM_STEP_DB: f32 = 0.5;
+
+/// Mid or side level change per key press in dB
+const MID_SIDE_STEP_DB: f32 = 1.0;
+
+/// Time an input gets to fade out before its source ports are swapped
+const SOURCE_SWAP_FADE: Duration = Duration::from_millis(20);
+
+/// How often the application ports are checked for new or gone streams
+const APP_STREAM_POLL: Duration = Duration::from_secs(2);
+
+/// Goniometer points kept on screen (16 blocks' worth)
+const GONIOMETER_TRAIL: usize = 16 * SCOPE_POINTS;
+
+/// Accelerated volume steps: (held for at least, step in dB)
+const VOLUME_ACCEL_STEPS: [(Duration, f32); 2] = [
+    (Duration::from_millis(1500), 3.0),
+    (Duration::from_millis(500), 1.0),
+];
+
 /// Selection type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SelectionType {
     Input,
     Output,
+    Master,
+    Passthrough,
+}
+
+impl From<ChannelKind> for SelectionType {
+    fn from(kind: ChannelKind) -> Self {
+        match kind {
+            ChannelKind::Input => SelectionType::Input,
+            ChannelKind::Output => SelectionType::Output,
+            ChannelKind::Master => SelectionType::Master,
+            ChannelKind::Passthrough => SelectionType::Passthrough,
+        }
+    }
+}
+
+/// Source change of an input waiting for the input to fade out
+#[derive(Debug, Clone, Copy)]
+struct SourceSwap {
+    /// Input channel index
+    channel: usize,
+
+    /// Source to connect: 0 for `connect`, then the alternates in order
+    source: usize,
+
+    /// When the fade out started
+    since: Instant,
+}
+
+/// Tracks a held volume key for step acceleration
+#[derive(Debug, Clone, Copy)]
+struct VolumeKeyRepeat {
+    /// Key being repeated
+    key: KeyCode,
+
+    /// When the key started repeating
+    held_since: Instant,
+
+    /// Time of the most recent press
+    last_press: Instant,
+}
+
+impl VolumeKeyRepeat {
+    /// Register a press and return how long the key has been held
+    fn press(previous: Option<Self>, key: KeyCode) -> Self {
+        let now = Instant::now();
+        match previous {
+            Some(r) if r.key == key && now.duration_since(r.last_press) <= KEY_REPEAT_GAP => Self {
+                last_press: now,
+                ..r
+            },
+            _ => Self {
+                key,
+                held_since: now,
+                last_press: now,
+            },
+        }
+    }
+
+    /// Volume step for the current hold duration
+    fn step_db(&self) -> f32 {
+        let held = self.last_press.duration_since(self.held_since);
+        VOLUME_ACCEL_STEPS
+            .iter()
+            .find(|(after, _)| held >= *after)
+            .map(|(_, step)| *step)
+            .unwrap_or(VOLUME_STEP_DB)
+    }
 }
 
 /// Main application state
@@ -65,6 +216,94 @@ pub struct App {
     
     /// Configuration (for saving volumes on exit)
     config: Config,
+
+    /// Optional CSV meter logger
+    meter_logger: Option<MeterLogger>,
+
+    /// Optional CSV loudness logger of the outputs
+    loudness_logger: Option<LoudnessLogger>,
+
+    /// Statistics for the session report
+    session_report: SessionReport,
+
+    /// Where the session report is written on exit, if anywhere
+    session_report_path: Option<PathBuf>,
+
+    /// File the take in progress is exported to, while recording
+    recording_path: Option<PathBuf>,
+
+    /// Exports of finished takes still running, with their target files
+    exports: Vec<(PathBuf, JoinHandle<Result<()>>)>,
+
+    /// Mode owning the keyboard, with the state of its view
+    mode: Mode,
+
+    /// Repeat tracking for the volume keys
+    volume_repeat: Option<VolumeKeyRepeat>,
+
+    /// Remote control socket, if enabled
+    control_server: Option<ControlServer>,
+
+    /// Control client reading standard input, when running without the UI
+    stdin_client: Option<usize>,
+
+    /// Substitute connections for missing devices awaiting confirmation
+    pending_substitutions: Vec<Substitution>,
+
+    /// Transient status message and when it was set
+    status: Option<(String, Instant)>,
+
+    /// Timers for the solo and mute reminders
+    reminders: Reminders,
+
+    /// On-air state of the inputs and the tally outputs
+    tally: Tally,
+
+    /// Time-of-day actions from the config
+    scheduler: Scheduler,
+
+    /// When the application ports were last checked
+    app_streams_checked: Instant,
+
+    /// Worker setting the capture device gains (None if no input has one)
+    device_gains: Option<DeviceGains>,
+
+    /// Recorders of the capture device peaks (None if no input has a device
+    /// meter)
+    device_meters: Option<DeviceMeters>,
+
+    /// Scheduled volume changes in progress
+    volume_fades: Vec<VolumeFade>,
+
+    /// Terminal title last set, if the title shows the mixer state
+    terminal_title: Option<String>,
+
+    /// Whether the loudness history is shown next to the strips
+    split_view: bool,
+
+    /// Recent left/right points of the output in the goniometer overlay
+    goniometer: VecDeque<[f32; 2]>,
+
+    /// Whether the metronome click is running
+    click_running: bool,
+
+    /// Taps of the tap tempo key
+    tap_tempo: TapTempo,
+
+    /// Whether the keys go to the loudness pane rather than the strips
+    analysis_focus: bool,
+
+    /// Channel shown in the loudness pane, in meter order
+    analysis_channel: usize,
+
+    /// Loudness over time for the loudness pane
+    loudness: LoudnessHistory,
+
+    /// Connected source per input: 0 for `connect`, then the alternates
+    active_sources: Vec<usize>,
+
+    /// Source changes waiting for their input to fade out
+    source_swaps: Vec<SourceSwap>,
 }
 
 impl App {
@@ -73,54 +312,20 @@ impl App {
         let client_name = config.client_name.clone();
 
         // Initialize channel states with saved volumes
-        let inputs: Vec<ChannelState> = config
-            .inputs
-            .iter()
-            .map(|c| {
-                let mut state = ChannelState::new(c.name.clone(), c.port_count());
-                if let Some(vol) = c.volume_db {
-                    state.volume_db = vol.clamp(-60.0, 12.0);
-                }
-                state
-            })
-            .collect();
-
-        let outputs: Vec<ChannelState> = config
-            .outputs
-            .iter()
-            .map(|c| {
-                let mut state = ChannelState::new(c.name.clone(), c.port_count());
-                if let Some(vol) = c.volume_db {
-                    state.volume_db = vol.clamp(-60.0, 12.0);
-                }
-                state
-            })
-            .collect();
-
-        let mixer_state = MixerState { inputs, outputs };
+        let mixer_state = MixerState::from_config(&config);
 
         // Create audio engine
-        let mut audio_engine = AudioEngine::new(config.clone())?;
-        
-        // Send initial volume levels to audio thread
-        for (i, c) in config.inputs.iter().enumerate() {
-            if let Some(vol) = c.volume_db {
-                let _ = audio_engine.send_control(ControlMsg::SetInputVolume {
-                    channel: i,
-                    volume_db: vol.clamp(-60.0, 12.0),
-                });
-            }
-        }
-        for (i, c) in config.outputs.iter().enumerate() {
-            if let Some(vol) = c.volume_db {
-                let _ = audio_engine.send_control(ControlMsg::SetOutputVolume {
-                    channel: i,
-                    volume_db: vol.clamp(-60.0, 12.0),
-                });
-            }
-        }
-
-        Ok(Self {
+        let audio_engine = AudioEngine::new(config.clone())?;
+
+        let session_report = SessionReport::new(&client_name, &mixer_state);
+        let reminders = Reminders::new(config.reminders.as_ref(), config.inputs.len());
+        let tally = Tally::new(config.tally.as_ref(), &config.inputs);
+        let scheduler = Scheduler::new(&config.schedule);
+        let loudness = LoudnessHistory::new(Self::analysis_channels(&config), Instant::now());
+        let active_sources = vec![0; config.inputs.len()];
+        let device_gains = DeviceGains::start(&config);
+        let device_meters = DeviceMeters::start(&config);
+        let mut app = Self {
             audio_engine,
             mixer_state,
             selected_channel: 0,
@@ -129,67 +334,442 @@ impl App {
             last_frame: Instant::now(),
             client_name,
             config,
-        })
+            meter_logger: None,
+            loudness_logger: None,
+            session_report,
+            session_report_path: None,
+            recording_path: None,
+            exports: Vec::new(),
+            mode: Mode::Normal,
+            volume_repeat: None,
+            control_server: None,
+            stdin_client: None,
+            pending_substitutions: Vec::new(),
+            status: None,
+            reminders,
+            tally,
+            scheduler,
+            app_streams_checked: Instant::now(),
+            device_gains,
+            device_meters,
+            volume_fades: Vec::new(),
+            terminal_title: None,
+            split_view: false,
+            goniometer: VecDeque::with_capacity(GONIOMETER_TRAIL),
+            click_running: false,
+            tap_tempo: TapTempo::default(),
+            analysis_focus: false,
+            analysis_channel: 0,
+            loudness,
+            active_sources,
+            source_swaps: Vec::new(),
+        };
+        app.check_capacity();
+        app.auto_connect();
+        Ok(app)
+    }
+
+    /// Warn when the config likely cannot keep up at the server's buffer size
+    fn check_capacity(&mut self) {
+        let capacity = self.audio_engine.capacity();
+        if !capacity.is_over_budget() {
+            return;
+        }
+        let load = format!("{:.0}", capacity.load() * 100.0);
+        let sample_rate = self.audio_engine.sample_rate() as usize;
+        self.set_status(match capacity.suggested_buffer_size(&self.config, sample_rate) {
+            Some(frames) => trf(Msg::StatusOverBudget, &[&capacity.buffer_size, &load, &frames]),
+            None => trf(Msg::StatusOverBudgetAny, &[&load]),
+        });
+    }
+
+    /// Make the configured connections, queueing substitutes for missing devices
+    fn auto_connect(&mut self) {
+        let report = self.audio_engine.auto_connect(&self.config);
+
+        if !report.unresolved.is_empty() {
+            for unresolved in &report.unresolved {
+                log::warn!(
+                    "'{}' not connected: '{}' is missing and has no substitute",
+                    unresolved.local_port,
+                    unresolved.missing
+                );
+            }
+            self.set_status(trf(
+                Msg::StatusConnectionsMissing,
+                &[&report.unresolved.len()],
+            ));
+        }
+
+        self.pending_substitutions = report.substitutions;
+    }
+
+    /// Handle a key while a substitute connection awaits confirmation
+    fn handle_substitution_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                let substitution = self.pending_substitutions.remove(0);
+                self.accept_substitution(substitution);
+            }
+            KeyCode::Char('a') => {
+                for substitution in std::mem::take(&mut self.pending_substitutions) {
+                    self.accept_substitution(substitution);
+                }
+            }
+            KeyCode::Char('n') => {
+                let skipped = self.pending_substitutions.remove(0);
+                self.set_status(trf(Msg::StatusPortUnrouted, &[&skipped.local_port]));
+            }
+            KeyCode::Esc => {
+                let skipped = std::mem::take(&mut self.pending_substitutions);
+                self.set_status(trf(Msg::StatusConnectionsUnrouted, &[&skipped.len()]));
+            }
+            _ => {}
+        }
+    }
+
+    /// Connect a confirmed substitute and remember it in the config
+    fn accept_substitution(&mut self, substitution: Substitution) {
+        match self.audio_engine.connect_port(
+            substitution.side,
+            &substitution.local_port,
+            &substitution.replacement,
+        ) {
+            Ok(()) => {
+                let channels = match substitution.side {
+                    Side::Input => &mut self.config.inputs,
+                    Side::Output => &mut self.config.outputs,
+                };
+                if let Some(target) = channels
+                    .get_mut(substitution.channel)
+                    .and_then(|c| c.connect.get_mut(substitution.entry))
+                {
+                    *target = substitution.replacement;
+                }
+            }
+            Err(e) => self.set_status(format!("{:#}", e)),
+        }
+    }
+
+    /// Show a transient message in the title bar
+    fn set_status(&mut self, message: impl Into<String>) {
+        self.status = Some((message.into(), Instant::now()));
+    }
+
+    /// Replace the running configuration, restarting the audio engine
+    fn apply_config(&mut self, mut config: Config) -> Result<()> {
+        config.config_path = self.config.config_path.clone();
+
+        // The recorder goes with the engine; keep what was recorded
+        if self.recording_path.is_some() {
+            if let Err(e) = self.stop_recording() {
+                log::error!("Recording lost: {:#}", e);
+            }
+        }
+
+        // The old client must be gone before the new one can take its name
+        self.audio_engine.stop();
+        self.session_report.add_xruns(self.audio_engine.xrun_count());
+
+        match AudioEngine::new(config.clone()) {
+            Ok(engine) => self.audio_engine = engine,
+            Err(e) => {
+                // Bring the previous configuration back up
+                self.audio_engine = AudioEngine::new(self.config.clone())?;
+                return Err(e);
+            }
+        }
+
+        self.mixer_state = MixerState::from_config(&config);
+        self.session_report.set_channels(&self.mixer_state);
+        self.reminders = Reminders::new(config.reminders.as_ref(), config.inputs.len());
+        self.tally = Tally::new(config.tally.as_ref(), &config.inputs);
+        self.scheduler = Scheduler::new(&config.schedule);
+        self.volume_fades.clear();
+        self.loudness = LoudnessHistory::new(Self::analysis_channels(&config), Instant::now());
+        self.analysis_channel = 0;
+        self.active_sources = vec![0; config.inputs.len()];
+        self.source_swaps.clear();
+        self.device_gains = DeviceGains::start(&config);
+        self.device_meters = DeviceMeters::start(&config);
+        self.click_running = false;
+        if matches!(
+            self.mode,
+            Mode::Calibration(_)
+                | Mode::Balance(_)
+                | Mode::Flip(_)
+                | Mode::Spectrogram(_)
+                | Mode::Oscilloscope(_)
+                | Mode::Rta(_)
+                | Mode::Feedback(_)
+        ) {
+            self.mode = Mode::Normal;
+        }
+        self.client_name = config.client_name.clone();
+        self.selected_channel = 0;
+        self.selection_type = SelectionType::Input;
+        self.config = config;
+        self.check_capacity();
+        self.auto_connect();
+
+        if self.meter_logger.take().is_some() {
+            log::warn!("Meter logging stopped: channel layout changed");
+        }
+        if self.loudness_logger.take().is_some() {
+            log::warn!("Loudness logging stopped: channel layout changed");
+        }
+
+        self.config.save()
+    }
+
+    /// Apply the mutes of the startup scene (its volumes are in the config
+    /// already) and show where the startup values came from
+    pub fn with_startup_report(mut self, report: &StartupReport) -> Result<Self> {
+        if let Some(index) = report.scene {
+            let scene = self.config.scenes[index].clone();
+            for (kind, settings) in [
+                (SelectionType::Input, &scene.inputs),
+                (SelectionType::Output, &scene.outputs),
+            ] {
+                for (name, channel) in settings {
+                    let index = self.channels(kind).iter().position(|c| &c.name == name);
+                    let (Some(i), Some(muted)) = (index, channel.muted) else {
+                        continue;
+                    };
+                    if let Err(e) = self.set_mute(kind, i, muted) {
+                        // The engine never got the toggle, so the strip
+                        // stays as the engine has it
+                        if let Some(channel) = self.channel_mut(kind, i) {
+                            channel.muted = !muted;
+                        }
+                        log::warn!("Startup scene mute of '{}' not applied: {:#}", name, e);
+                    }
+                }
+            }
+            self.session_report.record_scene(&scene.name);
+        }
+        if let Some(summary) = report.summary(&self.config) {
+            self.set_status(summary);
+        }
+        Ok(self)
+    }
+
+    /// Log meter values to a CSV file every `interval`
+    pub fn with_meter_log<P: AsRef<Path>>(mut self, path: P, interval: Duration) -> Result<Self> {
+        self.meter_logger = Some(MeterLogger::create(&path, interval, &self.mixer_state)?);
+        self.session_report.record_file(path.as_ref());
+        Ok(self)
+    }
+
+    /// Log the loudness of the outputs to a CSV file every `interval`
+    pub fn with_loudness_log<P: AsRef<Path>>(
+        mut self,
+        path: P,
+        interval: Duration,
+    ) -> Result<Self> {
+        let logger = LoudnessLogger::create(&path, interval, &self.mixer_state)?;
+        self.loudness_logger = Some(logger);
+        self.session_report.record_file(path.as_ref());
+        Ok(self)
+    }
+
+    /// Write a session report to `path` on exit (and on demand)
+    pub fn with_session_report<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.session_report_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Serve the remote control protocol on `address` (TCP host:port or Unix socket path)
+    pub fn with_control_socket(mut self, address: &str) -> Result<Self> {
+        let server = ControlServer::start(address, self.config.control_access.clone())?;
+        server.set_feedback_rate(self.config.control_feedback_hz);
+        self.control_server = Some(server);
+        Ok(self)
+    }
+
+    /// Show a compact mixer status in the terminal (and tmux pane) title
+    pub fn with_terminal_title(mut self) -> Self {
+        self.terminal_title = Some(String::new());
+        self
+    }
+
+    /// Run without the UI, taking control socket commands from standard
+    /// input and answering on standard output until it is closed
+    pub fn with_stdin_commands(mut self) -> Self {
+        let server = self.control_server.get_or_insert_with(|| {
+            let server = ControlServer::default();
+            server.set_feedback_rate(self.config.control_feedback_hz);
+            server
+        });
+        self.stdin_client = server.attach_stdio();
+        self
     }
 
     /// Run the main application loop
     pub fn run(mut self) -> Result<()> {
-        // Setup terminal
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
-
-        let result = self.main_loop(&mut terminal);
-
-        // Restore terminal
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
+        let result = if self.stdin_client.is_some() {
+            self.command_loop();
+            Ok(())
+        } else {
+            let signals = SuspendSignals::register()?;
+
+            // Setup terminal
+            let backend = CrosstermBackend::new(io::stdout());
+            let mut terminal = Terminal::new(backend)?;
+            suspend::enter_ui(&mut terminal)?;
+            self.push_terminal_title(&mut terminal)?;
+
+            let result = self.main_loop(&mut terminal, &signals);
+
+            // Restore terminal
+            suspend::leave_ui(&mut terminal)?;
+            self.pop_terminal_title(&mut terminal)?;
+            result
+        };
 
         // Save volumes to config
         self.save_volumes();
 
+        // Finish the take in progress and wait for exports
+        if self.recording_path.is_some() {
+            if let Err(e) = self.stop_recording() {
+                eprintln!("Warning: {:#}", e);
+            }
+        }
+        if !self.exports.is_empty() {
+            eprintln!("Exporting recordings...");
+            while !self.exports.is_empty() {
+                thread::sleep(Duration::from_millis(100));
+                self.poll_exports();
+            }
+            if let Some((message, _)) = &self.status {
+                eprintln!("{}", message);
+            }
+        }
+
+        if self.session_report_path.is_some() {
+            if let Err(e) = self.write_session_report() {
+                eprintln!("Warning: {:#}", e);
+            }
+        }
+
         // Stop audio engine
         self.audio_engine.quit();
 
+        // Send the last replies before exiting
+        self.control_server = None;
+
         result
     }
     
     /// Save current volume levels to config file
     fn save_volumes(&mut self) {
-        let input_volumes: Vec<f32> = self.mixer_state.inputs.iter().map(|c| c.volume_db).collect();
-        let output_volumes: Vec<f32> = self.mixer_state.outputs.iter().map(|c| c.volume_db).collect();
-        
-        self.config.update_volumes(&input_volumes, &output_volumes);
-        
+        self.store_state();
         if let Err(e) = self.config.save() {
             eprintln!("Warning: Failed to save config: {}", e);
         }
     }
 
+    /// Copy the live levels, routing and settings into the config
+    fn store_state(&mut self) {
+        let input_volumes: Vec<f32> = self.mixer_state.inputs.iter().map(|c| c.volume_db).collect();
+        let output_volumes: Vec<f32> = self.mixer_state.outputs.iter().map(|c| c.volume_db).collect();
+        
+        let passthrough_volumes: Vec<f32> =
+            self.mixer_state.passthrough.iter().map(|c| c.volume_db).collect();
+
+        self.config
+            .update_volumes(&input_volumes, &output_volumes, self.mixer_state.master.volume_db);
+        self.config.update_passthrough_volumes(&passthrough_volumes);
+        let switches = |channels: &[ChannelState], solo: bool| -> Vec<bool> {
+            channels
+                .iter()
+                .map(|c| if solo { c.soloed } else { c.muted })
+                .collect()
+        };
+        self.config.update_mutes(
+            &switches(&self.mixer_state.inputs, false),
+            &switches(&self.mixer_state.outputs, false),
+            &switches(&self.mixer_state.passthrough, false),
+            self.mixer_state.master.muted,
+        );
+        self.config.update_solos(
+            &switches(&self.mixer_state.inputs, true),
+            &switches(&self.mixer_state.outputs, true),
+        );
+        let trims: Vec<f32> = self.mixer_state.inputs.iter().map(|c| c.trim_db).collect();
+        self.config.update_trims(&trims);
+        let balances: Vec<f32> = self.mixer_state.inputs.iter().map(|c| c.balance_db).collect();
+        self.config.update_balances(&balances);
+        let weights: Vec<Option<f32>> =
+            self.mixer_state.inputs.iter().map(|c| c.automix_weight_db).collect();
+        self.config.update_automix_weights(&weights);
+        let devices: Vec<Option<f32>> =
+            self.mixer_state.inputs.iter().map(|c| c.device_db).collect();
+        self.config.update_device_gains(&devices);
+        let points: Vec<TapPoint> = self.mixer_state.inputs.iter().map(|c| c.meter_point).collect();
+        self.config.update_meter_points(&points);
+        let mid_side = |channels: &[ChannelState]| -> Vec<bool> {
+            channels.iter().map(|c| c.mid_side).collect()
+        };
+        self.config.update_mid_side(
+            &mid_side(&self.mixer_state.inputs),
+            &mid_side(&self.mixer_state.outputs),
+        );
+        let mid_side_levels = |channels: &[ChannelState]| -> Vec<(f32, f32)> {
+            channels.iter().map(|c| (c.mid_db, c.side_db)).collect()
+        };
+        self.config.update_mid_side_levels(
+            &mid_side_levels(&self.mixer_state.inputs),
+            &mid_side_levels(&self.mixer_state.outputs),
+        );
+        self.config.update_eq(&self.input_eqs());
+        self.config.update_multiband(&self.output_multibands());
+        self.config.update_routing(&self.mixer_state.routing);
+    }
+
     /// Main event loop
-    fn main_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+    fn main_loop(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        signals: &SuspendSignals,
+    ) -> Result<()> {
         let frame_duration = Duration::from_millis(1000 / TARGET_FPS);
 
         while !self.should_quit {
+            if signals.take_stop() {
+                self.suspend(terminal, signals)?;
+            } else if signals.take_resumed() {
+                // Stopped and continued from outside: the shell may have reset the terminal
+                self.resume(terminal)?;
+            }
+
             // Process meter updates from audio thread
             self.process_meter_updates();
 
+            // Serve control socket clients
+            self.process_remote_commands();
+            self.process_schedule();
+            self.process_source_swaps();
+            self.process_app_streams();
+            self.process_device_events();
+
             // Draw UI
             terminal.draw(|f| self.render(f))?;
+            self.update_terminal_title(terminal)?;
 
             // Handle input with timeout
             let timeout = frame_duration.saturating_sub(self.last_frame.elapsed());
             if event::poll(timeout)? {
                 if let Event::Key(key) = event::read()? {
                     if key.kind == KeyEventKind::Press {
-                        self.handle_key(key.code)?;
+                        if key.code == KeyCode::Char('z')
+                            && key.modifiers.contains(KeyModifiers::CONTROL)
+                        {
+                            self.suspend(terminal, signals)?;
+                        } else {
+                            self.handle_key(key.code, key.modifiers)?;
+                        }
                     }
                 }
             }
@@ -200,238 +780,2221 @@ impl App {
         Ok(())
     }
 
+    /// Event loop without the UI: runs until standard input is closed
+    fn command_loop(&mut self) {
+        let frame_duration = Duration::from_millis(1000 / TARGET_FPS);
+        loop {
+            self.process_meter_updates();
+            self.process_remote_commands();
+            self.process_schedule();
+            self.process_source_swaps();
+            self.process_app_streams();
+            self.process_device_events();
+
+            let open = match (&self.control_server, self.stdin_client) {
+                (Some(server), Some(client)) => server.is_connected(client),
+                _ => false,
+            };
+            if !open {
+                break;
+            }
+            thread::sleep(frame_duration);
+        }
+    }
+
+    /// Hand the terminal to the shell until the mixer is brought back
+    ///
+    /// By default the process stops like any job (`fg` resumes it), which
+    /// also stops the audio. With `keep_audio_on_suspend` a shell is started
+    /// instead and the mixer keeps running until it exits.
+    fn suspend(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        signals: &SuspendSignals,
+    ) -> Result<()> {
+        suspend::leave_ui(terminal)?;
+        self.pop_terminal_title(terminal)?;
+
+        if self.config.keep_audio_on_suspend {
+            println!("{}", trf(Msg::SuspendShell, &[&self.client_name]));
+            let interval = Duration::from_millis(1000 / TARGET_FPS);
+            let result = suspend::run_shell(interval, || {
+                self.process_meter_updates();
+                self.process_remote_commands();
+                self.process_schedule();
+            });
+            if let Err(e) = result {
+                self.set_status(trf(Msg::StatusShellFailed, &[&format!("{:#}", e)]));
+            }
+        } else {
+            suspend::stop_process()?;
+        }
+
+        // Continuing after our own stop needs no second redraw
+        signals.take_resumed();
+        self.resume(terminal)
+    }
+
+    /// Set the terminal up again after the process was suspended
+    fn resume(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+        suspend::enter_ui(terminal)?;
+        self.push_terminal_title(terminal)?;
+        terminal.clear()?;
+
+        // Time passed without key releases; don't continue an old key repeat
+        self.volume_repeat = None;
+        Ok(())
+    }
+
+    /// Save the current title on the terminal's title stack, if the title shows the mixer state
+    fn push_terminal_title(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        if self.terminal_title.is_some() {
+            execute!(terminal.backend_mut(), crossterm::style::Print("\x1b[22;0t"))?;
+            // Set the status title again on the next frame
+            self.terminal_title = Some(String::new());
+        }
+        Ok(())
+    }
+
+    /// Restore the title saved by `push_terminal_title`
+    fn pop_terminal_title(&self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+        if self.terminal_title.is_some() {
+            execute!(terminal.backend_mut(), crossterm::style::Print("\x1b[23;0t"))?;
+        }
+        Ok(())
+    }
+
     /// Process meter updates from the audio thread
     fn process_meter_updates(&mut self) {
+        self.audio_engine.drain_replay();
+        if let Err(e) = self.audio_engine.drain_recording() {
+            log::error!("Recording stopped: {:#}", e);
+            self.recording_path = None;
+            self.set_status(trf(Msg::StatusRecordFailed, &[&format!("{:#}", e)]));
+        }
+        self.poll_exports();
+
+        while let Some(change) = self.audio_engine.try_recv_program_change() {
+            self.handle_program_change(change);
+        }
+
+        while let Some(scope) = self.audio_engine.try_recv_scope() {
+            if matches!(self.mode, Mode::Overlay(Overlay::Goniometer(o)) if o == scope.output) {
+                if self.goniometer.len() + SCOPE_POINTS > GONIOMETER_TRAIL {
+                    self.goniometer.drain(..SCOPE_POINTS);
+                }
+                self.goniometer.extend(scope.points);
+            }
+        }
+
+        let hold = self.config.peak_hold_seconds.unwrap_or(PEAK_HOLD_DURATION);
         while let Some(meter) = self.audio_engine.try_recv_meter() {
             let num_inputs = self.mixer_state.inputs.len();
 
             if meter.channel_index < num_inputs {
                 // Input channel
-                self.mixer_state.inputs[meter.channel_index]
-                    .update_meter(meter.peaks, PEAK_HOLD_DURATION);
+                let input = &mut self.mixer_state.inputs[meter.channel_index];
+                input.update_meter(meter.peaks, meter.rms, meter.clipped, meter.timestamp, hold);
+                input.update_correlation(meter.correlation);
+                input.deesser_db = meter.gain_reduction_db.max(input.deesser_db * 0.9);
+                input.automix_db = meter.automix_db;
+                // A protective dip starting is a loud event worth a record
+                let event = (meter.dip_db > 0.0 && input.dip_db == 0.0).then(|| input.name.clone());
+                input.dip_db = meter.dip_db;
+                if let Some(name) = event {
+                    log::warn!("Loud event on input '{}', dipped by {:.0} dB", name, meter.dip_db);
+                    self.session_report.record_dip(&name);
+                    self.set_status(trf(
+                        Msg::StatusLoudEvent,
+                        &[&name, &format!("{:.0}", meter.dip_db)],
+                    ));
+                }
             } else {
                 // Output channel
                 let output_idx = meter.channel_index - num_inputs;
                 if output_idx < self.mixer_state.outputs.len() {
-                    self.mixer_state.outputs[output_idx]
-                        .update_meter(meter.peaks, PEAK_HOLD_DURATION);
+                    let output = &mut self.mixer_state.outputs[output_idx];
+                    output.update_meter(
+                        meter.peaks,
+                        meter.rms,
+                        meter.clipped,
+                        meter.timestamp,
+                        hold,
+                    );
+                    output.update_correlation(meter.correlation);
+                    output.auto_trim_db = meter.gain_reduction_db;
+                    // Fall back gradually so reductions of a single block stay readable
+                    output.limiter_db = meter.limiter_db.max(output.limiter_db * 0.9);
+                    for (shown, &db) in output.multiband_db.iter_mut().zip(&meter.multiband_db) {
+                        *shown = db.max(*shown * 0.9);
+                    }
+                } else if output_idx == self.mixer_state.outputs.len() {
+                    self.mixer_state.master.update_meter(
+                        meter.peaks,
+                        meter.rms,
+                        meter.clipped,
+                        meter.timestamp,
+                        hold,
+                    );
+                } else if let Some(passthrough) = self
+                    .mixer_state
+                    .passthrough
+                    .get_mut(output_idx - self.mixer_state.outputs.len() - 1)
+                {
+                    passthrough.update_meter(
+                        meter.peaks,
+                        meter.rms,
+                        meter.clipped,
+                        meter.timestamp,
+                        hold,
+                    );
+                    passthrough.update_correlation(meter.correlation);
                 }
             }
-        }
-    }
 
-    /// Handle keyboard input
-    fn handle_key(&mut self, code: KeyCode) -> Result<()> {
-        match code {
-            KeyCode::Char('q') | KeyCode::Esc => {
-                self.should_quit = true;
+            if let Some(logger) = self.meter_logger.as_mut() {
+                logger.record(&meter);
             }
-            KeyCode::Left => {
-                self.select_previous();
+            if let Some(logger) = self.loudness_logger.as_mut() {
+                logger.record(&meter, Instant::now());
             }
-            KeyCode::Right => {
-                self.select_next();
+            self.session_report.record(&meter);
+            self.loudness.record(&meter);
+            match &mut self.mode {
+                Mode::Calibration(view) => view.record(&meter, Instant::now()),
+                Mode::Balance(view) => view.record(&meter),
+                _ => {}
             }
-            KeyCode::Up => {
-                self.adjust_volume(VOLUME_STEP_DB)?;
+        }
+        match &mut self.mode {
+            Mode::Spectrogram(view) => {
+                self.audio_engine.drain_analyzer(view.pending());
+                view.analyse();
             }
-            KeyCode::Down => {
-                self.adjust_volume(-VOLUME_STEP_DB)?;
+            Mode::Oscilloscope(view) => {
+                self.audio_engine.drain_analyzer(view.pending());
+                view.update();
             }
-            KeyCode::Char('m') => {
-                self.toggle_mute()?;
+            Mode::Rta(view) => {
+                self.audio_engine.drain_analyzer(view.pending());
+                view.analyse();
             }
-            KeyCode::Char('s') => {
-                self.toggle_solo()?;
+            Mode::Feedback(view) => {
+                self.audio_engine.drain_analyzer(view.pending());
+                view.analyse();
             }
-            KeyCode::Char('0') => {
-                self.reset_volume_to_zero()?;
+            _ => {}
+        }
+        if let Mode::Calibration(view) = &mut self.mode {
+            if view.update(Instant::now()) {
+                self.send_calibration_noise();
             }
-            KeyCode::Tab => {
-                self.toggle_section();
+        }
+        self.loudness.update(Instant::now());
+        self.reminders.update(&self.mixer_state, Instant::now());
+        self.tally.update(&self.mixer_state);
+
+        if let Some(logger) = self.meter_logger.as_mut() {
+            if let Err(e) = logger.tick() {
+                log::error!("Meter logging stopped: {}", e);
+                self.meter_logger = None;
+            }
+        }
+        if let Some(logger) = self.loudness_logger.as_mut() {
+            if let Err(e) = logger.tick(Instant::now()) {
+                log::error!("Loudness logging stopped: {}", e);
+                self.loudness_logger = None;
+            }
+        }
+    }
+
+    /// Recall the scene mapped to a MIDI program change
+    fn handle_program_change(&mut self, change: ProgramChange) {
+        let Some(index) = self
+            .config
+            .scenes
+            .iter()
+            .position(|s| s.matches_program(change.bank, change.program))
+        else {
+            log::info!(
+                "No scene for program {} (bank {}, channel {})",
+                change.program,
+                change.bank,
+                change.channel + 1
+            );
+            return;
+        };
+        if let Err(e) = self.recall_scene(index) {
+            log::warn!("Scene recall failed: {:#}", e);
+        }
+    }
+
+    /// Apply the settings stored in a scene
+    fn recall_scene(&mut self, index: usize) -> Result<()> {
+        let scene = self.config.scenes[index].clone();
+        for (kind, settings) in [
+            (SelectionType::Input, &scene.inputs),
+            (SelectionType::Output, &scene.outputs),
+        ] {
+            for (name, channel) in settings {
+                let Some(i) = self.channels(kind).iter().position(|c| &c.name == name) else {
+                    continue;
+                };
+                if let Some(volume_db) = channel.volume_db {
+                    self.set_volume(kind, i, volume_db)?;
+                }
+                if let Some(muted) = channel.muted {
+                    self.set_mute(kind, i, muted)?;
+                }
             }
-            _ => {}
         }
+        if let Some(volume_db) = scene.master_volume_db {
+            self.set_volume(SelectionType::Master, 0, volume_db)?;
+        }
+        self.session_report.record_scene(&scene.name);
+        self.set_status(trf(Msg::StatusSceneRecalled, &[&scene.name]));
         Ok(())
     }
 
-    /// Select the previous channel
-    fn select_previous(&mut self) {
-        let max_idx = match self.selection_type {
-            SelectionType::Input => self.mixer_state.inputs.len(),
-            SelectionType::Output => self.mixer_state.outputs.len(),
+    /// Handle keyboard input: the substitution prompt first, then the
+    /// keymap of the active mode
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+        if !self.pending_substitutions.is_empty() {
+            self.handle_substitution_key(code);
+            return Ok(());
+        }
+
+        match &mut self.mode {
+            Mode::Normal => self.handle_normal_key(code, modifiers)?,
+            Mode::Overlay(overlay) => {
+                let overlay = *overlay;
+                self.handle_overlay_key(overlay, code, modifiers)?;
+            }
+            Mode::Flip(layer) => {
+                let layer = *layer;
+                self.handle_flip_key(layer, code, modifiers)?;
+            }
+            Mode::Editor(_) => self.handle_editor_key(code),
+            Mode::Matrix(matrix) => match matrix.handle_key(code, &self.mixer_state) {
+                MatrixAction::None => {}
+                MatrixAction::Close => self.mode = Mode::Normal,
+                MatrixAction::SetRoute {
+                    input,
+                    output,
+                    crosspoint,
+                } => self.set_route(input, output, crosspoint)?,
+            },
+            Mode::Eq(eq_view) => match eq_view.handle_key(code, &self.mixer_state) {
+                EqAction::None => {}
+                EqAction::Close => self.mode = Mode::Normal,
+                EqAction::Set { input, eq } => self.set_eq(input, eq)?,
+            },
+            Mode::Multiband(view) => match view.handle_key(code, &self.mixer_state) {
+                MultibandAction::None => {}
+                MultibandAction::Close => self.mode = Mode::Normal,
+                MultibandAction::Set { output, settings } => {
+                    self.set_multiband(output, settings)?
+                }
+            },
+            Mode::Calibration(view) => match view.handle_key(code) {
+                CalibrationAction::None => {}
+                CalibrationAction::Close => self.stop_calibration(),
+                CalibrationAction::Restart => self.start_calibration(),
+                CalibrationAction::Apply(changes) => {
+                    for &(output, offset_db) in &changes {
+                        let volume_db = self.mixer_state.outputs[output].volume_db + offset_db;
+                        self.set_volume(SelectionType::Output, output, volume_db)?;
+                    }
+                    self.stop_calibration();
+                    self.set_status(trf(Msg::StatusCalibrated, &[&changes.len()]));
+                }
+            },
+            Mode::Balance(view) => {
+                let input = view.input();
+                match view.handle_key(code) {
+                    BalanceAction::None => {}
+                    BalanceAction::Close => self.mode = Mode::Normal,
+                    BalanceAction::Restart => self.start_balance(input)?,
+                    BalanceAction::Apply(balance_db) => {
+                        self.set_balance(input, balance_db)?;
+                        self.start_balance(input)?;
+                    }
+                }
+            }
+            Mode::Spectrogram(view) => match view.handle_key(code) {
+                SpectrogramAction::None => {}
+                SpectrogramAction::Close => self.close_analyzer()?,
+                SpectrogramAction::Switch => {
+                    let sample_rate = self.audio_engine.sample_rate();
+                    let view = OscilloscopeView::new(view.source(), sample_rate);
+                    self.mode = Mode::Oscilloscope(Box::new(view));
+                }
+            },
+            Mode::Oscilloscope(view) => match view.handle_key(code) {
+                OscilloscopeAction::None => {}
+                OscilloscopeAction::Close => self.close_analyzer()?,
+                OscilloscopeAction::Switch => {
+                    let sample_rate = self.audio_engine.sample_rate();
+                    let outputs = self.mixer_state.outputs.len();
+                    let view = RtaView::new(view.source(), sample_rate, outputs);
+                    self.mode = Mode::Rta(Box::new(view));
+                }
+            },
+            Mode::Rta(view) => match view.handle_key(code) {
+                RtaAction::None => {}
+                RtaAction::Close => self.close_analyzer()?,
+                RtaAction::Switch => {
+                    let sample_rate = self.audio_engine.sample_rate();
+                    let view = SpectrogramView::new(view.source(), sample_rate);
+                    self.mode = Mode::Spectrogram(Box::new(view));
+                    self.send_calibration_noise();
+                }
+                RtaAction::Noise => self.send_calibration_noise(),
+            },
+            Mode::Feedback(view) => {
+                let output = view.output();
+                match view.handle_key(code) {
+                    FeedbackAction::None => {}
+                    FeedbackAction::Close => self.close_analyzer()?,
+                    FeedbackAction::Notch(Some(freq_hz)) => self.add_notch(output, freq_hz)?,
+                    FeedbackAction::Notch(None) => self.set_status(tr(Msg::StatusNoRinging)),
+                    FeedbackAction::ClearNotches => self.clear_notches(output)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle a key with an overlay open: closing keys act on the overlay,
+    /// everything else on the strips below it
+    fn handle_overlay_key(
+        &mut self,
+        overlay: Overlay,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<()> {
+        match (overlay, code) {
+            (_, KeyCode::Esc)
+            | (Overlay::Diagnostics, KeyCode::Char('d'))
+            | (Overlay::Contributions(_), KeyCode::Char('i'))
+            | (Overlay::Goniometer(_), KeyCode::Char('P'))
+            | (Overlay::Clips, KeyCode::Char('L'))
+            | (Overlay::BigMeter, KeyCode::Char('B')) => {
+                self.mode = Mode::Normal;
+            }
+            (Overlay::Diagnostics, KeyCode::Char('r')) => {
+                self.audio_engine.reset_control_diagnostics();
+            }
+            _ => self.handle_normal_key(code, modifiers)?,
+        }
+        Ok(())
+    }
+
+    /// Handle a key with a flip layer active: Up/Down set the layer's
+    /// parameter, everything else acts on the strips as usual
+    fn handle_flip_key(
+        &mut self,
+        layer: FlipLayer,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<()> {
+        match code {
+            KeyCode::Esc => {
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Char('g') => {
+                let devices = self.config.inputs.iter().any(|c| c.device_gain.is_some());
+                let automix = self
+                    .mixer_state
+                    .inputs
+                    .iter()
+                    .any(|c| c.automix_weight_db.is_some());
+                self.mode = layer
+                    .next(self.mixer_state.outputs.len(), devices, automix)
+                    .map_or(Mode::Normal, Mode::Flip);
+            }
+            KeyCode::Char('m') if layer == FlipLayer::DeviceGain => {
+                self.toggle_device_mute();
+            }
+            KeyCode::Up | KeyCode::Down if !modifiers.contains(KeyModifiers::SHIFT) => {
+                let step = self.volume_key_step(code);
+                let delta = if code == KeyCode::Up { step } else { -step };
+                self.adjust_flip(layer, delta)?;
+            }
+            _ => self.handle_normal_key(code, modifiers)?,
+        }
+        Ok(())
+    }
+
+    /// Handle a key on the channel strips
+    fn handle_normal_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.should_quit = true;
+            }
+            KeyCode::Left if self.analysis_focus => {
+                self.select_analysis_channel(-1);
+            }
+            KeyCode::Right if self.analysis_focus => {
+                self.select_analysis_channel(1);
+            }
+            KeyCode::Left => {
+                self.select_previous();
+            }
+            KeyCode::Right => {
+                self.select_next();
+            }
+            KeyCode::Up if modifiers.contains(KeyModifiers::SHIFT) => {
+                self.adjust_trim(TRIM_STEP_DB)?;
+            }
+            KeyCode::Down if modifiers.contains(KeyModifiers::SHIFT) => {
+                self.adjust_trim(-TRIM_STEP_DB)?;
+            }
+            KeyCode::Up => {
+                let step = self.volume_key_step(code);
+                self.adjust_volume(step)?;
+            }
+            KeyCode::Down => {
+                let step = self.volume_key_step(code);
+                self.adjust_volume(-step)?;
+            }
+            KeyCode::Char('m') => {
+                self.toggle_mute()?;
+            }
+            KeyCode::Char('s') => {
+                self.toggle_solo()?;
+            }
+            KeyCode::Char('0') => {
+                self.reset_volume_to_zero()?;
+            }
+            KeyCode::Char(c @ ('z' | 'x' | 'c' | 'Z' | 'X' | 'C')) => {
+                let band = match c.to_ascii_lowercase() {
+                    'z' => EqBand::Low,
+                    'x' => EqBand::Mid,
+                    _ => EqBand::High,
+                };
+                let delta = if c.is_ascii_uppercase() {
+                    EQ_STEP_DB
+                } else {
+                    -EQ_STEP_DB
+                };
+                self.adjust_eq(band, delta)?;
+            }
+            KeyCode::Tab => {
+                self.toggle_section();
+            }
+            KeyCode::Char('e') => {
+                self.open_editor();
+            }
+            KeyCode::Char('r') => {
+                self.mode = Mode::Matrix(MatrixView::default());
+            }
+            KeyCode::Char('p')
+                if self.selection_type == SelectionType::Input
+                    && self.selected_channel < self.mixer_state.inputs.len() =>
+            {
+                self.mode = Mode::Eq(EqView::new(self.selected_channel));
+            }
+            KeyCode::Char('p')
+                if self.selection_type == SelectionType::Output
+                    && self.selected_channel < self.mixer_state.outputs.len() =>
+            {
+                self.mode = Mode::Multiband(MultibandView::new(self.selected_channel));
+            }
+            KeyCode::Char('d') => {
+                self.mode = Mode::Overlay(Overlay::Diagnostics);
+            }
+            KeyCode::Char('L') => {
+                self.mode = Mode::Overlay(Overlay::Clips);
+            }
+            KeyCode::Char('B') => {
+                self.mode = Mode::Overlay(Overlay::BigMeter);
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                self.cycle_route(c as usize - '1' as usize)?;
+            }
+            KeyCode::Char('w') => {
+                if let Err(e) = self.save_replay(None) {
+                    self.set_status(trf(Msg::StatusReplayFailed, &[&format!("{:#}", e)]));
+                }
+            }
+            KeyCode::Char('W') => {
+                let result = if self.recording_path.is_some() {
+                    self.stop_recording()
+                } else {
+                    self.start_recording()
+                };
+                if let Err(e) = result {
+                    self.set_status(trf(Msg::StatusRecordFailed, &[&format!("{:#}", e)]));
+                }
+            }
+            KeyCode::Char('R') => {
+                if let Err(e) = self.write_session_report() {
+                    self.set_status(trf(Msg::StatusReportFailed, &[&format!("{:#}", e)]));
+                }
+            }
+            KeyCode::Char('f') | KeyCode::Char('F') => {
+                let output = (self.selection_type == SelectionType::Output)
+                    .then_some(self.selected_channel);
+                self.find_loudest_input(output, code == KeyCode::Char('F'))?;
+            }
+            KeyCode::Char('a') => {
+                self.reminders.acknowledge(Instant::now());
+            }
+            KeyCode::Char('M') => {
+                self.toggle_mid_side()?;
+            }
+            KeyCode::Char('[') => {
+                self.adjust_mid_side_levels(-MID_SIDE_STEP_DB, 0.0)?;
+            }
+            KeyCode::Char(']') => {
+                self.adjust_mid_side_levels(MID_SIDE_STEP_DB, 0.0)?;
+            }
+            KeyCode::Char('{') => {
+                self.adjust_mid_side_levels(0.0, -MID_SIDE_STEP_DB)?;
+            }
+            KeyCode::Char('}') => {
+                self.adjust_mid_side_levels(0.0, MID_SIDE_STEP_DB)?;
+            }
+            KeyCode::Char('o') => {
+                self.toggle_meter_point()?;
+            }
+            KeyCode::Char('O') => {
+                self.toggle_all_meter_points()?;
+            }
+            KeyCode::Char('k') => {
+                self.start_calibration();
+            }
+            KeyCode::Char('K') => {
+                if self.selection_type == SelectionType::Input {
+                    self.start_balance(self.selected_channel)?;
+                } else {
+                    self.set_status(tr(Msg::StatusBalanceStereo));
+                }
+            }
+            KeyCode::Char('b') => {
+                self.cycle_source();
+            }
+            KeyCode::Char('g') => {
+                self.mode = Mode::Flip(FlipLayer::Trim);
+            }
+            KeyCode::Backspace => {
+                self.reset_meters();
+            }
+            KeyCode::Char('v') => {
+                self.split_view = !self.split_view;
+                self.analysis_focus = false;
+                let narrow = crossterm::terminal::size().is_ok_and(|(w, _)| w < SPLIT_MIN_WIDTH);
+                if self.split_view && narrow {
+                    self.set_status(trf(Msg::StatusSplitNarrow, &[&SPLIT_MIN_WIDTH]));
+                }
+            }
+            KeyCode::Char('V') if self.split_view => {
+                self.analysis_focus = !self.analysis_focus;
+            }
+            KeyCode::Char('h') => {
+                self.toggle_filter(PassFilter::HighPass)?;
+            }
+            KeyCode::Char('l') => {
+                self.toggle_filter(PassFilter::LowPass)?;
+            }
+            KeyCode::Char('i') => {
+                if self.selection_type == SelectionType::Output {
+                    self.mode = Mode::Overlay(Overlay::Contributions(self.selected_channel));
+                } else {
+                    self.set_status(tr(Msg::StatusSelectOutput));
+                }
+            }
+            KeyCode::Char('t') => {
+                self.tap_click_tempo();
+            }
+            KeyCode::Char('T') => {
+                self.toggle_click();
+            }
+            KeyCode::Char(key @ ('S' | 'A')) => {
+                let source = match self.selection_type {
+                    SelectionType::Input => Some(AnalyzerSource::Input(self.selected_channel)),
+                    SelectionType::Output => Some(AnalyzerSource::Output(self.selected_channel)),
+                    SelectionType::Master | SelectionType::Passthrough => None,
+                };
+                match source {
+                    Some(source) if key == 'A' => self.open_rta(source)?,
+                    Some(source) => self.open_spectrogram(source)?,
+                    None => self.set_status(tr(Msg::StatusSelectInputOrOutput)),
+                }
+            }
+            KeyCode::Char('N') => {
+                if self.selection_type == SelectionType::Output {
+                    self.open_feedback_finder(self.selected_channel)?;
+                } else {
+                    self.set_status(tr(Msg::StatusSelectOutput));
+                }
+            }
+            KeyCode::Char('P') => {
+                let stereo = self.selection_type == SelectionType::Output
+                    && self.mixer_state.outputs[self.selected_channel].port_count == 2;
+                if stereo {
+                    self.goniometer.clear();
+                    self.mode = Mode::Overlay(Overlay::Goniometer(self.selected_channel));
+                } else {
+                    self.set_status(tr(Msg::StatusSelectStereoOutput));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Volume step for a volume key press, accelerating while the key is held
+    fn volume_key_step(&mut self, key: KeyCode) -> f32 {
+        let repeat = VolumeKeyRepeat::press(self.volume_repeat, key);
+        self.volume_repeat = Some(repeat);
+        repeat.step_db()
+    }
+
+    /// Open the config editor on a snapshot of the current settings
+    fn open_editor(&mut self) {
+        let mut config = self.config.clone();
+        let input_volumes: Vec<f32> = self.mixer_state.inputs.iter().map(|c| c.volume_db).collect();
+        let output_volumes: Vec<f32> = self.mixer_state.outputs.iter().map(|c| c.volume_db).collect();
+        let passthrough_volumes: Vec<f32> =
+            self.mixer_state.passthrough.iter().map(|c| c.volume_db).collect();
+        config.update_volumes(&input_volumes, &output_volumes, self.mixer_state.master.volume_db);
+        config.update_passthrough_volumes(&passthrough_volumes);
+        config.update_eq(&self.input_eqs());
+        config.update_multiband(&self.output_multibands());
+        config.update_routing(&self.mixer_state.routing);
+        self.mode = Mode::Editor(Box::new(ConfigEditor::new(config)));
+    }
+
+    /// Handle a key while the config editor is open
+    fn handle_editor_key(&mut self, code: KeyCode) {
+        let Mode::Editor(editor) = &mut self.mode else {
+            return;
+        };
+
+        match editor.handle_key(code) {
+            EditorAction::None => {}
+            EditorAction::Close => self.mode = Mode::Normal,
+            EditorAction::Apply(config) => {
+                let result = self.apply_config(*config);
+                if let Mode::Editor(editor) = &mut self.mode {
+                    match result {
+                        Ok(()) => {
+                            editor.mark_applied();
+                            editor.set_status(tr(Msg::StatusConfigApplied));
+                        }
+                        Err(e) => editor
+                            .set_error(trf(Msg::StatusApplyFailed, &[&format!("{:#}", e)])),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sections in screen order, skipping empty ones
+    fn sections(&self) -> Vec<SelectionType> {
+        [
+            SelectionType::Input,
+            SelectionType::Output,
+            SelectionType::Passthrough,
+            SelectionType::Master,
+        ]
+        .into_iter()
+            .filter(|&kind| !self.channels(kind).is_empty())
+            .collect()
+    }
+
+    /// The section after (or before, if `forward` is false) the current one, wrapping around
+    fn adjacent_section(&self, forward: bool) -> SelectionType {
+        let sections = self.sections();
+        let current = sections
+            .iter()
+            .position(|&kind| kind == self.selection_type)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % sections.len()
+        } else {
+            (current + sections.len() - 1) % sections.len()
+        };
+        sections[next]
+    }
+
+    /// Open the calibration view and start the noise on the first output
+    fn start_calibration(&mut self) {
+        let Some(settings) = self.config.calibration.as_ref() else {
+            self.set_status(tr(Msg::StatusNoCalibration));
+            return;
+        };
+        let Some(input) = self.config.inputs.iter().position(|i| i.name == settings.input) else {
+            return;
+        };
+        self.mode = Mode::Calibration(CalibrationView::new(
+            settings,
+            input,
+            self.mixer_state.outputs.len(),
+            Instant::now(),
+        ));
+        self.send_calibration_noise();
+    }
+
+    /// Open the balance view on an input, or start it over
+    fn start_balance(&mut self, input: usize) -> Result<()> {
+        let Some(channel) = self.mixer_state.inputs.get(input) else {
+            return Ok(());
+        };
+        if channel.port_count != 2 {
+            self.set_status(tr(Msg::StatusBalanceStereo));
+        } else if channel.mid_side {
+            self.set_status(tr(Msg::StatusBalanceMidSide));
+        } else {
+            self.mode = Mode::Balance(BalanceView::new(input, channel.balance_db));
+        }
+        Ok(())
+    }
+
+    /// Set the stereo balance correction of an input
+    fn set_balance(&mut self, input: usize, balance_db: f32) -> Result<()> {
+        let Some(channel) = self.mixer_state.inputs.get_mut(input) else {
+            return Ok(());
+        };
+        channel.balance_db = balance_db;
+        let name = channel.name.clone();
+        self.set_status(trf(
+            Msg::StatusBalanceSet,
+            &[&name, &format!("{:+.1}", balance_db)],
+        ));
+        self.audio_engine.send_control(ControlMsg::SetInputBalance {
+            channel: input,
+            balance_db,
+        })
+    }
+
+    /// Stop the noise and close the calibration view
+    fn stop_calibration(&mut self) {
+        self.mode = Mode::Normal;
+        self.send_calibration_noise();
+    }
+
+    /// Open the spectrogram view on a channel and have the engine feed it
+    fn open_spectrogram(&mut self, source: AnalyzerSource) -> Result<()> {
+        let sample_rate = self.audio_engine.sample_rate();
+        self.audio_engine.send_control(ControlMsg::SetAnalyzer {
+            source: Some(source),
+        })?;
+        self.mode = Mode::Spectrogram(Box::new(SpectrogramView::new(source, sample_rate)));
+        Ok(())
+    }
+
+    /// Open the feedback finder on an output
+    fn open_feedback_finder(&mut self, output: usize) -> Result<()> {
+        let sample_rate = self.audio_engine.sample_rate();
+        self.audio_engine.send_control(ControlMsg::SetAnalyzer {
+            source: Some(AnalyzerSource::Output(output)),
+        })?;
+        self.mode = Mode::Feedback(Box::new(FeedbackView::new(output, sample_rate)));
+        Ok(())
+    }
+
+    /// Open the third-octave analyzer on a channel and have the engine feed it
+    fn open_rta(&mut self, source: AnalyzerSource) -> Result<()> {
+        let sample_rate = self.audio_engine.sample_rate();
+        self.audio_engine.send_control(ControlMsg::SetAnalyzer {
+            source: Some(source),
+        })?;
+        let outputs = self.mixer_state.outputs.len();
+        self.mode = Mode::Rta(Box::new(RtaView::new(source, sample_rate, outputs)));
+        Ok(())
+    }
+
+    /// Close the spectrogram, oscilloscope, analyzer or feedback finder and
+    /// stop their feed and noise
+    fn close_analyzer(&mut self) -> Result<()> {
+        let noise = matches!(&self.mode, Mode::Rta(view) if view.noise_output().is_some());
+        self.mode = Mode::Normal;
+        if noise {
+            self.send_calibration_noise();
+        }
+        self.audio_engine
+            .send_control(ControlMsg::SetAnalyzer { source: None })
+    }
+
+    /// Start or stop the metronome click
+    fn toggle_click(&mut self) {
+        if self.config.click.is_none() {
+            self.set_status(tr(Msg::StatusNoClick));
+            return;
+        }
+        self.click_running = !self.click_running;
+        self.send_click();
+    }
+
+    /// Count a tap towards the click tempo
+    fn tap_click_tempo(&mut self) {
+        if self.config.click.is_none() {
+            self.set_status(tr(Msg::StatusNoClick));
+            return;
+        }
+        let Some(tempo_bpm) = self.tap_tempo.tap(Instant::now()) else {
+            return;
+        };
+        let tempo_bpm = tempo_bpm.round();
+        if let Some(click) = self.config.click.as_mut() {
+            click.tempo_bpm = tempo_bpm;
+        }
+        self.set_status(trf(Msg::StatusClickTempo, &[&tempo_bpm]));
+        self.send_click();
+    }
+
+    /// Tell the engine whether the click runs and at what tempo
+    fn send_click(&mut self) {
+        let Some(click) = &self.config.click else {
+            return;
+        };
+        let message = ControlMsg::SetClick {
+            running: self.click_running,
+            tempo_bpm: click.tempo_bpm,
+        };
+        if let Err(e) = self.audio_engine.send_control(message) {
+            log::warn!("Click: {:#}", e);
+        }
+    }
+
+    /// Tell the engine which output plays calibration noise, if any
+    fn send_calibration_noise(&mut self) {
+        let (output, level_db) = match &self.mode {
+            Mode::Calibration(view) => (view.noise_output(), view.level_db()),
+            Mode::Rta(view) => (
+                view.noise_output(),
+                self.config.calibration.as_ref().map_or(RTA_NOISE_DB, |c| c.level_db),
+            ),
+            _ => (None, 0.0),
+        };
+        if let Err(e) = self
+            .audio_engine
+            .send_control(ControlMsg::SetCalibrationNoise { output, level_db })
+        {
+            log::warn!("Calibration noise: {:#}", e);
+        }
+    }
+
+    /// External ports of a source of an input: 0 is `connect`, then the
+    /// alternates in order
+    fn source_ports(channel: &ChannelConfig, source: usize) -> &[String] {
+        match source {
+            0 => &channel.connect,
+            n => channel.alternates.get(n - 1).map_or(&[], |a| &a.connect),
+        }
+    }
+
+    /// Fade the selected input out and queue a switch to its next source
+    fn cycle_source(&mut self) {
+        if self.selection_type != SelectionType::Input {
+            self.set_status(tr(Msg::StatusSelectInput));
+            return;
+        }
+        let index = self.selected_channel;
+        let Some(channel) = self.config.inputs.get(index) else {
+            return;
+        };
+        if channel.alternates.is_empty() {
+            self.set_status(trf(Msg::StatusNoAlternates, &[&channel.name]));
+            return;
+        }
+        if self.source_swaps.iter().any(|s| s.channel == index) {
+            return;
+        }
+        let source = (self.active_sources[index] + 1) % (channel.alternates.len() + 1);
+        if let Err(e) = self.audio_engine.send_control(ControlMsg::SetInputSwitching {
+            channel: index,
+            switching: true,
+        }) {
+            self.set_status(format!("{:#}", e));
+            return;
+        }
+        self.source_swaps.push(SourceSwap {
+            channel: index,
+            source,
+            since: Instant::now(),
+        });
+    }
+
+    /// Swap the ports of inputs that have faded out and fade them back in
+    fn process_source_swaps(&mut self) {
+        let now = Instant::now();
+        let (due, waiting): (Vec<SourceSwap>, Vec<SourceSwap>) =
+            std::mem::take(&mut self.source_swaps)
+                .into_iter()
+                .partition(|s| now.duration_since(s.since) >= SOURCE_SWAP_FADE);
+        self.source_swaps = waiting;
+        for swap in due {
+            self.swap_source(swap.channel, swap.source);
+        }
+    }
+
+    /// Disconnect the active source of an input, connect `source` and fade
+    /// the input back in
+    fn swap_source(&mut self, index: usize, source: usize) {
+        let channel = &self.config.inputs[index];
+        let mut failure = None;
+        for (entry, target) in Self::source_ports(channel, self.active_sources[index])
+            .iter()
+            .enumerate()
+        {
+            if let Some(local) = local_port_for_entry(channel, entry) {
+                if let Err(e) = self.audio_engine.disconnect_port(Side::Input, local, target) {
+                    // The old source may already be gone, which is why it is swapped
+                    log::warn!("{:#}", e);
+                }
+            }
+        }
+        for (entry, target) in Self::source_ports(channel, source).iter().enumerate() {
+            if let Some(local) = local_port_for_entry(channel, entry) {
+                if let Err(e) = self.audio_engine.connect_port(Side::Input, local, target) {
+                    log::warn!("{:#}", e);
+                    failure.get_or_insert(e);
+                }
+            }
+        }
+        let message = match (&failure, source) {
+            (Some(e), _) => trf(Msg::StatusSourceFailed, &[&channel.name, &format!("{:#}", e)]),
+            (None, 0) => trf(Msg::StatusSourcePrimary, &[&channel.name]),
+            (None, n) => trf(
+                Msg::StatusSourceSwitched,
+                &[&channel.name, &channel.alternates[n - 1].name],
+            ),
+        };
+        self.active_sources[index] = source;
+        if let Err(e) = self.audio_engine.send_control(ControlMsg::SetInputSwitching {
+            channel: index,
+            switching: false,
+        }) {
+            log::warn!("{:#}", e);
+        }
+        self.set_status(message);
+    }
+
+    /// Channels of the loudness pane: inputs, outputs and the master
+    fn analysis_channels(config: &Config) -> usize {
+        config.inputs.len() + config.outputs.len() + 1
+    }
+
+    /// Step the loudness pane to another channel (wrapping around)
+    fn select_analysis_channel(&mut self, delta: isize) {
+        let count = Self::analysis_channels(&self.config) as isize;
+        self.analysis_channel = (self.analysis_channel as isize + delta).rem_euclid(count) as usize;
+    }
+
+    /// Select the previous channel
+    fn select_previous(&mut self) {
+        if self.selected_channel > 0 {
+            self.selected_channel -= 1;
+        } else {
+            // Move to the end of the previous section (wrapping around)
+            self.selection_type = self.adjacent_section(false);
+            self.selected_channel = self.channels(self.selection_type).len().saturating_sub(1);
+        }
+    }
+
+    /// Select the next channel
+    fn select_next(&mut self) {
+        if self.selected_channel + 1 < self.channels(self.selection_type).len() {
+            self.selected_channel += 1;
+        } else {
+            // Move to the start of the next section (wrapping around)
+            self.selection_type = self.adjacent_section(true);
+            self.selected_channel = 0;
+        }
+    }
+
+    /// Switch to the next section (inputs, outputs, passthrough, master)
+    fn toggle_section(&mut self) {
+        self.selection_type = self.adjacent_section(true);
+        self.selected_channel = 0;
+    }
+
+    /// Get the channel list of a section
+    fn channels(&self, kind: SelectionType) -> &[ChannelState] {
+        match kind {
+            SelectionType::Input => &self.mixer_state.inputs,
+            SelectionType::Output => &self.mixer_state.outputs,
+            SelectionType::Master => std::slice::from_ref(&self.mixer_state.master),
+            SelectionType::Passthrough => &self.mixer_state.passthrough,
+        }
+    }
+
+    /// Get a mutable channel state by section and index
+    fn channel_mut(&mut self, kind: SelectionType, index: usize) -> Option<&mut ChannelState> {
+        match kind {
+            SelectionType::Input => self.mixer_state.inputs.get_mut(index),
+            SelectionType::Output => self.mixer_state.outputs.get_mut(index),
+            SelectionType::Master => (index == 0).then_some(&mut self.mixer_state.master),
+            SelectionType::Passthrough => self.mixer_state.passthrough.get_mut(index),
+        }
+    }
+
+    /// Set the volume of a channel, clamped to the valid range
+    fn set_volume(&mut self, kind: SelectionType, index: usize, volume_db: f32) -> Result<()> {
+        let Some(channel) = self.channel_mut(kind, index) else {
+            return Ok(());
+        };
+        channel.volume_db = channel.clamp_volume(volume_db);
+        let volume_db = channel.volume_db;
+
+        let msg = match kind {
+            SelectionType::Input => ControlMsg::SetInputVolume {
+                channel: index,
+                volume_db,
+            },
+            SelectionType::Output => ControlMsg::SetOutputVolume {
+                channel: index,
+                volume_db,
+            },
+            SelectionType::Master => ControlMsg::SetMasterVolume { volume_db },
+            SelectionType::Passthrough => ControlMsg::SetPassthroughVolume {
+                channel: index,
+                volume_db,
+            },
+        };
+        self.audio_engine.send_control(msg)
+    }
+
+    /// Set the mute state of a channel
+    fn set_mute(&mut self, kind: SelectionType, index: usize, muted: bool) -> Result<()> {
+        let Some(channel) = self.channel_mut(kind, index) else {
+            return Ok(());
+        };
+        if channel.muted == muted {
+            return Ok(());
+        }
+        channel.muted = muted;
+
+        let msg = match kind {
+            SelectionType::Input => ControlMsg::ToggleInputMute { channel: index },
+            SelectionType::Output => ControlMsg::ToggleOutputMute { channel: index },
+            SelectionType::Master => ControlMsg::ToggleMasterMute,
+            SelectionType::Passthrough => ControlMsg::TogglePassthroughMute { channel: index },
+        };
+        self.audio_engine.send_control(msg)
+    }
+
+    /// Set the solo state of an input or output channel; in exclusive mode
+    /// a solo releases the others here and in the engine alike
+    fn set_solo(&mut self, kind: SelectionType, index: usize, soloed: bool) -> Result<()> {
+        let (toggle, msg): (fn(&mut MixerState, usize), _) = match kind {
+            SelectionType::Input => {
+                (solo::toggle_input, ControlMsg::ToggleInputSolo { channel: index })
+            }
+            SelectionType::Output => {
+                (solo::toggle_output, ControlMsg::ToggleOutputSolo { channel: index })
+            }
+            SelectionType::Master | SelectionType::Passthrough => return Ok(()),
+        };
+        if self.channels(kind).get(index).is_none_or(|c| c.soloed == soloed) {
+            return Ok(());
+        }
+        toggle(&mut self.mixer_state, index);
+        self.audio_engine.send_control(msg)
+    }
+
+    /// Set the EQ of an input channel, sending only the bands that changed
+    fn set_eq(&mut self, index: usize, eq: EqConfig) -> Result<()> {
+        let Some(channel) = self.mixer_state.inputs.get_mut(index) else {
+            return Ok(());
+        };
+        let old = std::mem::replace(&mut channel.eq, eq.clone());
+
+        for (band, old_db, gain_db) in [
+            (EqBand::Low, old.low_db, eq.low_db),
+            (EqBand::Mid, old.mid_db, eq.mid_db),
+            (EqBand::High, old.high_db, eq.high_db),
+        ] {
+            if old_db != gain_db {
+                self.audio_engine.send_control(ControlMsg::SetInputEq {
+                    channel: index,
+                    band,
+                    gain_db,
+                })?;
+            }
+        }
+        for band in 0..old.bands.len().max(eq.bands.len()) {
+            // A removed band is switched off by setting it to 0 dB
+            let settings = eq.bands.get(band).copied().unwrap_or(EqBandConfig {
+                gain_db: 0.0,
+                ..old.bands[band]
+            });
+            if old.bands.get(band) != Some(&settings) {
+                self.audio_engine.send_control(ControlMsg::SetInputEqBand {
+                    channel: index,
+                    band,
+                    settings,
+                })?;
+            }
+        }
+        for filter in [PassFilter::HighPass, PassFilter::LowPass] {
+            let settings = eq.pass_filter(filter);
+            if old.pass_filter(filter) != settings {
+                self.audio_engine.send_control(ControlMsg::SetInputFilter {
+                    channel: index,
+                    filter,
+                    settings,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Change the multiband compressor of an output
+    fn set_multiband(&mut self, index: usize, settings: MultibandConfig) -> Result<()> {
+        let Some(channel) = self.mixer_state.outputs.get_mut(index) else {
+            return Ok(());
+        };
+        channel.multiband = Some(settings);
+        self.audio_engine.send_control(ControlMsg::SetMultiband {
+            channel: index,
+            settings,
+        })
+    }
+
+    /// Cut a feedback frequency on an output with a notch in a free slot
+    fn add_notch(&mut self, output: usize, freq_hz: f32) -> Result<()> {
+        let Some(channel) = self.mixer_state.outputs.get_mut(output) else {
+            return Ok(());
+        };
+        let Some(slot) = channel.notches.iter().position(Option::is_none) else {
+            let status = trf(Msg::StatusNotchesFull, &[&MAX_NOTCHES, &channel.name]);
+            self.set_status(status);
+            return Ok(());
+        };
+        channel.notches[slot] = Some(freq_hz);
+        let status = trf(Msg::StatusNotchSet, &[&freq_hz.round(), &channel.name]);
+        self.set_status(status);
+        self.audio_engine.send_control(ControlMsg::SetOutputNotch {
+            channel: output,
+            slot,
+            freq_hz: Some(freq_hz),
+        })
+    }
+
+    /// Remove the feedback notches of an output
+    fn clear_notches(&mut self, output: usize) -> Result<()> {
+        let Some(channel) = self.mixer_state.outputs.get_mut(output) else {
+            return Ok(());
+        };
+        let slots: Vec<usize> = (0..MAX_NOTCHES)
+            .filter(|&slot| channel.notches[slot].take().is_some())
+            .collect();
+        let status = trf(Msg::StatusNotchesCleared, &[&channel.name]);
+        self.set_status(status);
+        for slot in slots {
+            self.audio_engine.send_control(ControlMsg::SetOutputNotch {
+                channel: output,
+                slot,
+                freq_hz: None,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Switch a high- or low-pass filter of the selected input on or off
+    fn toggle_filter(&mut self, filter: PassFilter) -> Result<()> {
+        if self.selection_type != SelectionType::Input {
+            return Ok(());
+        }
+        let index = self.selected_channel;
+        let Some(channel) = self.mixer_state.inputs.get(index) else {
+            return Ok(());
+        };
+        let mut eq = channel.eq.clone();
+        let settings = eq.pass_filter_mut(filter).get_or_insert(PassFilterConfig {
+            freq: filter.default_freq(),
+            enabled: false,
+        });
+        settings.enabled = !settings.enabled;
+        let settings = *settings;
+
+        let name = tr(match filter {
+            PassFilter::HighPass => Msg::FilterHighPass,
+            PassFilter::LowPass => Msg::FilterLowPass,
+        });
+        if settings.enabled {
+            self.set_status(trf(Msg::StatusFilterOn, &[&name, &settings.freq]));
+        } else {
+            self.set_status(trf(Msg::StatusFilterOff, &[&name]));
+        }
+        self.set_eq(index, eq)
+    }
+
+    /// Switch the meters and levels of the selected stereo channel between
+    /// left/right and mid/side
+    fn toggle_mid_side(&mut self) -> Result<()> {
+        let (kind, index) = (self.selection_type, self.selected_channel);
+        let Some(channel) = self.channel_mut(kind, index) else {
+            return Ok(());
+        };
+        if channel.port_count != 2 || !matches!(kind, SelectionType::Input | SelectionType::Output)
+        {
+            self.set_status(tr(Msg::StatusMidSideStereo));
+            return Ok(());
+        }
+        channel.mid_side = !channel.mid_side;
+        let (name, enabled) = (channel.name.clone(), channel.mid_side);
+        self.set_status(trf(
+            if enabled {
+                Msg::StatusMidSideOn
+            } else {
+                Msg::StatusMidSideOff
+            },
+            &[&name],
+        ));
+        self.audio_engine.send_control(if kind == SelectionType::Input {
+            ControlMsg::SetInputMidSide {
+                channel: index,
+                enabled,
+            }
+        } else {
+            ControlMsg::SetOutputMidSide {
+                channel: index,
+                enabled,
+            }
+        })
+    }
+
+    /// Adjust the mid and side levels of the selected channel in mid/side
+    /// mode
+    fn adjust_mid_side_levels(&mut self, mid_delta: f32, side_delta: f32) -> Result<()> {
+        let (kind, index) = (self.selection_type, self.selected_channel);
+        if !matches!(kind, SelectionType::Input | SelectionType::Output) {
+            return Ok(());
+        }
+        let Some(channel) = self.channel_mut(kind, index) else {
+            return Ok(());
+        };
+        if !channel.mid_side {
+            let name = channel.name.clone();
+            self.set_status(trf(Msg::StatusMidSideNeeded, &[&name]));
+            return Ok(());
+        }
+        channel.mid_db = (channel.mid_db + mid_delta).clamp(-MID_SIDE_RANGE_DB, MID_SIDE_RANGE_DB);
+        channel.side_db =
+            (channel.side_db + side_delta).clamp(-MID_SIDE_RANGE_DB, MID_SIDE_RANGE_DB);
+        let (name, mid_db, side_db) = (channel.name.clone(), channel.mid_db, channel.side_db);
+        let display = self.config.db_display.with_suffix(false);
+        let (mid, side) = (display.format_signed(mid_db), display.format_signed(side_db));
+        self.set_status(trf(Msg::StatusMidSideLevels, &[&name, &mid, &side]));
+        self.audio_engine.send_control(if kind == SelectionType::Input {
+            ControlMsg::SetInputMidSideLevels {
+                channel: index,
+                mid_db,
+                side_db,
+            }
+        } else {
+            ControlMsg::SetOutputMidSideLevels {
+                channel: index,
+                mid_db,
+                side_db,
+            }
+        })
+    }
+
+    /// Switch the meters of the selected input between pre- and post-fader
+    fn toggle_meter_point(&mut self) -> Result<()> {
+        if self.selection_type != SelectionType::Input {
+            self.set_status(tr(Msg::StatusSelectInput));
+            return Ok(());
+        }
+        let index = self.selected_channel;
+        let Some(channel) = self.mixer_state.inputs.get_mut(index) else {
+            return Ok(());
+        };
+        channel.meter_point = channel.meter_point.toggled();
+        let (name, point) = (channel.name.clone(), channel.meter_point);
+        self.set_status(trf(
+            match point {
+                TapPoint::PreFader => Msg::StatusMeterPre,
+                TapPoint::PostFader => Msg::StatusMeterPost,
+            },
+            &[&name],
+        ));
+        self.audio_engine
+            .send_control(ControlMsg::SetInputMeterPoint { channel: index, point })
+    }
+
+    /// Switch the meters of every input, and the global metering point, to
+    /// the other point
+    fn toggle_all_meter_points(&mut self) -> Result<()> {
+        let point = self.config.meter_point.toggled();
+        self.config.meter_point = point;
+        for (index, channel) in self.mixer_state.inputs.iter_mut().enumerate() {
+            channel.meter_point = point;
+            self.audio_engine
+                .send_control(ControlMsg::SetInputMeterPoint { channel: index, point })?;
+        }
+        self.set_status(tr(match point {
+            TapPoint::PreFader => Msg::StatusMeterPreAll,
+            TapPoint::PostFader => Msg::StatusMeterPostAll,
+        }));
+        Ok(())
+    }
+
+    /// Adjust one EQ band of the selected input
+    fn adjust_eq(&mut self, band: EqBand, delta: f32) -> Result<()> {
+        if self.selection_type != SelectionType::Input {
+            return Ok(());
+        }
+        let index = self.selected_channel;
+        let Some(channel) = self.mixer_state.inputs.get(index) else {
+            return Ok(());
+        };
+        let mut eq = channel.eq.clone();
+        let gain = eq.band_mut(band);
+        *gain = (*gain + delta).clamp(-EQ_RANGE_DB, EQ_RANGE_DB);
+        self.set_eq(index, eq)
+    }
+
+    /// Current EQ settings of all inputs
+    fn input_eqs(&self) -> Vec<EqConfig> {
+        self.mixer_state.inputs.iter().map(|c| c.eq.clone()).collect()
+    }
+
+    /// Multiband compressor settings of all outputs
+    fn output_multibands(&self) -> Vec<Option<MultibandConfig>> {
+        self.mixer_state.outputs.iter().map(|c| c.multiband).collect()
+    }
+
+    /// Adjust volume of the selected channel
+    fn adjust_volume(&mut self, delta: f32) -> Result<()> {
+        let (kind, index) = (self.selection_type, self.selected_channel);
+        if let Some(channel) = self.channel_mut(kind, index) {
+            channel.adjust_volume(delta);
+            let volume_db = channel.volume_db;
+            self.set_volume(kind, index, volume_db)?;
+        }
+        Ok(())
+    }
+
+    /// Adjust the trim of the selected input
+    fn adjust_trim(&mut self, delta: f32) -> Result<()> {
+        if self.selection_type != SelectionType::Input {
+            return Ok(());
+        }
+        let index = self.selected_channel;
+        let Some(channel) = self.mixer_state.inputs.get_mut(index) else {
+            return Ok(());
+        };
+        channel.trim_db =
+            (channel.trim_db + delta).clamp(-INPUT_TRIM_RANGE_DB, INPUT_TRIM_RANGE_DB);
+        let (name, trim_db) = (channel.name.clone(), channel.trim_db);
+        let trim = self.config.db_display.with_suffix(false).format_signed(trim_db);
+        self.set_status(trf(Msg::StatusTrim, &[&name, &trim]));
+        self.audio_engine.send_control(ControlMsg::SetInputTrim {
+            channel: index,
+            trim_db,
+        })
+    }
+
+    /// Device and source name of the selected input's capture device, or a
+    /// status message saying it has none
+    fn selected_device(&mut self) -> Option<(usize, String)> {
+        let index = self.selected_channel;
+        let config = self.config.inputs.get(index)?;
+        match &config.device_gain {
+            Some(device) => Some((index, device.source.clone())),
+            None => {
+                let name = config.name.clone();
+                self.set_status(trf(Msg::StatusNoDeviceGain, &[&name]));
+                None
+            }
+        }
+    }
+
+    /// Adjust the capture device volume of the selected input
+    fn adjust_device_gain(&mut self, delta: f32) {
+        let Some((index, source)) = self.selected_device() else {
+            return;
+        };
+        let channel = &mut self.mixer_state.inputs[index];
+        // Not read yet: wait for the device rather than guess its volume
+        let Some(device_db) = channel.device_db else {
+            return;
+        };
+        let device_db = (device_db + delta).clamp(VOLUME_MIN_DB, DEVICE_GAIN_MAX_DB);
+        channel.device_db = Some(device_db);
+        let name = channel.name.clone();
+        let gain = self.config.db_display.with_suffix(false).format_signed(device_db);
+        self.set_status(trf(Msg::StatusDeviceGain, &[&name, &gain]));
+        if let Some(devices) = &self.device_gains {
+            devices.set_volume(index, &source, device_db);
+        }
+    }
+
+    /// Mute or unmute the capture device of the selected input
+    fn toggle_device_mute(&mut self) {
+        let Some((index, source)) = self.selected_device() else {
+            return;
+        };
+        let channel = &mut self.mixer_state.inputs[index];
+        channel.device_muted = !channel.device_muted;
+        let (name, muted) = (channel.name.clone(), channel.device_muted);
+        let msg = if muted {
+            Msg::StatusDeviceMuted
+        } else {
+            Msg::StatusDeviceUnmuted
+        };
+        self.set_status(trf(msg, &[&name]));
+        if let Some(devices) = &self.device_gains {
+            devices.set_mute(index, &source, muted);
+        }
+    }
+
+    /// Show what the capture devices report back
+    fn process_device_events(&mut self) {
+        self.process_device_meters();
+        let Some(devices) = &self.device_gains else {
+            return;
+        };
+        let mut failures = Vec::new();
+        while let Some(event) = devices.try_recv() {
+            match event {
+                DeviceEvent::State {
+                    input,
+                    volume_db,
+                    muted,
+                } => {
+                    if let Some(channel) = self.mixer_state.inputs.get_mut(input) {
+                        channel.device_db = Some(volume_db);
+                        channel.device_muted = muted;
+                    }
+                }
+                DeviceEvent::Failed { input, error } => failures.push((input, error)),
+            }
+        }
+        for (input, error) in failures {
+            let name = self.mixer_state.inputs.get(input).map_or("", |c| c.name.as_str());
+            log::warn!("Device gain of {}: {}", name, error);
+            let message = trf(Msg::StatusDeviceGainFailed, &[&name, &error]);
+            self.set_status(message);
+        }
+    }
+
+    /// Take the peaks the capture devices delivered
+    fn process_device_meters(&mut self) {
+        let Some(meters) = &self.device_meters else {
+            return;
+        };
+        let hold = self.config.peak_hold_seconds.unwrap_or(PEAK_HOLD_DURATION);
+        let mut failures = Vec::new();
+        while let Some(event) = meters.try_recv() {
+            match event {
+                DeviceMeterEvent::Peaks {
+                    input,
+                    peaks,
+                    clipped,
+                } => {
+                    if let Some(channel) = self.mixer_state.inputs.get_mut(input) {
+                        channel.update_device_meter(peaks, clipped, hold);
+                    }
+                }
+                DeviceMeterEvent::Failed { input, error } => failures.push((input, error)),
+            }
+        }
+        for (input, error) in failures {
+            let Some(channel) = self.mixer_state.inputs.get_mut(input) else {
+                continue;
+            };
+            channel.device_peaks = None;
+            let name = channel.name.clone();
+            log::warn!("Device meter of {}: {}", name, error);
+            self.set_status(trf(Msg::StatusDeviceMeterFailed, &[&name, &error]));
+        }
+    }
+
+    /// Adjust the automixer weight of the selected input
+    fn adjust_automix_weight(&mut self, delta: f32) -> Result<()> {
+        let index = self.selected_channel;
+        let Some(channel) = self.mixer_state.inputs.get_mut(index) else {
+            return Ok(());
+        };
+        let Some(weight_db) = channel.automix_weight_db else {
+            let name = channel.name.clone();
+            self.set_status(trf(Msg::StatusNotInAutomix, &[&name]));
+            return Ok(());
+        };
+        let weight_db =
+            (weight_db + delta).clamp(-AUTOMIX_WEIGHT_RANGE_DB, AUTOMIX_WEIGHT_RANGE_DB);
+        channel.automix_weight_db = Some(weight_db);
+        let name = channel.name.clone();
+        let weight = self.config.db_display.with_suffix(false).format_signed(weight_db);
+        self.set_status(trf(Msg::StatusAutomixWeight, &[&name, &weight]));
+        self.audio_engine.send_control(ControlMsg::SetAutomixWeight {
+            channel: index,
+            weight_db,
+        })
+    }
+
+    /// Adjust the flip layer parameter of the selected input
+    fn adjust_flip(&mut self, layer: FlipLayer, delta: f32) -> Result<()> {
+        if self.selection_type != SelectionType::Input {
+            self.set_status(tr(Msg::StatusSelectInput));
+            return Ok(());
+        }
+        match layer {
+            FlipLayer::Trim => self.adjust_trim(delta),
+            FlipLayer::DeviceGain => {
+                self.adjust_device_gain(delta);
+                Ok(())
+            }
+            FlipLayer::AutomixWeight => self.adjust_automix_weight(delta),
+            FlipLayer::Send(output) => {
+                let input = self.selected_channel;
+                let crosspoint = self.mixer_state.routing.get(input, output);
+                let gain_db = (crosspoint.gain_db + delta).clamp(VOLUME_MIN_DB, VOLUME_MAX_DB);
+                self.set_route(input, output, Crosspoint { gain_db, ..crosspoint })
+            }
+        }
+    }
+
+    /// Value an input strip shows in place of its fader on a flip layer
+    fn flip_value(&self, layer: FlipLayer, input: usize) -> String {
+        let display = self.config.db_display;
+        match layer {
+            FlipLayer::Trim => display.format_signed(self.mixer_state.inputs[input].trim_db),
+            FlipLayer::DeviceGain => {
+                let channel = &self.mixer_state.inputs[input];
+                match channel.device_db {
+                    Some(_) if channel.device_muted => "MUTE".to_string(),
+                    Some(device_db) => display.format_signed(device_db),
+                    None => "--".to_string(),
+                }
+            }
+            FlipLayer::AutomixWeight => match self.mixer_state.inputs[input].automix_weight_db {
+                Some(weight_db) => display.format_signed(weight_db),
+                None => "--".to_string(),
+            },
+            FlipLayer::Send(output) => {
+                let crosspoint = self.mixer_state.routing.get(input, output);
+                if crosspoint.enabled {
+                    display.format_signed(crosspoint.gain_db)
+                } else {
+                    "--".to_string()
+                }
+            }
+        }
+    }
+
+    /// Reset the peak holds and clip counters of every channel
+    fn reset_meters(&mut self) {
+        let state = &mut self.mixer_state;
+        for channel in state
+            .inputs
+            .iter_mut()
+            .chain(&mut state.outputs)
+            .chain(std::iter::once(&mut state.master))
+            .chain(&mut state.passthrough)
+        {
+            channel.peak_hold = channel.current_peaks;
+            channel.clip_count = 0;
+            channel.device_peak_hold = channel.device_peaks.unwrap_or_default();
+            channel.device_clip_count = 0;
+        }
+        self.set_status(tr(Msg::StatusMetersReset));
+    }
+
+
+    /// Toggle mute on the selected channel
+    fn toggle_mute(&mut self) -> Result<()> {
+        let (kind, index) = (self.selection_type, self.selected_channel);
+        if let Some(channel) = self.channels(kind).get(index) {
+            let muted = !channel.muted;
+            self.set_mute(kind, index, muted)?;
+        }
+        Ok(())
+    }
+
+    /// Set the crosspoint from an input to an output
+    fn set_route(&mut self, input: usize, output: usize, crosspoint: Crosspoint) -> Result<()> {
+        if input >= self.mixer_state.inputs.len() || output >= self.mixer_state.outputs.len() {
+            return Ok(());
+        }
+        self.mixer_state.routing.set(input, output, crosspoint);
+        self.audio_engine.send_control(ControlMsg::SetRoute {
+            input,
+            output,
+            crosspoint,
+        })
+    }
+
+    /// Cycle the selected input's assignment to an output (stereo/mono/off)
+    fn cycle_route(&mut self, output: usize) -> Result<()> {
+        if self.selection_type != SelectionType::Input {
+            return Ok(());
+        }
+        let input = self.selected_channel;
+        let Some(channel) = self.mixer_state.inputs.get(input) else {
+            return Ok(());
+        };
+        let stereo = channel.port_count > 1;
+        let crosspoint = self.mixer_state.routing.get(input, output).cycle(stereo);
+        self.set_route(input, output, crosspoint)
+    }
+
+    /// Select (and optionally mute) the input contributing the highest post-fader level
+    ///
+    /// Only sends to `output` count if given; the key binding uses the
+    /// selected output when the output section is active.
+    fn find_loudest_input(
+        &mut self,
+        output: Option<usize>,
+        mute: bool,
+    ) -> Result<Option<(usize, f32)>> {
+        let Some((index, level_db)) = self.mixer_state.loudest_input(output) else {
+            self.set_status(tr(Msg::StatusNothingAudible));
+            return Ok(None);
+        };
+
+        self.selection_type = SelectionType::Input;
+        self.selected_channel = index;
+        let name = self.mixer_state.inputs[index].name.clone();
+        let level = self.config.db_display.with_suffix(false).format(level_db);
+        if mute {
+            self.set_mute(SelectionType::Input, index, true)?;
+            self.set_status(trf(Msg::StatusLoudestMuted, &[&name, &level]));
+        } else {
+            self.set_status(trf(Msg::StatusLoudest, &[&name, &level]));
+        }
+        Ok(Some((index, level_db)))
+    }
+
+    /// Write the replay buffer (the last `seconds`, default all of it) to a new WAV file
+    fn save_replay(&mut self, seconds: Option<f32>) -> Result<PathBuf> {
+        let directory = self
+            .config
+            .replay_buffer
+            .as_ref()
+            .and_then(|r| r.directory.as_deref())
+            .unwrap_or(".");
+        let unix_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = Path::new(directory).join(format!(
+            "{}-replay-{}.wav",
+            self.client_name.replace(' ', "_"),
+            unix_time
+        ));
+
+        let length = self.audio_engine.save_replay(&path, seconds)?;
+        self.session_report.record_file(&path);
+        self.set_status(trf(
+            Msg::StatusReplaySaved,
+            &[&format!("{:.0}", length.as_secs_f32()), &path.display()],
+        ));
+        Ok(path)
+    }
+
+    /// Write the session report to the configured path (or a new file in the
+    /// working directory) and return the path
+    fn write_session_report(&mut self) -> Result<PathBuf> {
+        let path = self.session_report_path.clone().unwrap_or_else(|| {
+            let unix_time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            PathBuf::from(format!(
+                "{}-session-{}.txt",
+                self.client_name.replace(' ', "_"),
+                unix_time
+            ))
+        });
+        self.session_report
+            .write(&path, self.audio_engine.xrun_count())?;
+        self.set_status(trf(Msg::StatusReportSaved, &[&path.display()]));
+        Ok(path)
+    }
+
+    /// Start recording the configured output
+    fn start_recording(&mut self) -> Result<PathBuf> {
+        let Some(settings) = self.config.recorder.as_ref() else {
+            anyhow::bail!("No recorder configured");
+        };
+        if self.recording_path.is_some() {
+            anyhow::bail!("Already recording");
+        }
+        let unix_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let stem = Path::new(settings.directory.as_deref().unwrap_or(".")).join(format!(
+            "{}-recording-{}",
+            self.client_name.replace(' ', "_"),
+            unix_time
+        ));
+        let path = stem.with_extension(settings.format.extension());
+
+        let freewheel = settings.freewheel;
+        self.audio_engine
+            .start_recording(&stem.with_extension("rec.wav"))?;
+        self.set_status(trf(Msg::StatusRecording, &[&path.display()]));
+        if freewheel {
+            if let Err(e) = self.audio_engine.set_freewheel(true) {
+                log::warn!("{:#}", e);
+                self.set_status(trf(Msg::StatusFreewheelFailed, &[&e]));
+            }
+        }
+        self.recording_path = Some(path.clone());
+        Ok(path)
+    }
+
+    /// Stop recording and export the take in the background
+    fn stop_recording(&mut self) -> Result<PathBuf> {
+        let Some(path) = self.recording_path.take() else {
+            anyhow::bail!("Not recording");
+        };
+        // Back to real time before the last frames are collected
+        if let Err(e) = self.audio_engine.release_freewheel() {
+            log::warn!("{:#}", e);
+        }
+        let recording = self.audio_engine.stop_recording()?;
+        let settings = self
+            .config
+            .recorder
+            .clone()
+            .context("No recorder configured")?;
+
+        log::info!(
+            "Recorded {:.0} s at {} LUFS, exporting to {}",
+            recording.duration.as_secs_f32(),
+            recording
+                .loudness
+                .map_or("-inf".to_string(), |l| format!("{:.1}", l)),
+            path.display()
+        );
+        let target = path.clone();
+        let export = thread::spawn(move || export_recording(&recording, &settings, &target));
+        self.exports.push((path.clone(), export));
+        self.set_status(trf(Msg::StatusRecordExporting, &[&path.display()]));
+        Ok(path)
+    }
+
+    /// Report exports that have finished
+    fn poll_exports(&mut self) {
+        let mut i = 0;
+        while i < self.exports.len() {
+            if !self.exports[i].1.is_finished() {
+                i += 1;
+                continue;
+            }
+            let (path, export) = self.exports.remove(i);
+            match export.join() {
+                Ok(Ok(())) => {
+                    self.session_report.record_file(&path);
+                    self.set_status(trf(Msg::StatusRecordSaved, &[&path.display()]));
+                }
+                Ok(Err(e)) => {
+                    log::error!("Recording export failed: {:#}", e);
+                    self.set_status(trf(Msg::StatusRecordFailed, &[&format!("{:#}", e)]));
+                }
+                Err(_) => {
+                    self.set_status(trf(Msg::StatusRecordFailed, &[&"export thread panicked"]))
+                }
+            }
+        }
+    }
+
+    /// Toggle solo on the selected channel (inputs and outputs)
+    fn toggle_solo(&mut self) -> Result<()> {
+        let kind = self.selection_type;
+        if let Some(channel) = self.channels(kind).get(self.selected_channel) {
+            let soloed = !channel.soloed;
+            self.set_solo(kind, self.selected_channel, soloed)?;
+        }
+        Ok(())
+    }
+
+    /// Reset volume of the selected channel to 0 dB
+    fn reset_volume_to_zero(&mut self) -> Result<()> {
+        self.set_volume(self.selection_type, self.selected_channel, 0.0)
+    }
+
+    /// Handle pending commands from control socket clients
+    fn process_remote_commands(&mut self) {
+        let Some(server) = self.control_server.take() else {
+            return;
+        };
+
+        while let Some(request) = server.try_recv() {
+            let reply = match request.command {
+                Ok(RemoteCommand::State) => {
+                    for (kind, remote_kind) in [
+                        (SelectionType::Input, ChannelKind::Input),
+                        (SelectionType::Output, ChannelKind::Output),
+                        (SelectionType::Master, ChannelKind::Master),
+                        (SelectionType::Passthrough, ChannelKind::Passthrough),
+                    ] {
+                        for (i, channel) in self.channels(kind).iter().enumerate() {
+                            server.reply(
+                                request.client,
+                                remote::format_channel_state(
+                                    remote_kind,
+                                    i,
+                                    channel,
+                                    &self.config.db_display,
+                                ),
+                            );
+                        }
+                    }
+                    for input in 0..self.mixer_state.inputs.len() {
+                        for output in 0..self.mixer_state.outputs.len() {
+                            server.reply(
+                                request.client,
+                                remote::format_route(
+                                    input,
+                                    output,
+                                    self.mixer_state.routing.get(input, output),
+                                    &self.config.db_display,
+                                ),
+                            );
+                        }
+                    }
+                    Ok("end".to_string())
+                }
+                Ok(RemoteCommand::Topology) => Ok(remote::format_topology(&self.mixer_state)),
+                Ok(RemoteCommand::Hello { version }) => {
+                    let features = self.remote_features(request.access);
+                    remote::format_hello(version, &features, request.access)
+                }
+                Ok(RemoteCommand::Help) => {
+                    for line in remote::HELP_TEXT {
+                        server.reply(request.client, *line);
+                    }
+                    Ok("end".to_string())
+                }
+                Ok(RemoteCommand::Loudest { output, mute }) => self.remote_loudest(output, mute),
+                Ok(RemoteCommand::Replay { seconds }) => self
+                    .save_replay(seconds)
+                    .map(|path| format!("saved {}", path.display()))
+                    .map_err(|e| format!("{:#}", e)),
+                Ok(RemoteCommand::Report) => self
+                    .write_session_report()
+                    .map(|path| format!("saved {}", path.display()))
+                    .map_err(|e| format!("{:#}", e)),
+                Ok(RemoteCommand::Record { start: true }) => self
+                    .start_recording()
+                    .map(|path| format!("recording {}", path.display()))
+                    .map_err(|e| format!("{:#}", e)),
+                Ok(RemoteCommand::Record { start: false }) => self
+                    .stop_recording()
+                    .map(|path| format!("exporting {}", path.display()))
+                    .map_err(|e| format!("{:#}", e)),
+                Ok(RemoteCommand::Meters { mode, rate_hz }) => {
+                    server.subscribe_meters(request.client, mode, rate_hz);
+                    Ok("ok".to_string())
+                }
+                Ok(RemoteCommand::Feedback { rate_hz }) => {
+                    server.set_client_feedback(request.client, rate_hz);
+                    Ok("ok".to_string())
+                }
+                Ok(RemoteCommand::Tally { subscribe: None }) => {
+                    Ok(remote::format_tally(self.tally.on_air()))
+                }
+                Ok(RemoteCommand::Tally {
+                    subscribe: Some(subscribe),
+                }) => {
+                    server.subscribe_tally(request.client, subscribe);
+                    Ok("ok".to_string())
+                }
+                Ok(command) => self.execute_remote(command).map(|_| "ok".to_string()),
+                Err(e) => Err(e),
+            };
+
+            match reply {
+                Ok(line) => server.reply(request.client, line),
+                Err(e) => server.reply(request.client, format!("err {}", e)),
+            }
+        }
+
+        server.publish_meters(&self.mixer_state, &self.config.db_display);
+        server.publish_tally(self.tally.on_air());
+        server.publish_topology(&remote::format_topology(&self.mixer_state));
+        self.control_server = Some(server);
+    }
+
+    /// Features offered on the control socket with this config to a
+    /// client with `access`
+    fn remote_features(&self, access: ControlAccess) -> Vec<&'static str> {
+        if access == ControlAccess::Observe {
+            return remote::OBSERVER_FEATURES.to_vec();
+        }
+        let mut features = remote::CORE_FEATURES.to_vec();
+        if self.config.replay_buffer.is_some() {
+            features.push("replay");
+        }
+        if self.config.recorder.is_some() {
+            features.push("record");
+        }
+        features
+    }
+
+    /// Execute a channel control command from a remote client
+    fn execute_remote(&mut self, command: RemoteCommand) -> Result<(), String> {
+        let resolve = |app: &Self, kind: SelectionType, channel: &ChannelRef| {
+            channel
+                .resolve(app.channels(kind))
+                .ok_or_else(|| "no such channel".to_string())
+        };
+
+        let result = match command {
+            RemoteCommand::Volume {
+                kind,
+                channel,
+                volume_db,
+                relative,
+            } => {
+                let kind = kind.into();
+                let index = resolve(self, kind, &channel)?;
+                let base = if relative {
+                    self.channels(kind)[index].volume_db
+                } else {
+                    0.0
+                };
+                self.set_volume(kind, index, base + volume_db)
+            }
+            RemoteCommand::Mute {
+                kind,
+                channel,
+                switch,
+            } => {
+                let kind = kind.into();
+                let index = resolve(self, kind, &channel)?;
+                let muted = switch.apply(self.channels(kind)[index].muted);
+                self.set_mute(kind, index, muted)
+            }
+            RemoteCommand::Solo { channel, switch } => {
+                let index = resolve(self, SelectionType::Input, &channel)?;
+                let soloed = switch.apply(self.mixer_state.inputs[index].soloed);
+                self.set_solo(SelectionType::Input, index, soloed)
+            }
+            RemoteCommand::Eq {
+                channel,
+                band,
+                gain_db,
+            } => {
+                let index = resolve(self, SelectionType::Input, &channel)?;
+                let mut eq = self.mixer_state.inputs[index].eq.clone();
+                *eq.band_mut(band) = gain_db.clamp(-EQ_RANGE_DB, EQ_RANGE_DB);
+                self.set_eq(index, eq)
+            }
+            RemoteCommand::Route {
+                input,
+                output,
+                switch,
+                mono,
+                gain_db,
+            } => {
+                let input = resolve(self, SelectionType::Input, &input)?;
+                let output = resolve(self, SelectionType::Output, &output)?;
+                let current = self.mixer_state.routing.get(input, output);
+                let crosspoint = Crosspoint {
+                    enabled: switch.apply(current.enabled),
+                    gain_db: gain_db
+                        .unwrap_or(current.gain_db)
+                        .clamp(VOLUME_MIN_DB, VOLUME_MAX_DB),
+                    mono: mono.unwrap_or(current.mono),
+                };
+                self.set_route(input, output, crosspoint)
+            }
+            RemoteCommand::Ping => return Ok(()),
+            _ => return Err("unsupported command".to_string()),
+        };
+        result.map_err(|e| e.to_string())
+    }
+
+    /// Add inputs for application streams that started playing and drop
+    /// those of applications that are gone
+    fn process_app_streams(&mut self) {
+        let Some(settings) = self.config.app_streams.clone() else {
+            return;
         };
-
-        if self.selected_channel > 0 {
-            self.selected_channel -= 1;
-        } else if max_idx > 0 {
-            // Wrap around or switch section
-            match self.selection_type {
-                SelectionType::Input => {
-                    if !self.mixer_state.outputs.is_empty() {
-                        self.selection_type = SelectionType::Output;
-                        self.selected_channel = self.mixer_state.outputs.len() - 1;
-                    } else {
-                        self.selected_channel = max_idx - 1;
-                    }
-                }
-                SelectionType::Output => {
-                    if !self.mixer_state.inputs.is_empty() {
-                        self.selection_type = SelectionType::Input;
-                        self.selected_channel = self.mixer_state.inputs.len() - 1;
-                    } else {
-                        self.selected_channel = max_idx - 1;
-                    }
-                }
-            }
+        if self.app_streams_checked.elapsed() < APP_STREAM_POLL {
+            return;
+        }
+        self.app_streams_checked = Instant::now();
+
+        let ports = self.audio_engine.application_ports();
+        let names = |inputs: &[ChannelConfig]| -> Vec<(String, Vec<String>)> {
+            inputs
+                .iter()
+                .filter(|c| c.temporary)
+                .map(|c| (c.name.clone(), c.connect.clone()))
+                .collect()
+        };
+        let current = names(&self.config.inputs);
+        if names(&app_streams::wanted_inputs(&settings, &ports, &self.config)) == current {
+            return;
         }
-    }
 
-    /// Select the next channel
-    fn select_next(&mut self) {
-        let max_idx = match self.selection_type {
-            SelectionType::Input => self.mixer_state.inputs.len(),
-            SelectionType::Output => self.mixer_state.outputs.len(),
+        // The engine restarts with the new inputs; keep what was set live
+        self.store_state();
+        let mut config = self.config.clone();
+        config.inputs.retain(|c| !c.temporary);
+        config
+            .inputs
+            .extend(app_streams::wanted_inputs(&settings, &ports, &self.config));
+        let wanted = names(&config.inputs);
+        let listed = |inputs: &[(String, Vec<String>)], others: &[(String, Vec<String>)]| {
+            inputs
+                .iter()
+                .filter(|input| !others.iter().any(|other| other.0 == input.0))
+                .map(|input| input.0.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
         };
+        let (added, removed) = (listed(&wanted, &current), listed(&current, &wanted));
 
-        if self.selected_channel + 1 < max_idx {
-            self.selected_channel += 1;
-        } else {
-            // Wrap around or switch section
-            match self.selection_type {
-                SelectionType::Input => {
-                    if !self.mixer_state.outputs.is_empty() {
-                        self.selection_type = SelectionType::Output;
-                        self.selected_channel = 0;
-                    } else {
-                        self.selected_channel = 0;
-                    }
-                }
-                SelectionType::Output => {
-                    if !self.mixer_state.inputs.is_empty() {
-                        self.selection_type = SelectionType::Input;
-                        self.selected_channel = 0;
-                    } else {
-                        self.selected_channel = 0;
-                    }
-                }
-            }
+        if let Err(e) = config.validate().and_then(|()| self.apply_config(config)) {
+            log::error!("Application inputs not changed: {:#}", e);
+            self.set_status(trf(Msg::StatusAppStreamsFailed, &[&format!("{:#}", e)]));
+            return;
+        }
+        log::info!("Application inputs: added [{}], removed [{}]", added, removed);
+        if !added.is_empty() {
+            self.set_status(trf(Msg::StatusAppStreamAdded, &[&added]));
+        } else if !removed.is_empty() {
+            self.set_status(trf(Msg::StatusAppStreamRemoved, &[&removed]));
         }
     }
 
-    /// Toggle between input and output sections
-    fn toggle_section(&mut self) {
-        match self.selection_type {
-            SelectionType::Input => {
-                if !self.mixer_state.outputs.is_empty() {
-                    self.selection_type = SelectionType::Output;
-                    self.selected_channel = 0;
-                }
-            }
-            SelectionType::Output => {
-                if !self.mixer_state.inputs.is_empty() {
-                    self.selection_type = SelectionType::Input;
-                    self.selected_channel = 0;
+    /// Run scheduled actions that are due and step scheduled fades
+    fn process_schedule(&mut self) {
+        for action in self.scheduler.due(SystemTime::now()) {
+            log::info!("Schedule: {}", action.text);
+            let result = match (action.fade, action.command) {
+                (
+                    Some(duration),
+                    RemoteCommand::Volume {
+                        kind,
+                        channel,
+                        volume_db,
+                        relative,
+                    },
+                ) => channel
+                    .resolve(self.channels(kind.into()))
+                    .ok_or_else(|| "no such channel".to_string())
+                    .map(|index| {
+                        let from_db = self.channels(kind.into())[index].volume_db;
+                        let to_db = if relative { from_db + volume_db } else { volume_db };
+                        // A newer fade of the channel takes over
+                        self.volume_fades
+                            .retain(|f| (f.kind, f.index) != (kind, index));
+                        self.volume_fades.push(VolumeFade::new(
+                            kind,
+                            index,
+                            from_db,
+                            to_db,
+                            duration,
+                            Instant::now(),
+                        ));
+                    }),
+                (_, command) => self.execute_remote(command),
+            };
+            match result {
+                Ok(()) => self.set_status(trf(Msg::StatusScheduled, &[&action.text])),
+                Err(e) => {
+                    log::warn!("Scheduled '{}' failed: {}", action.text, e);
+                    self.set_status(trf(Msg::StatusScheduleFailed, &[&action.text, &e]));
                 }
             }
         }
-    }
 
-    /// Adjust volume of the selected channel
-    fn adjust_volume(&mut self, delta: f32) -> Result<()> {
-        match self.selection_type {
-            SelectionType::Input => {
-                if self.selected_channel < self.mixer_state.inputs.len() {
-                    let channel = &mut self.mixer_state.inputs[self.selected_channel];
-                    channel.adjust_volume(delta);
-                    self.audio_engine.send_control(ControlMsg::SetInputVolume {
-                        channel: self.selected_channel,
-                        volume_db: channel.volume_db,
-                    })?;
-                }
+        let now = Instant::now();
+        for fade in std::mem::take(&mut self.volume_fades) {
+            let (volume_db, done) = fade.volume_at(now);
+            if let Err(e) = self.set_volume(fade.kind.into(), fade.index, volume_db) {
+                log::warn!("Scheduled fade: {:#}", e);
             }
-            SelectionType::Output => {
-                if self.selected_channel < self.mixer_state.outputs.len() {
-                    let channel = &mut self.mixer_state.outputs[self.selected_channel];
-                    channel.adjust_volume(delta);
-                    self.audio_engine.send_control(ControlMsg::SetOutputVolume {
-                        channel: self.selected_channel,
-                        volume_db: channel.volume_db,
-                    })?;
-                }
+            if !done {
+                self.volume_fades.push(fade);
             }
         }
-        Ok(())
     }
 
-    /// Toggle mute on the selected channel
-    fn toggle_mute(&mut self) -> Result<()> {
-        match self.selection_type {
-            SelectionType::Input => {
-                if self.selected_channel < self.mixer_state.inputs.len() {
-                    self.mixer_state.inputs[self.selected_channel].muted =
-                        !self.mixer_state.inputs[self.selected_channel].muted;
-                    self.audio_engine.send_control(ControlMsg::ToggleInputMute {
-                        channel: self.selected_channel,
-                    })?;
-                }
-            }
-            SelectionType::Output => {
-                if self.selected_channel < self.mixer_state.outputs.len() {
-                    self.mixer_state.outputs[self.selected_channel].muted =
-                        !self.mixer_state.outputs[self.selected_channel].muted;
-                    self.audio_engine
-                        .send_control(ControlMsg::ToggleOutputMute {
-                            channel: self.selected_channel,
-                        })?;
-                }
-            }
+    /// Run a `loudest` control socket command and format its reply
+    fn remote_loudest(&mut self, output: Option<ChannelRef>, mute: bool) -> Result<String, String> {
+        let output = match output {
+            Some(output) => Some(
+                output
+                    .resolve(&self.mixer_state.outputs)
+                    .ok_or_else(|| "no such channel".to_string())?,
+            ),
+            None => None,
+        };
+        match self.find_loudest_input(output, mute) {
+            Ok(Some((index, level_db))) => Ok(format!(
+                "input {} {} {}",
+                index,
+                self.mixer_state.inputs[index].name.replace(' ', "_"),
+                self.config.db_display.format_token(level_db)
+            )),
+            Ok(None) => Err("no input is audible".to_string()),
+            Err(e) => Err(e.to_string()),
         }
-        Ok(())
     }
 
-    /// Toggle solo on the selected channel (input only)
-    fn toggle_solo(&mut self) -> Result<()> {
-        if self.selection_type == SelectionType::Input {
-            if self.selected_channel < self.mixer_state.inputs.len() {
-                self.mixer_state.inputs[self.selected_channel].soloed =
-                    !self.mixer_state.inputs[self.selected_channel].soloed;
-                self.audio_engine.send_control(ControlMsg::ToggleInputSolo {
-                    channel: self.selected_channel,
-                })?;
-            }
+    /// Set the terminal title to the current status if it changed
+    fn update_terminal_title(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        let Some(current) = &self.terminal_title else {
+            return Ok(());
+        };
+        let title = self.status_title();
+        if *current != title {
+            execute!(terminal.backend_mut(), SetTitle(&title))?;
+            self.terminal_title = Some(title);
         }
         Ok(())
     }
 
-    /// Reset volume of the selected channel to 0 dB
-    fn reset_volume_to_zero(&mut self) -> Result<()> {
-        match self.selection_type {
-            SelectionType::Input => {
-                if self.selected_channel < self.mixer_state.inputs.len() {
-                    self.mixer_state.inputs[self.selected_channel].volume_db = 0.0;
-                    self.audio_engine.send_control(ControlMsg::SetInputVolume {
-                        channel: self.selected_channel,
-                        volume_db: 0.0,
-                    })?;
-                }
+    /// Compact status for the terminal title, e.g. "rmixer ▶ Mic -6.0 dB M ● REC"
+    fn status_title(&self) -> String {
+        let mut title = String::from("rmixer");
+        if let Some(channel) = self.channels(self.selection_type).get(self.selected_channel) {
+            let volume = self.config.db_display.with_suffix(true);
+            title.push_str(&format!(
+                " ▶ {} {}",
+                channel.name,
+                volume.format_signed(channel.volume_db)
+            ));
+            if channel.muted {
+                title.push_str(" M");
             }
-            SelectionType::Output => {
-                if self.selected_channel < self.mixer_state.outputs.len() {
-                    self.mixer_state.outputs[self.selected_channel].volume_db = 0.0;
-                    self.audio_engine.send_control(ControlMsg::SetOutputVolume {
-                        channel: self.selected_channel,
-                        volume_db: 0.0,
-                    })?;
-                }
+            if channel.soloed {
+                title.push_str(" S");
             }
         }
-        Ok(())
+        let logging = self.meter_logger.is_some() || self.loudness_logger.is_some();
+        if logging || self.recording_path.is_some() {
+            title.push_str(" ● REC");
+        }
+        match self.audio_engine.health() {
+            EngineHealth::Running => {}
+            EngineHealth::Stalled => title.push_str(&format!(" ⚠ {}", tr(Msg::TitleStalled))),
+            EngineHealth::Shutdown => title.push_str(&format!(" ⚠ {}", tr(Msg::TitleShutdown))),
+        }
+        title
     }
 
     /// Render the UI
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
+        if area.width < MIN_FULL_SIZE.0 || area.height < MIN_FULL_SIZE.1 {
+            self.render_micro(frame, area);
+            return;
+        }
 
         // Main layout: title bar, channels, help bar
         let main_chunks = Layout::default()
@@ -446,21 +3009,708 @@ impl App {
         // Title bar
         self.render_title(frame, main_chunks[0]);
 
-        // Channels area
-        self.render_channels(frame, main_chunks[1]);
+        // Channels area (or the view of the active mode)
+        match &self.mode {
+            Mode::Editor(editor) => editor.render(frame, main_chunks[1]),
+            Mode::Matrix(matrix) => matrix.render(frame, main_chunks[1], &self.mixer_state),
+            Mode::Eq(eq_view) => eq_view.render(frame, main_chunks[1], &self.mixer_state),
+            Mode::Multiband(view) => view.render(frame, main_chunks[1], &self.mixer_state),
+            Mode::Calibration(view) => {
+                view.render(frame, main_chunks[1], &self.mixer_state, Instant::now())
+            }
+            Mode::Balance(view) => view.render(frame, main_chunks[1], &self.mixer_state),
+            Mode::Spectrogram(view) => view.render(frame, main_chunks[1], &self.mixer_state),
+            Mode::Oscilloscope(view) => view.render(frame, main_chunks[1], &self.mixer_state),
+            Mode::Rta(view) => view.render(frame, main_chunks[1], &self.mixer_state),
+            Mode::Feedback(view) => view.render(frame, main_chunks[1], &self.mixer_state),
+            Mode::Normal | Mode::Overlay(_) | Mode::Flip(_) => {
+                self.render_strips(frame, main_chunks[1])
+            }
+        }
 
         // Help bar
         self.render_help(frame, main_chunks[2]);
+
+        match self.mode {
+            Mode::Overlay(Overlay::Contributions(output)) => {
+                self.render_contributions(frame, output)
+            }
+            Mode::Overlay(Overlay::Goniometer(output)) => self.render_goniometer(frame, output),
+            Mode::Overlay(Overlay::Diagnostics) => self.render_diagnostics(frame),
+            Mode::Overlay(Overlay::Clips) => self.render_clips(frame),
+            Mode::Overlay(Overlay::BigMeter) => self.render_big_meter(frame),
+            _ => {}
+        }
+
+        if let Some(reminder) = self.reminders.due(Instant::now()) {
+            self.render_reminder(frame, reminder);
+        }
+
+        // Audio engine alert (meters would otherwise just freeze)
+        let alert = Style::default().fg(Color::White).bg(Color::Red);
+        match self.audio_engine.health() {
+            EngineHealth::Running => {}
+            EngineHealth::Stalled => self.render_alert(frame, tr(Msg::AlertStalled), alert),
+            EngineHealth::Shutdown => self.render_alert(frame, tr(Msg::AlertShutdown), alert),
+        }
+
+        // Confirmation prompt on top of everything
+        if let Some(substitution) = self.pending_substitutions.first() {
+            self.render_substitution_prompt(frame, substitution);
+        }
+    }
+
+    /// Render the selected channel alone for terminals too small for the
+    /// strips: name, a meter row per port, fader value and mute/solo
+    fn render_micro(&self, frame: &mut Frame, area: Rect) {
+        let kind = self.selection_type;
+        let Some(channel) = self.channels(kind).get(self.selected_channel) else {
+            return;
+        };
+        let section = match kind {
+            SelectionType::Input => Msg::SectionInputs,
+            SelectionType::Output => Msg::SectionOutputs,
+            SelectionType::Master => Msg::SectionMaster,
+            SelectionType::Passthrough => Msg::SectionPassthrough,
+        };
+        let mode = if self.pending_substitutions.is_empty() {
+            self.mode.label()
+        } else {
+            Some(Msg::ModeConfirm)
+        };
+
+        let meter_rows = area.height.saturating_sub(2).min(channel.port_count as u16);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(meter_rows),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        let mut title = vec![
+            Span::styled(format!("{} ", tr(section)), Style::default().fg(Color::Gray)),
+            Span::styled(
+                channel.name.clone(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ];
+        if let Some(mode) = mode {
+            title.push(Span::styled(
+                format!(" [{}]", tr(mode)),
+                Style::default().fg(Color::Black).bg(Color::Cyan),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(title)), rows[0]);
+
+        for port in 0..meter_rows {
+            let row = Rect {
+                y: rows[1].y + port,
+                height: 1,
+                ..rows[1]
+            };
+            let port = port as usize;
+            let meter = HorizontalMeter::new(channel.current_peaks[port])
+                .peak_hold(channel.peak_hold[port])
+                .scale(self.config.meter_scale);
+            frame.render_widget(meter, row);
+        }
+
+        let flag = |on: bool, label: &'static str, color: Color| {
+            if on {
+                Span::styled(label, Style::default().fg(Color::Black).bg(color))
+            } else {
+                Span::styled(label, Style::default().fg(Color::DarkGray))
+            }
+        };
+        let mut controls = vec![
+            Span::raw(format!("{}  ", self.config.db_display.format_signed(channel.volume_db))),
+            flag(channel.muted, "M", Color::Red),
+        ];
+        if kind == SelectionType::Input {
+            controls.push(Span::raw(" "));
+            controls.push(flag(channel.soloed, "S", Color::Yellow));
+        }
+        frame.render_widget(Paragraph::new(Line::from(controls)), rows[2]);
+    }
+
+    /// Render the channel strips, with the loudness pane next to them when
+    /// the split view is on and the terminal is wide enough
+    fn render_strips(&self, frame: &mut Frame, area: Rect) {
+        let area = match self.mode {
+            Mode::Flip(layer) => {
+                let rows = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(1), Constraint::Min(0)])
+                    .split(area);
+                self.render_flip_banner(frame, rows[0], layer);
+                rows[1]
+            }
+            _ => area,
+        };
+        if !self.split_view || area.width < SPLIT_MIN_WIDTH {
+            self.render_channels(frame, area);
+            return;
+        }
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(area);
+        self.render_channels(frame, panes[0]);
+        self.loudness.render(
+            frame,
+            panes[1],
+            &self.mixer_state,
+            self.analysis_channel,
+            self.analysis_focus,
+        );
+    }
+
+    /// Render the banner naming the active flip layer
+    fn render_flip_banner(&self, frame: &mut Frame, area: Rect, layer: FlipLayer) {
+        let parameter = match layer {
+            FlipLayer::Trim => tr(Msg::FlipTrim).to_string(),
+            FlipLayer::DeviceGain => tr(Msg::FlipDeviceGain).to_string(),
+            FlipLayer::AutomixWeight => tr(Msg::FlipAutomix).to_string(),
+            FlipLayer::Send(output) => {
+                let name = self.mixer_state.outputs.get(output).map_or("", |c| c.name.as_str());
+                trf(Msg::FlipSend, &[&name])
+            }
+        };
+        let banner = Paragraph::new(trf(Msg::FlipBanner, &[&parameter]))
+            .alignment(Alignment::Center)
+            .style(
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            );
+        frame.render_widget(banner, area);
     }
 
     /// Render the title bar
     fn render_title(&self, frame: &mut Frame, area: Rect) {
         let title = format!(" RMixer - {} ", self.client_name);
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Cyan))
             .title(title);
+        let mode = if self.pending_substitutions.is_empty() {
+            self.mode.label()
+        } else {
+            Some(Msg::ModeConfirm)
+        };
+        if let Some(mode) = mode {
+            let label = Span::styled(
+                format!(" [{}] ", tr(mode)),
+                Style::default().fg(Color::Black).bg(Color::Cyan),
+            );
+            block = block.title_top(Line::from(label).right_aligned());
+        }
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        if let Some(elapsed) = self.audio_engine.recording_elapsed() {
+            let secs = elapsed.as_secs();
+            let mut text = format!("● REC {:02}:{:02}", secs / 60, secs % 60);
+            if self.audio_engine.recording_waiting() {
+                text.push_str(&format!(" ({})", tr(Msg::RecordWaiting)));
+            }
+            if self.audio_engine.is_freewheeling() {
+                text.push_str(&format!(" ({})", tr(Msg::RecordFreewheeling)));
+            }
+            let recording = Paragraph::new(text)
+                .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+            frame.render_widget(recording, inner);
+        }
+
+        if let Some(click) = self.config.click.as_ref().filter(|_| self.click_running) {
+            let text = format!("♩ {:.0} BPM {}/4", click.tempo_bpm, click.beats_per_bar);
+            let running = Paragraph::new(text)
+                .style(Style::default().fg(Color::Green))
+                .alignment(Alignment::Center);
+            frame.render_widget(running, inner);
+        }
+
+        if let Some((message, since)) = &self.status {
+            if since.elapsed() < STATUS_DURATION {
+                let status = Paragraph::new(message.as_str())
+                    .style(Style::default().fg(Color::Yellow))
+                    .alignment(Alignment::Right);
+                frame.render_widget(status, inner);
+            }
+        }
+    }
+
+    /// Render the confirmation prompt for a substitute connection
+    fn render_substitution_prompt(&self, frame: &mut Frame, substitution: &Substitution) {
+        let (before, after) = tr(Msg::MissingDeviceFor)
+            .split_once("{}")
+            .unwrap_or((tr(Msg::MissingDeviceFor), ""));
+        let all = trf(Msg::PromptAll, &[&self.pending_substitutions.len()]);
+        let text = vec![
+            Line::from(vec![
+                Span::raw(before),
+                Span::styled(
+                    substitution.local_port.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(after),
+            ]),
+            Line::from(Span::styled(
+                format!("  {}", substitution.missing),
+                Style::default().fg(Color::Red),
+            )),
+            Line::from(tr(Msg::ConnectClosestMatch)),
+            Line::from(Span::styled(
+                format!("  {}", substitution.replacement),
+                Style::default().fg(Color::Green),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("y", Style::default().fg(Color::Yellow)),
+                Span::raw(format!(" {}  ", tr(Msg::PromptYes))),
+                Span::styled("n", Style::default().fg(Color::Yellow)),
+                Span::raw(format!(" {}  ", tr(Msg::PromptNo))),
+                Span::styled("a", Style::default().fg(Color::Yellow)),
+                Span::raw(format!(" {}  ", all)),
+                Span::styled("Esc", Style::default().fg(Color::Yellow)),
+                Span::raw(format!(" {}", tr(Msg::PromptSkipAll))),
+            ]),
+        ];
+
+        let width = text.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 4;
+        let area = centered_rect(width, text.len() as u16 + 2, frame.area());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(format!(" {} ", tr(Msg::MissingDeviceTitle)));
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    /// Render a prominent alert box across the top of the channel area
+    fn render_alert(&self, frame: &mut Frame, message: &str, style: Style) {
+        let area = frame.area();
+        let width = (message.chars().count() as u16 + 4).min(area.width);
+        let alert_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + 3.min(area.height),
+            width,
+            height: 3.min(area.height.saturating_sub(3)),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+        let text = Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .style(style.add_modifier(Modifier::BOLD))
+            .block(block);
+        frame.render_widget(Clear, alert_area);
+        frame.render_widget(text, alert_area);
+    }
+
+    /// Render a flashing banner for a solo or mute left engaged
+    fn render_reminder(&self, frame: &mut Frame, reminder: Reminder) {
+        let minutes = |since: Duration| since.as_secs() / 60;
+        let message = match reminder {
+            Reminder::Solo(since) => trf(Msg::ReminderSolo, &[&minutes(since)]),
+            Reminder::Muted { input, since } => {
+                let name = self.mixer_state.inputs.get(input).map_or("", |c| c.name.as_str());
+                trf(Msg::ReminderMuted, &[&name, &minutes(since)])
+            }
+        };
+        let message = format!("{} ({})", message, tr(Msg::ReminderLater));
+
+        // Alternate the colors twice a second
+        let phase = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() / 500);
+        let style = if phase.is_multiple_of(2) {
+            Style::default().fg(Color::Black).bg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::Yellow).bg(Color::Black)
+        };
+        self.render_alert(frame, &message, style);
+    }
+
+    /// Render the control latency diagnostics overlay
+    fn render_diagnostics(&self, frame: &mut Frame) {
+        let diag = self.audio_engine.control_diagnostics();
+        let capacity = self.audio_engine.capacity();
+        let ms = |d: Duration| format!("{:7.2} ms", d.as_secs_f64() * 1000.0);
+        let row = |label: &str, value: String, style: Style| {
+            Line::from(vec![
+                Span::styled(format!("  {:<22}", label), Style::default().fg(Color::Gray)),
+                Span::styled(value, style),
+            ])
+        };
+
+        let normal = Style::default().fg(Color::White);
+        let warn = |bad: bool| {
+            if bad {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                normal
+            }
+        };
+        let latency = diag.latency;
+        let mut text = vec![
+            Line::from(Span::styled(
+                tr(Msg::DiagLatency),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            row(
+                tr(Msg::DiagLast),
+                ms(latency.last),
+                warn(latency.last > CONTROL_LATENCY_LIMIT),
+            ),
+            row(tr(Msg::DiagAverage), ms(latency.average), normal),
+            row(
+                tr(Msg::DiagMax),
+                ms(latency.max),
+                warn(latency.max > CONTROL_LATENCY_LIMIT),
+            ),
+            row(
+                &trf(Msg::DiagLate, &[&CONTROL_LATENCY_LIMIT.as_millis()]),
+                latency.late.to_string(),
+                warn(latency.late > 0),
+            ),
+            row(tr(Msg::DiagApplied), latency.applied.to_string(), normal),
+            row(
+                tr(Msg::DiagDropped),
+                latency.dropped.to_string(),
+                warn(latency.dropped > 0),
+            ),
+            row(
+                tr(Msg::DiagQueued),
+                format!("{} / {}", diag.queued, diag.capacity),
+                warn(diag.queued * 2 > diag.capacity),
+            ),
+            row(tr(Msg::DiagPeriod), ms(diag.period), normal),
+            row(
+                tr(Msg::DiagLoad),
+                trf(
+                    Msg::DiagLoadValue,
+                    &[
+                        &format!("{:.0}", capacity.load() * 100.0),
+                        &capacity.ports,
+                        &capacity.crosspoints,
+                    ],
+                ),
+                warn(capacity.is_over_budget()),
+            ),
+            Line::from(""),
+            Line::from(Span::styled(
+                tr(Msg::DiagInputPorts),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+        ];
+
+        // Only ports without signal are listed: unconnected or fed zeros
+        let ports = self.config.inputs.iter().flat_map(|c| &c.ports);
+        let statuses = self.audio_engine.input_port_statuses();
+        let mut all_active = true;
+        for (port, status) in ports.zip(statuses) {
+            let (value, style) = match status {
+                PortStatus::Active => continue,
+                PortStatus::Disconnected => (
+                    tr(Msg::DiagPortDisconnected).to_string(),
+                    Style::default().fg(Color::Yellow),
+                ),
+                PortStatus::Silent => (
+                    trf(Msg::DiagPortSilent, &[&SILENT_PORT_TIMEOUT.as_secs()]),
+                    warn(true),
+                ),
+            };
+            all_active = false;
+            text.push(row(port, value, style));
+        }
+        if all_active {
+            text.push(Line::from(Span::styled(
+                format!("  {}", tr(Msg::DiagPortsActive)),
+                Style::default().fg(Color::Green),
+            )));
+        }
+        text.push(Line::from(""));
+        text.push(Line::from(key_hints(&[
+            ("r", Msg::DiagReset),
+            ("d/Esc", Msg::DiagClose),
+        ])));
+
+        let width = text.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 4;
+        let area = centered_rect(width, text.len() as u16 + 2, frame.area());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", tr(Msg::DiagTitle)));
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    /// Render the clip events of the session, newest first
+    fn render_clips(&self, frame: &mut Frame) {
+        let events = self.session_report.clip_events();
+        let dropped = self.session_report.clips_dropped();
+        let hints = Line::from(key_hints(&[("L/Esc", Msg::DiagClose)]));
+        // Borders, the hints and the blank line above them
+        let rows = (frame.area().height as usize).saturating_sub(4).max(1);
+
+        let mut text = Vec::new();
+        if dropped > 0 {
+            text.push(Line::from(Span::styled(
+                trf(Msg::ClipsDropped, &[&dropped]),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        let shown = rows.saturating_sub(text.len());
+        for event in events.iter().rev().take(shown) {
+            text.push(Line::from(vec![
+                Span::styled(
+                    format!("{}  ", format_utc(event.unix_secs)),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    format!("+{}  ", format_elapsed(event.at)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:>6} dBFS  ", format_db(event.peak)),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(event.channel.clone(), Style::default().fg(Color::White)),
+            ]));
+        }
+        if events.is_empty() {
+            text.push(Line::from(Span::styled(
+                tr(Msg::ClipsNone),
+                Style::default().fg(Color::Green),
+            )));
+        }
+        text.push(Line::from(""));
+        text.push(hints);
+
+        let width = text.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 4;
+        let area = centered_rect(width, text.len() as u16 + 2, frame.area());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", trf(Msg::ClipsTitle, &[&(events.len() + dropped)])));
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    /// Render the selected channel across the whole terminal
+    fn render_big_meter(&self, frame: &mut Frame) {
+        let kind = self.selection_type;
+        let Some(channel) = self.channels(kind).get(self.selected_channel) else {
+            return;
+        };
+        let section = match kind {
+            SelectionType::Input => Msg::SectionInputs,
+            SelectionType::Output => Msg::SectionOutputs,
+            SelectionType::Master => Msg::SectionMaster,
+            SelectionType::Passthrough => Msg::SectionPassthrough,
+        };
+
+        let area = frame.area();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", tr(Msg::ModeBigMeter)));
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+        let mut meter = BigMeter::new(channel, format!("{} {}", tr(section), channel.name))
+            .display(self.config.db_display)
+            .scale(self.config.meter_scale)
+            .reductions(self.big_meter_reductions(kind, self.selected_channel));
+        if matches!(kind, SelectionType::Input | SelectionType::Output) {
+            meter = meter.soloable();
+        }
+        frame.render_widget(meter, rows[0]);
+        let hints = key_hints(&[
+            ("←/→", Msg::HelpSelect),
+            ("↑/↓", Msg::HelpVolume),
+            ("m", Msg::HelpMute),
+            ("B/Esc", Msg::DiagClose),
+        ]);
+        frame.render_widget(Paragraph::new(Line::from(hints)), rows[1]);
+    }
+
+    /// Gain reduction stages of a channel for the big meter, with their
+    /// current reduction in dB
+    fn big_meter_reductions(&self, kind: SelectionType, index: usize) -> Vec<(&'static str, f32)> {
+        let Some(channel) = self.channels(kind).get(index) else {
+            return Vec::new();
+        };
+        let mut reductions = Vec::new();
+        match kind {
+            SelectionType::Input => {
+                let Some(config) = self.config.inputs.get(index) else {
+                    return reductions;
+                };
+                if config.deesser.is_some() {
+                    reductions.push(("DE-ESSER", channel.deesser_db));
+                }
+                if config.protect.is_some() {
+                    reductions.push(("DIP", channel.dip_db));
+                }
+                if channel.automix_weight_db.is_some() {
+                    reductions.push(("AUTOMIX", -channel.automix_db));
+                }
+            }
+            SelectionType::Output => {
+                let Some(config) = self.config.outputs.get(index) else {
+                    return reductions;
+                };
+                if config.auto_trim.is_some() {
+                    reductions.push(("AUTO TRIM", channel.auto_trim_db));
+                }
+                if config.limiter.is_some() {
+                    reductions.push(("LIMITER", channel.limiter_db));
+                }
+                if channel.multiband.is_some() {
+                    let bands = ["MB LOW", "MB MID", "MB HIGH"];
+                    reductions.extend(bands.into_iter().zip(channel.multiband_db));
+                }
+            }
+            SelectionType::Master | SelectionType::Passthrough => {}
+        }
+        reductions
+    }
+
+    /// Render the overlay of what each input sends into an output
+    fn render_contributions(&self, frame: &mut Frame, output: usize) {
+        let Some(bus) = self.mixer_state.outputs.get(output) else {
+            return;
+        };
+        let contributions = self.mixer_state.contributions(output);
+        let bus_power: f32 = contributions.iter().flatten().map(|(peak, _)| peak * peak).sum();
+        let loudest = self.mixer_state.loudest_input(Some(output)).map(|(i, _)| i);
+
+        const METER_WIDTH: u16 = 30;
+        let name_width = self
+            .mixer_state
+            .inputs
+            .iter()
+            .map(|c| c.name.chars().count())
+            .max()
+            .unwrap_or(0) as u16;
+        let hints = Line::from(key_hints(&[("i/Esc", Msg::DiagClose)]));
+        let width = (name_width + METER_WIDTH + 21).max(hints.width() as u16) + 2;
+        let height = contributions.len() as u16 + 4;
+        let area = centered_rect(width, height, frame.area());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", trf(Msg::ContribTitle, &[&bus.name])));
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        for (i, contribution) in contributions.iter().enumerate() {
+            let y = inner.y + i as u16;
+            if y >= inner.bottom().saturating_sub(2) {
+                break;
+            }
+            let row = |x: u16, width: u16| {
+                let x = inner.x + x;
+                Rect {
+                    x,
+                    y,
+                    width: width.min(inner.right().saturating_sub(x)),
+                    height: 1,
+                }
+            };
+
+            let mut name_style = Style::default().fg(Color::White);
+            if loudest == Some(i) {
+                name_style = name_style.add_modifier(Modifier::BOLD);
+            }
+            let name = Span::styled(self.mixer_state.inputs[i].name.clone(), name_style);
+            frame.render_widget(Paragraph::new(name), row(1, name_width));
+
+            let meter_x = name_width + 3;
+            let Some((peak, held)) = *contribution else {
+                let off = Span::styled(
+                    tr(Msg::ContribNotRouted),
+                    Style::default().fg(Color::DarkGray),
+                );
+                frame.render_widget(Paragraph::new(off), row(meter_x, METER_WIDTH));
+                continue;
+            };
+            frame.render_widget(
+                HorizontalMeter::new(peak)
+                    .peak_hold(held)
+                    .scale(self.config.meter_scale),
+                row(meter_x, METER_WIDTH),
+            );
+
+            let db = MeterData::linear_to_db(peak);
+            let display = self.config.db_display.with_suffix(true);
+            let level = if db > VOLUME_MIN_DB || display.neg_inf_below_db.is_some() {
+                format!("{:>9}", display.format(db))
+            } else {
+                "    -- dB".to_string()
+            };
+            let share = if bus_power > 0.0 {
+                format!("{:4.0} %", peak * peak / bus_power * 100.0)
+            } else {
+                String::new()
+            };
+            let value = Span::styled(
+                format!("{} {}", level, share),
+                Style::default().fg(Color::Gray),
+            );
+            frame.render_widget(Paragraph::new(value), row(meter_x + METER_WIDTH + 1, 17));
+        }
+
+        let hint_area = Rect {
+            y: inner.bottom().saturating_sub(1),
+            height: 1,
+            ..inner
+        };
+        frame.render_widget(Paragraph::new(hints), hint_area);
+    }
+
+    /// Render the goniometer and correlation meter of a stereo output
+    fn render_goniometer(&self, frame: &mut Frame, output: usize) {
+        let Some(bus) = self.mixer_state.outputs.get(output) else {
+            return;
+        };
+        let hints = Line::from(key_hints(&[("P/Esc", Msg::DiagClose)]));
+        let area = centered_rect(44, 24, frame.area());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", trf(Msg::GonioTitle, &[&bus.name])));
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
         frame.render_widget(block, area);
+
+        let plot = Rect {
+            height: inner.height.saturating_sub(1),
+            ..inner
+        };
+        frame.render_widget(
+            Goniometer::new(&self.goniometer).correlation(bus.correlation),
+            plot,
+        );
+        let hint_area = Rect {
+            y: inner.bottom().saturating_sub(1),
+            height: 1,
+            ..inner
+        };
+        frame.render_widget(Paragraph::new(hints), hint_area);
     }
 
     /// Render all channels
@@ -478,6 +3728,19 @@ impl App {
         let input_ratio = total_inputs as f32 / total_channels as f32;
         let output_ratio = total_outputs as f32 / total_channels as f32;
 
+        // Passthrough and master strips sit on the far right with a fixed width
+        let passthrough_count = self.mixer_state.passthrough.len() as u16;
+        let sections = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(passthrough_count.min(1)), // Separator
+                Constraint::Length(passthrough_count * MASTER_WIDTH), // Passthrough
+                Constraint::Length(1), // Separator
+                Constraint::Length(MASTER_WIDTH), // Master
+            ])
+            .split(area);
+
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
@@ -485,7 +3748,7 @@ impl App {
                 Constraint::Length(1), // Separator
                 Constraint::Percentage((output_ratio * 100.0) as u16),
             ])
-            .split(area);
+            .split(sections[0]);
 
         // Render inputs
         if !self.mixer_state.inputs.is_empty() {
@@ -493,9 +3756,8 @@ impl App {
                 frame,
                 chunks[0],
                 &self.mixer_state.inputs,
-                "INPUTS",
-                true,
-                self.selection_type == SelectionType::Input,
+                tr(Msg::SectionInputs),
+                SelectionType::Input,
             );
         }
 
@@ -511,11 +3773,38 @@ impl App {
                 frame,
                 chunks[2],
                 &self.mixer_state.outputs,
-                "OUTPUTS",
-                false,
-                self.selection_type == SelectionType::Output,
+                tr(Msg::SectionOutputs),
+                SelectionType::Output,
+            );
+        }
+
+        // Render passthrough channels
+        if !self.mixer_state.passthrough.is_empty() {
+            let sep = Block::default()
+                .borders(Borders::LEFT)
+                .border_style(Style::default().fg(Color::DarkGray));
+            frame.render_widget(sep, sections[1]);
+            self.render_channel_section(
+                frame,
+                sections[2],
+                &self.mixer_state.passthrough,
+                tr(Msg::SectionPassthrough),
+                SelectionType::Passthrough,
             );
         }
+
+        // Render master
+        let sep = Block::default()
+            .borders(Borders::LEFT)
+            .border_style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(sep, sections[3]);
+        self.render_channel_section(
+            frame,
+            sections[4],
+            std::slice::from_ref(&self.mixer_state.master),
+            tr(Msg::SectionMaster),
+            SelectionType::Master,
+        );
     }
 
     /// Render a section of channels (inputs or outputs)
@@ -525,9 +3814,10 @@ impl App {
         area: Rect,
         channels: &[ChannelState],
         title: &str,
-        is_input: bool,
-        is_selected_section: bool,
+        kind: SelectionType,
     ) {
+        let is_input = kind == SelectionType::Input;
+        let is_selected_section = self.selection_type == kind;
         let section_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(1), Constraint::Min(5)])
@@ -562,36 +3852,103 @@ impl App {
             .constraints(constraints)
             .split(strip_area);
 
+        let now = Instant::now();
         for (i, channel) in channels.iter().enumerate() {
-            let selected =
-                is_selected_section && is_input == (self.selection_type == SelectionType::Input)
-                    && i == self.selected_channel
-                    && is_selected_section;
-            let strip = ChannelStrip::new(channel, is_input).selected(selected);
+            let selected = is_selected_section && i == self.selected_channel;
+            let mut strip = ChannelStrip::new(channel, is_input)
+                .selected(selected)
+                .display(self.config.db_display)
+                .scale(self.config.meter_scale)
+                .signal(channel.signal_status(now));
+            if kind == SelectionType::Output {
+                strip = strip.soloable();
+            }
+            if is_input {
+                if !channel.soloed && solo::input_soloed(&self.mixer_state, i) {
+                    strip = strip.auditioned();
+                }
+                let routes = (0..self.mixer_state.outputs.len())
+                    .map(|output| self.mixer_state.routing.get(i, output).mode())
+                    .collect();
+                strip = strip.routes(routes);
+                if let Some(source) = self.active_sources.get(i).and_then(|s| s.checked_sub(1)) {
+                    strip = strip.source(&self.config.inputs[i].alternates[source].name);
+                }
+                if let Mode::Flip(layer) = self.mode {
+                    strip = strip.flipped(self.flip_value(layer, i));
+                }
+            }
             frame.render_widget(strip, strip_chunks[i]);
         }
     }
 
     /// Render the help bar
     fn render_help(&self, frame: &mut Frame, area: Rect) {
-        let help_text = Line::from(vec![
-            Span::styled("←/→", Style::default().fg(Color::Yellow)),
-            Span::raw(" Sel "),
-            Span::styled("↑/↓", Style::default().fg(Color::Yellow)),
-            Span::raw(" Vol "),
-            Span::styled("0", Style::default().fg(Color::Yellow)),
-            Span::raw(" 0dB "),
-            Span::styled("m", Style::default().fg(Color::Yellow)),
-            Span::raw(" Mute "),
-            Span::styled("s", Style::default().fg(Color::Yellow)),
-            Span::raw(" Solo "),
-            Span::styled("Tab", Style::default().fg(Color::Yellow)),
-            Span::raw(" Switch "),
-            Span::styled("q", Style::default().fg(Color::Yellow)),
-            Span::raw(" Quit"),
-        ]);
+        let help_text = Line::from(key_hints(&[
+            ("←/→", Msg::HelpSelect),
+            ("↑/↓", Msg::HelpVolume),
+            ("⇧↑/↓", Msg::HelpTrim),
+            ("0", Msg::HelpZeroDb),
+            ("m", Msg::HelpMute),
+            ("s", Msg::HelpSolo),
+            ("z/x/c", Msg::HelpEq),
+            ("h/l", Msg::HelpFilters),
+            ("M", Msg::HelpMidSide),
+            ("[/]/{/}", Msg::HelpMidSideLevels),
+            ("o/O", Msg::HelpMeterPoint),
+            ("v/V", Msg::HelpSplit),
+            ("k", Msg::HelpCalibrate),
+            ("b", Msg::HelpSource),
+            ("g", Msg::HelpFlip),
+            ("⌫", Msg::HelpResetMeters),
+            ("p", Msg::HelpEqBands),
+            ("Tab", Msg::HelpSwitch),
+            ("e", Msg::HelpEdit),
+            ("1-9", Msg::HelpRoute),
+            ("r", Msg::HelpMatrix),
+            ("f/F", Msg::HelpLoudest),
+            ("i", Msg::HelpContributions),
+            ("P", Msg::HelpGoniometer),
+            ("S", Msg::HelpSpectrogram),
+            ("A", Msg::HelpRta),
+            ("N", Msg::HelpFeedback),
+            ("d", Msg::HelpDiagnostics),
+            ("L", Msg::HelpClips),
+            ("B", Msg::HelpBigMeter),
+            ("K", Msg::HelpBalance),
+            ("w", Msg::HelpReplay),
+            ("W", Msg::HelpRecord),
+            ("R", Msg::HelpReport),
+            ("^Z", Msg::HelpSuspend),
+            ("q", Msg::HelpQuit),
+        ]));
 
         let help = Paragraph::new(help_text).style(Style::default().fg(Color::Gray));
         frame.render_widget(help, area);
     }
 }
+
+/// Key hint spans for a help bar: highlighted key followed by its label
+pub(super) fn key_hints(hints: &[(&'static str, Msg)]) -> Vec<Span<'static>> {
+    hints
+        .iter()
+        .flat_map(|&(key, msg)| {
+            [
+                Span::styled(key, Style::default().fg(Color::Yellow)),
+                Span::raw(format!(" {} ", tr(msg))),
+            ]
+        })
+        .collect()
+}
+
+/// A rectangle of the given size centered in `area` (clamped to fit)
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
@@ -0,0 +1,317 @@
+//! Stereo balance measurement
+//!
+//! Compares the long-term energy of the left and right ports of a stereo
+//! input and suggests the balance that evens them out, for a source that
+//! leans one way all the time (a miswired console send, a one-sided feed)
+//! rather than with the program. Levels are taken before the balance in
+//! effect, so a corrected input can be measured again. Only blocks with
+//! signal count, and a correction is offered once most seconds of signal
+//! lean the same way as the whole measurement.
+
+use std::time::{Duration, Instant};
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::audio::balance_gains;
+use crate::config::BALANCE_RANGE_DB;
+use crate::i18n::{tr, trf, Msg};
+use crate::ipc::{MeterData, MixerState};
+
+use super::app::key_hints;
+
+/// Blocks whose louder port is below this level (dBFS) count as no signal
+const MIN_SIGNAL_DB: f32 = -50.0;
+
+/// Signal needed before a correction is offered
+const MIN_SIGNAL: Duration = Duration::from_secs(20);
+
+/// Longest a single meter block counts for (gaps are not signal)
+const MAX_BLOCK: Duration = Duration::from_millis(100);
+
+/// Seconds of signal that must agree with the whole measurement
+const CONSISTENT_SHARE: f32 = 0.8;
+
+/// Largest difference in dB of a second that still agrees
+const CONSISTENCY_DB: f32 = 3.0;
+
+/// Smallest change of the balance worth offering in dB
+const MIN_CORRECTION_DB: f32 = 0.5;
+
+/// Result of handling a key in the balance view
+pub enum BalanceAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Close the view
+    Close,
+
+    /// Restart the measurement
+    Restart,
+
+    /// Set the balance of the input to the given dB
+    Apply(f32),
+}
+
+/// Energy of the left and right ports over some signal time
+#[derive(Debug, Clone, Copy, Default)]
+struct Energy {
+    /// Mean squares of the left port weighted by block duration
+    left: f64,
+
+    /// Mean squares of the right port weighted by block duration
+    right: f64,
+
+    /// Signal time summed
+    time: Duration,
+}
+
+impl Energy {
+    /// Left over right level in dB (None without energy on both sides)
+    fn difference_db(&self) -> Option<f32> {
+        (self.left > 0.0 && self.right > 0.0)
+            .then(|| (10.0 * (self.left / self.right).log10()) as f32)
+    }
+
+    /// Mean level of each port in dBFS
+    fn levels_db(&self) -> Option<(f32, f32)> {
+        let seconds = self.time.as_secs_f64();
+        let db = |sum: f64| (10.0 * (sum / seconds).log10()) as f32;
+        (seconds > 0.0).then(|| (db(self.left), db(self.right)))
+    }
+}
+
+/// State of a balance measurement
+#[derive(Debug)]
+pub struct BalanceView {
+    /// Measured input index
+    input: usize,
+
+    /// Balance in effect while measuring in dB
+    balance_db: f32,
+
+    /// Energy of the whole measurement
+    total: Energy,
+
+    /// Energy of the second being filled
+    second: Energy,
+
+    /// Left over right level of each full second of signal in dB
+    seconds: Vec<f32>,
+
+    /// Time of the previous meter block of the input
+    last: Option<Instant>,
+}
+
+impl BalanceView {
+    /// Start measuring `input` with `balance_db` in effect
+    pub fn new(input: usize, balance_db: f32) -> Self {
+        Self {
+            input,
+            balance_db,
+            total: Energy::default(),
+            second: Energy::default(),
+            seconds: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// Measured input index
+    pub fn input(&self) -> usize {
+        self.input
+    }
+
+    /// Add a meter block of the measured input
+    pub fn record(&mut self, meter: &MeterData) {
+        if meter.channel_index != self.input || meter.port_count != 2 {
+            return;
+        }
+        let elapsed = self
+            .last
+            .map_or(Duration::ZERO, |last| meter.timestamp.saturating_duration_since(last))
+            .min(MAX_BLOCK);
+        self.last = Some(meter.timestamp);
+
+        // Undo the balance in effect to get the source as it arrives
+        let gains = balance_gains(self.balance_db);
+        let left = (meter.rms[0] / gains[0]).powi(2) as f64;
+        let right = (meter.rms[1] / gains[1]).powi(2) as f64;
+        if MeterData::linear_to_db(left.max(right).sqrt() as f32) < MIN_SIGNAL_DB {
+            return;
+        }
+        let weight = elapsed.as_secs_f64();
+        for energy in [&mut self.total, &mut self.second] {
+            energy.left += left * weight;
+            energy.right += right * weight;
+            energy.time += elapsed;
+        }
+        if self.second.time >= Duration::from_secs(1) {
+            self.seconds.extend(self.second.difference_db());
+            self.second = Energy::default();
+        }
+    }
+
+    /// Balance that evens out the ports, once there is enough signal and it
+    /// leans the same way most of the time
+    fn suggested(&self) -> Option<f32> {
+        if self.total.time < MIN_SIGNAL {
+            return None;
+        }
+        let difference = self.total.difference_db()?;
+        let agreeing = self
+            .seconds
+            .iter()
+            .filter(|db| (*db - difference).abs() <= CONSISTENCY_DB)
+            .count();
+        (agreeing as f32 >= CONSISTENT_SHARE * self.seconds.len() as f32)
+            .then(|| difference.clamp(-BALANCE_RANGE_DB, BALANCE_RANGE_DB))
+    }
+
+    /// Suggested balance if it differs enough from the one in effect
+    fn correction(&self) -> Option<f32> {
+        self.suggested()
+            .filter(|db| (db - self.balance_db).abs() >= MIN_CORRECTION_DB)
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode) -> BalanceAction {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('K') => BalanceAction::Close,
+            KeyCode::Char('r') => BalanceAction::Restart,
+            KeyCode::Enter => match self.correction() {
+                Some(balance_db) => BalanceAction::Apply(balance_db),
+                None => BalanceAction::None,
+            },
+            _ => BalanceAction::None,
+        }
+    }
+
+    /// Render the view into `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, state: &MixerState) {
+        let input = state.inputs.get(self.input).map_or("", |c| c.name.as_str());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", tr(Msg::BalanceTitle)));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(2), Constraint::Length(1)])
+            .split(inner);
+
+        let header = Style::default().fg(Color::Gray);
+        let row = |label: Msg, value: String| {
+            Line::from(vec![
+                Span::styled(format!("{:16}", tr(label)), header),
+                Span::raw(format!("{:>12}", value)),
+            ])
+        };
+        let levels = self.total.levels_db();
+        let level = |db: Option<f32>| db.map_or("…".to_string(), |db| format!("{:.1} dB", db));
+        let mut lines = vec![
+            Line::styled(trf(Msg::BalanceIntro, &[&input]), header),
+            Line::raw(""),
+            row(Msg::BalanceLeft, level(levels.map(|(left, _)| left))),
+            row(Msg::BalanceRight, level(levels.map(|(_, right)| right))),
+            row(
+                Msg::BalanceDifference,
+                self.total
+                    .difference_db()
+                    .map_or("…".to_string(), |db| format!("{:+.1} dB", db)),
+            ),
+            row(Msg::BalanceCurrent, format!("{:+.1} dB", self.balance_db)),
+            row(
+                Msg::BalanceSignal,
+                format!(
+                    "{:.0}/{} s",
+                    self.total.time.as_secs_f32().floor(),
+                    MIN_SIGNAL.as_secs()
+                ),
+            ),
+            Line::raw(""),
+        ];
+
+        let verdict = if self.total.time < MIN_SIGNAL {
+            Line::styled(tr(Msg::BalanceMeasuring), Style::default().fg(Color::Yellow))
+        } else if let Some(balance_db) = self.correction() {
+            Line::styled(
+                trf(Msg::BalanceSuggested, &[&format!("{:+.1}", balance_db)]),
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else if self.suggested().is_some() {
+            Line::styled(tr(Msg::BalanceEven), Style::default().fg(Color::Green))
+        } else {
+            Line::styled(tr(Msg::BalanceInconsistent), Style::default().fg(Color::Yellow))
+        };
+        lines.push(verdict);
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+        let hints = if self.correction().is_some() {
+            key_hints(&[
+                ("Enter", Msg::CalApply),
+                ("r", Msg::CalRestart),
+                ("K/Esc", Msg::EditorClose),
+            ])
+        } else {
+            key_hints(&[("r", Msg::CalRestart), ("K/Esc", Msg::EditorClose)])
+        };
+        frame.render_widget(Paragraph::new(Line::from(hints)), chunks[1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lopsided_source_is_corrected() {
+        let start = Instant::now();
+        let block = |ms: u64, left: f32, right: f32| {
+            let mut meter = MeterData::stereo(1, left, right, left, right);
+            meter.timestamp = start + Duration::from_millis(ms);
+            meter
+        };
+
+        // Left 6 dB louder for 25 s, with quiet gaps that do not count
+        let mut view = BalanceView::new(1, 0.0);
+        for ms in (0..30_000).step_by(10) {
+            let (left, right) = if ms % 6000 < 5000 { (0.2, 0.1) } else { (0.001, 0.0) };
+            view.record(&block(ms, left, right));
+            view.record(&MeterData::stereo(0, 1.0, 0.0, 1.0, 0.0));
+        }
+        assert!((view.total.time.as_secs_f32() - 25.0).abs() < 0.1);
+        let balance_db = match view.handle_key(KeyCode::Enter) {
+            BalanceAction::Apply(balance_db) => balance_db,
+            _ => panic!("expected a correction"),
+        };
+        assert!((balance_db - 6.02).abs() < 0.05);
+
+        // Measured again with the correction in effect: nothing left to do
+        let gains = balance_gains(balance_db);
+        let mut view = BalanceView::new(1, balance_db);
+        for ms in (0..25_000).step_by(10) {
+            view.record(&block(ms, 0.2 * gains[0], 0.1 * gains[1]));
+        }
+        assert!(view.suggested().is_some());
+        assert!(view.correction().is_none());
+
+        // A source that swings from side to side gets no correction
+        let mut view = BalanceView::new(1, 0.0);
+        for ms in (0..25_000).step_by(10) {
+            let (left, right) = if ms % 2000 < 1000 { (0.4, 0.1) } else { (0.1, 0.2) };
+            view.record(&block(ms, left, right));
+        }
+        assert!(view.total.difference_db().is_some());
+        assert_eq!(view.suggested(), None);
+    }
+}
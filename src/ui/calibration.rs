@@ -0,0 +1,282 @@
+//! Speaker level matching
+//!
+//! Plays pink noise through each output in turn while the measurement input
+//! listens, then suggests a volume change per output that brings every
+//! monitor pair down to the level of the quietest one. The first second of
+//! each output lets the speaker and the room settle; the rest is averaged.
+
+use std::time::{Duration, Instant};
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::config::CalibrationConfig;
+use crate::i18n::{tr, trf, Msg};
+use crate::ipc::{MeterData, MixerState};
+
+use super::app::key_hints;
+
+/// Noise played before measuring starts on each output
+const SETTLE: Duration = Duration::from_secs(1);
+
+/// Returns below this level (dBFS) count as no signal
+const MIN_RETURN_DB: f32 = -70.0;
+
+/// Result of handling a key in the calibration view
+pub enum CalibrationAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Stop the noise and close the view
+    Close,
+
+    /// Restart the measurements
+    Restart,
+
+    /// Change the volume of each output by the given dB
+    Apply(Vec<(usize, f32)>),
+}
+
+/// State of a calibration run
+#[derive(Debug)]
+pub struct CalibrationView {
+    /// Measurement input index
+    input: usize,
+
+    /// Noise level in dBFS
+    level_db: f32,
+
+    /// Noise per output, including the settling time
+    duration: Duration,
+
+    /// Output being measured (the output count once done)
+    step: usize,
+
+    /// When the current output started playing noise
+    step_start: Instant,
+
+    /// Sum of the input's mean squares since settling
+    sum: f32,
+
+    /// Meter blocks in `sum`
+    blocks: u32,
+
+    /// Measured return per output in dBFS (None for no signal)
+    levels: Vec<Option<f32>>,
+}
+
+impl CalibrationView {
+    /// Start measuring `outputs` outputs on `input`
+    pub fn new(settings: &CalibrationConfig, input: usize, outputs: usize, now: Instant) -> Self {
+        Self {
+            input,
+            level_db: settings.level_db,
+            duration: Duration::from_secs_f32(settings.seconds),
+            step: 0,
+            step_start: now,
+            sum: 0.0,
+            blocks: 0,
+            levels: vec![None; outputs],
+        }
+    }
+
+    /// Output that should play noise now, if any
+    pub fn noise_output(&self) -> Option<usize> {
+        (self.step < self.levels.len()).then_some(self.step)
+    }
+
+    /// Noise level in dBFS
+    pub fn level_db(&self) -> f32 {
+        self.level_db
+    }
+
+    /// Whether every output has been measured
+    fn done(&self) -> bool {
+        self.noise_output().is_none()
+    }
+
+    /// Add a meter block of the measurement input
+    pub fn record(&mut self, meter: &MeterData, now: Instant) {
+        if meter.channel_index != self.input
+            || self.done()
+            || now.duration_since(self.step_start) < SETTLE
+        {
+            return;
+        }
+        let ports = meter.port_count.max(1);
+        self.sum += meter.rms[..ports].iter().map(|r| r * r).sum::<f32>() / ports as f32;
+        self.blocks += 1;
+    }
+
+    /// Finish the current output once its time is up; true when the noise
+    /// moved on
+    pub fn update(&mut self, now: Instant) -> bool {
+        if self.done() || now.duration_since(self.step_start) < self.duration {
+            return false;
+        }
+        let level = (self.blocks > 0)
+            .then(|| 10.0 * (self.sum / self.blocks as f32).log10())
+            .filter(|&db| db > MIN_RETURN_DB);
+        self.levels[self.step] = level;
+        self.step += 1;
+        self.step_start = now;
+        self.sum = 0.0;
+        self.blocks = 0;
+        true
+    }
+
+    /// Suggested volume change per output: down to the quietest output that
+    /// returned signal (None for outputs without a return)
+    fn offsets(&self) -> Vec<Option<f32>> {
+        let reference = self
+            .levels
+            .iter()
+            .flatten()
+            .fold(None, |min: Option<f32>, &db| {
+                Some(min.map_or(db, |m| m.min(db)))
+            });
+        self.levels
+            .iter()
+            .map(|level| Some(reference? - (*level)?))
+            .collect()
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode) -> CalibrationAction {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('k') => CalibrationAction::Close,
+            KeyCode::Char('r') => CalibrationAction::Restart,
+            KeyCode::Enter if self.done() => CalibrationAction::Apply(
+                self.offsets()
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(output, offset)| Some((output, offset?)))
+                    .collect(),
+            ),
+            _ => CalibrationAction::None,
+        }
+    }
+
+    /// Render the view into `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, state: &MixerState, now: Instant) {
+        let input = state.inputs.get(self.input).map_or("", |c| c.name.as_str());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", tr(Msg::CalTitle)));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(2), Constraint::Length(1)])
+            .split(inner);
+
+        let header = Style::default().fg(Color::Gray);
+        let mut lines = vec![
+            Line::styled(
+                trf(Msg::CalIntro, &[&format!("{:.0}", self.level_db), &input]),
+                header,
+            ),
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled(format!("{:16}", tr(Msg::CalOutput)), header),
+                Span::styled(format!("{:>14}", tr(Msg::CalReturn)), header),
+                Span::styled(format!("{:>12}", tr(Msg::CalSuggested)), header),
+            ]),
+        ];
+
+        let offsets = self.offsets();
+        for (output, channel) in state.outputs.iter().enumerate() {
+            let (level, level_style) = if output == self.step {
+                let left = self
+                    .duration
+                    .saturating_sub(now.duration_since(self.step_start))
+                    .as_secs_f32()
+                    .ceil();
+                (
+                    format!("● {:.0} s", left),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else if output > self.step {
+                ("…".to_string(), Style::default().fg(Color::DarkGray))
+            } else {
+                match self.levels.get(output).copied().flatten() {
+                    Some(db) => (format!("{:.1} dB", db), Style::default()),
+                    None => (
+                        tr(Msg::CalNoSignal).to_string(),
+                        Style::default().fg(Color::Red),
+                    ),
+                }
+            };
+            let offset = match offsets.get(output).copied().flatten() {
+                Some(db) if self.done() => format!("{:+.1} dB", db),
+                _ => String::new(),
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!("{:16}", channel.name)),
+                Span::styled(format!("{:>14}", level), level_style),
+                Span::styled(format!("{:>12}", offset), Style::default().fg(Color::Green)),
+            ]));
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+        let hints = if self.done() {
+            key_hints(&[
+                ("Enter", Msg::CalApply),
+                ("r", Msg::CalRestart),
+                ("k/Esc", Msg::EditorClose),
+            ])
+        } else {
+            key_hints(&[("r", Msg::CalRestart), ("k/Esc", Msg::CalStop)])
+        };
+        frame.render_widget(Paragraph::new(Line::from(hints)), chunks[1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outputs_are_matched_to_the_quietest() {
+        let settings = CalibrationConfig {
+            input: "Mic".to_string(),
+            level_db: -20.0,
+            seconds: 3.0,
+        };
+        let start = Instant::now();
+        let mut view = CalibrationView::new(&settings, 1, 3, start);
+
+        // Returns of -30, -36 and nothing; only blocks after settling count
+        let mut now = start;
+        for rms in [0.0316, 0.0158, 0.0] {
+            assert_eq!(view.noise_output(), Some(view.step));
+            view.record(&MeterData::mono(1, 1.0, 1.0), now);
+            now += SETTLE;
+            view.record(&MeterData::mono(1, rms, rms), now);
+            view.record(&MeterData::mono(0, 1.0, 1.0), now);
+            assert!(!view.update(now));
+            now += Duration::from_secs(2);
+            assert!(view.update(now));
+        }
+        assert_eq!(view.noise_output(), None);
+
+        let offsets = view.offsets();
+        assert!((offsets[0].unwrap() + 6.0).abs() < 0.05);
+        assert_eq!(offsets[1], Some(0.0));
+        assert_eq!(offsets[2], None);
+        match view.handle_key(KeyCode::Enter) {
+            CalibrationAction::Apply(changes) => assert_eq!(changes.len(), 2),
+            _ => panic!("expected the changes"),
+        }
+    }
+}
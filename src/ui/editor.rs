@@ -0,0 +1,457 @@
+//! Interactive configuration editor
+//!
+//! A form-based view for editing the client name and the input/output
+//! channel lists without touching the YAML file by hand.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::config::{ChannelConfig, Config, TapPoint};
+use crate::i18n::{tr, trf, Msg};
+
+use super::app::key_hints;
+
+/// Editable field of a channel row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Ports,
+    Tap,
+}
+
+/// What a row of the editor refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    ClientName,
+    Input(usize),
+    Output(usize),
+}
+
+/// Result of handling a key in the editor
+pub enum EditorAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Close the editor, discarding unapplied changes
+    Close,
+
+    /// Apply and save the edited configuration
+    Apply(Box<Config>),
+}
+
+/// State of the configuration editor view
+pub struct ConfigEditor {
+    /// Working copy of the configuration
+    config: Config,
+
+    /// Selected row (0 = client name, then inputs, then outputs)
+    row: usize,
+
+    /// Selected field within a channel row
+    field: Field,
+
+    /// Text being edited, if a field is in edit mode
+    input: Option<String>,
+
+    /// Status line message and whether it is an error
+    status: Option<(String, bool)>,
+
+    /// Whether the working copy has unapplied changes
+    dirty: bool,
+
+    /// Set after the first Esc on a dirty editor
+    confirm_discard: bool,
+}
+
+impl ConfigEditor {
+    /// Create an editor for a copy of the given configuration
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            row: 0,
+            field: Field::Name,
+            input: None,
+            status: None,
+            dirty: false,
+            confirm_discard: false,
+        }
+    }
+
+    /// Show an informational message in the status line
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.status = Some((message.into(), false));
+    }
+
+    /// Show an error message in the status line
+    pub fn set_error(&mut self, message: impl Into<String>) {
+        self.status = Some((message.into(), true));
+    }
+
+    /// Mark the working copy as applied
+    pub fn mark_applied(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Total number of rows
+    fn row_count(&self) -> usize {
+        1 + self.config.inputs.len() + self.config.outputs.len()
+    }
+
+    /// Map the selected row index to what it refers to
+    fn current_row(&self) -> Row {
+        let inputs = self.config.inputs.len();
+        match self.row {
+            0 => Row::ClientName,
+            r if r <= inputs => Row::Input(r - 1),
+            r => Row::Output(r - 1 - inputs),
+        }
+    }
+
+    /// Get the channel config of the selected row, if any
+    fn current_channel_mut(&mut self) -> Option<&mut ChannelConfig> {
+        match self.current_row() {
+            Row::ClientName => None,
+            Row::Input(i) => self.config.inputs.get_mut(i),
+            Row::Output(i) => self.config.outputs.get_mut(i),
+        }
+    }
+
+    /// Current text of the selected field
+    fn field_text(&self) -> String {
+        match self.current_row() {
+            Row::ClientName => self.config.client_name.clone(),
+            Row::Input(i) => Self::channel_field_text(&self.config.inputs[i], self.field),
+            Row::Output(i) => Self::channel_field_text(&self.config.outputs[i], self.field),
+        }
+    }
+
+    fn channel_field_text(channel: &ChannelConfig, field: Field) -> String {
+        match field {
+            Field::Name => channel.name.clone(),
+            Field::Ports => channel.ports.join(", "),
+            Field::Tap => tap_label(channel.tap).to_string(),
+        }
+    }
+
+    /// Store edited text into the selected field
+    fn commit_input(&mut self, text: String) {
+        let field = self.field;
+        match self.current_row() {
+            Row::ClientName => self.config.client_name = text.trim().to_string(),
+            _ => {
+                if let Some(channel) = self.current_channel_mut() {
+                    match field {
+                        Field::Name => channel.name = text.trim().to_string(),
+                        Field::Ports => {
+                            channel.ports = text
+                                .split(',')
+                                .map(|p| p.trim().to_string())
+                                .filter(|p| !p.is_empty())
+                                .collect();
+                        }
+                        Field::Tap => {}
+                    }
+                }
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Cycle the tap point of the selected channel (none, pre, post)
+    fn cycle_tap(&mut self) {
+        if let Some(channel) = self.current_channel_mut() {
+            channel.tap = match channel.tap {
+                None => Some(TapPoint::PreFader),
+                Some(TapPoint::PreFader) => Some(TapPoint::PostFader),
+                Some(TapPoint::PostFader) => None,
+            };
+            self.dirty = true;
+        }
+    }
+
+    /// Generate a port name not used by any channel yet
+    fn unique_port_name(&self, prefix: &str) -> String {
+        let used: Vec<String> = self
+            .config
+            .inputs
+            .iter()
+            .chain(self.config.outputs.iter())
+            .flat_map(|c| c.registered_port_names())
+            .collect();
+        (1..)
+            .map(|n| format!("{}_{}", prefix, n))
+            .find(|name| !used.contains(name))
+            .unwrap_or_else(|| prefix.to_string())
+    }
+
+    /// Add a new mono channel and select it
+    fn add_channel(&mut self, is_input: bool) {
+        let (prefix, label) = if is_input {
+            ("in", Msg::EditorNewInput)
+        } else {
+            ("out", Msg::EditorNewOutput)
+        };
+        let port = self.unique_port_name(prefix);
+        let list = if is_input {
+            &mut self.config.inputs
+        } else {
+            &mut self.config.outputs
+        };
+        list.push(ChannelConfig {
+            name: trf(label, &[&(list.len() + 1)]),
+            ports: vec![port],
+            ..Default::default()
+        });
+        self.row = if is_input {
+            self.config.inputs.len()
+        } else {
+            self.row_count() - 1
+        };
+        self.field = Field::Name;
+        self.dirty = true;
+    }
+
+    /// Remove the selected channel
+    fn delete_channel(&mut self) {
+        match self.current_row() {
+            Row::ClientName => return,
+            Row::Input(i) => {
+                self.config.inputs.remove(i);
+            }
+            Row::Output(i) => {
+                self.config.outputs.remove(i);
+            }
+        }
+        self.row = self.row.min(self.row_count() - 1);
+        self.dirty = true;
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode) -> EditorAction {
+        // Text entry mode
+        if let Some(mut text) = self.input.take() {
+            match code {
+                KeyCode::Enter => self.commit_input(text),
+                KeyCode::Esc => {}
+                KeyCode::Backspace => {
+                    text.pop();
+                    self.input = Some(text);
+                }
+                KeyCode::Char(c) => {
+                    text.push(c);
+                    self.input = Some(text);
+                }
+                _ => self.input = Some(text),
+            }
+            return EditorAction::None;
+        }
+
+        if !matches!(code, KeyCode::Esc | KeyCode::Char('q')) {
+            self.confirm_discard = false;
+        }
+
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                if self.dirty && !self.confirm_discard {
+                    self.confirm_discard = true;
+                    self.set_error(tr(Msg::EditorDiscardPrompt));
+                } else {
+                    return EditorAction::Close;
+                }
+            }
+            KeyCode::Up => self.row = self.row.saturating_sub(1),
+            KeyCode::Down => self.row = (self.row + 1).min(self.row_count() - 1),
+            KeyCode::Left => {
+                self.field = match self.field {
+                    Field::Tap => Field::Ports,
+                    _ => Field::Name,
+                }
+            }
+            KeyCode::Right | KeyCode::Tab => {
+                self.field = match self.field {
+                    Field::Name => Field::Ports,
+                    _ => Field::Tap,
+                }
+            }
+            KeyCode::Enter if self.field == Field::Tap => self.cycle_tap(),
+            KeyCode::Enter => {
+                self.status = None;
+                self.input = Some(self.field_text());
+            }
+            KeyCode::Char('a') => self.add_channel(true),
+            KeyCode::Char('o') => self.add_channel(false),
+            KeyCode::Char('d') => self.delete_channel(),
+            KeyCode::Char('w') => match self.config.validate() {
+                Ok(()) => return EditorAction::Apply(Box::new(self.config.clone())),
+                Err(e) => self.set_error(trf(Msg::EditorInvalid, &[&e])),
+            },
+            _ => {}
+        }
+        EditorAction::None
+    }
+
+    /// Render the editor into the given area
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", tr(Msg::EditorTitle)));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),    // Rows
+                Constraint::Length(1), // Status
+                Constraint::Length(1), // Keys
+            ])
+            .split(inner);
+
+        let name_width = self
+            .config
+            .inputs
+            .iter()
+            .chain(self.config.outputs.iter())
+            .map(|c| c.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(12);
+
+        let mut lines = Vec::new();
+        let mut row = 0;
+        lines.push(self.row_line(row, tr(Msg::EditorClient), &self.config.client_name, None, name_width));
+        row += 1;
+
+        lines.push(Line::from(Span::styled(
+            tr(Msg::SectionInputs),
+            Style::default().fg(Color::DarkGray),
+        )));
+        for channel in &self.config.inputs {
+            lines.push(self.row_line(row, "", &channel.name, Some(channel), name_width));
+            row += 1;
+        }
+
+        lines.push(Line::from(Span::styled(
+            tr(Msg::SectionOutputs),
+            Style::default().fg(Color::DarkGray),
+        )));
+        for channel in &self.config.outputs {
+            lines.push(self.row_line(row, "", &channel.name, Some(channel), name_width));
+            row += 1;
+        }
+
+        // Keep the selected row visible
+        let height = chunks[0].height as usize;
+        let selected_line = self.row + if self.row > self.config.inputs.len() { 2 } else { 1 };
+        let scroll = selected_line.saturating_sub(height.saturating_sub(1));
+        let rows = Paragraph::new(lines).scroll((scroll as u16, 0));
+        frame.render_widget(rows, chunks[0]);
+
+        if let Some((message, is_error)) = &self.status {
+            let color = if *is_error { Color::Red } else { Color::Green };
+            let status = Paragraph::new(message.as_str()).style(Style::default().fg(color));
+            frame.render_widget(status, chunks[1]);
+        }
+
+        let keys = if self.input.is_some() {
+            let mut keys = key_hints(&[("Enter", Msg::EditorConfirm), ("Esc", Msg::EditorCancel)]);
+            keys.push(Span::raw(format!(" {}", tr(Msg::EditorPortsHint))));
+            keys
+        } else {
+            key_hints(&[
+                ("↑/↓", Msg::EditorRow),
+                ("←/→", Msg::EditorField),
+                ("Enter", Msg::EditorEditCycle),
+                ("a/o", Msg::EditorAddChannel),
+                ("d", Msg::EditorDelete),
+                ("w", Msg::EditorApplySave),
+                ("Esc", Msg::EditorClose),
+            ])
+        };
+        let keys = Paragraph::new(Line::from(keys)).style(Style::default().fg(Color::Gray));
+        frame.render_widget(keys, chunks[2]);
+    }
+
+    /// Build the display line for one editor row
+    fn row_line(
+        &self,
+        row: usize,
+        label: &str,
+        name: &str,
+        channel: Option<&ChannelConfig>,
+        name_width: usize,
+    ) -> Line<'static> {
+        let is_row = row == self.row;
+        let field_style = |field: Field| {
+            if is_row && (channel.is_none() || self.field == field) {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+        let editing = |field: Field| {
+            if is_row && (channel.is_none() || self.field == field) {
+                self.input.as_ref().map(|text| format!("{}_", text))
+            } else {
+                None
+            }
+        };
+
+        let marker = if is_row { "> " } else { "  " };
+        let mut spans = vec![Span::raw(marker)];
+        if !label.is_empty() {
+            spans.push(Span::styled(
+                format!("{}: ", label),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+
+        let name_text = editing(Field::Name).unwrap_or_else(|| name.to_string());
+        spans.push(Span::styled(
+            format!("{:<width$}", name_text, width = name_width),
+            field_style(Field::Name),
+        ));
+
+        if let Some(channel) = channel {
+            let kind = if !channel.is_stereo() {
+                tr(Msg::EditorMono).to_string()
+            } else if channel.ports.len() == 2 {
+                tr(Msg::EditorStereo).to_string()
+            } else {
+                trf(Msg::EditorSurround, &[&channel.ports.len()])
+            };
+            spans.push(Span::styled(
+                format!("  {:<6} ", kind),
+                Style::default().fg(Color::DarkGray),
+            ));
+            let ports_text = editing(Field::Ports).unwrap_or_else(|| channel.ports.join(", "));
+            spans.push(Span::styled(ports_text, field_style(Field::Ports)));
+            spans.push(Span::styled(
+                format!("  {}: ", tr(Msg::EditorTap)),
+                Style::default().fg(Color::DarkGray),
+            ));
+            spans.push(Span::styled(tap_label(channel.tap), field_style(Field::Tap)));
+        }
+
+        Line::from(spans)
+    }
+}
+
+/// Display label for a tap setting
+fn tap_label(tap: Option<TapPoint>) -> &'static str {
+    match tap {
+        None => tr(Msg::EditorTapOff),
+        Some(TapPoint::PreFader) => tr(Msg::EditorTapPre),
+        Some(TapPoint::PostFader) => tr(Msg::EditorTapPost),
+    }
+}
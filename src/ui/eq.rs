@@ -0,0 +1,332 @@
+//! EQ band editor
+//!
+//! Lists the high- and low-pass filters of an input's EQ, its fixed bands and
+//! its parametric bands. The cursor moves between rows and fields (frequency,
+//! gain, Q); filters can be switched and tuned, and bands adjusted, added and
+//! removed while the mixer keeps running.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::config::{
+    EqBand, EqBandConfig, EqConfig, PassFilter, PassFilterConfig, EQ_FREQ_RANGE, EQ_Q_RANGE,
+    EQ_RANGE_DB, MAX_EQ_BANDS,
+};
+use crate::i18n::{tr, trf, Msg};
+use crate::ipc::MixerState;
+
+use super::app::key_hints;
+
+/// Gain change per key press in dB
+const GAIN_STEP_DB: f32 = 1.0;
+
+/// Frequency change per key press (a sixth of an octave)
+const FREQ_STEP: f32 = 1.122_462;
+
+/// Q change per key press
+const Q_STEP: f32 = 1.25;
+
+/// Pass filters shown above the bands
+const FILTERS: [(PassFilter, Msg); 2] = [
+    (PassFilter::HighPass, Msg::EqHighPass),
+    (PassFilter::LowPass, Msg::EqLowPass),
+];
+
+/// Fixed bands shown above the parametric ones
+const FIXED: [(EqBand, Msg, &str); 3] = [
+    (EqBand::Low, Msg::EqLow, "100"),
+    (EqBand::Mid, Msg::EqMid, "1k"),
+    (EqBand::High, Msg::EqHigh, "10k"),
+];
+
+/// Rows ahead of the parametric bands
+const FIXED_ROWS: usize = FILTERS.len() + FIXED.len();
+
+/// What a row of the view edits
+#[derive(Debug, Clone, Copy)]
+enum Row {
+    Filter(PassFilter),
+    Fixed(EqBand),
+    Band(usize),
+}
+
+/// Editable field of a band
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Field {
+    Freq,
+    #[default]
+    Gain,
+    Q,
+}
+
+/// Result of handling a key in the EQ view
+pub enum EqAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Close the EQ view
+    Close,
+
+    /// Replace the EQ of the input
+    Set { input: usize, eq: EqConfig },
+}
+
+/// State of the EQ view for one input
+#[derive(Debug)]
+pub struct EqView {
+    /// Input being edited
+    input: usize,
+
+    /// Selected row (filters and fixed bands first)
+    row: usize,
+
+    /// Selected field of a parametric band
+    field: Field,
+}
+
+impl EqView {
+    /// Open the view on an input
+    pub fn new(input: usize) -> Self {
+        Self {
+            input,
+            row: 0,
+            field: Field::default(),
+        }
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode, state: &MixerState) -> EqAction {
+        let Some(channel) = state.inputs.get(self.input) else {
+            return EqAction::Close;
+        };
+        let mut eq = channel.eq.clone();
+        let rows = FIXED_ROWS + eq.bands.len();
+        self.row = self.row.min(rows - 1);
+
+        match code {
+            KeyCode::Up => self.row = (self.row + rows - 1) % rows,
+            KeyCode::Down => self.row = (self.row + 1) % rows,
+            KeyCode::Left => {
+                self.field = match self.field {
+                    Field::Freq | Field::Gain => Field::Freq,
+                    Field::Q => Field::Gain,
+                }
+            }
+            KeyCode::Right => {
+                self.field = match self.field {
+                    Field::Freq => Field::Gain,
+                    Field::Gain | Field::Q => Field::Q,
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('p') | KeyCode::Char('q') => return EqAction::Close,
+            KeyCode::Char('+') | KeyCode::Char('=') => self.adjust(&mut eq, 1.0),
+            KeyCode::Char('-') => self.adjust(&mut eq, -1.0),
+            KeyCode::Char('0') => {
+                if let Some(gain) = self.gain_mut(&mut eq) {
+                    *gain = 0.0;
+                }
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Row::Filter(filter) = self.row() {
+                    match eq.pass_filter_mut(filter) {
+                        Some(settings) => settings.enabled = !settings.enabled,
+                        None => {
+                            filter_mut(&mut eq, filter);
+                        }
+                    }
+                }
+            }
+            KeyCode::Char('a') if eq.bands.len() < MAX_EQ_BANDS => {
+                eq.bands.push(EqBandConfig::default());
+                self.row = FIXED_ROWS + eq.bands.len() - 1;
+            }
+            KeyCode::Delete | KeyCode::Char('x') if self.row >= FIXED_ROWS => {
+                eq.bands.remove(self.row - FIXED_ROWS);
+                self.row -= 1;
+            }
+            _ => return EqAction::None,
+        }
+
+        if eq == channel.eq {
+            EqAction::None
+        } else {
+            EqAction::Set {
+                input: self.input,
+                eq,
+            }
+        }
+    }
+
+    /// What the selected row edits
+    fn row(&self) -> Row {
+        if let Some(&(filter, _)) = FILTERS.get(self.row) {
+            Row::Filter(filter)
+        } else if let Some(&(band, _, _)) = FIXED.get(self.row - FILTERS.len()) {
+            Row::Fixed(band)
+        } else {
+            Row::Band(self.row - FIXED_ROWS)
+        }
+    }
+
+    /// Field the cursor is on (filters only have a frequency, fixed bands a gain)
+    fn selected_field(&self) -> Field {
+        match self.row() {
+            Row::Filter(_) => Field::Freq,
+            Row::Fixed(_) => Field::Gain,
+            Row::Band(_) => self.field,
+        }
+    }
+
+    /// Gain of the selected row (None on a filter)
+    fn gain_mut<'a>(&self, eq: &'a mut EqConfig) -> Option<&'a mut f32> {
+        match self.row() {
+            Row::Filter(_) => None,
+            Row::Fixed(band) => Some(eq.band_mut(band)),
+            Row::Band(index) => Some(&mut eq.bands[index].gain_db),
+        }
+    }
+
+    /// Step the selected field up (+1) or down (-1)
+    fn adjust(&self, eq: &mut EqConfig, direction: f32) {
+        let step_freq = |freq: f32| {
+            (freq * FREQ_STEP.powf(direction))
+                .round()
+                .clamp(EQ_FREQ_RANGE.0, EQ_FREQ_RANGE.1)
+        };
+        match (self.row(), self.selected_field()) {
+            (Row::Filter(filter), _) => {
+                let settings = filter_mut(eq, filter);
+                settings.freq = step_freq(settings.freq);
+            }
+            (Row::Band(index), Field::Freq) => {
+                let band = &mut eq.bands[index];
+                band.freq = step_freq(band.freq);
+            }
+            (Row::Band(index), Field::Q) => {
+                let band = &mut eq.bands[index];
+                band.q = (band.q * Q_STEP.powf(direction)).clamp(EQ_Q_RANGE.0, EQ_Q_RANGE.1);
+            }
+            _ => {
+                if let Some(gain) = self.gain_mut(eq) {
+                    *gain = (*gain + direction * GAIN_STEP_DB).clamp(-EQ_RANGE_DB, EQ_RANGE_DB);
+                }
+            }
+        }
+    }
+
+    /// Render the view into `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, state: &MixerState) {
+        let Some(channel) = state.inputs.get(self.input) else {
+            return;
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", trf(Msg::EqTitle, &[&channel.name])));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(2), Constraint::Length(1)])
+            .split(inner);
+
+        let header = Style::default().fg(Color::Gray);
+        let mut lines = vec![Line::from(vec![
+            Span::styled(format!("{:8}", ""), header),
+            Span::styled(format!("{:>8}", "Hz"), header),
+            Span::styled(format!("{:>8}", "dB"), header),
+            Span::styled(format!("{:>8}", "Q"), header),
+        ])];
+
+        let eq = &channel.eq;
+        let dim = Style::default().fg(Color::DarkGray);
+        for (row, &(filter, label)) in FILTERS.iter().enumerate() {
+            let (freq, active) = match eq.pass_filter(filter) {
+                Some(settings) => (format!("{:.0}", settings.freq), settings.enabled),
+                None => ("-".to_string(), false),
+            };
+            lines.push(Line::from(vec![
+                self.label(row, tr(label).to_string()),
+                self.cell(row, Field::Freq, freq, active),
+                Span::styled(format!("{:>8}", "-"), dim),
+                Span::styled(format!("{:>8}", "-"), dim),
+            ]));
+        }
+        for (i, &(band, label, freq)) in FIXED.iter().enumerate() {
+            let row = FILTERS.len() + i;
+            let gain = match band {
+                EqBand::Low => eq.low_db,
+                EqBand::Mid => eq.mid_db,
+                EqBand::High => eq.high_db,
+            };
+            lines.push(Line::from(vec![
+                self.label(row, tr(label).to_string()),
+                Span::styled(format!("{:>8}", freq), dim),
+                self.cell(row, Field::Gain, format!("{:+.1}", gain), gain != 0.0),
+                Span::styled(format!("{:>8}", "-"), dim),
+            ]));
+        }
+        for (i, band) in eq.bands.iter().enumerate() {
+            let row = FIXED_ROWS + i;
+            let active = band.gain_db != 0.0;
+            lines.push(Line::from(vec![
+                self.label(row, trf(Msg::EqBandLabel, &[&(i + 1)])),
+                self.cell(row, Field::Freq, format!("{:.0}", band.freq), active),
+                self.cell(row, Field::Gain, format!("{:+.1}", band.gain_db), active),
+                self.cell(row, Field::Q, format!("{:.2}", band.q), active),
+            ]));
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+        let keys = Line::from(key_hints(&[
+            ("↑↓←→", Msg::HelpSelect),
+            ("+/-", Msg::EqAdjust),
+            ("0", Msg::HelpZeroDb),
+            ("Enter", Msg::EqToggle),
+            ("a", Msg::EqAdd),
+            ("x", Msg::EqRemove),
+            ("p/Esc", Msg::EditorClose),
+        ]));
+        frame.render_widget(Paragraph::new(keys), chunks[1]);
+    }
+
+    /// Row label, highlighted on the selected row
+    fn label(&self, row: usize, text: String) -> Span<'static> {
+        let style = if row == self.row {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        Span::styled(format!("{:8}", text), style)
+    }
+
+    /// One field, reversed under the cursor
+    fn cell(&self, row: usize, field: Field, text: String, active: bool) -> Span<'static> {
+        let mut style = if active {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        if row == self.row && field == self.selected_field() {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        Span::styled(format!("{:>8}", text), style)
+    }
+}
+
+/// Settings of a filter, created switched on at its default cutoff if missing
+fn filter_mut(eq: &mut EqConfig, filter: PassFilter) -> &mut PassFilterConfig {
+    eq.pass_filter_mut(filter).get_or_insert(PassFilterConfig {
+        freq: filter.default_freq(),
+        enabled: true,
+    })
+}
@@ -0,0 +1,394 @@
+//! Feedback finder
+//!
+//! Listens to an output for the narrow tones that build up when a
+//! microphone hears its own speaker. Each FFT block is searched for peaks
+//! that stand well above the bins around them; a peak that stays at the
+//! same frequency block after block is followed, and once it has held for
+//! a second it is reported as ringing. Music moves on within that time, a
+//! feedback tone does not. The loudest ringing frequency can be cut with a
+//! temporary notch on the output.
+
+use std::f32::consts::TAU;
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::i18n::{tr, trf, Msg};
+use crate::ipc::MixerState;
+
+use super::app::key_hints;
+use super::spectrogram::fft;
+
+/// Samples per FFT (about 12 Hz resolution at 48 kHz)
+const FFT_SIZE: usize = 4096;
+
+/// Samples between blocks (half a block)
+const HOP: usize = FFT_SIZE / 2;
+
+/// Range searched in Hz; below it lies hum, above it little ever rings
+const FREQ_RANGE_HZ: (f32, f32) = (60.0, 16_000.0);
+
+/// Quietest peak considered in dBFS
+const MIN_LEVEL_DB: f32 = -50.0;
+
+/// How far a peak must stand above its surroundings in dB to be narrow
+const PROMINENCE_DB: f32 = 15.0;
+
+/// Bins on each side that make up a peak's surroundings
+const SURROUNDINGS: std::ops::RangeInclusive<usize> = 4..=12;
+
+/// How far in bins a followed peak may wander between blocks
+const TOLERANCE_BINS: f32 = 2.0;
+
+/// Blocks a followed peak may be missing before it is dropped
+const MAX_MISSED: u32 = 2;
+
+/// Seconds a peak must hold to be shown, and to count as ringing
+const BUILDING_SECONDS: f32 = 0.25;
+const RINGING_SECONDS: f32 = 1.0;
+
+/// Peaks listed at most
+const MAX_ROWS: usize = 8;
+
+/// Result of handling a key in the feedback finder
+pub enum FeedbackAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Close the view
+    Close,
+
+    /// Notch the loudest ringing frequency in Hz (None when nothing rings)
+    Notch(Option<f32>),
+
+    /// Clear the notches of the output
+    ClearNotches,
+}
+
+/// A narrow peak followed across blocks
+#[derive(Debug, Clone)]
+struct Track {
+    /// Position in (fractional) FFT bins
+    bin: f32,
+
+    /// Level in the latest block in dBFS
+    level_db: f32,
+
+    /// Blocks the peak has been present in
+    blocks: u32,
+
+    /// Blocks in a row the peak has been missing from
+    missed: u32,
+}
+
+/// Feedback finder on one output
+pub struct FeedbackView {
+    /// Output listened to
+    output: usize,
+
+    sample_rate: f32,
+
+    /// Hann window, and its sum for scaling to dBFS
+    window: Vec<f32>,
+    window_sum: f32,
+
+    /// Samples received and not yet analysed
+    pending: Vec<f32>,
+
+    /// FFT scratch (real and imaginary parts), then the level of each bin
+    re: Vec<f32>,
+    im: Vec<f32>,
+    levels: Vec<f32>,
+
+    tracks: Vec<Track>,
+}
+
+impl FeedbackView {
+    /// Open the finder on an output
+    pub fn new(output: usize, sample_rate: u32) -> Self {
+        let window: Vec<f32> = (0..FFT_SIZE)
+            .map(|i| 0.5 - 0.5 * (TAU * i as f32 / FFT_SIZE as f32).cos())
+            .collect();
+        Self {
+            output,
+            sample_rate: sample_rate as f32,
+            window_sum: window.iter().sum(),
+            window,
+            pending: Vec::with_capacity(sample_rate as usize),
+            re: vec![0.0; FFT_SIZE],
+            im: vec![0.0; FFT_SIZE],
+            levels: vec![0.0; FFT_SIZE / 2 + 1],
+            tracks: Vec::new(),
+        }
+    }
+
+    /// Output listened to
+    pub fn output(&self) -> usize {
+        self.output
+    }
+
+    /// Buffer for new samples of the output; call `analyse` after filling it
+    pub fn pending(&mut self) -> &mut Vec<f32> {
+        &mut self.pending
+    }
+
+    /// Search the samples received so far for narrow peaks
+    pub fn analyse(&mut self) {
+        let mut used = 0;
+        while self.pending.len() - used >= FFT_SIZE {
+            let block = &self.pending[used..used + FFT_SIZE];
+            for (i, (s, w)) in block.iter().zip(&self.window).enumerate() {
+                self.re[i] = s * w;
+                self.im[i] = 0.0;
+            }
+            fft(&mut self.re, &mut self.im);
+            let scale = 2.0 / self.window_sum;
+            for (k, level) in self.levels.iter_mut().enumerate() {
+                let magnitude = self.re[k].hypot(self.im[k]) * scale;
+                *level = 20.0 * magnitude.max(1e-10).log10();
+            }
+            let peaks = self.peaks();
+            self.follow(&peaks);
+            used += HOP;
+        }
+        self.pending.drain(..used);
+    }
+
+    /// Narrow peaks of the latest block as (fractional bin, level in dBFS)
+    fn peaks(&self) -> Vec<(f32, f32)> {
+        let bin_hz = self.sample_rate / FFT_SIZE as f32;
+        let last = self.levels.len() - 1 - SURROUNDINGS.end();
+        let from = ((FREQ_RANGE_HZ.0 / bin_hz) as usize).max(*SURROUNDINGS.end());
+        let to = ((FREQ_RANGE_HZ.1 / bin_hz) as usize).min(last);
+        let levels = &self.levels;
+        (from..=to)
+            .filter(|&k| {
+                let level = levels[k];
+                level >= MIN_LEVEL_DB && level > levels[k - 1] && level >= levels[k + 1]
+            })
+            .filter(|&k| {
+                let around: f32 = SURROUNDINGS
+                    .map(|d| levels[k - d] + levels[k + d])
+                    .sum::<f32>()
+                    / (2 * SURROUNDINGS.count()) as f32;
+                levels[k] - around >= PROMINENCE_DB
+            })
+            .map(|k| {
+                // Parabolic interpolation between the neighbouring bins
+                let (a, b, c) = (levels[k - 1], levels[k], levels[k + 1]);
+                let curve = a - 2.0 * b + c;
+                let offset = if curve < 0.0 {
+                    0.5 * (a - c) / curve
+                } else {
+                    0.0
+                };
+                (k as f32 + offset, b)
+            })
+            .collect()
+    }
+
+    /// Match the peaks of a block to the followed ones
+    fn follow(&mut self, peaks: &[(f32, f32)]) {
+        for track in &mut self.tracks {
+            track.missed += 1;
+        }
+        for &(bin, level_db) in peaks {
+            let nearest = self
+                .tracks
+                .iter_mut()
+                .filter(|t| (t.bin - bin).abs() <= TOLERANCE_BINS)
+                .min_by(|a, b| (a.bin - bin).abs().total_cmp(&(b.bin - bin).abs()));
+            match nearest {
+                Some(track) => {
+                    track.bin = bin;
+                    track.level_db = level_db;
+                    track.blocks += 1;
+                    track.missed = 0;
+                }
+                None => self.tracks.push(Track {
+                    bin,
+                    level_db,
+                    blocks: 1,
+                    missed: 0,
+                }),
+            }
+        }
+        self.tracks.retain(|t| t.missed <= MAX_MISSED);
+    }
+
+    /// Seconds a track has held
+    fn seconds(&self, track: &Track) -> f32 {
+        track.blocks as f32 * HOP as f32 / self.sample_rate
+    }
+
+    /// Frequency of a track in Hz
+    fn freq(&self, track: &Track) -> f32 {
+        track.bin * self.sample_rate / FFT_SIZE as f32
+    }
+
+    /// Tracks held long enough to show, loudest first
+    fn shown(&self) -> Vec<&Track> {
+        let mut shown: Vec<&Track> = self
+            .tracks
+            .iter()
+            .filter(|t| self.seconds(t) >= BUILDING_SECONDS)
+            .collect();
+        shown.sort_by(|a, b| b.level_db.total_cmp(&a.level_db));
+        shown.truncate(MAX_ROWS);
+        shown
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode) -> FeedbackAction {
+        match code {
+            KeyCode::Esc | KeyCode::Char('N') | KeyCode::Char('q') => FeedbackAction::Close,
+            KeyCode::Char('n') => {
+                // Forget the notched tone so it is not notched twice
+                let loudest = self
+                    .tracks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| self.seconds(t) >= RINGING_SECONDS)
+                    .max_by(|(_, a), (_, b)| a.level_db.total_cmp(&b.level_db))
+                    .map(|(i, _)| i);
+                FeedbackAction::Notch(loudest.map(|i| {
+                    let track = self.tracks.remove(i);
+                    self.freq(&track)
+                }))
+            }
+            KeyCode::Char('c') => FeedbackAction::ClearNotches,
+            _ => FeedbackAction::None,
+        }
+    }
+
+    /// Render the view into `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, state: &MixerState) {
+        let Some(output) = state.outputs.get(self.output) else {
+            return;
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", trf(Msg::FbTitle, &[&output.name])));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let shown = self.shown();
+        let lines: Vec<Line> = if shown.is_empty() {
+            vec![Line::styled(
+                trf(Msg::FbListening, &[&RINGING_SECONDS]),
+                Style::default().fg(Color::DarkGray),
+            )]
+        } else {
+            shown
+                .iter()
+                .map(|track| {
+                    let (label, style) = if self.seconds(track) >= RINGING_SECONDS {
+                        (
+                            Msg::FbRinging,
+                            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                        )
+                    } else {
+                        (Msg::FbBuilding, Style::default().fg(Color::Yellow))
+                    };
+                    Line::from(vec![
+                        Span::styled(format!("{:>7.0} Hz  ", self.freq(track)), style),
+                        Span::raw(format!("{:>6.1} dBFS  ", track.level_db)),
+                        Span::styled(
+                            format!("{:>5.1} s  ", self.seconds(track)),
+                            Style::default().fg(Color::Gray),
+                        ),
+                        Span::styled(tr(label), style),
+                    ])
+                })
+                .collect()
+        };
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+        let notches: Vec<String> = output
+            .notches
+            .iter()
+            .flatten()
+            .map(|freq| format!("{:.0} Hz", freq))
+            .collect();
+        let notches = if notches.is_empty() {
+            tr(Msg::FbNoNotches).to_string()
+        } else {
+            notches.join(" · ")
+        };
+        let notch_line = Line::from(vec![
+            Span::styled(
+                format!("{}: ", tr(Msg::FbNotches)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(notches, Style::default().fg(Color::Magenta)),
+        ]);
+        frame.render_widget(Paragraph::new(notch_line), chunks[1]);
+
+        let keys = Line::from(key_hints(&[
+            ("n", Msg::FbNotch),
+            ("c", Msg::FbClear),
+            ("N/Esc", Msg::EditorClose),
+        ]));
+        frame.render_widget(Paragraph::new(keys), chunks[2]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A second and a half of noise at about -40 dBFS, plus a tone
+    fn feed(view: &mut FeedbackView, tone: Option<f32>) {
+        let mut seed = 1u32;
+        let samples = (0..72000).map(|i| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let noise = (seed >> 8) as f32 / (1 << 24) as f32 - 0.5;
+            let sine = tone.map_or(0.0, |f| 0.1 * (TAU * f * i as f32 / 48000.0).sin());
+            0.03 * noise + sine
+        });
+        view.pending().extend(samples);
+        view.analyse();
+    }
+
+    #[test]
+    fn test_steady_tone_rings() {
+        let mut view = FeedbackView::new(0, 48000);
+        feed(&mut view, Some(2500.0));
+        let shown = view.shown();
+        assert_eq!(shown.len(), 1);
+        assert!(view.seconds(shown[0]) >= RINGING_SECONDS);
+
+        let FeedbackAction::Notch(Some(freq)) = view.handle_key(KeyCode::Char('n')) else {
+            panic!("nothing to notch");
+        };
+        assert!((freq - 2500.0).abs() < 3.0);
+        // Notched once only
+        assert!(matches!(
+            view.handle_key(KeyCode::Char('n')),
+            FeedbackAction::Notch(None)
+        ));
+    }
+
+    #[test]
+    fn test_noise_does_not_ring() {
+        let mut view = FeedbackView::new(0, 48000);
+        feed(&mut view, None);
+        assert!(view.shown().is_empty());
+    }
+}
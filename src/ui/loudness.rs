@@ -0,0 +1,229 @@
+//! Loudness history pane
+//!
+//! Keeps the loudness of every input, output and the master in one-second
+//! steps (mean K-weighted power, as LUFS) for the last minutes, and draws
+//! one channel's history as bars next to the channel strips, so level
+//! drift over a show is visible while mixing.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, RenderDirection, Sparkline, SparklineBar},
+    Frame,
+};
+
+use crate::audio::mean_square_to_lufs;
+use crate::i18n::{tr, trf, Msg};
+use crate::ipc::{MeterData, MixerState};
+
+use super::app::key_hints;
+
+/// Length of one history step
+const STEP: Duration = Duration::from_secs(1);
+
+/// Steps kept per channel (15 minutes)
+const HISTORY_STEPS: usize = 900;
+
+/// Bottom of the graph in LUFS
+const FLOOR_LUFS: f32 = -60.0;
+
+/// Loudness above which bars turn yellow, then red
+const WARN_LUFS: (f32, f32) = (-18.0, -10.0);
+
+/// Loudness over time per channel (inputs, outputs, then the master, as in
+/// the meter channel order)
+#[derive(Debug)]
+pub struct LoudnessHistory {
+    /// Sum of K-weighted mean squares in the running step, per channel
+    sums: Vec<f32>,
+
+    /// Meter blocks in the running step, per channel
+    blocks: Vec<u32>,
+
+    /// Start of the running step
+    step_start: Instant,
+
+    /// Finished steps per channel in LUFS, oldest first
+    history: Vec<VecDeque<f32>>,
+}
+
+impl LoudnessHistory {
+    /// Create an empty history for `channels` channels
+    pub fn new(channels: usize, now: Instant) -> Self {
+        Self {
+            sums: vec![0.0; channels],
+            blocks: vec![0; channels],
+            step_start: now,
+            history: vec![VecDeque::new(); channels],
+        }
+    }
+
+    /// Add a meter block (passthrough channels are ignored)
+    pub fn record(&mut self, meter: &MeterData) {
+        if let (Some(sum), Some(blocks)) = (
+            self.sums.get_mut(meter.channel_index),
+            self.blocks.get_mut(meter.channel_index),
+        ) {
+            *sum += meter.loudness_ms;
+            *blocks += 1;
+        }
+    }
+
+    /// Close the running step once it is complete
+    pub fn update(&mut self, now: Instant) {
+        if now.duration_since(self.step_start) < STEP {
+            return;
+        }
+        self.step_start = now;
+        for ((sum, blocks), history) in self
+            .sums
+            .iter_mut()
+            .zip(&mut self.blocks)
+            .zip(&mut self.history)
+        {
+            let lufs = if *blocks == 0 {
+                FLOOR_LUFS
+            } else {
+                mean_square_to_lufs(*sum / *blocks as f32).max(FLOOR_LUFS)
+            };
+            if history.len() == HISTORY_STEPS {
+                history.pop_front();
+            }
+            history.push_back(lufs);
+            *sum = 0.0;
+            *blocks = 0;
+        }
+    }
+
+    /// Finished steps of a channel, oldest first
+    fn steps(&self, channel: usize) -> impl DoubleEndedIterator<Item = f32> + '_ {
+        self.history.get(channel).into_iter().flatten().copied()
+    }
+
+    /// Render the history of `channel` into `area`
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        state: &MixerState,
+        channel: usize,
+        focused: bool,
+    ) {
+        let name = state
+            .inputs
+            .iter()
+            .chain(&state.outputs)
+            .chain(std::iter::once(&state.master))
+            .nth(channel)
+            .map_or("", |c| c.name.as_str());
+        let border = if focused {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border)
+            .title(format!(" {} ", trf(Msg::LoudnessTitle, &[&name])));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        // Newest step and loudest step on screen
+        let shown = chunks[1].width as usize;
+        let latest = self.steps(channel).next_back();
+        let loudest = self
+            .steps(channel)
+            .rev()
+            .take(shown)
+            .fold(None, |max: Option<f32>, v| {
+                Some(max.map_or(v, |m| m.max(v)))
+            });
+        let lufs = |value: Option<f32>| match value {
+            Some(v) if v > FLOOR_LUFS => format!("{:.1} LUFS", v),
+            _ => "-- LUFS".to_string(),
+        };
+        let summary = Line::from(vec![
+            Span::styled(
+                format!("{} ", tr(Msg::LoudnessNow)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::raw(lufs(latest)),
+            Span::styled(
+                format!("  {} ", tr(Msg::LoudnessMax)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::raw(lufs(loudest)),
+        ]);
+        frame.render_widget(Paragraph::new(summary), chunks[0]);
+
+        // Newest on the right
+        let bars: Vec<SparklineBar> = self
+            .steps(channel)
+            .rev()
+            .take(shown)
+            .map(|v| {
+                let color = if v >= WARN_LUFS.1 {
+                    Color::Red
+                } else if v >= WARN_LUFS.0 {
+                    Color::Yellow
+                } else {
+                    Color::Green
+                };
+                SparklineBar::from((v - FLOOR_LUFS).round() as u64)
+                    .style(Some(Style::default().fg(color)))
+            })
+            .collect();
+        let sparkline = Sparkline::default()
+            .data(bars)
+            .max((-FLOOR_LUFS) as u64)
+            .direction(RenderDirection::RightToLeft);
+        frame.render_widget(sparkline, chunks[1]);
+
+        let hints = if focused {
+            key_hints(&[("←/→", Msg::HelpSelect), ("V", Msg::LoudnessStrips)])
+        } else {
+            key_hints(&[("V", Msg::LoudnessFocus)])
+        };
+        frame.render_widget(Paragraph::new(Line::from(hints)), chunks[2]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_average_the_blocks_of_each_second() {
+        let start = Instant::now();
+        let mut history = LoudnessHistory::new(2, start);
+        // -20 LUFS on channel 0: mean square for -20 dB plus the K offset
+        let ms = 10f32.powf((-20.0 + 0.691) / 10.0);
+        for _ in 0..10 {
+            let mut meter = MeterData::mono(0, 0.1, 0.1);
+            meter.loudness_ms = ms;
+            history.record(&meter);
+        }
+        history.update(start + Duration::from_millis(500));
+        assert_eq!(history.steps(0).count(), 0);
+
+        history.update(start + STEP);
+        let steps: Vec<f32> = history.steps(0).collect();
+        assert_eq!(steps.len(), 1);
+        assert!((steps[0] + 20.0).abs() < 0.01, "{}", steps[0]);
+        // A channel without blocks sits on the floor
+        assert_eq!(history.steps(1).collect::<Vec<_>>(), [FLOOR_LUFS]);
+    }
+}
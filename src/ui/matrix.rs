@@ -0,0 +1,213 @@
+//! Routing matrix view
+//!
+//! Shows inputs as rows and outputs as columns. The cursor moves between
+//! crosspoints, which can be switched on/off, folded to mono or trimmed.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::i18n::{tr, Msg};
+use crate::ipc::{Crosspoint, MixerState, VOLUME_MAX_DB, VOLUME_MIN_DB};
+
+use super::app::key_hints;
+
+/// Send level change per trim key press
+const TRIM_STEP_DB: f32 = 1.0;
+
+/// Width of one output column
+const COLUMN_WIDTH: usize = 9;
+
+/// Result of handling a key in the matrix view
+pub enum MatrixAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Close the matrix view
+    Close,
+
+    /// Change the crosspoint from an input to an output
+    SetRoute {
+        input: usize,
+        output: usize,
+        crosspoint: Crosspoint,
+    },
+}
+
+/// State of the routing matrix view
+#[derive(Debug, Default)]
+pub struct MatrixView {
+    /// Selected input (row)
+    input: usize,
+
+    /// Selected output (column)
+    output: usize,
+}
+
+impl MatrixView {
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode, state: &MixerState) -> MatrixAction {
+        let (inputs, outputs) = (state.inputs.len(), state.outputs.len());
+        if inputs == 0 || outputs == 0 {
+            return MatrixAction::Close;
+        }
+        self.input = self.input.min(inputs - 1);
+        self.output = self.output.min(outputs - 1);
+
+        let current = state.routing.get(self.input, self.output);
+        let crosspoint = match code {
+            KeyCode::Up => {
+                self.input = (self.input + inputs - 1) % inputs;
+                return MatrixAction::None;
+            }
+            KeyCode::Down => {
+                self.input = (self.input + 1) % inputs;
+                return MatrixAction::None;
+            }
+            KeyCode::Left => {
+                self.output = (self.output + outputs - 1) % outputs;
+                return MatrixAction::None;
+            }
+            KeyCode::Right => {
+                self.output = (self.output + 1) % outputs;
+                return MatrixAction::None;
+            }
+            KeyCode::Esc | KeyCode::Char('r') | KeyCode::Char('q') => return MatrixAction::Close,
+            KeyCode::Enter | KeyCode::Char(' ') => Crosspoint {
+                enabled: !current.enabled,
+                ..current
+            },
+            KeyCode::Char('m') => Crosspoint {
+                mono: !current.mono,
+                ..current
+            },
+            KeyCode::Char('+') | KeyCode::Char('=') => Self::trim(current, TRIM_STEP_DB),
+            KeyCode::Char('-') => Self::trim(current, -TRIM_STEP_DB),
+            KeyCode::Char('0') => Crosspoint {
+                gain_db: 0.0,
+                ..current
+            },
+            _ => return MatrixAction::None,
+        };
+
+        MatrixAction::SetRoute {
+            input: self.input,
+            output: self.output,
+            crosspoint,
+        }
+    }
+
+    /// Adjust a send level, clamped to the fader range
+    fn trim(crosspoint: Crosspoint, delta: f32) -> Crosspoint {
+        Crosspoint {
+            gain_db: (crosspoint.gain_db + delta).clamp(VOLUME_MIN_DB, VOLUME_MAX_DB),
+            ..crosspoint
+        }
+    }
+
+    /// Render the matrix into `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, state: &MixerState) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", tr(Msg::MatrixTitle)));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(2), Constraint::Length(1)])
+            .split(inner);
+
+        let name_width = state
+            .inputs
+            .iter()
+            .map(|c| c.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(8);
+
+        // Header row: output names
+        let mut header = vec![Span::raw(format!("{:width$} ", "", width = name_width))];
+        for (o, output) in state.outputs.iter().enumerate() {
+            let style = if o == self.output {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            header.push(Span::styled(
+                format!(
+                    "{:^width$}",
+                    truncate(&output.name, COLUMN_WIDTH - 1),
+                    width = COLUMN_WIDTH
+                ),
+                style,
+            ));
+        }
+
+        // Scroll so the selected row stays visible
+        let visible_rows = (chunks[0].height as usize).saturating_sub(1).max(1);
+        let first_row = self.input.saturating_sub(visible_rows - 1);
+
+        let mut lines = vec![Line::from(header)];
+        for (i, input) in state.inputs.iter().enumerate().skip(first_row) {
+            let name_style = if i == self.input {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let mut spans = vec![Span::styled(
+                format!("{:width$} ", input.name, width = name_width),
+                name_style,
+            )];
+            for o in 0..state.outputs.len() {
+                let crosspoint = state.routing.get(i, o);
+                let text = if !crosspoint.enabled {
+                    tr(Msg::EditorTapOff).to_string()
+                } else if crosspoint.mono {
+                    format!("{:+.1}M", crosspoint.gain_db)
+                } else {
+                    format!("{:+.1}", crosspoint.gain_db)
+                };
+                let mut style = match (crosspoint.enabled, crosspoint.mono) {
+                    (false, _) => Style::default().fg(Color::DarkGray),
+                    (true, true) => Style::default().fg(Color::Yellow),
+                    (true, false) => Style::default().fg(Color::Green),
+                };
+                if i == self.input && o == self.output {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                spans.push(Span::styled(
+                    format!("{:^width$}", text, width = COLUMN_WIDTH),
+                    style,
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+        let keys = Line::from(key_hints(&[
+            ("↑↓←→", Msg::HelpSelect),
+            ("Enter", Msg::MatrixToggle),
+            ("m", Msg::MatrixMono),
+            ("+/-", Msg::MatrixTrim),
+            ("0", Msg::HelpZeroDb),
+            ("r/Esc", Msg::EditorClose),
+        ]));
+        frame.render_widget(Paragraph::new(keys), chunks[1]);
+    }
+}
+
+/// Shorten a name to at most `max` characters
+fn truncate(name: &str, max: usize) -> String {
+    name.chars().take(max).collect()
+}
@@ -3,6 +3,21 @@
 //! Provides the terminal user interface using ratatui.
 
 mod app;
+mod balance;
+mod calibration;
+mod editor;
+mod eq;
+mod feedback;
+mod loudness;
+mod matrix;
+mod mode;
+mod multiband;
+mod oscilloscope;
+mod reminders;
+mod rta;
+mod spectrogram;
+mod suspend;
+mod tap_tempo;
 mod widgets;
 
 pub use app::App;
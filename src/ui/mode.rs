@@ -0,0 +1,144 @@
+//! UI modes
+//!
+//! Exactly one mode owns the keyboard at a time: the channel strips, an
+//! overlay on top of them, or one of the full views (config editor, routing
+//! matrix, EQ bands, multiband page, calibration, stereo balance,
+//! spectrogram, oscilloscope, feedback finder), or the strips with a flip
+//! layer on the Up/Down keys. Each mode has its own keymap, so a key only
+//! means one thing at a time, and opening a view replaces whatever mode was
+//! active. The title bar names the active mode.
+//! The prompt for substitute connections is not a mode of its own: devices
+//! can go missing in any mode, so it takes the keys above all of them.
+
+use crate::i18n::Msg;
+
+use super::balance::BalanceView;
+use super::calibration::CalibrationView;
+use super::editor::ConfigEditor;
+use super::eq::EqView;
+use super::feedback::FeedbackView;
+use super::matrix::MatrixView;
+use super::multiband::MultibandView;
+use super::oscilloscope::OscilloscopeView;
+use super::rta::RtaView;
+use super::spectrogram::SpectrogramView;
+
+/// Overlay drawn over the strips; keys it does not use go to the strips
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlay {
+    /// Control and port diagnostics
+    Diagnostics,
+
+    /// Per-input contributions to an output
+    Contributions(usize),
+
+    /// Goniometer and correlation meter of a stereo output
+    Goniometer(usize),
+
+    /// Clip events of the session
+    Clips,
+
+    /// Selected channel across the whole terminal
+    BigMeter,
+}
+
+/// Parameter the Up/Down keys set in place of the faders, as when a
+/// console flips its fader row to another layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlipLayer {
+    /// Input trim
+    Trim,
+
+    /// Volume of the inputs' capture devices
+    DeviceGain,
+
+    /// Weight of the inputs in the automixer group
+    AutomixWeight,
+
+    /// Send level of the inputs to an output
+    Send(usize),
+}
+
+impl FlipLayer {
+    /// Layer after this one with `outputs` outputs, inputs with a capture
+    /// device or not and an automixer group or not (None after the last)
+    pub fn next(self, outputs: usize, devices: bool, automix: bool) -> Option<Self> {
+        match self {
+            FlipLayer::Trim if devices => Some(FlipLayer::DeviceGain),
+            FlipLayer::Trim | FlipLayer::DeviceGain if automix => Some(FlipLayer::AutomixWeight),
+            FlipLayer::Trim | FlipLayer::DeviceGain | FlipLayer::AutomixWeight => {
+                (outputs > 0).then_some(FlipLayer::Send(0))
+            }
+            FlipLayer::Send(output) => {
+                (output + 1 < outputs).then_some(FlipLayer::Send(output + 1))
+            }
+        }
+    }
+}
+
+/// Mode owning the keyboard
+pub enum Mode {
+    /// Channel strips
+    Normal,
+
+    /// Strips with an overlay on top
+    Overlay(Overlay),
+
+    /// Strips with the Up/Down keys on a flip layer
+    Flip(FlipLayer),
+
+    /// Config editor
+    Editor(Box<ConfigEditor>),
+
+    /// Routing matrix
+    Matrix(MatrixView),
+
+    /// EQ band editor of an input
+    Eq(EqView),
+
+    /// Multiband compressor page of an output
+    Multiband(MultibandView),
+
+    /// Speaker calibration run
+    Calibration(CalibrationView),
+
+    /// Stereo balance measurement of an input
+    Balance(BalanceView),
+
+    /// Spectrogram of an input or output
+    Spectrogram(Box<SpectrogramView>),
+
+    /// Oscilloscope of an input or output
+    Oscilloscope(Box<OscilloscopeView>),
+
+    /// Third-octave analyzer of an input or output
+    Rta(Box<RtaView>),
+
+    /// Feedback finder on an output
+    Feedback(Box<FeedbackView>),
+}
+
+impl Mode {
+    /// Name shown in the title bar (None for the strips)
+    pub fn label(&self) -> Option<Msg> {
+        match self {
+            Mode::Normal => None,
+            Mode::Overlay(Overlay::Diagnostics) => Some(Msg::ModeDiagnostics),
+            Mode::Overlay(Overlay::Contributions(_)) => Some(Msg::ModeContributions),
+            Mode::Overlay(Overlay::Goniometer(_)) => Some(Msg::ModeGoniometer),
+            Mode::Overlay(Overlay::Clips) => Some(Msg::ModeClips),
+            Mode::Overlay(Overlay::BigMeter) => Some(Msg::ModeBigMeter),
+            Mode::Flip(_) => Some(Msg::ModeFlip),
+            Mode::Editor(_) => Some(Msg::ModeEditor),
+            Mode::Matrix(_) => Some(Msg::ModeMatrix),
+            Mode::Eq(_) => Some(Msg::ModeEq),
+            Mode::Multiband(_) => Some(Msg::ModeMultiband),
+            Mode::Calibration(_) => Some(Msg::ModeCalibration),
+            Mode::Balance(_) => Some(Msg::ModeBalance),
+            Mode::Spectrogram(_) => Some(Msg::ModeSpectrogram),
+            Mode::Oscilloscope(_) => Some(Msg::ModeOscilloscope),
+            Mode::Rta(_) => Some(Msg::ModeRta),
+            Mode::Feedback(_) => Some(Msg::ModeFeedback),
+        }
+    }
+}
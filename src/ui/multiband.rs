@@ -0,0 +1,326 @@
+//! Multiband compressor page
+//!
+//! Shows the settings of an output's three-band compressor next to the live
+//! gain reduction of each band. Rows are parameters, columns the bands; the
+//! crossovers sit between the bands and the envelope times apply to all.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::config::{
+    MultibandConfig, EQ_FREQ_RANGE, MULTIBAND_BANDS, MULTIBAND_MAKEUP_RANGE_DB,
+    MULTIBAND_RATIO_RANGE, MULTIBAND_THRESHOLD_RANGE,
+};
+use crate::i18n::{tr, trf, Msg};
+use crate::ipc::MixerState;
+
+use super::app::key_hints;
+
+/// Threshold and makeup change per key press in dB
+const GAIN_STEP_DB: f32 = 1.0;
+
+/// Ratio, attack and release change per key press (factor)
+const FACTOR_STEP: f32 = 1.25;
+
+/// Crossover change per key press (a sixth of an octave)
+const FREQ_STEP: f32 = 1.122_462;
+
+/// Smallest spacing of the two crossovers (factor)
+const MIN_CROSSOVER_SPACING: f32 = 1.5;
+
+/// Attack range in ms offered by the page
+const ATTACK_RANGE_MS: (f32, f32) = (0.5, 200.0);
+
+/// Release range in ms offered by the page
+const RELEASE_RANGE_MS: (f32, f32) = (10.0, 2_000.0);
+
+/// Editable rows, top to bottom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Param {
+    Threshold,
+    Ratio,
+    Makeup,
+    Crossover,
+    Attack,
+    Release,
+}
+
+impl Param {
+    const ALL: [Param; 6] = [
+        Param::Threshold,
+        Param::Ratio,
+        Param::Makeup,
+        Param::Crossover,
+        Param::Attack,
+        Param::Release,
+    ];
+
+    /// Editable cells in the row
+    fn cells(self) -> usize {
+        match self {
+            Param::Threshold | Param::Ratio | Param::Makeup => MULTIBAND_BANDS,
+            Param::Crossover => 2,
+            Param::Attack | Param::Release => 1,
+        }
+    }
+
+    fn label(self) -> Msg {
+        match self {
+            Param::Threshold => Msg::MbThreshold,
+            Param::Ratio => Msg::MbRatio,
+            Param::Makeup => Msg::MbMakeup,
+            Param::Crossover => Msg::MbCrossover,
+            Param::Attack => Msg::MbAttack,
+            Param::Release => Msg::MbRelease,
+        }
+    }
+}
+
+/// Result of handling a key in the multiband page
+pub enum MultibandAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Close the page
+    Close,
+
+    /// Replace the compressor settings of the output
+    Set {
+        output: usize,
+        settings: MultibandConfig,
+    },
+}
+
+/// State of the multiband page for one output
+#[derive(Debug)]
+pub struct MultibandView {
+    /// Output being edited
+    output: usize,
+
+    /// Selected row
+    row: usize,
+
+    /// Selected cell of the row
+    column: usize,
+}
+
+impl MultibandView {
+    /// Open the page on an output
+    pub fn new(output: usize) -> Self {
+        Self {
+            output,
+            row: 0,
+            column: 0,
+        }
+    }
+
+    /// Settings shown for an output (switched off defaults if it has none)
+    fn settings(state: &MixerState, output: usize) -> Option<MultibandConfig> {
+        let channel = state.outputs.get(output)?;
+        Some(channel.multiband.unwrap_or(MultibandConfig {
+            enabled: false,
+            ..Default::default()
+        }))
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode, state: &MixerState) -> MultibandAction {
+        let Some(current) = Self::settings(state, self.output) else {
+            return MultibandAction::Close;
+        };
+        let mut settings = current;
+        let rows = Param::ALL.len();
+
+        match code {
+            KeyCode::Up => self.row = (self.row + rows - 1) % rows,
+            KeyCode::Down => self.row = (self.row + 1) % rows,
+            KeyCode::Left => self.column = self.column().saturating_sub(1),
+            KeyCode::Right => self.column = (self.column() + 1).min(self.param().cells() - 1),
+            KeyCode::Esc | KeyCode::Char('p') | KeyCode::Char('q') => {
+                return MultibandAction::Close
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => self.adjust(&mut settings, 1.0),
+            KeyCode::Char('-') => self.adjust(&mut settings, -1.0),
+            KeyCode::Enter | KeyCode::Char(' ') => settings.enabled = !settings.enabled,
+            _ => return MultibandAction::None,
+        }
+
+        if settings == current {
+            MultibandAction::None
+        } else {
+            MultibandAction::Set {
+                output: self.output,
+                settings,
+            }
+        }
+    }
+
+    fn param(&self) -> Param {
+        Param::ALL[self.row]
+    }
+
+    /// Selected cell, kept inside the rows with fewer cells
+    fn column(&self) -> usize {
+        self.column.min(self.param().cells() - 1)
+    }
+
+    /// Step the selected cell up (+1) or down (-1)
+    fn adjust(&self, settings: &mut MultibandConfig, direction: f32) {
+        let factor = FACTOR_STEP.powf(direction);
+        let column = self.column();
+        match self.param() {
+            Param::Threshold => {
+                let threshold = &mut settings.bands[column].threshold_db;
+                *threshold = (*threshold + direction * GAIN_STEP_DB)
+                    .clamp(MULTIBAND_THRESHOLD_RANGE.0, MULTIBAND_THRESHOLD_RANGE.1);
+            }
+            Param::Ratio => {
+                let ratio = &mut settings.bands[column].ratio;
+                *ratio = (*ratio * factor).clamp(MULTIBAND_RATIO_RANGE.0, MULTIBAND_RATIO_RANGE.1);
+            }
+            Param::Makeup => {
+                let makeup = &mut settings.bands[column].makeup_db;
+                *makeup = (*makeup + direction * GAIN_STEP_DB)
+                    .clamp(-MULTIBAND_MAKEUP_RANGE_DB, MULTIBAND_MAKEUP_RANGE_DB);
+            }
+            Param::Crossover => {
+                let [low, high] = settings.crossovers_hz;
+                let (min, max) = if column == 0 {
+                    let max = (high / MIN_CROSSOVER_SPACING).max(EQ_FREQ_RANGE.0);
+                    (EQ_FREQ_RANGE.0, max)
+                } else {
+                    let min = (low * MIN_CROSSOVER_SPACING).min(EQ_FREQ_RANGE.1);
+                    (min, EQ_FREQ_RANGE.1)
+                };
+                let freq = &mut settings.crossovers_hz[column];
+                *freq = (*freq * FREQ_STEP.powf(direction)).round().clamp(min, max);
+            }
+            Param::Attack => {
+                settings.attack_ms =
+                    (settings.attack_ms * factor).clamp(ATTACK_RANGE_MS.0, ATTACK_RANGE_MS.1);
+            }
+            Param::Release => {
+                settings.release_ms =
+                    (settings.release_ms * factor).clamp(RELEASE_RANGE_MS.0, RELEASE_RANGE_MS.1);
+            }
+        }
+    }
+
+    /// Render the page into `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, state: &MixerState) {
+        let (Some(channel), Some(settings)) = (
+            state.outputs.get(self.output),
+            Self::settings(state, self.output),
+        ) else {
+            return;
+        };
+        let (switch, switch_style) = if settings.enabled {
+            (tr(Msg::MbOn), Style::default().fg(Color::Green))
+        } else {
+            (tr(Msg::MbOff), Style::default().fg(Color::DarkGray))
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(Line::from(vec![
+                Span::raw(format!(" {} ", trf(Msg::MbTitle, &[&channel.name]))),
+                Span::styled(format!("[{}] ", switch), switch_style),
+            ]));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(2), Constraint::Length(1)])
+            .split(inner);
+
+        let header = Style::default().fg(Color::Gray);
+        let mut bands = vec![Span::raw(format!("{:12}", ""))];
+        for band in [Msg::EqLow, Msg::EqMid, Msg::EqHigh] {
+            bands.push(Span::styled(format!("{:>9}", tr(band)), header));
+        }
+        let mut lines = vec![Line::from(bands)];
+
+        // Live reduction per band
+        let mut reduction = vec![Span::styled(format!("{:12}", tr(Msg::MbReduction)), header)];
+        for &db in &channel.multiband_db {
+            let style = if settings.enabled && db >= 0.1 {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            reduction.push(Span::styled(format!("{:>9}", format!("-{:.1}", db)), style));
+        }
+        lines.push(Line::from(reduction));
+
+        for (row, param) in Param::ALL.into_iter().enumerate() {
+            let cells: Vec<String> = match param {
+                Param::Threshold => settings
+                    .bands
+                    .iter()
+                    .map(|b| format!("{:.0} dB", b.threshold_db))
+                    .collect(),
+                Param::Ratio => settings
+                    .bands
+                    .iter()
+                    .map(|b| format!("{:.1}:1", b.ratio))
+                    .collect(),
+                Param::Makeup => settings
+                    .bands
+                    .iter()
+                    .map(|b| format!("{:+.0} dB", b.makeup_db))
+                    .collect(),
+                Param::Crossover => settings
+                    .crossovers_hz
+                    .iter()
+                    .map(|f| format!("{:.0} Hz", f))
+                    .collect(),
+                Param::Attack => vec![format!("{:.1} ms", settings.attack_ms)],
+                Param::Release => vec![format!("{:.0} ms", settings.release_ms)],
+            };
+
+            let label_style = if row == self.row {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let mut spans = vec![Span::styled(
+                format!("{:12}", tr(param.label())),
+                label_style,
+            )];
+            // Crossovers are shown between the bands they separate
+            if param == Param::Crossover {
+                spans.push(Span::raw(format!("{:5}", "")));
+            }
+            for (column, text) in cells.into_iter().enumerate() {
+                let mut style = if settings.enabled {
+                    Style::default().fg(Color::Green)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                if row == self.row && column == self.column() {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                spans.push(Span::styled(format!("{:>9}", text), style));
+            }
+            lines.push(Line::from(spans));
+        }
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+        let keys = Line::from(key_hints(&[
+            ("↑↓←→", Msg::HelpSelect),
+            ("+/-", Msg::EqAdjust),
+            ("Enter", Msg::EqToggle),
+            ("p/Esc", Msg::EditorClose),
+        ]));
+        frame.render_widget(Paragraph::new(keys), chunks[1]);
+    }
+}
@@ -0,0 +1,365 @@
+//! Oscilloscope view
+//!
+//! The waveform of one channel over a few milliseconds, from the same feed
+//! as the spectrogram. An edge trigger holds periodic signals still: the
+//! window starts just before the latest crossing of the trigger level, or
+//! runs free when there is none. The vertical scale is fixed at full scale,
+//! so a DC offset shows as a trace lifted off the center line and clipping
+//! as flat tops against the red limits; the readout gives both in numbers.
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        canvas::{Canvas, Line as CanvasLine},
+        Block, Borders, Paragraph,
+    },
+    Frame,
+};
+
+use crate::i18n::{tr, trf, Msg};
+use crate::ipc::{AnalyzerSource, MeterData, MixerState};
+
+use super::app::key_hints;
+
+/// Time spans across the screen in ms, narrowest first
+const SPANS_MS: [f32; 9] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0];
+
+/// Span shown when the view opens (20 ms, one cycle of 50 Hz)
+const DEFAULT_SPAN: usize = 4;
+
+/// Seconds of signal kept to search for a trigger
+const HISTORY_SECONDS: f32 = 1.0;
+
+/// Share of the window ahead of the trigger point
+const PRE_TRIGGER: f32 = 0.1;
+
+/// Trigger level change per key press
+const LEVEL_STEP: f32 = 0.05;
+
+/// Edge the window is aligned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    Rising,
+    Falling,
+
+    /// No trigger: the latest samples
+    Free,
+}
+
+impl Trigger {
+    fn next(self) -> Self {
+        match self {
+            Trigger::Rising => Trigger::Falling,
+            Trigger::Falling => Trigger::Free,
+            Trigger::Free => Trigger::Rising,
+        }
+    }
+
+    fn label(self) -> Msg {
+        match self {
+            Trigger::Rising => Msg::OscRising,
+            Trigger::Falling => Msg::OscFalling,
+            Trigger::Free => Msg::OscFree,
+        }
+    }
+
+    /// Whether the signal crosses `level` on this edge from `a` to `b`
+    fn crosses(self, level: f32, a: f32, b: f32) -> bool {
+        match self {
+            Trigger::Rising => a < level && b >= level,
+            Trigger::Falling => a > level && b <= level,
+            Trigger::Free => false,
+        }
+    }
+}
+
+/// Result of handling a key in the oscilloscope view
+pub enum OscilloscopeAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Close the view
+    Close,
+
+    /// Show the third-octave analyzer of the same channel instead
+    Switch,
+}
+
+/// Oscilloscope of one channel
+pub struct OscilloscopeView {
+    /// Channel shown
+    source: AnalyzerSource,
+
+    sample_rate: f32,
+
+    /// Index into `SPANS_MS`
+    span: usize,
+
+    trigger: Trigger,
+
+    /// Trigger level (linear, -1 to +1)
+    level: f32,
+
+    /// Whether new samples are ignored, holding the picture
+    frozen: bool,
+
+    /// Samples received and not yet added to the history
+    pending: Vec<f32>,
+
+    /// Recent samples, oldest first
+    history: Vec<f32>,
+}
+
+impl OscilloscopeView {
+    /// Open the view on a channel
+    pub fn new(source: AnalyzerSource, sample_rate: u32) -> Self {
+        Self {
+            source,
+            sample_rate: sample_rate as f32,
+            span: DEFAULT_SPAN,
+            trigger: Trigger::Rising,
+            level: 0.0,
+            frozen: false,
+            pending: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Channel shown
+    pub fn source(&self) -> AnalyzerSource {
+        self.source
+    }
+
+    /// Buffer for new samples of the channel; call `update` after filling it
+    pub fn pending(&mut self) -> &mut Vec<f32> {
+        &mut self.pending
+    }
+
+    /// Move the samples received so far into the history (unless frozen)
+    pub fn update(&mut self) {
+        if !self.frozen {
+            self.history.extend_from_slice(&self.pending);
+            let keep = (HISTORY_SECONDS * self.sample_rate) as usize;
+            if self.history.len() > keep {
+                self.history.drain(..self.history.len() - keep);
+            }
+        }
+        self.pending.clear();
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode) -> OscilloscopeAction {
+        match code {
+            KeyCode::Esc | KeyCode::Char('S') | KeyCode::Char('q') => {
+                return OscilloscopeAction::Close
+            }
+            KeyCode::Tab => return OscilloscopeAction::Switch,
+            KeyCode::Char('+') | KeyCode::Char('=') => self.span = self.span.saturating_sub(1),
+            KeyCode::Char('-') => self.span = (self.span + 1).min(SPANS_MS.len() - 1),
+            KeyCode::Char('t') => self.trigger = self.trigger.next(),
+            KeyCode::Up => self.level = (self.level + LEVEL_STEP).min(1.0),
+            KeyCode::Down => self.level = (self.level - LEVEL_STEP).max(-1.0),
+            KeyCode::Char(' ') => self.frozen = !self.frozen,
+            _ => {}
+        }
+        OscilloscopeAction::None
+    }
+
+    /// Samples across the screen
+    fn span_samples(&self) -> usize {
+        ((SPANS_MS[self.span] / 1000.0 * self.sample_rate) as usize).max(2)
+    }
+
+    /// Samples shown: the latest window starting just before a trigger
+    /// crossing, or the latest samples when nothing crosses
+    fn window(&self) -> &[f32] {
+        let len = self.span_samples();
+        let history = &self.history;
+        if history.len() <= len {
+            return history;
+        }
+        let pre = (len as f32 * PRE_TRIGGER) as usize;
+        let latest = history.len() - len + pre;
+        (pre.max(1)..=latest)
+            .rev()
+            .find(|&i| self.trigger.crosses(self.level, history[i - 1], history[i]))
+            .map_or(&history[history.len() - len..], |i| {
+                &history[i - pre..i - pre + len]
+            })
+    }
+
+    /// Render the view into `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, state: &MixerState) {
+        let channel = match self.source {
+            AnalyzerSource::Input(i) => state.inputs.get(i),
+            AnalyzerSource::Output(o) => state.outputs.get(o),
+        };
+        let Some(channel) = channel else {
+            return;
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", trf(Msg::OscTitle, &[&channel.name])));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(2),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let window = self.window();
+        frame.render_widget(Paragraph::new(self.readout(window)), chunks[0]);
+
+        // Column by column at the canvas resolution: a line through the
+        // samples when zoomed in, the range of each column when zoomed out
+        let len = self.span_samples() as f64;
+        let mut trace = Vec::new();
+        let dots = chunks[1].width as usize * 2;
+        if window.len() <= dots {
+            for (i, pair) in window.windows(2).enumerate() {
+                trace.push((i as f64, pair[0] as f64, (i + 1) as f64, pair[1] as f64));
+            }
+        } else {
+            for dot in 0..dots {
+                let from = dot * window.len() / dots;
+                let to = ((dot + 1) * window.len() / dots).max(from + 1);
+                let (low, high) = window[from..to]
+                    .iter()
+                    .fold((f32::MAX, f32::MIN), |(l, h), s| (l.min(*s), h.max(*s)));
+                let x = from as f64;
+                trace.push((x, low as f64, x, high as f64));
+            }
+        }
+
+        let canvas = Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([0.0, len])
+            .y_bounds([-1.1, 1.1])
+            .paint(|ctx| {
+                let level_line = |y: f64, color| CanvasLine {
+                    x1: 0.0,
+                    y1: y,
+                    x2: len,
+                    y2: y,
+                    color,
+                };
+                ctx.draw(&level_line(1.0, Color::Red));
+                ctx.draw(&level_line(-1.0, Color::Red));
+                ctx.draw(&level_line(0.0, Color::DarkGray));
+                if self.trigger != Trigger::Free && self.level != 0.0 {
+                    ctx.draw(&level_line(self.level as f64, Color::Yellow));
+                }
+                ctx.layer();
+                for &(x1, y1, x2, y2) in &trace {
+                    ctx.draw(&CanvasLine {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        color: Color::Green,
+                    });
+                }
+            });
+        frame.render_widget(canvas, chunks[1]);
+
+        let keys = Line::from(key_hints(&[
+            ("+/-", Msg::OscZoom),
+            ("t", Msg::OscTrigger),
+            ("↑↓", Msg::OscLevel),
+            ("Space", Msg::OscFreeze),
+            ("Tab", Msg::HelpRta),
+            ("S/Esc", Msg::EditorClose),
+        ]));
+        frame.render_widget(Paragraph::new(keys), chunks[2]);
+    }
+
+    /// Span, trigger, DC offset and peak of the window
+    fn readout(&self, window: &[f32]) -> Line<'static> {
+        let gray = Style::default().fg(Color::Gray);
+        let dc = window.iter().sum::<f32>() / window.len().max(1) as f32;
+        let peak = window.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        let peak_db = format!("{:.1}", MeterData::linear_to_db(peak));
+        let peak_style = if peak >= 1.0 {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![
+            Span::styled(format!("{} ms  ", SPANS_MS[self.span]), gray),
+            Span::styled(
+                format!("{} {:+.2}  ", tr(self.trigger.label()), self.level),
+                gray,
+            ),
+            Span::raw(format!("DC {:+.3}  ", dc)),
+            Span::styled(trf(Msg::OscPeak, &[&peak_db]), peak_style),
+        ];
+        if self.frozen {
+            spans.push(Span::styled(
+                format!("  {}", tr(Msg::OscFrozen)),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        Line::from(spans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    /// A view holding a second of a 100 Hz sine with a DC offset
+    fn view() -> OscilloscopeView {
+        let mut view = OscilloscopeView::new(AnalyzerSource::Input(0), 48000);
+        let sine = |i: usize| 0.1 + 0.5 * (TAU * 100.0 * (i as f32 + 0.3) / 48000.0).sin();
+        view.pending().extend((0..48000).map(sine));
+        view.update();
+        view
+    }
+
+    #[test]
+    fn test_trigger_holds_the_edge() {
+        let mut view = view();
+        let pre = (view.span_samples() as f32 * PRE_TRIGGER) as usize;
+        let window = view.window();
+        assert_eq!(window.len(), 960);
+        assert!(window[pre - 1] < 0.0 && window[pre] >= 0.0);
+
+        view.handle_key(KeyCode::Char('t'));
+        view.handle_key(KeyCode::Up);
+        let window = view.window();
+        assert!(window[pre - 1] > 0.05 && window[pre] <= 0.05);
+
+        // Free running: the latest samples
+        view.handle_key(KeyCode::Char('t'));
+        assert_eq!(view.window(), &view.history[48000 - 960..]);
+    }
+
+    #[test]
+    fn test_freeze_holds_the_picture() {
+        let mut view = view();
+        view.handle_key(KeyCode::Char(' '));
+        view.pending().extend([0.9; 480]);
+        view.update();
+        assert!(view.pending.is_empty());
+        assert!(view.history.iter().all(|s| *s < 0.9));
+
+        // Never longer than a second
+        view.handle_key(KeyCode::Char(' '));
+        view.pending().extend([0.9; 480]);
+        view.update();
+        assert_eq!(view.history.len(), 48000);
+    }
+}
@@ -0,0 +1,189 @@
+//! Reminders for solo and mute left engaged
+//!
+//! A forgotten solo silences the rest of the mix, and a mic muted while its
+//! source is live is the classic "streamed 20 minutes muted" failure. The
+//! mixer state is sampled every frame; a solo that stays on, or an input
+//! that has been muted for longer than the configured time while it carries
+//! signal, raises a reminder until it is released or acknowledged.
+
+use std::time::{Duration, Instant};
+
+use crate::config::ReminderConfig;
+use crate::ipc::{MeterData, MixerState};
+use crate::solo;
+
+/// A reminder that is due
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reminder {
+    /// Some input or output has been soloed for this long
+    Solo(Duration),
+
+    /// An input with signal has been muted for this long
+    Muted { input: usize, since: Duration },
+}
+
+/// Tracks how long solos and mutes have been engaged
+#[derive(Debug)]
+pub struct Reminders {
+    /// Solo time before a reminder (None = never)
+    solo_after: Option<Duration>,
+
+    /// Mute time before a reminder (None = never)
+    mute_after: Option<Duration>,
+
+    /// Source peak counting as signal (linear)
+    signal_threshold: f32,
+
+    /// When the current solo started (or was last acknowledged)
+    solo_since: Option<Instant>,
+
+    /// When each input was muted (or last acknowledged)
+    muted_since: Vec<Option<Instant>>,
+
+    /// Whether each muted input currently carries signal
+    has_signal: Vec<bool>,
+}
+
+impl Reminders {
+    /// Create the tracker for `inputs` input channels (no reminders without a config)
+    pub fn new(config: Option<&ReminderConfig>, inputs: usize) -> Self {
+        let minutes = |m: Option<f32>| m.map(|m| Duration::from_secs_f32(m * 60.0));
+        Self {
+            solo_after: config.and_then(|c| minutes(c.solo_minutes)),
+            mute_after: config.and_then(|c| minutes(c.mute_minutes)),
+            signal_threshold: MeterData::db_to_linear(
+                config.map_or(-50.0, |c| c.signal_threshold_db),
+            ),
+            solo_since: None,
+            muted_since: vec![None; inputs],
+            has_signal: vec![false; inputs],
+        }
+    }
+
+    /// Sample the mixer state
+    pub fn update(&mut self, state: &MixerState, now: Instant) {
+        if solo::active(state) {
+            self.solo_since.get_or_insert(now);
+        } else {
+            self.solo_since = None;
+        }
+
+        for ((input, since), signal) in state
+            .inputs
+            .iter()
+            .zip(&mut self.muted_since)
+            .zip(&mut self.has_signal)
+        {
+            if input.muted {
+                since.get_or_insert(now);
+            } else {
+                *since = None;
+            }
+            // Held peaks bridge the gaps between words
+            *signal = input.held_peak() >= self.signal_threshold;
+        }
+    }
+
+    /// The most important reminder due at `now`, if any
+    pub fn due(&self, now: Instant) -> Option<Reminder> {
+        let muted = self.mute_after.and_then(|after| {
+            self.muted_since
+                .iter()
+                .zip(&self.has_signal)
+                .enumerate()
+                .filter_map(|(input, (since, &signal))| {
+                    let since = now.duration_since((*since)?);
+                    (signal && since >= after).then_some(Reminder::Muted { input, since })
+                })
+                .next()
+        });
+        let solo = self.solo_after.and_then(|after| {
+            let since = now.duration_since(self.solo_since?);
+            (since >= after).then_some(Reminder::Solo(since))
+        });
+        muted.or(solo)
+    }
+
+    /// Restart every running timer, postponing the reminders by their full time
+    pub fn acknowledge(&mut self, now: Instant) {
+        for since in self.muted_since.iter_mut().chain([&mut self.solo_since]) {
+            if since.is_some() {
+                *since = Some(now);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::{ChannelState, MAX_CHANNEL_PORTS};
+
+    fn state() -> MixerState {
+        MixerState::for_test(
+            vec![
+                ChannelState::new("Mic".to_string(), 1),
+                ChannelState::new("Music".to_string(), 2),
+            ],
+            vec![ChannelState::new("Main".to_string(), 2)],
+        )
+    }
+
+    fn config() -> ReminderConfig {
+        ReminderConfig {
+            solo_minutes: Some(10.0),
+            mute_minutes: Some(2.0),
+            signal_threshold_db: -50.0,
+        }
+    }
+
+    #[test]
+    fn test_muted_input_with_signal() {
+        let start = Instant::now();
+        let mut reminders = Reminders::new(Some(&config()), 2);
+        let mut state = state();
+        state.inputs[0].muted = true;
+        reminders.update(&state, start);
+
+        // Silent: no reminder however long it stays muted
+        let later = start + Duration::from_secs(300);
+        reminders.update(&state, later);
+        assert_eq!(reminders.due(later), None);
+
+        state.inputs[0].peak_hold = [0.1; MAX_CHANNEL_PORTS];
+        reminders.update(&state, later);
+        assert_eq!(
+            reminders.due(later),
+            Some(Reminder::Muted {
+                input: 0,
+                since: Duration::from_secs(300)
+            })
+        );
+
+        reminders.acknowledge(later);
+        assert_eq!(reminders.due(later + Duration::from_secs(60)), None);
+
+        state.inputs[0].muted = false;
+        reminders.update(&state, later + Duration::from_secs(500));
+        assert_eq!(reminders.due(later + Duration::from_secs(500)), None);
+    }
+
+    #[test]
+    fn test_solo_left_on() {
+        let start = Instant::now();
+        let mut reminders = Reminders::new(Some(&config()), 2);
+        let mut state = state();
+        state.inputs[1].soloed = true;
+        reminders.update(&state, start);
+        assert_eq!(reminders.due(start + Duration::from_secs(599)), None);
+        assert_eq!(
+            reminders.due(start + Duration::from_secs(600)),
+            Some(Reminder::Solo(Duration::from_secs(600)))
+        );
+
+        // Without a config nothing is ever due
+        let mut off = Reminders::new(None, 2);
+        off.update(&state, start);
+        assert_eq!(off.due(start + Duration::from_secs(6000)), None);
+    }
+}
@@ -0,0 +1,517 @@
+//! Real-time analyzer
+//!
+//! The level of one channel in the 31 third-octave bands from 20 Hz to
+//! 20 kHz, as bars with a falling peak hold. A, C or Z weighting is applied
+//! to each band at its center frequency, so with A weighting the bars follow
+//! what the ear hears and with Z the bars read flat for pink noise. The view
+//! can play the calibration pink noise into an output while it listens, e.g.
+//! to ring out a monitor through a measurement microphone.
+//!
+//! Each band is a sixth-order Butterworth band-pass (three second-order
+//! sections, staggered around the center) running on the UI thread over the
+//! samples the audio thread sends for the analyzer.
+
+use std::f32::consts::TAU;
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::audio::Biquad;
+use crate::i18n::{tr, trf, Msg};
+use crate::ipc::{AnalyzerSource, MixerState};
+
+use super::app::key_hints;
+
+/// Nominal center frequencies of the third-octave bands in Hz
+const BANDS: [f32; 31] = [
+    20.0, 25.0, 31.5, 40.0, 50.0, 63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0,
+    500.0, 630.0, 800.0, 1_000.0, 1_250.0, 1_600.0, 2_000.0, 2_500.0, 3_150.0, 4_000.0, 5_000.0,
+    6_300.0, 8_000.0, 10_000.0, 12_500.0, 16_000.0, 20_000.0,
+];
+
+/// Averaging time of the band levels in seconds (fast, slow)
+const FAST_SECONDS: f32 = 0.125;
+const SLOW_SECONDS: f32 = 1.0;
+
+/// Fall rate of the peak hold in dB per second
+const HOLD_FALL_DB_PER_S: f32 = 10.0;
+
+/// Range of the bottom of the scale in dBFS, and its step per key
+const FLOOR_RANGE_DB: (f32, f32) = (-120.0, -40.0);
+const FLOOR_STEP_DB: f32 = 10.0;
+
+/// Width of the dB labels
+const LABEL_WIDTH: u16 = 4;
+
+/// Partial blocks for the top of a bar, in eighths
+const EIGHTHS: [char; 8] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇'];
+
+/// Frequency weighting of the bands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weighting {
+    A,
+    C,
+    Z,
+}
+
+impl Weighting {
+    /// Next weighting in the order A, C, Z
+    fn next(self) -> Self {
+        match self {
+            Weighting::A => Weighting::C,
+            Weighting::C => Weighting::Z,
+            Weighting::Z => Weighting::A,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Weighting::A => "A",
+            Weighting::C => "C",
+            Weighting::Z => "Z",
+        }
+    }
+
+    /// Gain at `freq` in dB (IEC 61672)
+    fn gain_db(self, freq: f32) -> f32 {
+        let f2 = freq * freq;
+        let (p1, p4) = (20.6f32.powi(2), 12_194.0f32.powi(2));
+        match self {
+            Weighting::A => {
+                let (p2, p3) = (107.7f32.powi(2), 737.9f32.powi(2));
+                let r = p4 * f2 * f2 / ((f2 + p1) * ((f2 + p2) * (f2 + p3)).sqrt() * (f2 + p4));
+                20.0 * r.log10() + 2.0
+            }
+            Weighting::C => {
+                let r = p4 * f2 / ((f2 + p1) * (f2 + p4));
+                20.0 * r.log10() + 0.06
+            }
+            Weighting::Z => 0.0,
+        }
+    }
+}
+
+/// Result of handling a key in the analyzer
+pub enum RtaAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Close the view
+    Close,
+
+    /// Show the spectrogram of the same channel instead
+    Switch,
+
+    /// The noise output changed
+    Noise,
+}
+
+/// Band-pass filter and level of one band
+#[derive(Debug, Clone)]
+struct Band {
+    /// Nominal center frequency in Hz
+    nominal: f32,
+
+    /// Sections of the band-pass
+    filters: [Biquad; 3],
+
+    /// Averaged mean square of the filtered signal
+    mean_square: f32,
+
+    /// Peak hold in dB (unweighted)
+    hold_db: f32,
+}
+
+impl Band {
+    /// Band around the exact center `center` (Hz)
+    fn new(nominal: f32, center: f32, sample_rate: f32) -> Self {
+        // Poles of a third-order Butterworth low-pass moved to the center:
+        // one section at the center, two either side of it
+        let w0 = TAU * center;
+        let bandwidth = w0 * (2f32.powf(1.0 / 6.0) - 2f32.powf(-1.0 / 6.0));
+        let offset = bandwidth / 2.0 * 3f32.sqrt() / 2.0;
+        let mut sections = [
+            (w0, w0 / bandwidth),
+            (w0 - offset, 2.0 * (w0 - offset) / bandwidth),
+            (w0 + offset, 2.0 * (w0 + offset) / bandwidth),
+        ]
+        .map(|(w, q)| bandpass(w / TAU, q, sample_rate));
+
+        // Unity gain at the center
+        let gain: f32 = sections
+            .iter()
+            .map(|c| magnitude(c, center, sample_rate))
+            .product();
+        for b in &mut sections[0][..3] {
+            *b /= gain;
+        }
+        Self {
+            nominal,
+            filters: sections.map(|[b0, b1, b2, a1, a2]| Biquad::new(b0, b1, b2, a1, a2)),
+            mean_square: 0.0,
+            hold_db: f32::MIN,
+        }
+    }
+
+    /// Level in dBFS (unweighted)
+    fn level_db(&self) -> f32 {
+        10.0 * self.mean_square.max(1e-12).log10()
+    }
+}
+
+/// Coefficients (b0, b1, b2, a1, a2) of a band-pass with 0 dB at `freq`
+fn bandpass(freq: f32, q: f32, sample_rate: f32) -> [f32; 5] {
+    let w0 = TAU * freq / sample_rate;
+    let alpha = w0.sin() / (2.0 * q);
+    let a0 = 1.0 + alpha;
+    [
+        alpha / a0,
+        0.0,
+        -alpha / a0,
+        -2.0 * w0.cos() / a0,
+        (1.0 - alpha) / a0,
+    ]
+}
+
+/// Gain of a second-order section at `freq`
+fn magnitude(&[b0, b1, b2, a1, a2]: &[f32; 5], freq: f32, sample_rate: f32) -> f32 {
+    let w = TAU * freq / sample_rate;
+    let (cos1, sin1, cos2, sin2) = (w.cos(), w.sin(), (2.0 * w).cos(), (2.0 * w).sin());
+    let num = (b0 + b1 * cos1 + b2 * cos2).hypot(b1 * sin1 + b2 * sin2);
+    let den = (1.0 + a1 * cos1 + a2 * cos2).hypot(a1 * sin1 + a2 * sin2);
+    num / den
+}
+
+/// Third-octave analyzer of one channel
+pub struct RtaView {
+    /// Channel analysed
+    source: AnalyzerSource,
+
+    sample_rate: f32,
+
+    weighting: Weighting,
+
+    /// Whether the levels use the slow averaging time
+    slow: bool,
+
+    /// Bottom of the scale in dBFS
+    floor_db: f32,
+
+    /// Output playing pink noise, if any
+    noise: Option<usize>,
+
+    /// Outputs the noise can be played into
+    outputs: usize,
+
+    /// Samples received and not yet analysed
+    pending: Vec<f32>,
+
+    /// Bands below the Nyquist frequency
+    bands: Vec<Band>,
+}
+
+impl RtaView {
+    /// Open the analyzer on a channel; `outputs` is the number of outputs
+    /// the noise can be played into
+    pub fn new(source: AnalyzerSource, sample_rate: u32, outputs: usize) -> Self {
+        let sample_rate = sample_rate as f32;
+        let bands = BANDS
+            .iter()
+            .enumerate()
+            .map(|(i, nominal)| (*nominal, 1_000.0 * 10f32.powf((i as f32 - 17.0) / 10.0)))
+            .filter(|(_, center)| *center < 0.42 * sample_rate)
+            .map(|(nominal, center)| Band::new(nominal, center, sample_rate))
+            .collect();
+        Self {
+            source,
+            sample_rate,
+            weighting: Weighting::A,
+            slow: false,
+            floor_db: -90.0,
+            noise: None,
+            outputs,
+            pending: Vec::with_capacity(sample_rate as usize),
+            bands,
+        }
+    }
+
+    /// Channel analysed
+    pub fn source(&self) -> AnalyzerSource {
+        self.source
+    }
+
+    /// Output playing pink noise, if any
+    pub fn noise_output(&self) -> Option<usize> {
+        self.noise
+    }
+
+    /// Buffer for new samples of the channel; call `analyse` after filling it
+    pub fn pending(&mut self) -> &mut Vec<f32> {
+        &mut self.pending
+    }
+
+    /// Run the samples received so far through the bands
+    pub fn analyse(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let seconds = if self.slow {
+            SLOW_SECONDS
+        } else {
+            FAST_SECONDS
+        };
+        let coef = 1.0 - (-1.0 / (seconds * self.sample_rate)).exp();
+        let fall = HOLD_FALL_DB_PER_S * self.pending.len() as f32 / self.sample_rate;
+        for band in &mut self.bands {
+            for s in &self.pending {
+                let y = band.filters.iter_mut().fold(*s, |x, f| f.process(x));
+                band.mean_square += (y * y - band.mean_square) * coef;
+            }
+            band.hold_db = (band.hold_db - fall).max(band.level_db());
+        }
+        self.pending.clear();
+    }
+
+    /// Loudest band after weighting: nominal frequency and level in dB
+    fn loudest(&self) -> Option<(f32, f32)> {
+        self.bands
+            .iter()
+            .map(|b| (b.nominal, b.level_db() + self.weighting.gain_db(b.nominal)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode) -> RtaAction {
+        match code {
+            KeyCode::Esc | KeyCode::Char('A') | KeyCode::Char('q') => return RtaAction::Close,
+            KeyCode::Tab => return RtaAction::Switch,
+            KeyCode::Char('w') => self.weighting = self.weighting.next(),
+            KeyCode::Char('f') => self.slow = !self.slow,
+            KeyCode::Char('n') => {
+                // Off, then each output in turn
+                self.noise = match self.noise {
+                    None if self.outputs > 0 => Some(0),
+                    Some(o) if o + 1 < self.outputs => Some(o + 1),
+                    _ => None,
+                };
+                return RtaAction::Noise;
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.floor_db = (self.floor_db + FLOOR_STEP_DB).min(FLOOR_RANGE_DB.1);
+            }
+            KeyCode::Char('-') => {
+                self.floor_db = (self.floor_db - FLOOR_STEP_DB).max(FLOOR_RANGE_DB.0);
+            }
+            _ => {}
+        }
+        RtaAction::None
+    }
+
+    /// Render the view into `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, state: &MixerState) {
+        let channel = match self.source {
+            AnalyzerSource::Input(i) => state.inputs.get(i),
+            AnalyzerSource::Output(o) => state.outputs.get(o),
+        };
+        let Some(channel) = channel else {
+            return;
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", trf(Msg::RtaTitle, &[&channel.name])));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(2),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+        let columns = |area: Rect| {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(LABEL_WIDTH + 1), Constraint::Min(1)])
+                .split(area)
+        };
+        let rows = columns(chunks[1]);
+        let (labels, plot) = (rows[0], rows[1]);
+
+        // Weighting, averaging, loudest band and noise
+        let speed = tr(if self.slow {
+            Msg::RtaSlow
+        } else {
+            Msg::RtaFast
+        });
+        let mut status = match self.loudest() {
+            Some((freq, db)) => trf(
+                Msg::RtaStatus,
+                &[
+                    &self.weighting.label(),
+                    &speed,
+                    &format_freq(freq),
+                    &format!("{:.1}", db),
+                ],
+            ),
+            None => String::new(),
+        };
+        if let Some(output) = self.noise.and_then(|o| state.outputs.get(o)) {
+            status = format!("{}  ·  {}", status, trf(Msg::RtaNoiseOn, &[&output.name]));
+        }
+        frame.render_widget(
+            Paragraph::new(status).style(Style::default().fg(Color::Gray)),
+            chunks[0],
+        );
+
+        // dB labels every 10 or 20 dB
+        let range = -self.floor_db;
+        let row_of = |db: f32| {
+            let height = ((db - self.floor_db) / range).clamp(0.0, 1.0);
+            ((1.0 - height) * (plot.height - 1) as f32).round() as u16
+        };
+        let step = if plot.height as f32 >= range / 5.0 {
+            10
+        } else {
+            20
+        };
+        for db in (self.floor_db as i32..=0).rev().step_by(step) {
+            let label = Rect {
+                y: labels.y + row_of(db as f32),
+                height: 1,
+                ..labels
+            };
+            frame.render_widget(
+                Paragraph::new(format!("{:>w$}", db, w = LABEL_WIDTH as usize))
+                    .style(Style::default().fg(Color::Gray)),
+                label,
+            );
+        }
+
+        // Bars with a gap where there is room, the loudest highlighted
+        let count = self.bands.len().max(1) as u16;
+        let slot = (plot.width / count).max(1);
+        let bar = if slot >= 3 { slot - 1 } else { slot };
+        let loudest = self.loudest().map(|(freq, _)| freq);
+        let eighths = plot.height as f32 * 8.0;
+        let buf = frame.buffer_mut();
+        for (i, band) in self.bands.iter().enumerate() {
+            let x0 = plot.x + i as u16 * slot;
+            if x0 + bar > plot.right() {
+                break;
+            }
+            let weight = self.weighting.gain_db(band.nominal);
+            let fill = |db: f32| ((db - self.floor_db) / range).clamp(0.0, 1.0) * eighths;
+            let level = fill(band.level_db() + weight).round() as u16;
+            let hold = fill(band.hold_db + weight) as u16 / 8;
+            let color = if loudest == Some(band.nominal) {
+                Color::Yellow
+            } else {
+                Color::Cyan
+            };
+            for y in 0..plot.height {
+                let from_bottom = plot.height - 1 - y;
+                let filled = level.saturating_sub(from_bottom * 8).min(8);
+                let (symbol, fg) = match filled {
+                    8 => ('█', color),
+                    0 if hold == from_bottom && hold > 0 => ('▔', Color::White),
+                    n => (EIGHTHS[n as usize], color),
+                };
+                for x in x0..x0 + bar {
+                    buf[(x, plot.y + y)].set_char(symbol).set_fg(fg);
+                }
+            }
+        }
+
+        // Octave band labels
+        let freq_row = columns(chunks[2])[1];
+        for (i, band) in self.bands.iter().enumerate() {
+            let text = format_freq(band.nominal);
+            let x = freq_row.x + i as u16 * slot;
+            let is_octave = (i + 1) % 3 == 0;
+            if !is_octave || x + text.len() as u16 > freq_row.right() {
+                continue;
+            }
+            let label = Rect {
+                x,
+                width: text.len() as u16,
+                ..freq_row
+            };
+            frame.render_widget(
+                Paragraph::new(text).style(Style::default().fg(Color::Gray)),
+                label,
+            );
+        }
+
+        let keys = Line::from(key_hints(&[
+            ("w", Msg::RtaWeighting),
+            ("f", Msg::RtaSpeed),
+            ("n", Msg::RtaNoise),
+            ("+/-", Msg::SpecFloor),
+            ("Tab", Msg::HelpSpectrogram),
+            ("A/Esc", Msg::EditorClose),
+        ]));
+        frame.render_widget(Paragraph::new(keys), chunks[3]);
+    }
+}
+
+/// Nominal frequency as a label, e.g. `31.5` or `1.25k`
+fn format_freq(freq: f32) -> String {
+    if freq >= 1_000.0 {
+        format!("{}k", freq / 1_000.0)
+    } else {
+        format!("{}", freq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighting_curves() {
+        let close = |a: f32, b: f32| (a - b).abs() < 0.2;
+        assert!(close(Weighting::A.gain_db(1_000.0), 0.0));
+        assert!(close(Weighting::A.gain_db(100.0), -19.1));
+        assert!(close(Weighting::A.gain_db(31.5), -39.4));
+        assert!(close(Weighting::C.gain_db(1_000.0), 0.0));
+        assert!(close(Weighting::C.gain_db(31.5), -3.0));
+        assert!(close(Weighting::C.gain_db(8_000.0), -3.0));
+        assert_eq!(Weighting::Z.gain_db(31.5), 0.0);
+    }
+
+    #[test]
+    fn test_sine_fills_its_band() {
+        let mut view = RtaView::new(AnalyzerSource::Input(0), 48000, 2);
+        assert_eq!(view.bands.len(), 31);
+        let sine = |i: usize| 0.5 * (TAU * 1_000.0 * i as f32 / 48000.0).sin();
+        view.pending().extend((0..48000).map(sine));
+        view.analyse();
+
+        // RMS of the sine in its band, the neighbours well below
+        let level = |nominal: f32| {
+            let band = view.bands.iter().find(|b| b.nominal == nominal).unwrap();
+            band.level_db()
+        };
+        assert!((level(1_000.0) + 9.03).abs() < 0.5);
+        assert!(level(800.0) < level(1_000.0) - 15.0);
+        assert!(level(1_250.0) < level(1_000.0) - 15.0);
+        assert!(level(100.0) < -60.0);
+        assert_eq!(view.loudest().unwrap().0, 1_000.0);
+
+        // Noise runs through the outputs, then off
+        for expected in [Some(0), Some(1), None] {
+            view.handle_key(KeyCode::Char('n'));
+            assert_eq!(view.noise_output(), expected);
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Spectrogram view
+//!
+//! A scrolling picture of one channel: time runs left to right with the
+//! newest column at the right edge, frequency bottom to top on a log scale,
+//! level as color. Steady lines show hum (50/60 Hz and its harmonics) and
+//! whine, a raised band across the top shows hiss, and buzzing bursts from
+//! a phone near a microphone show as short stripes across the whole height.
+//!
+//! The audio thread sends the channel's samples, its ports averaged; the UI
+//! thread runs a Hann-windowed FFT over overlapping blocks of them. Each
+//! terminal cell holds two frequency rows, drawn with a half block.
+
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::i18n::{trf, Msg};
+use crate::ipc::{AnalyzerSource, MixerState};
+
+use super::app::key_hints;
+
+/// Samples per FFT (about 12 Hz resolution at 48 kHz, enough to tell 50
+/// from 60 Hz hum)
+const FFT_SIZE: usize = 4096;
+
+/// Samples between columns (half a block)
+const HOP: usize = FFT_SIZE / 2;
+
+/// Columns kept, more than any terminal is wide
+const HISTORY: usize = 512;
+
+/// Lowest frequency shown in Hz
+const MIN_FREQ_HZ: f32 = 20.0;
+
+/// Highest frequency shown in Hz (less if the sample rate is lower)
+const MAX_FREQ_HZ: f32 = 20_000.0;
+
+/// Range of the bottom of the color scale in dBFS, and its step per key
+const FLOOR_RANGE_DB: (f32, f32) = (-140.0, -40.0);
+const FLOOR_STEP_DB: f32 = 10.0;
+
+/// Frequencies labelled on the left edge in Hz
+const FREQ_LABELS: [f32; 9] = [
+    50.0, 100.0, 200.0, 500.0, 1_000.0, 2_000.0, 5_000.0, 10_000.0, 20_000.0,
+];
+
+/// Width of the frequency labels
+const LABEL_WIDTH: u16 = 5;
+
+/// Color scale from the floor (first) to 0 dBFS (last)
+const PALETTE: [(u8, u8, u8); 6] = [
+    (0, 0, 0),
+    (20, 0, 120),
+    (130, 0, 140),
+    (220, 50, 20),
+    (250, 200, 0),
+    (255, 255, 255),
+];
+
+/// Result of handling a key in the spectrogram view
+pub enum SpectrogramAction {
+    /// Nothing for the app to do
+    None,
+
+    /// Close the view
+    Close,
+
+    /// Show the oscilloscope of the same channel instead
+    Switch,
+}
+
+/// Spectrogram of one channel
+pub struct SpectrogramView {
+    /// Channel shown
+    source: AnalyzerSource,
+
+    sample_rate: f32,
+
+    /// Bottom of the color scale in dBFS
+    floor_db: f32,
+
+    /// Hann window, and its sum for scaling to dBFS
+    window: Vec<f32>,
+    window_sum: f32,
+
+    /// Samples received and not yet analysed
+    pending: Vec<f32>,
+
+    /// FFT scratch (real and imaginary parts)
+    re: Vec<f32>,
+    im: Vec<f32>,
+
+    /// Level in dBFS of each FFT bin, oldest column first
+    columns: VecDeque<Vec<f32>>,
+}
+
+impl SpectrogramView {
+    /// Open the view on a channel
+    pub fn new(source: AnalyzerSource, sample_rate: u32) -> Self {
+        let window: Vec<f32> = (0..FFT_SIZE)
+            .map(|i| 0.5 - 0.5 * (TAU * i as f32 / FFT_SIZE as f32).cos())
+            .collect();
+        Self {
+            source,
+            sample_rate: sample_rate as f32,
+            floor_db: -100.0,
+            window_sum: window.iter().sum(),
+            window,
+            pending: Vec::with_capacity(sample_rate as usize),
+            re: vec![0.0; FFT_SIZE],
+            im: vec![0.0; FFT_SIZE],
+            columns: VecDeque::with_capacity(HISTORY),
+        }
+    }
+
+    /// Channel shown
+    pub fn source(&self) -> AnalyzerSource {
+        self.source
+    }
+
+    /// Buffer for new samples of the channel; call `analyse` after filling it
+    pub fn pending(&mut self) -> &mut Vec<f32> {
+        &mut self.pending
+    }
+
+    /// Turn the samples received so far into columns
+    pub fn analyse(&mut self) {
+        let mut used = 0;
+        while self.pending.len() - used >= FFT_SIZE {
+            let block = &self.pending[used..used + FFT_SIZE];
+            for (i, (s, w)) in block.iter().zip(&self.window).enumerate() {
+                self.re[i] = s * w;
+                self.im[i] = 0.0;
+            }
+            fft(&mut self.re, &mut self.im);
+
+            let scale = 2.0 / self.window_sum;
+            let column = (0..=FFT_SIZE / 2)
+                .map(|k| {
+                    let magnitude = self.re[k].hypot(self.im[k]) * scale;
+                    20.0 * magnitude.max(1e-10).log10()
+                })
+                .collect();
+            if self.columns.len() == HISTORY {
+                self.columns.pop_front();
+            }
+            self.columns.push_back(column);
+            used += HOP;
+        }
+        self.pending.drain(..used);
+    }
+
+    /// Handle a key press
+    pub fn handle_key(&mut self, code: KeyCode) -> SpectrogramAction {
+        match code {
+            KeyCode::Esc | KeyCode::Char('S') | KeyCode::Char('q') => {
+                return SpectrogramAction::Close
+            }
+            KeyCode::Tab => return SpectrogramAction::Switch,
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.floor_db = (self.floor_db + FLOOR_STEP_DB).min(FLOOR_RANGE_DB.1);
+            }
+            KeyCode::Char('-') => {
+                self.floor_db = (self.floor_db - FLOOR_STEP_DB).max(FLOOR_RANGE_DB.0);
+            }
+            _ => {}
+        }
+        SpectrogramAction::None
+    }
+
+    /// Highest frequency shown in Hz
+    fn max_freq(&self) -> f32 {
+        MAX_FREQ_HZ.min(self.sample_rate / 2.0)
+    }
+
+    /// Frequency at a height of 0 (bottom) to 1 (top) of the plot
+    fn freq_at(&self, height: f32) -> f32 {
+        MIN_FREQ_HZ * (self.max_freq() / MIN_FREQ_HZ).powf(height)
+    }
+
+    /// Color of a level
+    fn color(&self, db: f32) -> Color {
+        let t = ((db - self.floor_db) / -self.floor_db).clamp(0.0, 1.0);
+        let at = t * (PALETTE.len() - 1) as f32;
+        let i = (at as usize).min(PALETTE.len() - 2);
+        let f = at - i as f32;
+        let (a, b) = (PALETTE[i], PALETTE[i + 1]);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+        Color::Rgb(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+    }
+
+    /// Render the view into `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, state: &MixerState) {
+        let channel = match self.source {
+            AnalyzerSource::Input(i) => state.inputs.get(i),
+            AnalyzerSource::Output(o) => state.outputs.get(o),
+        };
+        let Some(channel) = channel else {
+            return;
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!(" {} ", trf(Msg::SpecTitle, &[&channel.name])));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(2), Constraint::Length(1)])
+            .split(inner);
+        let rows = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(LABEL_WIDTH + 1), Constraint::Min(1)])
+            .split(chunks[0]);
+        let (labels, plot) = (rows[0], rows[1]);
+
+        // Frequency labels, skipping any that would share a row
+        let half_rows = plot.height as f32 * 2.0;
+        let span = (self.max_freq() / MIN_FREQ_HZ).ln();
+        let mut used_rows = Vec::new();
+        for freq in FREQ_LABELS.into_iter().filter(|f| *f <= self.max_freq()) {
+            let height = (freq / MIN_FREQ_HZ).ln() / span;
+            let row = ((1.0 - height) * half_rows / 2.0).min(plot.height as f32 - 1.0) as u16;
+            if used_rows.contains(&row) {
+                continue;
+            }
+            used_rows.push(row);
+            let text = if freq >= 1_000.0 {
+                format!("{}k", freq / 1_000.0)
+            } else {
+                format!("{}", freq)
+            };
+            let label = Rect {
+                y: labels.y + row,
+                height: 1,
+                ..labels
+            };
+            frame.render_widget(
+                Paragraph::new(format!("{:>w$}", text, w = LABEL_WIDTH as usize))
+                    .style(Style::default().fg(Color::Gray)),
+                label,
+            );
+        }
+
+        // FFT bins of each half row, top first
+        let bin_hz = self.sample_rate / FFT_SIZE as f32;
+        let bins: Vec<(usize, usize)> = (0..plot.height as usize * 2)
+            .map(|h| {
+                let top = self.freq_at(1.0 - h as f32 / half_rows);
+                let bottom = self.freq_at(1.0 - (h + 1) as f32 / half_rows);
+                let from = ((bottom / bin_hz).round() as usize).min(FFT_SIZE / 2);
+                let to = ((top / bin_hz).round() as usize).clamp(from, FFT_SIZE / 2);
+                (from, to)
+            })
+            .collect();
+
+        let buf = frame.buffer_mut();
+        let shown = self.columns.len().min(plot.width as usize);
+        let blank = plot.width as usize - shown;
+        let columns = self.columns.range(self.columns.len() - shown..);
+        for (x, column) in (plot.x + blank as u16..).zip(columns) {
+            let level = |(from, to): (usize, usize)| {
+                column[from..=to].iter().fold(f32::MIN, |m, db| m.max(*db))
+            };
+            for y in 0..plot.height {
+                let upper = level(bins[y as usize * 2]);
+                let lower = level(bins[y as usize * 2 + 1]);
+                buf[(x, plot.y + y)]
+                    .set_char('▀')
+                    .set_fg(self.color(upper))
+                    .set_bg(self.color(lower));
+            }
+        }
+
+        let keys = Line::from(key_hints(&[
+            ("+/-", Msg::SpecFloor),
+            ("Tab", Msg::HelpOscilloscope),
+            ("S/Esc", Msg::EditorClose),
+        ]));
+        let floor = trf(Msg::SpecFloorValue, &[&self.floor_db]);
+        let hint = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(1), Constraint::Length(floor.len() as u16)])
+            .split(chunks[1]);
+        frame.render_widget(Paragraph::new(keys), hint[0]);
+        frame.render_widget(
+            Paragraph::new(floor).style(Style::default().fg(Color::Gray)),
+            hint[1],
+        );
+    }
+}
+
+/// In-place radix-2 FFT; the length must be a power of two
+pub(super) fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    // Bit-reversed order
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -TAU / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sine_shows_at_its_frequency() {
+        let mut view = SpectrogramView::new(AnalyzerSource::Input(0), 48000);
+        let sine = |i: usize| 0.5 * (TAU * 1000.0 * i as f32 / 48000.0).sin();
+        view.pending().extend((0..FFT_SIZE + HOP).map(sine));
+        view.analyse();
+
+        // Two overlapping blocks, the rest kept for the next
+        assert_eq!(view.columns.len(), 2);
+        assert_eq!(view.pending.len(), HOP);
+
+        // 1 kHz lands in bin 85 of 4096 at 48 kHz, near -6 dBFS
+        let column = &view.columns[1];
+        let peak_db = column.iter().fold(f32::MIN, |m, db| m.max(*db));
+        assert_eq!(column[85], peak_db);
+        assert!(peak_db > -8.0 && peak_db < -5.5);
+        assert!(column[20] < -60.0 && column[400] < -60.0);
+    }
+}
@@ -0,0 +1,99 @@
+//! Suspend and resume of the terminal UI
+//!
+//! In raw mode Ctrl-Z reaches the app as a key press instead of stopping the
+//! process, while a SIGTSTP sent from outside would stop it with the terminal
+//! still raw and on the alternate screen. Both are routed through the app's
+//! suspend, which restores the terminal before handing it to the shell and
+//! sets the UI up again afterwards. SIGCONT is watched as well, so the screen
+//! is rebuilt after an outside SIGSTOP/SIGCONT pair.
+
+use std::io::{self, Stdout};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use signal_hook::consts::{SIGCONT, SIGTSTP};
+
+/// Flags set by the suspend-related signal handlers
+pub struct SuspendSignals {
+    /// SIGTSTP received and not yet handled
+    stop: Arc<AtomicBool>,
+
+    /// SIGCONT received and not yet handled
+    resumed: Arc<AtomicBool>,
+}
+
+impl SuspendSignals {
+    /// Catch SIGTSTP and SIGCONT
+    pub fn register() -> Result<Self> {
+        let signals = Self {
+            stop: Arc::new(AtomicBool::new(false)),
+            resumed: Arc::new(AtomicBool::new(false)),
+        };
+        signal_hook::flag::register(SIGTSTP, signals.stop.clone())
+            .context("Failed to handle SIGTSTP")?;
+        signal_hook::flag::register(SIGCONT, signals.resumed.clone())
+            .context("Failed to handle SIGCONT")?;
+        Ok(signals)
+    }
+
+    /// Whether a suspend was requested by signal since the last call
+    pub fn take_stop(&self) -> bool {
+        self.stop.swap(false, Ordering::Relaxed)
+    }
+
+    /// Whether the process was continued since the last call
+    pub fn take_resumed(&self) -> bool {
+        self.resumed.swap(false, Ordering::Relaxed)
+    }
+}
+
+/// Put the terminal into the state the UI needs
+pub fn enter_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )
+}
+
+/// Give the terminal back in the state the shell left it
+pub fn leave_ui(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()
+}
+
+/// Stop the process as SIGTSTP would by default, returning once continued
+pub fn stop_process() -> io::Result<()> {
+    signal_hook::low_level::emulate_default_handler(SIGTSTP)
+}
+
+/// Run the user's shell in the foreground, calling `tick` every `interval`
+/// until it exits
+pub fn run_shell(interval: Duration, mut tick: impl FnMut()) -> Result<()> {
+    let shell = std::env::var_os("SHELL").unwrap_or_else(|| "/bin/sh".into());
+    let mut child = Command::new(&shell)
+        .spawn()
+        .with_context(|| format!("Failed to start {}", shell.to_string_lossy()))?;
+
+    while child.try_wait()?.is_none() {
+        tick();
+        thread::sleep(interval);
+    }
+    Ok(())
+}
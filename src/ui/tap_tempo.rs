@@ -0,0 +1,68 @@
+//! Tap tempo for the metronome click
+//!
+//! The tempo is the average gap between the last few taps, so one early or
+//! late tap only nudges it. A pause longer than the slowest tempo starts a
+//! new count.
+
+use std::time::{Duration, Instant};
+
+use crate::config::CLICK_TEMPO_RANGE;
+
+/// Taps the tempo is averaged over
+const TAPS: usize = 5;
+
+/// Gap after which a tap starts a new count (the slowest tempo)
+const MAX_GAP: Duration = Duration::from_secs(3);
+
+/// Recent taps
+#[derive(Debug, Default)]
+pub struct TapTempo {
+    taps: Vec<Instant>,
+}
+
+impl TapTempo {
+    /// Note a tap; the tempo in beats per minute once there are two taps
+    pub fn tap(&mut self, now: Instant) -> Option<f32> {
+        if self
+            .taps
+            .last()
+            .is_some_and(|last| now.duration_since(*last) > MAX_GAP)
+        {
+            self.taps.clear();
+        }
+        if self.taps.len() == TAPS {
+            self.taps.remove(0);
+        }
+        self.taps.push(now);
+
+        let (first, last) = (self.taps.first()?, self.taps.last()?);
+        let gaps = self.taps.len() - 1;
+        if gaps == 0 {
+            return None;
+        }
+        let beat = last.duration_since(*first).as_secs_f32() / gaps as f32;
+        Some((60.0 / beat.max(f32::EPSILON)).clamp(CLICK_TEMPO_RANGE.0, CLICK_TEMPO_RANGE.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tempo_from_taps() {
+        let mut tap = TapTempo::default();
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        assert_eq!(tap.tap(at(0)), None);
+        assert_eq!(tap.tap(at(500)), Some(120.0));
+        // A late tap only nudges the average
+        let tempo = tap.tap(at(1100)).unwrap();
+        assert!((tempo - 109.09).abs() < 0.01);
+
+        // A long pause starts over
+        assert_eq!(tap.tap(at(5000)), None);
+        assert_eq!(tap.tap(at(5400)), Some(150.0));
+    }
+}
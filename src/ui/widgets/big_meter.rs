@@ -0,0 +1,421 @@
+//! Big meter widget
+//!
+//! One channel across the whole terminal, for checking a source closely
+//! from across the room: a tall meter per port, the peak hold, RMS and
+//! fader in large digits, and a bar for each gain reduction stage of the
+//! channel. Terminals too short for the large digits get them as text.
+//! Inputs with a device meter get a second set of meters with the peaks of
+//! their capture device, so a clip can be placed in the device or the mixer.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+
+use super::Meter;
+use crate::config::{DbDisplay, MeterScale};
+use crate::ipc::{ChannelState, MeterData, VOLUME_MIN_DB};
+
+/// Height of the large digits
+const DIGIT_ROWS: u16 = 5;
+
+/// Width of the dB scale next to the meters
+const SCALE_WIDTH: u16 = 5;
+
+/// Reduction at the right end of a gain reduction bar in dB
+const REDUCTION_RANGE_DB: f32 = 20.0;
+
+/// Width of the readout column: large digits and the reduction bars
+const READOUT_WIDTH: u16 = 38;
+
+/// Width of the gain reduction labels
+const REDUCTION_LABEL_WIDTH: usize = 10;
+
+/// Rows of a large character, drawn with full blocks
+fn glyph(c: char) -> [&'static str; 5] {
+    match c {
+        '0' => ["███", "█ █", "█ █", "█ █", "███"],
+        '1' => [" █ ", "██ ", " █ ", " █ ", "███"],
+        '2' => ["███", "  █", "███", "█  ", "███"],
+        '3' => ["███", "  █", "███", "  █", "███"],
+        '4' => ["█ █", "█ █", "███", "  █", "  █"],
+        '5' => ["███", "█  ", "███", "  █", "███"],
+        '6' => ["███", "█  ", "███", "█ █", "███"],
+        '7' => ["███", "  █", "  █", "  █", "  █"],
+        '8' => ["███", "█ █", "███", "█ █", "███"],
+        '9' => ["███", "█ █", "███", "  █", "███"],
+        '-' => ["   ", "   ", "███", "   ", "   "],
+        '+' => ["   ", " █ ", "███", " █ ", "   "],
+        '.' => [" ", " ", " ", " ", "█"],
+        'i' => ["█", " ", "█", "█", "█"],
+        'n' => ["   ", "   ", "██ ", "█ █", "█ █"],
+        'f' => [" ██", " █ ", "███", " █ ", " █ "],
+        _ => ["   ", "   ", "   ", "   ", "   "],
+    }
+}
+
+/// Width of `text` in large characters, with a column between them
+fn large_width(text: &str) -> u16 {
+    text.chars()
+        .map(|c| glyph(c)[0].chars().count() as u16 + 1)
+        .sum::<u16>()
+        .saturating_sub(1)
+}
+
+/// Draw `text` in large characters from the top left of `area`
+fn draw_large(text: &str, area: Rect, style: Style, buf: &mut Buffer) {
+    let mut x = area.x;
+    for c in text.chars() {
+        let rows = glyph(c);
+        let width = rows[0].chars().count() as u16;
+        if x + width > area.right() {
+            break;
+        }
+        for (dy, row) in rows.iter().enumerate().take(area.height as usize) {
+            for (dx, cell) in row.chars().enumerate() {
+                if cell != ' ' {
+                    buf[(x + dx as u16, area.y + dy as u16)]
+                        .set_char(cell)
+                        .set_style(style);
+                }
+            }
+        }
+        x += width + 1;
+    }
+}
+
+/// A single channel filling the terminal
+pub struct BigMeter<'a> {
+    /// Channel state
+    state: &'a ChannelState,
+
+    /// Section and name shown in the header
+    title: String,
+
+    /// How the fader value is written
+    display: DbDisplay,
+
+    /// Range and zones of the meters
+    scale: MeterScale,
+
+    /// Gain reduction stages of the channel: label and reduction in dB
+    reductions: Vec<(&'static str, f32)>,
+
+    /// Whether the channel has a solo switch
+    soloable: bool,
+}
+
+impl<'a> BigMeter<'a> {
+    /// Create a big meter for a channel
+    pub fn new(state: &'a ChannelState, title: String) -> Self {
+        Self {
+            state,
+            title,
+            display: DbDisplay::default(),
+            scale: MeterScale::Digital,
+            reductions: Vec::new(),
+            soloable: false,
+        }
+    }
+
+    /// Write the fader value with these display settings
+    pub fn display(mut self, display: DbDisplay) -> Self {
+        self.display = display;
+        self
+    }
+
+    /// Use the range and zones of a meter scale
+    pub fn scale(mut self, scale: MeterScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Show a bar for each gain reduction stage
+    pub fn reductions(mut self, reductions: Vec<(&'static str, f32)>) -> Self {
+        self.reductions = reductions;
+        self
+    }
+
+    /// Show the solo state
+    pub fn soloable(mut self) -> Self {
+        self.soloable = true;
+        self
+    }
+
+    /// Color of a level by the zones of the meter scale
+    fn level_style(&self, db: f32) -> Style {
+        let (yellow, red) = self.scale.zones_db();
+        let color = if db >= red {
+            Color::Red
+        } else if db >= yellow {
+            Color::Yellow
+        } else {
+            Color::Green
+        };
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
+
+    /// Level readout: dB with one decimal, dashes for silence
+    fn level_text(db: f32) -> String {
+        if db > VOLUME_MIN_DB {
+            format!("{:+.1}", db)
+        } else {
+            "---".to_string()
+        }
+    }
+
+    /// Header: name, fader and mute/solo state
+    fn header(&self) -> Line<'static> {
+        let flag = |on: bool, label: &'static str, color: Color| {
+            if on {
+                Span::styled(label, Style::default().fg(Color::Black).bg(color))
+            } else {
+                Span::styled(label, Style::default().fg(Color::DarkGray))
+            }
+        };
+        let mut spans = vec![
+            Span::styled(
+                self.title.clone(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  "),
+            flag(self.state.muted, " M ", Color::Red),
+        ];
+        if self.soloable {
+            spans.push(Span::raw(" "));
+            spans.push(flag(self.state.soloed, " S ", Color::Yellow));
+        }
+        if self.state.clip_count > 0 {
+            spans.push(Span::styled(
+                format!("  CLIP {}", self.state.clip_count),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+        }
+        if self.state.device_clip_count > 0 {
+            spans.push(Span::styled(
+                format!("  DEVICE CLIP {}", self.state.device_clip_count),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    /// Meters of every port with a dB scale on the left; with a device
+    /// meter, the device's peaks on the left of the mixer's
+    fn render_meters(&self, area: Rect, buf: &mut Buffer) {
+        let device = self.state.device_peaks.filter(|_| area.height > 4);
+        let (labels, area) = match device {
+            Some(_) => (
+                Some(Rect { height: 1, ..area }),
+                Rect {
+                    y: area.y + 1,
+                    height: area.height - 1,
+                    ..area
+                },
+            ),
+            None => (None, area),
+        };
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(SCALE_WIDTH), Constraint::Min(1)])
+            .split(area);
+        let (scale, meters) = (columns[0], columns[1]);
+
+        let (min_db, max_db) = self.scale.range_db();
+        let step = if area.height >= 24 { 6.0 } else { 12.0 };
+        let mut db = max_db - max_db.rem_euclid(step);
+        while db >= min_db {
+            let position = (db - min_db) / (max_db - min_db);
+            let row = ((1.0 - position) * (area.height - 1) as f32).round() as u16;
+            let label = Rect {
+                y: area.y + row,
+                height: 1,
+                ..scale
+            };
+            Paragraph::new(format!("{:>4}", db))
+                .style(Style::default().fg(Color::Gray))
+                .render(label, buf);
+            db -= step;
+        }
+
+        let (Some(device), Some(labels)) = (device, labels) else {
+            self.render_ports(meters, buf, |p| {
+                Meter::new(self.state.current_peaks[p])
+                    .rms(self.state.current_rms[p])
+                    .peak_hold(self.state.peak_hold[p])
+            });
+            return;
+        };
+        let groups = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+            .split(meters);
+        let label_style = Style::default().fg(Color::Gray);
+        for (group, label) in groups.iter().zip(["DEVICE", "MIXER"]) {
+            let row = Rect {
+                x: group.x,
+                width: group.width,
+                ..labels
+            };
+            Paragraph::new(label).style(label_style).render(row, buf);
+        }
+        // The device side has peaks only: its bar is the peak
+        self.render_ports(groups[0], buf, |p| {
+            Meter::new(device[p]).peak_hold(self.state.device_peak_hold[p])
+        });
+        self.render_ports(groups[1], buf, |p| {
+            Meter::new(self.state.current_peaks[p])
+                .rms(self.state.current_rms[p])
+                .peak_hold(self.state.peak_hold[p])
+        });
+    }
+
+    /// One meter per port side by side across `area`
+    fn render_ports(&self, area: Rect, buf: &mut Buffer, meter: impl Fn(usize) -> Meter) {
+        let ports = self.state.port_count.max(1) as u16;
+        let width = area.width / ports;
+        for port in 0..ports {
+            let rect = Rect {
+                x: area.x + port * width,
+                width: width.saturating_sub(1).max(1),
+                ..area
+            };
+            meter(port as usize).scale(self.scale).render(rect, buf);
+        }
+    }
+
+    /// Peak hold, RMS and fader readouts, then the gain reduction bars
+    fn render_readouts(&self, area: Rect, buf: &mut Buffer) {
+        let ports = self.state.port_count.clamp(1, self.state.peak_hold.len());
+        let loudest = |levels: &[f32]| levels[..ports].iter().fold(0.0f32, |m, l| m.max(*l));
+        let peak_db = MeterData::linear_to_db(loudest(&self.state.peak_hold));
+        let rms_db = MeterData::linear_to_db(loudest(&self.state.current_rms));
+        let fader_style = if self.state.muted {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        };
+        let mut readouts = vec![
+            ("PEAK", Self::level_text(peak_db), self.level_style(peak_db)),
+            ("RMS", Self::level_text(rms_db), self.level_style(rms_db)),
+            (
+                "FADER",
+                self.display
+                    .with_suffix(false)
+                    .format_signed(self.state.volume_db),
+                fader_style,
+            ),
+        ];
+        if self.state.device_peaks.is_some() {
+            let device_db = MeterData::linear_to_db(loudest(&self.state.device_peak_hold));
+            readouts.insert(
+                1,
+                (
+                    "DEVICE",
+                    Self::level_text(device_db),
+                    self.level_style(device_db),
+                ),
+            );
+        }
+
+        // Large digits where the readouts and bars fit below each other
+        let reduction_rows = self.reductions.len() as u16;
+        let large = area.height >= readouts.len() as u16 * (DIGIT_ROWS + 2) + reduction_rows
+            && readouts
+                .iter()
+                .all(|(_, text, _)| large_width(text) <= area.width);
+        let readout_rows = if large { DIGIT_ROWS + 2 } else { 1 };
+        let label_style = Style::default().fg(Color::Gray);
+        let mut y = area.y;
+        for (label, text, style) in readouts {
+            if y >= area.bottom() {
+                return;
+            }
+            let row = Rect {
+                y,
+                height: 1,
+                ..area
+            };
+            if large {
+                Paragraph::new(label).style(label_style).render(row, buf);
+                let digits = Rect {
+                    y: y + 1,
+                    height: DIGIT_ROWS,
+                    ..area
+                };
+                draw_large(&text, digits, style, buf);
+            } else {
+                let line = Line::from(vec![
+                    Span::styled(format!("{:<7}", label), label_style),
+                    Span::styled(text, style),
+                ]);
+                Paragraph::new(line).render(row, buf);
+            }
+            y += readout_rows;
+        }
+
+        // One bar per reduction stage, filling to the right
+        for (label, reduction_db) in &self.reductions {
+            if y >= area.bottom() {
+                return;
+            }
+            let value = format!(" -{:4.1} dB", reduction_db.max(0.0));
+            let bar_width =
+                (area.width as usize).saturating_sub(REDUCTION_LABEL_WIDTH + value.len());
+            let share = (reduction_db / REDUCTION_RANGE_DB).clamp(0.0, 1.0);
+            let filled = (share * bar_width as f32).round() as usize;
+            let line = Line::from(vec![
+                Span::styled(
+                    format!("{:<w$}", label, w = REDUCTION_LABEL_WIDTH),
+                    label_style,
+                ),
+                Span::styled("█".repeat(filled), Style::default().fg(Color::Magenta)),
+                Span::styled(
+                    "░".repeat(bar_width - filled),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(value, Style::default().fg(Color::White)),
+            ]);
+            let row = Rect {
+                y,
+                height: 1,
+                ..area
+            };
+            Paragraph::new(line).render(row, buf);
+            y += 1;
+        }
+    }
+}
+
+impl Widget for BigMeter<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 3 || area.width < 20 {
+            return;
+        }
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(1)])
+            .split(area);
+        Paragraph::new(self.header()).render(rows[0], buf);
+
+        // The meters take what the readouts leave
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(10), Constraint::Length(READOUT_WIDTH)])
+            .split(rows[1]);
+        self.render_meters(columns[0], buf);
+        let readouts = Rect {
+            x: columns[1].x + 2,
+            width: columns[1].width.saturating_sub(2),
+            ..columns[1]
+        };
+        self.render_readouts(readouts, buf);
+    }
+}
@@ -1,7 +1,9 @@
 //! Channel strip widget
 //!
-//! Renders a complete channel strip with name, meters, fader value,
-//! and mute/solo indicators.
+//! Renders a complete channel strip with name, meters, peak readout, crest
+//! factor, fader value, and mute/solo indicators, with a signal light and a
+//! correlation mark for stereo channels. Wide strips also get a sparkline
+//! of the level over the last minute.
 
 use ratatui::{
     buffer::Buffer,
@@ -12,7 +14,17 @@ use ratatui::{
 };
 
 use super::Meter;
-use crate::ipc::ChannelState;
+use crate::config::{DbDisplay, MeterScale, TapPoint};
+use crate::ipc::{ChannelState, MeterData, RouteMode, SignalStatus, VOLUME_MIN_DB};
+
+/// Automixer gain (dB) above which an input counts as holding the floor
+const AUTOMIX_ACTIVE_DB: f32 = -6.0;
+
+/// Inner width from which a strip shows the level history sparkline
+const HISTORY_MIN_WIDTH: u16 = 8;
+
+/// Sparkline bars, lowest first
+const SPARK_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
 
 /// A channel strip widget showing meters, fader, and controls
 pub struct ChannelStrip<'a> {
@@ -24,6 +36,30 @@ pub struct ChannelStrip<'a> {
 
     /// Whether this is an input (true) or output (false) channel
     is_input: bool,
+
+    /// Assignment to each output (inputs only)
+    routes: Vec<RouteMode>,
+
+    /// How the fader value is written
+    display: DbDisplay,
+
+    /// Range and zones of the meters
+    scale: MeterScale,
+
+    /// Alternate source connected in place of the primary one (inputs only)
+    source: Option<&'a str>,
+
+    /// Flip layer value shown in place of the fader value
+    flipped: Option<String>,
+
+    /// Whether the channel has a solo switch (inputs and outputs)
+    soloable: bool,
+
+    /// Whether a soloed output this input feeds auditions it
+    auditioned: bool,
+
+    /// Signal light state (None hides the light)
+    signal: Option<SignalStatus>,
 }
 
 impl<'a> ChannelStrip<'a> {
@@ -33,6 +69,14 @@ impl<'a> ChannelStrip<'a> {
             state,
             selected: false,
             is_input,
+            routes: Vec::new(),
+            display: DbDisplay::default(),
+            scale: MeterScale::default(),
+            source: None,
+            flipped: None,
+            soloable: is_input,
+            auditioned: false,
+            signal: None,
         }
     }
 
@@ -41,6 +85,126 @@ impl<'a> ChannelStrip<'a> {
         self.selected = selected;
         self
     }
+
+    /// Show the assignment to each output as a row of LEDs
+    pub fn routes(mut self, routes: Vec<RouteMode>) -> Self {
+        self.routes = routes;
+        self
+    }
+
+    /// Write the fader value with these display options
+    pub fn display(mut self, display: DbDisplay) -> Self {
+        self.display = display;
+        self
+    }
+
+    /// Draw the meters on this scale
+    pub fn scale(mut self, scale: MeterScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Show a flip layer value in place of the fader value
+    pub fn flipped(mut self, value: String) -> Self {
+        self.flipped = Some(value);
+        self
+    }
+
+    /// Show the solo switch (inputs have it already)
+    pub fn soloable(mut self) -> Self {
+        self.soloable = true;
+        self
+    }
+
+    /// Mark the input as heard through a soloed output
+    pub fn auditioned(mut self) -> Self {
+        self.auditioned = true;
+        self
+    }
+
+    /// Show the signal light
+    pub fn signal(mut self, status: SignalStatus) -> Self {
+        self.signal = Some(status);
+        self
+    }
+
+    /// Name the alternate source the input is connected to
+    pub fn source(mut self, source: &'a str) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Current and held peak, e.g. `-8.3 / -2.1`, shortened to fit `width`
+    /// (only the held peak on the narrowest strips)
+    fn peak_readout(&self, width: u16) -> String {
+        let display = self.display.with_suffix(false);
+        let level =
+            |linear: f32| display.format(MeterData::linear_to_db(linear).max(VOLUME_MIN_DB));
+        let current = level(self.state.peak());
+        let held = level(self.state.held_peak());
+        [
+            format!("{} / {}", current, held),
+            format!("{}/{}", current, held),
+        ]
+        .into_iter()
+        .find(|text| text.chars().count() <= width as usize)
+        .unwrap_or(held)
+    }
+
+    /// Correlation of a stereo channel as `-0+` with the mark nearest the
+    /// correlation lit: red below zero, yellow at it, green above; all dim
+    /// for silence
+    fn correlation_spans(&self) -> Vec<Span<'static>> {
+        let lit = self.state.correlation.map(|c| (c.round() as i8, c));
+        [(-1, "-"), (0, "0"), (1, "+")]
+            .into_iter()
+            .map(|(mark, label)| match lit {
+                Some((nearest, c)) if nearest == mark => {
+                    let color = if c < 0.0 {
+                        Color::Red
+                    } else if mark == 0 {
+                        Color::Yellow
+                    } else {
+                        Color::Green
+                    };
+                    Span::styled(label, Style::default().fg(Color::Black).bg(color))
+                }
+                _ => Span::styled(label, Style::default().fg(Color::DarkGray)),
+            })
+            .collect()
+    }
+
+    /// Sparkline of the RMS over the last minute, oldest on the left: one
+    /// column per few seconds (their loudest), a dot for seconds below the
+    /// meter floor, blank without meter data, red where the peak clipped
+    fn history_line(&self, width: u16) -> Line<'static> {
+        let seconds: Vec<Option<(f32, f32)>> = self.state.history.seconds().collect();
+        let (min_db, max_db) = self.scale.range_db();
+        let width = (width as usize).min(seconds.len()).max(1);
+        let spans: Vec<Span> = (0..width)
+            .map(|column| {
+                let from = column * seconds.len() / width;
+                let to = (column + 1) * seconds.len() / width;
+                let loudest = seconds[from..to]
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .reduce(|(p1, r1), (p2, r2)| (p1.max(p2), r1.max(r2)));
+                let Some((peak, rms)) = loudest else {
+                    return Span::raw(" ");
+                };
+                let db = MeterData::linear_to_db(rms);
+                let color = if peak >= 1.0 { Color::Red } else { Color::Cyan };
+                if db <= min_db {
+                    return Span::styled("·", Style::default().fg(Color::DarkGray));
+                }
+                let ratio = ((db - min_db) / (max_db - min_db)).clamp(0.0, 1.0);
+                let bar = SPARK_BARS[(ratio * (SPARK_BARS.len() - 1) as f32).round() as usize];
+                Span::styled(bar.to_string(), Style::default().fg(color))
+            })
+            .collect();
+        Line::from(spans)
+    }
 }
 
 impl Widget for ChannelStrip<'_> {
@@ -52,25 +216,55 @@ impl Widget for ChannelStrip<'_> {
             Style::default().fg(Color::White)
         };
 
-        let block = Block::default()
+        // Mid/side meters are marked so they are not read as left/right,
+        // post-fader meters so a pulled fader is not read as a quiet source,
+        // feedback notches so they are not forgotten
+        let mut title = format!(" {} ", self.state.name);
+        if self.state.mid_side && self.state.port_count == 2 {
+            title.push_str("M/S ");
+        }
+        if self.is_input && self.state.meter_point == TapPoint::PostFader {
+            title.push_str("POST ");
+        }
+        if self.state.notches.iter().any(Option::is_some) {
+            title.push_str("NOTCH ");
+        }
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_style(border_style)
-            .title(format!(" {} ", self.state.name));
+            .title(title);
+        if let Some(source) = self.source {
+            block = block.title_bottom(
+                Line::styled(format!(" {} ", source), Style::default().fg(Color::Yellow))
+                    .centered(),
+            );
+        }
 
         let inner = block.inner(area);
         block.render(area, buf);
 
-        if inner.height < 4 || inner.width < 3 {
+        let route_rows = u16::from(!self.routes.is_empty());
+        let eq_rows = u16::from(self.is_input);
+        if inner.height < 6 + route_rows + eq_rows || inner.width < 3 {
             return;
         }
+        // The history only where it leaves the meters room to read
+        let history_rows = u16::from(
+            inner.width >= HISTORY_MIN_WIDTH && inner.height >= 10 + route_rows + eq_rows,
+        );
 
         // Layout: meters at top, controls at bottom
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Min(3),    // Meters
-                Constraint::Length(1), // Volume
-                Constraint::Length(1), // Mute/Solo
+                Constraint::Min(3),               // Meters
+                Constraint::Length(history_rows), // Level history
+                Constraint::Length(1),            // Peak readout
+                Constraint::Length(1),            // Crest factor
+                Constraint::Length(eq_rows),      // EQ
+                Constraint::Length(1),            // Volume
+                Constraint::Length(route_rows),   // Output assignment LEDs
+                Constraint::Length(1),            // Mute/Solo
             ])
             .split(inner);
 
@@ -87,9 +281,12 @@ impl Widget for ChannelStrip<'_> {
                 height: meter_area.height,
             };
             Meter::new(self.state.current_peaks[0])
+                .rms(self.state.current_rms[0])
                 .peak_hold(self.state.peak_hold[0])
+                .range(self.state.range.range(0))
+                .scale(self.scale)
                 .render(meter_rect, buf);
-        } else {
+        } else if self.state.port_count == 2 {
             // Stereo: two meters side by side
             let meter_width = 2.min(meter_area.width / 2);
             let gap = 1.min(meter_area.width.saturating_sub(meter_width * 2));
@@ -104,7 +301,10 @@ impl Widget for ChannelStrip<'_> {
                 height: meter_area.height,
             };
             Meter::new(self.state.current_peaks[0])
+                .rms(self.state.current_rms[0])
                 .peak_hold(self.state.peak_hold[0])
+                .range(self.state.range.range(0))
+                .scale(self.scale)
                 .render(left_rect, buf);
 
             // Right meter
@@ -115,14 +315,146 @@ impl Widget for ChannelStrip<'_> {
                 height: meter_area.height,
             };
             Meter::new(self.state.current_peaks[1])
+                .rms(self.state.current_rms[1])
                 .peak_hold(self.state.peak_hold[1])
+                .range(self.state.range.range(1))
+                .scale(self.scale)
                 .render(right_rect, buf);
+        } else {
+            // Surround: one narrow meter per port, as many as fit
+            let count = self.state.port_count as u16;
+            let meter_width = (meter_area.width / count).clamp(1, 2);
+            let total_width = (meter_width * count).min(meter_area.width);
+            let x_offset = (meter_area.width - total_width) / 2;
+            for (i, x) in (0..self.state.port_count)
+                .map(|i| (i, meter_area.x + x_offset + i as u16 * meter_width))
+                .take_while(|(_, x)| x + meter_width <= meter_area.right())
+            {
+                let rect = Rect {
+                    x,
+                    y: meter_area.y,
+                    width: meter_width,
+                    height: meter_area.height,
+                };
+                Meter::new(self.state.current_peaks[i])
+                    .rms(self.state.current_rms[i])
+                    .peak_hold(self.state.peak_hold[i])
+                    .range(self.state.range.range(i))
+                    .scale(self.scale)
+                    .render(rect, buf);
+            }
+        }
+
+        if history_rows > 0 {
+            Paragraph::new(self.history_line(chunks[1].width))
+                .alignment(ratatui::layout::Alignment::Center)
+                .render(chunks[1], buf);
+        }
+
+        // Render the current and held peak in dB
+        let held_db = MeterData::linear_to_db(self.state.held_peak());
+        let readout_style = if held_db >= 0.0 {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        Paragraph::new(self.peak_readout(chunks[2].width))
+            .style(readout_style)
+            .alignment(ratatui::layout::Alignment::Center)
+            .render(chunks[2], buf);
+
+        // Render the clip count until it is cleared, a protective dip,
+        // auto-trim, limiter or de-esser reduction while active, a negative
+        // correlation of stereo channels, the automixer gain of inputs in its
+        // group (bright while the input holds the floor), otherwise crest
+        // factor (peak-to-RMS over the rolling window)
+        let (info_text, info_style) = if self.state.clip_count > 0 {
+            (
+                format!("CLIP {}", short_count(self.state.clip_count)),
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else if self.state.dip_db >= 0.1 {
+            (
+                format!("DIP -{:.0}", self.state.dip_db),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )
+        } else if self.state.auto_trim_db >= 0.1 {
+            (
+                format!("AT -{:.1}", self.state.auto_trim_db),
+                Style::default().fg(Color::Yellow),
+            )
+        } else if self.state.limiter_db >= 0.1 {
+            (
+                format!("LM -{:.1}", self.state.limiter_db),
+                Style::default().fg(Color::Magenta),
+            )
+        } else if self.state.deesser_db >= 0.1 {
+            (
+                format!("DS -{:.1}", self.state.deesser_db),
+                Style::default().fg(Color::LightBlue),
+            )
+        } else if let Some(correlation) = self.state.correlation.filter(|c| *c < 0.0) {
+            (
+                format!("PH {:+.1}", correlation),
+                Style::default().fg(Color::Red),
+            )
+        } else if self.state.automix_weight_db.is_some() {
+            let style = if self.state.automix_db > AUTOMIX_ACTIVE_DB {
+                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            (format!("AM {:.0}", self.state.automix_db), style)
+        } else {
+            let crest_text = match self.state.crest_factor_db() {
+                Some(crest) => format!("CF {:.1}", crest),
+                None => "CF --".to_string(),
+            };
+            (crest_text, Style::default().fg(Color::DarkGray))
+        };
+        Paragraph::new(info_text)
+            .style(info_style)
+            .alignment(ratatui::layout::Alignment::Center)
+            .render(chunks[3], buf);
+
+        // Render EQ gains (low mid high) for inputs
+        if self.is_input {
+            let eq = &self.state.eq;
+            let filters: Vec<&str> = [(eq.high_pass, "HP"), (eq.low_pass, "LP")]
+                .iter()
+                .filter(|(settings, _)| settings.is_some_and(|s| s.enabled))
+                .map(|&(_, label)| label)
+                .collect();
+            let (eq_text, eq_style) = if eq.is_flat() && filters.is_empty() {
+                ("EQ --".to_string(), Style::default().fg(Color::DarkGray))
+            } else if eq.is_flat() {
+                (filters.join(" "), Style::default().fg(Color::Cyan))
+            } else {
+                (
+                    format!("{:+.0} {:+.0} {:+.0}", eq.low_db, eq.mid_db, eq.high_db),
+                    Style::default().fg(Color::Cyan),
+                )
+            };
+            Paragraph::new(eq_text)
+                .style(eq_style)
+                .alignment(ratatui::layout::Alignment::Center)
+                .render(chunks[4], buf);
         }
 
         // Render volume display
-        let vol_area = chunks[1];
-        let volume_text = format!("{:+.1}", self.state.volume_db);
-        let volume_style = if self.state.muted {
+        let vol_area = chunks[5];
+        let volume_text = match &self.flipped {
+            Some(value) => value.clone(),
+            None => self.display.format_signed(self.state.volume_db),
+        };
+        let volume_style = if self.flipped.is_some() {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD)
+        } else if self.state.muted {
             Style::default().fg(Color::DarkGray)
         } else {
             Style::default().fg(Color::White)
@@ -132,8 +464,24 @@ impl Widget for ChannelStrip<'_> {
             .alignment(ratatui::layout::Alignment::Center);
         volume_para.render(vol_area, buf);
 
+        // Render output assignment LEDs: green stereo, yellow mono, grey off
+        if !self.routes.is_empty() {
+            let leds: Vec<Span> = self
+                .routes
+                .iter()
+                .map(|mode| match mode {
+                    RouteMode::Stereo => Span::styled("●", Style::default().fg(Color::Green)),
+                    RouteMode::Mono => Span::styled("◐", Style::default().fg(Color::Yellow)),
+                    RouteMode::Off => Span::styled("○", Style::default().fg(Color::DarkGray)),
+                })
+                .collect();
+            Paragraph::new(Line::from(leds))
+                .alignment(ratatui::layout::Alignment::Center)
+                .render(chunks[6], buf);
+        }
+
         // Render mute/solo indicators
-        let control_area = chunks[2];
+        let control_area = chunks[7];
         let mut spans = Vec::new();
 
         // Mute indicator
@@ -144,19 +492,60 @@ impl Widget for ChannelStrip<'_> {
         };
         spans.push(Span::styled("M", mute_style));
 
-        // Only show solo for input channels
-        if self.is_input {
+        // Only show solo for input and output channels
+        if self.soloable {
             spans.push(Span::raw(" "));
             let solo_style = if self.state.soloed {
                 Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else if self.auditioned {
+                Style::default().fg(Color::Yellow)
             } else {
                 Style::default().fg(Color::DarkGray)
             };
             spans.push(Span::styled("S", solo_style));
         }
 
+        // Signal light ahead of them where it fits: green with signal, a red
+        // warning once a channel that should have signal has been silent
+        if let Some(status) = self.signal {
+            let width: usize = spans.iter().map(Span::width).sum();
+            if width + 2 <= control_area.width as usize {
+                let (led, style) = match status {
+                    SignalStatus::Present => ("●", Style::default().fg(Color::Green)),
+                    SignalStatus::Absent => ("○", Style::default().fg(Color::DarkGray)),
+                    SignalStatus::Missing => (
+                        "!",
+                        Style::default()
+                            .fg(Color::White)
+                            .bg(Color::Red)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                };
+                spans.splice(0..0, [Span::styled(led, style), Span::raw(" ")]);
+            }
+        }
+
+        // Correlation of stereo channels where it fits beside them
+        if self.state.port_count == 2 {
+            let correlation = self.correlation_spans();
+            let width: usize = spans.iter().map(Span::width).sum();
+            if width + 1 + correlation.len() <= control_area.width as usize {
+                spans.push(Span::raw(" "));
+                spans.extend(correlation);
+            }
+        }
+
         let control_para = Paragraph::new(Line::from(spans))
             .alignment(ratatui::layout::Alignment::Center);
         control_para.render(control_area, buf);
     }
 }
+
+/// A count in at most four characters (e.g. `999`, `12k`, `3M`)
+fn short_count(count: u64) -> String {
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => format!("{}k", count / 1_000),
+        _ => format!("{}M", (count / 1_000_000).min(999)),
+    }
+}
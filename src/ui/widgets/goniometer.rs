@@ -0,0 +1,138 @@
+//! Goniometer and correlation meter for stereo outputs
+//!
+//! The goniometer plots left against right turned by 45°, as on a broadcast
+//! console: mono stands upright, wide material spreads sideways and a trace
+//! leaning towards the horizontal is out of phase and will cancel in a mono
+//! downmix. The plot scales itself to the loudest point shown, so quiet
+//! material keeps its shape. Below it the correlation meter runs from -1
+//! (one side inverted) through 0 (unrelated) to +1 (mono).
+
+use std::collections::VecDeque;
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        canvas::{Canvas, Line as CanvasLine, Points},
+        Paragraph, Widget,
+    },
+};
+
+/// Smallest level the plot scales up to (about -80 dBFS)
+const MIN_SCALE: f32 = 1e-4;
+
+/// Goniometer over a trail of left/right sample pairs
+pub struct Goniometer<'a> {
+    points: &'a VecDeque<[f32; 2]>,
+    correlation: Option<f32>,
+}
+
+impl<'a> Goniometer<'a> {
+    pub fn new(points: &'a VecDeque<[f32; 2]>) -> Self {
+        Self {
+            points,
+            correlation: None,
+        }
+    }
+
+    pub fn correlation(mut self, correlation: Option<f32>) -> Self {
+        self.correlation = correlation;
+        self
+    }
+
+    /// Correlation meter row: a -1..+1 scale with a marker
+    fn correlation_line(&self, width: u16) -> Line<'static> {
+        let value = match self.correlation {
+            Some(c) => format!(" {:+.2}", c),
+            None => "   --".to_string(),
+        };
+        let bar_width = (width as usize).saturating_sub(6 + value.len()).max(3);
+        let marker = self
+            .correlation
+            .map(|c| ((c + 1.0) / 2.0 * (bar_width - 1) as f32).round() as usize);
+        let bar: String = (0..bar_width)
+            .map(|i| match i {
+                _ if Some(i) == marker => '●',
+                _ if i == bar_width / 2 => '┼',
+                _ => '─',
+            })
+            .collect();
+        let color = match self.correlation {
+            Some(c) if c < 0.0 => Color::Red,
+            Some(_) => Color::Green,
+            None => Color::DarkGray,
+        };
+        let gray = Style::default().fg(Color::DarkGray);
+        Line::from(vec![
+            Span::styled("-1 ", gray),
+            Span::styled(bar, Style::default().fg(color)),
+            Span::styled(" +1", gray),
+            Span::styled(value, Style::default().fg(color)),
+        ])
+    }
+}
+
+impl Widget for Goniometer<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 2 || area.width < 12 {
+            return;
+        }
+        let plot = Rect {
+            height: area.height - 1,
+            ..area
+        };
+
+        // Turn by 45°: mid up, side across
+        let scale = self
+            .points
+            .iter()
+            .flatten()
+            .fold(MIN_SCALE, |m, s| m.max(s.abs())) as f64;
+        let coords: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .map(|&[l, r]| {
+                let (l, r) = (l as f64 / scale, r as f64 / scale);
+                ((r - l) * FRAC_1_SQRT_2, (l + r) * FRAC_1_SQRT_2)
+            })
+            .collect();
+
+        Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds([-1.0, 1.0])
+            .y_bounds([-1.0, 1.0])
+            .paint(|ctx| {
+                let axis = |x1, y1, x2, y2| CanvasLine {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    color: Color::DarkGray,
+                };
+                ctx.draw(&axis(0.0, -1.0, 0.0, 1.0));
+                ctx.draw(&axis(-1.0, 0.0, 1.0, 0.0));
+                ctx.draw(&axis(-1.0, 1.0, 1.0, -1.0));
+                ctx.draw(&axis(-1.0, -1.0, 1.0, 1.0));
+                ctx.layer();
+                ctx.draw(&Points {
+                    coords: &coords,
+                    color: Color::Green,
+                });
+                let label = Style::default().fg(Color::Gray);
+                ctx.print(-1.0, 1.0, Span::styled("L", label));
+                ctx.print(0.95, 1.0, Span::styled("R", label));
+            })
+            .render(plot, buf);
+
+        let row = Rect {
+            y: area.bottom() - 1,
+            height: 1,
+            ..area
+        };
+        Paragraph::new(self.correlation_line(area.width)).render(row, buf);
+    }
+}
@@ -1,7 +1,9 @@
 //! Level meter widget
 //!
 //! Renders a vertical level meter with green/yellow/red zones
-//! and peak hold indicator.
+//! and peak hold indicator. The range and zones follow the configured
+//! meter scale (digital or K-System). With an RMS level the solid bar shows the RMS
+//! and the current peak is drawn as a marker above it, as on most mixers.
 
 use ratatui::{
     buffer::Buffer,
@@ -10,14 +12,9 @@ use ratatui::{
     widgets::Widget,
 };
 
+use crate::config::MeterScale;
 use crate::ipc::VOLUME_MIN_DB;
 
-/// Threshold where yellow zone starts (dB)
-const YELLOW_THRESHOLD_DB: f32 = -12.0;
-
-/// Threshold where red zone starts (dB)
-const RED_THRESHOLD_DB: f32 = 0.0;
-
 /// Characters for meter display (from empty to full)
 const METER_CHARS: &[char] = &[' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
 
@@ -26,14 +23,23 @@ pub struct Meter {
     /// Current level in linear scale (0.0 to 1.0+)
     level: f32,
 
+    /// Current RMS level in linear scale, if the bar shows RMS
+    rms: Option<f32>,
+
     /// Peak hold level in linear scale
     peak_hold: f32,
 
+    /// Recent min/max peak band in linear scale, if shown
+    range: Option<(f32, f32)>,
+
     /// Minimum dB value (bottom of meter)
     min_db: f32,
 
     /// Maximum dB value (top of meter)
     max_db: f32,
+
+    /// Levels where the yellow and red zones start (dB)
+    zones: (f32, f32),
 }
 
 impl Meter {
@@ -41,18 +47,40 @@ impl Meter {
     pub fn new(level: f32) -> Self {
         Self {
             level,
+            rms: None,
             peak_hold: level,
-            min_db: VOLUME_MIN_DB,
-            max_db: 6.0, // +6 dB headroom display
+            range: None,
+            min_db: MeterScale::Digital.range_db().0,
+            max_db: MeterScale::Digital.range_db().1,
+            zones: MeterScale::Digital.zones_db(),
         }
     }
 
+    /// Use the range and zones of a meter scale
+    pub fn scale(mut self, scale: MeterScale) -> Self {
+        (self.min_db, self.max_db) = scale.range_db();
+        self.zones = scale.zones_db();
+        self
+    }
+
     /// Set the peak hold level
     pub fn peak_hold(mut self, peak: f32) -> Self {
         self.peak_hold = peak;
         self
     }
 
+    /// Draw the RMS level as the bar and the current level as a marker
+    pub fn rms(mut self, rms: f32) -> Self {
+        self.rms = Some(rms);
+        self
+    }
+
+    /// Shade the band between the lowest and highest recent peak
+    pub fn range(mut self, range: Option<(f32, f32)>) -> Self {
+        self.range = range;
+        self
+    }
+
     /// Convert linear level to dB
     fn linear_to_db(linear: f32) -> f32 {
         if linear <= 0.0 {
@@ -69,21 +97,32 @@ impl Meter {
     }
 
     /// Get the color for a given dB level
-    fn color_for_db(db: f32) -> Color {
-        if db >= RED_THRESHOLD_DB {
+    fn color_for_db(&self, db: f32) -> Color {
+        if db >= self.zones.1 {
             Color::Red
-        } else if db >= YELLOW_THRESHOLD_DB {
+        } else if db >= self.zones.0 {
             Color::Yellow
         } else {
             Color::Green
         }
     }
 
+    /// Get the band color for a given dB level (between bright and dimmed)
+    fn range_color_for_db(&self, db: f32) -> Color {
+        if db >= self.zones.1 {
+            Color::Rgb(140, 40, 40)
+        } else if db >= self.zones.0 {
+            Color::Rgb(120, 120, 40)
+        } else {
+            Color::Rgb(40, 110, 40)
+        }
+    }
+
     /// Get dimmed color for inactive meter zones
-    fn dimmed_color_for_db(db: f32) -> Color {
-        if db >= RED_THRESHOLD_DB {
+    fn dimmed_color_for_db(&self, db: f32) -> Color {
+        if db >= self.zones.1 {
             Color::Rgb(60, 20, 20)  // Dark red
-        } else if db >= YELLOW_THRESHOLD_DB {
+        } else if db >= self.zones.0 {
             Color::Rgb(50, 50, 20)  // Dark yellow/olive
         } else {
             Color::Rgb(20, 50, 20)  // Dark green
@@ -98,16 +137,30 @@ impl Widget for Meter {
         }
 
         let level_db = Self::linear_to_db(self.level);
+        let bar_db = Self::linear_to_db(self.rms.unwrap_or(self.level));
         let peak_db = Self::linear_to_db(self.peak_hold);
 
         let level_pos = self.db_to_position(level_db);
+        let bar_pos = self.db_to_position(bar_db);
         let peak_pos = self.db_to_position(peak_db);
 
         // Calculate how many rows should be filled
         let total_rows = area.height as f32;
-        let filled_rows = (level_pos * total_rows).ceil() as u16;
+        let filled_rows = (bar_pos * total_rows).ceil() as u16;
         let peak_row = ((1.0 - peak_pos) * total_rows).floor() as u16;
 
+        // Row (from the bottom) of the current peak marker above the RMS bar
+        let level_rows = (level_pos * total_rows).ceil() as u16;
+        let level_marker = (self.rms.is_some() && level_rows > filled_rows)
+            .then(|| level_rows - 1);
+
+        // Rows (counted from the bottom) covered by the recent range band
+        let band_rows = self.range.map(|(min, max)| {
+            let low = self.db_to_position(Self::linear_to_db(min));
+            let high = self.db_to_position(Self::linear_to_db(max));
+            ((low * total_rows).floor() as u16, (high * total_rows).ceil() as u16)
+        });
+
         // Render from bottom to top
         for row in 0..area.height {
             let y = area.y + row;
@@ -116,7 +169,7 @@ impl Widget for Meter {
             // Calculate the dB level at this row
             let row_position = row_from_bottom as f32 / total_rows;
             let row_db = self.min_db + row_position * (self.max_db - self.min_db);
-            let color = Self::color_for_db(row_db);
+            let color = self.color_for_db(row_db);
 
             for col in 0..area.width {
                 let x = area.x + col;
@@ -126,15 +179,27 @@ impl Widget for Meter {
                     buf[(x, y)]
                         .set_char('█')
                         .set_style(Style::default().fg(color));
+                } else if level_marker == Some(row_from_bottom) {
+                    // Current peak above the RMS bar
+                    buf[(x, y)]
+                        .set_char('▔')
+                        .set_style(Style::default().fg(self.color_for_db(level_db)));
                 } else if row == peak_row.min(area.height - 1) {
                     // Peak hold indicator
-                    let peak_color = Self::color_for_db(peak_db);
+                    let peak_color = self.color_for_db(peak_db);
                     buf[(x, y)]
                         .set_char('━')
                         .set_style(Style::default().fg(peak_color));
+                } else if band_rows
+                    .is_some_and(|(low, high)| row_from_bottom >= low && row_from_bottom < high)
+                {
+                    // Recent range band
+                    buf[(x, y)]
+                        .set_char('▒')
+                        .set_style(Style::default().fg(self.range_color_for_db(row_db)));
                 } else {
                     // Empty part - dimmed version of the zone color
-                    let dimmed_color = Self::dimmed_color_for_db(row_db);
+                    let dimmed_color = self.dimmed_color_for_db(row_db);
                     buf[(x, y)]
                         .set_char('░')
                         .set_style(Style::default().fg(dimmed_color));
@@ -150,6 +215,7 @@ pub struct HorizontalMeter {
     peak_hold: f32,
     min_db: f32,
     max_db: f32,
+    zones: (f32, f32),
 }
 
 impl HorizontalMeter {
@@ -157,8 +223,9 @@ impl HorizontalMeter {
         Self {
             level,
             peak_hold: level,
-            min_db: VOLUME_MIN_DB,
-            max_db: 6.0,
+            min_db: MeterScale::Digital.range_db().0,
+            max_db: MeterScale::Digital.range_db().1,
+            zones: MeterScale::Digital.zones_db(),
         }
     }
 
@@ -167,6 +234,12 @@ impl HorizontalMeter {
         self
     }
 
+    pub fn scale(mut self, scale: MeterScale) -> Self {
+        (self.min_db, self.max_db) = scale.range_db();
+        self.zones = scale.zones_db();
+        self
+    }
+
     fn linear_to_db(linear: f32) -> f32 {
         if linear <= 0.0 {
             VOLUME_MIN_DB
@@ -180,10 +253,10 @@ impl HorizontalMeter {
         (db_clamped - self.min_db) / (self.max_db - self.min_db)
     }
 
-    fn color_for_db(db: f32) -> Color {
-        if db >= RED_THRESHOLD_DB {
+    fn color_for_db(&self, db: f32) -> Color {
+        if db >= self.zones.1 {
             Color::Red
-        } else if db >= YELLOW_THRESHOLD_DB {
+        } else if db >= self.zones.0 {
             Color::Yellow
         } else {
             Color::Green
@@ -213,14 +286,14 @@ impl Widget for HorizontalMeter {
             let x = area.x + col;
             let col_position = col as f32 / total_cols;
             let col_db = self.min_db + col_position * (self.max_db - self.min_db);
-            let color = Self::color_for_db(col_db);
+            let color = self.color_for_db(col_db);
 
             if col < filled_cols {
                 buf[(x, y)]
                     .set_char('█')
                     .set_style(Style::default().fg(color));
             } else if col == peak_col.min(area.width - 1) {
-                let peak_color = Self::color_for_db(peak_db);
+                let peak_color = self.color_for_db(peak_db);
                 buf[(x, y)]
                     .set_char('│')
                     .set_style(Style::default().fg(peak_color));
@@ -2,6 +2,10 @@
 
 mod meter;
 mod channel_strip;
+mod goniometer;
+mod big_meter;
 
-pub use meter::Meter;
+pub use meter::{HorizontalMeter, Meter};
 pub use channel_strip::ChannelStrip;
+pub use goniometer::Goniometer;
+pub use big_meter::BigMeter;
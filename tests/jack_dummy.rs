@@ -0,0 +1,322 @@
+//! Audio path tests against a JACK dummy server
+//!
+//! Each test starts `jackd -d dummy` under its own server name, a probe
+//! client that plays a 1 kHz sine into the mixer and measures what comes
+//! back, and rmixer itself scripted over `--stdin-commands`. Levels are
+//! checked after every command, so a regression anywhere between the input
+//! port and the output port shows up as a wrong number. The tests pass
+//! without doing anything when `jackd` is not installed.
+
+use std::f32::consts::TAU;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use jack::{AudioIn, AudioOut, Client, ClientOptions, Control, Port, ProcessScope};
+
+/// Peak amplitude of the probe sine
+const SIGNAL: f32 = 0.5;
+
+/// Frequency of the probe sine in Hz
+const SIGNAL_HZ: f32 = 1000.0;
+
+/// Time for a command to reach the output (UI tick, fades, a few periods)
+const SETTLE: Duration = Duration::from_millis(300);
+
+/// Time the output peak is measured over
+const MEASURE: Duration = Duration::from_millis(200);
+
+/// How long to wait for jackd to accept clients
+const SERVER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Level error allowed in the checks (linear)
+const TOLERANCE: f32 = 0.01;
+
+/// Tests share the JACK_DEFAULT_SERVER variable, so they run one at a time
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// Linear gain of a level in dB
+fn gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Dummy JACK server, stopped on drop
+struct Server(Child);
+
+impl Server {
+    /// Start a dummy server, or None when jackd is not installed
+    fn start(name: &str) -> Option<Self> {
+        let child = Command::new("jackd")
+            .args(["--no-realtime", "--name", name, "-d", "dummy"])
+            .args(["--rate", "48000", "--period", "256"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match child {
+            Ok(child) => Some(Self(child)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                eprintln!("jackd not found, skipping audio path test");
+                None
+            }
+            Err(e) => panic!("Failed to start jackd: {}", e),
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Process callback of the probe client
+struct ProbeHandler {
+    /// Sine into the mixer input
+    signal: Port<AudioOut>,
+
+    /// Mixer output coming back
+    capture: Port<AudioIn>,
+
+    /// Sine phase in cycles
+    phase: f32,
+
+    /// Phase step per sample
+    step: f32,
+
+    /// Highest captured peak since the last reset (f32 bits)
+    peak: Arc<AtomicU32>,
+}
+
+impl jack::ProcessHandler for ProbeHandler {
+    fn process(&mut self, _: &Client, ps: &ProcessScope) -> Control {
+        for sample in self.signal.as_mut_slice(ps) {
+            *sample = SIGNAL * (TAU * self.phase).sin();
+            self.phase = (self.phase + self.step).fract();
+        }
+        // Bits of non-negative floats order like the floats
+        let peak = self
+            .capture
+            .as_slice(ps)
+            .iter()
+            .fold(0.0f32, |m, s| m.max(s.abs()));
+        self.peak.fetch_max(peak.to_bits(), Ordering::Relaxed);
+        Control::Continue
+    }
+}
+
+/// Test client with a `probe:signal` output and a `probe:capture` input
+struct Probe {
+    _client: jack::AsyncClient<(), ProbeHandler>,
+    peak: Arc<AtomicU32>,
+}
+
+impl Probe {
+    /// Connect to the server, waiting for it to come up
+    fn start() -> Self {
+        let started = Instant::now();
+        let client = loop {
+            match Client::new("probe", ClientOptions::NO_START_SERVER) {
+                Ok((client, _)) => break client,
+                Err(e) if started.elapsed() > SERVER_TIMEOUT => {
+                    panic!("jackd did not come up: {}", e)
+                }
+                Err(_) => thread::sleep(Duration::from_millis(100)),
+            }
+        };
+        let peak = Arc::new(AtomicU32::new(0));
+        let handler = ProbeHandler {
+            signal: client.register_port("signal", AudioOut::default()).unwrap(),
+            capture: client.register_port("capture", AudioIn::default()).unwrap(),
+            phase: 0.0,
+            step: SIGNAL_HZ / client.sample_rate() as f32,
+            peak: Arc::clone(&peak),
+        };
+        Self {
+            _client: client.activate_async((), handler).unwrap(),
+            peak,
+        }
+    }
+
+    /// Peak coming back once the last change has settled
+    fn level(&self) -> f32 {
+        thread::sleep(SETTLE);
+        self.peak.store(0, Ordering::Relaxed);
+        thread::sleep(MEASURE);
+        f32::from_bits(self.peak.load(Ordering::Relaxed))
+    }
+}
+
+/// rmixer without the UI, driven over stdin
+struct Mixer {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+    dir: PathBuf,
+}
+
+impl Mixer {
+    /// Start rmixer on `server` with a config
+    fn start(server: &str, config: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("{}-mixer", server));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.yaml");
+        std::fs::write(&path, config).unwrap();
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rmixer"))
+            .arg("--config")
+            .arg(&path)
+            .arg("--stdin-commands")
+            .env("JACK_DEFAULT_SERVER", server)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to start rmixer");
+        let mut mixer = Self {
+            stdin: child.stdin.take(),
+            stdout: BufReader::new(child.stdout.take().unwrap()),
+            child,
+            dir,
+        };
+        // Commands are read once the engine runs and the ports are connected
+        assert!(mixer.send("hello 1").starts_with("hello rmixer"));
+        mixer
+    }
+
+    /// Send a command and return the first line of the reply
+    fn send(&mut self, command: &str) -> String {
+        let stdin = self.stdin.as_mut().unwrap();
+        writeln!(stdin, "{}", command).unwrap();
+        stdin.flush().unwrap();
+        let mut reply = String::new();
+        self.stdout.read_line(&mut reply).unwrap();
+        reply.trim_end().to_string()
+    }
+
+    /// Send a command that must succeed
+    fn ok(&mut self, command: &str) {
+        assert_eq!(self.send(command), "ok", "{}", command);
+    }
+}
+
+impl Drop for Mixer {
+    fn drop(&mut self) {
+        // Closing stdin ends the mixer
+        self.stdin = None;
+        let started = Instant::now();
+        while matches!(self.child.try_wait(), Ok(None)) {
+            if started.elapsed() > SERVER_TIMEOUT {
+                let _ = self.child.kill();
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Mixer, probe and server of one test, torn down in that order
+struct Rig {
+    mixer: Mixer,
+    probe: Probe,
+    _server: Server,
+    _serial: MutexGuard<'static, ()>,
+}
+
+impl Rig {
+    /// Start everything with a mono input and output wired to the probe
+    /// (None when jackd is not installed)
+    fn start(test: &str) -> Option<Self> {
+        let serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let name = format!("rmixer-test-{}-{}", std::process::id(), test);
+        let server = Server::start(&name)?;
+        std::env::set_var("JACK_DEFAULT_SERVER", &name);
+        let probe = Probe::start();
+        let mixer = Mixer::start(
+            &name,
+            r#"
+client_name: "mixer"
+inputs:
+  - name: "Mic"
+    ports: ["mic_in"]
+    volume_db: 0
+    connect: ["probe:signal"]
+outputs:
+  - name: "Main"
+    ports: ["main_out"]
+    volume_db: 0
+    connect: ["probe:capture"]
+"#,
+        );
+        Some(Self {
+            mixer,
+            probe,
+            _server: server,
+            _serial: serial,
+        })
+    }
+
+    /// Check the level coming back from the mixer
+    fn expect(&self, expected: f32, what: &str) {
+        let level = self.probe.level();
+        assert!(
+            (level - expected).abs() < TOLERANCE,
+            "{}: expected {:.3}, got {:.3}",
+            what,
+            expected,
+            level
+        );
+    }
+}
+
+#[test]
+fn test_input_and_output_faders() {
+    let Some(mut rig) = Rig::start("faders") else {
+        return;
+    };
+    rig.expect(SIGNAL, "unity gain");
+
+    rig.mixer.ok("volume input Mic -6");
+    rig.expect(SIGNAL * gain(-6.0), "input at -6 dB");
+
+    rig.mixer.ok("volume output Main -6");
+    rig.expect(SIGNAL * gain(-12.0), "input and output at -6 dB");
+
+    rig.mixer.ok("volume master 0 -6");
+    rig.expect(SIGNAL * gain(-18.0), "master at -6 dB");
+}
+
+#[test]
+fn test_mutes() {
+    let Some(mut rig) = Rig::start("mutes") else {
+        return;
+    };
+    rig.mixer.ok("mute input Mic on");
+    rig.expect(0.0, "input muted");
+    rig.mixer.ok("mute input Mic off");
+    rig.expect(SIGNAL, "input unmuted");
+
+    rig.mixer.ok("mute output Main on");
+    rig.expect(0.0, "output muted");
+    rig.mixer.ok("mute output Main off");
+    rig.mixer.ok("mute master 0 on");
+    rig.expect(0.0, "master muted");
+}
+
+#[test]
+fn test_routing() {
+    let Some(mut rig) = Rig::start("routing") else {
+        return;
+    };
+    rig.mixer.ok("route Mic Main off");
+    rig.expect(0.0, "route off");
+
+    rig.mixer.ok("route Mic Main on -6");
+    rig.expect(SIGNAL * gain(-6.0), "route at -6 dB");
+}